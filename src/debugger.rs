@@ -10,64 +10,176 @@ use nix::{
 };
 use object::{Object, ObjectSection};
 use stackium_shared::{
-    Breakpoint, BreakpointPoint, Command, CommandOutput, DataType, DebugMeta, DwarfAttribute,
-    FunctionMeta, Location, MemoryMap, Registers, TypeName, Variable,
+    Action, Breakpoint, BreakpointPoint, Command, CommandOutput, DataType, DebugMeta, DebugeeState,
+    DwarfAttribute, FunctionMeta, Location, MemoryChunk, PlacedBreakpoint, Profile, Registers,
+    SourceAnchor, StopReason, StopState, TypeName,
 };
-use std::{ffi::c_void, fs, path::PathBuf, sync::Arc};
+use std::{
+    ffi::c_void,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tracing::{debug, debug_span, trace_span, warn};
 
+mod branch;
 pub mod breakpoint;
+mod breakpoint_address;
+pub mod cancel;
+mod debug_file;
+mod demangle;
+pub mod diagnostics;
+mod disassemble;
+mod dwarf_index;
+mod elf;
 pub mod error;
+mod exercise;
+mod expression;
+mod file_access;
+mod files;
+mod frames;
+mod heap;
+mod history;
+mod invariants;
+mod library_calls;
+mod line_hits;
+mod line_table;
+mod locations;
+mod maps;
+mod memory_cache;
+mod optimization;
+mod plt;
+mod process_stack;
+mod profile;
+mod quiz;
 pub mod registers;
+mod report;
+mod return_value;
+mod session;
+mod signature;
+mod snapshot;
+mod source_map;
+mod status;
+mod step_skips;
+mod stop;
+mod structures;
+mod symbols;
+mod trace;
+mod types;
+mod undo;
+mod user_code;
 mod util;
-
-#[cfg(debug_assertions)]
-macro_rules! debug_println {
-    ($($x:tt)*) => { println!($($x)*) }
-}
-
-#[cfg(not(debug_assertions))]
-macro_rules! debug_println {
-    ($($x:tt)*) => {{}};
-}
+mod watch;
 
 use crate::{
-    debugger::{
-        registers::FromUserRegsStruct,
-        util::{get_function_meta, get_piece_addr},
-    },
+    debugger::{registers::FromUserRegsStruct, util::get_function_meta},
     prompt::{command_prompt, CommandCompleter},
+    sandbox::Sandbox,
     util::{dw_at_to_string, tag_to_string},
 };
 
 use self::{
-    breakpoint::DebuggerBreakpoint,
+    breakpoint::{relocate_anchor, source_anchor, DebuggerBreakpoint},
+    cancel::Cancellation,
+    dwarf_index::IndexedDwarf,
     error::DebugError,
-    util::{find_function_from_name, get_addr_from_line, get_functions, get_line_from_pc},
+    exercise::ExerciseProgress,
+    files::FileIndex,
+    history::VariableHistory,
+    invariants::Invariants,
+    library_calls::{LibraryBreakpoints, LibraryCategory},
+    line_hits::LineCounters,
+    locations::FrameContext,
+    maps::{unreadable_address, MapHistory},
+    memory_cache::MemoryCache,
+    quiz::Quizzes,
+    session::SessionLog,
+    source_map::SourceMap,
+    status::{RunCounters, DEFAULT_CONTINUE_TIMEOUT},
+    step_skips::StepSkips,
+    trace::Tracer,
+    undo::UndoLog,
+    util::{
+        find_function_from_name, get_addr_from_line, get_line_addresses, get_line_containing_addr,
+        get_line_from_pc, get_post_prologue_addr,
+    },
+    watch::Watches,
 };
 
+/// Maximum number of bytes `ReadAround` reads on either side of the address
+const MAX_READ_AROUND: u64 = 4096;
+
 type ConcreteReader = gimli::read::EndianReader<gimli::NativeEndian, Arc<[u8]>>;
 pub struct Debugger {
     pub child: Pid,
     breakpoints: Vec<Breakpoint>,
     pub program: PathBuf,
-    dwarf: gimli::read::Dwarf<ConcreteReader>,
+    /// The separate file the debug information was read from if the program was stripped of it
+    debug_file: Option<PathBuf>,
+    /// Limits applied to the child when it is started or restarted
+    pub sandbox: Option<Sandbox>,
+    /// Leave the compilation units compiled outside the directory of `main` out of the files
+    /// and functions
+    pub user_code: bool,
+    /// Run to `main` when the child is started or restarted instead of stopping at the entry
+    /// point, see `start`
+    pub start_at_main: bool,
+    /// Substitutions applied to the paths of the source files before reading them
+    pub source_map: SourceMap,
+    /// Files and directories `GetFile` may read besides the source files of the program, see
+    /// `readable_source`
+    pub allowed_sources: Vec<PathBuf>,
+    dwarf: IndexedDwarf<ConcreteReader>,
+    files: FileIndex,
+    history: VariableHistory,
+    /// Changes made by commands which can be undone
+    actions: UndoLog,
+    tracer: Tracer,
+    /// Counts the iterations of loops while the child is continued
+    line_counters: LineCounters,
+    invariants: Invariants,
+    watches: Watches,
+    /// Result of the last profiling run
+    profile: Option<Profile>,
+    /// Guided exercise loaded from the manifest next to the program
+    exercise: Option<ExerciseProgress>,
+    /// Questions asked at breakpoints before the UI reveals the memory
+    quiz: Quizzes,
+    library_breakpoints: LibraryBreakpoints,
+    maps: MapHistory,
+    memory: MemoryCache,
+    /// Cancellation of the command being executed, see `Cancel`
+    cancellation: Cancellation,
+    session: SessionLog,
+    /// Whether the program has DWARF debug information, without it functions are looked up in
+    /// the symbol table and source features are unavailable
+    has_debug_info: bool,
+    /// Set once the breakpoints were removed and the child was detached
+    detached: bool,
+    /// Why the child stopped the last time it was waited for
+    stop_reason: StopReason,
+    /// Updated whenever the child is continued or waited for, see `set_state`
+    state: DebugeeState,
+    state_since: SystemTime,
+    started: SystemTime,
+    /// Reset when restarting the child, reported in the `ExitSummary`
+    counters: RunCounters,
+    /// How long the child may keep computing after `Continue`, see `SetContinueTimeout`
+    continue_timeout: Option<Duration>,
+    step_skips: StepSkips,
 }
 
 macro_rules! iter_every_entry {
     ($self:ident, $entry:ident $unit:ident | $body:block) => {
-        let dwarf = &$self.dwarf;
-        let mut units = dwarf.units();
-        while let Ok(Some(unit_header)) = units.next() {
-            let unit_opt = dwarf.unit(unit_header);
-            if unit_opt.is_ok() {
-                $unit = unit_opt.unwrap();
-                let mut entries = $unit.entries();
-                let mut entry_res = entries.next_dfs();
-                while entry_res.is_ok() && entry_res.unwrap().is_some() {
-                    $entry = entry_res.unwrap().unwrap().1;
-                    $body
-                    entry_res = entries.next_dfs();
-                }
+        for compilation_unit in $self.dwarf.compilation_units() {
+            $unit = compilation_unit;
+            let mut entries = $unit.entries();
+            let mut entry_res = entries.next_dfs();
+            while entry_res.is_ok() && entry_res.unwrap().is_some() {
+                $entry = entry_res.unwrap().unwrap().1;
+                $body
+                entry_res = entries.next_dfs();
             }
         }
     };
@@ -85,6 +197,33 @@ macro_rules! find_entry_with_offset {
     };
 }
 
+/// Commands working with source lines or variables, which fail without debug information
+fn requires_debug_info(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::Location
+            | Command::FindLine { .. }
+            | Command::GetFile(_)
+            | Command::GetSource(_)
+            | Command::ViewSource(_)
+            | Command::StepIn
+            | Command::StepStatement
+            | Command::StepIntoTarget(_)
+            | Command::GetLineCalls
+            | Command::SetBreakpoint(BreakpointPoint::Location(_))
+            | Command::DiscoverVariables
+            | Command::GetVariableScopes
+            | Command::ExportVariables(_)
+            | Command::PointerInfo(_)
+            | Command::AddWatch { .. }
+            | Command::AnswerQuiz { .. }
+            | Command::GetDataStructures
+            | Command::TrackVariable(_)
+            | Command::DumpDwarf
+            | Command::DumpTypes
+    )
+}
+
 fn unit_offset<T: gimli::Reader>(
     offset: gimli::AttributeValue<T>,
 ) -> Option<<T as gimli::Reader>::Offset> {
@@ -96,38 +235,72 @@ fn unit_offset<T: gimli::Reader>(
 }
 
 impl Debugger {
-    fn create_dwarf_reader(object_file: &PathBuf) -> gimli::read::Dwarf<ConcreteReader> {
-        let load_section = |id: gimli::SectionId| -> Result<Arc<Vec<u8>>, gimli::Error> {
-            let bin = fs::read(object_file.clone()).unwrap();
-            let object_file = object::File::parse(&bin[..]).unwrap();
-            match object_file.section_by_name(id.name()) {
-                Some(section) => Ok(Arc::new(
-                    section.uncompressed_data().unwrap().to_mut().clone(),
-                )),
-                None => Ok(Arc::new(vec![])),
-            }
+    fn create_dwarf_reader(object_file: &PathBuf) -> IndexedDwarf<ConcreteReader> {
+        let _span = debug_span!("dwarf_sections", ?object_file).entered();
+        // missing or unreadable sections are treated as empty, like in stripped binaries
+        let bin = fs::read(object_file).unwrap_or_default();
+        let object_file = object::File::parse(&bin[..]).ok();
+        // every section is copied once, the indexes are built when a lookup needs them
+        let load_section = |id: gimli::SectionId| -> Result<ConcreteReader, gimli::Error> {
+            let data = object_file
+                .as_ref()
+                .and_then(|object_file| object_file.section_by_name(id.name()))
+                .and_then(|section| section.uncompressed_data().ok())
+                .unwrap_or_default();
+            Ok(gimli::EndianArcSlice::new(
+                Arc::from(&data[..]),
+                gimli::NativeEndian,
+            ))
         };
-        let dwarf_cow = gimli::Dwarf::load(&load_section).unwrap();
-        let dwarf = dwarf_cow.borrow(|section| {
-            gimli::EndianArcSlice::new(Arc::from(&section[..]), gimli::NativeEndian)
-        });
+        let dwarf = gimli::Dwarf::load(&load_section).unwrap();
         let mut iter = dwarf.debug_info.units();
-        while let Some(unit) = iter.next().unwrap() {
-            let version = unit.version();
-            debug_println!("Dwarf Version = {}", version);
-            if version != 4 {
-                eprintln!("Stackium currently only supports binaries built with dwarf debug version 4. Please compile with the \x1b[1;33m-gdwarf-4\x1b[0m flag!");
-                // panic!();
-            }
+        while let Ok(Some(unit)) = iter.next() {
+            debug!("Dwarf Version = {}", unit.version());
         }
-        dwarf
+        IndexedDwarf::new(dwarf)
     }
     pub fn new(child: Pid, object_file: PathBuf) -> Self {
+        let debug_file = debug_file::find_debug_file(&object_file);
+        let dwarf = Debugger::create_dwarf_reader(debug_file.as_ref().unwrap_or(&object_file));
+        let has_debug_info = matches!(dwarf.units().next(), Ok(Some(_)));
+        if !has_debug_info {
+            eprintln!("{} has no debug information, source code and variables are unavailable. Please compile with the \x1b[1;33m-g\x1b[0m flag!", object_file.display());
+        }
         Debugger {
             child,
-            dwarf: Debugger::create_dwarf_reader(&object_file),
+            files: FileIndex::new(&dwarf),
+            dwarf,
             program: object_file,
+            debug_file,
+            sandbox: None,
+            user_code: false,
+            start_at_main: false,
+            source_map: SourceMap::default(),
+            allowed_sources: Vec::new(),
             breakpoints: Vec::new(),
+            history: VariableHistory::default(),
+            actions: UndoLog::default(),
+            tracer: Tracer::default(),
+            line_counters: LineCounters::default(),
+            invariants: Invariants::default(),
+            watches: Watches::default(),
+            profile: None,
+            exercise: None,
+            quiz: Quizzes::default(),
+            library_breakpoints: LibraryBreakpoints::default(),
+            maps: MapHistory::default(),
+            memory: MemoryCache::default(),
+            cancellation: Cancellation::default(),
+            session: SessionLog::default(),
+            has_debug_info,
+            detached: false,
+            stop_reason: StopReason::Step,
+            state: DebugeeState::Stopped,
+            state_since: SystemTime::now(),
+            started: SystemTime::now(),
+            counters: RunCounters::default(),
+            continue_timeout: Some(DEFAULT_CONTINUE_TIMEOUT),
+            step_skips: StepSkips::default(),
         }
     }
 
@@ -174,7 +347,11 @@ impl Debugger {
                 .to_string()
                 .unwrap()
                 .to_string(),
-            _ => todo!(),
+            // e.g. `DW_FORM_line_strp` of DWARF 5
+            other => dwarf
+                .attr_string(unit, other)
+                .and_then(|s| s.to_string_lossy().map(|s| s.into_owned()))
+                .unwrap_or_else(|_| "<unknown>".to_owned()),
         }
     }
 
@@ -195,6 +372,7 @@ impl Debugger {
                     unit_header: &gimli::UnitHeader<ConcreteReader>,
                     find_offset: gimli::UnitOffset<<ConcreteReader as gimli::Reader>::Offset>,
                     mut known_types: DataType,
+                    scope: &str,
                 ) -> Result<Option<DataType>, DebugError> {
                     let dwarf = &debugger.dwarf;
                     let unit = dwarf.unit(unit_header.clone()).unwrap();
@@ -218,10 +396,10 @@ impl Debugger {
                                     ));
                                     return Ok(Some(known_types));
                                 } else {
-                                    debug_println!("Failed getting type name");
+                                    debug!("Failed getting type name");
                                 }
                             }
-                            gimli::DW_TAG_const_type => {
+                            gimli::DW_TAG_const_type | gimli::DW_TAG_volatile_type => {
                                 if let Ok(Some(type_field)) = node.entry().attr(gimli::DW_AT_type) {
                                     known_types =
                                         debugger.decode_type(type_field.value(), known_types)?;
@@ -252,14 +430,17 @@ impl Debugger {
                                     return Ok(Some(known_types));
                                 }
                             }
-                            gimli::DW_TAG_pointer_type => {
+                            // C++ references are pointers which are dereferenced implicitly
+                            gimli::DW_TAG_pointer_type
+                            | gimli::DW_TAG_reference_type
+                            | gimli::DW_TAG_rvalue_reference_type => {
                                 if let Ok(Some(type_field)) = node.entry().attr(gimli::DW_AT_type) {
                                     //TODO: Find fix for recursive types
-                                    // debug_println!(
+                                    // debug!(
                                     //     "Resolving pointer for type {:?}",
                                     //     unit_offset(type_field.value())
                                     // );
-                                    // debug_println!("Known types: {:?}", known_types);
+                                    // debug!("Known types: {:?}", known_types);
                                     let index = known_types.0.iter().position(|e| {
                                         e.0 == unit_offset(type_field.value()).unwrap()
                                     });
@@ -299,10 +480,15 @@ impl Debugger {
                                             child.entry().attr(gimli::DW_AT_count)
                                         {
                                             lengths.push(count.udata_value().unwrap() as usize);
+                                        } else if let Ok(Some(upper_bound)) =
+                                            child.entry().attr(gimli::DW_AT_upper_bound)
+                                        {
+                                            // gcc emits the upper bound instead of the count
+                                            if let Some(upper_bound) = upper_bound.udata_value() {
+                                                lengths.push(upper_bound as usize + 1);
+                                            }
                                         } else {
-                                            debug_println!(
-                                                "Found child entry but failed getting count"
-                                            );
+                                            debug!("Found child entry but failed getting count");
                                         }
                                     }
                                     known_types.0.push((
@@ -336,16 +522,25 @@ impl Debugger {
                                     );
                                     return Ok(Some(known_types));
                                 } else {
-                                    debug_println!("Failed getting array type");
+                                    debug!("Failed getting array type");
                                 }
                             }
-                            gimli::DW_TAG_structure_type => {
+                            gimli::DW_TAG_structure_type | gimli::DW_TAG_class_type => {
                                 let (name, byte_size) = (
                                     node.entry().attr(gimli::DW_AT_name)?,
                                     node.entry().attr(gimli::DW_AT_byte_size)?,
                                 );
                                 let name = if let Some(name) = name {
-                                    Debugger::decode_string_attribute(name.value(), dwarf, &unit)
+                                    // qualified with the namespaces and classes around it
+                                    format!(
+                                        "{}{}",
+                                        scope,
+                                        Debugger::decode_string_attribute(
+                                            name.value(),
+                                            dwarf,
+                                            &unit
+                                        )
+                                    )
                                 } else {
                                     "unnamed struct".to_owned()
                                 };
@@ -355,7 +550,7 @@ impl Debugger {
                                     0
                                 };
                                 // Push Structure first in case of self referential struct
-                                // debug_println!("Decoding struct: {} {:?}", &name, known_types);
+                                // debug!("Decoding struct: {} {:?}", &name, known_types);
 
                                 known_types.0.push((
                                     find_offset.0,
@@ -369,6 +564,10 @@ impl Debugger {
                                 let mut children_iter = node.children();
                                 let mut types: Vec<(String, usize, usize)> = vec![];
                                 while let Ok(Some(child)) = children_iter.next() {
+                                    // methods and nested types of C++ classes
+                                    if child.entry().tag() != gimli::DW_TAG_member {
+                                        continue;
+                                    }
                                     if let (
                                         Ok(Some(name)),
                                         Ok(Some(typeoffset)),
@@ -400,7 +599,7 @@ impl Debugger {
                                         let byteoffset = byteoffset.udata_value().unwrap();
                                         types.push((name, index, byteoffset as usize));
                                     } else {
-                                        debug_println!("Failed to decode member type");
+                                        debug!("Failed to decode member type");
                                     }
                                 }
                                 known_types.0[struct_index] = (
@@ -414,7 +613,7 @@ impl Debugger {
                                 return Ok(Some(known_types));
                             }
                             _ => {
-                                debug_println!(
+                                debug!(
                                     "Invalid entry: {:?}, offset: {:?}",
                                     node.entry().tag(),
                                     node.entry().offset()
@@ -422,13 +621,27 @@ impl Debugger {
                                 return Err(DebugError::InvalidType);
                             }
                         }
-                        debug_println!(
+                        debug!(
                             "Failed parsing entry: {:?}, offset: {:?}",
                             node.entry().tag(),
                             node.entry().offset()
                         );
                         return Err(DebugError::InvalidType);
                     }
+                    let scope = match node.entry().tag() {
+                        gimli::DW_TAG_namespace
+                        | gimli::DW_TAG_structure_type
+                        | gimli::DW_TAG_class_type
+                        | gimli::DW_TAG_union_type => match node.entry().attr(gimli::DW_AT_name)? {
+                            Some(name) => format!(
+                                "{}{}::",
+                                scope,
+                                Debugger::decode_string_attribute(name.value(), dwarf, &unit)
+                            ),
+                            None => scope.to_owned(),
+                        },
+                        _ => scope.to_owned(),
+                    };
                     let mut children = node.children();
                     while let Some(child) = children.next()? {
                         match process_tree(
@@ -437,6 +650,7 @@ impl Debugger {
                             unit_header,
                             find_offset,
                             known_types.clone(),
+                            &scope,
                         )? {
                             Some(t) => {
                                 return Ok(Some(t));
@@ -446,36 +660,57 @@ impl Debugger {
                     }
                     Ok(None)
                 }
-                if let Some(t) = process_tree(self, root, &unit_header, r, known_types.clone())? {
+                if let Some(t) = process_tree(self, root, &unit_header, r, known_types.clone(), "")?
+                {
                     return Ok(t);
                 }
             }
-            debug_println!("Didn't find header");
+            debug!("Didn't find header");
             Err(DebugError::InvalidType)
         } else {
-            debug_println!("Invalid offset type");
+            debug!("Invalid offset type");
             Err(DebugError::InvalidType)
         }
     }
 
+    /// Combines the pieces of a variable to its value, the first piece holds the least
+    /// significant bits
     fn retrieve_pieces<T: gimli::Reader>(
         &self,
         pieces: Vec<gimli::Piece<T>>,
     ) -> Result<u64, DebugError> {
-        let mut value = 0;
+        let mut value = 0u64;
+        let mut shift = 0;
         for piece in pieces {
-            value = value
-                + match piece.location {
-                    gimli::Location::Empty => todo!(),
-                    gimli::Location::Register { register: _ } => todo!(),
-                    gimli::Location::Address { address } => self.read(address as *mut _)?,
-                    gimli::Location::Value { value: _ } => todo!(),
-                    gimli::Location::Bytes { value: _ } => todo!(),
-                    gimli::Location::ImplicitPointer {
-                        value: _,
-                        byte_offset: _,
-                    } => todo!(),
+            let size = piece.size_in_bits.unwrap_or(64).min(64);
+            let bits = match piece.location {
+                gimli::Location::Empty => {
+                    return Err(DebugError::InvalidArgument("Optimized out".to_owned()))
+                }
+                gimli::Location::Register { register } => {
+                    self.get_register_from_abi(register.0)? >> piece.bit_offset.unwrap_or(0)
+                }
+                gimli::Location::Address { address } => self.read(address as *mut _)?,
+                gimli::Location::Value { value } => value.to_u64(!0)?,
+                gimli::Location::Bytes { value } => {
+                    let mut bytes = [0u8; 8];
+                    let value = value.to_slice()?;
+                    let len = value.len().min(8);
+                    bytes[..len].copy_from_slice(&value[..len]);
+                    u64::from_le_bytes(bytes)
                 }
+                gimli::Location::ImplicitPointer { .. } => {
+                    return Err(DebugError::InvalidArgument(
+                        "Implicit pointers are not supported".to_owned(),
+                    ))
+                }
+            };
+            let mask = if size == 64 { !0 } else { (1 << size) - 1 };
+            value |= (bits & mask) << shift;
+            shift += size;
+            if shift >= 64 {
+                break;
+            }
         }
         Ok(value)
     }
@@ -484,9 +719,10 @@ impl Debugger {
         &self,
         unit: &Unit<ConcreteReader>,
         location: Expression<ConcreteReader>,
+        frame: &FrameContext,
     ) -> Result<Vec<gimli::Piece<ConcreteReader>>, DebugError> {
         let mut evaluation = location.evaluation(unit.encoding());
-        let mut result = evaluation.evaluate().unwrap();
+        let mut result = evaluation.evaluate()?;
         while result != EvaluationResult::Complete {
             // println!("{:?}", result);
             match result {
@@ -500,7 +736,7 @@ impl Debugger {
                     // size is at most word size
                     let data = self.read(address as *mut _)?;
                     // println("{:?}", evaluation.state)
-                    debug_println!("{:?} {:?} {:?} {:?}", address, size, space, base_type);
+                    debug!("{:?} {:?} {:?} {:?}", address, size, space, base_type);
                     result = evaluation.resume_with_memory(gimli::Value::Generic(data))?;
                 }
                 EvaluationResult::RequiresRegister {
@@ -511,14 +747,23 @@ impl Debugger {
                     result = evaluation.resume_with_register(gimli::Value::U64(value))?;
                 }
                 EvaluationResult::RequiresFrameBase => {
-                    let base_pointer = Registers::from_regs(self.get_registers()?).base_pointer;
-                    result = evaluation.resume_with_frame_base(base_pointer)?;
+                    let frame_base = frame.frame_base.ok_or(DebugError::InvalidArgument(
+                        "Function has no frame base".to_owned(),
+                    ))?;
+                    result = evaluation.resume_with_frame_base(frame_base)?;
+                }
+                EvaluationResult::RequiresTls(_)
+                | EvaluationResult::RequiresAtLocation(_)
+                | EvaluationResult::RequiresEntryValue(_)
+                | EvaluationResult::RequiresParameterRef(_) => {
+                    return Err(DebugError::InvalidArgument(format!(
+                        "Unsupported location expression: {:?}",
+                        result
+                    )))
+                }
+                EvaluationResult::RequiresCallFrameCfa => {
+                    result = evaluation.resume_with_call_frame_cfa(frame.cfa)?;
                 }
-                EvaluationResult::RequiresTls(_) => todo!(),
-                EvaluationResult::RequiresCallFrameCfa => todo!(),
-                EvaluationResult::RequiresAtLocation(_) => todo!(),
-                EvaluationResult::RequiresEntryValue(_) => todo!(),
-                EvaluationResult::RequiresParameterRef(_) => todo!(),
                 EvaluationResult::RequiresRelocatedAddress(addr) => {
                     // let mut iter = self.dwarf.debug_info.units();
                     // while let Ok(Some(header)) = iter.next() {
@@ -535,117 +780,25 @@ impl Debugger {
                     )?;
                     result = evaluation.resume_with_indexed_address(addr)?;
                 }
-                EvaluationResult::RequiresBaseType(_) => todo!(),
-            }
-        }
-        Ok(evaluation.result())
-    }
-
-    pub fn read_variables(&self) -> Result<Vec<Variable>, DebugError> {
-        let mut sub_entry;
-        let mut unit;
-        let mut variables = Vec::new();
-        let mut curr_high_pc = 0u64;
-        let mut curr_low_pc = 0u64;
-        iter_every_entry!(self, sub_entry unit | {
-            // debug_println!("{:#?}", tag_to_string(sub_entry.tag()));
-            if sub_entry.tag() == gimli::DW_TAG_subprogram || sub_entry.tag() == gimli::DW_TAG_lexical_block{
-
-                if let Ok(Some(lpc)) = sub_entry.attr_value(gimli::DW_AT_low_pc) {
-                    match lpc {
-                        gimli::AttributeValue::Addr(addr) => {
-                            curr_low_pc = addr;
-                        },
-                        gimli::AttributeValue::DebugAddrIndex(i) => {
-                            let addr = self.dwarf.address(&unit, i).unwrap();
-                            curr_low_pc = addr;
+                EvaluationResult::RequiresBaseType(offset) => {
+                    let entry = unit.entry(offset)?;
+                    let value_type = match (
+                        entry.attr_value(gimli::DW_AT_encoding)?,
+                        entry.attr_value(gimli::DW_AT_byte_size)?,
+                    ) {
+                        (Some(gimli::AttributeValue::Encoding(encoding)), Some(byte_size)) => {
+                            byte_size
+                                .udata_value()
+                                .and_then(|size| gimli::ValueType::from_encoding(encoding, size))
                         }
-                        _ => { println!("unexpected low pc value: {:#?}", lpc); }
+                        _ => None,
                     }
+                    .ok_or(DebugError::InvalidType)?;
+                    result = evaluation.resume_with_base_type(value_type)?;
                 }
-
-                if let Ok(Some(hpc)) = sub_entry.attr_value(gimli::DW_AT_high_pc) {
-                    curr_high_pc = curr_low_pc + hpc.udata_value().unwrap();
-                }
-
             }
-            if (sub_entry.tag() == gimli::DW_TAG_variable || sub_entry.tag() == gimli::DW_TAG_formal_parameter) && sub_entry.attr_value(gimli::DW_AT_location)?.is_some() {
-                let mut var = Variable::default();
-
-                var.type_name = self.decode_type(sub_entry.attr(gimli::DW_AT_type)?.unwrap().value(), DataType(vec![])).ok();
-
-                if let Some(name) = sub_entry.attr(gimli::DW_AT_name)? {
-                    var.name = Some(Debugger::decode_string_attribute(
-                        name.value(),
-                        &self.dwarf,
-                        &unit,
-                    ));
-                }
-                if let Some(location) = sub_entry.attr_value(gimli::DW_AT_location)? {
-                    let mut by_offset = |offset| -> Result<(), DebugError> {
-                        let mut locations = self.dwarf.locations(&unit, offset).unwrap();
-                        debug_println!("{:?}", locations);
-                        debug_println!("{:?}", var.name);
-                        let mut expression = None;
-                        while let Some(location) = locations.next()? {
-                            debug_println!("{:?}", location);
-                            if self.get_pc()? >= location.range.begin && self.get_pc()? <= location.range.end {
-                                expression = Some(location.data);
-                            }
-                        }
-                        let expression = expression.unwrap_or_else(|| {
-                            println!("No expression found for variable: {:?} in current scope", var.name);
-                            self.dwarf.locations(&unit, offset).unwrap().next().unwrap().unwrap().data
-                        });
-                        let pieces = self.evaluate_expression(&unit, expression)?;
-                        var.addr = get_piece_addr(&pieces[0]);
-                        var.value = self.retrieve_pieces(pieces).ok();
-                        Ok(())
-                    };
-                    match location {
-                        gimli::AttributeValue::Exprloc(_) | gimli::AttributeValue::Block(_) => {
-                            let location = location.exprloc_value().unwrap();
-                            let pieces = self.evaluate_expression(&unit, location)?;
-                            var.addr = get_piece_addr(&pieces[0]);
-                            var.value = self.retrieve_pieces(pieces).ok()
-                        },
-                        gimli::AttributeValue::LocationListsRef(offset) => {
-                            by_offset(offset)?
-                        },
-                        gimli::AttributeValue::DebugLocListsIndex(i) => {
-                            let offset = self.dwarf.locations_offset(&unit, i).unwrap();
-                            by_offset(offset)?
-                        }
-                        _ => {
-                            println!("Unexpected location type: {:#?}", location);
-                            todo!()
-                        }
-                    }
-                } else {
-                    println!("Missing location for variable: {} (Var: {}) (Param: {})", sub_entry.tag(), gimli::DW_TAG_variable, gimli::DW_TAG_formal_parameter);
-                    let mut iter = sub_entry.attrs();
-                    while let Some(attr) = iter.next()? {
-                        println!("{}: {:#?}", dw_at_to_string(attr.name()), attr.value());
-                    }
-                }
-                if let Some(file) = sub_entry.attr(gimli::DW_AT_decl_file)? {
-                    if let Some(file) = file.string_value(&self.dwarf.debug_str) {
-                        var.file = file.to_string().ok().map(|s| s.to_string());
-                    }
-                }
-                if let Some(line) = sub_entry.attr(gimli::DW_AT_decl_line)? {
-                    if let Some(line) = line.udata_value() {
-                        var.line = Some(line as u64);
-                    }
-                }
-                var.high_pc = curr_high_pc;
-                var.low_pc = curr_low_pc;
-                if self.get_pc()? >= curr_low_pc && self.get_pc()? <= curr_high_pc {
-                    variables.push(var);
-                }
-            }
-        });
-        Ok(variables)
+        }
+        Ok(evaluation.result())
     }
 
     fn get_func_from_addr(&self, addr: u64) -> Result<FunctionMeta, DebugError> {
@@ -656,7 +809,7 @@ impl Debugger {
             self,
             entry unit | {
                 if entry.tag() == gimli::DW_TAG_subprogram {
-                    meta = get_function_meta(&entry, &self.dwarf)?;
+                    meta = get_function_meta(entry, unit, &self.dwarf)?;
                     if let (Some(low_pc), Some(high_pc)) = (meta.low_pc, meta.high_pc) {
                         if addr >= low_pc && addr <= low_pc + high_pc {
                             return Ok(meta);
@@ -665,38 +818,13 @@ impl Debugger {
                 }
             }
         );
-        Err(DebugError::FunctionNotFound)
-    }
-
-    fn backtrace(&self) -> Result<Vec<FunctionMeta>, DebugError> {
-        let mut bt = Vec::<FunctionMeta>::new();
-        let pc = self.get_pc()?;
-        let mut func_meta = self.get_func_from_addr(pc)?;
-        bt.push(func_meta.clone());
-        let mut frame_pointer = Registers::from_regs(self.get_registers()?).base_pointer;
-        let mut return_addr = self.read((frame_pointer + 8) as *mut _)?;
-        let mut max_depth = 20;
-        while func_meta.name != Some("main".to_string()) {
-            max_depth -= 1;
-            if max_depth == 0 {
-                break;
-            }
-            let func_meta_res = self.get_func_from_addr(return_addr);
-            if func_meta_res.is_ok() {
-                func_meta = func_meta_res.unwrap();
-                bt.push(func_meta.clone());
-                frame_pointer = self.read(frame_pointer as *mut _)?;
-                return_addr = self.read((frame_pointer + 8) as *mut _)?;
-            } else {
-                bt.push(FunctionMeta {
-                    name: None,
-                    low_pc: None,
-                    high_pc: None,
-                    return_addr: None,
-                });
-            }
+        if !self.has_debug_info {
+            return self.find_symbol_function(|f| {
+                f.low_pc
+                    .is_some_and(|low_pc| low_pc <= addr && addr < low_pc + f.high_pc.unwrap_or(0))
+            });
         }
-        Ok(bt)
+        Err(DebugError::FunctionNotFound)
     }
 
     fn print_current_location(
@@ -706,7 +834,7 @@ impl Debugger {
         let pc = Registers::from_regs(self.get_registers()?).instruction_pointer;
         let line = get_line_from_pc(&self.dwarf, pc)?;
         let mut lines = Vec::new();
-        let file = fs::read_to_string(line.file).unwrap();
+        let file = self.read_source(Path::new(&line.file))?;
         for (index, line_str) in file.lines().enumerate() {
             if index as u64 >= line.line - window as u64
                 && index as u64 <= line.line + window as u64
@@ -726,94 +854,158 @@ impl Debugger {
         let mut unit;
         let mut vars = 0;
         let mut functions = 0;
-        let mut files = Vec::new();
         iter_every_entry!(self, entry unit | {
             if entry.tag() == gimli::DW_TAG_variable {
                 vars += 1;
             } else if entry.tag() == gimli::DW_TAG_subprogram {
                 functions += 1;
             }
-            let name = unit.name.clone();
-            if let Some(name) = name {
-                if let Ok(name) = name.to_string() {
-                    let name = name.to_string();
-                    if !files.contains(&name) {
-                        files.push(name);
-                    }
-                }
-            }
         });
+        let files = self
+            .source_files()
+            .iter()
+            .map(|f| f.display().to_string())
+            .collect();
+        let producers = self.producers();
         Ok(DebugMeta {
             binary_name: self.program.to_str().unwrap().to_owned(),
             file_type: format!("{:?}", self.dwarf.file_type),
             functions,
             vars,
             files,
+            has_debug_info: self.has_debug_info,
+            debug_file: self
+                .debug_file
+                .as_ref()
+                .map(|file| file.display().to_string()),
+            optimization: optimization::optimization(&producers),
+            producers,
         })
     }
 
-    pub fn get_maps(&self) -> Result<Vec<MemoryMap>, DebugError> {
-        let maps = std::fs::read_to_string(format!("/proc/{}/maps", self.child))?;
-        let lines = maps.lines();
-        use regex::Regex;
-        let re = Regex::new(
-                    r"^([0-9a-fA-F]+)-([0-9a-fA-F]+) (r|-)(w|-)(x|-)(p|s) ([0-9a-fA-f]+) [0-9a-fA-F]+:[0-9a-fA-F]+ [0-9]+ *(.+)?"
-                )
-                .unwrap();
-        let mut maps: Vec<MemoryMap> = Vec::new();
-        for line in lines {
-            let captures = re.captures(line).unwrap();
-            maps.push(MemoryMap {
-                from: u64::from_str_radix(&captures[1], 16).unwrap(),
-                to: u64::from_str_radix(&captures[2], 16).unwrap(),
-                read: &captures[3] == "r",
-                write: &captures[4] == "w",
-                execute: &captures[5] == "x",
-                shared: &captures[6] == "s",
-                offset: u64::from_str_radix(&captures[7], 16).unwrap(),
-                mapped: captures.get(8).map_or("", |m| m.as_str()).to_owned(),
-            });
+    /// Executes the command and records it for replaying the session
+    pub fn process_command(&mut self, command: Command) -> Result<CommandOutput, DebugError> {
+        let recorded = command.clone();
+        let _span = debug_span!("command", command = ?recorded).entered();
+        let output = self.execute_command(command);
+        if let Err(e) = &output {
+            debug!("Failed: {:?}", e);
         }
-        Ok(maps)
+        self.record_command(recorded, &output);
+        output
     }
 
-    pub fn process_command(&mut self, command: Command) -> Result<CommandOutput, DebugError> {
+    fn execute_command(&mut self, command: Command) -> Result<CommandOutput, DebugError> {
+        if !self.has_debug_info && requires_debug_info(&command) {
+            return Err(DebugError::NoDebugInfo);
+        }
+        if self.detached
+            && !matches!(
+                command,
+                Command::Quit | Command::ExportSession | Command::GetDiagnostics
+            )
+        {
+            return Err(DebugError::Detached);
+        }
+        self.poll_child()?;
+        if self.is_running()
+            && !matches!(
+                command,
+                Command::GetStatus
+                    | Command::Interrupt
+                    | Command::Quit
+                    | Command::DetachDebugger
+                    | Command::DebugMeta
+                    | Command::GetBreakpoints
+                    | Command::ExportSession
+                    | Command::GetDiagnostics
+                    | Command::SetContinueTimeout(_)
+            )
+        {
+            return Err(DebugError::ChildRunning);
+        }
         match command {
             Command::Maps => Ok(CommandOutput::Maps(self.get_maps()?)),
+            Command::MapsDiff => Ok(CommandOutput::MapChanges(self.maps_diff())),
+            Command::GetProcessStack => Ok(CommandOutput::ProcessStack(self.process_stack()?)),
+            Command::PointerInfo(expression) => {
+                Ok(CommandOutput::PointerInfo(self.pointer_info(&expression)?))
+            }
+            Command::FindReferences(addr) => {
+                Ok(CommandOutput::References(self.find_references(addr)?))
+            }
+            Command::ExportVariables(format) => {
+                Ok(CommandOutput::File(self.export_variables(format)?))
+            }
+            Command::ExportSession => Ok(CommandOutput::File(self.export_session())),
+            Command::ExportSnapshot(format) => {
+                Ok(CommandOutput::Snapshot(self.export_snapshot(format)?))
+            }
+            Command::GenerateReport(format) => {
+                Ok(CommandOutput::File(self.generate_report(format)?))
+            }
             Command::RestartDebugee => {
+                let exec = crate::DebuggeeExec::new(&self.program, &self.sandbox)?;
                 // Get locations for breakpoints, addresses may change during reload
-                let lines: Vec<Location> = self
+                let lines: Vec<(Location, Option<SourceAnchor>)> = self
                     .breakpoints
                     .iter()
-                    .map(|b| b.location.clone())
+                    .filter(|b| !self.library_breakpoints.contains(b.address))
+                    .map(|b| (b.location.clone(), b.anchor))
                     .collect();
+                let library_categories = self.library_breakpoints.take_categories();
                 for breakpoint in self.breakpoints.iter_mut() {
                     let _ = breakpoint.disable(self.child);
                 }
                 self.breakpoints.clear();
+                self.history.reset();
+                self.actions.reset();
+                self.counters = RunCounters::default();
+                self.tracer.reset();
+                self.line_counters.reset();
+                self.invariants.reset();
+                self.profile = None;
+                if let Some(exercise) = self.exercise.as_mut() {
+                    exercise.reset();
+                }
+                self.quiz.reset();
                 match ptrace::kill(self.child) {
-                    Ok(a) => debug_println!("Killed child: {:?}", a),
-                    Err(e) => debug_println!("Failed to kill child: {:?}", e),
+                    Ok(a) => debug!("Killed child: {:?}", a),
+                    Err(e) => debug!("Failed to kill child: {:?}", e),
                 };
                 match unsafe { fork() } {
                     Ok(fr) => match fr {
-                        Child => {
-                            crate::debuggee_init(self.program.clone()).unwrap();
-                            unreachable!();
-                        }
+                        Child => crate::debuggee_init(&exec),
                         Parent { child } => {
                             self.child = child;
+                            self.started = SystemTime::now();
                             self.waitpid()?;
+                            self.reset_maps();
                             // Reload binary to get updated debug info
-                            self.dwarf = Debugger::create_dwarf_reader(&self.program);
-                            // Enable breakpoints in the new process
-                            for line in lines {
-                                // Find address in new debug info
-                                let addr = get_addr_from_line(&self.dwarf, line.line, line.file)?;
-                                let mut breakpoint =
-                                    Breakpoint::new(&self.dwarf, self.child, addr as *const u8)?;
-                                breakpoint.enable(self.child)?;
-                                self.breakpoints.push(breakpoint);
+                            self.debug_file = debug_file::find_debug_file(&self.program);
+                            self.dwarf = Debugger::create_dwarf_reader(
+                                self.debug_file.as_ref().unwrap_or(&self.program),
+                            );
+                            self.files = FileIndex::new(&self.dwarf);
+                            self.start();
+                            // Enable breakpoints in the new process, the code of some may be
+                            // gone after a rebuild
+                            for (location, anchor) in lines {
+                                if let Err(e) = self.restore_breakpoint(&location, anchor.as_ref())
+                                {
+                                    warn!(
+                                        "Dropped the breakpoint at {}:{} when restarting: {:?}",
+                                        location.file, location.line, e
+                                    );
+                                }
+                            }
+                            for category in library_categories {
+                                if let Err(e) = self.break_on_library_calls(category, true) {
+                                    warn!(
+                                        "Dropped the breakpoints on calls of {:?} when restarting: {:?}",
+                                        category, e
+                                    );
+                                }
                             }
                             Ok(CommandOutput::None)
                         }
@@ -823,99 +1015,340 @@ impl Debugger {
             }
             Command::Disassemble => Ok(CommandOutput::File(
                 std::str::from_utf8(
-                    &std::process::Command::new("objdump")
-                        .arg("--disassemble")
-                        .arg(self.program.clone().into_os_string())
-                        .output()?
-                        .stdout,
+                    &self.program_output(
+                        std::process::Command::new("objdump")
+                            .arg("--disassemble")
+                            .arg(self.program.clone().into_os_string()),
+                    )?,
                 )?
                 .to_string(),
             )),
+            Command::ResolveAddress(address) => Ok(CommandOutput::ResolvedAddress(
+                self.resolve_address(address)?,
+            )),
+            Command::GetPltEntries => Ok(CommandOutput::PltEntries(self.plt_entries()?)),
+            Command::DisassembleFunction(function) => Ok(CommandOutput::FunctionDisassembly(
+                self.disassemble_function(&function)?,
+            )),
             Command::ReadMemory(addr, size) => {
+                let size = self.readable_length(addr, size)?;
                 Ok(CommandOutput::Memory(self.read_memory(addr, size)?))
             }
-            Command::GetFunctions => Ok(CommandOutput::Functions(get_functions(&self.dwarf)?)),
+            Command::WriteMemory(addr, bytes) => {
+                let old = self.read_memory(addr, bytes.len() as u64)?;
+                self.write_memory(addr, &bytes)?;
+                self.actions.record(Action::WriteMemory {
+                    address: addr,
+                    old,
+                    new: bytes,
+                });
+                Ok(CommandOutput::None)
+            }
+            Command::ReadAround(addr, before, after) => Ok(CommandOutput::MemoryChunk(
+                self.read_around(addr, before, after)?,
+            )),
+            Command::GetFunctions if !self.has_debug_info => {
+                Ok(CommandOutput::Functions(self.symbol_functions()?))
+            }
+            Command::GetFunctions => Ok(CommandOutput::Functions(self.functions()?)),
+            Command::FindFunctions { pattern, user_code } => Ok(CommandOutput::FunctionGroups(
+                self.function_groups(pattern.as_deref(), user_code)?,
+            )),
             Command::WaitPid => {
                 self.waitpid_flag(Some(WaitPidFlag::WNOHANG))?;
                 Ok(CommandOutput::None)
             }
-            Command::GetFile(filename) => Ok(CommandOutput::File(fs::read_to_string(filename)?)),
-            Command::GetBreakpoints => Ok(CommandOutput::Breakpoints(self.breakpoints.clone())),
+            Command::GetFile(filename) => {
+                let path = self.readable_source(&filename)?;
+                Ok(CommandOutput::File(self.read_source(&path)?))
+            }
+            Command::GetSource(filename) => {
+                let path = self.readable_source(&filename)?;
+                Ok(CommandOutput::Source(self.source_file(&path)))
+            }
+            Command::GetBreakpoints => Ok(CommandOutput::Breakpoints(
+                self.breakpoints
+                    .iter()
+                    .filter(|b| !self.library_breakpoints.contains(b.address))
+                    .cloned()
+                    .collect(),
+            )),
             Command::DebugMeta => Ok(CommandOutput::DebugMeta(self.debug_meta()?)),
+            Command::GetElfInfo => Ok(CommandOutput::ElfInfo(self.elf_info()?)),
             Command::DumpDwarf => Ok(CommandOutput::DwarfAttributes(self.dump_dwarf_attrs()?)),
+            Command::DumpTypes => Ok(CommandOutput::File(self.dump_types()?)),
             Command::Help => Ok(CommandOutput::Help(CommandCompleter::default().commands)),
             Command::Backtrace => Ok(CommandOutput::Backtrace(self.backtrace()?)),
+            Command::GetStackFrames => Ok(CommandOutput::StackFrames(
+                self.stack_frames_with_arguments()?,
+            )),
+            Command::GetVariableScopes => Ok(CommandOutput::VariableScopes(
+                self.variable_scope()?.into_iter().collect(),
+            )),
             Command::ReadVariables => Ok(CommandOutput::Variables(self.read_variables()?)),
             Command::DiscoverVariables => Ok(CommandOutput::DiscoveredVariables(
                 self.discover_variables()?,
             )),
             Command::Read(addr) => Ok(CommandOutput::Data(self.read(addr as *mut _)?)),
             Command::Continue => {
-                self.continue_exec()?;
+                if self.has_watches() {
+                    self.continue_watched()?;
+                } else if self.tracer.enabled() || self.has_invariants() {
+                    self.continue_traced()?;
+                } else {
+                    self.continue_async()?;
+                    if self.is_running() {
+                        return Ok(CommandOutput::Status(self.status()));
+                    }
+                }
+                self.record_stop();
+                Ok(CommandOutput::StopState(self.stop_state()))
+            }
+            Command::Interrupt => {
+                self.interrupt()?;
+                Ok(CommandOutput::StopState(self.stop_state()))
+            }
+            Command::GetStatus => Ok(CommandOutput::Status(self.status())),
+            Command::SetContinueTimeout(seconds) => {
+                self.set_continue_timeout(seconds);
+                Ok(CommandOutput::None)
+            }
+            Command::SetStepSkipList(patterns) => {
+                self.set_step_skips(patterns);
+                Ok(CommandOutput::None)
+            }
+            Command::SetSourceMap(mappings) => {
+                self.set_source_map(mappings);
+                Ok(CommandOutput::None)
+            }
+            Command::Quit => {
+                // exiting doesn't drop the debugger
+                if let Err(e) = self.detach() {
+                    warn!("Failed to detach from child: {:?}", e);
+                }
+                std::process::exit(0)
+            }
+            Command::DetachDebugger => {
+                self.detach()?;
+                Ok(CommandOutput::None)
+            }
+            Command::BreakOnAllocation(enabled) => {
+                self.break_on_library_calls(LibraryCategory::Allocation, enabled)?;
                 Ok(CommandOutput::None)
             }
-            Command::Quit => std::process::exit(0),
-            Command::StepOut => self.step_out().map(|_| CommandOutput::None),
+            Command::BreakOnIO(enabled) => {
+                self.break_on_library_calls(LibraryCategory::Io, enabled)?;
+                Ok(CommandOutput::None)
+            }
+            Command::StepOut => {
+                let return_value = self.step_out_with_return_value()?;
+                self.record_stop();
+                Ok(CommandOutput::StopState(StopState {
+                    return_value: Some(return_value),
+                    ..self.stop_state()
+                }))
+            }
+            Command::FinishFrames(count) => {
+                let returned_frames = self.finish_frames(count)?;
+                self.record_stop();
+                Ok(CommandOutput::StopState(StopState {
+                    returned_frames,
+                    ..self.stop_state()
+                }))
+            }
             Command::FindLine { line, filename } => {
-                let addr = get_addr_from_line(&self.dwarf, line, filename)?;
-                Ok(CommandOutput::Data(addr))
+                let file = self.files.resolve(&filename)?;
+                Ok(CommandOutput::LineAddresses(get_line_addresses(
+                    &self.dwarf,
+                    line,
+                    &file,
+                )?))
             }
             Command::FindFunc(name) => {
                 let func = find_function_from_name(&self.dwarf, name);
                 Ok(CommandOutput::FunctionMeta(func?))
             }
-            Command::StepIn => self.step_in().map(|_| CommandOutput::None),
-            Command::StepInstruction => self.step_instruction().map(|_| CommandOutput::None),
+            Command::StepIn => {
+                self.step_in()?;
+                self.record_stop();
+                Ok(CommandOutput::StopState(self.stop_state()))
+            }
+            Command::StepStatement => {
+                self.step_statement()?;
+                self.record_stop();
+                Ok(CommandOutput::StopState(self.stop_state()))
+            }
+            Command::StepIntoTarget(function) => {
+                self.step_into_target(&function)?;
+                self.record_stop();
+                Ok(CommandOutput::StopState(self.stop_state()))
+            }
+            Command::GetLineCalls => Ok(CommandOutput::LineCalls(self.line_calls()?)),
+            Command::StepInstruction => {
+                self.step_instruction()?;
+                self.record_stop();
+                Ok(CommandOutput::StopState(self.stop_state()))
+            }
+            Command::StepInstructionN(count) => {
+                for _ in 0..count {
+                    self.step_instruction()?;
+                    if self.has_exited() {
+                        break;
+                    }
+                }
+                self.record_stop();
+                Ok(CommandOutput::StopState(self.stop_state()))
+            }
+            Command::TrackVariable(name) => {
+                self.track_variable(name);
+                Ok(CommandOutput::None)
+            }
+            Command::UntrackVariable(name) => {
+                self.untrack_variable(&name);
+                Ok(CommandOutput::None)
+            }
+            Command::SetTracing(enabled) => {
+                self.tracer.set_enabled(enabled);
+                // the current line is the first line of the trace
+                self.record_trace();
+                Ok(CommandOutput::None)
+            }
+            Command::Profile(period) => {
+                self.profile(period)?;
+                self.record_stop();
+                Ok(CommandOutput::None)
+            }
+            Command::GetProfile => Ok(CommandOutput::Profile(self.profile.clone().ok_or(
+                DebugError::InvalidArgument("No profile has been recorded yet".to_owned()),
+            )?)),
+            Command::GetExercise => Ok(CommandOutput::Exercise(self.get_exercise())),
+            Command::AddQuizQuestion(question) => {
+                self.add_quiz_question(question)?;
+                Ok(CommandOutput::Quiz(self.get_quiz()))
+            }
+            Command::RemoveQuizQuestion(question) => {
+                self.remove_quiz_question(&question);
+                Ok(CommandOutput::Quiz(self.get_quiz()))
+            }
+            Command::GetQuiz => Ok(CommandOutput::Quiz(self.get_quiz())),
+            Command::AnswerQuiz { expression, answer } => Ok(CommandOutput::QuizAnswer(
+                self.answer_quiz(&expression, &answer)?,
+            )),
+            Command::GetDiagnostics => Ok(CommandOutput::Diagnostics(self.diagnostics())),
+            // the webserver cancels requests as they come in, without waiting for the debugger
+            Command::Cancel(_) => Err(DebugError::InvalidCommand(
+                "Only requests to the webserver can be cancelled".to_owned(),
+            )),
+            Command::GetExecutionTrace => Ok(CommandOutput::ExecutionTrace(self.tracer.trace())),
+            Command::GetLineHitCounts => Ok(CommandOutput::LineHitCounts(self.line_hit_counts())),
+            Command::AddInvariant(expression) => {
+                self.add_invariant(expression)?;
+                Ok(CommandOutput::Invariants(self.get_invariants()))
+            }
+            Command::RemoveInvariant(expression) => {
+                self.remove_invariant(&expression)?;
+                Ok(CommandOutput::Invariants(self.get_invariants()))
+            }
+            Command::GetInvariants => Ok(CommandOutput::Invariants(self.get_invariants())),
+            Command::AddWatch {
+                expression,
+                function,
+            } => {
+                self.add_watch(expression, function)?;
+                Ok(CommandOutput::Watches(self.get_watches()))
+            }
+            Command::RemoveWatch(expression) => {
+                self.remove_watch(&expression)?;
+                Ok(CommandOutput::Watches(self.get_watches()))
+            }
+            Command::GetWatches => Ok(CommandOutput::Watches(self.get_watches())),
+            Command::GetDataStructures => {
+                Ok(CommandOutput::DataStructures(self.data_structures()?))
+            }
+            Command::GetVariableHistory(name) => Ok(CommandOutput::VariableHistory(
+                self.variable_history(&name)?,
+            )),
             Command::ProgramCounter => Ok(CommandOutput::Data(
                 Registers::from_regs(self.get_registers()?).instruction_pointer,
             )),
             Command::SetBreakpoint(a) => match a {
-                BreakpointPoint::Name(name) => {
-                    debug_println!("Name: '{}'", &name);
-                    let func = find_function_from_name(&self.dwarf, name)?;
+                BreakpointPoint::Name(ref name) | BreakpointPoint::Entry(ref name)
+                    if !self.has_debug_info =>
+                {
+                    self.set_symbol_breakpoint(name)?;
+                    Ok(CommandOutput::None)
+                }
+                BreakpointPoint::Name(ref name) | BreakpointPoint::Entry(ref name) => {
+                    debug!("Name: '{}'", &name);
+                    let func = find_function_from_name(&self.dwarf, name.clone())?;
                     if let Some(addr) = func.low_pc {
-                        debug_println!(
+                        // Break after the prologue so the frame of the function is already set up
+                        let addr = match a {
+                            BreakpointPoint::Name(_) => get_post_prologue_addr(
+                                &self.dwarf,
+                                addr,
+                                func.high_pc.unwrap_or(0),
+                            )?,
+                            _ => addr,
+                        };
+                        debug!(
                             "Setting breakpoint at function: {:?} {:#x} for {:?}",
-                            func.name,
-                            addr,
-                            self.child
+                            func.name, addr, self.child
                         );
                         if self.breakpoints.iter().any(|b| b.address == addr) {
                             return Err(DebugError::BreakpointInvalidState);
                         }
-                        let mut breakpoint =
-                            Breakpoint::new(&self.dwarf, self.child, addr as *const u8)?;
-                        breakpoint.enable(self.child)?;
-                        self.breakpoints.push(breakpoint);
+                        let breakpoint = self.add_breakpoint(Breakpoint::new(
+                            &self.dwarf,
+                            self.child,
+                            addr as *const u8,
+                        )?)?;
+                        self.actions.record(Action::SetBreakpoint(breakpoint));
                     } else {
-                        debug_println!("Couldn't find function: {:?}", func.name);
+                        debug!("Couldn't find function: {:?}", func.name);
                     }
                     Ok(CommandOutput::None)
                 }
                 BreakpointPoint::Address(addr) => {
-                    debug_println!("Setting breakpoint at address: {:?}", addr);
+                    debug!("Setting breakpoint at address: {:?}", addr);
 
                     if self.breakpoints.iter().any(|b| b.address == addr) {
                         return Err(DebugError::BreakpointInvalidState);
                     }
-                    let mut breakpoint =
-                        Breakpoint::new(&self.dwarf, self.child, addr as *const u8)?;
-                    breakpoint.enable(self.child)?;
-                    self.breakpoints.push(breakpoint);
+                    self.check_breakpoint_address(addr)?;
+                    let breakpoint = match self.has_debug_info {
+                        true => Breakpoint::new(&self.dwarf, self.child, addr as *const u8)?,
+                        false => self.symbol_breakpoint(addr)?,
+                    };
+                    let breakpoint = self.add_breakpoint(breakpoint)?;
+                    self.actions.record(Action::SetBreakpoint(breakpoint));
                     Ok(CommandOutput::None)
                 }
                 BreakpointPoint::Location(location) => {
-                    debug_println!("Setting a breakpoint at location: {:?}", location);
-                    let addr = get_addr_from_line(&self.dwarf, location.line, location.file)?;
+                    debug!("Setting a breakpoint at location: {:?}", location);
+                    let file = self.files.resolve(&location.file)?;
+                    let addresses = get_line_addresses(&self.dwarf, location.line, &file)?;
+                    let addr = addresses.canonical;
 
                     if self.breakpoints.iter().any(|b| b.address == addr) {
                         return Err(DebugError::BreakpointInvalidState);
                     }
-                    let mut breakpoint =
-                        Breakpoint::new(&self.dwarf, self.child, addr as *const u8)?;
-                    breakpoint.enable(self.child)?;
-                    self.breakpoints.push(breakpoint);
-                    Ok(CommandOutput::None)
+                    let breakpoint = self.add_breakpoint(Breakpoint::new(
+                        &self.dwarf,
+                        self.child,
+                        addr as *const u8,
+                    )?)?;
+                    self.actions
+                        .record(Action::SetBreakpoint(breakpoint.clone()));
+                    Ok(CommandOutput::PlacedBreakpoint(PlacedBreakpoint {
+                        breakpoint,
+                        moved: (addresses.line != location.line).then(|| {
+                            format!(
+                                "line {} has no code, breakpoint moved to line {}",
+                                location.line, addresses.line
+                            )
+                        }),
+                    }))
                 }
             },
             Command::ViewSource(window) => self
@@ -925,30 +1358,110 @@ impl Debugger {
                 let regs = self.get_registers()?;
                 Ok(CommandOutput::Registers(Registers::from_regs(regs)))
             }
+            Command::GetFullRegisters => {
+                Ok(CommandOutput::FullRegisters(self.get_full_registers()?))
+            }
+            Command::ExplainBranch => Ok(CommandOutput::BranchExplanation(self.explain_branch()?)),
+            Command::SetRegister(name, value) => {
+                let old = self.get_register(&name)?;
+                self.set_register(&name, value)?;
+                self.actions.record(Action::SetRegister {
+                    name,
+                    old,
+                    new: value,
+                });
+                Ok(CommandOutput::None)
+            }
             Command::Location => Ok(CommandOutput::Location(get_line_from_pc(
                 &self.dwarf,
                 self.get_pc()?,
             )?)),
             Command::DeleteBreakpoint(address) => {
-                match self
-                    .breakpoints
-                    .iter_mut()
-                    .find(|breakpoint| breakpoint.address == address)
-                {
-                    Some(breakpoint) => {
-                        breakpoint.disable(self.child)?;
-                        self.breakpoints = self
-                            .breakpoints
-                            .iter()
-                            .filter(|b| b.address != address)
-                            .map(|b| b.clone())
-                            .collect();
-                        Ok(CommandOutput::None)
-                    }
-                    None => Err(DebugError::FunctionNotFound),
-                }
+                let breakpoint = self.delete_breakpoint(address)?;
+                self.actions.record(Action::DeleteBreakpoint(breakpoint));
+                Ok(CommandOutput::None)
             }
+            Command::Undo => {
+                self.undo()?;
+                Ok(CommandOutput::Actions(self.actions.actions()))
+            }
+            Command::GetActions => Ok(CommandOutput::Actions(self.actions.actions())),
+        }
+    }
+
+    /// Enables a breakpoint set by the user and remembers the text of its line
+    fn add_breakpoint(&mut self, mut breakpoint: Breakpoint) -> Result<Breakpoint, DebugError> {
+        breakpoint.anchor = self
+            .read_source(Path::new(&breakpoint.location.file))
+            .ok()
+            .and_then(|source| source_anchor(&source, &breakpoint.location));
+        self.memory.clear();
+        breakpoint.enable(self.child)?;
+        self.breakpoints.push(breakpoint.clone());
+        Ok(breakpoint)
+    }
+
+    /// Sets a breakpoint of the previous child at the same line of the reloaded program. The
+    /// source may have changed, so the line with the text of `anchor` is used if there is one.
+    fn restore_breakpoint(
+        &mut self,
+        location: &Location,
+        anchor: Option<&SourceAnchor>,
+    ) -> Result<(), DebugError> {
+        let line = match anchor {
+            Some(anchor) => match self.read_source(Path::new(&location.file)) {
+                Ok(source) => relocate_anchor(&source, location, anchor),
+                Err(_) => location.line,
+            },
+            None => location.line,
+        };
+        let file = self.files.resolve(&location.file)?;
+        let addr = get_addr_from_line(&self.dwarf, line, &file)?;
+        self.add_breakpoint(Breakpoint::new(&self.dwarf, self.child, addr as *const u8)?)?;
+        Ok(())
+    }
+
+    /// Disables and removes the breakpoint at `address`
+    fn delete_breakpoint(&mut self, address: u64) -> Result<Breakpoint, DebugError> {
+        let index = self
+            .breakpoints
+            .iter()
+            .position(|breakpoint| breakpoint.address == address)
+            .ok_or(DebugError::FunctionNotFound)?;
+        self.memory.clear();
+        self.breakpoints[index].disable(self.child)?;
+        Ok(self.breakpoints.remove(index))
+    }
+
+    /// Restores the original instructions at all enabled breakpoints and detaches from the
+    /// child, so it doesn't crash on a leftover `int3` once the debugger is gone
+    pub fn detach(&mut self) -> Result<(), DebugError> {
+        if self.detached {
+            return Ok(());
         }
+        if self.is_running() {
+            self.interrupt()?;
+        }
+        self.detached = true;
+        for breakpoint in self.breakpoints.iter_mut().filter(|b| b.enabled) {
+            match breakpoint.disable(self.child) {
+                // the child already exited
+                Err(DebugError::NixError(nix::errno::Errno::ESRCH)) => return Ok(()),
+                result => result?,
+            }
+        }
+        self.breakpoints.clear();
+        match ptrace::detach(self.child, None) {
+            Ok(()) | Err(nix::errno::Errno::ESRCH) => Ok(()),
+            Err(e) => Err(DebugError::NixError(e)),
+        }
+    }
+
+    /// Kills the child instead of detaching from it, e.g. before launching another program
+    pub fn terminate(&mut self) {
+        self.detached = true;
+        let _ = nix::sys::signal::kill(self.child, nix::sys::signal::Signal::SIGKILL);
+        let _ = waitpid(self.child, None);
     }
 
     pub fn debug_loop(mut self) -> Result<(), DebugError> {
@@ -958,31 +1471,81 @@ impl Debugger {
         }
     }
 
-    // #[allow(dead_code)]
-    // fn write(&self, addr: *mut c_void, data: u64) -> Result<(), DebugError> {
-    //     match unsafe { ptrace::write(self.child, addr, data as *mut _) } {
-    //         Ok(_) => Ok(()),
-    //         Err(e) => Err(DebugError::NixError(e)),
-    //     }
-    // }
+    fn write(&self, addr: *mut c_void, data: u64) -> Result<(), DebugError> {
+        let _span = trace_span!("ptrace_write", ?addr).entered();
+        self.memory.clear();
+        match unsafe { ptrace::write(self.child, addr, data as *mut _) } {
+            Ok(_) => Ok(()),
+            Err(e) => Err(DebugError::NixError(e)),
+        }
+    }
+
+    /// Writes `bytes` to the memory of the debugee, one byte at a time like
+    /// [`read_memory`](Debugger::read_memory)
+    pub fn write_memory(&self, addr: u64, bytes: &[u8]) -> Result<(), DebugError> {
+        for (i, byte) in bytes.iter().enumerate() {
+            let address = (addr + i as u64) as *mut c_void;
+            let word = self.read(address)?;
+            self.write(address, (word & !0xff) | *byte as u64)?;
+        }
+        Ok(())
+    }
 
     pub fn read(&self, addr: *mut c_void) -> Result<u64, DebugError> {
+        let _span = trace_span!("ptrace_read", ?addr).entered();
         match ptrace::read(self.child, addr) {
             Ok(d) => Ok(d as u64),
             Err(e) => Err(DebugError::NixError(e)),
         }
     }
 
+    /// Reads `len` bytes at `addr`, bytes read before since the child stopped are taken from
+    /// the [`MemoryCache`]
     pub fn read_memory(&self, addr: u64, len: u64) -> Result<Vec<u8>, DebugError> {
-        let mut values = vec![];
-        // debug_println!("Reading @ {:#x} : {}", addr, len);
-        for i in 0..len {
-            let v = ptrace::read(self.child, (addr + i as u64) as *mut c_void)?;
-            values.push((v & 0xFF) as u8);
+        if let Some(values) = self.memory.get(addr, len) {
+            return Ok(values);
+        }
+        let _span = trace_span!("ptrace_read_memory", addr, len).entered();
+        // ptrace reads whole words, the aligned ones don't reach past the end of the region
+        let word = std::mem::size_of::<std::os::raw::c_long>() as u64;
+        let end = addr.saturating_add(len);
+        let mut values = Vec::with_capacity(len as usize);
+        let mut word_addr = addr - addr % word;
+        while word_addr < end {
+            let bytes = ptrace::read(self.child, word_addr as *mut c_void)?.to_ne_bytes();
+            let from = addr.saturating_sub(word_addr) as usize;
+            let to = (end - word_addr).min(word) as usize;
+            values.extend_from_slice(&bytes[from..to]);
+            word_addr += word;
         }
+        self.memory.insert(addr, &values);
         Ok(values)
     }
 
+    pub fn read_around(
+        &self,
+        addr: u64,
+        before: u64,
+        after: u64,
+    ) -> Result<MemoryChunk, DebugError> {
+        let maps = self.get_maps()?;
+        let region = maps
+            .iter()
+            .find(|m| m.read && m.from <= addr && addr < m.to)
+            .ok_or_else(|| unreadable_address(&maps, addr))?;
+        let from = addr
+            .saturating_sub(before.min(MAX_READ_AROUND))
+            .max(region.from);
+        let to = addr
+            .saturating_add(after.min(MAX_READ_AROUND))
+            .min(region.to);
+        Ok(MemoryChunk {
+            from,
+            memory: self.read_memory(from, to - from)?,
+            region: region.label.clone(),
+        })
+    }
+
     fn get_pc(&self) -> Result<u64, DebugError> {
         Ok(Registers::from_regs(self.get_registers()?).instruction_pointer)
     }
@@ -1008,12 +1571,20 @@ impl Debugger {
             ptrace::step(self.child, None)?;
             self.waitpid()?;
         }
+        self.counters.instructions += 1;
+        self.count_line_hit();
+        self.record_trace();
         Ok(())
     }
 
     fn step_out(&mut self) -> Result<(), DebugError> {
         let fp = Registers::from_regs(self.get_registers()?).base_pointer;
         let ra = self.read((fp + 8) as *mut c_void)?;
+        self.run_to(ra)
+    }
+
+    /// Continues until `ra` is reached, or an earlier breakpoint or the end of the program
+    fn run_to(&mut self, ra: u64) -> Result<(), DebugError> {
         let bp: Vec<_> = self
             .breakpoints
             .iter()
@@ -1025,12 +1596,14 @@ impl Debugger {
             let mut breakpoint = Breakpoint::new(&self.dwarf, self.child, ra as *const u8)?;
             breakpoint.enable(self.child)?;
             self.continue_exec()?;
+            self.memory.clear();
             breakpoint.disable(self.child)?;
             Ok(())
         } else if bp.len() == 1 {
             let index = bp[0];
             self.breakpoints[index].enable(self.child)?;
             self.continue_exec()?;
+            self.memory.clear();
             self.breakpoints[index].disable(self.child)?;
             Ok(())
         } else {
@@ -1038,12 +1611,43 @@ impl Debugger {
         }
     }
 
-    fn step_in(&mut self) -> Result<(), DebugError> {
-        let line = get_line_from_pc(&self.dwarf, self.get_pc()?)?.line;
-        while get_line_from_pc(&self.dwarf, self.get_pc()?)?.line == line {
+    /// Steps until `function` is entered from the current line, the calls of other functions
+    /// run to their return and the instructions of the line are stepped
+    fn step_into_target(&mut self, function: &str) -> Result<(), DebugError> {
+        let meta = find_function_from_name(&self.dwarf, function.to_owned())?;
+        let Some(entry) = meta.low_pc else {
+            return Err(DebugError::FunctionNotFound);
+        };
+        let body = get_post_prologue_addr(&self.dwarf, entry, meta.high_pc.unwrap_or(0))?;
+        let line = get_line_containing_addr(&self.dwarf, self.get_pc()?)?.line;
+        loop {
+            let before = Registers::from_regs(self.get_registers()?);
             self.step_instruction()?;
+            if self.has_exited() {
+                return Ok(());
+            }
+            let pc = self.get_pc()?;
+            if pc == entry {
+                // stop after the prologue like a breakpoint on the function
+                return match body == entry {
+                    true => Ok(()),
+                    false => self.run_to(body),
+                };
+            }
+            if let Some(ra) = self.called_from(&before)? {
+                self.run_to(ra)?;
+                // stopped by a breakpoint in the called function or the end of the program
+                if self.has_exited() || self.get_pc()? != ra {
+                    return Ok(());
+                }
+                continue;
+            }
+            match get_line_containing_addr(&self.dwarf, pc) {
+                Ok(location) if location.line == line => {}
+                // the line is done or the function returned without calling `function`
+                _ => return Ok(()),
+            }
         }
-        Ok(())
     }
 
     fn step_breakpoint(&mut self) -> Result<(), DebugError> {
@@ -1069,42 +1673,54 @@ impl Debugger {
             Err(DebugError::BreakpointInvalidState)
         }
     }
-    pub fn waitpid(&self) -> Result<(), DebugError> {
+    pub fn waitpid(&mut self) -> Result<(), DebugError> {
         self.waitpid_flag(Some(WaitPidFlag::WUNTRACED))
     }
 
-    pub fn waitpid_flag(&self, flags: Option<WaitPidFlag>) -> Result<(), DebugError> {
+    /// Waits like `wait_status`, resuming the child from the loop counters it hits
+    pub fn waitpid_flag(&mut self, flags: Option<WaitPidFlag>) -> Result<(), DebugError> {
+        let since = self.state_since;
+        self.wait_status(flags)?;
+        self.resume_line_counters(flags, since)
+    }
+
+    fn wait_status(&mut self, flags: Option<WaitPidFlag>) -> Result<(), DebugError> {
+        let _span = trace_span!("waitpid", ?flags).entered();
         match waitpid(self.child, flags) {
             Ok(s) => match s {
                 nix::sys::wait::WaitStatus::Exited(pid, status) => {
-                    debug_println!("Child {} exited with status: {}", pid, status);
+                    debug!("Child {} exited with status: {}", pid, status);
+                    self.stop_reason = StopReason::Exited(status);
+                    self.set_state(DebugeeState::Exited(status));
                     Ok(())
                 }
                 nix::sys::wait::WaitStatus::Signaled(pid, status, coredump) => {
-                    debug_println!(
+                    debug!(
                         "Child {} signaled with status: {:?} and coredump: {}",
-                        pid,
-                        status,
-                        coredump
+                        pid, status, coredump
                     );
+                    self.stop_reason = StopReason::Killed(status.as_str().to_owned());
+                    self.set_state(DebugeeState::Signaled(status.as_str().to_owned()));
                     Ok(())
                 }
                 nix::sys::wait::WaitStatus::Stopped(pid, signal) => {
+                    self.stop_reason = StopReason::Signal(signal.as_str().to_owned());
+                    self.set_state(DebugeeState::Stopped);
                     match signal {
                         nix::sys::signal::Signal::SIGTRAP => {
                             let siginfo = nix::sys::ptrace::getsiginfo(pid)?;
                             // I think nix doesn't have a constant for this
                             if siginfo.si_code == 128 {
-                                debug_println!("Hit breakpoint!");
+                                debug!("Hit breakpoint!");
+                                self.stop_reason = StopReason::Breakpoint;
 
                                 // step back one instruction
                                 self.set_pc(self.get_pc()? - 1)?;
                             } else {
-                                debug_println!(
+                                self.stop_reason = StopReason::Step;
+                                debug!(
                                     "Child {} stopped with {:?} and code {}",
-                                    pid,
-                                    siginfo,
-                                    siginfo.si_code
+                                    pid, siginfo, siginfo.si_code
                                 );
                             }
                         }
@@ -1112,38 +1728,38 @@ impl Debugger {
                             println!("Segmentation fault!");
 
                             match ptrace::kill(self.child) {
-                                Ok(a) => debug_println!("Killed child: {:?}", a),
-                                Err(e) => debug_println!("Failed to kill child: {:?}", e),
+                                Ok(a) => debug!("Killed child: {:?}", a),
+                                Err(e) => debug!("Failed to kill child: {:?}", e),
                             }
+                            // reap the child, it exited because of the segmentation fault
+                            let _ = waitpid(self.child, None);
+                            self.stop_reason = StopReason::Killed(signal.as_str().to_owned());
+                            self.set_state(DebugeeState::Signaled(signal.as_str().to_owned()));
                         }
                         _ => {
-                            debug_println!("Child {} stopped with signal: {:?}", pid, signal);
+                            debug!("Child {} stopped with signal: {:?}", pid, signal);
                         }
                     }
                     Ok(())
                 }
                 nix::sys::wait::WaitStatus::Continued(pid) => {
-                    debug_println!("Child {} continued", pid);
+                    debug!("Child {} continued", pid);
                     Ok(())
                 }
                 #[cfg(target_os = "linux")]
-                nix::sys::wait::WaitStatus::StillAlive => {
-                    debug_println!("Child is still alive");
-                    Ok(())
-                }
+                // the running child is polled with WNOHANG
+                nix::sys::wait::WaitStatus::StillAlive => Ok(()),
                 #[cfg(target_os = "linux")]
                 nix::sys::wait::WaitStatus::PtraceEvent(pid, signal, int) => {
-                    debug_println!(
+                    debug!(
                         "Child {} ptrace event with signal: {:?} and int: {}",
-                        pid,
-                        signal,
-                        int
+                        pid, signal, int
                     );
                     Ok(())
                 }
                 #[cfg(target_os = "linux")]
                 nix::sys::wait::WaitStatus::PtraceSyscall(pid) => {
-                    debug_println!("Child {} ptrace syscall", pid);
+                    debug!("Child {} ptrace syscall", pid);
                     Ok(())
                 }
             },
@@ -1151,15 +1767,45 @@ impl Debugger {
         }
     }
 
+    /// Records everything that is kept per stop, called after every command which resumed the
+    /// debugee
+    fn record_stop(&mut self) {
+        // memory changed by a step is reported like a change while continuing
+        if let Some(reason) = self.check_watches() {
+            self.stop_reason = reason;
+        }
+        if let Some(expression) = self.check_invariants() {
+            self.stop_reason = StopReason::InvariantBroken(expression);
+        }
+        self.count_stop();
+        self.record_history();
+        self.record_maps();
+        self.record_session_stop();
+        self.advance_exercise();
+        self.ask_quiz_questions();
+    }
+
     fn continue_exec(&mut self) -> Result<(), DebugError> {
         match self.step_breakpoint() {
             Ok(_) => (),
             Err(DebugError::NoBreakpointFound) => {
-                debug_println!("Warning: continuing execution from non-breakpoint");
+                debug!("Warning: continuing execution from non-breakpoint");
             }
             Err(e) => return Err(e),
         }
+        self.insert_line_counters()?;
+        if self.has_exited() {
+            return Ok(());
+        }
         ptrace::cont(self.child, None).map_err(|e| DebugError::NixError(e))?;
         self.waitpid()
     }
 }
+
+impl Drop for Debugger {
+    fn drop(&mut self) {
+        if let Err(e) = self.detach() {
+            warn!("Failed to detach from child {}: {:?}", self.child, e);
+        }
+    }
+}
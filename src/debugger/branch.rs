@@ -0,0 +1,219 @@
+use stackium_shared::{BranchExplanation, Flag};
+
+use super::{error::DebugError, registers::decode_flags, Debugger};
+
+/// What a conditional instruction does if its condition holds
+enum Kind {
+    Jump,
+    Move,
+    Set,
+}
+
+impl Kind {
+    fn outcome(&self, taken: bool) -> &'static str {
+        match (self, taken) {
+            (Kind::Jump, true) => "the jump will be taken",
+            (Kind::Jump, false) => "the jump won't be taken, execution falls through",
+            (Kind::Move, true) => "the value will be moved",
+            (Kind::Move, false) => "the destination keeps its value",
+            (Kind::Set, true) => "the destination will be set to 1",
+            (Kind::Set, false) => "the destination will be set to 0",
+        }
+    }
+}
+
+/// Meaning, condition and tested flags of a condition code
+type Condition = (&'static str, &'static str, &'static [&'static str]);
+
+/// Splits a mnemonic like `jle` or `cmovne` into its kind and condition code
+#[cfg(target_arch = "x86_64")]
+fn split_mnemonic(mnemonic: &str) -> Option<(Kind, &str)> {
+    if let Some(code) = mnemonic.strip_prefix("cmov") {
+        Some((Kind::Move, code))
+    } else if let Some(code) = mnemonic.strip_prefix("set") {
+        Some((Kind::Set, code))
+    } else if mnemonic == "jmp" {
+        None
+    } else {
+        mnemonic.strip_prefix('j').map(|code| (Kind::Jump, code))
+    }
+}
+/// Splits a mnemonic like `b.eq` into its kind and condition code
+#[cfg(target_arch = "aarch64")]
+fn split_mnemonic(mnemonic: &str) -> Option<(Kind, &str)> {
+    mnemonic.strip_prefix("b.").map(|code| (Kind::Jump, code))
+}
+
+/// Evaluates a condition code given a lookup of the flags
+#[cfg(target_arch = "x86_64")]
+fn condition(code: &str, flag: impl Fn(&str) -> bool) -> Option<(Condition, bool)> {
+    Some(match code {
+        "a" | "nbe" => (
+            ("above (unsigned >)", "CF = 0 and ZF = 0", &["CF", "ZF"]),
+            !flag("CF") && !flag("ZF"),
+        ),
+        "ae" | "nb" | "nc" => (
+            ("above or equal (unsigned >=)", "CF = 0", &["CF"]),
+            !flag("CF"),
+        ),
+        "b" | "nae" | "c" => (("below (unsigned <)", "CF = 1", &["CF"]), flag("CF")),
+        "be" | "na" => (
+            (
+                "below or equal (unsigned <=)",
+                "CF = 1 or ZF = 1",
+                &["CF", "ZF"],
+            ),
+            flag("CF") || flag("ZF"),
+        ),
+        "e" | "z" => (("equal", "ZF = 1", &["ZF"]), flag("ZF")),
+        "ne" | "nz" => (("not equal", "ZF = 0", &["ZF"]), !flag("ZF")),
+        "g" | "nle" => (
+            (
+                "greater (signed >)",
+                "ZF = 0 and SF = OF",
+                &["ZF", "SF", "OF"],
+            ),
+            !flag("ZF") && flag("SF") == flag("OF"),
+        ),
+        "ge" | "nl" => (
+            ("greater or equal (signed >=)", "SF = OF", &["SF", "OF"]),
+            flag("SF") == flag("OF"),
+        ),
+        "l" | "nge" => (
+            ("less (signed <)", "SF != OF", &["SF", "OF"]),
+            flag("SF") != flag("OF"),
+        ),
+        "le" | "ng" => (
+            (
+                "less or equal (signed <=)",
+                "ZF = 1 or SF != OF",
+                &["ZF", "SF", "OF"],
+            ),
+            flag("ZF") || flag("SF") != flag("OF"),
+        ),
+        "o" => (("overflow", "OF = 1", &["OF"]), flag("OF")),
+        "no" => (("no overflow", "OF = 0", &["OF"]), !flag("OF")),
+        "s" => (("sign (negative)", "SF = 1", &["SF"]), flag("SF")),
+        "ns" => (("no sign (not negative)", "SF = 0", &["SF"]), !flag("SF")),
+        "p" | "pe" => (("parity even", "PF = 1", &["PF"]), flag("PF")),
+        "np" | "po" => (("parity odd", "PF = 0", &["PF"]), !flag("PF")),
+        _ => return None,
+    })
+}
+/// Evaluates a condition code given a lookup of the flags
+#[cfg(target_arch = "aarch64")]
+fn condition(code: &str, flag: impl Fn(&str) -> bool) -> Option<(Condition, bool)> {
+    Some(match code {
+        "eq" => (("equal", "Z = 1", &["Z"]), flag("Z")),
+        "ne" => (("not equal", "Z = 0", &["Z"]), !flag("Z")),
+        "cs" | "hs" => (
+            ("carry set / unsigned higher or same (>=)", "C = 1", &["C"]),
+            flag("C"),
+        ),
+        "cc" | "lo" => (
+            ("carry clear / unsigned lower (<)", "C = 0", &["C"]),
+            !flag("C"),
+        ),
+        "mi" => (("minus (negative)", "N = 1", &["N"]), flag("N")),
+        "pl" => (("plus (positive or zero)", "N = 0", &["N"]), !flag("N")),
+        "vs" => (("overflow", "V = 1", &["V"]), flag("V")),
+        "vc" => (("no overflow", "V = 0", &["V"]), !flag("V")),
+        "hi" => (
+            ("unsigned higher (>)", "C = 1 and Z = 0", &["C", "Z"]),
+            flag("C") && !flag("Z"),
+        ),
+        "ls" => (
+            ("unsigned lower or same (<=)", "C = 0 or Z = 1", &["C", "Z"]),
+            !flag("C") || flag("Z"),
+        ),
+        "ge" => (
+            ("signed greater or equal (>=)", "N = V", &["N", "V"]),
+            flag("N") == flag("V"),
+        ),
+        "lt" => (
+            ("signed less (<)", "N != V", &["N", "V"]),
+            flag("N") != flag("V"),
+        ),
+        "gt" => (
+            ("signed greater (>)", "Z = 0 and N = V", &["Z", "N", "V"]),
+            !flag("Z") && flag("N") == flag("V"),
+        ),
+        "le" => (
+            (
+                "signed less or equal (<=)",
+                "Z = 1 or N != V",
+                &["Z", "N", "V"],
+            ),
+            flag("Z") || flag("N") != flag("V"),
+        ),
+        _ => return None,
+    })
+}
+
+#[cfg(target_arch = "x86_64")]
+fn flags_register(regs: &nix::libc::user_regs_struct) -> u64 {
+    regs.eflags
+}
+#[cfg(target_arch = "aarch64")]
+fn flags_register(regs: &nix::libc::user_regs_struct) -> u64 {
+    regs.pstate
+}
+
+impl Debugger {
+    pub fn explain_branch(&self) -> Result<BranchExplanation, DebugError> {
+        let address = self.get_pc()?;
+        let instruction = self
+            .disassemble_range(address, address + 16)?
+            .into_iter()
+            .find(|i| i.address == address)
+            .ok_or(DebugError::InvalidPC(address))?
+            .text;
+        let flags = decode_flags(flags_register(&self.get_registers()?));
+        let flag = |name: &str| flags.iter().any(|f| f.name == name && f.set);
+        let mnemonic = instruction
+            .split_whitespace()
+            .find(|word| !matches!(*word, "bnd" | "notrack" | "rep" | "repz" | "repnz"))
+            .unwrap_or_default();
+
+        let Some(((kind, code), ((meaning, condition, tested), taken))) = split_mnemonic(mnemonic)
+            .and_then(|(kind, code)| {
+                condition(code, flag).map(|condition| ((kind, code), condition))
+            })
+        else {
+            return Ok(BranchExplanation {
+                address,
+                condition: None,
+                tested_flags: vec![],
+                taken: None,
+                explanation: format!("`{}` doesn't depend on the flags", mnemonic),
+                instruction,
+            });
+        };
+        let tested_flags = tested
+            .iter()
+            .filter_map(|name| flags.iter().find(|f| f.name == *name))
+            .cloned()
+            .collect::<Vec<Flag>>();
+        let current = tested_flags
+            .iter()
+            .map(|f| format!("{} = {}", f.name, f.set as u8))
+            .collect::<Vec<_>>()
+            .join(", ");
+        Ok(BranchExplanation {
+            address,
+            explanation: format!(
+                "`{}` tests the condition code `{}`, {}: {}. Currently {}, so {}.",
+                mnemonic,
+                code,
+                meaning,
+                condition,
+                current,
+                kind.outcome(taken)
+            ),
+            instruction,
+            condition: Some(condition.to_owned()),
+            tested_flags,
+            taken: Some(taken),
+        })
+    }
+}
@@ -1,16 +1,26 @@
-use std::ffi::c_void;
+use std::{
+    ffi::c_void,
+    hash::{DefaultHasher, Hash, Hasher},
+};
 
 use nix::{sys::ptrace, unistd::Pid};
-use stackium_shared::Breakpoint;
+use stackium_shared::{Breakpoint, Location, SourceAnchor};
+use tracing::debug;
 
-use super::{error::DebugError, util::get_line_from_pc};
+use super::{dwarf_index::IndexedDwarf, error::DebugError, util::get_line_containing_addr};
 
 pub trait DebuggerBreakpoint {
     fn new<T: gimli::Reader>(
-        dwarf: &gimli::Dwarf<T>,
+        dwarf: &IndexedDwarf<T>,
         child: Pid,
         address: *const u8,
     ) -> Result<Breakpoint, DebugError>;
+    /// Breakpoint at an address without line information, e.g. in the PLT
+    fn with_location(
+        child: Pid,
+        address: *const u8,
+        location: Location,
+    ) -> Result<Breakpoint, DebugError>;
     fn replace_byte(&self, child: Pid, byte: u8) -> Result<(), DebugError>;
     fn enable(&mut self, child: Pid) -> Result<(), DebugError>;
     fn replace_4_bytes(&self, child: Pid, bytes: u32) -> Result<(), DebugError>;
@@ -19,22 +29,32 @@ pub trait DebuggerBreakpoint {
 
 impl DebuggerBreakpoint for Breakpoint {
     fn new<T: gimli::Reader>(
-        dwarf: &gimli::Dwarf<T>,
+        dwarf: &IndexedDwarf<T>,
         child: Pid,
         address: *const u8,
     ) -> Result<Self, DebugError> {
-        let location = get_line_from_pc(dwarf, address as u64)?;
+        // return addresses are usually in the middle of a line
+        let location = get_line_containing_addr(dwarf, address as u64)?;
+        Self::with_location(child, address, location)
+    }
+
+    fn with_location(
+        child: Pid,
+        address: *const u8,
+        location: Location,
+    ) -> Result<Self, DebugError> {
         Ok(Self {
             address: address as u64,
             original_byte: match ptrace::read(child, address as *mut _) {
                 Ok(b) => b as u32,
                 Err(e) => {
-                    println!("Error in ptrace::read: {} {:?} {:?}", e, child, address);
+                    debug!("Error in ptrace::read: {} {:?} {:?}", e, child, address);
                     return Err(DebugError::NixError(e));
                 }
             },
             enabled: false,
             location,
+            anchor: None,
         })
     }
 
@@ -135,3 +155,49 @@ impl DebuggerBreakpoint for Breakpoint {
         Ok(())
     }
 }
+
+fn hash_lines(lines: &[&str]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    for line in lines {
+        line.trim().hash(&mut hasher);
+    }
+    hasher.finish()
+}
+
+fn anchor_at(lines: &[&str], index: usize) -> SourceAnchor {
+    SourceAnchor {
+        line: hash_lines(&lines[index..=index]),
+        context: hash_lines(&lines[index.saturating_sub(1)..(index + 2).min(lines.len())]),
+    }
+}
+
+/// Anchor of the line of `location` in the `source` of its file as it is now
+pub fn source_anchor(source: &str, location: &Location) -> Option<SourceAnchor> {
+    let lines = source.lines().collect::<Vec<_>>();
+    let index = (location.line as usize)
+        .checked_sub(1)
+        .filter(|i| *i < lines.len())?;
+    Some(anchor_at(&lines, index))
+}
+
+/// The line the text of `anchor` moved to in the current `source` of the file. Lines matching
+/// with their surroundings are preferred over lines only matching themselves, if several lines
+/// match the one closest to the old line is taken. Keeps the line of `location` if no line
+/// matches.
+pub fn relocate_anchor(source: &str, location: &Location, anchor: &SourceAnchor) -> u64 {
+    let lines = source.lines().collect::<Vec<_>>();
+    let anchors = (0..lines.len())
+        .map(|i| anchor_at(&lines, i))
+        .collect::<Vec<_>>();
+    let closest = |matches: &dyn Fn(&SourceAnchor) -> bool| {
+        anchors
+            .iter()
+            .enumerate()
+            .filter(|(_, a)| matches(a))
+            .map(|(i, _)| i as u64 + 1)
+            .min_by_key(|line| line.abs_diff(location.line))
+    };
+    closest(&|a| a.context == anchor.context)
+        .or_else(|| closest(&|a| a.line == anchor.line))
+        .unwrap_or(location.line)
+}
@@ -0,0 +1,120 @@
+use std::path::Path;
+
+use object::{Object, ObjectSection, SectionKind};
+
+use super::{error::DebugError, maps::describe_region, Debugger};
+
+impl Debugger {
+    /// The instruction of the program closest to `file_address`, in the function of the symbol
+    /// table containing it or else the closest function
+    fn nearest_instruction(&self, file_address: u64) -> Option<u64> {
+        let distance = |(from, to): &(u64, u64)| match file_address < *from {
+            true => from - file_address,
+            false => file_address.saturating_sub(to - 1),
+        };
+        let (from, to) = self
+            .symbol_functions()
+            .ok()?
+            .into_iter()
+            .filter_map(|f| Some((f.low_pc?, f.low_pc? + f.high_pc?)))
+            .filter(|(from, to)| from < to)
+            .min_by_key(distance)?;
+        self.disassemble_range(from, to)
+            .ok()?
+            .into_iter()
+            .map(|i| i.address)
+            .min_by_key(|address| address.abs_diff(file_address))
+    }
+
+    /// Suggestion added to the errors of [`Self::check_breakpoint_address`], e.g.
+    /// `, the nearest instruction is at 0x401136 (main+0x4 (test.c:5))`
+    fn nearest_instruction_hint(&self, file_address: u64, load_bias: u64) -> String {
+        let Some(nearest) = self.nearest_instruction(file_address) else {
+            return String::new();
+        };
+        let address = nearest + load_bias;
+        match self.resolve_address(address) {
+            Ok(resolved) => format!(
+                ", the nearest instruction is at {:#x} ({})",
+                address, resolved.description
+            ),
+            Err(_) => format!(", the nearest instruction is at {:#x}", address),
+        }
+    }
+
+    /// Refuses to break at addresses the `int3` of a breakpoint would crash the child at
+    /// instead of stopping it: unmapped and non-executable memory, sections of the program
+    /// without code and the middle of an instruction, whose other bytes would be run as a
+    /// different one. Code of libraries is only checked against the memory map.
+    pub fn check_breakpoint_address(&self, address: u64) -> Result<(), DebugError> {
+        let maps = self.get_maps().unwrap_or_default();
+        let bin = std::fs::read(&self.program)?;
+        let object_file = object::File::parse(&bin[..])
+            .map_err(|e| DebugError::InvalidArgument(e.to_string()))?;
+        let load_bias = self.load_bias(&object_file, &maps);
+        let file_address = address.wrapping_sub(load_bias);
+        let refuse = |problem: String| {
+            Err(DebugError::InvalidBreakpointAddress(
+                problem + &self.nearest_instruction_hint(file_address, load_bias),
+            ))
+        };
+
+        let map = maps.iter().find(|m| m.from <= address && address < m.to);
+        match map {
+            // the memory map can't be read on every platform
+            None if maps.is_empty() => {}
+            None => return refuse(format!("{:#x} is not mapped", address)),
+            Some(map) if !map.execute => {
+                return refuse(format!(
+                    "{:#x} is in {}, which isn't executable",
+                    address,
+                    describe_region(map)
+                ))
+            }
+            Some(_) => {}
+        }
+        let program = std::fs::canonicalize(&self.program).unwrap_or(self.program.clone());
+        if map.is_some_and(|map| Path::new(&map.mapped) != program) {
+            return Ok(());
+        }
+
+        let section = object_file.sections().find(|s| {
+            s.address() <= file_address && file_address < s.address() + s.size() && s.size() > 0
+        });
+        match section {
+            Some(section) if section.kind() == SectionKind::Text => {}
+            Some(section) => {
+                return refuse(format!(
+                    "{:#x} is in the {} section of the program, which has no code",
+                    address,
+                    section.name().unwrap_or("unnamed")
+                ))
+            }
+            None => return refuse(format!("{:#x} isn't in a section of the program", address)),
+        }
+
+        let Ok(function) = self.find_symbol_function(|f| {
+            f.low_pc.is_some_and(|low_pc| {
+                low_pc <= file_address && file_address < low_pc + f.high_pc.unwrap_or(0)
+            })
+        }) else {
+            return Ok(());
+        };
+        let (Some(low_pc), Some(length)) = (function.low_pc, function.high_pc) else {
+            return Ok(());
+        };
+        let instructions = self.disassemble_range(low_pc, low_pc + length)?;
+        if instructions.is_empty() || instructions.iter().any(|i| i.address == file_address) {
+            return Ok(());
+        }
+        match instructions.iter().rev().find(|i| i.address < file_address) {
+            Some(instruction) => refuse(format!(
+                "{:#x} is inside the instruction `{}` at {:#x}",
+                address,
+                instruction.text,
+                instruction.address + load_bias
+            )),
+            None => refuse(format!("{:#x} isn't the start of an instruction", address)),
+        }
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    io::Read,
+    process::Stdio,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use super::{error::DebugError, Debugger};
+
+/// How often a program run by a command is checked for the cancellation of the command
+const POLL_INTERVAL: Duration = Duration::from_millis(20);
+/// How many instructions commands single-stepping the child execute between checks for their
+/// cancellation
+pub const STEPS_BETWEEN_CHECKS: u64 = 1000;
+
+/// Set by the webserver when the client of the running command cancels it with `Cancel`
+#[derive(Clone, Default)]
+pub struct Cancellation(Arc<AtomicBool>);
+
+impl Cancellation {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl Debugger {
+    /// Lets long commands stop with [`DebugError::Cancelled`] when `cancellation` is cancelled
+    /// until the next call
+    pub fn set_cancellation(&mut self, cancellation: Cancellation) {
+        self.cancellation = cancellation;
+    }
+
+    /// Called between the steps of long commands
+    pub fn check_cancelled(&self) -> Result<(), DebugError> {
+        match self.cancellation.is_cancelled() {
+            true => Err(DebugError::Cancelled),
+            false => Ok(()),
+        }
+    }
+
+    /// Runs `command` and returns its standard output, kills it when the command of the debugger
+    /// running it is cancelled
+    pub fn program_output(
+        &self,
+        command: &mut std::process::Command,
+    ) -> Result<Vec<u8>, DebugError> {
+        let mut child = command
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        // read while the program runs, it blocks once the pipe is full
+        let mut stdout = child.stdout.take().expect("stdout is piped");
+        let reader = std::thread::spawn(move || {
+            let mut content = vec![];
+            stdout.read_to_end(&mut content).map(|_| content)
+        });
+        while child.try_wait()?.is_none() {
+            if self.cancellation.is_cancelled() {
+                child.kill()?;
+                child.wait()?;
+                return Err(DebugError::Cancelled);
+            }
+            std::thread::sleep(POLL_INTERVAL);
+        }
+        Ok(reader.join().expect("reading stdout doesn't panic")?)
+    }
+}
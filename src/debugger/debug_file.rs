@@ -0,0 +1,87 @@
+use std::path::{Path, PathBuf};
+
+use object::Object;
+use tracing::{debug, info};
+
+/// Where distributions install the separate debug files of their packages
+const DEBUG_DIRECTORY: &str = "/usr/lib/debug";
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+/// Whether the file is the one `.gnu_debuglink` names, which records the CRC-32 of its content
+fn matches_crc(path: &Path, crc: u32) -> bool {
+    let Ok(content) = std::fs::read(path) else {
+        return false;
+    };
+    let mut sum = flate2::Crc::new();
+    sum.update(&content);
+    sum.sum() == crc
+}
+
+/// Downloads the debug file with `debuginfod-find` from the servers in `DEBUGINFOD_URLS`, which
+/// caches it and prints its path
+fn debuginfod(build_id: &str) -> Option<PathBuf> {
+    std::env::var_os("DEBUGINFOD_URLS").filter(|urls| !urls.is_empty())?;
+    info!(
+        "Looking up the debug information of {} with debuginfod",
+        build_id
+    );
+    let output = std::process::Command::new("debuginfod-find")
+        .args(["debuginfo", build_id])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        debug!(
+            "debuginfod-find failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        return None;
+    }
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    path.exists().then_some(path)
+}
+
+/// The separate file holding the debug information of a program which was stripped of it, like
+/// the programs and libraries of distributions. Looked up like GDB does by the build ID in
+/// `/usr/lib/debug/.build-id`, by the name in `.gnu_debuglink` next to the program, in its
+/// `.debug` directory and below `/usr/lib/debug` and finally with debuginfod. `None` if the
+/// program has its own debug information or no debug file was found.
+pub fn find_debug_file(program: &Path) -> Option<PathBuf> {
+    let bin = std::fs::read(program).ok()?;
+    let object_file = object::File::parse(&bin[..]).ok()?;
+    // also finds `.zdebug_info` of programs compressed with `-gz=zlib-gnu`
+    if object_file.section_by_name(".debug_info").is_some() {
+        return None;
+    }
+    let build_id = object_file.build_id().ok().flatten().map(hex);
+    if let Some(build_id) = build_id.as_ref().filter(|id| id.len() > 2) {
+        let path = Path::new(DEBUG_DIRECTORY)
+            .join(".build-id")
+            .join(&build_id[..2])
+            .join(format!("{}.debug", &build_id[2..]));
+        if path.exists() {
+            return Some(path);
+        }
+    }
+    if let Ok(Some((name, crc))) = object_file.gnu_debuglink() {
+        let name = String::from_utf8_lossy(name).into_owned();
+        let program = std::fs::canonicalize(program).unwrap_or(program.to_path_buf());
+        let directory = program.parent().unwrap_or(Path::new("/"));
+        let candidates = [
+            directory.join(&name),
+            directory.join(".debug").join(&name),
+            Path::new(DEBUG_DIRECTORY)
+                .join(directory.strip_prefix("/").unwrap_or(directory))
+                .join(&name),
+        ];
+        if let Some(path) = candidates
+            .into_iter()
+            .find(|path| *path != program && matches_crc(path, crc))
+        {
+            return Some(path);
+        }
+    }
+    build_id.as_deref().and_then(debuginfod)
+}
@@ -0,0 +1,23 @@
+use std::borrow::Cow;
+
+use cpp_demangle::DemangleOptions;
+
+/// Readable name of a symbol mangled by Rust (legacy `_ZN..17h<hash>E` or v0 `_R`) or C++ (Itanium
+/// ABI `_Z`), e.g. `shapes::Rect::area` for `_ZNK6shapes4Rect4areaEv`. The parameters and the
+/// hash of Rust are left out, so the name can be used for breakpoints. Names which aren't
+/// mangled like the ones of C are returned unchanged.
+pub fn demangle(name: &str) -> Cow<'_, str> {
+    if let Ok(demangled) = rustc_demangle::try_demangle(name) {
+        return Cow::Owned(format!("{:#}", demangled));
+    }
+    if name.starts_with("_Z") {
+        let options = DemangleOptions::new().no_params().no_return_type();
+        if let Some(demangled) = cpp_demangle::Symbol::new(name)
+            .ok()
+            .and_then(|symbol| symbol.demangle_with_options(&options).ok())
+        {
+            return Cow::Owned(demangled);
+        }
+    }
+    Cow::Borrowed(name)
+}
@@ -0,0 +1,291 @@
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    fmt::Write,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::Instant,
+};
+
+use stackium_shared::{Diagnostics, LogEntry, VERSION};
+use tracing::{
+    field::{Field, Visit},
+    level_filters::LevelFilter,
+    span,
+    subscriber::Interest,
+    Event, Level, Metadata, Subscriber,
+};
+
+use super::Debugger;
+
+/// Only the most recent messages are kept for `GetDiagnostics` and `/logs`
+const MAX_LOG_ENTRIES: usize = 2000;
+
+static LOG: Mutex<VecDeque<LogEntry>> = Mutex::new(VecDeque::new());
+
+thread_local! {
+    /// Spans entered on this thread, the innermost comes last
+    static ENTERED: RefCell<Vec<u64>> = const { RefCell::new(vec![]) };
+}
+
+/// Collects a message and the other fields of an event or span like `addr=0x1000`
+#[derive(Default)]
+struct Fields {
+    message: String,
+    fields: String,
+}
+
+impl Visit for Fields {
+    fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+        match field.name() {
+            "message" => {
+                let _ = write!(self.message, "{:?}", value);
+            }
+            name => {
+                let _ = write!(self.fields, " {}={:?}", name, value);
+            }
+        }
+    }
+}
+
+struct OpenSpan {
+    name: &'static str,
+    /// The fields recorded so far, see [`Fields`]
+    fields: String,
+    level: Level,
+    opened: Instant,
+    /// Open handles of the span, it closes when the last one is dropped
+    references: usize,
+}
+
+/// The fields of a span like `{addr=0x1000}`, nothing if it has none
+fn braced(fields: &str) -> String {
+    match fields.trim_start() {
+        "" => String::new(),
+        fields => format!("{{{}}}", fields),
+    }
+}
+
+impl OpenSpan {
+    fn describe(&self) -> String {
+        self.name.to_owned() + &braced(&self.fields)
+    }
+}
+
+/// Prints messages up to the level of `STACKIUM_LOG` (`error`, `warn`, `info`, `debug` or
+/// `trace`) to stderr, `debug` in debug builds and `warn` in release builds by default. The
+/// most recent messages up to `debug` are also kept for bug reports, even if they aren't
+/// printed. The time spent in spans is logged when they close, e.g. for the walks of the debug
+/// information, the ptrace calls are traced in spans at the `trace` level.
+struct Logger {
+    printed: Level,
+    kept: Level,
+    started: Instant,
+    next_span: AtomicU64,
+    spans: Mutex<HashMap<u64, OpenSpan>>,
+}
+
+impl Logger {
+    fn new() -> Self {
+        let default = match cfg!(debug_assertions) {
+            true => Level::DEBUG,
+            false => Level::WARN,
+        };
+        let printed = std::env::var("STACKIUM_LOG")
+            .ok()
+            .and_then(|level| Level::from_str(&level).ok())
+            .unwrap_or(default);
+        Self {
+            printed,
+            kept: printed.max(Level::DEBUG),
+            started: Instant::now(),
+            next_span: AtomicU64::new(1),
+            spans: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn log(&self, level: Level, target: &str, message: String) {
+        let spans = ENTERED.with(|entered| {
+            let spans = self.spans.lock().unwrap();
+            entered
+                .borrow()
+                .iter()
+                .filter_map(|id| spans.get(id).map(OpenSpan::describe))
+                .collect::<Vec<_>>()
+        });
+        let entry = LogEntry {
+            time: self.started.elapsed().as_millis() as u64,
+            level: level.to_string(),
+            target: target.to_owned(),
+            spans,
+            message,
+        };
+        if level <= self.printed {
+            eprintln!("{}", format_entry(&entry));
+        }
+        let mut log = LOG.lock().unwrap();
+        if log.len() == MAX_LOG_ENTRIES {
+            log.pop_front();
+        }
+        log.push_back(entry);
+    }
+}
+
+impl Subscriber for Logger {
+    fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+        match self.enabled(metadata) {
+            true => Interest::always(),
+            false => Interest::never(),
+        }
+    }
+
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() <= self.kept
+    }
+
+    fn max_level_hint(&self) -> Option<LevelFilter> {
+        Some(LevelFilter::from_level(self.kept))
+    }
+
+    fn new_span(&self, attributes: &span::Attributes<'_>) -> span::Id {
+        let mut fields = Fields::default();
+        attributes.record(&mut fields);
+        let id = self.next_span.fetch_add(1, Ordering::Relaxed);
+        self.spans.lock().unwrap().insert(
+            id,
+            OpenSpan {
+                name: attributes.metadata().name(),
+                fields: fields.fields,
+                level: *attributes.metadata().level(),
+                opened: Instant::now(),
+                references: 1,
+            },
+        );
+        span::Id::from_u64(id)
+    }
+
+    fn record(&self, span: &span::Id, values: &span::Record<'_>) {
+        let mut fields = Fields::default();
+        values.record(&mut fields);
+        if let Some(span) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            span.fields.push_str(&fields.fields);
+        }
+    }
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut fields = Fields::default();
+        event.record(&mut fields);
+        self.log(
+            *event.metadata().level(),
+            event.metadata().target(),
+            fields.message + &fields.fields,
+        );
+    }
+
+    fn enter(&self, span: &span::Id) {
+        ENTERED.with(|entered| entered.borrow_mut().push(span.into_u64()));
+    }
+
+    fn exit(&self, span: &span::Id) {
+        ENTERED.with(|entered| {
+            let mut entered = entered.borrow_mut();
+            if let Some(i) = entered.iter().rposition(|id| *id == span.into_u64()) {
+                entered.remove(i);
+            }
+        });
+    }
+
+    fn clone_span(&self, id: &span::Id) -> span::Id {
+        if let Some(span) = self.spans.lock().unwrap().get_mut(&id.into_u64()) {
+            span.references += 1;
+        }
+        id.clone()
+    }
+
+    fn try_close(&self, id: span::Id) -> bool {
+        let closed = {
+            let mut spans = self.spans.lock().unwrap();
+            match spans.get_mut(&id.into_u64()) {
+                Some(span) if span.references > 1 => {
+                    span.references -= 1;
+                    None
+                }
+                Some(_) => spans.remove(&id.into_u64()),
+                None => None,
+            }
+        };
+        match closed {
+            Some(span) => {
+                self.log(
+                    span.level,
+                    "stackium::span",
+                    format!(
+                        "{} took {:.3} ms",
+                        span.describe(),
+                        span.opened.elapsed().as_secs_f64() * 1000.0
+                    ),
+                );
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Installs the logger, called once at startup
+pub fn init() {
+    if tracing::subscriber::set_global_default(Logger::new()).is_err() {
+        eprintln!("Failed installing the logger, another one is installed");
+        return;
+    }
+    // panics are kept in the log with their location, the web server goes on after them
+    std::panic::set_hook(Box::new(|info| tracing::error!("{}", info)));
+}
+
+fn format_entry(entry: &LogEntry) -> String {
+    let mut line = format!(
+        "[{:>9.3}s {:<5} {}]",
+        entry.time as f64 / 1000.0,
+        entry.level,
+        entry.target
+    );
+    for span in &entry.spans {
+        let _ = write!(line, " {}:", span);
+    }
+    let _ = write!(line, " {}", entry.message);
+    line
+}
+
+/// The most recent log messages, the oldest comes first
+pub fn recent_log() -> Vec<LogEntry> {
+    LOG.lock().unwrap().iter().cloned().collect()
+}
+
+/// The most recent log messages as printed to stderr, served on `/logs`
+pub fn log_text() -> String {
+    LOG.lock()
+        .unwrap()
+        .iter()
+        .map(|entry| format_entry(entry) + "\n")
+        .collect()
+}
+
+impl Debugger {
+    pub fn diagnostics(&self) -> Diagnostics {
+        Diagnostics {
+            version: VERSION.to_owned(),
+            platform: format!("{} {}", std::env::consts::OS, std::env::consts::ARCH),
+            kernel: std::fs::read_to_string("/proc/sys/kernel/osrelease")
+                .ok()
+                .map(|release| release.trim().to_owned()),
+            program: self.program.display().to_string(),
+            has_debug_info: self.has_debug_info,
+            log: recent_log(),
+        }
+    }
+}
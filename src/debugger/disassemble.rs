@@ -0,0 +1,141 @@
+use std::{collections::HashMap, path::Path};
+
+use stackium_shared::{FunctionDisassembly, Instruction, SourceBlock};
+
+use super::{
+    error::DebugError,
+    util::{find_function_from_name, get_line_from_pc},
+    Debugger,
+};
+
+impl Debugger {
+    /// Disassembles the instructions in `from..to` using objdump
+    pub fn disassemble_range(&self, from: u64, to: u64) -> Result<Vec<Instruction>, DebugError> {
+        let output = self.program_output(
+            std::process::Command::new("objdump")
+                .arg("--disassemble")
+                .arg(format!("--start-address={:#x}", from))
+                .arg(format!("--stop-address={:#x}", to))
+                .arg(self.program.clone().into_os_string()),
+        )?;
+        let mut instructions: Vec<Instruction> = vec![];
+        for line in std::str::from_utf8(&output)?.lines() {
+            let mut fields = line.split('\t');
+            let Some(address) = fields
+                .next()
+                .and_then(|a| a.trim().strip_suffix(':'))
+                .and_then(|a| u64::from_str_radix(a, 16).ok())
+            else {
+                continue;
+            };
+            let bytes = fields.next().unwrap_or_default().trim().to_owned();
+            match fields.next() {
+                Some(text) => instructions.push(Instruction {
+                    address,
+                    bytes,
+                    text: text.split_whitespace().collect::<Vec<_>>().join(" "),
+                }),
+                // objdump continues the bytes of long instructions on the next line
+                None => {
+                    if let Some(last) = instructions.last_mut() {
+                        last.bytes = format!("{} {}", last.bytes, bytes);
+                    }
+                }
+            }
+        }
+        Ok(instructions)
+    }
+
+    /// Names of the functions the call instructions of the current line call, e.g. `printf`
+    /// for `call 401030 <printf@plt>`
+    pub fn line_calls(&self) -> Result<Vec<String>, DebugError> {
+        let pc = self.get_pc()?;
+        let disassembly = self.disassemble_function(&format!("{:#x}", pc))?;
+        let Some(block) = disassembly
+            .blocks
+            .iter()
+            .find(|b| b.instructions.iter().any(|i| i.address == pc))
+        else {
+            return Ok(vec![]);
+        };
+        let mut calls: Vec<String> = vec![];
+        for instruction in &block.instructions {
+            let target = match instruction.text.strip_prefix("call") {
+                Some(operands) => operands
+                    .split_once('<')
+                    .and_then(|(_, target)| target.split_once('>'))
+                    .map(|(target, _)| target),
+                None => None,
+            };
+            // `f@plt` for library functions, `f+0x10` for calls into the middle of a function
+            let Some(name) = target.and_then(|t| t.split(['@', '+']).next()) else {
+                continue;
+            };
+            if !calls.iter().any(|c| c == name) {
+                calls.push(name.to_owned());
+            }
+        }
+        Ok(calls)
+    }
+
+    /// Disassembles the function with the specified name or containing the specified hex address
+    /// and groups consecutive instructions by their source line
+    pub fn disassemble_function(&self, function: &str) -> Result<FunctionDisassembly, DebugError> {
+        let meta = match function
+            .strip_prefix("0x")
+            .and_then(|a| u64::from_str_radix(a, 16).ok())
+        {
+            Some(address) => self.get_func_from_addr(address)?,
+            None if !self.has_debug_info => {
+                self.find_symbol_function(|f| f.name.as_deref() == Some(function))?
+            }
+            None => find_function_from_name(&self.dwarf, function.to_owned())?,
+        };
+        let (Some(low_pc), Some(length)) = (meta.low_pc, meta.high_pc) else {
+            return Err(DebugError::FunctionNotFound);
+        };
+        let high_pc = low_pc + length;
+
+        let mut sources: HashMap<String, Option<Vec<String>>> = HashMap::new();
+        let mut blocks: Vec<SourceBlock> = vec![];
+        for instruction in self.disassemble_range(low_pc, high_pc)? {
+            let location = get_line_from_pc(&self.dwarf, instruction.address).ok();
+            let same_line = match (blocks.last(), &location) {
+                // rows of the line table only mark the first instruction of a line
+                (Some(_), None) => true,
+                (Some(block), Some(b)) => block
+                    .location
+                    .as_ref()
+                    .is_some_and(|a| a.line == b.line && a.file == b.file),
+                (None, _) => false,
+            };
+            if same_line {
+                blocks.last_mut().unwrap().instructions.push(instruction);
+                continue;
+            }
+            let source = location.as_ref().and_then(|location| {
+                sources
+                    .entry(location.file.clone())
+                    .or_insert_with(|| {
+                        self.read_source(Path::new(&location.file))
+                            .ok()
+                            .map(|file| file.lines().map(|l| l.to_owned()).collect())
+                    })
+                    .as_ref()
+                    .and_then(|lines| lines.get((location.line as usize).checked_sub(1)?))
+                    .cloned()
+            });
+            blocks.push(SourceBlock {
+                location,
+                source,
+                instructions: vec![instruction],
+            });
+        }
+        Ok(FunctionDisassembly {
+            name: meta.name,
+            low_pc,
+            high_pc,
+            blocks,
+        })
+    }
+}
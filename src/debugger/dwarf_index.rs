@@ -0,0 +1,209 @@
+use std::{
+    cell::OnceCell,
+    collections::{HashMap, HashSet},
+    ops::{Deref, Range},
+    path::Path,
+};
+
+use gimli::Reader;
+use stackium_shared::FunctionMeta;
+
+use super::{frames::CallFrameInfo, line_table::LineTable, util::get_function_meta};
+
+/// Code ranges of the units sorted by their start with the index of their unit, and the units
+/// without ranges, which can contain any address
+type UnitRanges = (Vec<(Range<u64>, usize)>, Vec<usize>);
+
+/// The debug information of the program with indexes which are built the first time a lookup
+/// needs them, so starting the debugger doesn't walk the compilation units and commands don't
+/// walk them again. Lookups by address like the line of the program counter only walk the
+/// units covering the address. Replaced when the program is reloaded.
+pub struct IndexedDwarf<R: Reader> {
+    dwarf: gimli::Dwarf<R>,
+    /// Parsed compilation units in the order of `.debug_info`
+    units: OnceCell<Vec<gimli::Unit<R>>>,
+    /// Code ranges of the units by their start and the units without ranges
+    ranges: OnceCell<UnitRanges>,
+    /// Every function in the order of the debug information
+    functions: OnceCell<Vec<FunctionMeta>>,
+    /// Index in `functions` by name, definitions are preferred over declarations
+    names: OnceCell<HashMap<String, usize>>,
+    /// Line table of each unit by the index of the unit, built when a lookup needs the unit
+    line_tables: OnceCell<Vec<OnceCell<LineTable>>>,
+    /// Call frame information of the program, read when the stack is unwound the first time
+    call_frame_info: OnceCell<Option<CallFrameInfo>>,
+}
+
+impl<R: Reader> Deref for IndexedDwarf<R> {
+    type Target = gimli::Dwarf<R>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.dwarf
+    }
+}
+
+impl<R: Reader> IndexedDwarf<R> {
+    pub fn new(dwarf: gimli::Dwarf<R>) -> Self {
+        Self {
+            dwarf,
+            units: OnceCell::new(),
+            ranges: OnceCell::new(),
+            functions: OnceCell::new(),
+            names: OnceCell::new(),
+            line_tables: OnceCell::new(),
+            call_frame_info: OnceCell::new(),
+        }
+    }
+
+    /// Call frame information of `program`, the index may be built from a separate debug file
+    pub fn call_frame_info(&self, program: &Path) -> Option<&CallFrameInfo> {
+        self.call_frame_info
+            .get_or_init(|| CallFrameInfo::read(program))
+            .as_ref()
+    }
+
+    /// All compilation units, units which fail to parse are left out
+    pub fn compilation_units(&self) -> &[gimli::Unit<R>] {
+        self.units.get_or_init(|| {
+            let _span = tracing::debug_span!("dwarf_units").entered();
+            let mut units = vec![];
+            let mut headers = self.dwarf.units();
+            while let Ok(Some(header)) = headers.next() {
+                if let Ok(unit) = self.dwarf.unit(header) {
+                    units.push(unit);
+                }
+            }
+            units
+        })
+    }
+
+    /// Ranges of the units from `.debug_aranges`, which lists them without parsing the DIEs of
+    /// the units, and from the unit DIEs of the units it doesn't list
+    fn unit_ranges(&self) -> UnitRanges {
+        let units = self.compilation_units();
+        let _span = tracing::debug_span!("dwarf_unit_ranges").entered();
+        let by_offset = units
+            .iter()
+            .enumerate()
+            .filter_map(|(i, unit)| Some((unit.header.offset().as_debug_info_offset()?, i)))
+            .collect::<HashMap<_, _>>();
+        let mut ranges = vec![];
+        let mut headers = self.dwarf.debug_aranges.headers();
+        while let Ok(Some(header)) = headers.next() {
+            let Some(&unit) = by_offset.get(&header.debug_info_offset()) else {
+                continue;
+            };
+            let mut entries = header.entries();
+            while let Ok(Some(entry)) = entries.next() {
+                ranges.push((entry.range().begin..entry.range().end, unit));
+            }
+        }
+        let listed = ranges.iter().map(|(_, unit)| *unit).collect::<HashSet<_>>();
+        let mut unranged = vec![];
+        for (i, unit) in units
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !listed.contains(i))
+        {
+            let found = ranges.len();
+            if let Ok(mut unit_ranges) = self.dwarf.unit_ranges(unit) {
+                while let Ok(Some(range)) = unit_ranges.next() {
+                    ranges.push((range.begin..range.end, i));
+                }
+            }
+            if ranges.len() == found {
+                unranged.push(i);
+            }
+        }
+        ranges.retain(|(range, _)| !range.is_empty());
+        ranges.sort_by_key(|(range, _)| range.start);
+        (ranges, unranged)
+    }
+
+    /// Indices of the units whose code contains `address` and of the units without ranges
+    fn unit_indices_containing(&self, address: u64) -> Vec<usize> {
+        let (ranges, unranged) = self.ranges.get_or_init(|| self.unit_ranges());
+        let starting_before = ranges.partition_point(|(range, _)| range.start <= address);
+        let mut containing = ranges[..starting_before]
+            .iter()
+            .filter(|(range, _)| address < range.end)
+            .map(|(_, unit)| *unit)
+            .chain(unranged.iter().copied())
+            .collect::<Vec<_>>();
+        containing.sort_unstable();
+        containing.dedup();
+        containing
+    }
+
+    /// The units whose code contains `address` and the units without ranges, in the order of
+    /// the debug information
+    pub fn units_containing(&self, address: u64) -> Vec<&gimli::Unit<R>> {
+        let units = self.compilation_units();
+        self.unit_indices_containing(address)
+            .into_iter()
+            .map(|i| &units[i])
+            .collect()
+    }
+
+    fn line_table(&self, unit: usize) -> &LineTable {
+        let units = self.compilation_units();
+        self.line_tables
+            .get_or_init(|| units.iter().map(|_| OnceCell::new()).collect())[unit]
+            .get_or_init(|| {
+                let _span = tracing::debug_span!("dwarf_line_table", unit).entered();
+                LineTable::new(&self.dwarf, &units[unit])
+            })
+    }
+
+    /// Line tables of every unit, looking up a line by its file needs all of them
+    pub fn line_tables(&self) -> impl Iterator<Item = &LineTable> {
+        (0..self.compilation_units().len()).map(|i| self.line_table(i))
+    }
+
+    /// Line tables of the units returned by [`units_containing`](Self::units_containing)
+    pub fn line_tables_containing(&self, address: u64) -> impl Iterator<Item = &LineTable> {
+        self.unit_indices_containing(address)
+            .into_iter()
+            .map(|i| self.line_table(i))
+    }
+
+    /// Every function, see [`get_functions`](super::util::get_functions)
+    pub fn functions(&self) -> &[FunctionMeta] {
+        self.functions.get_or_init(|| {
+            let _span = tracing::debug_span!("dwarf_functions").entered();
+            let mut functions = vec![];
+            for unit in self.compilation_units() {
+                let mut cursor = unit.entries();
+                while let Ok(Some((_, entry))) = cursor.next_dfs() {
+                    if entry.tag() != gimli::DW_TAG_subprogram {
+                        continue;
+                    }
+                    if let Ok(function) = get_function_meta(entry, unit, &self.dwarf) {
+                        functions.push(function);
+                    }
+                }
+            }
+            functions
+        })
+    }
+
+    /// The function called `name`, a unit calling a function of another unit only declares it
+    /// without its address
+    pub fn function(&self, name: &str) -> Option<&FunctionMeta> {
+        let functions = self.functions();
+        let names = self.names.get_or_init(|| {
+            let mut names = HashMap::new();
+            for (i, function) in functions.iter().enumerate() {
+                let Some(name) = &function.name else {
+                    continue;
+                };
+                let first = names.entry(name.clone()).or_insert(i);
+                if functions[*first].low_pc.is_none() && function.low_pc.is_some() {
+                    *first = i;
+                }
+            }
+            names
+        });
+        names.get(name).map(|i| &functions[*i])
+    }
+}
@@ -0,0 +1,204 @@
+use object::{
+    elf,
+    read::elf::{FileHeader, ProgramHeader, SectionHeader, Sym},
+    Endianness, Object, ObjectKind,
+};
+use stackium_shared::{ElfInfo, ElfSection, ElfSegment, ElfSymbolTable};
+
+use super::{error::DebugError, Debugger};
+
+fn section_type(sh_type: u32) -> String {
+    match sh_type {
+        elf::SHT_NULL => "NULL",
+        elf::SHT_PROGBITS => "PROGBITS",
+        elf::SHT_SYMTAB => "SYMTAB",
+        elf::SHT_STRTAB => "STRTAB",
+        elf::SHT_RELA => "RELA",
+        elf::SHT_HASH => "HASH",
+        elf::SHT_DYNAMIC => "DYNAMIC",
+        elf::SHT_NOTE => "NOTE",
+        elf::SHT_NOBITS => "NOBITS",
+        elf::SHT_REL => "REL",
+        elf::SHT_DYNSYM => "DYNSYM",
+        elf::SHT_INIT_ARRAY => "INIT_ARRAY",
+        elf::SHT_FINI_ARRAY => "FINI_ARRAY",
+        elf::SHT_PREINIT_ARRAY => "PREINIT_ARRAY",
+        elf::SHT_GROUP => "GROUP",
+        elf::SHT_GNU_HASH => "GNU_HASH",
+        elf::SHT_GNU_VERSYM => "VERSYM",
+        elf::SHT_GNU_VERNEED => "VERNEED",
+        elf::SHT_GNU_VERDEF => "VERDEF",
+        other => return format!("{:#x}", other),
+    }
+    .to_owned()
+}
+
+fn segment_type(p_type: u32) -> String {
+    match p_type {
+        elf::PT_NULL => "NULL",
+        elf::PT_LOAD => "LOAD",
+        elf::PT_DYNAMIC => "DYNAMIC",
+        elf::PT_INTERP => "INTERP",
+        elf::PT_NOTE => "NOTE",
+        elf::PT_PHDR => "PHDR",
+        elf::PT_TLS => "TLS",
+        elf::PT_GNU_EH_FRAME => "GNU_EH_FRAME",
+        elf::PT_GNU_STACK => "GNU_STACK",
+        elf::PT_GNU_RELRO => "GNU_RELRO",
+        elf::PT_GNU_PROPERTY => "GNU_PROPERTY",
+        other => return format!("{:#x}", other),
+    }
+    .to_owned()
+}
+
+fn section_flags(sh_flags: u64) -> String {
+    [
+        (elf::SHF_WRITE, 'W'),
+        (elf::SHF_ALLOC, 'A'),
+        (elf::SHF_EXECINSTR, 'X'),
+        (elf::SHF_MERGE, 'M'),
+        (elf::SHF_STRINGS, 'S'),
+        (elf::SHF_TLS, 'T'),
+    ]
+    .iter()
+    .filter(|(flag, _)| sh_flags & *flag as u64 != 0)
+    .map(|(_, c)| c)
+    .collect()
+}
+
+fn segment_flags(p_flags: u32) -> String {
+    [(elf::PF_R, 'R'), (elf::PF_W, 'W'), (elf::PF_X, 'X')]
+        .iter()
+        .map(|(flag, c)| match p_flags & flag != 0 {
+            true => *c,
+            false => '-',
+        })
+        .collect()
+}
+
+type ElfTables = (
+    Vec<ElfSection>,
+    Vec<ElfSegment>,
+    Vec<ElfSymbolTable>,
+    Option<String>,
+);
+
+/// Sections, segments, symbol tables and the interpreter of a 32 or 64 bit ELF file
+fn elf_tables<Elf: FileHeader<Endian = Endianness>>(
+    data: &[u8],
+) -> Result<ElfTables, object::read::Error> {
+    let header = Elf::parse(data)?;
+    let endian = header.endian()?;
+    let table = header.sections(endian, data)?;
+    let headers = table.iter().collect::<Vec<_>>();
+    let sections = headers
+        .iter()
+        .map(|section| ElfSection {
+            name: String::from_utf8_lossy(table.section_name(endian, section).unwrap_or_default())
+                .into_owned(),
+            section_type: section_type(section.sh_type(endian)),
+            address: section.sh_addr(endian).into(),
+            offset: section.sh_offset(endian).into(),
+            size: section.sh_size(endian).into(),
+            flags: section_flags(section.sh_flags(endian).into()),
+        })
+        .collect::<Vec<_>>();
+
+    let mut interpreter = None;
+    let mut segments = vec![];
+    for segment in header.program_headers(endian, data)? {
+        let p_type = segment.p_type(endian);
+        if p_type == elf::PT_INTERP {
+            interpreter = segment.data(endian, data).ok().map(|path| {
+                String::from_utf8_lossy(path.strip_suffix(&[0]).unwrap_or(path)).into_owned()
+            });
+        }
+        let address: u64 = segment.p_vaddr(endian).into();
+        let memory_size: u64 = segment.p_memsz(endian).into();
+        let mapped = headers
+            .iter()
+            .zip(&sections)
+            .filter(|(header, section)| {
+                let flags: u64 = header.sh_flags(endian).into();
+                flags & elf::SHF_ALLOC as u64 != 0
+                    && section.size > 0
+                    // `.tbss` takes no memory outside of the thread local storage
+                    && (flags & elf::SHF_TLS as u64 == 0
+                        || header.sh_type(endian) != elf::SHT_NOBITS
+                        || p_type == elf::PT_TLS)
+                    && address <= section.address
+                    && section.address + section.size <= address + memory_size
+            })
+            .map(|(_, section)| section.name.clone())
+            .collect();
+        segments.push(ElfSegment {
+            segment_type: segment_type(p_type),
+            offset: segment.p_offset(endian).into(),
+            address,
+            file_size: segment.p_filesz(endian).into(),
+            memory_size,
+            flags: segment_flags(segment.p_flags(endian)),
+            sections: mapped,
+        });
+    }
+
+    let mut symbol_tables = vec![];
+    for (name, sh_type) in [(".symtab", elf::SHT_SYMTAB), (".dynsym", elf::SHT_DYNSYM)] {
+        let symbols = table.symbols(endian, data, sh_type)?;
+        if symbols.is_empty() {
+            continue;
+        }
+        // the first symbol is always the null symbol
+        let symbols = symbols.iter().skip(1).collect::<Vec<_>>();
+        symbol_tables.push(ElfSymbolTable {
+            name: name.to_owned(),
+            symbols: symbols.len(),
+            functions: symbols
+                .iter()
+                .filter(|s| s.st_type() == elf::STT_FUNC)
+                .count(),
+            objects: symbols
+                .iter()
+                .filter(|s| s.st_type() == elf::STT_OBJECT)
+                .count(),
+            undefined: symbols.iter().filter(|s| s.is_undefined(endian)).count(),
+        });
+    }
+    Ok((sections, segments, symbol_tables, interpreter))
+}
+
+impl Debugger {
+    /// The headers, sections, segments and symbol tables of the program, what `readelf -a`
+    /// would show
+    pub fn elf_info(&self) -> Result<ElfInfo, DebugError> {
+        let bin = std::fs::read(&self.program)?;
+        let object_file = object::File::parse(&bin[..])
+            .map_err(|e| DebugError::InvalidArgument(e.to_string()))?;
+        let (sections, segments, symbol_tables, interpreter) = match object_file.is_64() {
+            true => elf_tables::<elf::FileHeader64<Endianness>>(&bin),
+            false => elf_tables::<elf::FileHeader32<Endianness>>(&bin),
+        }
+        .map_err(|e| DebugError::InvalidArgument(e.to_string()))?;
+        Ok(ElfInfo {
+            bits: match object_file.is_64() {
+                true => 64,
+                false => 32,
+            },
+            little_endian: object_file.is_little_endian(),
+            file_type: match object_file.kind() {
+                ObjectKind::Executable => "Executable",
+                ObjectKind::Dynamic => "Shared object or position independent executable",
+                ObjectKind::Relocatable => "Relocatable",
+                ObjectKind::Core => "Core dump",
+                _ => "Unknown",
+            }
+            .to_owned(),
+            architecture: format!("{:?}", object_file.architecture()),
+            entry: object_file.entry(),
+            interpreter,
+            sections,
+            segments,
+            symbol_tables,
+        })
+    }
+}
@@ -16,6 +16,31 @@ pub enum DebugError {
     InvalidCommand(String),
     InvalidArgument(String),
     EncodingError(String),
+    /// No source file in the debug information matches the given name
+    FileNotFound(String),
+    /// The given file name matches several source files (name, candidates)
+    AmbiguousFile(String, Vec<String>),
+    /// The file isn't a source file of the program or allowed with `--allow-source`
+    FileNotAllowed(String),
+    /// The command needs debug information the program was built without
+    NoDebugInfo,
+    /// The debugger was detached from the child with `DetachDebugger`
+    Detached,
+    /// The command needs the child to be stopped, see `Interrupt`
+    ChildRunning,
+    /// `Undo` was called without any action left to undo
+    NothingToUndo,
+    /// The client cancelled the command with `Cancel`
+    Cancelled,
+    /// The memory at the address isn't mapped or can't be read, the message names the nearest
+    /// region
+    UnreadableAddress(String),
+    /// A breakpoint at the address would crash the child, e.g. because it isn't executable or
+    /// in the middle of an instruction. The message suggests the nearest instruction.
+    InvalidBreakpointAddress(String),
+    /// The debugger panicked while executing the command, with the message of the panic. The
+    /// session stays usable, the state of the command is lost.
+    InternalError(String),
 }
 
 impl From<Utf8Error> for DebugError {
@@ -0,0 +1,155 @@
+use std::path::{Path, PathBuf};
+
+use stackium_shared::{Breakpoint, Exercise, ExerciseCondition};
+use tracing::warn;
+
+use super::{
+    breakpoint::DebuggerBreakpoint,
+    error::DebugError,
+    util::{
+        find_function_from_name, get_line_addresses, get_line_containing_addr, get_line_from_pc,
+        get_post_prologue_addr,
+    },
+    Debugger,
+};
+
+/// An exercise with the resolved path of the file its lines refer to
+pub struct ExerciseProgress {
+    exercise: Exercise,
+    file: Option<PathBuf>,
+}
+
+impl ExerciseProgress {
+    /// Starts over at the first step, e.g. when the debugee is restarted
+    pub fn reset(&mut self) {
+        self.exercise.current = 0;
+    }
+}
+
+/// Path of the exercise manifest of `program`
+fn manifest_path(program: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.exercise.json", program.display()))
+}
+
+impl Debugger {
+    /// Loads the exercise next to the program if there is one and sets the breakpoints its
+    /// steps ask for. An invalid manifest is reported and ignored.
+    pub fn load_exercise(&mut self) {
+        let path = manifest_path(&self.program);
+        let Ok(manifest) = std::fs::read(&path) else {
+            return;
+        };
+        let exercise: Exercise = match serde_json::from_slice(&manifest) {
+            Ok(exercise) => exercise,
+            Err(e) => {
+                warn!("Ignoring the exercise {}: {}", path.display(), e);
+                return;
+            }
+        };
+        let file = match &exercise.file {
+            Some(file) => self.files.resolve(file).ok(),
+            None => self.main_file(),
+        };
+        let progress = ExerciseProgress { exercise, file };
+        for step in progress.exercise.steps.iter().filter(|s| s.breakpoint) {
+            let Some(condition) = &step.until else {
+                continue;
+            };
+            match self.exercise_breakpoint_address(condition, progress.file.as_deref()) {
+                Ok(Some(addr)) if !self.breakpoints.iter().any(|b| b.address == addr) => {
+                    let breakpoint = Breakpoint::new(&self.dwarf, self.child, addr as *const u8)
+                        .and_then(|breakpoint| self.add_breakpoint(breakpoint));
+                    if let Err(e) = breakpoint {
+                        warn!("Failed setting the breakpoint of {:?}: {:?}", condition, e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => warn!("Failed setting the breakpoint of {:?}: {:?}", condition, e),
+            }
+        }
+        self.exercise = Some(progress);
+    }
+
+    pub fn get_exercise(&self) -> Option<Exercise> {
+        self.exercise.as_ref().map(|p| p.exercise.clone())
+    }
+
+    /// Moves to the next step if the debugee stopped where the current step expects
+    pub fn advance_exercise(&mut self) {
+        let Some(progress) = &self.exercise else {
+            return;
+        };
+        let Some(step) = progress.exercise.steps.get(progress.exercise.current) else {
+            return;
+        };
+        let done = match &step.until {
+            None => true,
+            Some(condition) => self.reached(condition, progress.file.as_deref()),
+        };
+        if done {
+            if let Some(progress) = self.exercise.as_mut() {
+                progress.exercise.current += 1;
+            }
+        }
+    }
+
+    fn reached(&self, condition: &ExerciseCondition, file: Option<&Path>) -> bool {
+        if self.has_exited() {
+            return *condition == ExerciseCondition::Exited;
+        }
+        let Ok(pc) = self.get_pc() else {
+            return false;
+        };
+        match condition {
+            ExerciseCondition::Line(line) => {
+                // lines without code are reached at the next line which has some
+                let in_file =
+                    |location_file: &str| file.map_or(true, |f| Path::new(location_file) == f);
+                get_line_containing_addr(&self.dwarf, pc)
+                    .is_ok_and(|l| l.line == *line && in_file(&l.file))
+                    || self
+                        .exercise_breakpoint_address(condition, file)
+                        .is_ok_and(|addr| addr == Some(pc))
+            }
+            ExerciseCondition::Function(name) => self
+                .get_func_from_addr(pc)
+                .is_ok_and(|f| f.name.as_deref() == Some(name.as_str())),
+            ExerciseCondition::Exited => false,
+        }
+    }
+
+    /// Address a breakpoint for `condition` is set at, like the `SetBreakpoint` command does
+    fn exercise_breakpoint_address(
+        &self,
+        condition: &ExerciseCondition,
+        file: Option<&Path>,
+    ) -> Result<Option<u64>, DebugError> {
+        match condition {
+            ExerciseCondition::Line(line) => match file {
+                Some(file) => Ok(Some(
+                    get_line_addresses(&self.dwarf, *line, file)?.canonical,
+                )),
+                None => Ok(None),
+            },
+            ExerciseCondition::Function(name) => {
+                let function = find_function_from_name(&self.dwarf, name.clone())?;
+                match function.low_pc {
+                    Some(low_pc) => Ok(Some(get_post_prologue_addr(
+                        &self.dwarf,
+                        low_pc,
+                        function.high_pc.unwrap_or(0),
+                    )?)),
+                    None => Ok(None),
+                }
+            }
+            ExerciseCondition::Exited => Ok(None),
+        }
+    }
+
+    /// The source file containing `main`
+    fn main_file(&self) -> Option<PathBuf> {
+        let main = find_function_from_name(&self.dwarf, "main".to_owned()).ok()?;
+        let location = get_line_from_pc(&self.dwarf, main.low_pc?).ok()?;
+        self.files.resolve(&location.file).ok()
+    }
+}
@@ -0,0 +1,495 @@
+use stackium_shared::{get_byte_size, numeric_value, DataType, TypeName, Variable};
+
+use super::{error::DebugError, Debugger};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BinaryOp {
+    Or,
+    And,
+    Equal,
+    NotEqual,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+/// A C expression like `arr[i - 1] <= arr[i] && i < n`
+#[derive(Debug, Clone)]
+pub enum Expression {
+    Integer(i64),
+    Float(f64),
+    Variable(String),
+    Index(Box<Expression>, Box<Expression>),
+    Member(Box<Expression>, String),
+    Deref(Box<Expression>),
+    AddressOf(Box<Expression>),
+    Negate(Box<Expression>),
+    Not(Box<Expression>),
+    Binary(BinaryOp, Box<Expression>, Box<Expression>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Integer(i64),
+    Float(f64),
+    Identifier(String),
+    Symbol(&'static str),
+}
+
+/// Longer symbols first, so `<=` isn't read as `<`
+const SYMBOLS: [&str; 21] = [
+    "->", "<=", ">=", "==", "!=", "&&", "||", "<", ">", "+", "-", "*", "/", "%", "!", "&", "(",
+    ")", "[", "]", ".",
+];
+
+fn invalid(message: String) -> DebugError {
+    DebugError::InvalidArgument(message)
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, DebugError> {
+    let mut tokens = vec![];
+    let mut rest = source.trim_start();
+    while !rest.is_empty() {
+        let c = rest.chars().next().unwrap();
+        let end = if c.is_ascii_digit() {
+            let end = rest
+                .find(|c: char| !c.is_ascii_alphanumeric() && c != '.')
+                .unwrap_or(rest.len());
+            let number = &rest[..end];
+            tokens.push(
+                match number.strip_prefix("0x") {
+                    Some(hex) => i64::from_str_radix(hex, 16).map(Token::Integer).ok(),
+                    None if number.contains('.') => number.parse().map(Token::Float).ok(),
+                    None => number.parse().map(Token::Integer).ok(),
+                }
+                .ok_or(invalid(format!("Invalid number {}", number)))?,
+            );
+            end
+        } else if c.is_alphabetic() || c == '_' {
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_')
+                .unwrap_or(rest.len());
+            tokens.push(Token::Identifier(rest[..end].to_owned()));
+            end
+        } else if c == '\'' {
+            // character literals like 'a'
+            let mut chars = rest[1..].chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), Some('\'')) => {
+                    tokens.push(Token::Integer(c as i64));
+                    c.len_utf8() + 2
+                }
+                _ => return Err(invalid("Invalid character literal".to_owned())),
+            }
+        } else {
+            let symbol = SYMBOLS
+                .iter()
+                .find(|s| rest.starts_with(**s))
+                .ok_or(invalid(format!("Unexpected character {}", c)))?;
+            tokens.push(Token::Symbol(symbol));
+            symbol.len()
+        };
+        rest = rest[end..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// Binary operators from the lowest to the highest precedence
+const PRECEDENCE: [&[(&str, BinaryOp)]; 6] = [
+    &[("||", BinaryOp::Or)],
+    &[("&&", BinaryOp::And)],
+    &[("==", BinaryOp::Equal), ("!=", BinaryOp::NotEqual)],
+    &[
+        ("<", BinaryOp::Less),
+        ("<=", BinaryOp::LessEqual),
+        (">", BinaryOp::Greater),
+        (">=", BinaryOp::GreaterEqual),
+    ],
+    &[("+", BinaryOp::Add), ("-", BinaryOp::Sub)],
+    &[
+        ("*", BinaryOp::Mul),
+        ("/", BinaryOp::Div),
+        ("%", BinaryOp::Rem),
+    ],
+];
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn eat(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.position += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect(&mut self, symbol: &str) -> Result<(), DebugError> {
+        match self.eat(symbol) {
+            true => Ok(()),
+            false => Err(invalid(format!("Expected {}", symbol))),
+        }
+    }
+
+    fn binary(&mut self, level: usize) -> Result<Expression, DebugError> {
+        if level == PRECEDENCE.len() {
+            return self.unary();
+        }
+        let mut left = self.binary(level + 1)?;
+        'operators: loop {
+            for (symbol, op) in PRECEDENCE[level] {
+                if self.eat(symbol) {
+                    let right = self.binary(level + 1)?;
+                    left = Expression::Binary(*op, Box::new(left), Box::new(right));
+                    continue 'operators;
+                }
+            }
+            return Ok(left);
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expression, DebugError> {
+        if self.eat("-") {
+            Ok(Expression::Negate(Box::new(self.unary()?)))
+        } else if self.eat("!") {
+            Ok(Expression::Not(Box::new(self.unary()?)))
+        } else if self.eat("*") {
+            Ok(Expression::Deref(Box::new(self.unary()?)))
+        } else if self.eat("&") {
+            Ok(Expression::AddressOf(Box::new(self.unary()?)))
+        } else {
+            self.postfix()
+        }
+    }
+
+    fn postfix(&mut self) -> Result<Expression, DebugError> {
+        let mut expression = self.primary()?;
+        loop {
+            if self.eat("[") {
+                let index = self.binary(0)?;
+                self.expect("]")?;
+                expression = Expression::Index(Box::new(expression), Box::new(index));
+                continue;
+            }
+            // `p->member` is `(*p).member`
+            if self.eat("->") {
+                expression = Expression::Deref(Box::new(expression));
+            } else if !self.eat(".") {
+                return Ok(expression);
+            }
+            let Some(Token::Identifier(member)) = self.next() else {
+                return Err(invalid("Expected a member name".to_owned()));
+            };
+            expression = Expression::Member(Box::new(expression), member);
+        }
+    }
+
+    fn primary(&mut self) -> Result<Expression, DebugError> {
+        match self.next() {
+            Some(Token::Integer(value)) => Ok(Expression::Integer(value)),
+            Some(Token::Float(value)) => Ok(Expression::Float(value)),
+            Some(Token::Identifier(name)) => Ok(Expression::Variable(name)),
+            Some(Token::Symbol("(")) => {
+                let expression = self.binary(0)?;
+                self.expect(")")?;
+                Ok(expression)
+            }
+            Some(token) => Err(invalid(format!("Unexpected {:?}", token))),
+            None => Err(invalid("Unexpected end of expression".to_owned())),
+        }
+    }
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self, DebugError> {
+        let mut parser = Parser {
+            tokens: tokenize(source)?,
+            position: 0,
+        };
+        let expression = parser.binary(0)?;
+        match parser.peek() {
+            Some(token) => Err(invalid(format!("Unexpected {:?}", token))),
+            None => Ok(expression),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum Value {
+    Integer(i64),
+    Float(f64),
+}
+
+impl Value {
+    fn as_f64(self) -> f64 {
+        match self {
+            Value::Integer(value) => value as f64,
+            Value::Float(value) => value,
+        }
+    }
+
+    pub fn is_true(self) -> bool {
+        self.as_f64() != 0.
+    }
+
+    /// Whether both values compare equal with `==`
+    pub fn equals(self, other: Value) -> bool {
+        binary(BinaryOp::Equal, self, other).is_ok_and(Value::is_true)
+    }
+}
+
+fn binary(op: BinaryOp, left: Value, right: Value) -> Result<Value, DebugError> {
+    let bool = |b: bool| Value::Integer(b as i64);
+    Ok(match (op, left, right) {
+        (BinaryOp::Or, _, _) => bool(left.is_true() || right.is_true()),
+        (BinaryOp::And, _, _) => bool(left.is_true() && right.is_true()),
+        (BinaryOp::Div | BinaryOp::Rem, Value::Integer(_), Value::Integer(0)) => {
+            return Err(invalid("Division by zero".to_owned()))
+        }
+        (_, Value::Integer(l), Value::Integer(r)) => match op {
+            BinaryOp::Equal => bool(l == r),
+            BinaryOp::NotEqual => bool(l != r),
+            BinaryOp::Less => bool(l < r),
+            BinaryOp::LessEqual => bool(l <= r),
+            BinaryOp::Greater => bool(l > r),
+            BinaryOp::GreaterEqual => bool(l >= r),
+            BinaryOp::Add => Value::Integer(l.wrapping_add(r)),
+            BinaryOp::Sub => Value::Integer(l.wrapping_sub(r)),
+            BinaryOp::Mul => Value::Integer(l.wrapping_mul(r)),
+            BinaryOp::Div => Value::Integer(l.wrapping_div(r)),
+            BinaryOp::Rem => Value::Integer(l.wrapping_rem(r)),
+            BinaryOp::Or | BinaryOp::And => unreachable!(),
+        },
+        _ => {
+            let (l, r) = (left.as_f64(), right.as_f64());
+            match op {
+                BinaryOp::Equal => bool(l == r),
+                BinaryOp::NotEqual => bool(l != r),
+                BinaryOp::Less => bool(l < r),
+                BinaryOp::LessEqual => bool(l <= r),
+                BinaryOp::Greater => bool(l > r),
+                BinaryOp::GreaterEqual => bool(l >= r),
+                BinaryOp::Add => Value::Float(l + r),
+                BinaryOp::Sub => Value::Float(l - r),
+                BinaryOp::Mul => Value::Float(l * r),
+                BinaryOp::Div => Value::Float(l / r),
+                BinaryOp::Rem => Value::Float(l % r),
+                BinaryOp::Or | BinaryOp::And => unreachable!(),
+            }
+        }
+    })
+}
+
+/// Where the value of a (part of a) variable is stored
+struct Place<'a> {
+    types: &'a DataType,
+    index: usize,
+    address: Option<u64>,
+    /// Value of variables stored in registers
+    raw: Option<u64>,
+    /// Remaining dimensions if this is an array of elements of the type at `index`
+    dims: Option<&'a [usize]>,
+}
+
+impl<'a> Place<'a> {
+    fn new(types: &'a DataType, index: usize, address: Option<u64>, raw: Option<u64>) -> Self {
+        match &types.0[index].1 {
+            TypeName::Arr { arr_type, count } => Place {
+                types,
+                index: *arr_type,
+                address,
+                raw: None,
+                dims: Some(count),
+            },
+            _ => Place {
+                types,
+                index,
+                address,
+                raw,
+                dims: None,
+            },
+        }
+    }
+}
+
+impl Debugger {
+    fn place<'a>(
+        &self,
+        expression: &Expression,
+        variables: &'a [Variable],
+    ) -> Result<Place<'a>, DebugError> {
+        match expression {
+            Expression::Variable(name) => {
+                let variable = variables
+                    .iter()
+                    .find(|v| v.in_scope && v.name.as_deref() == Some(name.as_str()))
+                    .ok_or(invalid(format!("Unknown variable {}", name)))?;
+                let types = variable
+                    .type_name
+                    .as_ref()
+                    .ok_or(invalid(format!("Unknown type of {}", name)))?;
+                Ok(Place::new(types, 0, variable.addr, variable.value))
+            }
+            Expression::Index(base, index) => {
+                let base = self.place(base, variables)?;
+                let index = match self.evaluate(index, variables)? {
+                    Value::Integer(index) => index,
+                    Value::Float(_) => return Err(invalid("Index isn't an integer".to_owned())),
+                };
+                self.element(base, index)
+            }
+            Expression::Deref(base) => {
+                let base = self.place(base, variables)?;
+                self.element(base, 0)
+            }
+            Expression::Member(base, member) => {
+                let base = self.place(base, variables)?;
+                let TypeName::ProductType { members, .. } = &base.types.0[base.index].1 else {
+                    return Err(invalid(format!("Not a struct, can't access {}", member)));
+                };
+                let (_, index, offset) = members
+                    .iter()
+                    .find(|(name, _, _)| name == member)
+                    .ok_or(invalid(format!("Unknown member {}", member)))?;
+                let address = base
+                    .address
+                    .ok_or(invalid("The struct has no address".to_owned()))?;
+                Ok(Place::new(
+                    base.types,
+                    *index,
+                    Some(address + *offset as u64),
+                    None,
+                ))
+            }
+            _ => Err(invalid("Not a variable".to_owned())),
+        }
+    }
+
+    /// Element `index` of an array or the memory a pointer points to
+    fn element<'a>(&self, base: Place<'a>, index: i64) -> Result<Place<'a>, DebugError> {
+        let size = get_byte_size(base.types, base.index) as i64;
+        match base.dims {
+            Some(dims) => {
+                let stride = dims[1..].iter().product::<usize>() as i64 * size;
+                let address = base
+                    .address
+                    .ok_or(invalid("The array has no address".to_owned()))?;
+                Ok(Place {
+                    address: Some(address.wrapping_add_signed(index * stride)),
+                    dims: (dims.len() > 1).then(|| &dims[1..]),
+                    ..base
+                })
+            }
+            None => {
+                let TypeName::Ref {
+                    index: Some(target),
+                } = base.types.0[base.index].1
+                else {
+                    return Err(invalid("Not a pointer or an array".to_owned()));
+                };
+                let pointer = match self.value(&base)? {
+                    Value::Integer(pointer) => pointer as u64,
+                    Value::Float(_) => unreachable!(),
+                };
+                let size = get_byte_size(base.types, target) as i64;
+                Ok(Place::new(
+                    base.types,
+                    target,
+                    Some(pointer.wrapping_add_signed(index * size)),
+                    None,
+                ))
+            }
+        }
+    }
+
+    fn value(&self, place: &Place) -> Result<Value, DebugError> {
+        if let (Some(_), Some(address)) = (place.dims, place.address) {
+            // arrays decay to pointers
+            return Ok(Value::Integer(address as i64));
+        }
+        let size = get_byte_size(place.types, place.index);
+        let memory = match (place.raw, place.address) {
+            (Some(raw), _) => raw.to_le_bytes().to_vec(),
+            (None, Some(address)) => self.read_memory(address, size as u64)?,
+            (None, None) => return Err(invalid("The value isn't available".to_owned())),
+        };
+        let value = numeric_value(place.types, place.index, &memory).ok_or(invalid(
+            "Only numbers and pointers can be compared".to_owned(),
+        ))?;
+        Ok(match &place.types.0[place.index].1 {
+            TypeName::Name { name, .. } if name == "float" || name == "double" => {
+                Value::Float(value)
+            }
+            _ => Value::Integer(value as i64),
+        })
+    }
+
+    /// The memory of `expression` if it has an address, e.g. `head->next` or `arr[2]`, with
+    /// its type and the index of the type in it
+    pub fn memory_of(
+        &self,
+        expression: &Expression,
+        variables: &[Variable],
+    ) -> Result<(DataType, usize, u64, u64), DebugError> {
+        let place = self.place(expression, variables)?;
+        let address = place
+            .address
+            .ok_or(invalid("The value has no address".to_owned()))?;
+        let count = place.dims.map_or(1, |dims| dims.iter().product::<usize>());
+        let size = (count * get_byte_size(place.types, place.index)) as u64;
+        Ok((place.types.clone(), place.index, address, size))
+    }
+
+    /// Evaluates `expression` using the variables of the current function
+    pub fn evaluate(
+        &self,
+        expression: &Expression,
+        variables: &[Variable],
+    ) -> Result<Value, DebugError> {
+        match expression {
+            Expression::Integer(value) => Ok(Value::Integer(*value)),
+            Expression::Float(value) => Ok(Value::Float(*value)),
+            Expression::Negate(operand) => Ok(match self.evaluate(operand, variables)? {
+                Value::Integer(value) => Value::Integer(value.wrapping_neg()),
+                Value::Float(value) => Value::Float(-value),
+            }),
+            Expression::Not(operand) => Ok(Value::Integer(
+                !self.evaluate(operand, variables)?.is_true() as i64,
+            )),
+            Expression::AddressOf(operand) => match self.place(operand, variables)?.address {
+                Some(address) => Ok(Value::Integer(address as i64)),
+                None => Err(invalid("The value has no address".to_owned())),
+            },
+            Expression::Binary(op, left, right) => {
+                let left = self.evaluate(left, variables)?;
+                // `i < n && arr[i] > 0` mustn't read `arr[i]` if `i` is out of bounds
+                match (op, left.is_true()) {
+                    (BinaryOp::And, false) => return Ok(Value::Integer(0)),
+                    (BinaryOp::Or, true) => return Ok(Value::Integer(1)),
+                    _ => (),
+                }
+                binary(*op, left, self.evaluate(right, variables)?)
+            }
+            place => self.value(&self.place(place, variables)?),
+        }
+    }
+}
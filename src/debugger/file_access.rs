@@ -0,0 +1,41 @@
+use std::path::{Path, PathBuf};
+
+use super::{error::DebugError, files::normalize_path, Debugger};
+
+/// The path with symbolic links resolved, normalized if it doesn't exist, which is the case for
+/// sources only found through the source map
+fn canonical(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| normalize_path(path))
+}
+
+impl Debugger {
+    /// Resolves a file name of `GetFile` or `GetSource` to a source file clients may read, see
+    /// `may_read`. Anything else, e.g. `../../etc/passwd`, is rejected with `FileNotAllowed`,
+    /// the web server would serve every file the user running it can read otherwise.
+    pub fn readable_source(&self, query: &str) -> Result<PathBuf, DebugError> {
+        let path = match self.files.resolve(query) {
+            Ok(path) => path,
+            Err(DebugError::FileNotFound(_)) => PathBuf::from(query),
+            Err(e) => return Err(e),
+        };
+        match self.may_read(&path) {
+            true => Ok(path),
+            false => Err(DebugError::FileNotAllowed(query.to_owned())),
+        }
+    }
+
+    /// Whether `path` is a file of `DebugMeta` or one below a path of `allowed_sources` once
+    /// symbolic links are resolved
+    pub fn may_read(&self, path: &Path) -> bool {
+        let resolved = canonical(path);
+        let listed = self
+            .source_files()
+            .iter()
+            .any(|source| canonical(source) == resolved);
+        listed
+            || self
+                .allowed_sources
+                .iter()
+                .any(|allowed| resolved.starts_with(canonical(allowed)))
+    }
+}
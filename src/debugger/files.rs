@@ -0,0 +1,148 @@
+use std::path::{Component, Path, PathBuf};
+
+use gimli::Reader;
+
+use super::error::DebugError;
+
+/// Lexically normalizes a path by removing `.` and resolving `..` components.
+/// The file system is not touched as the sources don't have to exist locally.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            c => normalized.push(c.as_os_str()),
+        }
+    }
+    normalized
+}
+
+fn attr_to_string<T: Reader>(
+    dwarf: &gimli::Dwarf<T>,
+    unit: &gimli::Unit<T>,
+    value: gimli::AttributeValue<T>,
+) -> Option<String> {
+    Some(
+        dwarf
+            .attr_string(unit, value)
+            .ok()?
+            .to_string_lossy()
+            .ok()?
+            .to_string(),
+    )
+}
+
+/// Full, normalized path of a file entry of a line program
+/// (compilation directory + include directory + file name)
+pub fn get_file_path<T: Reader>(
+    dwarf: &gimli::Dwarf<T>,
+    unit: &gimli::Unit<T>,
+    header: &gimli::LineProgramHeader<T>,
+    file: &gimli::FileEntry<T>,
+) -> Option<PathBuf> {
+    let mut path = PathBuf::new();
+    if let Some(comp_dir) = &unit.comp_dir {
+        path.push(comp_dir.to_string_lossy().ok()?.as_ref());
+    }
+    if let Some(directory) = file.directory(header) {
+        path.push(attr_to_string(dwarf, unit, directory)?);
+    }
+    path.push(attr_to_string(dwarf, unit, file.path_name())?);
+    Some(normalize_path(&path))
+}
+
+/// Full, normalized path of the primary source file of a compilation unit
+pub fn get_unit_path<T: Reader>(unit: &gimli::Unit<T>) -> Option<PathBuf> {
+    let name = unit.name.as_ref()?.to_string_lossy().ok()?.to_string();
+    let mut path = PathBuf::new();
+    if let Some(comp_dir) = &unit.comp_dir {
+        path.push(comp_dir.to_string_lossy().ok()?.as_ref());
+    }
+    path.push(name);
+    Some(normalize_path(&path))
+}
+
+/// Index of all source files referenced by the debug information
+pub struct FileIndex {
+    /// Primary source files of the compilation units
+    sources: Vec<PathBuf>,
+    /// Every file referenced by a line program, including headers
+    files: Vec<PathBuf>,
+}
+
+impl FileIndex {
+    pub fn new<T: Reader>(dwarf: &gimli::Dwarf<T>) -> Self {
+        let _span = tracing::debug_span!("dwarf_files").entered();
+        let mut sources = vec![];
+        let mut files = vec![];
+        let mut units = dwarf.units();
+        while let Ok(Some(unit_header)) = units.next() {
+            let Ok(unit) = dwarf.unit(unit_header) else {
+                continue;
+            };
+            if let Some(path) = get_unit_path(&unit) {
+                if !sources.contains(&path) {
+                    sources.push(path.clone());
+                }
+                if !files.contains(&path) {
+                    files.push(path);
+                }
+            }
+            if let Some(line_program) = unit.line_program.clone() {
+                let header = line_program.header();
+                for file in header.file_names() {
+                    if let Some(path) = get_file_path(dwarf, &unit, header, file) {
+                        if !files.contains(&path) {
+                            files.push(path);
+                        }
+                    }
+                }
+            }
+        }
+        Self { sources, files }
+    }
+
+    pub fn sources(&self) -> &Vec<PathBuf> {
+        &self.sources
+    }
+
+    /// Resolves a user supplied file name to a file of the index.
+    /// Tries an exact match first, then matches the query as a path suffix
+    /// (e.g. `src/main.c`) and finally falls back to the file name only.
+    pub fn resolve(&self, query: &str) -> Result<PathBuf, DebugError> {
+        let query_path = normalize_path(Path::new(query));
+        if let Some(path) = self.files.iter().find(|f| **f == query_path) {
+            return Ok(path.clone());
+        }
+        let suffix_matches = self
+            .files
+            .iter()
+            .filter(|f| query_path.is_relative() && f.ends_with(&query_path))
+            .collect::<Vec<_>>();
+        let matches = if suffix_matches.is_empty() {
+            match query_path.file_name() {
+                Some(name) => self
+                    .files
+                    .iter()
+                    .filter(|f| f.file_name() == Some(name))
+                    .collect::<Vec<_>>(),
+                None => vec![],
+            }
+        } else {
+            suffix_matches
+        };
+        match matches.len() {
+            0 => Err(DebugError::FileNotFound(query.to_owned())),
+            1 => Ok(matches[0].clone()),
+            _ => Err(DebugError::AmbiguousFile(
+                query.to_owned(),
+                matches.iter().map(|f| f.display().to_string()).collect(),
+            )),
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::path::Path;
+
+use gimli::{CfaRule, RegisterRule, UnwindSection};
+use object::{Object, ObjectKind, ObjectSection};
+use stackium_shared::{Frame, Registers, SavedRegister, StackFrame};
+
+use super::{
+    error::DebugError,
+    registers::{abi_register_name, FromUserRegsStruct},
+    util::get_line_containing_addr,
+    Debugger,
+};
+
+/// Frames deeper than this are not unwound, e.g. in an endless recursion
+const MAX_FRAMES: usize = 64;
+
+#[cfg(target_arch = "x86_64")]
+mod dwarf_registers {
+    pub const STACK_POINTER: gimli::Register = gimli::X86_64::RSP;
+    pub const FRAME_POINTER: gimli::Register = gimli::X86_64::RBP;
+    pub const RETURN_ADDRESS: gimli::Register = gimli::X86_64::RA;
+}
+#[cfg(target_arch = "aarch64")]
+mod dwarf_registers {
+    pub const STACK_POINTER: gimli::Register = gimli::AArch64::SP;
+    pub const FRAME_POINTER: gimli::Register = gimli::AArch64::X29;
+    pub const RETURN_ADDRESS: gimli::Register = gimli::AArch64::X30;
+}
+
+/// Registers needed to find the caller of a frame
+#[derive(Clone, Copy)]
+struct UnwindRegisters {
+    pc: u64,
+    stack_pointer: u64,
+    frame_pointer: u64,
+    /// The link register, only known in the innermost frame
+    return_address: Option<u64>,
+}
+
+/// What unwinding a frame found out about its caller
+struct Unwound {
+    cfa: u64,
+    /// Frame pointer of the caller
+    frame_pointer: u64,
+    return_address: Option<u64>,
+    /// Slots the return address and the registers of the caller are saved in
+    saved_registers: Vec<SavedRegister>,
+}
+
+/// Call frame information of the program, read from `.eh_frame` once by the
+/// [`IndexedDwarf`](super::dwarf_index::IndexedDwarf)
+pub struct CallFrameInfo {
+    data: Vec<u8>,
+    bases: gimli::BaseAddresses,
+    /// Position independent executables are loaded at another address than in the file
+    position_independent: bool,
+}
+
+impl CallFrameInfo {
+    /// `.eh_frame` of `program`, which stripped programs keep unlike the debug information.
+    /// `None` if the program has none or can't be read.
+    pub fn read(program: &Path) -> Option<Self> {
+        let bin = std::fs::read(program).ok()?;
+        let object_file = object::File::parse(&bin[..]).ok()?;
+        let eh_frame = object_file.section_by_name(".eh_frame")?;
+        let mut bases = gimli::BaseAddresses::default().set_eh_frame(eh_frame.address());
+        if let Some(text) = object_file.section_by_name(".text") {
+            bases = bases.set_text(text.address());
+        }
+        Some(Self {
+            data: eh_frame.data().ok()?.to_vec(),
+            bases,
+            position_independent: object_file.kind() == ObjectKind::Dynamic,
+        })
+    }
+}
+
+impl Debugger {
+    #[cfg(target_arch = "x86_64")]
+    fn link_register(&self) -> Result<Option<u64>, DebugError> {
+        Ok(None)
+    }
+    #[cfg(target_arch = "aarch64")]
+    fn link_register(&self) -> Result<Option<u64>, DebugError> {
+        Ok(Some(self.get_registers()?.regs[30]))
+    }
+
+    /// Canonical frame address, frame pointer and return address of the caller of the frame.
+    /// Uses the call frame information if there is any for `address`, otherwise assumes the
+    /// frame pointer points to the saved frame pointer followed by the return address.
+    fn unwind_frame(
+        &self,
+        cfi: Option<&CallFrameInfo>,
+        load_bias: u64,
+        address: u64,
+        registers: &UnwindRegisters,
+    ) -> Result<Unwound, DebugError> {
+        if let Some(cfi) = cfi {
+            let eh_frame = gimli::EhFrame::new(&cfi.data, gimli::NativeEndian);
+            let mut context = gimli::UnwindContext::new();
+            if let Ok(row) = eh_frame.unwind_info_for_address(
+                &cfi.bases,
+                &mut context,
+                address.wrapping_sub(load_bias),
+                gimli::EhFrame::cie_from_offset,
+            ) {
+                let value = |register| match register {
+                    dwarf_registers::STACK_POINTER => Some(registers.stack_pointer),
+                    dwarf_registers::FRAME_POINTER => Some(registers.frame_pointer),
+                    _ => None,
+                };
+                let cfa = match row.cfa() {
+                    CfaRule::RegisterAndOffset { register, offset } => {
+                        value(*register).map(|v| v.wrapping_add_signed(*offset))
+                    }
+                    CfaRule::Expression(_) => None,
+                };
+                if let Some(cfa) = cfa {
+                    let mut saved_registers = vec![];
+                    for (register, rule) in row.registers() {
+                        if let RegisterRule::Offset(offset) = rule {
+                            let address = cfa.wrapping_add_signed(*offset);
+                            saved_registers.push(SavedRegister {
+                                name: saved_register_name(*register),
+                                address,
+                                value: self.read(address as *mut _)?,
+                            });
+                        }
+                    }
+                    let saved = |register| {
+                        saved_registers
+                            .iter()
+                            .find(|s| s.name == saved_register_name(register))
+                            .map(|s| s.value)
+                    };
+                    let frame_pointer =
+                        saved(dwarf_registers::FRAME_POINTER).unwrap_or(registers.frame_pointer);
+                    let return_address = match row.register(dwarf_registers::RETURN_ADDRESS) {
+                        RegisterRule::Offset(_) => saved(dwarf_registers::RETURN_ADDRESS),
+                        RegisterRule::Undefined => None,
+                        _ => registers.return_address,
+                    };
+                    return Ok(Unwound {
+                        cfa,
+                        frame_pointer,
+                        return_address,
+                        saved_registers,
+                    });
+                }
+            }
+        }
+        let frame_pointer = registers.frame_pointer;
+        let saved_registers = vec![
+            SavedRegister {
+                name: saved_register_name(dwarf_registers::FRAME_POINTER),
+                address: frame_pointer,
+                value: self.read(frame_pointer as *mut _)?,
+            },
+            SavedRegister {
+                name: saved_register_name(dwarf_registers::RETURN_ADDRESS),
+                address: frame_pointer + 8,
+                value: self.read((frame_pointer + 8) as *mut _)?,
+            },
+        ];
+        Ok(Unwound {
+            cfa: frame_pointer + 16,
+            frame_pointer: saved_registers[0].value,
+            return_address: Some(saved_registers[1].value),
+            saved_registers,
+        })
+    }
+
+    /// Boundaries of all frames on the stack, from the current function up to `main`
+    pub fn stack_frames(&self) -> Result<Vec<StackFrame>, DebugError> {
+        Ok(self
+            .unwind_stack()?
+            .into_iter()
+            .map(|(frame, _)| frame)
+            .collect())
+    }
+
+    /// The frames on the stack with the return addresses and registers they saved
+    pub fn backtrace(&self) -> Result<Vec<Frame>, DebugError> {
+        Ok(self
+            .unwind_stack()?
+            .into_iter()
+            .map(|(_, frame)| frame)
+            .collect())
+    }
+
+    fn unwind_stack(&self) -> Result<Vec<(StackFrame, Frame)>, DebugError> {
+        let registers = Registers::from_regs(self.get_registers()?);
+        let cfi = self.dwarf.call_frame_info(&self.program);
+        let load_bias = match cfi {
+            Some(cfi) if cfi.position_independent => self.load_address(self.current_maps()),
+            _ => 0,
+        };
+        let mut current = UnwindRegisters {
+            pc: registers.instruction_pointer,
+            stack_pointer: registers.stack_pointer,
+            frame_pointer: registers.base_pointer,
+            return_address: self.link_register()?,
+        };
+        let mut frames = vec![];
+        while frames.len() < MAX_FRAMES {
+            // the return address of callers may already belong to the next line or function
+            let address = match frames.is_empty() {
+                true => current.pc,
+                false => current.pc - 1,
+            };
+            let function = self.get_func_from_addr(address).ok().and_then(|f| f.name);
+            let Ok(unwound) = self.unwind_frame(cfi, load_bias, address, &current) else {
+                break;
+            };
+            if unwound.cfa <= current.stack_pointer {
+                break;
+            }
+            let is_main = function.as_deref() == Some("main");
+            let return_address = unwound.return_address.filter(|a| *a != 0 && !is_main);
+            frames.push((
+                StackFrame {
+                    function: function.clone(),
+                    pc: current.pc,
+                    from: current.stack_pointer,
+                    to: unwound.cfa,
+                    arguments: vec![],
+                },
+                Frame {
+                    pc: current.pc,
+                    cfa: unwound.cfa,
+                    frame_pointer: current.frame_pointer,
+                    return_address,
+                    saved_registers: unwound.saved_registers,
+                    function,
+                    location: get_line_containing_addr(&self.dwarf, address).ok(),
+                },
+            ));
+            let Some(return_address) = return_address else {
+                break;
+            };
+            current = UnwindRegisters {
+                pc: return_address,
+                stack_pointer: unwound.cfa,
+                frame_pointer: unwound.frame_pointer,
+                return_address: None,
+            };
+        }
+        Ok(frames)
+    }
+}
+
+/// Name of a register saved in a frame, see [`SavedRegister`]
+fn saved_register_name(register: gimli::Register) -> String {
+    match register {
+        dwarf_registers::RETURN_ADDRESS => "return address".to_owned(),
+        register => abi_register_name(register.0),
+    }
+}
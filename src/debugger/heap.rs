@@ -0,0 +1,59 @@
+use std::{collections::HashMap, os::raw::c_void};
+
+use stackium_shared::{MallocChunk, MemoryMap, RegionKind};
+
+use super::Debugger;
+
+/// Flags in the low bits of the size in a chunk header
+const PREV_INUSE: u64 = 1;
+const IS_MMAPPED: u64 = 2;
+const NON_MAIN_ARENA: u64 = 4;
+/// `prev_size` and `size` in front of the memory returned by malloc
+pub const CHUNK_HEADER_SIZE: u64 = 16;
+/// Smallest chunk on 64-bit glibc, the header and room for the pointers of a free chunk
+const MIN_CHUNK_SIZE: u64 = 32;
+/// Walking stops after this many chunks to keep huge heaps fast
+const MAX_CHUNKS: usize = 100_000;
+
+/// Walks the chunks of glibc's allocator in the heap from `from` to `to`, `read` reads the word
+/// at an address. The walk stops at the first header which doesn't look like a chunk, e.g. in a
+/// heap managed by another allocator.
+pub fn malloc_chunks(from: u64, to: u64, read: impl Fn(u64) -> Option<u64>) -> Vec<MallocChunk> {
+    let mut chunks = vec![];
+    let mut chunk = from;
+    while chunks.len() < MAX_CHUNKS {
+        let Some(size_field) = read(chunk + 8) else {
+            break;
+        };
+        let size = size_field & !(PREV_INUSE | IS_MMAPPED | NON_MAIN_ARENA);
+        if size < MIN_CHUNK_SIZE || size % 16 != 0 || chunk + size > to {
+            break;
+        }
+        let next = chunk + size;
+        chunks.push(MallocChunk {
+            address: chunk + CHUNK_HEADER_SIZE,
+            prev_size: read(chunk).unwrap_or(0),
+            size,
+            prev_in_use: size_field & PREV_INUSE != 0,
+            mmapped: size_field & IS_MMAPPED != 0,
+            non_main_arena: size_field & NON_MAIN_ARENA != 0,
+            in_use: match next + CHUNK_HEADER_SIZE <= to {
+                true => read(next + 8).map(|s| s & PREV_INUSE != 0),
+                false => None,
+            },
+        });
+        chunk = next;
+    }
+    chunks
+}
+
+impl Debugger {
+    /// The chunks of all heap regions by the address malloc returned for them
+    pub fn heap_chunks(&self, maps: &[MemoryMap]) -> HashMap<u64, MallocChunk> {
+        maps.iter()
+            .filter(|m| m.kind == RegionKind::Heap && m.read)
+            .flat_map(|m| malloc_chunks(m.from, m.to, |addr| self.read(addr as *mut c_void).ok()))
+            .map(|c| (c.address, c))
+            .collect()
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use stackium_shared::{
+    format_value, numeric_value, DiscoveredVariable, HistoryEntry, VARIABLE_MEM_PADDING,
+};
+
+use super::{error::DebugError, util::get_line_from_pc, Debugger};
+
+/// Maximum number of recorded stops per variable, older entries are dropped
+const MAX_HISTORY_LENGTH: usize = 1000;
+
+/// Values of the tracked variables at every stop of the debugee
+#[derive(Default)]
+pub struct VariableHistory {
+    entries: HashMap<String, Vec<HistoryEntry>>,
+    stops: u64,
+}
+
+impl VariableHistory {
+    /// Drops all recorded values but keeps tracking the same variables
+    pub fn reset(&mut self) {
+        self.entries.values_mut().for_each(|e| e.clear());
+        self.stops = 0;
+    }
+}
+
+impl Debugger {
+    /// Returns the current value of the variable `name` among the discovered `variables` as a
+    /// history entry
+    fn history_entry(
+        &self,
+        name: &str,
+        variables: &[DiscoveredVariable],
+        stop: u64,
+    ) -> HistoryEntry {
+        let location = self
+            .get_pc()
+            .ok()
+            .and_then(|pc| get_line_from_pc(&self.dwarf, pc).ok());
+        let variable = variables.iter().find(|v| v.name.as_deref() == Some(name));
+        let memory = variable
+            .as_ref()
+            .and_then(|v| v.memory.as_ref())
+            .map(|m| &m[VARIABLE_MEM_PADDING as usize..]);
+        let (value, numeric) = match (variable, memory) {
+            (Some(v), Some(memory)) => (
+                format_value(&v.types, v.type_index, memory),
+                numeric_value(&v.types, v.type_index, memory),
+            ),
+            _ => (None, None),
+        };
+        HistoryEntry {
+            stop,
+            location,
+            value,
+            numeric,
+        }
+    }
+
+    pub fn track_variable(&mut self, name: String) {
+        let variables = self.discover_variables().unwrap_or_default();
+        let entry = self.history_entry(&name, &variables, self.history.stops);
+        self.history.entries.entry(name).or_insert(vec![entry]);
+    }
+
+    pub fn untrack_variable(&mut self, name: &str) {
+        self.history.entries.remove(name);
+    }
+
+    /// Records the values of all tracked variables, called after every command which resumed
+    /// the debugee
+    pub fn record_history(&mut self) {
+        if self.history.entries.is_empty() {
+            return;
+        }
+        self.history.stops += 1;
+        let names = self.history.entries.keys().cloned().collect::<Vec<_>>();
+        let variables = self.discover_variables().unwrap_or_default();
+        for name in names {
+            let entry = self.history_entry(&name, &variables, self.history.stops);
+            let entries = self.history.entries.get_mut(&name).unwrap();
+            entries.push(entry);
+            if entries.len() > MAX_HISTORY_LENGTH {
+                entries.remove(0);
+            }
+        }
+    }
+
+    pub fn variable_history(&self, name: &str) -> Result<Vec<HistoryEntry>, DebugError> {
+        self.history
+            .entries
+            .get(name)
+            .cloned()
+            .ok_or(DebugError::InvalidArgument(format!(
+                "Variable {} is not tracked",
+                name
+            )))
+    }
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use stackium_shared::{Invariant, StopReason};
+
+use super::{error::DebugError, expression::Expression, util::get_line_from_pc, Debugger};
+
+/// Expressions checked whenever the debugee stops and at every line while continuing
+#[derive(Default)]
+pub struct Invariants {
+    invariants: Vec<(Expression, Invariant)>,
+    /// Whether an address starts a line, looking lines up in the dwarf is slow
+    line_starts: HashMap<u64, bool>,
+}
+
+impl Invariants {
+    pub fn is_empty(&self) -> bool {
+        self.invariants.is_empty()
+    }
+
+    /// Forgets the values of the last checks, e.g. when the debugee is restarted
+    pub fn reset(&mut self) {
+        for (_, invariant) in self.invariants.iter_mut() {
+            invariant.holds = None;
+            invariant.error = None;
+        }
+    }
+}
+
+impl Debugger {
+    pub fn add_invariant(&mut self, source: String) -> Result<(), DebugError> {
+        let expression = Expression::parse(&source)?;
+        if self
+            .invariants
+            .invariants
+            .iter()
+            .any(|(_, i)| i.expression == source)
+        {
+            return Ok(());
+        }
+        self.invariants.invariants.push((
+            expression,
+            Invariant {
+                expression: source,
+                holds: None,
+                error: None,
+            },
+        ));
+        // an invariant which is already false doesn't stop the program right away
+        self.check_invariants();
+        Ok(())
+    }
+
+    pub fn remove_invariant(&mut self, source: &str) -> Result<(), DebugError> {
+        let count = self.invariants.invariants.len();
+        self.invariants
+            .invariants
+            .retain(|(_, i)| i.expression != source);
+        match self.invariants.invariants.len() < count {
+            true => Ok(()),
+            false => Err(DebugError::InvalidArgument(format!(
+                "No invariant {}",
+                source
+            ))),
+        }
+    }
+
+    pub fn get_invariants(&self) -> Vec<Invariant> {
+        self.invariants
+            .invariants
+            .iter()
+            .map(|(_, i)| i.clone())
+            .collect()
+    }
+
+    /// Evaluates every invariant in the current function and returns the first one which
+    /// became false since the last check. Invariants using variables which aren't in scope
+    /// are skipped, they keep their last value.
+    pub fn check_invariants(&mut self) -> Option<String> {
+        if self.invariants.is_empty() {
+            return None;
+        }
+        let variables = self.read_variables().unwrap_or_default();
+        let results = self
+            .invariants
+            .invariants
+            .iter()
+            .map(|(expression, _)| self.evaluate(expression, &variables))
+            .collect::<Vec<_>>();
+        let mut broken = None;
+        for ((_, invariant), result) in self.invariants.invariants.iter_mut().zip(results) {
+            match result {
+                Ok(value) => {
+                    if !value.is_true() && invariant.holds != Some(false) && broken.is_none() {
+                        broken = Some(invariant.expression.clone());
+                    }
+                    invariant.holds = Some(value.is_true());
+                    invariant.error = None;
+                }
+                Err(DebugError::InvalidArgument(error)) => invariant.error = Some(error),
+                Err(error) => invariant.error = Some(format!("{:?}", error)),
+            }
+        }
+        broken
+    }
+
+    /// Checks the invariants if `pc` starts a line, stopping with `InvariantBroken` if one
+    /// became false. Returns whether the debugee should keep going.
+    pub fn check_invariants_at(&mut self, pc: u64) -> bool {
+        if self.invariants.is_empty() {
+            return true;
+        }
+        let line_start = match self.invariants.line_starts.get(&pc) {
+            Some(line_start) => *line_start,
+            None => {
+                let line_start = get_line_from_pc(&self.dwarf, pc).is_ok();
+                self.invariants.line_starts.insert(pc, line_start);
+                line_start
+            }
+        };
+        if !line_start {
+            return true;
+        }
+        match self.check_invariants() {
+            Some(expression) => {
+                self.stop_reason = StopReason::InvariantBroken(expression);
+                false
+            }
+            None => true,
+        }
+    }
+
+    pub fn has_invariants(&self) -> bool {
+        !self.invariants.is_empty()
+    }
+}
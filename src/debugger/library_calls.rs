@@ -0,0 +1,305 @@
+use std::collections::{HashMap, HashSet};
+
+use object::{Object, ObjectSymbol};
+use stackium_shared::{Breakpoint, LibraryCall, Location};
+
+use super::{breakpoint::DebuggerBreakpoint, error::DebugError, Debugger};
+
+/// Maximum number of bytes read from string arguments
+const MAX_STRING_ARGUMENT_LENGTH: usize = 128;
+
+/// How an argument of a library function is displayed
+#[derive(Clone, Copy)]
+enum Argument {
+    /// Unsigned size or count
+    Size(&'static str),
+    Int(&'static str),
+    Pointer(&'static str),
+    /// Pointer to a null terminated string
+    String(&'static str),
+}
+
+pub struct LibraryFunction {
+    name: &'static str,
+    arguments: &'static [Argument],
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LibraryCategory {
+    Allocation,
+    Io,
+}
+
+impl LibraryCategory {
+    fn functions(self) -> &'static [LibraryFunction] {
+        match self {
+            LibraryCategory::Allocation => &ALLOCATION_FUNCTIONS,
+            LibraryCategory::Io => &IO_FUNCTIONS,
+        }
+    }
+}
+
+const ALLOCATION_FUNCTIONS: [LibraryFunction; 4] = [
+    LibraryFunction {
+        name: "malloc",
+        arguments: &[Argument::Size("size")],
+    },
+    LibraryFunction {
+        name: "calloc",
+        arguments: &[Argument::Size("nmemb"), Argument::Size("size")],
+    },
+    LibraryFunction {
+        name: "realloc",
+        arguments: &[Argument::Pointer("ptr"), Argument::Size("size")],
+    },
+    LibraryFunction {
+        name: "free",
+        arguments: &[Argument::Pointer("ptr")],
+    },
+];
+
+const IO_FUNCTIONS: [LibraryFunction; 12] = [
+    LibraryFunction {
+        name: "read",
+        arguments: &[
+            Argument::Int("fd"),
+            Argument::Pointer("buf"),
+            Argument::Size("count"),
+        ],
+    },
+    LibraryFunction {
+        name: "write",
+        arguments: &[
+            Argument::Int("fd"),
+            Argument::Pointer("buf"),
+            Argument::Size("count"),
+        ],
+    },
+    LibraryFunction {
+        name: "printf",
+        arguments: &[Argument::String("format")],
+    },
+    LibraryFunction {
+        name: "fprintf",
+        arguments: &[Argument::Pointer("stream"), Argument::String("format")],
+    },
+    LibraryFunction {
+        name: "puts",
+        arguments: &[Argument::String("s")],
+    },
+    LibraryFunction {
+        name: "fputs",
+        arguments: &[Argument::String("s"), Argument::Pointer("stream")],
+    },
+    LibraryFunction {
+        name: "putchar",
+        arguments: &[Argument::Int("c")],
+    },
+    LibraryFunction {
+        name: "getchar",
+        arguments: &[],
+    },
+    LibraryFunction {
+        name: "scanf",
+        arguments: &[Argument::String("format")],
+    },
+    LibraryFunction {
+        name: "fgets",
+        arguments: &[
+            Argument::Pointer("s"),
+            Argument::Int("size"),
+            Argument::Pointer("stream"),
+        ],
+    },
+    LibraryFunction {
+        name: "fopen",
+        arguments: &[Argument::String("pathname"), Argument::String("mode")],
+    },
+    LibraryFunction {
+        name: "fclose",
+        arguments: &[Argument::Pointer("stream")],
+    },
+];
+
+/// Breakpoints set by `BreakOnAllocation` and `BreakOnIO`, they are part of the regular
+/// breakpoints but hidden from the user
+#[derive(Default)]
+pub struct LibraryBreakpoints {
+    categories: HashSet<LibraryCategory>,
+    functions: HashMap<u64, (LibraryCategory, &'static LibraryFunction)>,
+}
+
+impl LibraryBreakpoints {
+    pub fn contains(&self, address: u64) -> bool {
+        self.functions.contains_key(&address)
+    }
+
+    /// Categories to break on again after the debugee was restarted, forgets the breakpoints
+    pub fn take_categories(&mut self) -> HashSet<LibraryCategory> {
+        self.functions.clear();
+        std::mem::take(&mut self.categories)
+    }
+}
+
+impl Debugger {
+    /// Addresses of the functions called by the program, i.e. their PLT entries or the
+    /// functions themselves if the program is statically linked
+    fn library_function_addresses(&self) -> Result<HashMap<String, u64>, DebugError> {
+        let bin = std::fs::read(&self.program)?;
+        let object_file = object::File::parse(&bin[..])
+            .map_err(|e| DebugError::InvalidArgument(e.to_string()))?;
+        let load_bias = self.load_bias(&object_file, &self.get_maps()?);
+        let mut addresses: HashMap<String, u64> = object_file
+            .symbols()
+            .filter(|s| s.is_definition() && s.kind() == object::SymbolKind::Text)
+            .filter_map(|s| Some((s.name().ok()?.to_owned(), s.address() + load_bias)))
+            .collect();
+        // objdump labels PLT entries like `0000000000401030 <malloc@plt>:`
+        let output = std::process::Command::new("objdump")
+            .arg("--disassemble")
+            .arg("--section=.plt")
+            .arg("--section=.plt.sec")
+            .arg(self.program.clone().into_os_string())
+            .output()?;
+        for line in std::str::from_utf8(&output.stdout)?.lines() {
+            let Some((address, label)) = line.split_once(' ') else {
+                continue;
+            };
+            let (Some(name), Ok(address)) = (
+                label
+                    .strip_prefix('<')
+                    .and_then(|l| l.strip_suffix("@plt>:")),
+                u64::from_str_radix(address, 16),
+            ) else {
+                continue;
+            };
+            addresses.insert(name.to_owned(), address + load_bias);
+        }
+        Ok(addresses)
+    }
+
+    /// Sets or removes breakpoints on all functions of the category the program calls
+    pub fn break_on_library_calls(
+        &mut self,
+        category: LibraryCategory,
+        enabled: bool,
+    ) -> Result<(), DebugError> {
+        if !enabled {
+            let addresses = self
+                .library_breakpoints
+                .functions
+                .iter()
+                .filter(|(_, (c, _))| *c == category)
+                .map(|(address, _)| *address)
+                .collect::<Vec<_>>();
+            for address in addresses {
+                if let Some(breakpoint) = self.breakpoints.iter_mut().find(|b| b.address == address)
+                {
+                    breakpoint.disable(self.child)?;
+                }
+                self.breakpoints.retain(|b| b.address != address);
+                self.library_breakpoints.functions.remove(&address);
+            }
+            self.library_breakpoints.categories.remove(&category);
+            return Ok(());
+        }
+        if self.library_breakpoints.categories.contains(&category) {
+            return Ok(());
+        }
+        let addresses = self.library_function_addresses()?;
+        let functions = category
+            .functions()
+            .iter()
+            .filter_map(|f| Some((*addresses.get(f.name)?, f)))
+            .collect::<Vec<_>>();
+        if functions.is_empty() {
+            return Err(DebugError::FunctionNotFound);
+        }
+        for (address, function) in functions {
+            // a user breakpoint on the function stays as it is
+            if self.breakpoints.iter().any(|b| b.address == address) {
+                continue;
+            }
+            let mut breakpoint = Breakpoint::with_location(
+                self.child,
+                address as *const u8,
+                Location {
+                    line: 0,
+                    file: format!("{}@plt", function.name),
+                    column: 0,
+                },
+            )?;
+            breakpoint.enable(self.child)?;
+            self.breakpoints.push(breakpoint);
+            self.library_breakpoints
+                .functions
+                .insert(address, (category, function));
+        }
+        self.library_breakpoints.categories.insert(category);
+        Ok(())
+    }
+
+    /// Null terminated string at `address`, read word by word
+    fn read_string_argument(&self, address: u64) -> Option<String> {
+        let mut bytes = vec![];
+        while bytes.len() < MAX_STRING_ARGUMENT_LENGTH {
+            let word = self.read((address + bytes.len() as u64) as *mut _).ok()?;
+            for byte in word.to_le_bytes() {
+                if byte == 0 {
+                    return Some(String::from_utf8_lossy(&bytes).into_owned());
+                }
+                bytes.push(byte);
+            }
+        }
+        Some(format!("{}..", String::from_utf8_lossy(&bytes)))
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn call_return_address(&self) -> Result<u64, DebugError> {
+        let rsp = self.get_registers()?.rsp;
+        self.read(rsp as *mut _)
+    }
+    #[cfg(target_arch = "aarch64")]
+    fn call_return_address(&self) -> Result<u64, DebugError> {
+        Ok(self.get_registers()?.regs[30])
+    }
+
+    /// The library call the debugee is stopped at, if any
+    pub fn library_call(&self) -> Result<Option<LibraryCall>, DebugError> {
+        let Ok(pc) = self.get_pc() else {
+            return Ok(None);
+        };
+        let Some((_, function)) = self.library_breakpoints.functions.get(&pc) else {
+            return Ok(None);
+        };
+        let registers = self.get_argument_registers()?;
+        let arguments = function
+            .arguments
+            .iter()
+            .zip(registers)
+            .map(|(argument, value)| match *argument {
+                Argument::Size(name) => (name.to_owned(), value.to_string()),
+                Argument::Int(name) => (name.to_owned(), (value as i32).to_string()),
+                Argument::Pointer(name) => (name.to_owned(), format!("{:#x}", value)),
+                Argument::String(name) => (
+                    name.to_owned(),
+                    match self.read_string_argument(value) {
+                        Some(string) => format!("{:?}", string),
+                        None => format!("{:#x}", value),
+                    },
+                ),
+            })
+            .collect();
+        // the return address may already belong to the next line, the call is right before it
+        let caller = self
+            .call_return_address()
+            .and_then(|address| self.resolve_address(address - 1))
+            .ok()
+            .map(|resolved| resolved.description);
+        Ok(Some(LibraryCall {
+            function: function.name.to_owned(),
+            arguments,
+            caller,
+        }))
+    }
+}
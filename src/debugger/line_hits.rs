@@ -0,0 +1,220 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant, SystemTime},
+};
+
+use nix::sys::{ptrace, wait::WaitPidFlag};
+use stackium_shared::{Breakpoint, DebugeeState, LineHits, Location, StopReason};
+
+use super::{
+    breakpoint::DebuggerBreakpoint,
+    error::DebugError,
+    util::{get_functions, get_line_containing_addr},
+    Debugger,
+};
+
+/// Breakpoints on the targets of backward jumps, i.e. the first instruction of loop bodies. They
+/// are only written while the child is continued and resume it right away when hit, so
+/// stepping, reading memory and the user's breakpoints don't see them.
+/// A running loop hits its counters again right away, so the child is waited for this long
+/// after resuming it instead of waiting for the next poll
+const COUNTER_WAIT: Duration = Duration::from_millis(1);
+/// Maximum time spent resuming the child from counters without blocking
+const COUNTER_BUDGET: Duration = Duration::from_millis(100);
+
+#[derive(Default)]
+pub struct LineCounters {
+    /// Counted addresses with their line, found when the child is continued the first time
+    addresses: Option<HashMap<u64, Location>>,
+    /// Counters written to the child while it is continued
+    inserted: HashMap<u64, Breakpoint>,
+    hits: HashMap<u64, u64>,
+}
+
+impl LineCounters {
+    /// Forgets the counts, e.g. when the debugee is restarted
+    pub fn reset(&mut self) {
+        self.inserted.clear();
+        self.hits.clear();
+    }
+}
+
+/// Target of a direct jump like `jl 401136 <main+0x10>` or `b.le 400544 <main+0x18>`
+fn jump_target(text: &str) -> Option<u64> {
+    let mnemonic = text
+        .split_whitespace()
+        .find(|word| !matches!(*word, "bnd" | "notrack"))?;
+    let is_jump = mnemonic.starts_with('j')
+        || mnemonic == "b"
+        || mnemonic.starts_with("b.")
+        || matches!(mnemonic, "cbz" | "cbnz" | "tbz" | "tbnz");
+    if !is_jump {
+        return None;
+    }
+    let target = text.split_once(" <")?.0.rsplit([' ', ',']).next()?;
+    u64::from_str_radix(target, 16).ok()
+}
+
+impl Debugger {
+    /// Finds the targets of the backward jumps inside the functions with debug information
+    fn loop_addresses(&self) -> Result<HashMap<u64, Location>, DebugError> {
+        let mut addresses = HashMap::new();
+        for function in get_functions(&self.dwarf)? {
+            let (Some(low_pc), Some(size)) = (function.low_pc, function.high_pc) else {
+                continue;
+            };
+            for instruction in self.disassemble_range(low_pc, low_pc + size)? {
+                let Some(target) = jump_target(&instruction.text) else {
+                    continue;
+                };
+                if target < low_pc || target > instruction.address {
+                    continue;
+                }
+                if let Ok(location) = get_line_containing_addr(&self.dwarf, target) {
+                    addresses.insert(target, location);
+                }
+            }
+        }
+        Ok(addresses)
+    }
+
+    /// Counts the current line if it starts a loop body, called whenever the child arrives
+    /// somewhere without hitting a counter, e.g. after single-stepping
+    pub fn count_line_hit(&mut self) {
+        let Ok(pc) = self.get_pc() else {
+            return;
+        };
+        let counted = self
+            .line_counters
+            .addresses
+            .as_ref()
+            .is_some_and(|a| a.contains_key(&pc));
+        if counted {
+            *self.line_counters.hits.entry(pc).or_insert(0) += 1;
+        }
+    }
+
+    /// Writes the counters before the child is continued, except where the user has a
+    /// breakpoint. The child is stepped off a counted instruction first, otherwise it would be
+    /// counted twice.
+    pub fn insert_line_counters(&mut self) -> Result<(), DebugError> {
+        if self.line_counters.addresses.is_none() {
+            self.line_counters.addresses = Some(self.loop_addresses().unwrap_or_default());
+        }
+        let addresses = self.line_counters.addresses.clone().unwrap_or_default();
+        let pc = self.get_pc()?;
+        if addresses.contains_key(&pc) && !self.breakpoints.iter().any(|b| b.address == pc) {
+            ptrace::step(self.child, None)?;
+            self.wait_status(Some(WaitPidFlag::WUNTRACED))?;
+            if self.has_exited() {
+                return Ok(());
+            }
+        }
+        for (address, location) in addresses {
+            if self
+                .breakpoints
+                .iter()
+                .any(|b| b.enabled && b.address == address)
+            {
+                continue;
+            }
+            let mut counter =
+                Breakpoint::with_location(self.child, address as *const u8, location)?;
+            counter.enable(self.child)?;
+            self.line_counters.inserted.insert(address, counter);
+        }
+        Ok(())
+    }
+
+    /// Removes the counters once the child stopped somewhere else, the counters of an exited
+    /// child are dropped
+    fn remove_line_counters(&mut self) -> Result<(), DebugError> {
+        let inserted = std::mem::take(&mut self.line_counters.inserted);
+        if self.has_exited() {
+            return Ok(());
+        }
+        for (_, mut counter) in inserted {
+            counter.disable(self.child)?;
+        }
+        Ok(())
+    }
+
+    /// Counts a hit counter and continues the child over it, `false` if the child stopped
+    /// for another reason
+    fn resume_from_line_counter(&mut self) -> Result<bool, DebugError> {
+        if self.has_exited() || self.stop_reason != StopReason::Breakpoint {
+            return Ok(false);
+        }
+        let pc = self.get_pc()?;
+        let Some(counter) = self.line_counters.inserted.get_mut(&pc) else {
+            return Ok(false);
+        };
+        counter.disable(self.child)?;
+        *self.line_counters.hits.entry(pc).or_insert(0) += 1;
+        ptrace::step(self.child, None)?;
+        self.wait_status(Some(WaitPidFlag::WUNTRACED))?;
+        if self.has_exited() || self.stop_reason != StopReason::Step {
+            // e.g. a signal was delivered instead
+            self.line_counters.inserted.remove(&pc);
+            return Ok(false);
+        }
+        if let Some(counter) = self.line_counters.inserted.get_mut(&pc) {
+            counter.enable(self.child)?;
+        }
+        ptrace::cont(self.child, None)?;
+        self.set_state(DebugeeState::Running);
+        Ok(true)
+    }
+
+    /// Resumes the child from every counter it hits after being waited for with `flags`, until
+    /// it stops for a different reason or keeps running. Hitting a counter doesn't change when
+    /// the child started running, which is still `since`.
+    pub fn resume_line_counters(
+        &mut self,
+        flags: Option<WaitPidFlag>,
+        since: SystemTime,
+    ) -> Result<(), DebugError> {
+        let start = Instant::now();
+        let mut last_hit = start;
+        while !self.line_counters.inserted.is_empty() {
+            if self.is_running() {
+                if last_hit.elapsed() > COUNTER_WAIT || start.elapsed() > COUNTER_BUDGET {
+                    break;
+                }
+                std::thread::yield_now();
+            } else if self.resume_from_line_counter()? {
+                self.state_since = since;
+                last_hit = Instant::now();
+            } else {
+                self.remove_line_counters()?;
+                break;
+            }
+            self.wait_status(flags)?;
+        }
+        // a user breakpoint where a counter would have been
+        if !self.is_running() && self.stop_reason == StopReason::Breakpoint {
+            self.count_line_hit();
+        }
+        Ok(())
+    }
+
+    /// How often the first line of every loop body was executed in the current run. Lines
+    /// without a counter are taken from the execution trace if tracing was enabled.
+    pub fn line_hit_counts(&self) -> Vec<LineHits> {
+        let mut counts: HashMap<(String, u64), u64> = HashMap::new();
+        for (address, location) in self.line_counters.addresses.iter().flatten() {
+            *counts
+                .entry((location.file.clone(), location.line))
+                .or_insert(0) += self.line_counters.hits.get(address).copied().unwrap_or(0);
+        }
+        for hits in self.tracer.hit_counts() {
+            counts.entry((hits.file, hits.line)).or_insert(hits.count);
+        }
+        let mut hit_counts = counts
+            .into_iter()
+            .map(|((file, line), count)| LineHits { file, line, count })
+            .collect::<Vec<_>>();
+        hit_counts.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        hit_counts
+    }
+}
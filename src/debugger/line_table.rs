@@ -0,0 +1,175 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use gimli::Reader;
+use stackium_shared::{LineAddress, Location};
+
+use super::files::get_file_path;
+
+/// A row of a line program without the state which is only needed while running it
+struct LineRow {
+    address: u64,
+    /// Index in `LineTable::files`
+    file: usize,
+    /// 0 if the row has no line
+    line: u64,
+    column: u64,
+    is_stmt: bool,
+    prologue_end: bool,
+}
+
+/// The line program of a compilation unit run once, so looking up the line of an address or the
+/// addresses of a line is a binary search instead of running the program again
+pub struct LineTable {
+    /// Rows of each sequence sorted by address, without the rows ending the sequences
+    rows: Vec<LineRow>,
+    /// Code range of each sequence with the range of its rows in `rows`, sorted by start
+    sequences: Vec<(Range<u64>, Range<usize>)>,
+    /// Paths of the files the rows are in
+    files: Vec<Option<PathBuf>>,
+    /// Indices in `rows` by file path and line
+    lines: HashMap<PathBuf, BTreeMap<u64, Vec<usize>>>,
+}
+
+impl LineTable {
+    pub fn new<R: Reader>(dwarf: &gimli::Dwarf<R>, unit: &gimli::Unit<R>) -> Self {
+        let mut table = Self {
+            rows: vec![],
+            sequences: vec![],
+            files: vec![],
+            lines: HashMap::new(),
+        };
+        let Some(line_program) = unit.line_program.clone() else {
+            return table;
+        };
+        let mut file_indices = HashMap::new();
+        let mut sequence_start = 0;
+        let mut rows = line_program.rows();
+        while let Ok(Some((header, row))) = rows.next_row() {
+            if row.end_sequence() {
+                let sequence = &mut table.rows[sequence_start..];
+                sequence.sort_by_key(|r| r.address);
+                if let Some(first) = sequence.first() {
+                    table.sequences.push((
+                        first.address..row.address(),
+                        sequence_start..table.rows.len(),
+                    ));
+                }
+                sequence_start = table.rows.len();
+                continue;
+            }
+            let file = *file_indices.entry(row.file_index()).or_insert_with(|| {
+                table.files.push(
+                    row.file(header)
+                        .and_then(|file| get_file_path(dwarf, unit, header, file)),
+                );
+                table.files.len() - 1
+            });
+            table.rows.push(LineRow {
+                address: row.address(),
+                file,
+                line: row.line().map_or(0, u64::from),
+                column: match row.column() {
+                    gimli::ColumnType::LeftEdge => 0,
+                    gimli::ColumnType::Column(c) => c.into(),
+                },
+                is_stmt: row.is_stmt(),
+                prologue_end: row.prologue_end(),
+            });
+        }
+        // rows of a sequence which isn't ended can't be looked up by address
+        table.rows.truncate(sequence_start);
+        table.sequences.sort_by_key(|(range, _)| range.start);
+        for (i, row) in table.rows.iter().enumerate() {
+            if let (Some(path), true) = (&table.files[row.file], row.line != 0) {
+                table
+                    .lines
+                    .entry(path.clone())
+                    .or_default()
+                    .entry(row.line)
+                    .or_default()
+                    .push(i);
+            }
+        }
+        table
+    }
+
+    fn location(&self, row: &LineRow) -> Location {
+        Location {
+            line: row.line,
+            file: self.files[row.file]
+                .as_ref()
+                .map(|path| path.display().to_string())
+                .unwrap_or_default(),
+            column: row.column,
+        }
+    }
+
+    /// Rows of the sequences whose code contains `address`
+    fn sequences_containing(&self, address: u64) -> impl Iterator<Item = &[LineRow]> {
+        let starting_before = self
+            .sequences
+            .partition_point(|(range, _)| range.start <= address);
+        self.sequences[..starting_before]
+            .iter()
+            .filter(move |(range, _)| address < range.end)
+            .map(|(_, rows)| &self.rows[rows.clone()])
+    }
+
+    /// The location of the row at exactly `address`
+    pub fn row_at(&self, address: u64) -> Option<Location> {
+        self.sequences_containing(address).find_map(|rows| {
+            let row = rows.get(rows.partition_point(|r| r.address < address))?;
+            (row.address == address).then(|| self.location(row))
+        })
+    }
+
+    /// The location of the last row at or before `address`
+    pub fn row_containing(&self, address: u64) -> Option<Location> {
+        self.sequences_containing(address).next().map(|rows| {
+            // the first row of a sequence is at its start, so at least one row is before
+            let after = rows.partition_point(|r| r.address <= address);
+            self.location(&rows[after - 1])
+        })
+    }
+
+    /// Rows of the function at `low_pc..high_pc`
+    fn rows_between(&self, low_pc: u64, high_pc: u64) -> impl Iterator<Item = &LineRow> {
+        self.sequences_containing(low_pc).flat_map(move |rows| {
+            let from = rows.partition_point(|r| r.address < low_pc);
+            let to = rows.partition_point(|r| r.address < high_pc);
+            &rows[from..to]
+        })
+    }
+
+    /// The first address of the function at `low_pc..high_pc` marked with `prologue_end`
+    pub fn prologue_end(&self, low_pc: u64, high_pc: u64) -> Option<u64> {
+        self.rows_between(low_pc, high_pc)
+            .find(|r| r.prologue_end)
+            .map(|r| r.address)
+    }
+
+    /// The address of the second statement of the function at `low_pc..high_pc`
+    pub fn second_statement(&self, low_pc: u64, high_pc: u64) -> Option<u64> {
+        self.rows_between(low_pc, high_pc)
+            .filter(|r| r.address > low_pc && r.is_stmt)
+            .map(|r| r.address)
+            .min()
+    }
+
+    /// The closest line at or after `line` in `file` which has code, with its addresses
+    pub fn line_addresses(&self, line: u64, file: &Path) -> Option<(u64, Vec<LineAddress>)> {
+        let (line, rows) = self.lines.get(file)?.range(line..).next()?;
+        let addresses = rows
+            .iter()
+            .map(|i| LineAddress {
+                address: self.rows[*i].address,
+                is_stmt: self.rows[*i].is_stmt,
+            })
+            .collect();
+        Some((*line, addresses))
+    }
+}
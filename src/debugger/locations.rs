@@ -0,0 +1,380 @@
+use gimli::{AttributeValue, DebuggingInformationEntry, Expression, Reader, Unit};
+use stackium_shared::{Registers, StackFrame, Variable, VariableScope};
+use tracing::warn;
+
+use super::{
+    error::DebugError,
+    registers::{abi_register_name, FromUserRegsStruct},
+    util::{get_line_containing_addr, get_piece_addr},
+    ConcreteReader, Debugger,
+};
+
+/// Values the location expressions of the variables of a frame are evaluated against
+#[derive(Clone, Copy)]
+pub struct FrameContext {
+    /// Canonical frame address, pushed by `DW_OP_call_frame_cfa`
+    pub cfa: u64,
+    /// Value of the `DW_AT_frame_base` of the function, `DW_OP_fbreg` is relative to it
+    pub frame_base: Option<u64>,
+}
+
+/// A function or block enclosing the entries visited while walking a unit
+struct Scope {
+    depth: isize,
+    is_function: bool,
+    scope: VariableScope,
+}
+
+impl Debugger {
+    /// The frame of the function currently executed
+    fn innermost_frame(&self) -> Result<StackFrame, DebugError> {
+        if let Some(frame) = self.stack_frames()?.into_iter().next() {
+            return Ok(frame);
+        }
+        let registers = Registers::from_regs(self.get_registers()?);
+        Ok(StackFrame {
+            function: None,
+            pc: registers.instruction_pointer,
+            from: registers.stack_pointer,
+            to: registers.base_pointer + 16,
+            arguments: vec![],
+        })
+    }
+
+    /// Lowest address, highest address and whether `pc` is in one of the ranges of the entry
+    fn scope_range(
+        &self,
+        unit: &Unit<ConcreteReader>,
+        entry: &DebuggingInformationEntry<ConcreteReader>,
+        pc: u64,
+    ) -> Result<(u64, u64, bool), DebugError> {
+        let mut ranges = self.dwarf.die_ranges(unit, entry)?;
+        let (mut low_pc, mut high_pc, mut contains_pc) = (u64::MAX, 0, false);
+        while let Some(range) = ranges.next()? {
+            low_pc = low_pc.min(range.begin);
+            high_pc = high_pc.max(range.end);
+            contains_pc |= range.begin <= pc && pc < range.end;
+        }
+        Ok((low_pc.min(high_pc), high_pc, contains_pc))
+    }
+
+    /// Evaluates the `DW_AT_frame_base` of `function` in `frame`
+    fn frame_context(
+        &self,
+        unit: &Unit<ConcreteReader>,
+        function: &DebuggingInformationEntry<ConcreteReader>,
+        frame: &StackFrame,
+    ) -> Result<FrameContext, DebugError> {
+        let mut context = FrameContext {
+            cfa: frame.to,
+            frame_base: None,
+        };
+        if let Some(AttributeValue::Exprloc(expression)) =
+            function.attr_value(gimli::DW_AT_frame_base)?
+        {
+            let pieces = self.evaluate_expression(unit, expression, &context)?;
+            context.frame_base = match pieces.first().map(|p| &p.location) {
+                Some(gimli::Location::Register { register }) => {
+                    Some(self.get_register_from_abi(register.0)?)
+                }
+                Some(_) => get_piece_addr(&pieces[0]),
+                None => None,
+            };
+        }
+        Ok(context)
+    }
+
+    /// The expression describing where the variable is stored at `pc`, `None` if the variable
+    /// has no location there, e.g. because it isn't initialized yet
+    fn location_expression(
+        &self,
+        unit: &Unit<ConcreteReader>,
+        location: AttributeValue<ConcreteReader>,
+        pc: u64,
+    ) -> Result<Option<Expression<ConcreteReader>>, DebugError> {
+        let offset = match location {
+            AttributeValue::Exprloc(expression) => return Ok(Some(expression)),
+            AttributeValue::LocationListsRef(offset) => offset,
+            AttributeValue::DebugLocListsIndex(index) => {
+                self.dwarf.locations_offset(unit, index)?
+            }
+            _ => {
+                warn!("Unexpected location type: {:?}", location);
+                return Ok(None);
+            }
+        };
+        let mut locations = self.dwarf.locations(unit, offset)?;
+        while let Some(location) = locations.next()? {
+            if location.range.begin <= pc && pc < location.range.end {
+                return Ok(Some(location.data));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Decodes a variable or parameter of a scope, evaluating its location only if the scope is
+    /// live
+    fn scope_variable(
+        &self,
+        unit: &Unit<ConcreteReader>,
+        entry: &DebuggingInformationEntry<ConcreteReader>,
+        location: AttributeValue<ConcreteReader>,
+        scope: &VariableScope,
+        frame: &StackFrame,
+        context: &FrameContext,
+    ) -> Result<Variable, DebugError> {
+        let mut var = Variable {
+            low_pc: scope.low_pc,
+            high_pc: scope.high_pc,
+            parameter: entry.tag() == gimli::DW_TAG_formal_parameter,
+            ..Default::default()
+        };
+        if let Some(type_attr) = entry.attr(gimli::DW_AT_type)? {
+            var.type_name = self
+                .decode_type(type_attr.value(), stackium_shared::DataType(vec![]))
+                .ok();
+        }
+        if let Some(name) = entry.attr(gimli::DW_AT_name)? {
+            var.name = Some(Debugger::decode_string_attribute(
+                name.value(),
+                &self.dwarf,
+                unit,
+            ));
+        }
+        if let Some(file) = entry.attr(gimli::DW_AT_decl_file)? {
+            if let Some(file) = file.string_value(&self.dwarf.debug_str) {
+                var.file = file.to_string().ok().map(|s| s.to_string());
+            }
+        }
+        if let Some(line) = entry.attr(gimli::DW_AT_decl_line)? {
+            var.line = line.udata_value();
+        }
+        // variables of blocks which were left or not entered yet are out of scope
+        if scope.live {
+            if let Some(expression) = self.location_expression(unit, location, frame.pc)? {
+                match self.evaluate_expression(unit, expression, context) {
+                    Ok(pieces) => {
+                        var.optimized = describe_pieces(&pieces);
+                        if var.optimized.is_none() {
+                            var.addr = get_piece_addr(&pieces[0]);
+                        }
+                        var.value = self.retrieve_pieces(pieces).ok();
+                    }
+                    // e.g. values the function was called with that are not stored anywhere
+                    Err(_) => var.optimized = Some("optimized out".to_owned()),
+                }
+                var.in_scope = true;
+            }
+        }
+        Ok(var)
+    }
+
+    /// The current function with its blocks and variables, `None` if it has no debug
+    /// information. Only variables live at the program counter are `in_scope` and have an
+    /// address, which is evaluated using the canonical frame address of the frame instead of
+    /// the frame pointer.
+    pub fn variable_scope(&self) -> Result<Option<VariableScope>, DebugError> {
+        let frame = self.innermost_frame()?;
+        for unit in self.dwarf.units_containing(frame.pc) {
+            let mut entries = unit.entries();
+            let mut depth = 0;
+            let mut scopes: Vec<Scope> = vec![];
+            let mut function = None;
+            let mut context = None;
+            while let Some((delta, entry)) = entries.next_dfs()? {
+                depth += delta;
+                while scopes.last().is_some_and(|s| s.depth >= depth) {
+                    close_scope(&mut scopes, &mut function);
+                }
+                match entry.tag() {
+                    gimli::DW_TAG_subprogram
+                    | gimli::DW_TAG_lexical_block
+                    | gimli::DW_TAG_inlined_subroutine => {
+                        let (low_pc, high_pc, contains_pc) =
+                            self.scope_range(unit, entry, frame.pc)?;
+                        let is_function = entry.tag() == gimli::DW_TAG_subprogram;
+                        let mut name = None;
+                        if is_function && contains_pc {
+                            context = Some(self.frame_context(unit, entry, &frame)?);
+                            if let Some(attr) = entry.attr_value(gimli::DW_AT_name)? {
+                                name = Some(Debugger::decode_string_attribute(
+                                    attr,
+                                    &self.dwarf,
+                                    unit,
+                                ));
+                            }
+                        }
+                        let live = contains_pc && scopes.last().map_or(true, |s| s.scope.live);
+                        scopes.push(Scope {
+                            depth,
+                            is_function,
+                            scope: VariableScope {
+                                function: name,
+                                line: None,
+                                low_pc,
+                                high_pc,
+                                live,
+                                variables: vec![],
+                                scopes: vec![],
+                            },
+                        });
+                    }
+                    gimli::DW_TAG_variable | gimli::DW_TAG_formal_parameter => {
+                        // only variables of the current function, not globals or other functions
+                        let Some(index) = scopes.iter().rposition(|s| s.is_function) else {
+                            continue;
+                        };
+                        let (Some(context), true) = (&context, scopes[index].scope.live) else {
+                            continue;
+                        };
+                        let Some(location) = entry.attr_value(gimli::DW_AT_location)? else {
+                            continue;
+                        };
+                        let scope = &scopes.last().unwrap().scope;
+                        let variable =
+                            self.scope_variable(unit, entry, location, scope, &frame, context)?;
+                        scopes.last_mut().unwrap().scope.variables.push(variable);
+                    }
+                    _ => {}
+                }
+            }
+            while !scopes.is_empty() {
+                close_scope(&mut scopes, &mut function);
+            }
+            if let Some(mut function) = function {
+                self.add_scope_lines(&mut function);
+                return Ok(Some(function));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The parameters of the function of `frame` with the values they have in it. Callers are
+    /// looked up by the address of the call instruction as their `pc` is the return address.
+    fn frame_arguments(
+        &self,
+        frame: &StackFrame,
+        innermost: bool,
+    ) -> Result<Vec<Variable>, DebugError> {
+        let frame = StackFrame {
+            pc: if innermost { frame.pc } else { frame.pc - 1 },
+            ..frame.clone()
+        };
+        for unit in self.dwarf.units_containing(frame.pc) {
+            let mut entries = unit.entries();
+            let mut depth = 0;
+            // depth and scope of the function containing the pc once it was found
+            let mut function: Option<(isize, VariableScope, FrameContext)> = None;
+            let mut arguments = vec![];
+            while let Some((delta, entry)) = entries.next_dfs()? {
+                depth += delta;
+                if let Some((function_depth, scope, context)) = &function {
+                    if depth <= *function_depth {
+                        return Ok(arguments);
+                    }
+                    if depth != function_depth + 1 || entry.tag() != gimli::DW_TAG_formal_parameter
+                    {
+                        continue;
+                    }
+                    if let Some(location) = entry.attr_value(gimli::DW_AT_location)? {
+                        arguments.push(
+                            self.scope_variable(unit, entry, location, scope, &frame, context)?,
+                        );
+                    }
+                } else if entry.tag() == gimli::DW_TAG_subprogram {
+                    let (low_pc, high_pc, contains_pc) = self.scope_range(unit, entry, frame.pc)?;
+                    if contains_pc {
+                        let scope = VariableScope {
+                            function: None,
+                            line: None,
+                            low_pc,
+                            high_pc,
+                            live: true,
+                            variables: vec![],
+                            scopes: vec![],
+                        };
+                        let context = self.frame_context(unit, entry, &frame)?;
+                        function = Some((depth, scope, context));
+                    }
+                }
+            }
+            if function.is_some() {
+                return Ok(arguments);
+            }
+        }
+        Ok(vec![])
+    }
+
+    /// The frames of `stack_frames` with the arguments of their functions
+    pub fn stack_frames_with_arguments(&self) -> Result<Vec<StackFrame>, DebugError> {
+        let mut frames = self.stack_frames()?;
+        for (i, frame) in frames.iter_mut().enumerate() {
+            frame.arguments = self.frame_arguments(frame, i == 0).unwrap_or_default();
+        }
+        Ok(frames)
+    }
+
+    fn add_scope_lines(&self, scope: &mut VariableScope) {
+        scope.line = get_line_containing_addr(&self.dwarf, scope.low_pc)
+            .ok()
+            .map(|l| l.line);
+        for scope in scope.scopes.iter_mut() {
+            self.add_scope_lines(scope);
+        }
+    }
+
+    /// All variables and parameters of the current function, see `variable_scope`
+    pub fn read_variables(&self) -> Result<Vec<Variable>, DebugError> {
+        fn flatten(scope: VariableScope, variables: &mut Vec<Variable>) {
+            variables.extend(scope.variables);
+            for scope in scope.scopes {
+                flatten(scope, variables);
+            }
+        }
+        let mut variables = vec![];
+        if let Some(scope) = self.variable_scope()? {
+            flatten(scope, &mut variables);
+        }
+        Ok(variables)
+    }
+}
+
+/// Describes where a variable is stored, `None` if it is in one piece of memory
+fn describe_pieces(pieces: &[gimli::Piece<ConcreteReader>]) -> Option<String> {
+    let describe = |piece: &gimli::Piece<ConcreteReader>| match piece.location {
+        gimli::Location::Empty => "optimized out".to_owned(),
+        gimli::Location::Register { register } => abi_register_name(register.0),
+        gimli::Location::Address { address } => format!("memory at {:#x}", address),
+        gimli::Location::Value { .. } | gimli::Location::Bytes { .. } => "constant".to_owned(),
+        gimli::Location::ImplicitPointer { .. } => "implicit pointer".to_owned(),
+    };
+    match pieces {
+        [] => Some("optimized out".to_owned()),
+        [piece] => match piece.location {
+            gimli::Location::Address { .. } => None,
+            gimli::Location::Register { register } => {
+                Some(format!("in register {}", abi_register_name(register.0)))
+            }
+            _ => Some(describe(piece)),
+        },
+        pieces => Some(format!(
+            "split: {}",
+            pieces.iter().map(describe).collect::<Vec<_>>().join(", ")
+        )),
+    }
+}
+
+/// Attaches the innermost scope to the scope enclosing it, or stores it in `function` if it is
+/// the function containing the program counter
+fn close_scope(scopes: &mut Vec<Scope>, function: &mut Option<VariableScope>) {
+    let Some(closed) = scopes.pop() else {
+        return;
+    };
+    if closed.is_function {
+        if closed.scope.live {
+            *function = Some(closed.scope);
+        }
+    } else if let Some(parent) = scopes.last_mut() {
+        parent.scope.scopes.push(closed.scope);
+    }
+}
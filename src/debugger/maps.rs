@@ -0,0 +1,358 @@
+use object::{Object, ObjectSection};
+use stackium_shared::{MapChange, MapChangeKind, MemoryMap, RegionKind};
+use tracing::warn;
+
+use super::{error::DebugError, Debugger};
+
+/// Memory maps at the last two stops, used to show how the memory layout changed
+#[derive(Default)]
+pub struct MapHistory {
+    /// `None` until the debugee was resumed for the first time
+    previous: Option<Vec<MemoryMap>>,
+    current: Vec<MemoryMap>,
+}
+
+/// File name of a mapped path, e.g. `libc.so.6`
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Parses a line of `/proc/<pid>/maps` like
+/// `55d0c000-55d0d000 r-xp 00001000 08:01 1234    /usr/bin/cat`. The path is missing for
+/// anonymous regions, it may contain spaces and ends with ` (deleted)` if the file was removed.
+#[cfg(target_os = "linux")]
+fn parse_proc_map(line: &str) -> Option<MemoryMap> {
+    let mut rest = line;
+    let mut field = || {
+        let trimmed = rest.trim_start();
+        let (field, after) = trimmed.split_at(trimmed.find(' ').unwrap_or(trimmed.len()));
+        rest = after;
+        field
+    };
+    let (from, to) = field().split_once('-')?;
+    let permissions = field().as_bytes();
+    let offset = field();
+    // device and inode
+    field();
+    field();
+    if permissions.len() != 4 {
+        return None;
+    }
+    Some(MemoryMap {
+        from: u64::from_str_radix(from, 16).ok()?,
+        to: u64::from_str_radix(to, 16).ok()?,
+        read: permissions[0] == b'r',
+        write: permissions[1] == b'w',
+        execute: permissions[2] == b'x',
+        shared: permissions[3] == b's',
+        offset: u64::from_str_radix(offset, 16).ok()?,
+        mapped: rest.trim_start().to_owned(),
+        kind: RegionKind::Other,
+        label: String::new(),
+    })
+}
+
+/// Parses a region of `vmmap -wide` like
+/// `__TEXT  100000000-100004000  [ 16K 16K 0K 0K] r-x/r-x SM=COW  /usr/bin/cat`. Regions without
+/// a file are named like `[stack]` and `[heap]` on Linux, so they are classified the same way.
+#[cfg(not(target_os = "linux"))]
+fn parse_vmmap_region(line: &str) -> Option<MemoryMap> {
+    let fields = line.split_whitespace().collect::<Vec<_>>();
+    let range = fields.iter().position(|field| {
+        field
+            .split_once('-')
+            .is_some_and(|(from, to)| u64::from_str_radix(from, 16).is_ok() && !to.is_empty())
+    })?;
+    let (from, to) = fields[range].split_once('-')?;
+    let permissions = fields[range..]
+        .iter()
+        .find(|field| field.len() == 7 && field.as_bytes()[3] == b'/')?
+        .as_bytes();
+    let sharing = fields[range..]
+        .iter()
+        .position(|field| field.starts_with("SM="))?;
+    let path = line
+        .split_once(fields[range + sharing])
+        .map_or("", |(_, path)| path.trim());
+    let mapped = match fields[0] {
+        "Stack" if fields[1] != "Guard" => "[stack]",
+        region if region.starts_with("MALLOC") => "[heap]",
+        // besides files vmmap names e.g. the thread of a stack or the zone of malloc
+        _ if path.starts_with('/') => path,
+        _ => "",
+    }
+    .to_owned();
+    Some(MemoryMap {
+        from: u64::from_str_radix(from, 16).ok()?,
+        to: u64::from_str_radix(to, 16).ok()?,
+        read: permissions[0] == b'r',
+        write: permissions[1] == b'w',
+        execute: permissions[2] == b'x',
+        shared: fields[range + sharing] == "SM=SHM",
+        offset: 0,
+        mapped,
+        kind: RegionKind::Other,
+        label: String::new(),
+    })
+}
+
+/// Describes a region for errors, e.g. `heap at 0x5555a000-0x5557b000`
+pub fn describe_region(map: &MemoryMap) -> String {
+    let name = match (map.label.as_str(), map.mapped.as_str()) {
+        ("", "") => "anonymous mapping",
+        ("", mapped) => mapped,
+        (label, _) => label,
+    };
+    format!("{} at {:#x}-{:#x}", name, map.from, map.to)
+}
+
+/// The error for reading `addr` if no readable region of `maps` contains it
+pub fn unreadable_address(maps: &[MemoryMap], addr: u64) -> DebugError {
+    if let Some(map) = maps.iter().find(|m| m.from <= addr && addr < m.to) {
+        return DebugError::UnreadableAddress(format!(
+            "{:#x} is in {}, which can't be read",
+            addr,
+            describe_region(map)
+        ));
+    }
+    let distance = |m: &&MemoryMap| match addr < m.from {
+        true => m.from - addr,
+        false => addr - m.to + 1,
+    };
+    DebugError::UnreadableAddress(match maps.iter().filter(|m| m.read).min_by_key(distance) {
+        Some(nearest) => format!(
+            "{:#x} is not mapped, the nearest region is {}",
+            addr,
+            describe_region(nearest)
+        ),
+        None => format!("{:#x} is not mapped", addr),
+    })
+}
+
+/// How many of the `len` bytes at `addr` are in readable regions of `maps`, which can't be
+/// checked without any
+fn readable_length(maps: &[MemoryMap], addr: u64, len: u64) -> Result<u64, DebugError> {
+    if maps.is_empty() || len == 0 {
+        return Ok(len);
+    }
+    let Some(region) = maps
+        .iter()
+        .find(|m| m.read && m.from <= addr && addr < m.to)
+    else {
+        return Err(unreadable_address(maps, addr));
+    };
+    // e.g. the code and the constants of a library are adjacent regions
+    let mut end = region.to;
+    while let Some(next) = maps.iter().find(|m| m.read && m.from == end) {
+        end = next.to;
+    }
+    Ok(len.min(end - addr))
+}
+
+impl Debugger {
+    /// How many of the `len` bytes at `addr` can be read, checked against the maps of the
+    /// current stop instead of failing in ptrace for every byte
+    pub fn readable_length(&self, addr: u64, len: u64) -> Result<u64, DebugError> {
+        match readable_length(&self.maps.current, addr, len) {
+            Ok(readable) if readable == len => Ok(len),
+            // the maps of the stop miss regions mapped since, e.g. by a function called with
+            // an expression
+            _ => readable_length(&self.get_maps()?, addr, len),
+        }
+    }
+
+    /// The memory regions of the child in the order of their addresses. Lines which can't be
+    /// parsed are left out with a warning in the log instead of failing the command.
+    #[cfg(target_os = "linux")]
+    pub fn get_maps(&self) -> Result<Vec<MemoryMap>, DebugError> {
+        let text = std::fs::read_to_string(format!("/proc/{}/maps", self.child))?;
+        let mut maps = vec![];
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            match parse_proc_map(line) {
+                Some(map) => maps.push(map),
+                None => warn!("Skipped the unexpected memory map {:?}", line),
+            }
+        }
+        self.classify_regions(&mut maps);
+        Ok(maps)
+    }
+
+    /// Without `/proc` the regions are read from the output of `vmmap`, which macOS ships with.
+    /// The commands looking at the memory layout see no regions if it fails.
+    #[cfg(not(target_os = "linux"))]
+    pub fn get_maps(&self) -> Result<Vec<MemoryMap>, DebugError> {
+        let output = std::process::Command::new("vmmap")
+            .arg("-wide")
+            .arg(self.child.to_string())
+            .output();
+        let text = match output {
+            Ok(output) if output.status.success() => output.stdout,
+            Ok(output) => {
+                warn!("vmmap failed with {}", output.status);
+                return Ok(vec![]);
+            }
+            Err(e) => {
+                warn!("Failed running vmmap: {}", e);
+                return Ok(vec![]);
+            }
+        };
+        let mut maps = String::from_utf8_lossy(&text)
+            .lines()
+            .filter_map(parse_vmmap_region)
+            .collect::<Vec<_>>();
+        maps.sort_by_key(|map| map.from);
+        self.classify_regions(&mut maps);
+        Ok(maps)
+    }
+
+    /// Sets the kind and label of regions parsed from `/proc/<pid>/maps`
+    pub fn classify_regions(&self, maps: &mut [MemoryMap]) {
+        let program = std::fs::canonicalize(&self.program).unwrap_or(self.program.clone());
+        let bin = std::fs::read(&self.program).unwrap_or_default();
+        let object_file = object::File::parse(&bin[..]).ok();
+        let load_bias = object_file
+            .as_ref()
+            .map_or(0, |object_file| self.load_bias(object_file, maps));
+        let mut previous_program_end = None;
+        for map in maps.iter_mut() {
+            let is_program = std::path::Path::new(&map.mapped) == program;
+            // names of the sections of the program in this region
+            let sections = match (&object_file, is_program) {
+                (Some(object_file), true) => object_file
+                    .sections()
+                    .filter(|s| {
+                        s.size() > 0
+                            && s.address() > 0
+                            && s.address() + load_bias < map.to
+                            && s.address() + s.size() + load_bias > map.from
+                    })
+                    .filter_map(|s| s.name().ok().map(|n| n.to_owned()))
+                    .collect::<Vec<_>>(),
+                _ => vec![],
+            };
+            let has_section = |name: &str| sections.iter().any(|s| s == name);
+            let (kind, label) = match map.mapped.as_str() {
+                "[stack]" => (RegionKind::Stack, "stack".to_owned()),
+                "[heap]" => (RegionKind::Heap, "heap".to_owned()),
+                "[vdso]" => (
+                    RegionKind::Vdso,
+                    "vdso (kernel code for fast system calls)".to_owned(),
+                ),
+                "[vvar]" | "[vvar_vclock]" | "[vsyscall]" => {
+                    (RegionKind::Kernel, format!("kernel data {}", map.mapped))
+                }
+                _ if is_program => {
+                    let kind = if map.execute {
+                        RegionKind::Text
+                    } else if !map.write {
+                        RegionKind::ReadOnlyData
+                    } else if has_section(".data") || !has_section(".bss") {
+                        RegionKind::Data
+                    } else {
+                        RegionKind::Bss
+                    };
+                    let description = match kind {
+                        RegionKind::Text => "program code",
+                        RegionKind::ReadOnlyData => "program constants",
+                        RegionKind::Bss => "program zero-initialized globals",
+                        _ => "program globals",
+                    };
+                    let label = if sections.is_empty() {
+                        description.to_owned()
+                    } else {
+                        format!("{} ({})", description, sections.join(" "))
+                    };
+                    (kind, label)
+                }
+                // the rest of .bss which didn't fit into the last page of the program
+                "" if map.write && previous_program_end == Some(map.from) => (
+                    RegionKind::Bss,
+                    "program zero-initialized globals (.bss)".to_owned(),
+                ),
+                "" => (RegionKind::Anonymous, "anonymous mapping".to_owned()),
+                mapped if mapped.contains(".so") => (
+                    RegionKind::SharedLibrary,
+                    format!(
+                        "{} {}",
+                        file_name(mapped),
+                        if map.execute {
+                            "code"
+                        } else if map.write {
+                            "globals"
+                        } else {
+                            "constants"
+                        }
+                    ),
+                ),
+                mapped => (RegionKind::Other, file_name(mapped).to_owned()),
+            };
+            previous_program_end = match is_program {
+                true => Some(map.to),
+                false => None,
+            };
+            map.kind = kind;
+            map.label = label;
+        }
+    }
+
+    /// Forgets the recorded maps and starts over with the current ones, e.g. when the debugee
+    /// was (re)started
+    pub fn reset_maps(&mut self) {
+        self.maps = MapHistory {
+            previous: None,
+            current: self.get_maps().unwrap_or_default(),
+        };
+    }
+
+    /// Remembers the memory maps of the current stop, called after every command which resumed
+    /// the debugee
+    pub fn record_maps(&mut self) {
+        let maps = self.get_maps().unwrap_or_default();
+        self.maps.previous = Some(std::mem::replace(&mut self.maps.current, maps));
+    }
+
+    /// The memory maps recorded at the current stop
+    pub fn current_maps(&self) -> &[MemoryMap] {
+        &self.maps.current
+    }
+
+    /// Regions which were added, removed or resized since the previous stop
+    pub fn maps_diff(&self) -> Vec<MapChange> {
+        let Some(previous) = &self.maps.previous else {
+            return vec![];
+        };
+        // regions keep their start (e.g. the heap) or their end (e.g. the stack) when resized
+        let same_region = |a: &MemoryMap, b: &MemoryMap| {
+            a.mapped == b.mapped
+                && a.offset == b.offset
+                && a.kind == b.kind
+                && (a.from == b.from || a.to == b.to)
+        };
+        let mut changes = vec![];
+        for map in self.maps.current.iter() {
+            match previous.iter().find(|p| same_region(p, map)) {
+                Some(p) if p.from != map.from || p.to != map.to => changes.push(MapChange {
+                    map: map.clone(),
+                    change: MapChangeKind::Resized {
+                        previous_from: p.from,
+                        previous_to: p.to,
+                    },
+                }),
+                Some(_) => {}
+                None => changes.push(MapChange {
+                    map: map.clone(),
+                    change: MapChangeKind::Added,
+                }),
+            }
+        }
+        for map in previous.iter() {
+            if !self.maps.current.iter().any(|c| same_region(map, c)) {
+                changes.push(MapChange {
+                    map: map.clone(),
+                    change: MapChangeKind::Removed,
+                });
+            }
+        }
+        changes
+    }
+}
@@ -0,0 +1,43 @@
+use std::{cell::RefCell, collections::HashMap};
+
+/// Reads are no longer cached once this many bytes are
+const MAX_CACHED_BYTES: usize = 16 * 1024 * 1024;
+
+/// Memory read since the child stopped by address and length, so the windows of the UI reading
+/// overlapping regions after a stop don't read every byte with ptrace again. Cleared whenever
+/// the child is continued or waited for and when the debugger writes to its memory.
+#[derive(Default)]
+pub struct MemoryCache {
+    reads: RefCell<HashMap<(u64, u64), Vec<u8>>>,
+}
+
+impl MemoryCache {
+    /// The `len` bytes at `addr` if they are part of an earlier read
+    pub fn get(&self, addr: u64, len: u64) -> Option<Vec<u8>> {
+        let reads = self.reads.borrow();
+        if let Some(bytes) = reads.get(&(addr, len)) {
+            return Some(bytes.clone());
+        }
+        reads
+            .iter()
+            .find(|((from, size), _)| {
+                *from <= addr && addr.saturating_add(len) <= from.saturating_add(*size)
+            })
+            .map(|((from, _), bytes)| {
+                let start = (addr - from) as usize;
+                bytes[start..start + len as usize].to_vec()
+            })
+    }
+
+    pub fn insert(&self, addr: u64, bytes: &[u8]) {
+        let mut reads = self.reads.borrow_mut();
+        let cached: usize = reads.values().map(Vec::len).sum();
+        if cached + bytes.len() <= MAX_CACHED_BYTES {
+            reads.insert((addr, bytes.len() as u64), bytes.to_vec());
+        }
+    }
+
+    pub fn clear(&self) {
+        self.reads.borrow_mut().clear();
+    }
+}
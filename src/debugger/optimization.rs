@@ -0,0 +1,53 @@
+use gimli::Reader;
+
+use super::Debugger;
+
+/// The optimization flag of a producer like `GNU C17 11.4.0 -mtune=generic -g -O2`, in which the
+/// last `-O` flag wins. `None` for `-O0` and `-Og`, which keep the variables debuggable, and
+/// for compilers like clang which don't record their flags
+fn optimization_flag(producer: &str) -> Option<&str> {
+    let flag = producer
+        .split_whitespace()
+        .rfind(|flag| flag.starts_with("-O"))?;
+    match flag {
+        "-O0" | "-Og" => None,
+        flag => Some(flag),
+    }
+}
+
+impl Debugger {
+    /// `DW_AT_producer` of every compilation unit which records it, without duplicates
+    pub fn producers(&self) -> Vec<String> {
+        let mut producers = vec![];
+        for unit in self.dwarf.compilation_units() {
+            let mut entries = unit.entries();
+            let Ok(Some((_, root))) = entries.next_dfs() else {
+                continue;
+            };
+            let Ok(Some(value)) = root.attr_value(gimli::DW_AT_producer) else {
+                continue;
+            };
+            let Some(producer) = self
+                .dwarf
+                .attr_string(unit, value)
+                .ok()
+                .and_then(|s| s.to_string_lossy().ok().map(|s| s.into_owned()))
+            else {
+                continue;
+            };
+            if !producers.contains(&producer) {
+                producers.push(producer);
+            }
+        }
+        producers
+    }
+}
+
+/// The optimization flag like `-O2` of the first producer which optimizes, optimized code keeps
+/// variables in registers or removes them and reorders or merges lines
+pub fn optimization(producers: &[String]) -> Option<String> {
+    producers
+        .iter()
+        .find_map(|producer| optimization_flag(producer))
+        .map(str::to_owned)
+}
@@ -0,0 +1,102 @@
+use std::{collections::HashMap, ffi::c_void, ops::Range};
+
+use object::{Object, ObjectSection, ObjectSymbol, ObjectSymbolTable, RelocationTarget};
+use stackium_shared::PltEntry;
+
+use super::{demangle::demangle, error::DebugError, Debugger};
+
+impl Debugger {
+    /// Stubs in `.plt` and `.plt.sec` by the name of the function they call, objdump labels
+    /// them like `401030 <puts@plt>:`
+    fn plt_stubs(&self) -> Result<HashMap<String, u64>, DebugError> {
+        let output = self.program_output(
+            std::process::Command::new("objdump")
+                .args(["--disassemble", "-j", ".plt", "-j", ".plt.sec"])
+                .arg(self.program.clone().into_os_string()),
+        )?;
+        let mut stubs = HashMap::new();
+        for line in std::str::from_utf8(&output)?.lines() {
+            let Some((address, label)) = line.split_once(' ') else {
+                continue;
+            };
+            let (Ok(address), Some(name)) = (
+                u64::from_str_radix(address, 16),
+                label
+                    .strip_prefix('<')
+                    .and_then(|l| l.strip_suffix(">:"))
+                    .and_then(|l| l.strip_suffix("@plt")),
+            ) else {
+                continue;
+            };
+            // with IBT the stub in `.plt.sec` is the one which is called
+            stubs.insert(name.to_owned(), address);
+        }
+        Ok(stubs)
+    }
+
+    /// The imported functions of the program with their stub in the procedure linkage table
+    /// and the slot of the global offset table it jumps through. With lazy binding the slot
+    /// points back into the stub until the dynamic linker patches it at the first call.
+    pub fn plt_entries(&self) -> Result<Vec<PltEntry>, DebugError> {
+        let bin = std::fs::read(&self.program)?;
+        let object_file = object::File::parse(&bin[..])
+            .map_err(|e| DebugError::InvalidArgument(e.to_string()))?;
+        let load_bias = self.load_bias(&object_file, &self.get_maps().unwrap_or_default());
+        let plt_sections = object_file
+            .sections()
+            .filter(|s| matches!(s.name(), Ok(".plt" | ".plt.sec")))
+            .map(|s| s.address() + load_bias..s.address() + s.size() + load_bias)
+            .collect::<Vec<Range<u64>>>();
+        let stubs = self.plt_stubs()?;
+        let (Some(relocations), Some(symbols)) = (
+            object_file.dynamic_relocations(),
+            object_file.dynamic_symbol_table(),
+        ) else {
+            // statically linked
+            return Ok(vec![]);
+        };
+        let mut entries = vec![];
+        for (slot, relocation) in relocations {
+            let RelocationTarget::Symbol(index) = relocation.target() else {
+                continue;
+            };
+            let Some(function) = symbols
+                .symbol_by_index(index)
+                .ok()
+                .and_then(|s| s.name().ok().map(|n| n.to_owned()))
+            else {
+                continue;
+            };
+            // slots of data and function pointers aren't jumped through by a stub
+            let Some(stub) = stubs.get(&function) else {
+                continue;
+            };
+            let got_address = slot + load_bias;
+            let target = self.read(got_address as *mut c_void)?;
+            let into_plt = plt_sections.iter().any(|s| s.contains(&target));
+            let target_description = match into_plt {
+                true => stubs
+                    .iter()
+                    .map(|(name, stub)| (name, stub + load_bias))
+                    .filter(|(_, stub)| *stub <= target)
+                    .max_by_key(|(_, stub)| *stub)
+                    .map(|(name, stub)| format!("{}@plt+{:#x}", demangle(name), target - stub))
+                    .unwrap_or(format!("{:#x}", target)),
+                false => self
+                    .resolve_address(target)
+                    .map(|resolved| resolved.description)
+                    .unwrap_or(format!("{:#x}", target)),
+            };
+            entries.push(PltEntry {
+                function: demangle(&function).into_owned(),
+                plt_address: stub + load_bias,
+                got_address,
+                target,
+                resolved: target != 0 && !into_plt,
+                target_description,
+            });
+        }
+        entries.sort_by_key(|e| e.plt_address);
+        Ok(entries)
+    }
+}
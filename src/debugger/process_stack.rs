@@ -0,0 +1,126 @@
+use std::os::raw::c_void;
+
+use stackium_shared::{AuxEntry, ProcessStack, ProcessString};
+
+use super::{error::DebugError, Debugger};
+use crate::variables::{escape_string, MAX_STRING_LENGTH};
+
+/// Reading a pointer array stops after this many entries if it isn't terminated
+const MAX_ENTRIES: usize = 4096;
+
+/// Keys of the auxiliary vector from `<elf.h>`, `AT_NULL` ends the vector
+const AUX_KEYS: &[(u64, &str)] = &[
+    (3, "AT_PHDR"),
+    (4, "AT_PHENT"),
+    (5, "AT_PHNUM"),
+    (6, "AT_PAGESZ"),
+    (7, "AT_BASE"),
+    (8, "AT_FLAGS"),
+    (9, "AT_ENTRY"),
+    (11, "AT_UID"),
+    (12, "AT_EUID"),
+    (13, "AT_GID"),
+    (14, "AT_EGID"),
+    (15, "AT_PLATFORM"),
+    (16, "AT_HWCAP"),
+    (17, "AT_CLKTCK"),
+    (23, "AT_SECURE"),
+    (24, "AT_BASE_PLATFORM"),
+    (25, "AT_RANDOM"),
+    (26, "AT_HWCAP2"),
+    (27, "AT_RSEQ_FEATURE_SIZE"),
+    (28, "AT_RSEQ_ALIGN"),
+    (31, "AT_EXECFN"),
+    (33, "AT_SYSINFO_EHDR"),
+    (51, "AT_MINSIGSTKSZ"),
+];
+const AT_NULL: u64 = 0;
+/// Keys whose value points to a string
+const AUX_STRING_KEYS: [u64; 3] = [15, 24, 31];
+
+impl Debugger {
+    /// Address of `argc`, the `startstack` field of `/proc/<pid>/stat`
+    fn start_stack(&self) -> Result<u64, DebugError> {
+        let stat = std::fs::read_to_string(format!("/proc/{}/stat", self.child))?;
+        // the command name in parentheses may contain spaces, the fields after it start at 3
+        stat.rsplit_once(')')
+            .and_then(|(_, fields)| fields.split_whitespace().nth(28 - 3)?.parse().ok())
+            .ok_or(DebugError::InvalidArgument(
+                "Failed parsing the start of the stack".to_owned(),
+            ))
+    }
+
+    fn read_word(&self, address: u64) -> Result<u64, DebugError> {
+        self.read(address as *mut c_void)
+    }
+
+    fn read_string(&self, pointer: u64) -> String {
+        match self.read_c_string(pointer, MAX_STRING_LENGTH) {
+            Some((bytes, terminated)) => escape_string(&bytes, terminated),
+            None => String::new(),
+        }
+    }
+
+    /// Reads a NULL terminated array of string pointers at `address`, returns the strings and
+    /// the address after the NULL
+    fn read_string_array(&self, mut address: u64) -> Result<(Vec<ProcessString>, u64), DebugError> {
+        let mut strings = vec![];
+        while strings.len() < MAX_ENTRIES {
+            let pointer = self.read_word(address)?;
+            address += 8;
+            if pointer == 0 {
+                break;
+            }
+            strings.push(ProcessString {
+                address: address - 8,
+                pointer,
+                value: self.read_string(pointer),
+            });
+        }
+        Ok((strings, address))
+    }
+
+    pub fn process_stack(&self) -> Result<ProcessStack, DebugError> {
+        let start = self.start_stack()?;
+        let end = self
+            .get_maps()?
+            .iter()
+            .find(|m| m.from <= start && start < m.to)
+            .map(|m| m.to)
+            .ok_or(DebugError::InvalidArgument(
+                "The start of the stack is not mapped".to_owned(),
+            ))?;
+        let argc = self.read_word(start)?;
+        let (argv, envp_start) = self.read_string_array(start + 8)?;
+        let (envp, mut address) = self.read_string_array(envp_start)?;
+        let mut auxv = vec![];
+        while auxv.len() < MAX_ENTRIES {
+            let key = self.read_word(address)?;
+            if key == AT_NULL {
+                break;
+            }
+            let value = self.read_word(address + 8)?;
+            auxv.push(AuxEntry {
+                address,
+                key,
+                name: AUX_KEYS
+                    .iter()
+                    .find(|(k, _)| *k == key)
+                    .map(|(_, name)| name.to_string()),
+                value,
+                string: AUX_STRING_KEYS
+                    .contains(&key)
+                    .then(|| self.read_string(value)),
+            });
+            address += 16;
+        }
+        Ok(ProcessStack {
+            start,
+            argc,
+            argv,
+            envp,
+            auxv,
+            end,
+        })
+    }
+}
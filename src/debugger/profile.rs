@@ -0,0 +1,91 @@
+use std::collections::HashMap;
+
+use stackium_shared::{FunctionSamples, LineHits, Profile};
+
+use super::{error::DebugError, util::get_line_from_pc, Debugger};
+
+/// Maximum number of instructions single-stepped by one profiling run
+const MAX_PROFILE_INSTRUCTIONS: u64 = 10_000_000;
+/// Name used for samples in code without debug information, e.g. libc
+const UNKNOWN_FUNCTION: &str = "<no debug info>";
+
+/// Samples aggregated while profiling
+#[derive(Default)]
+struct Samples {
+    samples: u64,
+    /// Function name -> (self samples, total samples)
+    functions: HashMap<String, (u64, u64)>,
+    lines: HashMap<(String, u64), u64>,
+}
+
+impl Samples {
+    fn into_profile(self, period: u64) -> Profile {
+        let mut functions = self
+            .functions
+            .into_iter()
+            .map(|(name, (self_samples, total_samples))| FunctionSamples {
+                name,
+                self_samples,
+                total_samples,
+            })
+            .collect::<Vec<_>>();
+        functions.sort_by_key(|f| std::cmp::Reverse(f.self_samples));
+        let mut lines = self
+            .lines
+            .into_iter()
+            .map(|((file, line), count)| LineHits { file, line, count })
+            .collect::<Vec<_>>();
+        lines.sort_by_key(|l| std::cmp::Reverse(l.count));
+        Profile {
+            samples: self.samples,
+            period,
+            functions,
+            lines,
+        }
+    }
+}
+
+impl Debugger {
+    fn take_sample(&self, pc: u64, samples: &mut Samples) {
+        samples.samples += 1;
+        let function = self.get_func_from_addr(pc).ok().and_then(|f| f.name);
+        let name = function.clone().unwrap_or(UNKNOWN_FUNCTION.to_owned());
+        samples.functions.entry(name.clone()).or_default().0 += 1;
+
+        let mut callers = vec![name];
+        // frames are only reliably unwound from code compiled with debug information
+        if function.is_some() {
+            if let Ok(backtrace) = self.backtrace() {
+                callers.extend(backtrace.into_iter().skip(1).filter_map(|f| f.function));
+            }
+        }
+        // recursive functions only count once per sample
+        callers.sort();
+        callers.dedup();
+        for caller in callers {
+            samples.functions.entry(caller).or_default().1 += 1;
+        }
+
+        if let Ok(location) = get_line_from_pc(&self.dwarf, pc) {
+            *samples
+                .lines
+                .entry((location.file, location.line))
+                .or_default() += 1;
+        }
+    }
+
+    /// Continues execution until the next breakpoint or the end of the program, sampling the
+    /// current function and line every `period` instructions
+    pub fn profile(&mut self, period: u64) -> Result<(), DebugError> {
+        let period = period.max(1);
+        let mut samples = Samples::default();
+        let result = self.step_until_breakpoint(MAX_PROFILE_INSTRUCTIONS, |debugger, step, pc| {
+            if step % period == 0 {
+                debugger.take_sample(pc, &mut samples);
+            }
+            true
+        });
+        self.profile = Some(samples.into_profile(period));
+        result
+    }
+}
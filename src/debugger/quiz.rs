@@ -0,0 +1,125 @@
+use stackium_shared::{Quiz, QuizAnswer, QuizKind, QuizQuestion, StopReason};
+
+use super::{
+    error::DebugError,
+    expression::{Expression, Value},
+    Debugger,
+};
+
+/// Questions asked at breakpoints and the answers given to them
+#[derive(Default)]
+pub struct Quizzes {
+    questions: Vec<QuizQuestion>,
+    pending: Vec<QuizQuestion>,
+    answers: Vec<QuizAnswer>,
+}
+
+impl Quizzes {
+    /// Drops the pending questions when the debugee is restarted, the answers are kept
+    pub fn reset(&mut self) {
+        self.pending.clear();
+    }
+}
+
+fn format_value(value: Value) -> String {
+    match value {
+        Value::Integer(value) => value.to_string(),
+        Value::Float(value) => value.to_string(),
+    }
+}
+
+impl Debugger {
+    pub fn add_quiz_question(&mut self, question: QuizQuestion) -> Result<(), DebugError> {
+        Expression::parse(&question.expression)?;
+        if !self
+            .breakpoints
+            .iter()
+            .any(|b| b.address == question.breakpoint)
+        {
+            return Err(DebugError::InvalidArgument(format!(
+                "No breakpoint at {:#x}",
+                question.breakpoint
+            )));
+        }
+        if !self.quiz.questions.contains(&question) {
+            self.quiz.questions.push(question);
+        }
+        Ok(())
+    }
+
+    pub fn remove_quiz_question(&mut self, question: &QuizQuestion) {
+        self.quiz.questions.retain(|q| q != question);
+        self.quiz.pending.retain(|q| q != question);
+    }
+
+    pub fn get_quiz(&self) -> Quiz {
+        Quiz {
+            questions: self.quiz.questions.clone(),
+            pending: self.quiz.pending.clone(),
+            answers: self.quiz.answers.clone(),
+        }
+    }
+
+    /// Asks the questions of the breakpoint the debugee stopped at
+    pub fn ask_quiz_questions(&mut self) {
+        self.quiz.pending.clear();
+        if self.has_exited() || self.stop_reason != StopReason::Breakpoint {
+            return;
+        }
+        let Ok(pc) = self.get_pc() else {
+            return;
+        };
+        self.quiz.pending = self
+            .quiz
+            .questions
+            .iter()
+            .filter(|q| q.breakpoint == pc)
+            .cloned()
+            .collect();
+    }
+
+    /// Compares the answer with the value of the pending question about `expression` and
+    /// records it. Answers which can't be evaluated, e.g. because of a typo, aren't recorded.
+    pub fn answer_quiz(
+        &mut self,
+        expression: &str,
+        answer: &str,
+    ) -> Result<QuizAnswer, DebugError> {
+        let index = self
+            .quiz
+            .pending
+            .iter()
+            .position(|q| q.expression == expression)
+            .ok_or(DebugError::InvalidArgument(format!(
+                "No pending question about {}",
+                expression
+            )))?;
+        let question = self.quiz.pending[index].clone();
+        let variables = self.read_variables()?;
+        let solution = self.evaluate(&Expression::parse(&question.expression)?, &variables)?;
+        let predicted = match question.kind {
+            QuizKind::Value => Expression::parse(answer)?,
+            QuizKind::Target => Expression::AddressOf(Box::new(Expression::parse(answer)?)),
+        };
+        let predicted = self.evaluate(&predicted, &variables)?;
+        let solution_text = match (question.kind, solution) {
+            (QuizKind::Target, Value::Integer(address)) => {
+                let address = format!("{:#x}", address);
+                match self.pointer_info(&address).ok().and_then(|i| i.element) {
+                    Some(element) => format!("{} ({})", element, address),
+                    None => address,
+                }
+            }
+            _ => format_value(solution),
+        };
+        let answer = QuizAnswer {
+            question,
+            answer: answer.to_owned(),
+            correct: predicted.equals(solution),
+            solution: solution_text,
+        };
+        self.quiz.pending.remove(index);
+        self.quiz.answers.push(answer.clone());
+        Ok(answer)
+    }
+}
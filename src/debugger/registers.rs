@@ -1,6 +1,129 @@
 use super::{error::DebugError, Debugger};
 use nix::{libc::user_regs_struct, sys::ptrace};
-use stackium_shared::Registers;
+use stackium_shared::{Flag, FullRegisters, Register, Registers};
+
+/// Bits of the flags register as (bit, name, description)
+#[cfg(target_arch = "x86_64")]
+const FLAGS: [(u8, &str, &str); 9] = [
+    (
+        0,
+        "CF",
+        "Carry: unsigned overflow of the last arithmetic operation",
+    ),
+    (
+        2,
+        "PF",
+        "Parity: lowest byte of the result has an even number of set bits",
+    ),
+    (4, "AF", "Adjust: carry out of the lowest nibble"),
+    (6, "ZF", "Zero: result of the last operation was zero"),
+    (7, "SF", "Sign: result of the last operation was negative"),
+    (8, "TF", "Trap: single stepping is enabled"),
+    (9, "IF", "Interrupt: interrupts are enabled"),
+    (
+        10,
+        "DF",
+        "Direction: string instructions decrement addresses",
+    ),
+    (
+        11,
+        "OF",
+        "Overflow: signed overflow of the last arithmetic operation",
+    ),
+];
+/// Bits of the flags register as (bit, name, description)
+#[cfg(target_arch = "aarch64")]
+const FLAGS: [(u8, &str, &str); 4] = [
+    (
+        31,
+        "N",
+        "Negative: result of the last operation was negative",
+    ),
+    (30, "Z", "Zero: result of the last operation was zero"),
+    (
+        29,
+        "C",
+        "Carry: unsigned overflow of the last arithmetic operation",
+    ),
+    (
+        28,
+        "V",
+        "Overflow: signed overflow of the last arithmetic operation",
+    ),
+];
+
+/// Decodes the flags register bit by bit
+pub fn decode_flags(flags: u64) -> Vec<Flag> {
+    FLAGS
+        .iter()
+        .map(|(bit, name, description)| Flag {
+            name: name.to_string(),
+            description: description.to_string(),
+            bit: *bit,
+            set: flags & (1 << bit) != 0,
+        })
+        .collect()
+}
+
+/// Mutable reference to the register with the specified name
+#[cfg(target_arch = "x86_64")]
+fn register_mut<'a>(regs: &'a mut user_regs_struct, name: &str) -> Option<&'a mut u64> {
+    Some(match name {
+        "rax" => &mut regs.rax,
+        "rbx" => &mut regs.rbx,
+        "rcx" => &mut regs.rcx,
+        "rdx" => &mut regs.rdx,
+        "rsi" => &mut regs.rsi,
+        "rdi" => &mut regs.rdi,
+        "rbp" => &mut regs.rbp,
+        "rsp" => &mut regs.rsp,
+        "r8" => &mut regs.r8,
+        "r9" => &mut regs.r9,
+        "r10" => &mut regs.r10,
+        "r11" => &mut regs.r11,
+        "r12" => &mut regs.r12,
+        "r13" => &mut regs.r13,
+        "r14" => &mut regs.r14,
+        "r15" => &mut regs.r15,
+        "rip" => &mut regs.rip,
+        "eflags" => &mut regs.eflags,
+        "cs" => &mut regs.cs,
+        "ss" => &mut regs.ss,
+        "ds" => &mut regs.ds,
+        "es" => &mut regs.es,
+        "fs" => &mut regs.fs,
+        "gs" => &mut regs.gs,
+        "fs_base" => &mut regs.fs_base,
+        "gs_base" => &mut regs.gs_base,
+        _ => return None,
+    })
+}
+/// Mutable reference to the register with the specified name
+#[cfg(target_arch = "aarch64")]
+fn register_mut<'a>(regs: &'a mut user_regs_struct, name: &str) -> Option<&'a mut u64> {
+    match name {
+        "sp" => Some(&mut regs.sp),
+        "pc" => Some(&mut regs.pc),
+        "pstate" => Some(&mut regs.pstate),
+        _ => {
+            let index = name.strip_prefix('x')?.parse::<usize>().ok()?;
+            regs.regs.get_mut(index)
+        }
+    }
+}
+
+/// Name of the register with the DWARF register number `reg`
+#[cfg(target_arch = "x86_64")]
+pub fn abi_register_name(reg: u16) -> String {
+    gimli::X86_64::register_name(gimli::Register(reg))
+        .map_or_else(|| format!("register {}", reg), str::to_owned)
+}
+/// Name of the register with the DWARF register number `reg`
+#[cfg(target_arch = "aarch64")]
+pub fn abi_register_name(reg: u16) -> String {
+    gimli::AArch64::register_name(gimli::Register(reg))
+        .map_or_else(|| format!("register {}", reg), str::to_owned)
+}
 
 impl Debugger {
     #[cfg(target_arch = "aarch64")]
@@ -32,24 +155,187 @@ impl Debugger {
             14 => Ok(registers.r14),
             15 => Ok(registers.r15),
             16 => Ok(registers.rip),
-            17 => Ok(registers.eflags),
-            18 => Ok(registers.cs),
-            19 => Ok(registers.ss),
-            20 => Ok(registers.ds),
-            21 => Ok(registers.es),
-            22 => Ok(registers.fs),
-            23 => Ok(registers.gs),
+            // 17 to 32 are the SSE registers, which aren't read
+            49 => Ok(registers.eflags),
+            50 => Ok(registers.es),
+            51 => Ok(registers.cs),
+            52 => Ok(registers.ss),
+            53 => Ok(registers.ds),
+            54 => Ok(registers.fs),
+            55 => Ok(registers.gs),
             _ => Err(DebugError::InvalidRegister),
         }
     }
 
+    /// All general purpose registers with their name
+    #[cfg(target_arch = "x86_64")]
+    pub fn get_named_registers(&self) -> Result<Vec<(String, u64)>, DebugError> {
+        let r = self.get_registers()?;
+        Ok([
+            ("rax", r.rax),
+            ("rbx", r.rbx),
+            ("rcx", r.rcx),
+            ("rdx", r.rdx),
+            ("rsi", r.rsi),
+            ("rdi", r.rdi),
+            ("rbp", r.rbp),
+            ("rsp", r.rsp),
+            ("r8", r.r8),
+            ("r9", r.r9),
+            ("r10", r.r10),
+            ("r11", r.r11),
+            ("r12", r.r12),
+            ("r13", r.r13),
+            ("r14", r.r14),
+            ("r15", r.r15),
+            ("rip", r.rip),
+        ]
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value))
+        .collect())
+    }
+    /// All general purpose registers with their name
+    #[cfg(target_arch = "aarch64")]
+    pub fn get_named_registers(&self) -> Result<Vec<(String, u64)>, DebugError> {
+        let r = self.get_registers()?;
+        let mut registers = r
+            .regs
+            .iter()
+            .enumerate()
+            .map(|(i, value)| (format!("x{}", i), *value))
+            .collect::<Vec<_>>();
+        registers.push(("sp".to_owned(), r.sp));
+        registers.push(("pc".to_owned(), r.pc));
+        Ok(registers)
+    }
+
+    /// General purpose registers, decoded flags and segment registers
+    #[cfg(target_arch = "x86_64")]
+    pub fn get_full_registers(&self) -> Result<FullRegisters, DebugError> {
+        let r = self.get_registers()?;
+        Ok(FullRegisters {
+            general: self
+                .get_named_registers()?
+                .into_iter()
+                .map(|(name, value)| Register { name, value })
+                .collect(),
+            flags_register: r.eflags,
+            flags: decode_flags(r.eflags),
+            segment: [
+                ("cs", r.cs),
+                ("ss", r.ss),
+                ("ds", r.ds),
+                ("es", r.es),
+                ("fs", r.fs),
+                ("gs", r.gs),
+                ("fs_base", r.fs_base),
+                ("gs_base", r.gs_base),
+            ]
+            .into_iter()
+            .map(|(name, value)| Register {
+                name: name.to_owned(),
+                value,
+            })
+            .collect(),
+        })
+    }
+    /// General purpose registers, decoded flags and segment registers
+    #[cfg(target_arch = "aarch64")]
+    pub fn get_full_registers(&self) -> Result<FullRegisters, DebugError> {
+        let r = self.get_registers()?;
+        Ok(FullRegisters {
+            general: self
+                .get_named_registers()?
+                .into_iter()
+                .map(|(name, value)| Register { name, value })
+                .collect(),
+            flags_register: r.pstate,
+            flags: decode_flags(r.pstate),
+            segment: vec![],
+        })
+    }
+
+    /// Sets the register with the specified name, e.g. `rax` or `x0`
+    pub fn get_register(&self, name: &str) -> Result<u64, DebugError> {
+        let mut regs = self.get_registers()?;
+        register_mut(&mut regs, name)
+            .copied()
+            .ok_or(DebugError::InvalidRegister)
+    }
+
+    pub fn set_register(&self, name: &str, value: u64) -> Result<(), DebugError> {
+        let mut regs = self.get_registers()?;
+        *register_mut(&mut regs, name).ok_or(DebugError::InvalidRegister)? = value;
+        self.set_registers(regs)
+    }
+
+    /// Lowest 16 bytes of the first vector register (`xmm0`), which holds floating point
+    /// return values
+    #[cfg(target_arch = "x86_64")]
+    pub fn get_vector_return_register(&self) -> Result<[u8; 16], DebugError> {
+        let mut fpregs = std::mem::MaybeUninit::<nix::libc::user_fpregs_struct>::uninit();
+        // nix doesn't wrap PTRACE_GETFPREGS
+        let result = unsafe {
+            nix::libc::ptrace(
+                nix::libc::PTRACE_GETFPREGS,
+                self.child.as_raw(),
+                std::ptr::null_mut::<nix::libc::c_void>(),
+                fpregs.as_mut_ptr(),
+            )
+        };
+        if result < 0 {
+            return Err(DebugError::NixError(nix::errno::Errno::last()));
+        }
+        let fpregs = unsafe { fpregs.assume_init() };
+        let mut bytes = [0u8; 16];
+        for (chunk, word) in bytes.chunks_mut(4).zip(&fpregs.xmm_space[..4]) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        Ok(bytes)
+    }
+    /// Lowest 16 bytes of the first vector register (`v0`), which holds floating point
+    /// return values
+    #[cfg(target_arch = "aarch64")]
+    pub fn get_vector_return_register(&self) -> Result<[u8; 16], DebugError> {
+        Err(DebugError::InvalidArgument(
+            "reading vector registers isn't supported on aarch64".to_owned(),
+        ))
+    }
+    /// The two registers used for integer return values (`rax`, `rdx` on x86_64 and `x0`, `x1`
+    /// on aarch64)
+    pub fn get_integer_return_registers(&self) -> Result<[u64; 2], DebugError> {
+        let regs = self.get_registers()?;
+        #[cfg(target_arch = "x86_64")]
+        return Ok([regs.rax, regs.rdx]);
+        #[cfg(target_arch = "aarch64")]
+        return Ok([regs.regs[0], regs.regs[1]]);
+    }
+
+    /// The registers holding the first six integer arguments of a function call
+    pub fn get_argument_registers(&self) -> Result<[u64; 6], DebugError> {
+        let regs = self.get_registers()?;
+        #[cfg(target_arch = "x86_64")]
+        return Ok([regs.rdi, regs.rsi, regs.rdx, regs.rcx, regs.r8, regs.r9]);
+        #[cfg(target_arch = "aarch64")]
+        return Ok([
+            regs.regs[0],
+            regs.regs[1],
+            regs.regs[2],
+            regs.regs[3],
+            regs.regs[4],
+            regs.regs[5],
+        ]);
+    }
+
     pub fn get_registers(&self) -> Result<user_regs_struct, DebugError> {
+        let _span = tracing::trace_span!("ptrace_getregs").entered();
         match ptrace::getregs(self.child) {
             Ok(r) => Ok(r),
             Err(e) => Err(DebugError::NixError(e)),
         }
     }
     pub fn set_registers(&self, reg: user_regs_struct) -> Result<(), DebugError> {
+        let _span = tracing::trace_span!("ptrace_setregs").entered();
         match ptrace::setregs(self.child, reg) {
             Ok(_) => Ok(()),
             Err(e) => Err(DebugError::NixError(e)),
@@ -0,0 +1,305 @@
+use std::fmt::Write;
+
+use stackium_shared::{MallocChunk, MemoryChunk, ReportFormat};
+
+use super::{
+    error::DebugError,
+    heap::{malloc_chunks, CHUNK_HEADER_SIZE},
+    Debugger,
+};
+
+/// Lines of source code shown before and after the current line
+const SOURCE_CONTEXT: u64 = 5;
+/// Bytes of a heap chunk shown in the report
+const CHUNK_PREVIEW: usize = 16;
+
+/// The malloc chunks in the memory of a heap region with their first bytes
+fn heap_chunks(heap: &MemoryChunk) -> Vec<(MallocChunk, &[u8])> {
+    let read = |addr: u64| -> Option<u64> {
+        let offset = addr.checked_sub(heap.from)? as usize;
+        heap.memory
+            .get(offset..offset + 8)
+            .map(|b| u64::from_le_bytes(b.try_into().unwrap()))
+    };
+    let to = heap.from + heap.memory.len() as u64;
+    malloc_chunks(heap.from, to, read)
+        .into_iter()
+        .map(|c| {
+            let start = (c.address - heap.from) as usize;
+            let end = (c.address - CHUNK_HEADER_SIZE + c.size - heap.from) as usize;
+            let preview = &heap.memory[start..end.min(start + CHUNK_PREVIEW)];
+            (c, preview)
+        })
+        .collect()
+}
+
+fn hex_bytes(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn printable(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|b| match b.is_ascii_graphic() || *b == b' ' {
+            true => *b as char,
+            false => '.',
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Writes the sections of the report in markdown or HTML
+struct Report {
+    format: ReportFormat,
+    out: String,
+}
+
+impl Report {
+    fn new(format: ReportFormat, title: &str) -> Self {
+        let mut report = Self {
+            format,
+            out: String::new(),
+        };
+        match format {
+            ReportFormat::Html => {
+                let _ = write!(
+                    report.out,
+                    "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n\
+                     <style>\nbody {{ font-family: sans-serif; margin: 2em; }}\n\
+                     pre, td {{ font-family: monospace; }}\n\
+                     pre {{ background: #f4f4f4; padding: 0.5em; }}\n\
+                     table {{ border-collapse: collapse; }}\n\
+                     th, td {{ border: 1px solid #ccc; padding: 0.2em 0.5em; text-align: left; }}\n\
+                     </style>\n</head>\n<body>\n<h1>{}</h1>\n",
+                    escape_html(title),
+                    escape_html(title)
+                );
+            }
+            ReportFormat::Markdown => {
+                let _ = write!(report.out, "# {}\n\n", title);
+            }
+        }
+        report
+    }
+
+    fn heading(&mut self, text: &str) {
+        let _ = match self.format {
+            ReportFormat::Html => writeln!(self.out, "<h2>{}</h2>", escape_html(text)),
+            ReportFormat::Markdown => write!(self.out, "## {}\n\n", text),
+        };
+    }
+
+    fn paragraph(&mut self, text: &str) {
+        let _ = match self.format {
+            ReportFormat::Html => writeln!(self.out, "<p>{}</p>", escape_html(text)),
+            ReportFormat::Markdown => write!(self.out, "{}\n\n", text),
+        };
+    }
+
+    fn code(&mut self, lines: &[String]) {
+        let text = lines.join("\n");
+        let _ = match self.format {
+            ReportFormat::Html => writeln!(self.out, "<pre>{}</pre>", escape_html(&text)),
+            ReportFormat::Markdown => write!(self.out, "```\n{}\n```\n\n", text),
+        };
+    }
+
+    fn table(&mut self, header: &[&str], rows: &[Vec<String>]) {
+        match self.format {
+            ReportFormat::Html => {
+                let row = |cells: Vec<String>, tag: &str| {
+                    cells
+                        .iter()
+                        .map(|c| format!("<{}>{}</{}>", tag, escape_html(c), tag))
+                        .collect::<String>()
+                };
+                let _ = writeln!(
+                    self.out,
+                    "<table>\n<tr>{}</tr>",
+                    row(header.iter().map(|h| h.to_string()).collect(), "th")
+                );
+                for cells in rows {
+                    let _ = writeln!(self.out, "<tr>{}</tr>", row(cells.clone(), "td"));
+                }
+                self.out.push_str("</table>\n");
+            }
+            ReportFormat::Markdown => {
+                let row = |cells: &[String]| {
+                    cells
+                        .iter()
+                        .map(|c| format!(" {} |", c.replace('|', "\\|").replace('\n', " ")))
+                        .collect::<String>()
+                };
+                let header = header.iter().map(|h| h.to_string()).collect::<Vec<_>>();
+                let _ = writeln!(self.out, "|{}", row(&header));
+                let _ = writeln!(self.out, "|{}", " --- |".repeat(header.len()));
+                for cells in rows {
+                    let _ = writeln!(self.out, "|{}", row(cells));
+                }
+                self.out.push('\n');
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        if let ReportFormat::Html = self.format {
+            self.out.push_str("</body>\n</html>\n");
+        }
+        self.out
+    }
+}
+
+impl Debugger {
+    /// A self-contained report of the current stop with the source code around it, the
+    /// backtrace, the variables, the memory of the current frame and the heap chunks
+    pub fn generate_report(&self, format: ReportFormat) -> Result<String, DebugError> {
+        let state = self.stop_state();
+        let mut report = Report::new(
+            format,
+            &format!("Report of {}", self.program.to_string_lossy()),
+        );
+        report.heading("Stop");
+        report.paragraph(&format!("Stopped because of {:?}", state.reason));
+        if self.has_exited() {
+            return Ok(report.finish());
+        }
+        let snapshot = self.snapshot()?;
+        report.paragraph(&format!(
+            "Program counter {:#x}, stack pointer {:#x}",
+            snapshot.registers.instruction_pointer, snapshot.registers.stack_pointer
+        ));
+
+        if let Some(location) = &state.location {
+            report.heading("Source");
+            report.paragraph(&format!("{}:{}", location.file, location.line));
+            let source = self
+                .files
+                .resolve(&location.file)
+                .ok()
+                .and_then(|path| self.read_source(&path).ok());
+            match source {
+                Some(source) => {
+                    let first = location.line.saturating_sub(SOURCE_CONTEXT).max(1);
+                    let lines = source
+                        .lines()
+                        .enumerate()
+                        .map(|(i, line)| (i as u64 + 1, line))
+                        .skip_while(|(number, _)| *number < first)
+                        .take_while(|(number, _)| *number <= location.line + SOURCE_CONTEXT)
+                        .map(|(number, line)| {
+                            let marker = match number == location.line {
+                                true => ">",
+                                false => " ",
+                            };
+                            format!("{} {:4} {}", marker, number, line)
+                        })
+                        .collect::<Vec<_>>();
+                    report.code(&lines);
+                }
+                None => report.paragraph("The source file could not be read"),
+            }
+        }
+
+        report.heading("Backtrace");
+        let backtrace = self
+            .backtrace()?
+            .into_iter()
+            .enumerate()
+            .map(|(i, frame)| {
+                vec![
+                    i.to_string(),
+                    frame.function.unwrap_or("??".to_owned()),
+                    frame
+                        .location
+                        .map(|l| format!("{}:{}", l.file, l.line))
+                        .unwrap_or_default(),
+                    format!("{:#x}", frame.pc),
+                ]
+            })
+            .collect::<Vec<_>>();
+        report.table(&["#", "Function", "Location", "PC"], &backtrace);
+
+        report.heading("Variables");
+        match self.has_debug_info {
+            true => {
+                let variables = self
+                    .exported_variables()?
+                    .into_iter()
+                    .map(|v| {
+                        vec![
+                            v.name,
+                            v.type_name,
+                            format!("{:#x}", v.address),
+                            v.size.to_string(),
+                            v.value,
+                        ]
+                    })
+                    .collect::<Vec<_>>();
+                report.table(&["Name", "Type", "Address", "Size", "Value"], &variables);
+            }
+            false => report.paragraph("The program has no debug information"),
+        }
+
+        report.heading("Current frame");
+        let frame_end = self
+            .stack_frames()?
+            .first()
+            .map_or(snapshot.stack.from, |f| f.to);
+        let frame = &snapshot.stack.memory[..((frame_end.saturating_sub(snapshot.stack.from))
+            as usize)
+            .min(snapshot.stack.memory.len())];
+        report.paragraph(&format!(
+            "{:#x} to {:#x}, {} bytes",
+            snapshot.stack.from,
+            snapshot.stack.from + frame.len() as u64,
+            frame.len()
+        ));
+        let dump = frame
+            .chunks(16)
+            .enumerate()
+            .map(|(i, row)| {
+                format!(
+                    "{:#014x}  {:<47}  {}",
+                    snapshot.stack.from + i as u64 * 16,
+                    hex_bytes(row),
+                    printable(row)
+                )
+            })
+            .collect::<Vec<_>>();
+        report.code(&dump);
+
+        report.heading("Heap");
+        let chunks = snapshot
+            .heap
+            .iter()
+            .flat_map(heap_chunks)
+            .map(|(c, preview)| {
+                vec![
+                    format!("{:#x}", c.address),
+                    c.size.to_string(),
+                    match c.in_use {
+                        Some(true) => "in use".to_owned(),
+                        Some(false) => "free".to_owned(),
+                        None => "top".to_owned(),
+                    },
+                    format!("{}  {}", hex_bytes(preview), printable(preview)),
+                ]
+            })
+            .collect::<Vec<_>>();
+        match chunks.is_empty() {
+            true => report.paragraph("No heap chunks"),
+            false => report.table(&["Address", "Size", "State", "Contents"], &chunks),
+        }
+        Ok(report.finish())
+    }
+}
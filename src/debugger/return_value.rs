@@ -0,0 +1,131 @@
+use stackium_shared::{format_value, get_byte_size, DataType, Registers, ReturnValue, TypeName};
+
+use super::{error::DebugError, registers::FromUserRegsStruct, util::get_function_meta, Debugger};
+use crate::variables::get_type_name;
+
+/// Values larger than this are returned in memory, the return register holds their address
+const MAX_REGISTER_RETURN_SIZE: usize = 16;
+
+impl Debugger {
+    /// Return type of the function containing `pc`, `None` for `void` functions
+    fn return_type(&self, pc: u64) -> Result<Option<DataType>, DebugError> {
+        for unit in self.dwarf.units_containing(pc) {
+            let mut cursor = unit.entries();
+            while let Some((_, entry)) = cursor.next_dfs()? {
+                if entry.tag() != gimli::DW_TAG_subprogram {
+                    continue;
+                }
+                let meta = get_function_meta(entry, unit, &self.dwarf)?;
+                let (Some(low_pc), Some(high_pc)) = (meta.low_pc, meta.high_pc) else {
+                    continue;
+                };
+                if pc >= low_pc && pc <= low_pc + high_pc {
+                    return match entry.attr_value(gimli::DW_AT_type)? {
+                        Some(type_attribute) => {
+                            Ok(Some(self.decode_type(type_attribute, DataType(vec![]))?))
+                        }
+                        None => Ok(None),
+                    };
+                }
+            }
+        }
+        Err(DebugError::FunctionNotFound)
+    }
+
+    /// Bytes of a value of `return_type` that was just returned, following the System V
+    /// calling convention. Structs of up to 16 bytes are assumed to be returned in the integer
+    /// registers.
+    fn read_return_value(&self, return_type: &DataType) -> Result<Vec<u8>, DebugError> {
+        let size = get_byte_size(return_type, 0);
+        let [first, second] = self.get_integer_return_registers()?;
+        match &return_type.0[0].1 {
+            TypeName::Name { name, .. } if name == "float" || name == "double" => {
+                Ok(self.get_vector_return_register()?.to_vec())
+            }
+            _ if size > MAX_REGISTER_RETURN_SIZE => self.read_memory(first, size as u64),
+            _ => Ok(first
+                .to_le_bytes()
+                .into_iter()
+                .chain(second.to_le_bytes())
+                .take(size)
+                .collect()),
+        }
+    }
+
+    /// Steps out of the current function and reports the value it returned
+    pub fn step_out_with_return_value(&mut self) -> Result<ReturnValue, DebugError> {
+        let pc = self.get_pc()?;
+        let function = self.get_func_from_addr(pc).ok().and_then(|f| f.name);
+        let return_type = self.return_type(pc).ok().flatten();
+        self.step_out()?;
+        self.returned_value(function, return_type)
+    }
+
+    /// Continues until `count` frames returned to their callers and reports the value each of
+    /// them returned, fewer if a breakpoint is hit or the program ends before
+    pub fn finish_frames(&mut self, count: u64) -> Result<Vec<ReturnValue>, DebugError> {
+        let frames = self.backtrace()?;
+        let finishable = frames
+            .iter()
+            .take_while(|frame| frame.return_address.is_some())
+            .count();
+        if count == 0 || count as usize > finishable {
+            return Err(DebugError::InvalidArgument(format!(
+                "{} frames can be finished, not {}",
+                finishable, count
+            )));
+        }
+        let mut returned = vec![];
+        for (i, frame) in frames.iter().take(count as usize).enumerate() {
+            let Some(return_address) = frame.return_address else {
+                break;
+            };
+            // the pc of the callers is their return address, which may be in the next line
+            let address = match i {
+                0 => frame.pc,
+                _ => frame.pc - 1,
+            };
+            let return_type = self.return_type(address).ok().flatten();
+            loop {
+                self.run_to(return_address)?;
+                if self.has_exited() || self.get_pc()? != return_address {
+                    return Ok(returned);
+                }
+                // the stack pointer is the canonical frame address once the frame returned,
+                // below it a recursive call returned to the same address
+                let registers = Registers::from_regs(self.get_registers()?);
+                if registers.stack_pointer >= frame.cfa {
+                    break;
+                }
+                self.step_instruction()?;
+                if self.has_exited() {
+                    return Ok(returned);
+                }
+            }
+            returned.push(self.returned_value(frame.function.clone(), return_type)?);
+        }
+        Ok(returned)
+    }
+
+    /// The value a function of `return_type` just returned
+    fn returned_value(
+        &self,
+        function: Option<String>,
+        return_type: Option<DataType>,
+    ) -> Result<ReturnValue, DebugError> {
+        let raw = self.get_integer_return_registers()?[0];
+        let value = match &return_type {
+            Some(return_type) => self
+                .read_return_value(return_type)
+                .ok()
+                .and_then(|memory| format_value(return_type, 0, &memory)),
+            None => None,
+        };
+        Ok(ReturnValue {
+            function,
+            type_name: return_type.as_ref().map(|t| get_type_name(t, 0)),
+            value,
+            raw,
+        })
+    }
+}
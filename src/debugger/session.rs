@@ -0,0 +1,56 @@
+use std::collections::VecDeque;
+
+use stackium_shared::{Command, CommandOutput, SessionEntry, StopEvent};
+
+use super::{error::DebugError, util::get_line_containing_addr, Debugger};
+
+/// The oldest commands are dropped once the session has this many
+const MAX_SESSION_ENTRIES: usize = 20000;
+
+/// Every command sent to the debugger with its output, exported with `ExportSession`
+#[derive(Default)]
+pub struct SessionLog {
+    entries: VecDeque<SessionEntry>,
+    /// Set by `record_stop` while a command is executed
+    stopped: bool,
+}
+
+impl Debugger {
+    /// Marks the command currently executed as one which resumed the debugee
+    pub fn record_session_stop(&mut self) {
+        self.session.stopped = true;
+    }
+
+    /// Adds the executed command to the session, exports aren't recorded
+    pub fn record_command(&mut self, command: Command, output: &Result<CommandOutput, DebugError>) {
+        let stopped = std::mem::take(&mut self.session.stopped);
+        if matches!(
+            command,
+            Command::ExportSession | Command::ExportSnapshot(_) | Command::GenerateReport(_)
+        ) {
+            return;
+        }
+        let stop = match (stopped, self.get_pc()) {
+            (true, Ok(pc)) => Some(StopEvent {
+                pc,
+                location: get_line_containing_addr(&self.dwarf, pc).ok(),
+            }),
+            _ => None,
+        };
+        if self.session.entries.len() >= MAX_SESSION_ENTRIES {
+            self.session.entries.pop_front();
+        }
+        self.session.entries.push_back(SessionEntry {
+            command,
+            output: match output {
+                Ok(output) => Ok(output.clone()),
+                Err(err) => Err(format!("{:?}", err)),
+            },
+            stop,
+        });
+    }
+
+    pub fn export_session(&self) -> String {
+        serde_json::to_string(&self.session.entries).unwrap()
+    }
+}
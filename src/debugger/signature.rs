@@ -0,0 +1,130 @@
+use gimli::Reader;
+use stackium_shared::FunctionParameter;
+
+use super::error::DebugError;
+
+/// Types nested deeper than this, e.g. in self referential function pointers, are shown as `?`
+const MAX_TYPE_DEPTH: usize = 8;
+
+type Entry<'a, 'u, T> = gimli::DebuggingInformationEntry<'a, 'u, T, <T as Reader>::Offset>;
+
+fn name<T: Reader>(
+    entry: &Entry<T>,
+    unit: &gimli::Unit<T>,
+    dwarf: &gimli::Dwarf<T>,
+) -> Result<Option<String>, DebugError> {
+    Ok(match entry.attr_value(gimli::DW_AT_name)? {
+        Some(value) => Some(
+            dwarf
+                .attr_string(unit, value)?
+                .to_string_lossy()?
+                .into_owned(),
+        ),
+        None => None,
+    })
+}
+
+/// The entry a parameter or function of an inlined call or a method defined outside of its
+/// class takes its name and type from
+fn origin<'u, T: Reader>(
+    entry: &Entry<'_, 'u, T>,
+    unit: &'u gimli::Unit<T>,
+) -> Result<Option<Entry<'u, 'u, T>>, DebugError> {
+    for attribute in [gimli::DW_AT_abstract_origin, gimli::DW_AT_specification] {
+        if let Some(gimli::AttributeValue::UnitRef(offset)) = entry.attr_value(attribute)? {
+            return Ok(Some(unit.entry(offset)?));
+        }
+    }
+    Ok(None)
+}
+
+/// C declaration of the type `DW_AT_type` of the entry refers to like `const char *`, `None` if
+/// it has none, i.e. `void`
+fn type_name<T: Reader>(
+    entry: &Entry<T>,
+    unit: &gimli::Unit<T>,
+    dwarf: &gimli::Dwarf<T>,
+    depth: usize,
+) -> Result<Option<String>, DebugError> {
+    let Some(value) = entry.attr_value(gimli::DW_AT_type)? else {
+        return Ok(None);
+    };
+    let gimli::AttributeValue::UnitRef(offset) = value else {
+        // types in other units aren't resolved
+        return Ok(Some("?".to_owned()));
+    };
+    if depth > MAX_TYPE_DEPTH {
+        return Ok(Some("?".to_owned()));
+    }
+    let target = unit.entry(offset)?;
+    let inner = || -> Result<String, DebugError> {
+        Ok(type_name(&target, unit, dwarf, depth + 1)?.unwrap_or("void".to_owned()))
+    };
+    let named = |prefix: &str| -> Result<String, DebugError> {
+        let name = name(&target, unit, dwarf)?.unwrap_or("<anonymous>".to_owned());
+        Ok(format!("{}{}", prefix, name))
+    };
+    Ok(Some(match target.tag() {
+        gimli::DW_TAG_pointer_type => format!("{} *", inner()?),
+        gimli::DW_TAG_reference_type => format!("{} &", inner()?),
+        gimli::DW_TAG_rvalue_reference_type => format!("{} &&", inner()?),
+        gimli::DW_TAG_const_type => format!("const {}", inner()?),
+        gimli::DW_TAG_volatile_type => format!("volatile {}", inner()?),
+        gimli::DW_TAG_array_type => format!("{} []", inner()?),
+        gimli::DW_TAG_structure_type => named("struct ")?,
+        gimli::DW_TAG_union_type => named("union ")?,
+        gimli::DW_TAG_enumeration_type => named("enum ")?,
+        gimli::DW_TAG_subroutine_type => format!("{} (*)()", inner()?),
+        _ => named("")?,
+    }))
+}
+
+/// Parameters of a function entry from its `DW_TAG_formal_parameter` children, without the
+/// `this` pointer the compiler adds to methods
+pub fn parameters<T: Reader>(
+    entry: &Entry<T>,
+    unit: &gimli::Unit<T>,
+    dwarf: &gimli::Dwarf<T>,
+) -> Result<Vec<FunctionParameter>, DebugError> {
+    let mut parameters = vec![];
+    let mut tree = unit.entries_tree(Some(entry.offset()))?;
+    let mut children = tree.root()?.children();
+    while let Some(child) = children.next()? {
+        let child = child.entry();
+        match child.tag() {
+            gimli::DW_TAG_formal_parameter => {
+                if let Some(gimli::AttributeValue::Flag(true)) =
+                    child.attr_value(gimli::DW_AT_artificial)?
+                {
+                    continue;
+                }
+                let origin = origin(child, unit)?;
+                let declaration = origin.as_ref().unwrap_or(child);
+                parameters.push(FunctionParameter {
+                    name: name(declaration, unit, dwarf)?,
+                    type_name: type_name(declaration, unit, dwarf, 0)?.unwrap_or("void".to_owned()),
+                });
+            }
+            gimli::DW_TAG_unspecified_parameters => parameters.push(FunctionParameter {
+                name: None,
+                type_name: "...".to_owned(),
+            }),
+            _ => {}
+        }
+    }
+    Ok(parameters)
+}
+
+/// C declaration of the return type of a function entry, `None` for `void`
+pub fn return_type<T: Reader>(
+    entry: &Entry<T>,
+    unit: &gimli::Unit<T>,
+    dwarf: &gimli::Dwarf<T>,
+) -> Result<Option<String>, DebugError> {
+    match origin(entry, unit)? {
+        Some(origin) if entry.attr_value(gimli::DW_AT_type)?.is_none() => {
+            return_type(&origin, unit, dwarf)
+        }
+        _ => type_name(entry, unit, dwarf, 0),
+    }
+}
@@ -0,0 +1,68 @@
+use std::{io::Write, os::unix::fs::FileExt};
+
+use stackium_shared::{MemoryChunk, MemoryMap, RegionKind, Registers, Snapshot, SnapshotFormat};
+
+use super::{
+    error::DebugError, registers::FromUserRegsStruct, util::get_line_containing_addr, Debugger,
+};
+
+/// At most this many bytes of every region are included in a snapshot
+const MAX_SNAPSHOT_REGION_SIZE: u64 = 1 << 20;
+
+impl Debugger {
+    /// Reads the region from `from` to its end at once through `/proc/<pid>/mem`, reading byte
+    /// by byte like `read_memory` is too slow for whole regions
+    fn read_region(&self, map: &MemoryMap, from: u64) -> Result<MemoryChunk, DebugError> {
+        let to = map.to.min(from + MAX_SNAPSHOT_REGION_SIZE);
+        let mem = std::fs::File::open(format!("/proc/{}/mem", self.child))?;
+        let mut memory = vec![0u8; (to - from) as usize];
+        mem.read_exact_at(&mut memory, from)?;
+        Ok(MemoryChunk {
+            from,
+            memory,
+            region: map.label.clone(),
+        })
+    }
+
+    pub fn snapshot(&self) -> Result<Snapshot, DebugError> {
+        let registers = Registers::from_regs(self.get_registers()?);
+        let maps = self.get_maps()?;
+        let stack = maps
+            .iter()
+            .find(|m| m.from <= registers.stack_pointer && registers.stack_pointer < m.to)
+            .ok_or(DebugError::InvalidArgument(
+                "Stack pointer is not in a mapped region".to_owned(),
+            ))?;
+        Ok(Snapshot {
+            program: self.program.to_string_lossy().into_owned(),
+            location: get_line_containing_addr(&self.dwarf, registers.instruction_pointer).ok(),
+            full_registers: self.get_full_registers()?,
+            stack: self.read_region(stack, registers.stack_pointer)?,
+            variables: match self.has_debug_info {
+                true => self.discover_variables()?,
+                false => vec![],
+            },
+            heap: maps
+                .iter()
+                .filter(|m| m.kind == RegionKind::Heap && m.read)
+                .map(|m| self.read_region(m, m.from))
+                .collect::<Result<_, _>>()?,
+            registers,
+            maps,
+        })
+    }
+
+    /// The snapshot serialized in the specified format
+    pub fn export_snapshot(&self, format: SnapshotFormat) -> Result<Vec<u8>, DebugError> {
+        let json = serde_json::to_vec(&self.snapshot()?).unwrap();
+        match format {
+            SnapshotFormat::Json => Ok(json),
+            SnapshotFormat::Binary => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(vec![], flate2::Compression::default());
+                encoder.write_all(&json)?;
+                Ok(encoder.finish()?)
+            }
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use std::path::{Path, PathBuf};
+
+use gimli::Reader;
+use stackium_shared::{SourceFile, SourceMapping, SourceOrigin};
+use tracing::warn;
+
+use super::{
+    error::DebugError,
+    files::{get_file_path, normalize_path},
+    Debugger,
+};
+
+/// Substitutions of path prefixes applied to the source files before reading them, see
+/// `SetSourceMap`
+#[derive(Default, Clone)]
+pub struct SourceMap {
+    mappings: Vec<SourceMapping>,
+}
+
+impl SourceMap {
+    pub fn new(mappings: Vec<SourceMapping>) -> Self {
+        Self { mappings }
+    }
+
+    /// `path` with the prefix of the first matching mapping replaced, `None` if none matches.
+    /// Only whole components match, `/build/src` doesn't match `/build/src2/main.c`.
+    fn map(&self, path: &Path) -> Option<PathBuf> {
+        self.mappings.iter().find_map(|mapping| {
+            let rest = path
+                .strip_prefix(normalize_path(Path::new(&mapping.from)))
+                .ok()?;
+            Some(normalize_path(&Path::new(&mapping.to).join(rest)))
+        })
+    }
+}
+
+impl Debugger {
+    pub fn set_source_map(&mut self, mappings: Vec<SourceMapping>) {
+        self.source_map = SourceMap::new(mappings);
+    }
+
+    /// A source file of the debug information read from the file the source map maps it to,
+    /// the file itself or the source embedded in the debug information (`clang -gdwarf-5
+    /// -gembed-source`), in this order. Mapped files are only read if clients may read them
+    /// (`may_read`), a mapping onto e.g. `/etc/passwd` is ignored.
+    pub fn source_file(&self, path: &Path) -> SourceFile {
+        let path = normalize_path(path);
+        let (embedded, md5) = self.file_entry(&path).unwrap_or_default();
+        let mapped = self.source_map.map(&path).filter(|mapped| mapped.exists());
+        let mapped = mapped.filter(|mapped| {
+            let readable = self.may_read(mapped);
+            if !readable {
+                warn!(
+                    "Not reading {} mapped from {}, it is neither a source of the program nor allowed with --allow-source",
+                    mapped.display(),
+                    path.display()
+                );
+            }
+            readable
+        });
+        let (content, origin) = match mapped {
+            Some(mapped) => (
+                std::fs::read_to_string(&mapped).ok(),
+                SourceOrigin::Mapped(mapped.display().to_string()),
+            ),
+            None => match (std::fs::read_to_string(&path), embedded) {
+                (Ok(source), _) => (Some(source), SourceOrigin::Local),
+                (Err(_), Some(source)) => (Some(source), SourceOrigin::Embedded),
+                (Err(_), None) => (None, SourceOrigin::Missing),
+            },
+        };
+        SourceFile {
+            path: path.display().to_string(),
+            content,
+            origin,
+            md5,
+        }
+    }
+
+    /// Text of a source file of the debug information, see `source_file`
+    pub fn read_source(&self, path: &Path) -> Result<String, DebugError> {
+        let file = self.source_file(path);
+        file.content.ok_or_else(|| {
+            DebugError::IoError(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!("{} doesn't exist", file.path),
+            ))
+        })
+    }
+
+    /// The source embedded in the file entry of `path` in the line tables
+    /// (`DW_LNCT_LLVM_source`) and its MD5 checksum as hex (`DW_LNCT_MD5`)
+    fn file_entry(&self, path: &Path) -> Option<(Option<String>, Option<String>)> {
+        for unit in self.dwarf.compilation_units() {
+            let Some(program) = &unit.line_program else {
+                continue;
+            };
+            let header = program.header();
+            for file in header.file_names() {
+                if get_file_path(&self.dwarf, unit, header, file).as_deref() != Some(path) {
+                    continue;
+                }
+                let source = file
+                    .source()
+                    .and_then(|source| self.dwarf.attr_string(unit, source).ok())
+                    .and_then(|source| source.to_string_lossy().ok().map(|s| s.into_owned()))
+                    // an empty source means it wasn't embedded
+                    .filter(|source| !source.is_empty());
+                let md5 = header.file_has_md5().then(|| {
+                    file.md5()
+                        .iter()
+                        .map(|byte| format!("{:02x}", byte))
+                        .collect()
+                });
+                return Some((source, md5));
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,207 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use nix::sys::{
+    ptrace,
+    signal::{kill, Signal},
+    wait::WaitPidFlag,
+};
+use stackium_shared::{DebugeeState, DebugeeStatus, ExitSummary, StopReason};
+use tracing::warn;
+
+use super::{error::DebugError, Debugger};
+
+/// How long `Continue` waits for the child to stop before returning while it keeps running
+const CONTINUE_TIMEOUT: Duration = Duration::from_millis(200);
+const POLL_INTERVAL: Duration = Duration::from_millis(5);
+/// Default of `SetContinueTimeout`
+pub const DEFAULT_CONTINUE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Statistics of the current run of the child
+#[derive(Default)]
+pub struct RunCounters {
+    pub resumes: u64,
+    pub instructions: u64,
+    pub breakpoints_hit: u64,
+}
+
+fn unix_millis(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+impl Debugger {
+    /// Transitions the child to `state`, called whenever it is continued or waited for. Every
+    /// stop restarts `since`, even if the child was already stopped before single-stepping, and
+    /// forgets the memory read at the previous stop.
+    pub fn set_state(&mut self, state: DebugeeState) {
+        self.memory.clear();
+        self.state = state;
+        self.state_since = SystemTime::now();
+    }
+
+    /// Counts a command which resumed the child, called by `record_stop`
+    pub fn count_stop(&mut self) {
+        self.counters.resumes += 1;
+        let Ok(pc) = self.get_pc() else {
+            return;
+        };
+        if self
+            .breakpoints
+            .iter()
+            .any(|b| b.enabled && b.address == pc)
+        {
+            self.counters.breakpoints_hit += 1;
+        }
+    }
+
+    fn exit_summary(&self) -> Option<ExitSummary> {
+        if !self.has_exited() {
+            return None;
+        }
+        Some(ExitSummary {
+            state: self.state.clone(),
+            duration: unix_millis(self.state_since).saturating_sub(unix_millis(self.started)),
+            resumes: self.counters.resumes,
+            instructions: self.counters.instructions,
+            breakpoints_hit: self.counters.breakpoints_hit,
+        })
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.state == DebugeeState::Running
+    }
+
+    /// Whether the program can't be resumed anymore
+    pub fn has_exited(&self) -> bool {
+        matches!(
+            self.state,
+            DebugeeState::Exited(_) | DebugeeState::Signaled(_)
+        )
+    }
+
+    /// Continues the child without waiting for it to stop, unless it stops within
+    /// `CONTINUE_TIMEOUT`, e.g. at a breakpoint in a loop. Programs waiting for input keep
+    /// running and can be stopped with `interrupt`.
+    pub fn continue_async(&mut self) -> Result<(), DebugError> {
+        match self.step_breakpoint() {
+            Ok(_) | Err(DebugError::NoBreakpointFound) => (),
+            Err(e) => return Err(e),
+        }
+        self.insert_line_counters()?;
+        if self.has_exited() {
+            return Ok(());
+        }
+        ptrace::cont(self.child, None)?;
+        self.set_state(DebugeeState::Running);
+        let start = Instant::now();
+        while self.is_running() && start.elapsed() < CONTINUE_TIMEOUT {
+            std::thread::sleep(POLL_INTERVAL);
+            self.waitpid_flag(Some(WaitPidFlag::WNOHANG))?;
+        }
+        Ok(())
+    }
+
+    /// Checks whether the running child stopped, without blocking
+    pub fn poll_child(&mut self) -> Result<(), DebugError> {
+        if !self.is_running() {
+            return Ok(());
+        }
+        self.waitpid_flag(Some(WaitPidFlag::WNOHANG))?;
+        if !self.is_running() {
+            self.record_stop();
+            return Ok(());
+        }
+        self.check_timeout()
+    }
+
+    /// Whether the child is computing instead of sleeping, e.g. while waiting for input
+    fn is_computing(&self) -> bool {
+        let Ok(stat) = std::fs::read_to_string(format!("/proc/{}/stat", self.child)) else {
+            return false;
+        };
+        // the state follows the name in parentheses, which may contain spaces
+        stat.rsplit_once(')')
+            .is_some_and(|(_, rest)| rest.trim_start().starts_with('R'))
+    }
+
+    /// Stops the child if it kept computing for longer than the continue timeout since it was
+    /// continued
+    fn check_timeout(&mut self) -> Result<(), DebugError> {
+        let Some(timeout) = self.continue_timeout else {
+            return Ok(());
+        };
+        let elapsed = self.state_since.elapsed().unwrap_or_default();
+        if elapsed < timeout || !self.is_computing() {
+            return Ok(());
+        }
+        self.interrupt()?;
+        if self.stop_reason != StopReason::Interrupted {
+            // stopped by something else in the meantime
+            return Ok(());
+        }
+        let backtrace = self
+            .backtrace()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|f| f.function.unwrap_or("??".to_owned()))
+            .collect::<Vec<_>>();
+        match self.stop_state().location {
+            Some(location) => warn!(
+                "Possible infinite loop at {}:{} in {}",
+                location.file,
+                location.line,
+                backtrace.join(" <- ")
+            ),
+            None => warn!("Possible infinite loop at {:#x}", self.get_pc()?),
+        }
+        self.stop_reason = StopReason::Timeout {
+            seconds: elapsed.as_secs(),
+            backtrace,
+        };
+        Ok(())
+    }
+
+    /// Sets the time the child may keep computing after `Continue` before it is stopped, 0
+    /// disables the timeout
+    pub fn set_continue_timeout(&mut self, seconds: u64) {
+        self.continue_timeout = match seconds {
+            0 => None,
+            seconds => Some(Duration::from_secs(seconds)),
+        };
+    }
+
+    /// Stops the running child with `SIGSTOP`
+    pub fn interrupt(&mut self) -> Result<(), DebugError> {
+        if !self.is_running() {
+            return Err(DebugError::InvalidArgument(
+                "The child isn't running".to_owned(),
+            ));
+        }
+        kill(self.child, Signal::SIGSTOP)?;
+        self.waitpid()?;
+        if self.stop_reason == StopReason::Signal(Signal::SIGSTOP.as_str().to_owned()) {
+            self.stop_reason = StopReason::Interrupted;
+        }
+        self.record_stop();
+        Ok(())
+    }
+
+    pub fn status(&self) -> DebugeeStatus {
+        let stop = match self.state {
+            DebugeeState::Stopped => Some(self.stop_state()),
+            _ => None,
+        };
+        DebugeeStatus {
+            state: match &stop {
+                Some(stop) if stop.reason == StopReason::Breakpoint => {
+                    DebugeeState::StoppedAtBreakpoint
+                }
+                _ => self.state.clone(),
+            },
+            since: unix_millis(self.state_since),
+            started: unix_millis(self.started),
+            stop,
+            summary: self.exit_summary(),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use std::{ffi::c_void, path::Path};
+
+use stackium_shared::Registers;
+
+use super::{
+    error::DebugError,
+    registers::FromUserRegsStruct,
+    util::{get_line_containing_addr, get_line_from_pc},
+    Debugger,
+};
+
+/// Functions `StepIn` steps over instead of entering them, besides the ones without line
+/// information like the functions of libc
+#[derive(Default)]
+pub struct StepSkips {
+    /// Patterns of function names or file names of mapped objects, `*` matches any text
+    patterns: Vec<String>,
+}
+
+/// Whether `text` matches `pattern`, in which `*` matches any text
+fn matches_pattern(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let parts = parts.collect::<Vec<_>>();
+    let Some((last, middle)) = parts.split_last() else {
+        // no `*` in the pattern
+        return rest.is_empty();
+    };
+    for part in middle {
+        match rest.find(part) {
+            Some(i) => rest = &rest[i + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+impl StepSkips {
+    fn matches(&self, function: Option<&str>, object: Option<&str>) -> bool {
+        self.patterns.iter().any(|pattern| {
+            function.is_some_and(|f| matches_pattern(pattern, f))
+                || object.is_some_and(|o| matches_pattern(pattern, o))
+        })
+    }
+}
+
+impl Debugger {
+    pub fn set_step_skips(&mut self, patterns: Vec<String>) {
+        self.step_skips.patterns = patterns
+            .into_iter()
+            .map(|pattern| pattern.trim().to_owned())
+            .filter(|pattern| !pattern.is_empty())
+            .collect();
+    }
+
+    /// File name of the object mapped at `addr`, e.g. `libc.so.6`
+    fn mapped_object(&self, addr: u64) -> Option<String> {
+        let maps = self.get_maps().ok()?;
+        let map = maps.iter().find(|m| m.from <= addr && addr < m.to)?;
+        Path::new(&map.mapped)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+    }
+
+    /// Whether `StepIn` steps over the function at `addr`
+    fn skips_function(&self, addr: u64) -> bool {
+        // PLT stubs and functions of libraries without debug information
+        if get_line_containing_addr(&self.dwarf, addr).is_err() {
+            return true;
+        }
+        if self.step_skips.patterns.is_empty() {
+            return false;
+        }
+        let function = self.get_func_from_addr(addr).ok().and_then(|f| f.name);
+        self.step_skips
+            .matches(function.as_deref(), self.mapped_object(addr).as_deref())
+    }
+
+    /// The return address pushed by the instruction stepped from `before` if it was a call. A
+    /// call pushes the address of the next instruction, which is at most 15 bytes after it.
+    pub fn called_from(&self, before: &Registers) -> Result<Option<u64>, DebugError> {
+        let after = Registers::from_regs(self.get_registers()?);
+        if after.stack_pointer != before.stack_pointer.wrapping_sub(8) {
+            return Ok(None);
+        }
+        Ok(self
+            .read(after.stack_pointer as *mut c_void)
+            .ok()
+            .filter(|ra| {
+                *ra > before.instruction_pointer && *ra <= before.instruction_pointer + 15
+            }))
+    }
+
+    /// Steps an instruction and runs to the return of the function it calls if `StepIn`
+    /// skips it. False if stepping has to stop, because the program ended or a breakpoint
+    /// in the skipped function was hit.
+    fn step_instruction_skipping(&mut self) -> Result<bool, DebugError> {
+        let before = Registers::from_regs(self.get_registers()?);
+        self.step_instruction()?;
+        if self.has_exited() {
+            return Ok(false);
+        }
+        match self.called_from(&before)? {
+            Some(ra) if self.skips_function(self.get_pc()?) => {
+                self.run_to(ra)?;
+                Ok(!self.has_exited() && self.get_pc()? == ra)
+            }
+            _ => Ok(true),
+        }
+    }
+
+    /// Steps until another line starts, the functions in the skip list and the ones without
+    /// line information are stepped over
+    pub fn step_in(&mut self) -> Result<(), DebugError> {
+        let line = get_line_containing_addr(&self.dwarf, self.get_pc()?)?.line;
+        loop {
+            if !self.step_instruction_skipping()? {
+                return Ok(());
+            }
+            let pc = self.get_pc()?;
+            match get_line_from_pc(&self.dwarf, pc) {
+                Ok(location) if location.line != line => return Ok(()),
+                Ok(_) => {}
+                // returned into code without line information, e.g. from `main` into libc
+                Err(_) if get_line_containing_addr(&self.dwarf, pc).is_err() => return Ok(()),
+                Err(_) => {}
+            }
+        }
+    }
+
+    /// Like [`Self::step_in`] but also stops at a new column of the same line, i.e. at the
+    /// beginning of the next statement of lines with several statements
+    pub fn step_statement(&mut self) -> Result<(), DebugError> {
+        let start = get_line_containing_addr(&self.dwarf, self.get_pc()?)?;
+        loop {
+            if !self.step_instruction_skipping()? {
+                return Ok(());
+            }
+            let pc = self.get_pc()?;
+            match get_line_from_pc(&self.dwarf, pc) {
+                Ok(location) if (location.line, location.column) != (start.line, start.column) => {
+                    return Ok(())
+                }
+                Ok(_) => {}
+                Err(_) if get_line_containing_addr(&self.dwarf, pc).is_err() => return Ok(()),
+                Err(_) => {}
+            }
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use stackium_shared::{Registers, StopReason, StopState};
+
+use super::{registers::FromUserRegsStruct, util::get_line_containing_addr, Debugger};
+
+impl Debugger {
+    /// Where and why the debugee stopped, returned by every command resuming it so clients
+    /// don't have to query the registers and location separately
+    pub fn stop_state(&self) -> StopState {
+        let registers = self.get_registers().ok().map(Registers::from_regs);
+        let pc = registers.as_ref().map(|r| r.instruction_pointer);
+        let breakpoint = match (&self.stop_reason, pc) {
+            (StopReason::Step | StopReason::Breakpoint, Some(pc)) => self
+                .breakpoints
+                .iter()
+                .find(|b| b.enabled && b.address == pc)
+                .cloned(),
+            _ => None,
+        };
+        let reason = match (&self.stop_reason, &breakpoint) {
+            // single-stepping onto a breakpoint, e.g. while tracing
+            (StopReason::Step, Some(_)) => StopReason::Breakpoint,
+            // temporary breakpoints, e.g. the return address when stepping out
+            (StopReason::Breakpoint, None) => StopReason::Step,
+            (reason, _) => reason.clone(),
+        };
+        StopState {
+            reason,
+            registers,
+            location: pc.and_then(|pc| get_line_containing_addr(&self.dwarf, pc).ok()),
+            breakpoint,
+            return_value: None,
+            returned_frames: vec![],
+            library_call: self.library_call().ok().flatten(),
+        }
+    }
+}
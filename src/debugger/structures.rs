@@ -0,0 +1,220 @@
+use std::collections::HashMap;
+
+use stackium_shared::{
+    format_value, get_byte_size, DataStructure, DataType, MemoryMap, NodeLink, StructureKind,
+    StructureNode, TypeName, Variable,
+};
+
+use super::{error::DebugError, Debugger};
+
+/// Nodes read per data structure, the rest of larger structures is left out
+const MAX_NODES: usize = 256;
+
+/// Members of the struct at `index` which point to the struct itself, with their offsets
+fn self_links(types: &DataType, index: usize) -> Vec<(String, usize)> {
+    let TypeName::ProductType { members, .. } = &types.0[index].1 else {
+        return vec![];
+    };
+    members
+        .iter()
+        .filter(|(_, member_type, _)| {
+            matches!(types.0[*member_type].1, TypeName::Ref { index: Some(i) } if i == index)
+        })
+        .map(|(name, _, offset)| (name.clone(), *offset))
+        .collect()
+}
+
+/// Guesses the shape from the self-referencing members: one is a singly linked list, a `prev`
+/// member makes it doubly linked and `left`/`right` or two unnamed links are a binary tree
+fn structure_kind(types: &DataType, index: usize) -> Option<StructureKind> {
+    let links = self_links(types, index);
+    let named = |names: &[&str]| {
+        links
+            .iter()
+            .map(|(name, _)| name)
+            .find(|name| {
+                let name = name.to_lowercase();
+                names.iter().any(|n| name.contains(n))
+            })
+            .cloned()
+    };
+    if let [(next, _)] = links.as_slice() {
+        return Some(StructureKind::LinkedList {
+            next: next.clone(),
+            prev: None,
+        });
+    }
+    if let (Some(left), Some(right)) = (named(&["left"]), named(&["right"])) {
+        return Some(StructureKind::BinaryTree { left, right });
+    }
+    let prev = named(&["prev", "back"]);
+    let next = named(&["next", "succ"]);
+    match (next, prev) {
+        (Some(next), prev) => Some(StructureKind::LinkedList { next, prev }),
+        (None, Some(prev)) => Some(StructureKind::LinkedList {
+            next: links.iter().find(|(name, _)| *name != prev)?.0.clone(),
+            prev: Some(prev),
+        }),
+        (None, None) if links.len() == 2 => Some(StructureKind::BinaryTree {
+            left: links[0].0.clone(),
+            right: links[1].0.clone(),
+        }),
+        (None, None) => None,
+    }
+}
+
+fn is_mapped(maps: &[MemoryMap], address: u64, size: u64) -> bool {
+    address != 0
+        && maps
+            .iter()
+            .any(|m| m.read && m.from <= address && address.saturating_add(size) <= m.to)
+}
+
+/// Reads the nodes of one structure, every address at most once
+struct Walk<'a> {
+    debugger: &'a Debugger,
+    maps: &'a [MemoryMap],
+    size: u64,
+    /// Node addresses with their depth in traversal order
+    order: Vec<(u64, usize)>,
+    memory: HashMap<u64, Vec<u8>>,
+    truncated: bool,
+}
+
+impl Walk<'_> {
+    /// Reads the node at `address`, `None` if it is null, unmapped or was already read
+    fn read(&mut self, address: u64) -> Option<Vec<u8>> {
+        if self.memory.contains_key(&address) || !is_mapped(self.maps, address, self.size) {
+            return None;
+        }
+        if self.memory.len() >= MAX_NODES {
+            self.truncated = true;
+            return None;
+        }
+        let memory = self.debugger.read_memory(address, self.size).ok()?;
+        self.memory.insert(address, memory.clone());
+        Some(memory)
+    }
+
+    fn list(&mut self, mut address: u64, next: usize) {
+        while let Some(memory) = self.read(address) {
+            self.order.push((address, self.order.len()));
+            address = pointer_at(&memory, next);
+        }
+    }
+
+    fn in_order(&mut self, address: u64, depth: usize, left: usize, right: usize) {
+        let Some(memory) = self.read(address) else {
+            return;
+        };
+        self.in_order(pointer_at(&memory, left), depth + 1, left, right);
+        self.order.push((address, depth));
+        self.in_order(pointer_at(&memory, right), depth + 1, left, right);
+    }
+}
+
+fn pointer_at(memory: &[u8], offset: usize) -> u64 {
+    memory
+        .get(offset..offset + 8)
+        .map_or(0, |p| u64::from_le_bytes(p.try_into().unwrap()))
+}
+
+impl Debugger {
+    /// Linked lists and binary trees reached from the variables of the current function.
+    /// Pointers to a node struct and node structs themselves are recognized by the members
+    /// pointing to their own type, `discover_variables` can't follow those as it would never
+    /// stop at cycles.
+    pub fn data_structures(&self) -> Result<Vec<DataStructure>, DebugError> {
+        let maps = self.get_maps()?;
+        let mut structures = vec![];
+        for variable in self.read_variables()? {
+            if let Some(structure) = self.data_structure(&variable, &maps) {
+                structures.push(structure);
+            }
+        }
+        Ok(structures)
+    }
+
+    fn data_structure(&self, variable: &Variable, maps: &[MemoryMap]) -> Option<DataStructure> {
+        let (Some(name), Some(types), true) =
+            (&variable.name, &variable.type_name, variable.in_scope)
+        else {
+            return None;
+        };
+        let (index, address) = match types.0.first()?.1 {
+            TypeName::Ref { index: Some(index) } => (index, variable.value?),
+            TypeName::ProductType { .. } => (0, variable.addr?),
+            _ => return None,
+        };
+        let kind = structure_kind(types, index)?;
+        let TypeName::ProductType {
+            name: node_type,
+            members,
+            ..
+        } = &types.0[index].1
+        else {
+            return None;
+        };
+        let offset = |member: &String| members.iter().find(|m| m.0 == *member).map(|m| m.2);
+        let mut walk = Walk {
+            debugger: self,
+            maps,
+            size: get_byte_size(types, index) as u64,
+            order: vec![],
+            memory: HashMap::new(),
+            truncated: false,
+        };
+        match &kind {
+            StructureKind::LinkedList { next, .. } => walk.list(address, offset(next)?),
+            StructureKind::BinaryTree { left, right } => {
+                walk.in_order(address, 0, offset(left)?, offset(right)?)
+            }
+        }
+        let links = self_links(types, index);
+        let node_index = walk
+            .order
+            .iter()
+            .enumerate()
+            .map(|(i, (address, _))| (*address, i))
+            .collect::<HashMap<_, _>>();
+        let nodes = walk
+            .order
+            .iter()
+            .map(|(address, depth)| {
+                let memory = &walk.memory[address];
+                let mut node = StructureNode {
+                    address: *address,
+                    fields: vec![],
+                    links: vec![],
+                    depth: *depth,
+                };
+                for (member, member_type, offset) in members {
+                    if links.iter().any(|(link, _)| link == member) {
+                        let target = pointer_at(memory, *offset);
+                        node.links.push(NodeLink {
+                            member: member.clone(),
+                            address: target,
+                            node: node_index.get(&target).copied(),
+                            mapped: is_mapped(maps, target, walk.size),
+                        });
+                    } else {
+                        let value = memory
+                            .get(*offset..)
+                            .and_then(|m| format_value(types, *member_type, m));
+                        node.fields
+                            .push((member.clone(), value.unwrap_or("?".to_owned())));
+                    }
+                }
+                node
+            })
+            .collect();
+        Some(DataStructure {
+            variable: name.clone(),
+            node_type: node_type.clone(),
+            kind,
+            address,
+            nodes,
+            truncated: walk.truncated,
+        })
+    }
+}
@@ -0,0 +1,188 @@
+use object::{Object, ObjectKind, ObjectSection, ObjectSymbol, SymbolKind};
+use stackium_shared::{Breakpoint, FunctionMeta, Location, MemoryMap, ResolvedAddress};
+
+use super::{
+    breakpoint::DebuggerBreakpoint, demangle::demangle, error::DebugError,
+    util::get_line_containing_addr, Debugger,
+};
+
+impl Debugger {
+    /// Difference between the addresses in the program file and in memory
+    pub fn load_bias(&self, object_file: &object::File<'_>, maps: &[MemoryMap]) -> u64 {
+        // position independent executables are loaded at the start of their first mapping
+        if object_file.kind() != ObjectKind::Dynamic {
+            return 0;
+        }
+        self.load_address(maps)
+    }
+
+    /// Start of the first mapping of the program
+    pub fn load_address(&self, maps: &[MemoryMap]) -> u64 {
+        let program = std::fs::canonicalize(&self.program).unwrap_or(self.program.clone());
+        maps.iter()
+            .filter(|m| std::path::Path::new(&m.mapped) == program)
+            .map(|m| m.from)
+            .min()
+            .unwrap_or(0)
+    }
+
+    /// Resolves an address to the enclosing function, source line, section of the program and
+    /// the memory mapping it belongs to
+    pub fn resolve_address(&self, address: u64) -> Result<ResolvedAddress, DebugError> {
+        let maps = self.get_maps()?;
+        let mapping = maps
+            .iter()
+            .find(|m| m.from <= address && address < m.to)
+            .cloned();
+        let bin = std::fs::read(&self.program)?;
+        let object_file = object::File::parse(&bin[..])
+            .map_err(|e| DebugError::InvalidArgument(e.to_string()))?;
+        let load_bias = self.load_bias(&object_file, &maps);
+        let file_address = address.wrapping_sub(load_bias);
+
+        // symbol table entry containing the address, also covers code without debug information
+        // like `_start` and global variables
+        let symbol = object_file
+            .symbols()
+            .filter(|s| matches!(s.kind(), SymbolKind::Text | SymbolKind::Data))
+            .find(|s| s.address() <= file_address && file_address < s.address() + s.size().max(1))
+            .and_then(|s| {
+                Some((
+                    demangle(s.name().ok()?).into_owned(),
+                    file_address - s.address(),
+                    s.kind(),
+                ))
+            });
+        let (function, offset) = match self.get_func_from_addr(file_address) {
+            Ok(meta) => (meta.name, meta.low_pc.map(|low_pc| file_address - low_pc)),
+            Err(_) => match &symbol {
+                Some((name, offset, SymbolKind::Text)) => (Some(name.clone()), Some(*offset)),
+                _ => (None, None),
+            },
+        };
+        let section = object_file
+            .sections()
+            .find(|s| {
+                s.address() <= file_address && file_address < s.address() + s.size() && s.size() > 0
+            })
+            .and_then(|s| s.name().ok().map(|n| n.to_owned()));
+        let location = get_line_containing_addr(&self.dwarf, file_address).ok();
+
+        let mut description = match (&function, offset, &symbol, &mapping) {
+            (Some(function), Some(0), _, _) => function.clone(),
+            (Some(function), Some(offset), _, _) => format!("{}+{:#x}", function, offset),
+            (_, _, Some((name, 0, _)), _) => name.clone(),
+            (_, _, Some((name, offset, _)), _) => format!("{}+{:#x}", name, offset),
+            (_, _, _, Some(mapping)) if !mapping.mapped.is_empty() => format!(
+                "{}+{:#x}",
+                mapping.mapped.rsplit('/').next().unwrap_or(&mapping.mapped),
+                address - mapping.from + mapping.offset
+            ),
+            _ => format!("{:#x}", address),
+        };
+        if let Some(location) = &location {
+            description = format!(
+                "{} ({}:{})",
+                description,
+                location.file.rsplit('/').next().unwrap_or(&location.file),
+                location.line
+            );
+        }
+        Ok(ResolvedAddress {
+            address,
+            function,
+            offset,
+            location,
+            section,
+            mapping,
+            description,
+        })
+    }
+
+    /// Functions defined in the symbol table, used instead of the debug information if there
+    /// is none. `high_pc` is the size of the function like in the debug information.
+    pub fn symbol_functions(&self) -> Result<Vec<FunctionMeta>, DebugError> {
+        let bin = std::fs::read(&self.program)?;
+        let object_file = object::File::parse(&bin[..])
+            .map_err(|e| DebugError::InvalidArgument(e.to_string()))?;
+        let mut functions: Vec<FunctionMeta> = vec![];
+        // stripped binaries only have the dynamic symbol table
+        for symbol in object_file.symbols().chain(object_file.dynamic_symbols()) {
+            let Ok(name) = symbol.name() else {
+                continue;
+            };
+            if symbol.kind() != SymbolKind::Text
+                || !symbol.is_definition()
+                || symbol.size() == 0
+                || functions.iter().any(|f| f.low_pc == Some(symbol.address()))
+            {
+                continue;
+            }
+            functions.push(FunctionMeta {
+                name: Some(demangle(name).into_owned()),
+                low_pc: Some(symbol.address()),
+                high_pc: Some(symbol.size()),
+                return_addr: None,
+                declaration: None,
+                parameters: vec![],
+                return_type: None,
+            });
+        }
+        functions.sort_by_key(|f| f.low_pc);
+        Ok(functions)
+    }
+
+    pub fn find_symbol_function(
+        &self,
+        predicate: impl Fn(&FunctionMeta) -> bool,
+    ) -> Result<FunctionMeta, DebugError> {
+        self.symbol_functions()?
+            .into_iter()
+            .find(predicate)
+            .ok_or(DebugError::FunctionNotFound)
+    }
+
+    /// Breakpoint at an address of a program without debug information, labeled with the
+    /// function from the symbol table instead of a source line
+    pub fn symbol_breakpoint(&self, address: u64) -> Result<Breakpoint, DebugError> {
+        let file = match self.get_func_from_addr(address) {
+            Ok(FunctionMeta {
+                name: Some(name),
+                low_pc: Some(low_pc),
+                ..
+            }) if low_pc == address => name,
+            Ok(FunctionMeta {
+                name: Some(name),
+                low_pc: Some(low_pc),
+                ..
+            }) => format!("{}+{:#x}", name, address - low_pc),
+            _ => format!("{:#x}", address),
+        };
+        Breakpoint::with_location(
+            self.child,
+            address as *const u8,
+            Location {
+                line: 0,
+                file,
+                column: 0,
+            },
+        )
+    }
+
+    /// Breakpoint at the first instruction of a function of the symbol table, there is no
+    /// line table to skip the prologue
+    pub fn set_symbol_breakpoint(&mut self, name: &str) -> Result<(), DebugError> {
+        let address = self
+            .find_symbol_function(|f| f.name.as_deref() == Some(name))?
+            .low_pc
+            .unwrap();
+        if self.breakpoints.iter().any(|b| b.address == address) {
+            return Err(DebugError::BreakpointInvalidState);
+        }
+        let mut breakpoint = self.symbol_breakpoint(address)?;
+        self.memory.clear();
+        breakpoint.enable(self.child)?;
+        self.breakpoints.push(breakpoint);
+        Ok(())
+    }
+}
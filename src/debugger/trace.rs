@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+
+use stackium_shared::{ExecutionTrace, LineHits, Location};
+use tracing::warn;
+
+use super::{cancel::STEPS_BETWEEN_CHECKS, error::DebugError, util::get_line_from_pc, Debugger};
+
+/// Maximum number of instructions single-stepped by one traced continue
+const MAX_TRACE_INSTRUCTIONS: u64 = 1_000_000;
+/// Maximum number of entries in the sequence of executed lines, hit counts are still updated
+const MAX_TRACE_LINES: usize = 100_000;
+
+/// Source lines executed while tracing is enabled
+#[derive(Default)]
+pub struct Tracer {
+    enabled: bool,
+    lines: Vec<Location>,
+    hits: HashMap<(String, u64), u64>,
+    instructions: u64,
+    truncated: bool,
+    /// Line of each program counter seen so far, looking lines up in the dwarf is slow
+    line_cache: HashMap<u64, Option<Location>>,
+}
+
+impl Tracer {
+    /// Enabling starts a new trace, disabling keeps the recorded one
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if enabled {
+            *self = Tracer {
+                enabled,
+                line_cache: std::mem::take(&mut self.line_cache),
+                ..Default::default()
+            };
+        } else {
+            self.enabled = false;
+        }
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Drops the recorded trace and the line cache, e.g. when the debugee is restarted
+    pub fn reset(&mut self) {
+        *self = Tracer {
+            enabled: self.enabled,
+            ..Default::default()
+        };
+    }
+
+    fn record(&mut self, location: Location) {
+        self.instructions += 1;
+        if self
+            .lines
+            .last()
+            .is_some_and(|l| l.line == location.line && l.file == location.file)
+        {
+            return;
+        }
+        *self
+            .hits
+            .entry((location.file.clone(), location.line))
+            .or_insert(0) += 1;
+        if self.lines.len() < MAX_TRACE_LINES {
+            self.lines.push(location);
+        } else {
+            self.truncated = true;
+        }
+    }
+
+    pub fn hit_counts(&self) -> Vec<LineHits> {
+        let mut hit_counts = self
+            .hits
+            .iter()
+            .map(|((file, line), count)| LineHits {
+                file: file.clone(),
+                line: *line,
+                count: *count,
+            })
+            .collect::<Vec<_>>();
+        hit_counts.sort_by(|a, b| (&a.file, a.line).cmp(&(&b.file, b.line)));
+        hit_counts
+    }
+
+    pub fn trace(&self) -> ExecutionTrace {
+        ExecutionTrace {
+            enabled: self.enabled,
+            lines: self.lines.clone(),
+            hit_counts: self.hit_counts(),
+            instructions: self.instructions,
+            truncated: self.truncated,
+        }
+    }
+}
+
+impl Debugger {
+    /// Records the line of the current instruction if tracing is enabled
+    pub fn record_trace(&mut self) {
+        if !self.tracer.enabled {
+            return;
+        }
+        let Ok(pc) = self.get_pc() else {
+            return;
+        };
+        let location = match self.tracer.line_cache.get(&pc) {
+            Some(location) => location.clone(),
+            None => {
+                let location = get_line_from_pc(&self.dwarf, pc).ok();
+                self.tracer.line_cache.insert(pc, location.clone());
+                location
+            }
+        };
+        // instructions without debug information, e.g. in libc, aren't part of the trace
+        if let Some(location) = location {
+            self.tracer.record(location);
+        }
+    }
+
+    /// Single-steps until a breakpoint is hit, the child exits or `limit` instructions were
+    /// executed, calling `on_step` with the number of the step and the new program counter.
+    /// Stops early if `on_step` returns `false` and with `Cancelled` if the command is cancelled.
+    pub fn step_until_breakpoint(
+        &mut self,
+        limit: u64,
+        mut on_step: impl FnMut(&mut Self, u64, u64) -> bool,
+    ) -> Result<(), DebugError> {
+        for step in 0..limit {
+            if step % STEPS_BETWEEN_CHECKS == 0 {
+                self.check_cancelled()?;
+            }
+            self.step_instruction()?;
+            let Ok(pc) = self.get_pc() else {
+                // the child exited
+                return Ok(());
+            };
+            if self
+                .breakpoints
+                .iter()
+                .any(|b| b.enabled && b.address == pc)
+            {
+                return Ok(());
+            }
+            if !on_step(self, step, pc) {
+                return Ok(());
+            }
+        }
+        warn!(
+            "Stopped single-stepping after {} instructions without hitting a breakpoint",
+            limit
+        );
+        Ok(())
+    }
+
+    /// Continues execution by single-stepping until a breakpoint is hit, an invariant breaks
+    /// or the child exits, recording every executed line
+    pub fn continue_traced(&mut self) -> Result<(), DebugError> {
+        self.step_until_breakpoint(MAX_TRACE_INSTRUCTIONS, |debugger, _, pc| {
+            debugger.check_invariants_at(pc)
+        })
+    }
+}
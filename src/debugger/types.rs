@@ -0,0 +1,109 @@
+use std::path::Path;
+
+use gimli::Reader;
+use stackium_shared::{DataType, TypeName};
+
+use super::{error::DebugError, Debugger};
+use crate::variables::get_type_name;
+
+impl Debugger {
+    /// The decoded type of every variable and parameter in the binary, see [`format_types`].
+    /// Variables are listed in the order of the debug information, prefixed with the file name
+    /// of their compilation unit and their function.
+    pub fn dump_types(&self) -> Result<String, DebugError> {
+        let mut dump = String::new();
+        let mut units = self.dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = self.dwarf.unit(header)?;
+            let file = unit
+                .name
+                .as_ref()
+                .and_then(|name| name.to_string_lossy().ok())
+                .and_then(|name| {
+                    Path::new(name.as_ref())
+                        .file_name()
+                        .map(|n| n.to_string_lossy().into_owned())
+                })
+                .unwrap_or("??".to_owned());
+            let mut entries = unit.entries();
+            let mut depth = 0;
+            // depth and name of the functions enclosing the entry
+            let mut functions: Vec<(isize, String)> = vec![];
+            while let Some((delta, entry)) = entries.next_dfs()? {
+                depth += delta;
+                while functions.last().is_some_and(|(d, _)| *d >= depth) {
+                    functions.pop();
+                }
+                let name = entry
+                    .attr_value(gimli::DW_AT_name)?
+                    .map(|name| Debugger::decode_string_attribute(name, &self.dwarf, &unit));
+                match entry.tag() {
+                    gimli::DW_TAG_subprogram => {
+                        functions.push((depth, name.unwrap_or("??".to_owned())));
+                    }
+                    gimli::DW_TAG_variable | gimli::DW_TAG_formal_parameter => {
+                        let Some(type_attr) = entry.attr_value(gimli::DW_AT_type)? else {
+                            continue;
+                        };
+                        let function = functions.last().map_or("", |(_, name)| name.as_str());
+                        let kind = match entry.tag() {
+                            gimli::DW_TAG_formal_parameter => " (parameter)",
+                            _ => "",
+                        };
+                        let variable = format!(
+                            "{} {}::{}{}",
+                            file,
+                            function,
+                            name.as_deref().unwrap_or("??"),
+                            kind
+                        );
+                        match self.decode_type(type_attr, DataType(vec![])) {
+                            Ok(types) => dump.push_str(&format_types(&variable, &types)),
+                            Err(e) => dump.push_str(&format!("{}: {:?}\n", variable, e)),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+        Ok(dump)
+    }
+}
+
+/// Canonical text form of the types of `variable`: the C name of the type followed by every
+/// node of the type graph, which reference each other by index. Unlike the debug information
+/// offsets the indices only change if the decoding does.
+fn format_types(variable: &str, types: &DataType) -> String {
+    if types.0.is_empty() {
+        return format!("{}: no type\n", variable);
+    }
+    let mut text = format!("{}: {}\n", variable, get_type_name(types, 0));
+    for (i, (_, type_name)) in types.0.iter().enumerate() {
+        let node = match type_name {
+            TypeName::Name { name, byte_size } => format!("{} ({} bytes)", name, byte_size),
+            TypeName::Arr { arr_type, count } => format!(
+                "array of #{} {}",
+                arr_type,
+                count.iter().map(|c| format!("[{}]", c)).collect::<String>()
+            ),
+            TypeName::Ref { index: Some(index) } => format!("pointer to #{}", index),
+            TypeName::Ref { index: None } => "pointer to void".to_owned(),
+            TypeName::ProductType {
+                name,
+                members,
+                byte_size,
+            } => format!(
+                "struct {} ({} bytes) {{ {} }}",
+                name,
+                byte_size,
+                members
+                    .iter()
+                    .map(|(name, index, offset)| format!("{}: #{} at {}", name, index, offset))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        };
+        text.push_str(&format!("  #{} {}\n", i, node));
+    }
+    text
+}
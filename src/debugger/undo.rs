@@ -0,0 +1,59 @@
+use stackium_shared::{Action, Breakpoint};
+
+use super::{breakpoint::DebuggerBreakpoint, error::DebugError, Debugger};
+
+/// Maximum number of actions that can be undone, older actions are dropped
+const MAX_UNDO: usize = 100;
+
+/// Breakpoint changes and writes to memory and registers, most recent last
+#[derive(Default)]
+pub struct UndoLog {
+    actions: Vec<Action>,
+}
+
+impl UndoLog {
+    pub fn record(&mut self, action: Action) {
+        if self.actions.len() == MAX_UNDO {
+            self.actions.remove(0);
+        }
+        self.actions.push(action);
+    }
+
+    pub fn actions(&self) -> Vec<Action> {
+        self.actions.clone()
+    }
+
+    /// Forgets all actions, e.g. when the debugee is restarted and they no longer apply
+    pub fn reset(&mut self) {
+        self.actions.clear();
+    }
+}
+
+impl Debugger {
+    /// Reverts the most recent action. It stays in the log if reverting it fails.
+    pub fn undo(&mut self) -> Result<(), DebugError> {
+        let action = self
+            .actions
+            .actions
+            .pop()
+            .ok_or(DebugError::NothingToUndo)?;
+        let result = match &action {
+            Action::SetBreakpoint(breakpoint) => {
+                self.delete_breakpoint(breakpoint.address).map(|_| ())
+            }
+            Action::DeleteBreakpoint(breakpoint) => Breakpoint::with_location(
+                self.child,
+                breakpoint.address as *const u8,
+                breakpoint.location.clone(),
+            )
+            .and_then(|restored| self.add_breakpoint(restored))
+            .map(|_| ()),
+            Action::WriteMemory { address, old, .. } => self.write_memory(*address, old),
+            Action::SetRegister { name, old, .. } => self.set_register(name, *old),
+        };
+        if result.is_err() {
+            self.actions.actions.push(action);
+        }
+        result
+    }
+}
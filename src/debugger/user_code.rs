@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+
+use gimli::Reader;
+use stackium_shared::{FunctionGroup, FunctionMeta, StopReason};
+use tracing::warn;
+
+use super::{
+    breakpoint::DebuggerBreakpoint,
+    error::DebugError,
+    files::{get_unit_path, normalize_path},
+    util::{find_function_from_name, get_functions, get_post_prologue_addr},
+    Breakpoint, Debugger,
+};
+
+impl Debugger {
+    /// `main`, from the symbol table if the program has no debug information
+    fn main_function(&self) -> Result<FunctionMeta, DebugError> {
+        let main = match self.has_debug_info {
+            true => find_function_from_name(&self.dwarf, "main".to_owned())?,
+            false => self.find_symbol_function(|f| f.name.as_deref() == Some("main"))?,
+        };
+        match main.low_pc {
+            Some(_) => Ok(main),
+            None => Err(DebugError::FunctionNotFound),
+        }
+    }
+
+    /// Continues to the first line of `main` after its prologue with a temporary breakpoint,
+    /// which skips the dynamic linker and the startup code of the C library, statically linked
+    /// programs run a lot of it without debug information
+    pub fn run_to_main(&mut self) -> Result<(), DebugError> {
+        let main = self.main_function()?;
+        let address = main.low_pc.unwrap_or_default();
+        let mut breakpoint = match self.has_debug_info {
+            true => {
+                let body = get_post_prologue_addr(&self.dwarf, address, main.high_pc.unwrap_or(0))?;
+                Breakpoint::new(&self.dwarf, self.child, body as *const u8)?
+            }
+            false => self.symbol_breakpoint(address)?,
+        };
+        breakpoint.enable(self.child)?;
+        let result = self.continue_exec();
+        if !self.has_exited() {
+            self.memory.clear();
+            breakpoint.disable(self.child)?;
+        }
+        result
+    }
+
+    /// Called once the child was started or restarted, runs to `main` with `start_at_main` and
+    /// reports the stop as `Started`, so clients show where the program is before the first
+    /// command
+    pub fn start(&mut self) {
+        if self.start_at_main {
+            if let Err(e) = self.run_to_main() {
+                // e.g. programs without `main` or position independent ones
+                warn!(
+                    "Stopping at the entry point, running to main failed: {:?}",
+                    e
+                );
+            }
+        }
+        if !self.has_exited() {
+            self.stop_reason = StopReason::Started;
+        }
+    }
+
+    /// Directory the compilation unit of `main` was compiled in, units compiled elsewhere like
+    /// the C library of statically linked programs aren't code of the user
+    fn user_directory(&self) -> Option<PathBuf> {
+        let main = self.main_function().ok()?.low_pc?;
+        let unit = self.dwarf.units_containing(main).into_iter().next()?;
+        let directory = unit.comp_dir.as_ref()?.to_string_lossy().ok()?.into_owned();
+        Some(normalize_path(Path::new(&directory)))
+    }
+
+    /// The primary source files of the compilation units, only the ones of the user with
+    /// `user_code`
+    pub fn source_files(&self) -> Vec<PathBuf> {
+        let sources = self.files.sources().iter().cloned();
+        match self.user_code.then(|| self.user_directory()).flatten() {
+            Some(directory) => sources.filter(|s| s.starts_with(&directory)).collect(),
+            None => sources.collect(),
+        }
+    }
+
+    /// Whether the code at the address belongs to a compilation unit compiled in `directory`
+    fn compiled_in(&self, address: u64, directory: &Path) -> bool {
+        self.dwarf.units_containing(address).iter().any(|unit| {
+            unit.comp_dir
+                .as_ref()
+                .and_then(|dir| dir.to_string_lossy().ok().map(|d| d.into_owned()))
+                .is_some_and(|dir| normalize_path(Path::new(&dir)) == directory)
+        })
+    }
+
+    /// The functions of the debug information, only the ones defined in compilation units of
+    /// the user with `user_code`
+    pub fn functions(&self) -> Result<Vec<FunctionMeta>, DebugError> {
+        let functions = get_functions(&self.dwarf)?;
+        let Some(directory) = self.user_code.then(|| self.user_directory()).flatten() else {
+            return Ok(functions);
+        };
+        Ok(functions
+            .into_iter()
+            .filter(|f| {
+                f.low_pc
+                    .is_some_and(|address| self.compiled_in(address, &directory))
+            })
+            .collect())
+    }
+
+    /// The functions with code grouped by the primary source file of their compilation unit,
+    /// see `FindFunctions`
+    pub fn function_groups(
+        &self,
+        pattern: Option<&str>,
+        user_code: bool,
+    ) -> Result<Vec<FunctionGroup>, DebugError> {
+        let directory = (user_code || self.user_code)
+            .then(|| self.user_directory())
+            .flatten();
+        let pattern = pattern.map(str::to_lowercase);
+        let functions = match self.has_debug_info {
+            true => get_functions(&self.dwarf)?,
+            false => self.symbol_functions()?,
+        };
+        let mut groups: Vec<FunctionGroup> = vec![];
+        for function in functions {
+            let (Some(name), Some(address)) = (&function.name, function.low_pc) else {
+                continue;
+            };
+            if pattern
+                .as_ref()
+                .is_some_and(|pattern| !name.to_lowercase().contains(pattern))
+                || directory
+                    .as_ref()
+                    .is_some_and(|directory| !self.compiled_in(address, directory))
+            {
+                continue;
+            }
+            let file = self
+                .dwarf
+                .units_containing(address)
+                .into_iter()
+                .find_map(get_unit_path)
+                .map(|path| path.display().to_string())
+                .unwrap_or_default();
+            match groups.iter_mut().find(|group| group.file == file) {
+                Some(group) => group.functions.push(function),
+                None => groups.push(FunctionGroup {
+                    file,
+                    functions: vec![function],
+                }),
+            }
+        }
+        groups.sort_by(|a, b| a.file.cmp(&b.file));
+        for group in &mut groups {
+            group.functions.sort_by(|a, b| a.name.cmp(&b.name));
+        }
+        Ok(groups)
+    }
+}
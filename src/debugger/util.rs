@@ -1,30 +1,109 @@
-use std::num::NonZeroU64;
+use std::path::Path;
 
 use gimli::Reader;
-use stackium_shared::FunctionMeta;
+use stackium_shared::{FunctionMeta, LineAddress, LineAddresses};
+use tracing::warn;
 
-use super::{error::DebugError, Location};
+use super::{
+    demangle::demangle, dwarf_index::IndexedDwarf, error::DebugError, files::get_file_path,
+    signature, Location,
+};
+
+/// Name of a function entry, `DW_AT_linkage_name` demangled to the qualified name like
+/// `shapes::Rect::area` for Rust and C++, `DW_AT_name` otherwise. Methods and inlined functions
+/// keep their names in the declaration their `DW_AT_specification` or `DW_AT_abstract_origin`
+/// refers to.
+fn function_name<T: Reader>(
+    entry: &gimli::DebuggingInformationEntry<T, <T as gimli::Reader>::Offset>,
+    unit: &gimli::Unit<T>,
+    dwarf: &gimli::Dwarf<T>,
+) -> Result<Option<String>, DebugError> {
+    let string = |attribute: gimli::DwAt| -> Result<Option<String>, DebugError> {
+        Ok(match entry.attr_value(attribute)? {
+            Some(value) => Some(
+                dwarf
+                    .attr_string(unit, value)?
+                    .to_string_lossy()?
+                    .into_owned(),
+            ),
+            None => None,
+        })
+    };
+    let linkage_name = match string(gimli::DW_AT_linkage_name)? {
+        Some(name) => Some(name),
+        None => string(gimli::DW_AT_MIPS_linkage_name)?,
+    };
+    if let Some(linkage_name) = linkage_name {
+        return Ok(Some(demangle(&linkage_name).into_owned()));
+    }
+    if let Some(name) = string(gimli::DW_AT_name)? {
+        return Ok(Some(name));
+    }
+    for attribute in [gimli::DW_AT_specification, gimli::DW_AT_abstract_origin] {
+        if let Some(gimli::AttributeValue::UnitRef(offset)) = entry.attr_value(attribute)? {
+            let declaration = unit.entry(offset)?;
+            return function_name(&declaration, unit, dwarf);
+        }
+    }
+    Ok(None)
+}
+
+/// Where a function entry is declared, methods are declared in their class which their
+/// `DW_AT_specification` refers to
+fn function_declaration<T: Reader>(
+    entry: &gimli::DebuggingInformationEntry<T, <T as gimli::Reader>::Offset>,
+    unit: &gimli::Unit<T>,
+    dwarf: &gimli::Dwarf<T>,
+) -> Result<Option<Location>, DebugError> {
+    let line = entry
+        .attr_value(gimli::DW_AT_decl_line)?
+        .and_then(|line| line.udata_value());
+    let Some(gimli::AttributeValue::FileIndex(index)) = entry.attr_value(gimli::DW_AT_decl_file)?
+    else {
+        for attribute in [gimli::DW_AT_specification, gimli::DW_AT_abstract_origin] {
+            if let Some(gimli::AttributeValue::UnitRef(offset)) = entry.attr_value(attribute)? {
+                let declaration = unit.entry(offset)?;
+                return function_declaration(&declaration, unit, dwarf);
+            }
+        }
+        return Ok(None);
+    };
+    let (Some(line), Some(program)) = (line, &unit.line_program) else {
+        return Ok(None);
+    };
+    let header = program.header();
+    let Some(file) = header
+        .file(index)
+        .and_then(|file| get_file_path(dwarf, unit, header, file))
+    else {
+        return Ok(None);
+    };
+    let column = entry
+        .attr_value(gimli::DW_AT_decl_column)?
+        .and_then(|column| column.udata_value())
+        .unwrap_or(0);
+    Ok(Some(Location {
+        line,
+        file: file.display().to_string(),
+        column,
+    }))
+}
 
 pub fn get_function_meta<T: Reader>(
     entry: &gimli::DebuggingInformationEntry<T, <T as gimli::Reader>::Offset>,
+    unit: &gimli::Unit<T>,
     dwarf: &gimli::Dwarf<T>,
 ) -> Result<FunctionMeta, DebugError> {
-    let mut name: Option<String> = None;
+    let name = function_name(entry, unit, dwarf)?;
+    let declaration = function_declaration(entry, unit, dwarf)?;
+    let parameters = signature::parameters(entry, unit, dwarf)?;
+    let return_type = signature::return_type(entry, unit, dwarf)?;
     let mut attrs = entry.attrs();
     let mut low_pc = None;
     let mut high_pc = None;
     let mut return_addr = None;
     while let Some(attr) = attrs.next()? {
         match attr.name() {
-            gimli::DW_AT_name => {
-                if let gimli::AttributeValue::DebugStrRef(offset) = attr.value() {
-                    if let Ok(s) = dwarf.debug_str.get_str(offset)?.to_string() {
-                        name = Some(s.to_string());
-                    }
-                } else if let Some(str) = attr.string_value(&dwarf.debug_str) {
-                    name = Some(str.to_string().unwrap().to_string());
-                }
-            }
             gimli::DW_AT_low_pc => {
                 if let gimli::AttributeValue::Addr(addr) = attr.value() {
                     low_pc = Some(addr);
@@ -48,6 +127,9 @@ pub fn get_function_meta<T: Reader>(
         return_addr,
         low_pc,
         high_pc,
+        declaration,
+        parameters,
+        return_type,
     })
 }
 
@@ -55,127 +137,121 @@ pub fn get_piece_addr<T: gimli::Reader>(piece: &gimli::Piece<T>) -> Option<u64>
     match piece.location {
         gimli::Location::Address { address } => Some(address),
         _ => {
-            println!("Unknown location type: {:?}", piece.location);
+            warn!("Unknown location type: {:?}", piece.location);
             None
         }
     }
 }
 
 pub fn get_functions<T: gimli::Reader>(
-    dwarf: &gimli::Dwarf<T>,
+    dwarf: &IndexedDwarf<T>,
 ) -> Result<Vec<FunctionMeta>, DebugError> {
-    let mut units = dwarf.units();
-    let mut ret_val = vec![];
-    while let Some(unit_header) = units.next()? {
-        let unit = dwarf.unit(unit_header)?;
-        let mut cursor = unit.entries();
-        while let Some((_, entry)) = cursor.next_dfs()? {
-            if entry.tag() != gimli::DW_TAG_subprogram {
-                continue;
-            }
-            ret_val.push(get_function_meta(entry, &dwarf)?);
-        }
-    }
-    Ok(ret_val)
+    Ok(dwarf.functions().to_vec())
 }
 
 pub fn find_function_from_name<T: gimli::Reader>(
-    dwarf: &gimli::Dwarf<T>,
+    dwarf: &IndexedDwarf<T>,
     name_to_find: String,
 ) -> Result<FunctionMeta, DebugError> {
-    let mut units = dwarf.units();
-    while let Some(unit_header) = units.next()? {
-        let unit = dwarf.unit(unit_header)?;
-        let mut cursor = unit.entries();
-        while let Some((_, entry)) = cursor.next_dfs()? {
-            if entry.tag() != gimli::DW_TAG_subprogram {
-                continue;
-            }
-            if let Ok(Some(name)) = entry.attr(gimli::DW_AT_name) {
-                if let Some(name) = name.string_value(&dwarf.debug_str) {
-                    if let Ok(name) = name.to_string() {
-                        if name == name_to_find {
-                            return get_function_meta(entry, &dwarf);
-                        }
-                    }
-                }
-            }
-        }
-    }
-    Err(DebugError::FunctionNotFound)
+    dwarf
+        .function(&name_to_find)
+        .cloned()
+        .ok_or(DebugError::FunctionNotFound)
 }
 
+/// Returns the address breakpoints on `line_to_find` in `file_to_search` are set at, see
+/// [`get_line_addresses`].
 pub fn get_addr_from_line<T: gimli::Reader>(
-    dwarf: &gimli::Dwarf<T>,
+    dwarf: &IndexedDwarf<T>,
     line_to_find: u64,
-    file_to_search: String,
+    file_to_search: &Path,
 ) -> Result<u64, DebugError> {
-    let mut units = dwarf.units();
-    while let Ok(Some(unit_header)) = units.next() {
-        if let Ok(unit) = dwarf.unit(unit_header) {
-            if let Some(line_program) = unit.line_program {
-                let mut rows = line_program.rows();
-                while let Ok(Some((header, row))) = rows.next_row() {
-                    if let Some(file) = row.file(header) {
-                        if let Some(filename) = file.path_name().string_value(&dwarf.debug_str) {
-                            if filename.to_string()? == file_to_search
-                                && row.line() == NonZeroU64::new(line_to_find)
-                            {
-                                return Ok(row.address());
-                            }
-                        }
-                    }
-                }
-            }
+    Ok(get_line_addresses(dwarf, line_to_find, file_to_search)?.canonical)
+}
+
+/// Returns all addresses of `line_to_find` in `file_to_search`, a line can have several if the
+/// compiler duplicated its code, e.g. when unrolling a loop. Lines without code (comments, blank
+/// lines, declarations) resolve to the next line that has code. The file has to be a normalized
+/// path as returned by [`FileIndex::resolve`](super::files::FileIndex::resolve), all
+/// compilation units are searched.
+pub fn get_line_addresses<T: gimli::Reader>(
+    dwarf: &IndexedDwarf<T>,
+    line_to_find: u64,
+    file_to_search: &Path,
+) -> Result<LineAddresses, DebugError> {
+    // the closest line at or after `line_to_find` over all units
+    let mut line: Option<u64> = None;
+    let mut addresses: Vec<LineAddress> = vec![];
+    for (unit_line, unit_addresses) in dwarf
+        .line_tables()
+        .filter_map(|table| table.line_addresses(line_to_find, file_to_search))
+    {
+        if line.is_some_and(|l| unit_line > l) {
+            continue;
         }
+        if line != Some(unit_line) {
+            line = Some(unit_line);
+            addresses.clear();
+        }
+        addresses.extend(unit_addresses);
     }
-    Err(DebugError::FunctionNotFound)
+    let Some(line) = line else {
+        return Err(DebugError::FunctionNotFound);
+    };
+    addresses.sort_by_key(|a| (a.address, !a.is_stmt));
+    addresses.dedup_by_key(|a| a.address);
+    let canonical = addresses
+        .iter()
+        .find(|a| a.is_stmt)
+        .unwrap_or(&addresses[0])
+        .address;
+    Ok(LineAddresses {
+        line,
+        addresses,
+        canonical,
+    })
+}
+
+/// Returns the first address after the prologue of the function spanning `low_pc..low_pc + high_pc`.
+/// Uses the `prologue_end` flag of the line table if the compiler emitted it, otherwise the
+/// address of the second statement of the function (the same heuristic gdb uses).
+pub fn get_post_prologue_addr<T: Reader>(
+    dwarf: &IndexedDwarf<T>,
+    low_pc: u64,
+    high_pc: u64,
+) -> Result<u64, DebugError> {
+    let high_pc = low_pc + high_pc;
+    if let Some(prologue_end) = dwarf
+        .line_tables_containing(low_pc)
+        .find_map(|table| table.prologue_end(low_pc, high_pc))
+    {
+        return Ok(prologue_end);
+    }
+    Ok(dwarf
+        .line_tables_containing(low_pc)
+        .filter_map(|table| table.second_statement(low_pc, high_pc))
+        .min()
+        .unwrap_or(low_pc))
 }
 
 pub fn get_line_from_pc<T: Reader>(
-    dwarf: &gimli::Dwarf<T>,
+    dwarf: &IndexedDwarf<T>,
     pc: u64,
 ) -> Result<Location, DebugError> {
-    let mut units = dwarf.units();
-    while let Ok(Some(unit_header)) = units.next() {
-        if let Ok(unit) = dwarf.unit(unit_header) {
-            if let Some(line_program) = unit.line_program {
-                let mut rows = line_program.rows();
-                while let Ok(Some((header, row))) = rows.next_row() {
-                    if row.address() == pc {
-                        if let Some(file) = row.file(header) {
-                            if let Some(filename) = file.path_name().string_value(&dwarf.debug_str)
-                            {
-                                if let Ok(filename) = filename.to_string() {
-                                    return Ok(Location {
-                                        line: match row.line() {
-                                            Some(l) => l.into(),
-                                            None => 0,
-                                        },
-                                        file: filename.to_string(),
-                                        column: match row.column() {
-                                            gimli::ColumnType::LeftEdge => 0,
-                                            gimli::ColumnType::Column(c) => c.into(),
-                                        },
-                                    });
-                                }
-                            }
-                        }
-                        return Ok(Location {
-                            line: match row.line() {
-                                Some(l) => l.into(),
-                                None => 0,
-                            },
-                            file: String::new(),
-                            column: match row.column() {
-                                gimli::ColumnType::LeftEdge => 0,
-                                gimli::ColumnType::Column(c) => c.into(),
-                            },
-                        });
-                    }
-                }
-            }
-        }
-    }
-    Err(DebugError::NoSourceUnitFoundForCurrentPC)
+    dwarf
+        .line_tables_containing(pc)
+        .find_map(|table| table.row_at(pc))
+        .ok_or(DebugError::NoSourceUnitFoundForCurrentPC)
+}
+
+/// Like [`get_line_from_pc`] but also finds the line of addresses in the middle of a line,
+/// i.e. the last row of the line table at or before `addr`
+pub fn get_line_containing_addr<T: Reader>(
+    dwarf: &IndexedDwarf<T>,
+    addr: u64,
+) -> Result<Location, DebugError> {
+    dwarf
+        .line_tables_containing(addr)
+        .find_map(|table| table.row_containing(addr))
+        .ok_or(DebugError::NoSourceUnitFoundForCurrentPC)
 }
@@ -0,0 +1,248 @@
+use std::ops::Range;
+
+use stackium_shared::{format_value, Breakpoint, DataType, Registers, StopReason, Watch};
+use tracing::warn;
+
+use super::{
+    breakpoint::DebuggerBreakpoint, cancel::STEPS_BETWEEN_CHECKS, error::DebugError,
+    expression::Expression, registers::FromUserRegsStruct, util::find_function_from_name, Debugger,
+};
+
+/// Maximum number of instructions single-stepped by one watched continue
+const MAX_WATCHED_INSTRUCTIONS: u64 = 1_000_000;
+
+struct WatchedMemory {
+    watch: Watch,
+    types: DataType,
+    index: usize,
+    /// The bytes at the last check
+    memory: Vec<u8>,
+    /// Code of `watch.function`
+    code: Option<Range<u64>>,
+}
+
+/// Memory compared after every single-stepped instruction while continuing, a software
+/// replacement for hardware watchpoints which works everywhere, just slower
+#[derive(Default)]
+pub struct Watches {
+    watches: Vec<WatchedMemory>,
+}
+
+impl Watches {
+    pub fn is_empty(&self) -> bool {
+        self.watches.is_empty()
+    }
+
+    /// Code single-stepped while continuing, `None` if all of it is
+    fn stepped_code(&self) -> Option<Vec<Range<u64>>> {
+        self.watches.iter().map(|w| w.code.clone()).collect()
+    }
+}
+
+/// The value of watched memory, its bytes in hex if it isn't a number or pointer
+fn describe(types: &DataType, index: usize, memory: &[u8]) -> String {
+    format_value(types, index, memory).unwrap_or_else(|| {
+        memory
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ")
+    })
+}
+
+impl Debugger {
+    pub fn add_watch(
+        &mut self,
+        expression: String,
+        function: Option<String>,
+    ) -> Result<(), DebugError> {
+        if self
+            .watches
+            .watches
+            .iter()
+            .any(|w| w.watch.expression == expression)
+        {
+            return Ok(());
+        }
+        let variables = self.read_variables()?;
+        let (types, index, address, size) =
+            self.memory_of(&Expression::parse(&expression)?, &variables)?;
+        let code = match &function {
+            Some(name) => {
+                let meta = find_function_from_name(&self.dwarf, name.clone())?;
+                let (Some(low_pc), Some(length)) = (meta.low_pc, meta.high_pc) else {
+                    return Err(DebugError::FunctionNotFound);
+                };
+                Some(low_pc..low_pc + length)
+            }
+            None => None,
+        };
+        let memory = self.read_memory(address, size)?;
+        self.watches.watches.push(WatchedMemory {
+            watch: Watch {
+                value: describe(&types, index, &memory),
+                expression,
+                address,
+                size,
+                function,
+            },
+            types,
+            index,
+            memory,
+            code,
+        });
+        Ok(())
+    }
+
+    pub fn remove_watch(&mut self, expression: &str) -> Result<(), DebugError> {
+        let count = self.watches.watches.len();
+        self.watches
+            .watches
+            .retain(|w| w.watch.expression != expression);
+        match self.watches.watches.len() < count {
+            true => Ok(()),
+            false => Err(DebugError::InvalidArgument(format!(
+                "No watch for {}",
+                expression
+            ))),
+        }
+    }
+
+    pub fn get_watches(&self) -> Vec<Watch> {
+        self.watches
+            .watches
+            .iter()
+            .map(|w| w.watch.clone())
+            .collect()
+    }
+
+    pub fn has_watches(&self) -> bool {
+        !self.watches.is_empty()
+    }
+
+    /// Reads the watched memory again, the reason to stop for the first watch whose memory
+    /// changed since the last check
+    pub fn check_watches(&mut self) -> Option<StopReason> {
+        let memories = self
+            .watches
+            .watches
+            .iter()
+            .map(|w| self.read_memory(w.watch.address, w.watch.size).ok())
+            .collect::<Vec<_>>();
+        let mut changed = None;
+        for (watched, memory) in self.watches.watches.iter_mut().zip(memories) {
+            // memory which is unmapped now, e.g. after `free`, keeps its last value
+            let Some(memory) = memory.filter(|m| *m != watched.memory) else {
+                continue;
+            };
+            let new = describe(&watched.types, watched.index, &memory);
+            changed.get_or_insert_with(|| StopReason::WatchChanged {
+                expression: watched.watch.expression.clone(),
+                old: watched.watch.value.clone(),
+                new: new.clone(),
+            });
+            watched.watch.value = new;
+            watched.memory = memory;
+        }
+        changed
+    }
+
+    /// Continues until one of `addresses` is reached like [`Self::run_to`], or an earlier
+    /// breakpoint or the end of the program
+    fn run_to_any(&mut self, addresses: &[u64]) -> Result<(), DebugError> {
+        let mut temporary = vec![];
+        let mut enabled = vec![];
+        for address in addresses {
+            match self.breakpoints.iter().position(|b| b.address == *address) {
+                Some(i) if self.breakpoints[i].enabled => {}
+                Some(i) => {
+                    self.breakpoints[i].enable(self.child)?;
+                    enabled.push(i);
+                }
+                None => {
+                    let mut breakpoint =
+                        Breakpoint::new(&self.dwarf, self.child, *address as *const u8)?;
+                    breakpoint.enable(self.child)?;
+                    temporary.push(breakpoint);
+                }
+            }
+        }
+        self.continue_exec()?;
+        if self.has_exited() {
+            return Ok(());
+        }
+        self.memory.clear();
+        for mut breakpoint in temporary {
+            breakpoint.disable(self.child)?;
+        }
+        for i in enabled {
+            self.breakpoints[i].disable(self.child)?;
+        }
+        Ok(())
+    }
+
+    /// Continues by single-stepping the code of the watched functions until the memory of a
+    /// watch changes, a breakpoint is hit, an invariant breaks or the child exits. The other
+    /// code runs at full speed until it returns to or calls a watched function. Fails with
+    /// `Cancelled` if the command is cancelled while single-stepping.
+    pub fn continue_watched(&mut self) -> Result<(), DebugError> {
+        let stepped_code = self.watches.stepped_code();
+        let is_stepped = |pc: u64| {
+            stepped_code
+                .as_ref()
+                .map_or(true, |code| code.iter().any(|range| range.contains(&pc)))
+        };
+        let mut steps = 0;
+        while steps < MAX_WATCHED_INSTRUCTIONS {
+            if steps % STEPS_BETWEEN_CHECKS == 0 {
+                self.check_cancelled()?;
+            }
+            let pc = self.get_pc()?;
+            if is_stepped(pc) {
+                let before = Registers::from_regs(self.get_registers()?);
+                self.step_instruction()?;
+                steps += 1;
+                if self.has_exited() {
+                    return Ok(());
+                }
+                if let Some(return_address) = self.called_from(&before)? {
+                    if !is_stepped(self.get_pc()?) {
+                        self.run_to(return_address)?;
+                    }
+                }
+            } else {
+                let entries = stepped_code
+                    .iter()
+                    .flatten()
+                    .map(|range| range.start)
+                    .collect::<Vec<_>>();
+                self.run_to_any(&entries)?;
+            }
+            if self.has_exited() {
+                return Ok(());
+            }
+            if let Some(reason) = self.check_watches() {
+                self.stop_reason = reason;
+                return Ok(());
+            }
+            let pc = self.get_pc()?;
+            if self
+                .breakpoints
+                .iter()
+                .any(|b| b.enabled && b.address == pc)
+                || !self.check_invariants_at(pc)
+            {
+                return Ok(());
+            }
+            // stopped by a signal instead of a breakpoint or a step
+            if !matches!(self.stop_reason, StopReason::Step | StopReason::Breakpoint) {
+                return Ok(());
+            }
+        }
+        warn!(
+            "Stopped single-stepping after {} instructions without a watch changing",
+            MAX_WATCHED_INSTRUCTIONS
+        );
+        Ok(())
+    }
+}
@@ -7,27 +7,80 @@
 //!
 //! Options:
 //! * -m, --mode <MODE> [default: cli] [possible values: cli, web]
+//! * --sandbox         Limit CPU time, memory, files, network and processes of the program
+//! * --cpu-limit <CPU_LIMIT>       CPU time limit of the sandbox in seconds [default: 10]
+//! * --memory-limit <MEMORY_LIMIT> Memory limit of the sandbox in MiB [default: 256]
+//! * --dump-types    Print the decoded type of every variable in the program and exit
+//! * --read-only     Let web clients only observe the program, see below
+//! * --token <TOKEN> Instructor token of `--read-only` instead of a random one
+//! * --user-code     Only list the files and functions of the program, not of the libraries in it
+//! * --stop-at-entry Stop at the entry point of the program instead of `main`
+//! * --source-map <FROM=TO> Read the source files below FROM in TO, e.g. /build/src=/home/user/src
+//! * --allow-source <PATH> Let clients read the file or the files below the directory besides the
+//!   sources of the program
 //! * -h, --help        Print help
 //! * -V, --version     Print version
 //! ```
 //! Launch with `-m web` to expose the API on port `8080`.
 //! Have a look at the [crate::prompt::Command] struct for documentation on the API or
 //! inspect the JSON Schema on `/schema` (or in the [schema.json](./schema.json)) or `/response_schema`.
-use std::ffi::CStr;
-use std::path::PathBuf;
+//! POST a binary or C source file to `/launch?name=<file name>` to debug it instead of the
+//! current program. `/capabilities` returns the API version, the supported commands and the
+//! features of the backend. An OpenAPI document with examples of every command is served on
+//! `/openapi.json` (or in the [openapi.json](./openapi.json)) for generating clients.
+//! GET `/registers`, `/breakpoints`, `/variables`, `/location`, `/backtrace` and
+//! `/memory?addr=<address>&len=<length>` return the output of the corresponding command without
+//! wrapping it in a `CommandOutput`. Rust programs can use the `stackium_client` crate, which
+//! has an `async` method returning the typed output of every command.
+//!
+//! A guided exercise can be put next to the program in `<program>.exercise.json`: a `title` and
+//! `steps`, each with a `prompt`, the stop completing it in `until` (`{"Line": 10}`,
+//! `{"Function": "leaf"}` or `"Exited"`) and whether a `breakpoint` is set there. The UI shows
+//! the current step in a panel and advances once the program stops where the step expects,
+//! `GetExercise` returns the progress.
+//!
+//! For live demonstrations start the web server with `--read-only`: clients can follow the
+//! program, but commands changing it like breakpoints, stepping and writes are rejected unless
+//! the request carries the instructor token printed at startup in the `token` query parameter.
+//! The instructor sets the backend URL in the settings of the UI to
+//! `http://localhost:8080/?token=<token>`. `SetSourceMap` changes which files of the host are
+//! read, the web server only accepts it from the instructor.
+//!
+//! When several clients are connected, only one drives the session at a time: the first client
+//! sending a command that changes the program keeps control until it POSTs to `/driver/release`
+//! or sends no such command for a minute, the commands of the others are rejected with `409`
+//! meanwhile. Clients identify themselves with the `client` and `client_name` query parameters,
+//! GET `/driver` returns the name of the driver. The UI shows who drives the session, its name
+//! is set in the settings.
+//!
+//! Log messages are printed to stderr up to the level set in `STACKIUM_LOG` (`error`, `warn`,
+//! `info`, `debug` or `trace`), `warn` in release builds by default. The most recent messages
+//! are also served on `/logs` and returned by `GetDiagnostics` with the version and platform of
+//! the backend, attach them to bug reports. Both are only served to the instructor of
+//! `--read-only` and the client driving the session, the settings of the UI link to `/logs`.
+use std::ffi::CString;
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
 
 use clap::Parser;
 use debugger::error::DebugError;
 use nix::sys::ptrace;
 use nix::unistd::ForkResult::{Child, Parent};
-use nix::unistd::{execv, fork, getcwd, Pid};
+use nix::unistd::{fork, Pid};
+use stackium_shared::SourceMapping;
 #[cfg(feature = "web")]
-use web::start_webserver;
+use web::{start_webserver, ReadOnly};
 
 use crate::debugger::Debugger;
+use crate::sandbox::{PreparedSandbox, Sandbox};
 
 mod debugger;
+#[cfg(feature = "web")]
+mod openapi;
 mod prompt;
+mod sandbox;
+#[cfg(test)]
+mod tests;
 mod util;
 mod variables;
 #[cfg(feature = "web")]
@@ -50,73 +103,198 @@ struct Args {
     #[cfg_attr(feature = "web", clap(short, long, default_value = "web"))]
     #[cfg_attr(not(feature = "web"), clap(short, long, default_value = "cli"))]
     mode: DebugInterfaceMode,
+    /// Limit the CPU time, memory and file size of the debugee and run it in an empty
+    /// temporary directory, which is the only one it can write. It can only read the system
+    /// libraries besides it and can't use the network or start processes.
+    #[clap(long)]
+    sandbox: bool,
+    /// CPU time limit of the sandbox in seconds
+    #[clap(long, default_value_t = 10, requires = "sandbox")]
+    cpu_limit: u64,
+    /// Memory limit of the sandbox in MiB
+    #[clap(long, default_value_t = 256, requires = "sandbox")]
+    memory_limit: u64,
+    /// Print the decoded type of every variable in the program and exit
+    #[clap(long)]
+    dump_types: bool,
+    /// Let web clients only observe the debugee, changing it with breakpoints, stepping or
+    /// writes needs the instructor token printed at startup
+    #[clap(long)]
+    read_only: bool,
+    /// Instructor token of `--read-only` instead of a random one
+    #[clap(long, requires = "read_only")]
+    token: Option<String>,
+    /// Leave the code compiled outside the directory of `main`, like the C library of statically
+    /// linked programs, out of the files and functions
+    #[clap(long)]
+    user_code: bool,
+    /// Stop at the entry point of the program, before the dynamic linker and the startup code of
+    /// the C library ran, instead of running to `main`
+    #[clap(long)]
+    stop_at_entry: bool,
+    /// Read the source files the debug information places below FROM in TO instead, e.g. if the
+    /// program was compiled in a container. Can be given several times, the first match is used.
+    /// Clients may read the files below TO like those of `--allow-source`.
+    #[clap(long, value_name = "FROM=TO")]
+    source_map: Vec<SourceMapping>,
+    /// Let clients read the file or the files below the directory with `GetFile`, which only
+    /// reads the source files of the program otherwise. Can be given several times.
+    #[clap(long, value_name = "PATH")]
+    allow_source: Vec<PathBuf>,
 }
 
-pub fn debuggee_init(prog: PathBuf) -> Result<(), DebugError> {
-    match ptrace::traceme() {
-        Ok(_) => (),
-        Err(e) => {
-            println!("Error in ptrace::traceme: {}", e);
-            return Err(DebugError::NixError(e));
-        }
+/// The path as a C string for system calls
+pub fn c_path(path: &Path) -> Result<CString, DebugError> {
+    CString::new(path.as_os_str().as_bytes())
+        .map_err(|_| DebugError::InvalidArgument(format!("{} contains a NUL byte", path.display())))
+}
+
+/// The program and the sandbox of the debugee prepared before forking it. Until it executes
+/// the program, the child of the multithreaded web server may only call async-signal-safe
+/// functions, allocating or printing could wait for a lock another thread held while forking.
+pub struct DebuggeeExec {
+    program: CString,
+    sandbox: Option<PreparedSandbox>,
+}
+
+impl DebuggeeExec {
+    pub fn new(prog: &Path, sandbox: &Option<Sandbox>) -> Result<Self, DebugError> {
+        Ok(match sandbox {
+            Some(sandbox) => {
+                // resolve the path before changing into the sandbox directory
+                let program = std::fs::canonicalize(prog)?;
+                Self {
+                    sandbox: Some(sandbox.prepare(&program)?),
+                    program: c_path(&program)?,
+                }
+            }
+            None => Self {
+                program: c_path(prog)?,
+                sandbox: None,
+            },
+        })
     }
+}
 
+/// Turns the forked child into the debugee, exits with 127 if the program can't be executed
+pub fn debuggee_init(exec: &DebuggeeExec) -> ! {
+    if let Some(sandbox) = &exec.sandbox {
+        if sandbox::apply(sandbox).is_err() {
+            unsafe { nix::libc::_exit(127) };
+        }
+    }
+    if ptrace::traceme().is_err() {
+        unsafe { nix::libc::_exit(127) };
+    }
     // I think ASLR can't be disabled under macOS
     #[cfg(target_os = "linux")]
-    nix::sys::personality::set(nix::sys::personality::Persona::ADDR_NO_RANDOMIZE)?;
+    let _ = nix::sys::personality::set(nix::sys::personality::Persona::ADDR_NO_RANDOMIZE);
+    // `nix::unistd::execv` allocates the argument array
+    let argv = [exec.program.as_ptr(), std::ptr::null()];
+    unsafe {
+        nix::libc::execv(exec.program.as_ptr(), argv.as_ptr());
+        nix::libc::_exit(127)
+    }
+}
 
-    println!(
-        "Child running in {:?}",
-        getcwd().map_err(|e| DebugError::NixError(e))?
-    );
-    let path = format!("{}\0", prog.display());
-    let path = CStr::from_bytes_with_nul(path.as_bytes()).unwrap();
-    match execv(path, &[path]) {
-        Ok(e) => {
-            println!("Execv returned: {}", e);
-            Ok(())
-        }
-        Err(e) => {
-            println!("Error in execv: {}", nix::Error::from(e));
-            Err(DebugError::NixError(e))
-        }
+/// Compiles a C source file with the debug information the debugger needs, used by the `/launch`
+/// endpoint and the tests
+#[cfg(any(feature = "web", test))]
+pub fn compile_program(source: &std::path::Path, binary: &std::path::Path) -> Result<(), String> {
+    let output = std::process::Command::new("cc")
+        .args([
+            "-no-pie",
+            "-gdwarf-4",
+            "-fno-omit-frame-pointer",
+            "-O0",
+            "-o",
+        ])
+        .arg(binary)
+        .arg(source)
+        .output()
+        .map_err(|e| format!("Failed running cc: {}", e))?;
+    match output.status.success() {
+        true => Ok(()),
+        false => Err(String::from_utf8_lossy(&output.stderr).into_owned()),
     }
 }
 
-fn start_debuggee<'a>(prog: PathBuf) -> Result<Option<Debugger>, DebugError> {
+/// Starts debugging a program, used at startup, by the `/launch` endpoint and the tests
+pub fn launch_debuggee(
+    prog: PathBuf,
+    sandbox: Option<Sandbox>,
+    user_code: bool,
+    start_at_main: bool,
+) -> Result<Debugger, DebugError> {
+    let exec = DebuggeeExec::new(&prog, &sandbox)?;
     match unsafe { fork() } {
-        Ok(fr) => match fr {
-            Parent { child } => debugger_init(child, prog).map(|o| Some(o)),
-            Child => debuggee_init(prog).map(|_| None),
-        },
+        Ok(Parent { child }) => debugger_init(child, prog, sandbox, user_code, start_at_main),
+        Ok(Child) => debuggee_init(&exec),
         Err(e) => Err(DebugError::NixError(e)),
     }
 }
 
-pub fn debugger_init<'a>(child: Pid, prog: PathBuf) -> Result<Debugger, DebugError> {
+pub fn debugger_init(
+    child: Pid,
+    prog: PathBuf,
+    sandbox: Option<Sandbox>,
+    user_code: bool,
+    start_at_main: bool,
+) -> Result<Debugger, DebugError> {
     println!("Child pid: {}", child);
 
-    let debugger = Debugger::new(child, prog);
+    let mut debugger = Debugger::new(child, prog);
+    debugger.sandbox = sandbox;
+    debugger.user_code = user_code;
+    debugger.start_at_main = start_at_main;
     debugger.waitpid()?;
+    debugger.reset_maps();
+    debugger.start();
+    debugger.load_exercise();
     Ok(debugger)
 }
 
 fn main() -> Result<(), DebugError> {
+    debugger::diagnostics::init();
     let args = Args::parse();
-    let debugger = start_debuggee(args.program)?.unwrap();
+    let sandbox = match args.sandbox {
+        true => Some(Sandbox::new(args.cpu_limit, args.memory_limit)),
+        false => None,
+    };
+    let mut debugger = launch_debuggee(args.program, sandbox, args.user_code, !args.stop_at_entry)?;
+    debugger.allowed_sources = args.allow_source;
+    // the targets of the command line are trusted, unlike those of `SetSourceMap`
+    debugger.allowed_sources.extend(
+        args.source_map
+            .iter()
+            .map(|mapping| PathBuf::from(&mapping.to)),
+    );
+    debugger.set_source_map(args.source_map);
+    if args.dump_types {
+        print!("{}", debugger.dump_types()?);
+        debugger.terminate();
+        return Ok(());
+    }
+    #[cfg(feature = "web")]
+    let read_only = match args.read_only {
+        true => Some(ReadOnly::new(args.token)?),
+        false => None,
+    };
     match args.mode {
         DebugInterfaceMode::CLI => debugger.debug_loop(),
         #[cfg(feature = "web")]
-        DebugInterfaceMode::Web => start_webserver(debugger),
+        DebugInterfaceMode::Web => start_webserver(debugger, read_only),
         #[cfg(feature = "gui")]
         DebugInterfaceMode::Gui => match unsafe { fork() } {
             Ok(fr) => match fr {
-                Parent { child: _ } => start_webserver(debugger),
+                Parent { child: _ } => start_webserver(debugger, read_only),
                 Child => {
+                    // only the webserver process is tracing the child
+                    std::mem::forget(debugger);
                     match stackium_ui::start_ui() {
                         Ok(_) => {}
                         Err(e) => {
-                            println!("{:?}", e);
+                            tracing::error!("Failed starting the UI: {:?}", e);
                             panic!();
                         }
                     }
@@ -0,0 +1,329 @@
+use schemars::gen::SchemaSettings;
+use serde_json::{json, Map, Value};
+use stackium_shared::{
+    Breakpoint, Capabilities, Command, CommandOutput, DiscoveredVariable, Driver, Frame,
+    FullRegisters, Location,
+};
+
+/// Nested types deeper than this are left out of the examples
+const MAX_EXAMPLE_DEPTH: usize = 16;
+
+/// OpenAPI 3 document describing the web API, served on `/openapi.json`. The request and
+/// response types are generated from [`Command`] and [`CommandOutput`], every command and output
+/// has an example.
+pub fn openapi() -> Value {
+    let mut generator = SchemaSettings::openapi3().into_generator();
+    let command = generator.subschema_for::<Command>();
+    let output = generator.subschema_for::<CommandOutput>();
+    let capabilities = generator.subschema_for::<Capabilities>();
+    let driver = generator.subschema_for::<Option<Driver>>();
+    let registers = generator.subschema_for::<FullRegisters>();
+    let breakpoints = generator.subschema_for::<Vec<Breakpoint>>();
+    let variables = generator.subschema_for::<Vec<DiscoveredVariable>>();
+    let memory = generator.subschema_for::<Vec<u8>>();
+    let location = generator.subschema_for::<Location>();
+    let backtrace = generator.subschema_for::<Vec<Frame>>();
+    let schemas = generator
+        .definitions()
+        .iter()
+        .map(|(name, schema)| {
+            (
+                name.clone(),
+                serde_json::to_value(schema).unwrap_or_default(),
+            )
+        })
+        .collect::<Map<_, _>>();
+    let file = |content_type: &str, description: &str| {
+        json!({
+            "200": {
+                "description": description,
+                "content": { content_type: { "schema": { "type": "string" } } }
+            },
+            "500": error_response()
+        })
+    };
+    // the typed routes return the payload of the output of a single command
+    let payload = |summary: &str, schema: &schemars::schema::Schema| {
+        json!({ "get": {
+            "summary": summary,
+            "responses": {
+                "200": {
+                    "description": "The output of the command without the `CommandOutput` variant",
+                    "content": { "application/json": { "schema": schema } }
+                },
+                "500": error_response()
+            }
+        } })
+    };
+    json!({
+        "openapi": "3.0.3",
+        "info": {
+            "title": "Stackium",
+            "description": "Debugger for C programs built to visualize their memory. All commands are \
+                            sent to `/command`, frequently used outputs can also be read from \
+                            their own paths. The other paths export data or launch programs.",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": {
+            "/command": {
+                "post": {
+                    "summary": "Execute a command",
+                    "parameters": [
+                        client_parameter(),
+                        {
+                            "name": "request",
+                            "in": "query",
+                            "required": false,
+                            "description": "Id of the request, the same client cancels the command with `Cancel` and this id until it finished",
+                            "schema": { "type": "string" },
+                            "example": "42"
+                        }
+                    ],
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": {
+                            "schema": command,
+                            "examples": variant_examples(&schemas, "Command"),
+                        } }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "The output of the command",
+                            "content": { "application/json": {
+                                "schema": output,
+                                "examples": variant_examples(&schemas, "CommandOutput"),
+                            } }
+                        },
+                        "409": { "description": "Another client drives the session, see `/driver`" },
+                        "500": error_response()
+                    }
+                }
+            },
+            "/registers": payload("All registers, like the command `GetFullRegisters`", &registers),
+            "/breakpoints": payload("The breakpoints, like the command `GetBreakpoints`", &breakpoints),
+            "/variables": payload("The variables, like the command `DiscoverVariables`", &variables),
+            "/location": payload("The current location, like the command `Location`", &location),
+            "/backtrace": payload("The frames on the stack, like the command `Backtrace`", &backtrace),
+            "/memory": {
+                "get": {
+                    "summary": "Read memory of the program, like the command `ReadMemory`",
+                    "parameters": [
+                        {
+                            "name": "addr",
+                            "in": "query",
+                            "required": true,
+                            "description": "Address to read from, decimal or hexadecimal with `0x`",
+                            "schema": { "type": "string" },
+                            "example": "0x401000"
+                        },
+                        {
+                            "name": "len",
+                            "in": "query",
+                            "required": true,
+                            "description": "Number of bytes to read",
+                            "schema": { "type": "string" },
+                            "example": "16"
+                        }
+                    ],
+                    "responses": {
+                        "200": {
+                            "description": "The bytes",
+                            "content": { "application/json": { "schema": memory } }
+                        },
+                        "400": { "description": "A parameter is missing or not a number" },
+                        "500": error_response()
+                    }
+                }
+            },
+            "/capabilities": {
+                "get": {
+                    "summary": "Version, supported commands and features of the backend",
+                    "responses": { "200": {
+                        "description": "The capabilities",
+                        "content": { "application/json": { "schema": capabilities } }
+                    } }
+                }
+            },
+            "/driver": {
+                "get": {
+                    "summary": "The client driving the session, the commands of other clients changing the debugee are rejected with 409",
+                    "parameters": [client_parameter()],
+                    "responses": { "200": {
+                        "description": "The driver or `null`",
+                        "content": { "application/json": { "schema": driver } }
+                    } }
+                }
+            },
+            "/driver/release": {
+                "post": {
+                    "summary": "Let other clients drive the session",
+                    "parameters": [client_parameter()],
+                    "responses": { "200": { "description": "The session is not driven by the client" } }
+                }
+            },
+            "/launch": {
+                "post": {
+                    "summary": "Debug an uploaded binary or C source file instead of the current program",
+                    "parameters": [{
+                        "name": "name",
+                        "in": "query",
+                        "required": true,
+                        "description": "File name of the upload, `.c` files are compiled with debug information",
+                        "schema": { "type": "string" },
+                        "example": "main.c"
+                    }],
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/octet-stream": { "schema": { "type": "string", "format": "binary" } } }
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "Path of the launched binary",
+                            "content": { "text/plain": { "schema": { "type": "string" } } }
+                        },
+                        "400": { "description": "The upload couldn't be stored or compiled" },
+                        "413": { "description": "The program is too large" },
+                        "500": error_response()
+                    }
+                }
+            },
+            "/export/variables.json": {
+                "get": { "summary": "Download the variables as JSON", "responses": file("application/json", "The variables") }
+            },
+            "/export/variables.csv": {
+                "get": { "summary": "Download the variables as CSV", "responses": file("text/csv", "The variables") }
+            },
+            "/export/session.json": {
+                "get": { "summary": "Download a recording of the session", "responses": file("application/json", "The session") }
+            },
+            "/export/snapshot.json": {
+                "get": { "summary": "Download a snapshot of the current stop", "responses": file("application/json", "The snapshot") }
+            },
+            "/export/snapshot.json.gz": {
+                "get": { "summary": "Download a compressed snapshot of the current stop", "responses": file("application/gzip", "The snapshot") }
+            },
+            "/schema": {
+                "get": { "summary": "JSON schema of the commands", "responses": file("application/json", "The schema") }
+            },
+            "/response_schema": {
+                "get": { "summary": "JSON schema of the command outputs", "responses": file("application/json", "The schema") }
+            },
+            "/openapi.json": {
+                "get": { "summary": "This document", "responses": file("application/json", "The OpenAPI document") }
+            },
+            "/ping": {
+                "get": { "summary": "Check whether the backend is running", "responses": file("text/plain", "`pong`") }
+            },
+            "/logs": {
+                "get": { "summary": "The recent log messages of the debugger, to be attached to bug reports", "responses": file("text/plain", "One message per line, the oldest comes first") }
+            }
+        },
+        "components": { "schemas": schemas }
+    })
+}
+
+fn client_parameter() -> Value {
+    json!({
+        "name": "client",
+        "in": "query",
+        "required": false,
+        "description": "Id of the client, sent with every request together with the `client_name` shown to the other clients",
+        "schema": { "type": "string" },
+        "example": "4f1c2a"
+    })
+}
+
+fn error_response() -> Value {
+    json!({
+        "description": "The command failed, the body describes the error",
+        "content": { "text/plain": { "schema": { "type": "string" } } }
+    })
+}
+
+/// An example for every variant of the enum schema `name`, named after the variant
+fn variant_examples(schemas: &Map<String, Value>, name: &str) -> Value {
+    let variants = schemas
+        .get(name)
+        .and_then(|schema| schema["oneOf"].as_array())
+        .cloned()
+        .unwrap_or_default();
+    let mut examples = Map::new();
+    // unit variants of externally tagged enums share one schema listing their names
+    let variants = variants
+        .into_iter()
+        .flat_map(|variant| match variant["enum"].as_array() {
+            Some(names) => names
+                .iter()
+                .map(|name| json!({ "description": variant["description"], "enum": [name] }))
+                .collect(),
+            None => vec![variant],
+        });
+    for variant in variants {
+        let value = example(&variant, schemas, 0);
+        // `{"Command": "Name", ..}` for commands, `{"Name": ..}` or `"Name"` for outputs
+        let variant_name = match &value {
+            Value::String(name) => name.clone(),
+            Value::Object(object) => match object.get("Command") {
+                Some(Value::String(name)) => name.clone(),
+                _ => object.keys().next().cloned().unwrap_or_default(),
+            },
+            _ => continue,
+        };
+        let mut entry = Map::new();
+        if let Some(description) = variant["description"].as_str() {
+            entry.insert("summary".to_owned(), description.into());
+        }
+        entry.insert("value".to_owned(), value);
+        examples.insert(variant_name, entry.into());
+    }
+    examples.into()
+}
+
+/// Smallest value matching `schema`: the first variant of enums, zero for numbers and the
+/// required fields of objects
+fn example(schema: &Value, schemas: &Map<String, Value>, depth: usize) -> Value {
+    if depth > MAX_EXAMPLE_DEPTH {
+        return Value::Null;
+    }
+    if let Some(reference) = schema["$ref"].as_str() {
+        let name = reference.trim_start_matches("#/components/schemas/");
+        return match schemas.get(name) {
+            Some(schema) => example(schema, schemas, depth + 1),
+            None => Value::Null,
+        };
+    }
+    if let Some(value) = schema["enum"].as_array().and_then(|values| values.first()) {
+        return value.clone();
+    }
+    for key in ["oneOf", "anyOf", "allOf"] {
+        if let Some(first) = schema[key].as_array().and_then(|schemas| schemas.first()) {
+            return example(first, schemas, depth + 1);
+        }
+    }
+    match schema["type"].as_str() {
+        Some("integer") | Some("number") => json!(0),
+        Some("boolean") => json!(false),
+        Some("string") => json!(""),
+        Some("array") => match &schema["items"] {
+            // tuples list the schema of every element
+            Value::Array(items) => items
+                .iter()
+                .map(|item| example(item, schemas, depth + 1))
+                .collect(),
+            _ => json!([]),
+        },
+        Some("object") => schema["properties"]
+            .as_object()
+            .map(|properties| {
+                let required = schema["required"].as_array().cloned().unwrap_or_default();
+                properties
+                    .iter()
+                    .filter(|(name, _)| required.iter().any(|r| r.as_str() == Some(name)))
+                    .map(|(name, property)| (name.clone(), example(property, schemas, depth + 1)))
+                    .collect::<Map<_, _>>()
+                    .into()
+            })
+            .unwrap_or(json!({})),
+        _ => Value::Null,
+    }
+}
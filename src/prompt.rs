@@ -14,28 +14,89 @@ impl Default for CommandCompleter {
         CommandCompleter {
             commands: vec![
                 "get_functions".to_string(),
+                "find_functions".to_string(),
+                "find_user_functions".to_string(),
                 "location".to_string(),
                 "continue".to_string(),
                 "delete_breakpoint".to_string(),
                 "disassemble".to_string(),
+                "disassemble_function".to_string(),
+                "resolve_address".to_string(),
+                "plt".to_string(),
+                "maps_diff".to_string(),
+                "get_process_stack".to_string(),
                 "quit".to_string(),
+                "interrupt".to_string(),
+                "status".to_string(),
+                "set_timeout".to_string(),
+                "set_skip_list".to_string(),
+                "set_source_map".to_string(),
+                "detach".to_string(),
                 "src".to_string(),
+                "source".to_string(),
                 "get_breakpoints".to_string(),
+                "undo".to_string(),
+                "get_actions".to_string(),
                 "help".to_string(),
                 "backtrace".to_string(),
+                "stack_frames".to_string(),
+                "variable_scopes".to_string(),
                 "debug_meta".to_string(),
+                "elf".to_string(),
                 "read_variables".to_string(),
                 "set_breakpoint".to_string(),
                 "read".to_string(),
+                "write_memory".to_string(),
+                "read_around".to_string(),
                 "step_in".to_string(),
+                "step_statement".to_string(),
+                "step_into_target".to_string(),
+                "line_calls".to_string(),
                 "get_registers".to_string(),
+                "get_full_registers".to_string(),
+                "set_register".to_string(),
+                "explain_branch".to_string(),
                 "waitpid".to_string(),
                 "find_func".to_string(),
                 "find_line".to_string(),
                 "pc".to_string(),
                 "step_out".to_string(),
+                "finish_frames".to_string(),
+                "break_on_allocation".to_string(),
+                "break_on_io".to_string(),
                 "step_instruction".to_string(),
+                "step_instruction_n".to_string(),
                 "dump_dwarf".to_string(),
+                "dump_types".to_string(),
+                "export_variables".to_string(),
+                "export_snapshot".to_string(),
+                "generate_report".to_string(),
+                "export_session".to_string(),
+                "find_references".to_string(),
+                "pointer_info".to_string(),
+                "track_variable".to_string(),
+                "untrack_variable".to_string(),
+                "variable_history".to_string(),
+                "trace".to_string(),
+                "execution_trace".to_string(),
+                "line_hits".to_string(),
+                "invariant".to_string(),
+                "remove_invariant".to_string(),
+                "invariants".to_string(),
+                "watch".to_string(),
+                "watch_in".to_string(),
+                "remove_watch".to_string(),
+                "watches".to_string(),
+                "data_structures".to_string(),
+                "profile".to_string(),
+                "get_profile".to_string(),
+                "get_exercise".to_string(),
+                "quiz_value".to_string(),
+                "quiz_target".to_string(),
+                "get_quiz".to_string(),
+                "answer_quiz".to_string(),
+                "cancel".to_string(),
+                "get_diagnostics".to_string(),
             ],
         }
     }
@@ -0,0 +1,318 @@
+use std::{
+    ffi::CString,
+    path::{Path, PathBuf},
+};
+
+use nix::{
+    errno::Errno,
+    libc::{self, c_int, sock_filter, sock_fprog},
+    sys::resource::{setrlimit, Resource},
+};
+
+use crate::{c_path, debugger::error::DebugError};
+
+const MIB: u64 = 1024 * 1024;
+
+/// A resource with its soft and hard limit
+type Limit = (Resource, u64, u64);
+
+const LANDLOCK_CREATE_RULESET_VERSION: u32 = 1;
+const LANDLOCK_RULE_PATH_BENEATH: c_int = 1;
+const ACCESS_EXECUTE: u64 = 1 << 0;
+const ACCESS_READ_FILE: u64 = 1 << 2;
+const ACCESS_READ_DIR: u64 = 1 << 3;
+/// Every access right of the first Landlock ABI, which the sandbox restricts
+const ACCESS_ALL: u64 = (1 << 13) - 1;
+
+/// `struct landlock_ruleset_attr` of `linux/landlock.h`
+#[repr(C)]
+struct RulesetAttr {
+    handled_access_fs: u64,
+}
+
+/// `struct landlock_path_beneath_attr` of `linux/landlock.h`
+#[repr(C, packed)]
+struct PathBeneathAttr {
+    allowed_access: u64,
+    parent_fd: c_int,
+}
+
+#[cfg(target_arch = "x86_64")]
+const AUDIT_ARCH: u32 = 0xc000_003e;
+#[cfg(target_arch = "aarch64")]
+const AUDIT_ARCH: u32 = 0xc000_00b7;
+/// Offsets of `nr`, `arch` and the first argument in `struct seccomp_data`
+const SECCOMP_NR: u32 = 0;
+const SECCOMP_ARCH: u32 = 4;
+const SECCOMP_ARG0: u32 = 16;
+
+/// Limits applied to the debugee before it is executed, enabled with `--sandbox` so untrusted
+/// programs can be debugged on a shared server. Besides limiting its resources, the debugee
+/// can only read the `readable` paths and write its working directory (Landlock), and it can't
+/// open sockets or start processes (seccomp).
+#[derive(Debug, Clone)]
+pub struct Sandbox {
+    /// CPU time in seconds, the time the debugee is stopped doesn't count
+    pub cpu_seconds: u64,
+    /// Size of the address space in bytes
+    pub memory: u64,
+    /// Largest file the debugee may write in bytes
+    pub file_size: u64,
+    pub open_files: u64,
+    /// Empty working directory of the debugee
+    pub directory: PathBuf,
+    /// Files and directories besides the program the debugee may read, e.g. the shared
+    /// libraries. Those which don't exist are skipped.
+    pub readable: Vec<PathBuf>,
+}
+
+/// A [`Sandbox`] prepared before forking the debugee, see [`apply`]
+pub struct PreparedSandbox {
+    directory: CString,
+    limits: Vec<Limit>,
+    /// Paths the debugee may access with their Landlock access rights
+    paths: Vec<(CString, u64)>,
+    filter: Vec<sock_filter>,
+}
+
+impl Sandbox {
+    pub fn new(cpu_seconds: u64, memory_mib: u64) -> Self {
+        Self {
+            cpu_seconds,
+            memory: memory_mib * MIB,
+            file_size: MIB,
+            open_files: 64,
+            directory: std::env::temp_dir()
+                .join("stackium")
+                .join(format!("sandbox-{}", std::process::id())),
+            readable: [
+                "/usr",
+                "/lib",
+                "/lib64",
+                "/bin",
+                "/etc/ld.so.cache",
+                "/dev/null",
+                "/dev/zero",
+                "/dev/urandom",
+            ]
+            .into_iter()
+            .map(PathBuf::from)
+            .collect(),
+        }
+    }
+
+    /// Creates the working directory and prepares the restrictions of executing `program`,
+    /// called before forking the debugee. Fails if the kernel doesn't support Landlock.
+    pub fn prepare(&self, program: &Path) -> Result<PreparedSandbox, DebugError> {
+        let version = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_create_ruleset,
+                std::ptr::null::<RulesetAttr>(),
+                0,
+                LANDLOCK_CREATE_RULESET_VERSION,
+            )
+        };
+        if version < 1 {
+            return Err(DebugError::InvalidArgument(
+                "--sandbox needs Landlock to restrict the files of the debugee, which Linux supports since 5.13".to_owned(),
+            ));
+        }
+        std::fs::create_dir_all(&self.directory)?;
+        let mut paths = vec![
+            (c_path(&self.directory)?, ACCESS_ALL),
+            (c_path(program)?, ACCESS_EXECUTE | ACCESS_READ_FILE),
+        ];
+        for path in &self.readable {
+            let access = match std::fs::metadata(path) {
+                Ok(metadata) if metadata.is_dir() => {
+                    ACCESS_EXECUTE | ACCESS_READ_FILE | ACCESS_READ_DIR
+                }
+                Ok(_) => ACCESS_EXECUTE | ACCESS_READ_FILE,
+                Err(_) => continue,
+            };
+            paths.push((c_path(path)?, access));
+        }
+        Ok(PreparedSandbox {
+            directory: c_path(&self.directory)?,
+            limits: self.limits(),
+            paths,
+            filter: seccomp_filter(),
+        })
+    }
+
+    /// Soft and hard limit of every resource the sandbox limits
+    fn limits(&self) -> Vec<Limit> {
+        vec![
+            // SIGXCPU is swallowed when continuing, the hard limit kills the debugee
+            (Resource::RLIMIT_CPU, self.cpu_seconds, self.cpu_seconds + 1),
+            (Resource::RLIMIT_AS, self.memory, self.memory),
+            (Resource::RLIMIT_FSIZE, self.file_size, self.file_size),
+            (Resource::RLIMIT_NOFILE, self.open_files, self.open_files),
+            (Resource::RLIMIT_CORE, 0, 0),
+        ]
+    }
+}
+
+/// Where a jump of the seccomp filter continues
+#[derive(Clone, Copy, PartialEq)]
+enum Target {
+    Next,
+    /// Skips the given number of instructions
+    Skip(u8),
+    /// Returns the action, e.g. `SECCOMP_RET_ALLOW`
+    Return(u32),
+}
+
+/// Seccomp filter refusing sockets and new processes. Threads can still be started, `clone3` is
+/// refused with `ENOSYS` because its flags can't be checked, so the C library falls back to
+/// `clone`.
+fn seccomp_filter() -> Vec<sock_filter> {
+    use Target::*;
+    const LOAD: u32 = libc::BPF_LD | libc::BPF_W | libc::BPF_ABS;
+    const JEQ: u32 = libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K;
+    const JSET: u32 = libc::BPF_JMP | libc::BPF_JSET | libc::BPF_K;
+    let allow = Return(libc::SECCOMP_RET_ALLOW);
+    let kill = Return(libc::SECCOMP_RET_KILL_PROCESS);
+    let error = |errno: Errno| Return(libc::SECCOMP_RET_ERRNO | errno as u32);
+    let mut checks = vec![
+        (LOAD, SECCOMP_ARCH, Next, Next),
+        (JEQ, AUDIT_ARCH, Next, kill),
+        (LOAD, SECCOMP_NR, Next, Next),
+    ];
+    // the x32 system calls of x86_64 have the same architecture
+    #[cfg(target_arch = "x86_64")]
+    checks.push((
+        libc::BPF_JMP | libc::BPF_JGE | libc::BPF_K,
+        0x4000_0000,
+        kill,
+        Next,
+    ));
+    checks.extend([
+        (JEQ, libc::SYS_socket as u32, error(Errno::EACCES), Next),
+        (JEQ, libc::SYS_clone3 as u32, error(Errno::ENOSYS), Next),
+        (JEQ, libc::SYS_clone as u32, Next, Skip(2)),
+        (LOAD, SECCOMP_ARG0, Next, Next),
+        (JSET, libc::CLONE_THREAD as u32, allow, error(Errno::EPERM)),
+    ]);
+    #[cfg(target_arch = "x86_64")]
+    checks.extend([
+        (JEQ, libc::SYS_fork as u32, error(Errno::EPERM), Next),
+        (JEQ, libc::SYS_vfork as u32, error(Errno::EPERM), Next),
+    ]);
+    // the returns follow the checks, the first one is reached by falling through
+    let mut returns = vec![libc::SECCOMP_RET_ALLOW];
+    for (_, _, jt, jf) in &checks {
+        for target in [jt, jf] {
+            if let Return(action) = target {
+                if !returns.contains(action) {
+                    returns.push(*action);
+                }
+            }
+        }
+    }
+    let offset = |index: usize, target: Target| match target {
+        Next => 0,
+        Skip(count) => count,
+        Return(action) => {
+            let position = returns.iter().position(|a| *a == action).unwrap();
+            (checks.len() + position - index - 1) as u8
+        }
+    };
+    let mut filter: Vec<sock_filter> = checks
+        .iter()
+        .enumerate()
+        .map(|(index, (code, k, jt, jf))| sock_filter {
+            code: *code as u16,
+            jt: offset(index, *jt),
+            jf: offset(index, *jf),
+            k: *k,
+        })
+        .collect();
+    filter.extend(returns.iter().map(|action| sock_filter {
+        code: (libc::BPF_RET | libc::BPF_K) as u16,
+        jt: 0,
+        jf: 0,
+        k: *action,
+    }));
+    filter
+}
+
+/// Changes into the directory of the sandbox, applies its limits and restricts the files and
+/// system calls of the debugee, called in the forked child before executing the program. Only
+/// makes async-signal-safe system calls.
+pub fn apply(sandbox: &PreparedSandbox) -> Result<(), DebugError> {
+    if unsafe { libc::chdir(sandbox.directory.as_ptr()) } != 0 {
+        return Err(DebugError::NixError(Errno::last()));
+    }
+    for (resource, soft, hard) in &sandbox.limits {
+        setrlimit(*resource, *soft, *hard)?;
+    }
+    // setuid binaries can't gain privileges, which Landlock and seccomp require as well
+    if unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) } != 0 {
+        return Err(DebugError::NixError(Errno::last()));
+    }
+    restrict_paths(&sandbox.paths)?;
+    let program = sock_fprog {
+        len: sandbox.filter.len() as u16,
+        filter: sandbox.filter.as_ptr() as *mut sock_filter,
+    };
+    let filtered = unsafe {
+        libc::prctl(
+            libc::PR_SET_SECCOMP,
+            libc::SECCOMP_MODE_FILTER,
+            &program as *const sock_fprog,
+        )
+    };
+    if filtered != 0 {
+        return Err(DebugError::NixError(Errno::last()));
+    }
+    Ok(())
+}
+
+/// Lets the calling process only access `paths` with their access rights
+fn restrict_paths(paths: &[(CString, u64)]) -> Result<(), DebugError> {
+    let attr = RulesetAttr {
+        handled_access_fs: ACCESS_ALL,
+    };
+    let ruleset = unsafe {
+        libc::syscall(
+            libc::SYS_landlock_create_ruleset,
+            &attr as *const RulesetAttr,
+            std::mem::size_of::<RulesetAttr>(),
+            0,
+        )
+    };
+    if ruleset < 0 {
+        return Err(DebugError::NixError(Errno::last()));
+    }
+    let ruleset = ruleset as c_int;
+    for (path, access) in paths {
+        let parent_fd = unsafe { libc::open(path.as_ptr(), libc::O_PATH | libc::O_CLOEXEC) };
+        if parent_fd < 0 {
+            return Err(DebugError::NixError(Errno::last()));
+        }
+        let rule = PathBeneathAttr {
+            allowed_access: *access,
+            parent_fd,
+        };
+        let added = unsafe {
+            libc::syscall(
+                libc::SYS_landlock_add_rule,
+                ruleset,
+                LANDLOCK_RULE_PATH_BENEATH,
+                &rule as *const PathBeneathAttr,
+                0,
+            )
+        };
+        let error = Errno::last();
+        unsafe { libc::close(parent_fd) };
+        if added != 0 {
+            return Err(DebugError::NixError(error));
+        }
+    }
+    if unsafe { libc::syscall(libc::SYS_landlock_restrict_self, ruleset, 0) } != 0 {
+        return Err(DebugError::NixError(Errno::last()));
+    }
+    unsafe { libc::close(ruleset) };
+    Ok(())
+}
@@ -0,0 +1,1225 @@
+//! Runs the debugger against the C programs in `tests/fixtures`, which are compiled when a
+//! test starts
+use std::{
+    path::{Path, PathBuf},
+    sync::{Mutex, MutexGuard},
+};
+
+use stackium_shared::{
+    BreakpointPoint, Command, CommandOutput, DiscoveredVariable, Frame, Location, QuizKind,
+    QuizQuestion, ReportFormat, SourceMapping, SourceOrigin, StackFrame, StopReason, TypeName,
+    Variable, VARIABLE_MEM_PADDING,
+};
+
+use crate::{
+    compile_program,
+    debugger::{cancel::Cancellation, error::DebugError, Debugger},
+    launch_debuggee,
+    sandbox::Sandbox,
+};
+
+/// Forking while another test traces its child can deadlock the forked process, so only one
+/// fixture runs at a time
+static DEBUGGING: Mutex<()> = Mutex::new(());
+
+/// A fixture program stopped at its entry point
+struct Fixture {
+    name: &'static str,
+    debugger: Debugger,
+    _running: MutexGuard<'static, ()>,
+}
+
+impl Fixture {
+    fn launch(name: &'static str) -> Self {
+        Self::launch_compiled(name, |source, binary| {
+            compile_program(source, binary).unwrap()
+        })
+    }
+
+    /// Compiles the fixture with `cc` and the `flags` instead of the flags of the `/launch`
+    /// endpoint
+    fn launch_with_flags(name: &'static str, flags: &[&str]) -> Self {
+        Self::launch_compiled(name, |source, binary| {
+            let status = std::process::Command::new("cc")
+                .args(flags)
+                .arg("-o")
+                .arg(binary)
+                .arg(source)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        })
+    }
+
+    fn launch_compiled(name: &'static str, compile: impl FnOnce(&Path, &Path)) -> Self {
+        // a failed test poisons the lock without affecting the others
+        let running = DEBUGGING.lock().unwrap_or_else(|e| e.into_inner());
+        let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+        let source = match fixtures.join(format!("{}.cpp", name)) {
+            cpp if cpp.exists() => cpp,
+            _ => fixtures.join(format!("{}.c", name)),
+        };
+        let dir = std::env::temp_dir()
+            .join("stackium-tests")
+            .join(std::process::id().to_string());
+        std::fs::create_dir_all(&dir).unwrap();
+        let binary = dir.join(name);
+        compile(&source, &binary);
+        Self {
+            name,
+            debugger: launch_debuggee(binary, None, false, false).unwrap(),
+            _running: running,
+        }
+    }
+
+    fn run(&mut self, command: Command) -> CommandOutput {
+        self.debugger.process_command(command).unwrap()
+    }
+
+    fn break_at_line(&mut self, line: u64) {
+        let file = format!("{}.c", self.name);
+        self.run(Command::SetBreakpoint(BreakpointPoint::Location(
+            Location {
+                line,
+                file,
+                column: 0,
+            },
+        )));
+    }
+
+    fn break_at_function(&mut self, name: &str) {
+        self.run(Command::SetBreakpoint(BreakpointPoint::Name(
+            name.to_owned(),
+        )));
+    }
+
+    fn continue_to_breakpoint(&mut self) {
+        match self.run(Command::Continue) {
+            CommandOutput::StopState(_) => {}
+            output => panic!("expected the program to stop, got {:?}", output),
+        }
+    }
+
+    #[allow(deprecated)]
+    fn read_variables(&mut self) -> Vec<Variable> {
+        match self.run(Command::ReadVariables) {
+            CommandOutput::Variables(variables) => variables,
+            output => panic!("unexpected output {:?}", output),
+        }
+    }
+
+    fn discover_variables(&mut self) -> Vec<DiscoveredVariable> {
+        match self.run(Command::DiscoverVariables) {
+            CommandOutput::DiscoveredVariables(variables) => variables,
+            output => panic!("unexpected output {:?}", output),
+        }
+    }
+
+    fn read_memory(&mut self, addr: u64, len: u64) -> Vec<u8> {
+        match self.run(Command::ReadMemory(addr, len)) {
+            CommandOutput::Memory(memory) => memory,
+            output => panic!("unexpected output {:?}", output),
+        }
+    }
+
+    fn backtrace(&mut self) -> Vec<Frame> {
+        match self.run(Command::Backtrace) {
+            CommandOutput::Backtrace(frames) => frames,
+            output => panic!("unexpected output {:?}", output),
+        }
+    }
+
+    fn answer_quiz(&mut self, expression: &str, answer: &str) -> bool {
+        match self.run(Command::AnswerQuiz {
+            expression: expression.to_owned(),
+            answer: answer.to_owned(),
+        }) {
+            CommandOutput::QuizAnswer(answer) => answer.correct,
+            output => panic!("unexpected output {:?}", output),
+        }
+    }
+
+    fn dump_types(&mut self) -> String {
+        match self.run(Command::DumpTypes) {
+            CommandOutput::File(dump) => dump,
+            output => panic!("unexpected output {:?}", output),
+        }
+    }
+
+    fn stack_frames(&mut self) -> Vec<StackFrame> {
+        match self.run(Command::GetStackFrames) {
+            CommandOutput::StackFrames(frames) => frames,
+            output => panic!("unexpected output {:?}", output),
+        }
+    }
+}
+
+impl Drop for Fixture {
+    fn drop(&mut self) {
+        self.debugger.terminate();
+    }
+}
+
+fn variable<'a>(variables: &'a [Variable], name: &str) -> &'a Variable {
+    variables
+        .iter()
+        .find(|v| v.name.as_deref() == Some(name))
+        .unwrap_or_else(|| panic!("no variable {} in {:?}", name, variables))
+}
+
+fn discovered<'a>(variables: &'a [DiscoveredVariable], name: &str) -> &'a DiscoveredVariable {
+    variables
+        .iter()
+        .find(|v| v.name.as_deref() == Some(name))
+        .unwrap_or_else(|| panic!("no variable {} in {:?}", name, variables))
+}
+
+/// The `int` stored in the lower half of the value of a variable
+fn int_value(variable: &Variable) -> i32 {
+    variable.value.unwrap() as u32 as i32
+}
+
+/// The `int`s at the start of the memory of a discovered variable
+fn ints(variable: &DiscoveredVariable, count: usize) -> Vec<i32> {
+    let memory = &variable.memory.as_ref().unwrap()[VARIABLE_MEM_PADDING as usize..];
+    memory
+        .chunks(4)
+        .take(count)
+        .map(|bytes| i32::from_le_bytes(bytes.try_into().unwrap()))
+        .collect()
+}
+
+/// Compares the type dump of a fixture with `tests/snapshots/<name>.types`, running the tests
+/// with `UPDATE_SNAPSHOTS=1` writes the current dumps to the snapshots instead
+fn assert_types_snapshot(name: &'static str) {
+    let dump = Fixture::launch(name).dump_types();
+    let snapshot = PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/snapshots")
+        .join(format!("{}.types", name));
+    if std::env::var("UPDATE_SNAPSHOTS").is_ok_and(|v| v == "1") {
+        std::fs::write(&snapshot, &dump).unwrap();
+        return;
+    }
+    let expected = std::fs::read_to_string(&snapshot).unwrap_or_default();
+    assert!(
+        dump == expected,
+        "the types of {} differ from {}, rerun with UPDATE_SNAPSHOTS=1 if the change is \
+         intended\n--- expected\n{}\n--- decoded\n{}",
+        name,
+        snapshot.display(),
+        expected,
+        dump
+    );
+}
+
+fn functions(frames: &[Frame]) -> Vec<&str> {
+    frames
+        .iter()
+        .map(|f| f.function.as_deref().unwrap_or("??"))
+        .collect()
+}
+
+#[test]
+fn pointers_point_to_their_targets() {
+    let mut fixture = Fixture::launch("pointers");
+    fixture.break_at_line(8);
+    fixture.continue_to_breakpoint();
+    let variables = fixture.read_variables();
+    let value = variable(&variables, "value");
+    let pointer = variable(&variables, "pointer");
+    let numbers = variable(&variables, "numbers");
+    assert_eq!(int_value(value), 42);
+    assert_eq!(pointer.value, value.addr);
+    assert_eq!(variable(&variables, "double_pointer").value, pointer.addr);
+    assert_eq!(
+        variable(&variables, "element").value,
+        numbers.addr.map(|a| a + 8)
+    );
+    assert_eq!(int_value(numbers), 1);
+    assert!(matches!(
+        numbers.type_name.as_ref().unwrap().0[0].1,
+        TypeName::Arr { ref count, .. } if count == &[4]
+    ));
+}
+
+#[test]
+fn pointers_are_dereferenced() {
+    let mut fixture = Fixture::launch("pointers");
+    fixture.break_at_line(8);
+    fixture.continue_to_breakpoint();
+    let variables = fixture.discover_variables();
+    let value = discovered(&variables, "value");
+    assert_eq!(discovered(&variables, "*pointer").addr, value.addr);
+    let target = discovered(&variables, "**double_pointer");
+    assert_eq!(target.addr, value.addr);
+    assert_eq!(ints(target, 1), [42]);
+    assert_eq!(ints(discovered(&variables, "numbers"), 4), [1, 2, 42, 4]);
+    assert_eq!(ints(discovered(&variables, "*element"), 2), [42, 4]);
+}
+
+#[test]
+fn pointers_with_wrong_arithmetic_have_warnings() {
+    let mut fixture = Fixture::launch("arithmetic");
+    fixture.break_at_line(6);
+    fixture.continue_to_breakpoint();
+    let variables = fixture.discover_variables();
+    assert!(discovered(&variables, "first").warnings.is_empty());
+    let misaligned = &discovered(&variables, "misaligned").warnings;
+    assert_eq!(misaligned.len(), 1);
+    assert!(misaligned[0].ends_with("which isn't aligned to the 2 bytes of short int"));
+    assert_eq!(
+        discovered(&variables, "end").warnings,
+        ["points to numbers[4] past the end of its 4 elements, which can't be dereferenced"]
+    );
+}
+
+#[test]
+fn pointer_expressions_out_of_range_are_refused() {
+    let mut fixture = Fixture::launch("arithmetic");
+    fixture.break_at_line(6);
+    fixture.continue_to_breakpoint();
+    let CommandOutput::PointerInfo(info) =
+        fixture.run(Command::PointerInfo("numbers + 1".to_owned()))
+    else {
+        panic!("expected the pointer info");
+    };
+    let CommandOutput::PointerInfo(numbers) =
+        fixture.run(Command::PointerInfo("numbers".to_owned()))
+    else {
+        panic!("expected the pointer info");
+    };
+    assert_eq!(info.address, numbers.address + 2);
+    for expression in ["numbers + 9223372036854775807", "0x10 - 17"] {
+        assert!(matches!(
+            fixture
+                .debugger
+                .process_command(Command::PointerInfo(expression.to_owned())),
+            Err(DebugError::InvalidArgument(_))
+        ));
+    }
+}
+
+#[test]
+fn stepping_into_a_call_runs_the_other_calls_of_the_line() {
+    let mut fixture = Fixture::launch("calls");
+    fixture.break_at_line(10);
+    fixture.continue_to_breakpoint();
+    let CommandOutput::LineCalls(calls) = fixture.run(Command::GetLineCalls) else {
+        panic!("expected the calls");
+    };
+    assert_eq!(calls, ["g", "f"]);
+    let CommandOutput::StopState(stop) = fixture.run(Command::StepIntoTarget("f".to_owned()))
+    else {
+        panic!("expected the program to stop");
+    };
+    assert_eq!(stop.location.map(|l| l.line), Some(6));
+    assert_eq!(int_value(variable(&fixture.read_variables(), "x")), 2);
+}
+
+#[test]
+fn stepping_in_steps_over_library_and_skipped_functions() {
+    let mut fixture = Fixture::launch("skips");
+    fixture.break_at_line(9);
+    fixture.continue_to_breakpoint();
+    let CommandOutput::StopState(stop) = fixture.run(Command::StepIn) else {
+        panic!("expected the program to stop");
+    };
+    assert_eq!(stop.location.map(|l| l.line), Some(10));
+    fixture.run(Command::SetStepSkipList(vec!["help*".to_owned()]));
+    let CommandOutput::StopState(stop) = fixture.run(Command::StepIn) else {
+        panic!("expected the program to stop");
+    };
+    assert_eq!(stop.location.map(|l| l.line), Some(11));
+}
+
+#[test]
+fn breakpoints_at_addresses_without_code_are_refused() {
+    let mut fixture = Fixture::launch("calls");
+    let CommandOutput::FunctionDisassembly(main) =
+        fixture.run(Command::DisassembleFunction("main".to_owned()))
+    else {
+        panic!("expected the disassembly");
+    };
+    let instruction = main
+        .blocks
+        .iter()
+        .flat_map(|b| &b.instructions)
+        .find(|i| i.bytes.split_whitespace().count() > 1)
+        .unwrap();
+    let CommandOutput::Registers(registers) = fixture.run(Command::GetRegister) else {
+        panic!("expected the registers");
+    };
+    for address in [0x10, registers.stack_pointer, instruction.address + 1] {
+        let result = fixture
+            .debugger
+            .process_command(Command::SetBreakpoint(BreakpointPoint::Address(address)));
+        match result {
+            Err(DebugError::InvalidBreakpointAddress(message)) => {
+                assert!(
+                    message.contains("the nearest instruction is at 0x"),
+                    "{}",
+                    message
+                )
+            }
+            other => panic!("expected the breakpoint to be refused, got {:?}", other),
+        }
+    }
+    fixture.run(Command::SetBreakpoint(BreakpointPoint::Address(
+        instruction.address,
+    )));
+}
+
+#[test]
+fn watches_stop_after_the_memory_changed() {
+    let mut fixture = Fixture::launch("watch");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    fixture.run(Command::AddWatch {
+        expression: "head.next".to_owned(),
+        function: None,
+    });
+    let CommandOutput::StopState(stop) = fixture.run(Command::Continue) else {
+        panic!("expected the program to stop");
+    };
+    let StopReason::WatchChanged { expression, .. } = stop.reason else {
+        panic!("expected the watch to change, got {:?}", stop.reason);
+    };
+    assert_eq!(expression, "head.next");
+    let line = stop.location.map(|l| l.line);
+    assert!(matches!(line, Some(12 | 13)), "stopped at {:?}", line);
+}
+
+#[test]
+fn watched_continues_can_be_cancelled() {
+    let mut fixture = Fixture::launch("watch");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    fixture.run(Command::AddWatch {
+        expression: "head.next".to_owned(),
+        function: None,
+    });
+    let cancellation = Cancellation::default();
+    fixture.debugger.set_cancellation(cancellation.clone());
+    cancellation.cancel();
+    assert!(matches!(
+        fixture.debugger.process_command(Command::Continue),
+        Err(DebugError::Cancelled)
+    ));
+    fixture.debugger.set_cancellation(Cancellation::default());
+    let CommandOutput::StopState(stop) = fixture.run(Command::Continue) else {
+        panic!("expected the program to stop");
+    };
+    assert!(matches!(stop.reason, StopReason::WatchChanged { .. }));
+}
+
+#[test]
+fn watches_in_a_function_notice_changes_of_its_calls() {
+    let mut fixture = Fixture::launch("watch");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    for expression in ["count", "head.next"] {
+        fixture.run(Command::AddWatch {
+            expression: expression.to_owned(),
+            function: Some("main".to_owned()),
+        });
+    }
+    let mut changes = vec![];
+    for _ in 0..2 {
+        let CommandOutput::StopState(stop) = fixture.run(Command::Continue) else {
+            panic!("expected the program to stop");
+        };
+        let StopReason::WatchChanged {
+            expression,
+            old,
+            new,
+        } = stop.reason
+        else {
+            panic!("expected a watch to change, got {:?}", stop.reason);
+        };
+        changes.push((expression, stop.location.map(|l| l.line)));
+        assert_ne!(old, new);
+    }
+    // `append` runs without single-stepping, its change is noticed at its return address
+    assert_eq!(
+        changes,
+        [
+            ("count".to_owned(), Some(19)),
+            ("head.next".to_owned(), Some(20))
+        ]
+    );
+}
+
+#[test]
+fn got_slots_are_patched_at_the_first_call() {
+    let mut fixture = Fixture::launch("plt");
+    fixture.break_at_line(4);
+    fixture.continue_to_breakpoint();
+    let puts = |fixture: &mut Fixture| {
+        let CommandOutput::PltEntries(entries) = fixture.run(Command::GetPltEntries) else {
+            panic!("expected the PLT entries");
+        };
+        entries.into_iter().find(|e| e.function == "puts").unwrap()
+    };
+    let before = puts(&mut fixture);
+    assert!(!before.resolved);
+    assert_eq!(before.target_description, "puts@plt+0x6");
+    fixture.run(Command::StepIn);
+    let after = puts(&mut fixture);
+    assert!(after.resolved);
+    assert_eq!(after.got_address, before.got_address);
+    assert!(
+        after.target_description.starts_with("libc"),
+        "{}",
+        after.target_description
+    );
+}
+
+#[test]
+fn optimized_programs_are_reported() {
+    let mut fixture = Fixture::launch("arithmetic");
+    let CommandOutput::DebugMeta(meta) = fixture.run(Command::DebugMeta) else {
+        panic!("expected the metadata");
+    };
+    assert!(
+        meta.producers.iter().any(|p| p.contains("-O0")),
+        "{:?}",
+        meta.producers
+    );
+    assert_eq!(meta.optimization, None);
+    drop(fixture);
+
+    let mut fixture = Fixture::launch_with_flags("arithmetic", &["-g", "-gdwarf-4", "-O1", "-O2"]);
+    let CommandOutput::DebugMeta(meta) = fixture.run(Command::DebugMeta) else {
+        panic!("expected the metadata");
+    };
+    assert_eq!(meta.optimization.as_deref(), Some("-O2"));
+}
+
+#[test]
+fn statically_linked_programs_start_at_main() {
+    let mut fixture =
+        Fixture::launch_with_flags("arithmetic", &["-static", "-g", "-gdwarf-4", "-O0"]);
+    fixture.debugger.user_code = true;
+    fixture.debugger.start_at_main = true;
+    fixture.debugger.start();
+    let CommandOutput::Status(status) = fixture.run(Command::GetStatus) else {
+        panic!("expected the status");
+    };
+    let stop = status.stop.unwrap();
+    assert_eq!(stop.reason, StopReason::Started);
+    let location = stop.location.unwrap();
+    assert!(location.file.ends_with("arithmetic.c"));
+    // after the prologue of main
+    assert_eq!(location.line, 2);
+    let CommandOutput::Functions(functions) = fixture.run(Command::GetFunctions) else {
+        panic!("expected the functions");
+    };
+    let names = functions
+        .iter()
+        .map(|f| f.name.as_deref().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(names, ["main"]);
+    let CommandOutput::DebugMeta(meta) = fixture.run(Command::DebugMeta) else {
+        panic!("expected the metadata");
+    };
+    assert!(meta.files.iter().all(|f| f.ends_with("arithmetic.c")));
+}
+
+#[test]
+fn source_files_are_read_through_the_source_map() {
+    let mut fixture = Fixture::launch("arithmetic");
+    let moved = std::env::temp_dir()
+        .join("stackium-tests")
+        .join(std::process::id().to_string())
+        .join("moved");
+    std::fs::create_dir_all(&moved).unwrap();
+    std::fs::write(moved.join("arithmetic.c"), "// moved\n").unwrap();
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    fixture.debugger.allowed_sources = vec![moved.clone()];
+    fixture.run(Command::SetSourceMap(vec![
+        SourceMapping {
+            from: "/nonexistent".to_owned(),
+            to: "/".to_owned(),
+        },
+        SourceMapping {
+            from: fixtures.display().to_string(),
+            to: moved.display().to_string(),
+        },
+    ]));
+    let CommandOutput::File(source) = fixture.run(Command::GetFile("arithmetic.c".to_owned()))
+    else {
+        panic!("expected the file");
+    };
+    assert_eq!(source, "// moved\n");
+
+    // files missing after mapping are read from their original path
+    std::fs::remove_file(moved.join("arithmetic.c")).unwrap();
+    let CommandOutput::File(source) = fixture.run(Command::GetFile("arithmetic.c".to_owned()))
+    else {
+        panic!("expected the file");
+    };
+    assert!(source.contains("int main()"));
+}
+
+#[test]
+fn sources_are_not_mapped_onto_other_files() {
+    let mut fixture = Fixture::launch("arithmetic");
+    let source = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/arithmetic.c");
+    fixture.run(Command::SetSourceMap(vec![SourceMapping {
+        from: source.display().to_string(),
+        to: "/etc/passwd".to_owned(),
+    }]));
+    let CommandOutput::Source(source) = fixture.run(Command::GetSource("arithmetic.c".to_owned()))
+    else {
+        panic!("expected the source");
+    };
+    assert_eq!(source.origin, SourceOrigin::Local);
+    assert!(source.content.unwrap().contains("int main()"));
+}
+
+#[test]
+fn missing_sources_are_reported_without_content() {
+    let mut fixture = Fixture::launch_compiled("arithmetic", |source, binary| {
+        // compiled from a copy which is deleted afterwards
+        let copy = binary.with_file_name("deleted.c");
+        std::fs::copy(source, &copy).unwrap();
+        compile_program(&copy, binary).unwrap();
+        std::fs::remove_file(&copy).unwrap();
+    });
+    let CommandOutput::Source(source) = fixture.run(Command::GetSource("deleted.c".to_owned()))
+    else {
+        panic!("expected the source");
+    };
+    assert!(source.path.ends_with("deleted.c"));
+    assert_eq!(source.origin, SourceOrigin::Missing);
+    assert_eq!(source.content, None);
+    assert!(fixture
+        .debugger
+        .process_command(Command::GetFile("deleted.c".to_owned()))
+        .is_err());
+}
+
+#[test]
+fn only_sources_of_the_program_can_be_read() {
+    let mut fixture = Fixture::launch("arithmetic");
+    for path in [
+        "/etc/passwd",
+        "../../../../../../etc/passwd",
+        "tests/fixtures/calls.c",
+    ] {
+        assert!(matches!(
+            fixture
+                .debugger
+                .process_command(Command::GetFile(path.to_owned())),
+            Err(DebugError::FileNotAllowed(_))
+        ));
+        assert!(matches!(
+            fixture
+                .debugger
+                .process_command(Command::GetSource(path.to_owned())),
+            Err(DebugError::FileNotAllowed(_))
+        ));
+    }
+    assert!(matches!(
+        fixture.run(Command::GetFile("arithmetic.c".to_owned())),
+        CommandOutput::File(_)
+    ));
+
+    let fixtures = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    fixture.debugger.allowed_sources = vec![fixtures.clone()];
+    let CommandOutput::File(source) = fixture.run(Command::GetFile(
+        fixtures.join("calls.c").display().to_string(),
+    )) else {
+        panic!("expected the file");
+    };
+    assert!(source.contains("int main()"));
+}
+
+/// Continues to line 3 of `arithmetic.c`, which needs the line tables
+fn assert_stops_at_line_3(fixture: &mut Fixture) {
+    fixture.break_at_line(3);
+    fixture.continue_to_breakpoint();
+    let CommandOutput::Status(status) = fixture.run(Command::GetStatus) else {
+        panic!("expected the status");
+    };
+    assert_eq!(status.stop.unwrap().location.unwrap().line, 3);
+}
+
+#[test]
+fn compressed_debug_sections_are_read() {
+    let mut fixture = Fixture::launch_with_flags(
+        "arithmetic",
+        &["-no-pie", "-g", "-gdwarf-4", "-gz=zlib", "-O0"],
+    );
+    let CommandOutput::DebugMeta(meta) = fixture.run(Command::DebugMeta) else {
+        panic!("expected the metadata");
+    };
+    assert!(meta.has_debug_info);
+    assert_eq!(meta.debug_file, None);
+    assert_stops_at_line_3(&mut fixture);
+}
+
+#[test]
+fn restarted_programs_run_in_the_sandbox() {
+    let mut fixture = Fixture::launch("arithmetic");
+    let sandbox = Sandbox::new(10, 256);
+    let directory = sandbox.directory.clone();
+    fixture.debugger.sandbox = Some(sandbox);
+    fixture.break_at_line(3);
+    fixture.run(Command::RestartDebugee);
+    let cwd = std::fs::read_link(format!("/proc/{}/cwd", fixture.debugger.child)).unwrap();
+    assert_eq!(cwd, std::fs::canonicalize(&directory).unwrap());
+    fixture.continue_to_breakpoint();
+    let CommandOutput::Status(status) = fixture.run(Command::GetStatus) else {
+        panic!("expected the status");
+    };
+    assert_eq!(status.stop.unwrap().location.unwrap().line, 3);
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn breakpoints_which_no_longer_resolve_are_dropped_when_restarting() {
+    let mut fixture = Fixture::launch_compiled("arithmetic", |source, binary| {
+        let copy = binary.with_file_name("rebuilt.c");
+        std::fs::copy(source, &copy).unwrap();
+        compile_program(&copy, binary).unwrap();
+    });
+    let source = fixture.debugger.program.with_file_name("rebuilt.c");
+    for line in [3, 6] {
+        fixture.run(Command::SetBreakpoint(BreakpointPoint::Location(
+            Location {
+                line,
+                file: "rebuilt.c".to_owned(),
+                column: 0,
+            },
+        )));
+    }
+    // the rebuilt program ends before the second breakpoint
+    std::fs::write(
+        &source,
+        "int main() {\n    short numbers[4] = {1, 2, 3, 4};\n    short *first = numbers;\n    return *first;\n}\n",
+    )
+    .unwrap();
+    compile_program(&source, &fixture.debugger.program).unwrap();
+    fixture.run(Command::RestartDebugee);
+    let CommandOutput::Breakpoints(breakpoints) = fixture.run(Command::GetBreakpoints) else {
+        panic!("expected the breakpoints");
+    };
+    assert_eq!(breakpoints.len(), 1);
+    assert_eq!(breakpoints[0].location.line, 3);
+    fixture.continue_to_breakpoint();
+    let CommandOutput::Status(status) = fixture.run(Command::GetStatus) else {
+        panic!("expected the status");
+    };
+    assert_eq!(status.stop.unwrap().location.unwrap().line, 3);
+}
+
+#[test]
+fn sandboxed_programs_only_write_their_directory() {
+    let mut fixture = Fixture::launch("sandbox");
+    let sandbox = Sandbox::new(10, 256);
+    let directory = sandbox.directory.clone();
+    fixture.debugger.sandbox = Some(sandbox);
+    fixture.break_at_line(10);
+    fixture.run(Command::RestartDebugee);
+    fixture.continue_to_breakpoint();
+    let variables = fixture.read_variables();
+    assert_eq!(int_value(variable(&variables, "parent")), -1);
+    assert!(int_value(variable(&variables, "created")) >= 0);
+    assert_eq!(int_value(variable(&variables, "network")), -1);
+    assert_eq!(int_value(variable(&variables, "forked")), -1);
+    assert!(directory.join("created.txt").exists());
+    std::fs::remove_dir_all(directory).unwrap();
+}
+
+#[test]
+fn strings_of_dwarf_5_are_read() {
+    let mut fixture =
+        Fixture::launch_with_flags("arithmetic", &["-no-pie", "-g", "-gdwarf-5", "-O0"]);
+    // the name of the compilation unit is in `.debug_line_str`
+    assert!(fixture.dump_types().contains("arithmetic.c main::numbers"));
+    assert_stops_at_line_3(&mut fixture);
+}
+
+#[test]
+fn debug_information_of_stripped_programs_is_read_from_the_debug_link() {
+    let mut fixture = Fixture::launch_compiled("arithmetic", |source, binary| {
+        compile_program(source, binary).unwrap();
+        let debug_file = binary.with_extension("debug");
+        for args in [
+            vec![
+                "--only-keep-debug".into(),
+                binary.into(),
+                debug_file.clone(),
+            ],
+            vec!["--strip-debug".into(), binary.into()],
+            vec![
+                format!("--add-gnu-debuglink={}", debug_file.display()).into(),
+                binary.into(),
+            ],
+        ] {
+            let status = std::process::Command::new("objcopy")
+                .args(args)
+                .status()
+                .unwrap();
+            assert!(status.success());
+        }
+    });
+    let CommandOutput::DebugMeta(meta) = fixture.run(Command::DebugMeta) else {
+        panic!("expected the metadata");
+    };
+    assert!(meta.has_debug_info);
+    assert!(meta.debug_file.unwrap().ends_with("arithmetic.debug"));
+    assert_stops_at_line_3(&mut fixture);
+}
+
+#[test]
+fn elf_info_maps_the_sections_into_segments() {
+    let mut fixture = Fixture::launch("plt");
+    let CommandOutput::ElfInfo(info) = fixture.run(Command::GetElfInfo) else {
+        panic!("expected the ELF info");
+    };
+    assert_eq!(info.bits, 64);
+    assert_eq!(info.file_type, "Executable");
+    assert!(info.interpreter.unwrap().contains("ld-linux"));
+    let section = |name: &str| info.sections.iter().find(|s| s.name == name).unwrap();
+    let text = section(".text");
+    assert_eq!(text.flags, "AX");
+    assert!(text.address <= info.entry && info.entry < text.address + text.size);
+    assert_eq!(section(".bss").section_type, "NOBITS");
+    assert!(info
+        .segments
+        .iter()
+        .any(|s| s.segment_type == "LOAD" && s.flags == "R-X" && s.sections.contains(&text.name)));
+    let dynsym = info
+        .symbol_tables
+        .iter()
+        .find(|t| t.name == ".dynsym")
+        .unwrap();
+    assert!(dynsym.undefined >= 1, "{:?}", dynsym);
+}
+
+#[test]
+fn functions_are_grouped_by_source_file() {
+    let mut fixture = Fixture::launch("calls");
+    let CommandOutput::FunctionGroups(groups) = fixture.run(Command::FindFunctions {
+        pattern: None,
+        user_code: true,
+    }) else {
+        panic!("expected the function groups");
+    };
+    assert_eq!(groups.len(), 1);
+    assert!(groups[0].file.ends_with("calls.c"));
+    let names: Vec<_> = groups[0]
+        .functions
+        .iter()
+        .map(|f| f.name.as_deref().unwrap())
+        .collect();
+    assert_eq!(names, ["f", "g", "main"]);
+    let declaration = groups[0].functions[2].declaration.as_ref().unwrap();
+    assert!(declaration.file.ends_with("calls.c"));
+    assert_eq!(declaration.line, 9);
+
+    let CommandOutput::FunctionGroups(groups) = fixture.run(Command::FindFunctions {
+        pattern: Some("MA".to_owned()),
+        user_code: false,
+    }) else {
+        panic!("expected the function groups");
+    };
+    let names: Vec<_> = groups
+        .iter()
+        .flat_map(|g| &g.functions)
+        .map(|f| f.name.as_deref().unwrap())
+        .collect();
+    assert_eq!(names, ["main"]);
+}
+
+#[test]
+fn functions_have_their_signature() {
+    let mut fixture = Fixture::launch("structs");
+    let CommandOutput::FunctionGroups(groups) = fixture.run(Command::FindFunctions {
+        pattern: Some("area".to_owned()),
+        user_code: false,
+    }) else {
+        panic!("expected the function groups");
+    };
+    let area = &groups[0].functions[0];
+    assert_eq!(area.signature(), "int area(struct rectangle * rectangle)");
+    assert_eq!(area.declaration.as_ref().unwrap().line, 12);
+}
+
+#[test]
+fn names_and_types_of_cpp_are_readable() {
+    let mut fixture = Fixture::launch_compiled("classes", |source, binary| {
+        let status = std::process::Command::new("c++")
+            .args(["-no-pie", "-gdwarf-4", "-O0", "-o"])
+            .arg(binary)
+            .arg(source)
+            .status()
+            .unwrap();
+        assert!(status.success());
+    });
+    let CommandOutput::Functions(defined) = fixture.run(Command::GetFunctions) else {
+        panic!("expected the functions");
+    };
+    assert!(defined
+        .iter()
+        .any(|f| f.name.as_deref() == Some("shapes::Rect::area") && f.low_pc.is_some()));
+    fixture.break_at_function("shapes::scale");
+    fixture.continue_to_breakpoint();
+    assert_eq!(functions(&fixture.backtrace()), ["shapes::scale", "main"]);
+    let variables = fixture.read_variables();
+    let types = variable(&variables, "rect").type_name.as_ref().unwrap();
+    assert!(matches!(types.0[0].1, TypeName::Ref { index: Some(1) }));
+    let TypeName::ProductType { name, members, .. } = &types.0[1].1 else {
+        panic!("expected a class, got {:?}", types.0[1].1);
+    };
+    assert_eq!(name, "shapes::Rect");
+    let members: Vec<_> = members.iter().map(|(name, ..)| name.as_str()).collect();
+    assert_eq!(members, ["width", "height"]);
+    let scale = defined
+        .iter()
+        .find(|f| f.name.as_deref() == Some("shapes::scale") && f.low_pc.is_some())
+        .unwrap();
+    assert_eq!(
+        scale.signature(),
+        "int shapes::scale(Rect & rect, int factor)"
+    );
+}
+
+#[test]
+fn struct_parameters_have_their_members() {
+    let mut fixture = Fixture::launch("structs");
+    fixture.break_at_function("area");
+    fixture.continue_to_breakpoint();
+    let variables = fixture.read_variables();
+    let rectangle = variable(&variables, "rectangle");
+    assert!(rectangle.parameter);
+    let types = rectangle.type_name.as_ref().unwrap();
+    assert!(matches!(types.0[0].1, TypeName::Ref { index: Some(1) }));
+    let TypeName::ProductType {
+        name,
+        members,
+        byte_size,
+    } = &types.0[1].1
+    else {
+        panic!("expected a struct, got {:?}", types.0[1].1);
+    };
+    assert_eq!(name, "rectangle");
+    assert_eq!(*byte_size, 24);
+    let members: Vec<_> = members
+        .iter()
+        .map(|(name, _, offset)| (name.as_str(), *offset))
+        .collect();
+    assert_eq!(members, [("origin", 0), ("size", 8), ("name", 16)]);
+}
+
+#[test]
+fn struct_members_are_read_through_pointers() {
+    let mut fixture = Fixture::launch("structs");
+    fixture.break_at_function("area");
+    fixture.continue_to_breakpoint();
+    let variables = fixture.discover_variables();
+    let rectangle = discovered(&variables, "*rectangle");
+    assert_eq!(ints(rectangle, 4), [1, 2, 3, 4]);
+    let memory = &rectangle.memory.as_ref().unwrap()[VARIABLE_MEM_PADDING as usize..];
+    assert_eq!(&memory[16..20], b"box\0");
+}
+
+#[test]
+fn backtrace_contains_the_caller() {
+    let mut fixture = Fixture::launch("structs");
+    fixture.break_at_function("area");
+    fixture.continue_to_breakpoint();
+    let frames = fixture.backtrace();
+    assert_eq!(functions(&frames), ["area", "main"]);
+    assert_eq!(frames[1].location.as_ref().map(|l| l.line), Some(19));
+    assert_eq!(frames[1].return_address, None);
+}
+
+#[test]
+fn backtrace_of_recursion_has_every_call() {
+    let mut fixture = Fixture::launch("recursion");
+    fixture.break_at_line(2);
+    for _ in 0..4 {
+        fixture.continue_to_breakpoint();
+    }
+    let frames = fixture.backtrace();
+    assert_eq!(
+        functions(&frames),
+        ["factorial", "factorial", "factorial", "factorial", "main"]
+    );
+    assert!(frames[1..4]
+        .iter()
+        .all(|f| f.location.as_ref().map(|l| l.line) == Some(5)));
+    let arguments: Vec<_> = fixture
+        .stack_frames()
+        .iter()
+        .flat_map(|f| f.arguments.iter().map(int_value).collect::<Vec<_>>())
+        .collect();
+    assert_eq!(arguments, [1, 2, 3, 4]);
+    assert_eq!(int_value(variable(&fixture.read_variables(), "n")), 1);
+}
+
+#[test]
+fn finishing_frames_returns_from_each_call() {
+    let mut fixture = Fixture::launch("recursion");
+    fixture.break_at_line(2);
+    for _ in 0..3 {
+        fixture.continue_to_breakpoint();
+    }
+    let CommandOutput::Registers(registers) = fixture.run(Command::GetRegister) else {
+        panic!("expected the registers");
+    };
+    // factorial(2) calls factorial(1), which returns to the same address first
+    fixture.run(Command::DeleteBreakpoint(registers.instruction_pointer));
+    let CommandOutput::StopState(stop) = fixture.run(Command::FinishFrames(2)) else {
+        panic!("expected the program to stop");
+    };
+    let returned: Vec<_> = stop
+        .returned_frames
+        .iter()
+        .map(|r| (r.function.as_deref(), r.value.as_deref()))
+        .collect();
+    assert_eq!(
+        returned,
+        [
+            (Some("factorial"), Some("2")),
+            (Some("factorial"), Some("6"))
+        ]
+    );
+    assert_eq!(stop.location.map(|l| l.line), Some(5));
+    assert_eq!(int_value(variable(&fixture.read_variables(), "n")), 4);
+}
+
+#[test]
+fn process_stack_has_the_arguments() {
+    let mut fixture = Fixture::launch("pointers");
+    fixture.break_at_function("main");
+    fixture.continue_to_breakpoint();
+    let CommandOutput::ProcessStack(stack) = fixture.run(Command::GetProcessStack) else {
+        panic!("expected the process stack");
+    };
+    assert_eq!(stack.argc, 1);
+    assert_eq!(stack.argv.len(), 1);
+    assert!(stack.argv[0].value.ends_with("/pointers\""));
+    assert_eq!(stack.argv[0].address, stack.start + 8);
+    let page_size = stack
+        .auxv
+        .iter()
+        .find(|e| e.name.as_deref() == Some("AT_PAGESZ"))
+        .expect("expected AT_PAGESZ");
+    assert_eq!(page_size.value, 4096);
+    assert!(stack.start < stack.end);
+}
+
+#[test]
+fn heap_lists_are_followed() {
+    let mut fixture = Fixture::launch("heap");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    let variables = fixture.discover_variables();
+    let head = discovered(&variables, "*head");
+    let second = discovered(&variables, "**head.next");
+    let third = discovered(&variables, "***head.next.next");
+    assert_eq!(ints(head, 1), [2]);
+    assert_eq!(ints(second, 1), [1]);
+    assert_eq!(ints(third, 1), [0]);
+    // `next` follows `value` and its padding
+    assert_eq!(ints(head, 4)[2..], [second.addr.unwrap() as i32, 0]);
+    assert_eq!(ints(third, 4)[2..], [0, 0]);
+    assert_eq!(ints(discovered(&variables, "*numbers"), 1), [2]);
+}
+
+#[test]
+fn references_in_struct_members_are_found() {
+    let mut fixture = Fixture::launch("heap");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    let variables = fixture.discover_variables();
+    let head = discovered(&variables, "*head").addr.unwrap();
+    let second = discovered(&variables, "**head.next").addr.unwrap();
+    // only the `next` member of the first node points to the second one
+    let CommandOutput::References(references) = fixture.run(Command::FindReferences(second)) else {
+        panic!("expected the references");
+    };
+    let reference = references
+        .iter()
+        .find(|r| r.address.is_some())
+        .expect("expected a reference in memory");
+    assert_eq!(reference.name, "head->next");
+    assert_eq!(reference.address, Some(head + 8));
+    assert_eq!(reference.value, second);
+}
+
+#[test]
+fn heap_blocks_have_their_chunk_header() {
+    let mut fixture = Fixture::launch("heap");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    let variables = fixture.discover_variables();
+    let head = discovered(&variables, "*head");
+    let chunk = head
+        .heap_chunk
+        .as_ref()
+        .expect("expected the chunk of *head");
+    assert_eq!(chunk.address, head.addr.unwrap());
+    assert_eq!(chunk.size, 32);
+    assert_eq!(chunk.in_use, Some(true));
+    assert!(chunk.prev_in_use);
+    assert!(discovered(&variables, "***head.next.next")
+        .heap_chunk
+        .is_some());
+    assert!(discovered(&variables, "*numbers").heap_chunk.is_some());
+    assert!(discovered(&variables, "head").heap_chunk.is_none());
+}
+
+#[test]
+fn cached_memory_is_read_again_after_writes() {
+    let mut fixture = Fixture::launch("heap");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    let address = discovered(&fixture.discover_variables(), "*head")
+        .addr
+        .unwrap();
+    let block = fixture.read_memory(address, 16);
+    assert_eq!(block[..4], [2, 0, 0, 0]);
+    // served from the first read
+    assert_eq!(fixture.read_memory(address + 4, 4), block[4..8]);
+    fixture.run(Command::WriteMemory(address, vec![7, 1, 0, 0]));
+    assert_eq!(fixture.read_memory(address + 1, 2), [1, 0]);
+    assert_eq!(fixture.read_memory(address, 16)[..4], [7, 1, 0, 0]);
+}
+
+#[test]
+fn cancelled_commands_stop_until_the_cancellation_is_reset() {
+    let mut fixture = Fixture::launch("heap");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    let cancellation = Cancellation::default();
+    fixture.debugger.set_cancellation(cancellation.clone());
+    cancellation.cancel();
+    for command in [Command::DiscoverVariables, Command::Disassemble] {
+        assert!(matches!(
+            fixture.debugger.process_command(command),
+            Err(DebugError::Cancelled)
+        ));
+    }
+    fixture.debugger.set_cancellation(Cancellation::default());
+    assert!(!fixture.discover_variables().is_empty());
+}
+
+#[test]
+fn deleted_files_with_spaces_are_mapped() {
+    let mut fixture = Fixture::launch("mappings");
+    fixture.break_at_line(14);
+    fixture.continue_to_breakpoint();
+    let CommandOutput::Maps(maps) = fixture.run(Command::Maps) else {
+        panic!("expected the maps");
+    };
+    let mapped = maps
+        .iter()
+        .find(|m| m.mapped.starts_with("/tmp/stackium mapped "))
+        .unwrap();
+    assert!(mapped.mapped.ends_with(" (deleted)"));
+    assert!(mapped.shared && mapped.write);
+    assert_eq!(mapped.to - mapped.from, 4096);
+    assert!(maps.iter().any(|m| m.mapped == "[stack]"));
+}
+
+#[test]
+fn memory_reads_stay_in_readable_regions() {
+    let mut fixture = Fixture::launch("heap");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    let CommandOutput::Maps(maps) = fixture.run(Command::Maps) else {
+        panic!("expected the maps");
+    };
+    let stack = maps.iter().find(|m| m.mapped == "[stack]").unwrap();
+    assert_eq!(fixture.read_memory(stack.to - 8, 64).len(), 8);
+    match fixture
+        .debugger
+        .process_command(Command::ReadMemory(0x10, 8))
+    {
+        Err(DebugError::UnreadableAddress(message)) => {
+            assert!(message.starts_with("0x10 is not mapped, the nearest region is "))
+        }
+        output => panic!("unexpected output {:?}", output),
+    }
+}
+
+#[test]
+fn variables_of_finished_blocks_are_out_of_scope() {
+    let mut fixture = Fixture::launch("heap");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    let variables = fixture.read_variables();
+    let head = variable(&variables, "head");
+    assert!(head.in_scope);
+    assert!(head.value.is_some_and(|v| v != 0));
+    for name in ["i", "node"] {
+        let variable = variable(&variables, name);
+        assert!(!variable.in_scope);
+        assert_eq!(variable.addr, None);
+    }
+}
+
+#[test]
+fn report_lists_variables_and_heap_chunks() {
+    let mut fixture = Fixture::launch("heap");
+    fixture.break_at_line(18);
+    fixture.continue_to_breakpoint();
+    let CommandOutput::File(report) = fixture.run(Command::GenerateReport(ReportFormat::Markdown))
+    else {
+        panic!("expected the report");
+    };
+    assert!(report.contains(">   18 "));
+    assert!(report.contains("| 0 | main |"));
+    assert!(report.contains("| head |"));
+    assert!(report.matches("| in use |").count() >= 4);
+}
+
+#[test]
+fn quiz_answers_are_checked() {
+    let mut fixture = Fixture::launch("pointers");
+    fixture.break_at_line(8);
+    let CommandOutput::Breakpoints(breakpoints) = fixture.run(Command::GetBreakpoints) else {
+        panic!("expected the breakpoints");
+    };
+    for (expression, kind) in [("*pointer", QuizKind::Value), ("element", QuizKind::Target)] {
+        fixture.run(Command::AddQuizQuestion(QuizQuestion {
+            breakpoint: breakpoints[0].address,
+            expression: expression.to_owned(),
+            kind,
+        }));
+    }
+    fixture.continue_to_breakpoint();
+    assert!(!fixture.answer_quiz("*pointer", "41"));
+    assert!(fixture.answer_quiz("element", "numbers[2]"));
+    let CommandOutput::Quiz(quiz) = fixture.run(Command::GetQuiz) else {
+        panic!("expected the quiz");
+    };
+    assert!(quiz.pending.is_empty());
+    assert_eq!(quiz.answers.iter().filter(|a| a.correct).count(), 1);
+    assert_eq!(quiz.answers[0].solution, "42");
+}
+
+#[test]
+fn types_of_pointers_match_snapshot() {
+    assert_types_snapshot("pointers");
+}
+
+#[test]
+fn types_of_structs_match_snapshot() {
+    assert_types_snapshot("structs");
+}
+
+#[test]
+fn types_of_self_referential_structs_match_snapshot() {
+    assert_types_snapshot("heap");
+}
+
+#[test]
+fn types_of_typedefs_and_arrays_match_snapshot() {
+    assert_types_snapshot("types");
+}
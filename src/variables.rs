@@ -1,24 +1,264 @@
 use std::os::raw::c_void;
 
+use serde::Serialize;
 use stackium_shared::{
-    DataType, DiscoveredVariable, MemoryMap, TypeName, Variable, VARIABLE_MEM_PADDING,
+    format_value, get_byte_size, DataType, DiscoveredVariable, ExportFormat, MemoryMap,
+    PointerInfo, Reference, TypeName, Variable, VARIABLE_MEM_PADDING,
 };
+use tracing::debug;
 
 use crate::debugger::{error::DebugError, Debugger};
-pub fn get_byte_size(types: &DataType, index: usize) -> usize {
+
+/// Readable C-like name of the type at `index`, e.g. `int*` or `char[16]`
+pub fn get_type_name(types: &DataType, index: usize) -> String {
     match &types.0[index].1 {
-        TypeName::Name { name: _, byte_size } => *byte_size,
-        TypeName::Arr { arr_type, count } => {
-            count.iter().cloned().fold(1, |e1, e2| e1 * e2) * get_byte_size(types, *arr_type)
-        }
-        TypeName::Ref { index: _ } => 8usize,
+        TypeName::Name { name, byte_size: _ } => name.clone(),
+        TypeName::Arr { arr_type, count } => format!(
+            "{}{}",
+            get_type_name(types, *arr_type),
+            count
+                .iter()
+                .map(|c| format!("[{}]", c))
+                .collect::<Vec<String>>()
+                .join("")
+        ),
+        TypeName::Ref { index: Some(index) } => format!("{}*", get_type_name(types, *index)),
+        TypeName::Ref { index: None } => "void*".to_owned(),
         TypeName::ProductType {
-            name: _,
+            name,
             members: _,
-            byte_size,
-        } => *byte_size,
+            byte_size: _,
+        } => name.clone(),
+    }
+}
+
+/// Maximum number of bytes read when following a `char*`
+pub const MAX_STRING_LENGTH: usize = 256;
+
+fn is_char_type(types: &DataType, index: usize) -> bool {
+    matches!(&types.0[index].1, TypeName::Name { name, byte_size: 1 } if name.contains("char"))
+}
+
+/// Renders bytes as a C string literal, escaping non printable characters.
+/// Appends `...` if the string wasn't terminated by a NUL byte.
+pub fn escape_string(bytes: &[u8], terminated: bool) -> String {
+    let mut string = String::from("\"");
+    for byte in bytes {
+        match byte {
+            b'\n' => string.push_str("\\n"),
+            b'\r' => string.push_str("\\r"),
+            b'\t' => string.push_str("\\t"),
+            b'"' => string.push_str("\\\""),
+            b'\\' => string.push_str("\\\\"),
+            0x20..=0x7e => string.push(*byte as char),
+            _ => string.push_str(&format!("\\x{:02x}", byte)),
+        }
+    }
+    string.push('"');
+    if !terminated {
+        string.push_str("...");
+    }
+    string
+}
+
+/// Walks into arrays and structs to find the innermost element containing `offset`.
+/// Returns the element path (appended to `name`), its offset and its byte size.
+fn find_element(types: &DataType, index: usize, name: String, offset: u64) -> (String, u64, u64) {
+    match &types.0[index].1 {
+        TypeName::Arr { arr_type, count } => {
+            let element_size = get_byte_size(types, *arr_type) as u64;
+            let elements = count.iter().fold(1, |acc, e| acc * *e) as u64;
+            if element_size == 0 || offset / element_size >= elements {
+                return (name, 0, get_byte_size(types, index) as u64);
+            }
+            let i = offset / element_size;
+            let (name, element_offset, size) = find_element(
+                types,
+                *arr_type,
+                format!("{}[{}]", name, i),
+                offset % element_size,
+            );
+            (name, i * element_size + element_offset, size)
+        }
+        TypeName::ProductType { members, .. } => {
+            for (member_name, member_type, member_offset) in members {
+                let member_offset = *member_offset as u64;
+                let member_size = get_byte_size(types, *member_type) as u64;
+                if member_offset <= offset && offset < member_offset + member_size {
+                    let (name, element_offset, size) = find_element(
+                        types,
+                        *member_type,
+                        format!("{}.{}", name, member_name),
+                        offset - member_offset,
+                    );
+                    return (name, member_offset + element_offset, size);
+                }
+            }
+            (name, 0, get_byte_size(types, index) as u64)
+        }
+        _ => (name, 0, get_byte_size(types, index) as u64),
+    }
+}
+
+/// A discovered variable with its decoded value
+#[derive(Serialize)]
+pub struct ExportedVariable {
+    pub name: String,
+    pub type_name: String,
+    pub address: u64,
+    pub size: usize,
+    pub value: String,
+}
+
+/// Alignment of the type at `index`, scalars are aligned to their size on x86-64
+fn get_alignment(types: &DataType, index: usize) -> u64 {
+    match &types.0[index].1 {
+        TypeName::Name { byte_size, .. } if byte_size.is_power_of_two() => *byte_size as u64,
+        TypeName::Name { .. } => 1,
+        TypeName::Arr { arr_type, .. } => get_alignment(types, *arr_type),
+        TypeName::Ref { .. } => 8,
+        TypeName::ProductType { members, .. } => members
+            .iter()
+            .map(|(_, member, _)| get_alignment(types, *member))
+            .max()
+            .unwrap_or(1),
+    }
+}
+
+/// The value of a pointer variable and the index of the type it points to
+fn pointer_target(variable: &DiscoveredVariable) -> Option<(u64, usize)> {
+    let TypeName::Ref {
+        index: Some(pointee),
+    } = variable.types.0[variable.type_index].1
+    else {
+        return None;
+    };
+    let padding = VARIABLE_MEM_PADDING as usize;
+    let bytes = variable.memory.as_ref()?.get(padding..padding + 8)?;
+    let value = u64::from_le_bytes(bytes.try_into().ok()?);
+    (value != 0).then_some((value, pointee))
+}
+
+/// Warns about pointers which aren't aligned for the type they point to, e.g. an `int*` moved
+/// by one byte with `char*` arithmetic, and pointers past the end of an array, e.g. from an
+/// index counting one element too far
+fn add_pointer_warnings(variables: &mut [DiscoveredVariable]) {
+    let size = |v: &DiscoveredVariable| get_byte_size(&v.types, v.type_index) as u64;
+    let mut warnings = vec![vec![]; variables.len()];
+    for (i, variable) in variables.iter().enumerate() {
+        let Some((value, pointee)) = pointer_target(variable) else {
+            continue;
+        };
+        let pointee_name = get_type_name(&variable.types, pointee);
+        let alignment = get_alignment(&variable.types, pointee);
+        if value % alignment != 0 {
+            warnings[i].push(format!(
+                "points to {:#x}, which isn't aligned to the {} bytes of {}",
+                value, alignment, pointee_name
+            ));
+        }
+        // e.g. `&x` of a variable declared right after an array, targets found through
+        // pointers (`*pointer`) are at the value of every pointer
+        let points_to_variable = variables.iter().any(|v| {
+            v.addr == Some(value)
+                && !v.name.as_ref().is_some_and(|n| n.starts_with('*'))
+                && get_type_name(&v.types, v.type_index) == pointee_name
+        });
+        if points_to_variable {
+            continue;
+        }
+        // the array the pointer most likely walked out of ends right before its target
+        let array = variables.iter().find_map(|v| {
+            let (TypeName::Arr { arr_type, count }, Some(start)) =
+                (&v.types.0[v.type_index].1, v.addr)
+            else {
+                return None;
+            };
+            let end = start + size(v);
+            (end <= value && value < end + size(v)).then_some((v, *arr_type, count, start))
+        });
+        if let Some((array, element_type, count, start)) = array {
+            let element_size = (get_byte_size(&array.types, element_type) as u64).max(1);
+            warnings[i].push(format!(
+                "points to {}[{}] past the end of its {} elements, which can't be dereferenced",
+                array.name.as_deref().unwrap_or("??"),
+                (value - start) / element_size,
+                count.iter().product::<usize>()
+            ));
+        }
+    }
+    for (variable, warnings) in variables.iter_mut().zip(warnings) {
+        variable.warnings = warnings;
+    }
+}
+
+fn escape_csv(field: &str) -> String {
+    if field.contains(',') || field.contains('"') || field.contains('\n') {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+/// Collects the pointers inside a value of the type at `type_index` with their offset in
+/// `memory`, their path below the value (e.g. `.next` or `[2]`) and their value
+fn collect_pointers(
+    types: &DataType,
+    type_index: usize,
+    memory: &[u8],
+    offset: usize,
+    path: String,
+    pointers: &mut Vec<(usize, String, u64)>,
+) {
+    match &types.0[type_index].1 {
+        TypeName::Name { .. } => {}
+        TypeName::Ref { index: _ } => {
+            if let Some(value) = memory.get(offset..offset + 8) {
+                pointers.push((offset, path, u64::from_le_bytes(value.try_into().unwrap())));
+            }
+        }
+        TypeName::Arr { arr_type, count } => {
+            if let TypeName::Name { .. } = &types.0[*arr_type].1 {
+                return;
+            }
+            let element_size = get_byte_size(types, *arr_type);
+            for i in 0..count.iter().product() {
+                collect_pointers(
+                    types,
+                    *arr_type,
+                    memory,
+                    offset + i * element_size,
+                    format!("{}[{}]", path, i),
+                    pointers,
+                );
+            }
+        }
+        TypeName::ProductType { members, .. } => {
+            for (name, member_type, member_offset) in members {
+                collect_pointers(
+                    types,
+                    *member_type,
+                    memory,
+                    offset + member_offset,
+                    format!("{}.{}", path, name),
+                    pointers,
+                );
+            }
+        }
     }
 }
+
+/// Name of the member at `path` below the discovered variable `name`, members of the target of
+/// a pointer like `*head` are named `head->next`
+fn member_path(name: &str, path: &str) -> String {
+    match (name.strip_prefix('*'), path.strip_prefix('.')) {
+        (_, _) if path.is_empty() => name.to_owned(),
+        (Some(pointer), Some(member)) => format!("{}->{}", pointer, member),
+        (Some(_), None) => format!("({}){}", name, path),
+        (None, _) => format!("{}{}", name, path),
+    }
+}
+
 fn check_variable_recursive(
     debugger: &Debugger,
     mapping: &Vec<MemoryMap>,
@@ -53,6 +293,9 @@ fn check_variable_recursive(
                         high_pc: original_var.high_pc,
                         low_pc: original_var.low_pc,
                         memory: None,
+                        string: None,
+                        heap_chunk: None,
+                        warnings: vec![],
                     }];
                 } else {
                     return vec![];
@@ -84,6 +327,9 @@ fn check_variable_recursive(
                         high_pc: original_var.high_pc,
                         low_pc: original_var.low_pc,
                         memory: None,
+                        string: None,
+                        heap_chunk: None,
+                        warnings: vec![],
                     });
                 }
                 return ret_val;
@@ -114,6 +360,9 @@ fn check_variable_recursive(
                             high_pc: original_var.high_pc,
                             low_pc: original_var.low_pc,
                             memory: None,
+                            string: None,
+                            heap_chunk: None,
+                            warnings: vec![],
                         });
                     }
                     if let Some(index) = index {
@@ -139,7 +388,7 @@ fn check_variable_recursive(
                         ));
                     }
                 } else {
-                    println!("Failed to read value at {:x}", addr);
+                    debug!("Failed to read value at {:x}", addr);
                 }
                 return ret_val;
             }
@@ -184,6 +433,9 @@ fn check_variable_recursive(
                         high_pc: original_var.high_pc,
                         low_pc: original_var.low_pc,
                         memory: None,
+                        string: None,
+                        heap_chunk: None,
+                        warnings: vec![],
                     });
                 }
                 return ret_val;
@@ -194,43 +446,329 @@ fn check_variable_recursive(
     }
 }
 impl Debugger {
+    /// Reads a NUL terminated string at `addr` word by word, stopping at unmapped memory or
+    /// after `max_len` bytes. Returns the bytes without the NUL and whether it was found.
+    pub fn read_c_string(&self, addr: u64, max_len: usize) -> Option<(Vec<u8>, bool)> {
+        let mut bytes = vec![];
+        while bytes.len() < max_len {
+            let Ok(word) = self.read((addr + bytes.len() as u64) as *mut c_void) else {
+                break;
+            };
+            for byte in word.to_le_bytes() {
+                if byte == 0 {
+                    return Some((bytes, true));
+                }
+                bytes.push(byte);
+            }
+        }
+        if bytes.is_empty() {
+            None
+        } else {
+            bytes.truncate(max_len);
+            Some((bytes, false))
+        }
+    }
+
+    /// Decodes the string a `char[]` or `char*` variable holds
+    fn decode_string(&self, variable: &DiscoveredVariable) -> Option<String> {
+        let memory = variable.memory.as_ref()?;
+        let value = &memory[VARIABLE_MEM_PADDING as usize..];
+        match &variable.types.0[variable.type_index].1 {
+            TypeName::Arr { arr_type, count }
+                if count.len() == 1 && is_char_type(&variable.types, *arr_type) =>
+            {
+                let bytes = &value[..count[0].min(value.len())];
+                Some(match bytes.iter().position(|b| *b == 0) {
+                    Some(end) => escape_string(&bytes[..end], true),
+                    None => escape_string(bytes, false),
+                })
+            }
+            TypeName::Ref { index: Some(index) } if is_char_type(&variable.types, *index) => {
+                let ptr = u64::from_le_bytes(value.get(..8)?.try_into().ok()?);
+                if ptr == 0 {
+                    return None;
+                }
+                let (bytes, terminated) = self.read_c_string(ptr, MAX_STRING_LENGTH)?;
+                Some(escape_string(&bytes, terminated))
+            }
+            _ => None,
+        }
+    }
+
     pub fn discover_variables(&self) -> Result<Vec<DiscoveredVariable>, DebugError> {
         let scope_variables = self.read_variables()?;
         let mut variables = vec![];
         let mapping = self.get_maps()?;
-        for scope_variable in scope_variables {
+        for scope_variable in scope_variables.into_iter().filter(|v| v.in_scope) {
+            self.check_cancelled()?;
+            let (Some(addr), Some(types)) = (scope_variable.addr, scope_variable.type_name.clone())
+            else {
+                continue;
+            };
             // println!("Discovering variable: {:?}", scope_variable);
             let mut scope_variables = check_variable_recursive(
                 &self,
                 &mapping,
                 &DiscoveredVariable {
-                    addr: scope_variable.addr,
+                    addr: Some(addr),
                     name: scope_variable.name.clone(),
                     type_index: 0,
-                    types: scope_variable.type_name.clone().unwrap(),
+                    types: types.clone(),
                     file: scope_variable.file.clone(),
                     line: scope_variable.line.clone(),
                     high_pc: scope_variable.high_pc,
                     low_pc: scope_variable.low_pc,
                     memory: None,
+                    string: None,
+                    heap_chunk: None,
+                    warnings: vec![],
                 },
-                scope_variable.addr.unwrap(),
+                addr,
                 0,
-                scope_variable.type_name.clone().unwrap(),
+                types,
                 scope_variable.name.clone().unwrap_or("unknown".to_string()),
                 false,
             );
             variables.append(&mut scope_variables);
         }
         for variable in &mut variables {
+            self.check_cancelled()?;
+            let Some(addr) = variable.addr else {
+                continue;
+            };
             variable.memory = self
                 .read_memory(
-                    variable.addr.unwrap() - VARIABLE_MEM_PADDING,
+                    addr.saturating_sub(VARIABLE_MEM_PADDING),
                     get_byte_size(&variable.types, variable.type_index) as u64
                         + VARIABLE_MEM_PADDING * 2,
                 )
                 .ok();
+            variable.string = self.decode_string(variable);
         }
+        // besides the variables only the targets of pointers are discovered, named `*pointer`
+        let chunks = self.heap_chunks(&mapping);
+        for variable in &mut variables {
+            if variable.name.as_ref().is_some_and(|n| n.starts_with('*')) {
+                variable.heap_chunk = variable.addr.and_then(|a| chunks.get(&a)).cloned();
+            }
+        }
+        add_pointer_warnings(&mut variables);
         Ok(variables)
     }
+
+    /// Finds all pointers into the variable containing `addr` (or `addr` itself if it doesn't
+    /// belong to a variable) by scanning the discovered variables and the registers
+    pub fn find_references(&self, addr: u64) -> Result<Vec<Reference>, DebugError> {
+        let variables = self.discover_variables()?;
+        let target = variables
+            .iter()
+            .filter_map(|v| {
+                let start = v.addr?;
+                Some(start..start + get_byte_size(&v.types, v.type_index).max(1) as u64)
+            })
+            .filter(|range| range.contains(&addr))
+            .max_by_key(|range| range.end - range.start)
+            .unwrap_or(addr..addr + 1);
+        let mut references = vec![];
+        for variable in &variables {
+            let (Some(address), Some(memory)) = (variable.addr, &variable.memory) else {
+                continue;
+            };
+            let mut pointers = vec![];
+            collect_pointers(
+                &variable.types,
+                variable.type_index,
+                memory
+                    .get(VARIABLE_MEM_PADDING as usize..)
+                    .unwrap_or_default(),
+                0,
+                String::new(),
+                &mut pointers,
+            );
+            for (offset, path, value) in pointers {
+                let pointer = address + offset as u64;
+                if target.contains(&value)
+                    && !references
+                        .iter()
+                        .any(|r: &Reference| r.address == Some(pointer))
+                {
+                    references.push(Reference {
+                        name: member_path(variable.name.as_deref().unwrap_or("unknown"), &path),
+                        address: Some(pointer),
+                        value,
+                    });
+                }
+            }
+        }
+        for (name, value) in self.get_named_registers()? {
+            if target.contains(&value) {
+                references.push(Reference {
+                    name,
+                    address: None,
+                    value,
+                });
+            }
+        }
+        Ok(references)
+    }
+
+    /// Evaluates `expression` (`<address>`, `<name>`, `&<name>`, optionally followed by
+    /// `+ n` or `- n` using C pointer arithmetic) to an address
+    fn evaluate_pointer_expression(
+        &self,
+        expression: &str,
+        variables: &[DiscoveredVariable],
+    ) -> Result<u64, DebugError> {
+        let invalid = |msg: &str| DebugError::InvalidArgument(format!("{}: {}", msg, expression));
+        let expression = expression.trim();
+        let (term, offset) = match expression.rfind(['+', '-']) {
+            Some(pos) if pos > 0 => {
+                let offset = expression[pos + 1..]
+                    .trim()
+                    .parse::<i64>()
+                    .map_err(|_| invalid("Invalid offset"))?;
+                let offset = if &expression[pos..pos + 1] == "-" {
+                    -offset
+                } else {
+                    offset
+                };
+                (expression[..pos].trim(), offset)
+            }
+            _ => (expression, 0),
+        };
+        let parse_number = |s: &str| match s.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16).ok(),
+            None => s.parse::<u64>().ok(),
+        };
+        let find_variable = |name: &str| {
+            variables
+                .iter()
+                .find(|v| v.name.as_deref() == Some(name))
+                .ok_or(invalid("Unknown variable"))
+        };
+        let (base, scale) = if let Some(address) = parse_number(term) {
+            (address, 1)
+        } else if let Some(name) = term.strip_prefix('&') {
+            let variable = find_variable(name.trim())?;
+            (
+                variable.addr.ok_or(invalid("Variable has no address"))?,
+                get_byte_size(&variable.types, variable.type_index) as u64,
+            )
+        } else {
+            let variable = find_variable(term)?;
+            let address = variable.addr.ok_or(invalid("Variable has no address"))?;
+            match &variable.types.0[variable.type_index].1 {
+                TypeName::Arr { arr_type, count: _ } => {
+                    (address, get_byte_size(&variable.types, *arr_type) as u64)
+                }
+                TypeName::Ref { index } => (
+                    self.read(address as *mut c_void)?,
+                    index
+                        .map(|i| get_byte_size(&variable.types, i) as u64)
+                        .unwrap_or(1),
+                ),
+                _ => return Err(invalid("Not a pointer or array, use &")),
+            }
+        };
+        i64::try_from(scale)
+            .ok()
+            .and_then(|scale| offset.checked_mul(scale))
+            .and_then(|offset| base.checked_add_signed(offset))
+            .ok_or(invalid("Address out of range"))
+    }
+
+    pub fn pointer_info(&self, expression: &str) -> Result<PointerInfo, DebugError> {
+        let variables = self.discover_variables()?;
+        let address = self.evaluate_pointer_expression(expression, &variables)?;
+        let region = self
+            .get_maps()?
+            .into_iter()
+            .find(|m| m.from <= address && address < m.to);
+        // The outermost variable containing the address, the element lookup walks into it
+        let variable = variables
+            .iter()
+            .filter(|v| {
+                v.addr.is_some_and(|start| {
+                    start <= address
+                        && address < start + get_byte_size(&v.types, v.type_index) as u64
+                })
+            })
+            // Prefer declared variables over aliases found through a pointer (`*ptr`)
+            .max_by_key(|v| {
+                (
+                    get_byte_size(&v.types, v.type_index),
+                    !v.name.as_ref().is_some_and(|n| n.starts_with('*')),
+                )
+            });
+        let (variable, element, element_address, element_size) = match variable {
+            Some(v) => {
+                let name = v.name.clone().unwrap_or("unknown".to_owned());
+                let start = v.addr.unwrap();
+                let (element, offset, size) =
+                    find_element(&v.types, v.type_index, name.clone(), address - start);
+                (Some(name), Some(element), Some(start + offset), Some(size))
+            }
+            None => (None, None, None, None),
+        };
+        Ok(PointerInfo {
+            address,
+            region,
+            variable,
+            element,
+            element_address,
+            element_size,
+        })
+    }
+
+    /// The discovered variables in memory with their type and formatted value
+    pub fn exported_variables(&self) -> Result<Vec<ExportedVariable>, DebugError> {
+        Ok(self
+            .discover_variables()?
+            .iter()
+            .filter_map(|variable| {
+                let address = variable.addr?;
+                let memory = variable.memory.as_ref()?;
+                let value = format_value(
+                    &variable.types,
+                    variable.type_index,
+                    &memory[VARIABLE_MEM_PADDING as usize..],
+                )
+                .unwrap_or_default();
+                // Show strings like gdb: `0x4006e4 "text"` for pointers, `"text"` for arrays
+                let value = match (&variable.string, &variable.types.0[variable.type_index].1) {
+                    (Some(string), TypeName::Ref { index: _ }) => format!("{} {}", value, string),
+                    (Some(string), _) => string.clone(),
+                    (None, _) => value,
+                };
+                Some(ExportedVariable {
+                    name: variable.name.clone().unwrap_or("unknown".to_owned()),
+                    type_name: get_type_name(&variable.types, variable.type_index),
+                    address,
+                    size: get_byte_size(&variable.types, variable.type_index),
+                    value,
+                })
+            })
+            .collect())
+    }
+
+    pub fn export_variables(&self, format: ExportFormat) -> Result<String, DebugError> {
+        let exported = self.exported_variables()?;
+        match format {
+            ExportFormat::Json => Ok(serde_json::to_string_pretty(&exported).unwrap()),
+            ExportFormat::Csv => {
+                Ok(exported
+                    .iter()
+                    .fold("name,type,address,size,value\n".to_owned(), |acc, v| {
+                        acc + &format!(
+                            "{},{},{:#x},{},{}\n",
+                            escape_csv(&v.name),
+                            escape_csv(&v.type_name),
+                            v.address,
+                            v.size,
+                            escape_csv(&v.value)
+                        )
+                    }))
+            }
+        }
+    }
 }
@@ -1,8 +1,24 @@
+use std::{
+    collections::HashMap,
+    io::Read,
+    os::unix::fs::PermissionsExt,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
 use include_dir::{include_dir, Dir};
-use stackium_shared::{Command, CommandOutput};
+use nix::sys::signal::{sigaction, SaFlags, SigAction, SigHandler, SigSet, Signal};
+use stackium_shared::{
+    Capabilities, Command, CommandOutput, Driver, ExportFormat, ReportFormat, SnapshotFormat,
+};
 use tiny_http::{Header, Response, Server};
 
-use crate::debugger::{error::DebugError, Debugger};
+use crate::debugger::{cancel::Cancellation, diagnostics, error::DebugError, Debugger};
 
 // static WEBSITE: &'static str = include_str!("../web/index.html");
 
@@ -10,6 +26,285 @@ static DIST_DIR: Dir<'_> = include_dir!("$CARGO_MANIFEST_DIR/ui/dist");
 
 type ResponseType = Response<std::io::Cursor<Vec<u8>>>;
 
+/// Largest program accepted by `/launch`
+const MAX_UPLOAD_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Threads receiving requests, the debugger runs on the thread which started the webserver
+const HTTP_THREADS: usize = 4;
+
+/// The driver of the session releases it after this long without commands changing the debugee
+const DRIVER_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Set when the webserver is asked to terminate, so the debugger is dropped and detaches
+static TERMINATE: AtomicBool = AtomicBool::new(false);
+
+extern "C" fn request_termination(_: nix::libc::c_int) {
+    TERMINATE.store(true, Ordering::SeqCst);
+}
+
+/// Set with `--read-only`: only clients sending the instructor token in the `token` query
+/// parameter may change the debugee, the others can only observe it, e.g. students following a
+/// live demonstration
+pub struct ReadOnly {
+    token: String,
+}
+
+impl ReadOnly {
+    /// Uses `token` or generates a random one
+    pub fn new(token: Option<String>) -> Result<Self, DebugError> {
+        let token = match token {
+            Some(token) => token,
+            None => {
+                let mut random = [0u8; 12];
+                std::fs::File::open("/dev/urandom")?.read_exact(&mut random)?;
+                random.iter().map(|b| format!("{:02x}", b)).collect()
+            }
+        };
+        Ok(Self { token })
+    }
+
+    fn authenticated(&self, query: &str) -> bool {
+        query_value(query, "token") == Some(self.token.as_str())
+    }
+}
+
+/// Whether `command` only observes the debugee, which read-only clients may execute. Commands
+/// added later are rejected until they are listed here.
+#[allow(deprecated)]
+fn observes(command: &Command) -> bool {
+    matches!(
+        command,
+        Command::GetStatus
+            | Command::GetDiagnostics
+            | Command::GetLineCalls
+            | Command::GetRegister
+            | Command::GetFullRegisters
+            | Command::ExplainBranch
+            | Command::FindFunc(_)
+            | Command::Read(_)
+            | Command::ReadMemory(_, _)
+            | Command::ReadAround(..)
+            | Command::ProgramCounter
+            | Command::DebugMeta
+            | Command::GetElfInfo
+            | Command::DumpDwarf
+            | Command::DumpTypes
+            | Command::Location
+            | Command::FindLine { .. }
+            | Command::ViewSource(_)
+            | Command::Backtrace
+            | Command::GetStackFrames
+            | Command::GetVariableScopes
+            | Command::DiscoverVariables
+            | Command::GetBreakpoints
+            | Command::GetActions
+            | Command::GetFunctions
+            | Command::FindFunctions { .. }
+            | Command::GetFile(_)
+            | Command::GetSource(_)
+            | Command::Disassemble
+            | Command::DisassembleFunction(_)
+            | Command::ResolveAddress(_)
+            | Command::GetPltEntries
+            | Command::Help
+            | Command::Maps
+            | Command::MapsDiff
+            | Command::GetProcessStack
+            | Command::ExportVariables(_)
+            | Command::ExportSnapshot(_)
+            | Command::GenerateReport(_)
+            | Command::ExportSession
+            | Command::FindReferences(_)
+            | Command::PointerInfo(_)
+            | Command::GetVariableHistory(_)
+            | Command::GetExecutionTrace
+            | Command::GetLineHitCounts
+            | Command::GetInvariants
+            | Command::GetWatches
+            | Command::GetDataStructures
+            | Command::GetProfile
+            | Command::GetExercise
+            | Command::GetQuiz
+            | Command::ReadVariables
+    )
+}
+
+/// Whether `command` changes which files of the host clients can read, which only the
+/// instructor may do like `--source-map` on the command line. Without `--read-only` there is no
+/// instructor and clients can't send it at all.
+fn reconfigures_host(command: &Command) -> bool {
+    matches!(command, Command::SetSourceMap(_))
+}
+
+fn instructor_only_response() -> ResponseType {
+    Response::from_string(
+        "Only the instructor can change the source map, start the backend with --source-map instead"
+            .to_owned(),
+    )
+    .with_status_code(403)
+}
+
+fn read_only_response() -> ResponseType {
+    Response::from_string(
+        "The backend is read-only, only the instructor can change the debugee".to_owned(),
+    )
+    .with_status_code(403)
+}
+
+/// Client changing the debugee, identified by the `client` query parameter
+struct DriverLock {
+    client: String,
+    name: String,
+    last_command: Instant,
+}
+
+/// Lets only one of several clients connected to the session change the debugee at a time, e.g.
+/// the instructor while a projector and students follow. The first client sending a command
+/// which doesn't only observe the debugee drives the session until it releases it or stops
+/// sending such commands for [`DRIVER_TIMEOUT`], the others can only observe meanwhile.
+#[derive(Default)]
+struct Session {
+    driver: Option<DriverLock>,
+    /// When the clients sent their last request, by their id
+    clients: HashMap<String, Instant>,
+}
+
+impl Session {
+    /// Called for every request, remembers that the client sending `query` is connected and
+    /// releases the session of an idle driver
+    fn seen(&mut self, query: &str) {
+        if let Some(client) = query_value(query, "client") {
+            self.clients.insert(client.to_owned(), Instant::now());
+        }
+        self.clients
+            .retain(|_, last_request| last_request.elapsed() < DRIVER_TIMEOUT);
+        if self
+            .driver
+            .as_ref()
+            .is_some_and(|d| d.last_command.elapsed() >= DRIVER_TIMEOUT)
+        {
+            self.driver = None;
+        }
+    }
+
+    /// Fails if another client than the one sending `query` drives the session
+    fn may_drive(&self, query: &str) -> Result<(), ResponseType> {
+        let client = query_value(query, "client").unwrap_or_default();
+        match &self.driver {
+            Some(driver) if driver.client != client => Err(Response::from_string(format!(
+                "The session is currently driven by {}",
+                display_name(&driver.name)
+            ))
+            .with_status_code(409)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Whether the client sending `query` drives the session
+    fn drives(&self, query: &str) -> bool {
+        let client = query_value(query, "client").unwrap_or_default();
+        self.driver.as_ref().is_some_and(|d| d.client == client)
+    }
+
+    /// Makes the client sending `query` the driver unless another client drives the session
+    fn drive(&mut self, query: &str) -> Result<(), ResponseType> {
+        self.may_drive(query)?;
+        let client = query_value(query, "client").unwrap_or_default();
+        let name = query_text(query, "client_name").unwrap_or_default();
+        match self.driver.as_mut() {
+            Some(driver) => {
+                driver.name = name;
+                driver.last_command = Instant::now();
+            }
+            None => {
+                self.driver = Some(DriverLock {
+                    client: client.to_owned(),
+                    name,
+                    last_command: Instant::now(),
+                })
+            }
+        }
+        Ok(())
+    }
+
+    /// `/driver`, the driver of the session as JSON or `null`
+    fn driver(&mut self, query: &str) -> ResponseType {
+        let client = query_value(query, "client").unwrap_or_default();
+        let driver = self.driver.as_ref().map(|d| Driver {
+            name: display_name(&d.name).to_owned(),
+            you: d.client == client,
+            expires_in: DRIVER_TIMEOUT
+                .saturating_sub(d.last_command.elapsed())
+                .as_secs(),
+            followers: self.clients.keys().filter(|c| **c != d.client).count() as u64,
+        });
+        Response::from_string(serde_json::to_string(&driver).unwrap())
+            .with_header("Content-Type: application/json".parse::<Header>().unwrap())
+    }
+
+    /// `/driver/release`, lets other clients drive the session if the client sending `query`
+    /// drives it
+    fn release(&mut self, query: &str) -> ResponseType {
+        if self.drives(query) {
+            self.driver = None;
+        }
+        Response::from_data([])
+    }
+}
+
+/// Commands sent with an id in the `request` query parameter which didn't finish yet, by the id
+/// of the client sending them and the request id, see `Command::Cancel`
+#[derive(Default)]
+struct Requests {
+    running: HashMap<(String, String), Cancellation>,
+}
+
+impl Requests {
+    /// Registers the command sent with `query`, `None` if it has no request id
+    fn start(&mut self, query: &str) -> Option<((String, String), Cancellation)> {
+        let request = query_text(query, "request")?;
+        let client = query_value(query, "client").unwrap_or_default().to_owned();
+        let cancellation = Cancellation::default();
+        self.running
+            .insert((client.clone(), request.clone()), cancellation.clone());
+        Some(((client, request), cancellation))
+    }
+
+    fn finish(&mut self, request: &(String, String)) {
+        self.running.remove(request);
+    }
+
+    /// Cancels the command the client sending `query` sent with the id `request`, clients can
+    /// only cancel their own commands
+    fn cancel(&mut self, query: &str, request: String) -> ResponseType {
+        let client = query_value(query, "client").unwrap_or_default().to_owned();
+        if let Some(cancellation) = self.running.get(&(client, request)) {
+            cancellation.cancel();
+        }
+        Response::from_string(serde_json::to_string(&CommandOutput::None).unwrap())
+            .with_header("Content-Type: application/json".parse::<Header>().unwrap())
+    }
+}
+
+fn display_name(name: &str) -> &str {
+    match name.is_empty() {
+        true => "an unnamed client",
+        false => name,
+    }
+}
+
+fn handle_termination() -> Result<(), DebugError> {
+    let action = SigAction::new(
+        SigHandler::Handler(request_termination),
+        SaFlags::empty(),
+        SigSet::empty(),
+    );
+    for signal in [Signal::SIGINT, Signal::SIGTERM, Signal::SIGHUP] {
+        unsafe { sigaction(signal, &action) }?;
+    }
+    Ok(())
+}
+
 fn index(debugger: &mut Debugger) -> ResponseType {
     Response::from_string(format!(
         "{} @ {}",
@@ -22,6 +317,21 @@ fn ping() -> ResponseType {
     Response::from_string("pong")
 }
 
+fn logs_forbidden() -> ResponseType {
+    Response::from_string(
+        "Only the instructor or the client driving the session can read the logs".to_owned(),
+    )
+    .with_status_code(403)
+}
+
+fn logs() -> ResponseType {
+    Response::from_string(diagnostics::log_text()).with_header(
+        "Content-Type: text/plain; charset=utf-8"
+            .parse::<Header>()
+            .unwrap(),
+    )
+}
+
 fn process_command(debugger: &mut Debugger, command: Command) -> ResponseType {
     let result = debugger.process_command(command);
     match result {
@@ -31,10 +341,262 @@ fn process_command(debugger: &mut Debugger, command: Command) -> ResponseType {
     }
 }
 
+/// Executes `command` and responds with the payload of its output, e.g. the registers instead of
+/// `{"FullRegisters": ..}`
+fn process_typed_command(debugger: &mut Debugger, command: Command) -> ResponseType {
+    match debugger.process_command(command) {
+        Ok(output) => {
+            let payload = match serde_json::to_value(&output).unwrap() {
+                serde_json::Value::Object(mut output) if output.len() == 1 => {
+                    output.values_mut().next().unwrap().take()
+                }
+                // `None`
+                _ => serde_json::Value::Null,
+            };
+            Response::from_string(payload.to_string())
+                .with_header("Content-Type: application/json".parse::<Header>().unwrap())
+        }
+        Err(err) => Response::from_string(format!("{:#?}", err)).with_status_code(500),
+    }
+}
+
+/// Value of the parameter `name` in a query like `addr=0x1000&len=16`
+fn query_value<'a>(query: &'a str, name: &str) -> Option<&'a str> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+}
+
+/// Like [`query_value`] for text, decodes `+` and percent-encoded bytes
+fn query_text(query: &str, name: &str) -> Option<String> {
+    let value = query_value(query, name)?.as_bytes();
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut i = 0;
+    while i < value.len() {
+        let hex = value
+            .get(i + 1..i + 3)
+            .and_then(|h| u8::from_str_radix(std::str::from_utf8(h).ok()?, 16).ok());
+        match (value[i], hex) {
+            (b'+', _) => bytes.push(b' '),
+            (b'%', Some(byte)) => {
+                bytes.push(byte);
+                i += 2;
+            }
+            (byte, _) => bytes.push(byte),
+        }
+        i += 1;
+    }
+    Some(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// Like [`query_value`] for numbers, hexadecimal with `0x`
+fn query_number(query: &str, name: &str) -> Option<u64> {
+    let value = query_value(query, name)?;
+    match value.strip_prefix("0x") {
+        Some(hex) => u64::from_str_radix(hex, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+/// `/memory?addr=0x1000&len=16`
+fn read_memory(debugger: &mut Debugger, query: &str) -> ResponseType {
+    match (query_number(query, "addr"), query_number(query, "len")) {
+        (Some(addr), Some(len)) => process_typed_command(debugger, Command::ReadMemory(addr, len)),
+        _ => Response::from_string("/memory requires the parameters addr and len")
+            .with_status_code(400),
+    }
+}
+
+fn export_variables(debugger: &mut Debugger, format: ExportFormat) -> ResponseType {
+    match debugger.process_command(Command::ExportVariables(format)) {
+        Ok(CommandOutput::File(content)) => Response::from_string(content)
+            .with_header(
+                format!(
+                    "Content-Type: {}",
+                    mime_guess::from_ext(format.extension()).first_or_text_plain()
+                )
+                .parse::<Header>()
+                .unwrap(),
+            )
+            .with_header(
+                format!(
+                    "Content-Disposition: attachment; filename=\"variables.{}\"",
+                    format.extension()
+                )
+                .parse::<Header>()
+                .unwrap(),
+            ),
+        Ok(_) => Response::from_string("Unexpected output").with_status_code(500),
+        Err(err) => Response::from_string(format!("{:#?}", err)).with_status_code(500),
+    }
+}
+
+fn export_session(debugger: &mut Debugger) -> ResponseType {
+    match debugger.process_command(Command::ExportSession) {
+        Ok(CommandOutput::File(content)) => Response::from_string(content)
+            .with_header("Content-Type: application/json".parse::<Header>().unwrap())
+            .with_header(
+                "Content-Disposition: attachment; filename=\"session.json\""
+                    .parse::<Header>()
+                    .unwrap(),
+            ),
+        Ok(_) => Response::from_string("Unexpected output").with_status_code(500),
+        Err(err) => Response::from_string(format!("{:#?}", err)).with_status_code(500),
+    }
+}
+
+fn export_snapshot(debugger: &mut Debugger, format: SnapshotFormat) -> ResponseType {
+    let content_type = match format {
+        SnapshotFormat::Json => "application/json",
+        SnapshotFormat::Binary => "application/gzip",
+    };
+    match debugger.process_command(Command::ExportSnapshot(format)) {
+        Ok(CommandOutput::Snapshot(content)) => Response::from_data(content)
+            .with_header(
+                format!("Content-Type: {}", content_type)
+                    .parse::<Header>()
+                    .unwrap(),
+            )
+            .with_header(
+                format!(
+                    "Content-Disposition: attachment; filename=\"snapshot.{}\"",
+                    format.extension()
+                )
+                .parse::<Header>()
+                .unwrap(),
+            ),
+        Ok(_) => Response::from_string("Unexpected output").with_status_code(500),
+        Err(err) => Response::from_string(format!("{:#?}", err)).with_status_code(500),
+    }
+}
+
+fn generate_report(debugger: &mut Debugger, format: ReportFormat) -> ResponseType {
+    match debugger.process_command(Command::GenerateReport(format)) {
+        Ok(CommandOutput::File(content)) => Response::from_string(content)
+            .with_header(
+                format!(
+                    "Content-Type: {}",
+                    mime_guess::from_ext(format.extension()).first_or_text_plain()
+                )
+                .parse::<Header>()
+                .unwrap(),
+            )
+            .with_header(
+                format!(
+                    "Content-Disposition: attachment; filename=\"report.{}\"",
+                    format.extension()
+                )
+                .parse::<Header>()
+                .unwrap(),
+            ),
+        Ok(_) => Response::from_string("Unexpected output").with_status_code(500),
+        Err(err) => Response::from_string(format!("{:#?}", err)).with_status_code(500),
+    }
+}
+
+/// Stores the uploaded binary in the temporary directory, C sources are compiled with debug
+/// information first. Returns the path of the binary.
+fn store_upload(name: &str, content: &[u8]) -> Result<PathBuf, String> {
+    let dir = std::env::temp_dir()
+        .join("stackium")
+        .join(std::process::id().to_string());
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    // the name is only used for the file in the directory
+    let name: String = name
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '_' | '-' => c,
+            _ => '_',
+        })
+        .collect();
+    let name = match name.trim_start_matches('.') {
+        "" => "program",
+        name => name,
+    };
+    let path = dir.join(name);
+    std::fs::write(&path, content).map_err(|e| e.to_string())?;
+    if name.ends_with(".c") {
+        let binary = path.with_extension("");
+        crate::compile_program(&path, &binary)?;
+        return Ok(binary);
+    }
+    if !content.starts_with(b"\x7fELF") {
+        return Err("Expected an ELF binary or a C source file ending in .c".to_owned());
+    }
+    std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+        .map_err(|e| e.to_string())?;
+    Ok(path)
+}
+
+/// Stores the program uploaded to `/launch?name=test.c`, see [`store_upload`]
+fn receive_upload(request: &mut tiny_http::Request) -> Result<PathBuf, ResponseType> {
+    let name = request
+        .url()
+        .split_once('?')
+        .and_then(|(_, query)| query_value(query, "name"))
+        .unwrap_or_default()
+        .to_owned();
+    let mut content = vec![];
+    if let Err(e) = request
+        .as_reader()
+        .take(MAX_UPLOAD_SIZE + 1)
+        .read_to_end(&mut content)
+    {
+        return Err(Response::from_string(e.to_string()).with_status_code(400));
+    }
+    if content.len() as u64 > MAX_UPLOAD_SIZE {
+        return Err(Response::from_string("The program is too large").with_status_code(413));
+    }
+    store_upload(&name, &content).map_err(|e| Response::from_string(e).with_status_code(400))
+}
+
+/// Replaces the debugged program with the uploaded `program`
+fn launch(debugger: &mut Debugger, program: PathBuf) -> ResponseType {
+    debugger.terminate();
+    match crate::launch_debuggee(
+        program,
+        debugger.sandbox.clone(),
+        debugger.user_code,
+        debugger.start_at_main,
+    ) {
+        Ok(mut launched) => {
+            launched.source_map = debugger.source_map.clone();
+            launched.allowed_sources = debugger.allowed_sources.clone();
+            *debugger = launched;
+            Response::from_string(debugger.program.to_string_lossy())
+        }
+        Err(err) => Response::from_string(format!("{:#?}", err)).with_status_code(500),
+    }
+}
+
 fn schema() -> ResponseType {
     Response::from_string(serde_json::to_string_pretty(&schemars::schema_for!(Command)).unwrap())
 }
 
+fn capabilities(read_only: bool) -> ResponseType {
+    let features = [
+        ("web", cfg!(feature = "web")),
+        ("gui", cfg!(feature = "gui")),
+    ]
+    .into_iter()
+    .filter(|(_, enabled)| *enabled)
+    .map(|(feature, _)| feature.to_owned())
+    .collect();
+    let capabilities = Capabilities {
+        read_only,
+        ..Capabilities::current(features)
+    };
+    Response::from_string(serde_json::to_string(&capabilities).unwrap())
+        .with_header("Content-Type: application/json".parse::<Header>().unwrap())
+}
+
+fn openapi() -> ResponseType {
+    Response::from_string(serde_json::to_string_pretty(&crate::openapi::openapi()).unwrap())
+        .with_header("Content-Type: application/json".parse::<Header>().unwrap())
+}
+
 fn res_schema() -> ResponseType {
     Response::from_string(
         serde_json::to_string_pretty(&schemars::schema_for!(CommandOutput)).unwrap(),
@@ -60,39 +622,276 @@ fn other(path: &str) -> ResponseType {
     return Response::from_data([]).with_status_code(404);
 }
 
-pub fn start_webserver(mut debugger: Debugger) -> Result<(), DebugError> {
-    println!("API available at localhost:8080");
-    let server = Server::http("0.0.0.0:8080").unwrap();
-    println!("UI available at http://localhost:8080/index.html");
-    for mut request in server.incoming_requests() {
-        match request.method() {
-            tiny_http::Method::Get => match request.url() {
-                "/schema" => request.respond(schema()),
-                "/response_schema" => request.respond(res_schema()),
-                "/" => request.respond(index(&mut debugger)),
-                "/ping" => request.respond(ping()),
-                path => {
-                    let path = path.to_string();
-                    request.respond(other(&path))
+/// Work for the debugger thread
+type Job = Box<dyn FnOnce(&mut Debugger) + Send>;
+
+/// Queue of the thread owning the [`Debugger`], which has to stay on the thread which started
+/// the debugee because only the thread tracing a process may use ptrace on it. The HTTP
+/// threads answer requests which don't need the debugger themselves, e.g. `/ping` and the UI,
+/// while a slow command runs.
+struct DebuggerThread {
+    jobs: mpsc::Sender<Job>,
+}
+
+impl DebuggerThread {
+    /// Runs `job` on the debugger thread after the jobs queued before and waits for its response
+    fn run(
+        &self,
+        job: impl FnOnce(&mut Debugger) -> ResponseType + Send + 'static,
+    ) -> ResponseType {
+        self.run_cancellable(Cancellation::default(), job)
+    }
+
+    /// Like [`run`](Self::run), but `job` isn't run if `cancellation` is cancelled while it is
+    /// queued and long commands it executes stop when it is cancelled while they run
+    fn run_cancellable(
+        &self,
+        cancellation: Cancellation,
+        job: impl FnOnce(&mut Debugger) -> ResponseType + Send + 'static,
+    ) -> ResponseType {
+        let (reply, response) = mpsc::channel();
+        let job: Job = Box::new(move |debugger| {
+            let response = match cancellation.is_cancelled() {
+                true => Response::from_string(format!("{:#?}", DebugError::Cancelled))
+                    .with_status_code(500),
+                false => {
+                    debugger.set_cancellation(cancellation);
+                    let response = run_catching_panics(debugger, job);
+                    debugger.set_cancellation(Cancellation::default());
+                    response
                 }
-            },
-            tiny_http::Method::Post => match request.url() {
-                "/command" => {
-                    let mut content = String::new();
-                    request.as_reader().read_to_string(&mut content).unwrap();
-                    let command = serde_json::from_str(&content);
-                    match command {
-                        Ok(command) => request.respond(process_command(&mut debugger, command)),
-                        Err(e) => request.respond(
-                            Response::from_string(format!("{:#?}", e)).with_status_code(500),
-                        ),
+            };
+            // the HTTP thread stops waiting when the server terminates
+            let _ = reply.send(response);
+        });
+        if self.jobs.send(job).is_err() {
+            return debugger_stopped();
+        }
+        response.recv().unwrap_or_else(|_| debugger_stopped())
+    }
+}
+
+/// Runs `job`, a panic while it runs is answered with [`DebugError::InternalError`] instead of
+/// stopping the debugger thread, so the session goes on with the next request
+fn run_catching_panics(
+    debugger: &mut Debugger,
+    job: impl FnOnce(&mut Debugger) -> ResponseType,
+) -> ResponseType {
+    match panic::catch_unwind(AssertUnwindSafe(|| job(debugger))) {
+        Ok(response) => response,
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_owned());
+            Response::from_string(format!("{:#?}", DebugError::InternalError(message)))
+                .with_status_code(500)
+        }
+    }
+}
+
+fn debugger_stopped() -> ResponseType {
+    Response::from_string("The debugger stopped").with_status_code(503)
+}
+
+/// State of the webserver shared by the HTTP threads
+struct Shared {
+    read_only: Option<ReadOnly>,
+    session: Mutex<Session>,
+    requests: Mutex<Requests>,
+}
+
+/// Receives requests until the server terminates, one of several threads sharing `server`
+fn serve(
+    server: &Server,
+    debugger: &DebuggerThread,
+    shared: &Shared,
+) -> Result<(), std::io::Error> {
+    while !TERMINATE.load(Ordering::SeqCst) {
+        let request = match server.recv_timeout(Duration::from_millis(100)) {
+            Ok(Some(request)) => request,
+            Ok(None) => continue,
+            // interrupted by a signal
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        // a panic is logged by the panic hook and tiny_http answers the dropped request with
+        // 500, the thread goes on with the next request
+        let _ = panic::catch_unwind(AssertUnwindSafe(|| {
+            handle_request(request, debugger, shared)
+        }));
+    }
+    Ok(())
+}
+
+/// Executes `command` sent to `/command` with `query`, it can be cancelled if the query has a
+/// request id
+fn run_command(
+    debugger: &DebuggerThread,
+    shared: &Shared,
+    query: &str,
+    command: Command,
+) -> ResponseType {
+    let started = shared.requests.lock().unwrap().start(query);
+    match started {
+        Some((request, cancellation)) => {
+            let response = debugger.run_cancellable(cancellation, |d| process_command(d, command));
+            shared.requests.lock().unwrap().finish(&request);
+            response
+        }
+        None => debugger.run(|d| process_command(d, command)),
+    }
+}
+
+fn handle_request(mut request: tiny_http::Request, debugger: &DebuggerThread, shared: &Shared) {
+    let (path, query) = request.url().split_once('?').unwrap_or((request.url(), ""));
+    let observer = shared
+        .read_only
+        .as_ref()
+        .is_some_and(|r| !r.authenticated(query));
+    let instructor = shared
+        .read_only
+        .as_ref()
+        .is_some_and(|r| r.authenticated(query));
+    let session = &shared.session;
+    let (path, query) = (path.to_owned(), query.to_owned());
+    session.lock().unwrap().seen(&query);
+    let response = match request.method() {
+        tiny_http::Method::Get => match path.as_str() {
+            "/registers" => debugger.run(|d| process_typed_command(d, Command::GetFullRegisters)),
+            "/breakpoints" => debugger.run(|d| process_typed_command(d, Command::GetBreakpoints)),
+            "/variables" => debugger.run(|d| process_typed_command(d, Command::DiscoverVariables)),
+            "/memory" => debugger.run(move |d| read_memory(d, &query)),
+            "/location" => debugger.run(|d| process_typed_command(d, Command::Location)),
+            "/backtrace" => debugger.run(|d| process_typed_command(d, Command::Backtrace)),
+            "/schema" => schema(),
+            "/response_schema" => res_schema(),
+            "/openapi.json" => openapi(),
+            "/" => debugger.run(index),
+            "/ping" => ping(),
+            // may contain paths and values of the debugee like the responses of commands
+            "/logs" if instructor || session.lock().unwrap().drives(&query) => logs(),
+            "/logs" => logs_forbidden(),
+            "/capabilities" => capabilities(observer),
+            "/driver" => session.lock().unwrap().driver(&query),
+            "/export/variables.json" => debugger.run(|d| export_variables(d, ExportFormat::Json)),
+            "/export/variables.csv" => debugger.run(|d| export_variables(d, ExportFormat::Csv)),
+            "/export/session.json" => debugger.run(export_session),
+            "/export/snapshot.json" => debugger.run(|d| export_snapshot(d, SnapshotFormat::Json)),
+            "/export/snapshot.json.gz" => {
+                debugger.run(|d| export_snapshot(d, SnapshotFormat::Binary))
+            }
+            "/export/report.html" => debugger.run(|d| generate_report(d, ReportFormat::Html)),
+            "/export/report.md" => debugger.run(|d| generate_report(d, ReportFormat::Markdown)),
+            path => other(path),
+        },
+        tiny_http::Method::Post => match path.as_str() {
+            "/command" => {
+                let mut content = String::new();
+                request.as_reader().read_to_string(&mut content).unwrap();
+                let command = serde_json::from_str(&content);
+                match command {
+                    // answered right away instead of waiting for the command it cancels, the
+                    // client ids aren't authenticated, so observers can't cancel commands
+                    Ok(Command::Cancel(_)) if observer => read_only_response(),
+                    Ok(Command::Cancel(request)) => {
+                        let driving = session.lock().unwrap().may_drive(&query);
+                        match driving {
+                            Ok(()) => shared.requests.lock().unwrap().cancel(&query, request),
+                            Err(response) => response,
+                        }
+                    }
+                    Ok(command) if !instructor && reconfigures_host(&command) => {
+                        instructor_only_response()
                     }
+                    Ok(Command::GetDiagnostics)
+                        if !instructor && !session.lock().unwrap().drives(&query) =>
+                    {
+                        logs_forbidden()
+                    }
+                    Ok(command) if observer && !observes(&command) => read_only_response(),
+                    Ok(command) => match observes(&command) {
+                        false => match session.lock().unwrap().drive(&query) {
+                            Ok(()) => run_command(debugger, shared, &query, command),
+                            Err(response) => response,
+                        },
+                        true => run_command(debugger, shared, &query, command),
+                    },
+                    Err(e) => Response::from_string(format!("{:#?}", e)).with_status_code(500),
+                }
+            }
+            "/launch" if observer => read_only_response(),
+            "/launch" => {
+                let driving = session.lock().unwrap().drive(&query);
+                match driving.and_then(|()| receive_upload(&mut request)) {
+                    Ok(program) => debugger.run(|d| launch(d, program)),
+                    Err(response) => response,
                 }
-                _ => request.respond(Response::empty(404)),
-            },
-            _ => request.respond(Response::empty(404)),
+            }
+            "/driver/release" => session.lock().unwrap().release(&query),
+            _ => Response::from_data([]).with_status_code(404),
+        },
+        _ => Response::from_data([]).with_status_code(404),
+    };
+    request
+        .respond(response)
+        .unwrap_or_else(|e| tracing::warn!("Failed to respond to request {}", e));
+}
+
+pub fn start_webserver(
+    mut debugger: Debugger,
+    read_only: Option<ReadOnly>,
+) -> Result<(), DebugError> {
+    println!("API available at localhost:8080");
+    let server = Arc::new(Server::http("0.0.0.0:8080").unwrap());
+    println!("UI available at http://localhost:8080/index.html");
+    if let Some(read_only) = &read_only {
+        println!(
+            "Read-only, set the backend URL to http://localhost:8080/?token={} in the settings of the UI to control the debugee",
+            read_only.token
+        );
+    }
+    handle_termination()?;
+    let shared = Arc::new(Shared {
+        read_only,
+        session: Mutex::new(Session::default()),
+        requests: Mutex::new(Requests::default()),
+    });
+    let (jobs, queue) = mpsc::channel::<Job>();
+    let http_threads = (0..HTTP_THREADS)
+        .map(|_| {
+            let (server, shared) = (server.clone(), shared.clone());
+            let debugger = DebuggerThread { jobs: jobs.clone() };
+            std::thread::spawn(move || {
+                let served = serve(&server, &debugger, &shared);
+                // the other threads and the debugger stop as well
+                TERMINATE.store(true, Ordering::SeqCst);
+                served
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(jobs);
+    while !TERMINATE.load(Ordering::SeqCst) {
+        match queue.recv_timeout(Duration::from_millis(100)) {
+            Ok(job) => job(&mut debugger),
+            Err(mpsc::RecvTimeoutError::Timeout) => {
+                // the continue timeout is also checked while no requests are sent
+                if let Err(e) = debugger.poll_child() {
+                    tracing::warn!("Failed polling child: {:?}", e);
+                }
+            }
+            Err(mpsc::RecvTimeoutError::Disconnected) => break,
         }
-        .unwrap_or_else(|e| eprintln!("Failed to respond to request {}", e));
     }
-    Ok(())
+    // answers the requests waiting for the debugger
+    drop(queue);
+    let mut served = Ok(());
+    for thread in http_threads {
+        if let Ok(Err(e)) = thread.join() {
+            served = Err(DebugError::IoError(e));
+        }
+    }
+    println!("Detaching from child {}", debugger.child);
+    served
 }
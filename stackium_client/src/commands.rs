@@ -0,0 +1,276 @@
+use stackium_shared::{
+    Action, BranchExplanation, Breakpoint, BreakpointPoint, Command, CommandOutput, DataStructure,
+    DebugMeta, DebugeeStatus, Diagnostics, DiscoveredVariable, DwarfAttribute, ElfInfo,
+    ExecutionTrace, Exercise, ExportFormat, Frame, FullRegisters, FunctionDisassembly,
+    FunctionGroup, FunctionMeta, HistoryEntry, Invariant, LineAddresses, LineHits, Location,
+    MapChange, MemoryChunk, MemoryMap, PlacedBreakpoint, PltEntry, PointerInfo, ProcessStack,
+    Profile, Quiz, QuizAnswer, QuizQuestion, Reference, Registers, ReportFormat, ResolvedAddress,
+    SnapshotFormat, SourceFile, SourceMapping, StackFrame, StopState, VariableScope, Watch,
+};
+
+use crate::{Client, Error};
+
+/// Output of [`Client::continue_running`]
+#[derive(Debug, Clone)]
+pub enum Continued {
+    /// The debugee stopped within the continue timeout
+    Stopped(StopState),
+    /// The debugee keeps running
+    Running(DebugeeStatus),
+}
+
+/// An `async` method for every entry, returning the payload of the output variant following `=>`
+/// or `()` for commands returning `None`
+macro_rules! commands {
+    () => {};
+    (
+        $(#[$doc:meta])*
+        $name:ident($($arg:ident: $ty:ty),*) = $command:expr => None;
+        $($rest:tt)*
+    ) => {
+        $(#[$doc])*
+        pub async fn $name(&self, $($arg: $ty),*) -> Result<(), Error> {
+            match self.command($command).await? {
+                CommandOutput::None => Ok(()),
+                output => Err(Error::UnexpectedOutput(Box::new(output))),
+            }
+        }
+        commands!($($rest)*);
+    };
+    (
+        $(#[$doc:meta])*
+        $name:ident($($arg:ident: $ty:ty),*) -> $output:ty = $command:expr => $variant:ident;
+        $($rest:tt)*
+    ) => {
+        $(#[$doc])*
+        pub async fn $name(&self, $($arg: $ty),*) -> Result<$output, Error> {
+            match self.command($command).await? {
+                CommandOutput::$variant(output) => Ok(output),
+                output => Err(Error::UnexpectedOutput(Box::new(output))),
+            }
+        }
+        commands!($($rest)*);
+    };
+}
+
+impl Client {
+    /// Resumes the execution of the debugee, see [`Command::Continue`]
+    pub async fn continue_running(&self) -> Result<Continued, Error> {
+        match self.command(Command::Continue).await? {
+            CommandOutput::StopState(stop) => Ok(Continued::Stopped(stop)),
+            CommandOutput::Status(status) => Ok(Continued::Running(status)),
+            output => Err(Error::UnexpectedOutput(Box::new(output))),
+        }
+    }
+
+    /// Sets a breakpoint, returns where breakpoints at a source location were placed
+    pub async fn set_breakpoint(
+        &self,
+        point: BreakpointPoint,
+    ) -> Result<Option<PlacedBreakpoint>, Error> {
+        match self.command(Command::SetBreakpoint(point)).await? {
+            CommandOutput::PlacedBreakpoint(placed) => Ok(Some(placed)),
+            CommandOutput::None => Ok(None),
+            output => Err(Error::UnexpectedOutput(Box::new(output))),
+        }
+    }
+
+    commands! {
+        /// Stops the running debugee
+        interrupt() -> StopState = Command::Interrupt => StopState;
+        /// Whether the debugee is running, stopped or exited and since when
+        get_status() -> DebugeeStatus = Command::GetStatus => Status;
+        /// Stops the debugee if it keeps computing for `seconds` after continuing, 0 disables it
+        set_continue_timeout(seconds: u64) = Command::SetContinueTimeout(seconds) => None;
+        /// Steps over the functions matching `patterns` like `helper_*` when stepping in
+        set_step_skip_list(patterns: Vec<String>) = Command::SetStepSkipList(patterns) => None;
+        /// Reads the source files of the debug information below `from` in `to` instead
+        set_source_map(mappings: Vec<SourceMapping>) = Command::SetSourceMap(mappings) => None;
+        /// Detaches from the debugee, which keeps running without the debugger
+        detach_debugger() = Command::DetachDebugger => None;
+        /// Program counter, base pointer and stack pointer
+        get_register() -> Registers = Command::GetRegister => Registers;
+        /// All general purpose, flags and segment registers
+        get_full_registers() -> FullRegisters = Command::GetFullRegisters => FullRegisters;
+        /// Sets the register `name` to `value`
+        set_register(name: &str, value: u64) =
+            Command::SetRegister(name.to_owned(), value) => None;
+        /// Which flags the conditional instruction at the program counter tests
+        explain_branch() -> BranchExplanation = Command::ExplainBranch => BranchExplanation;
+        /// Steps the debugee by one instruction
+        step_instruction() -> StopState = Command::StepInstruction => StopState;
+        /// Steps the debugee by `count` instructions
+        step_instruction_n(count: u64) -> StopState =
+            Command::StepInstructionN(count) => StopState;
+        /// Finds the function called `name`
+        find_func(name: &str) -> FunctionMeta = Command::FindFunc(name.to_owned()) => FunctionMeta;
+        /// Reads 8 bytes at `address`
+        read(address: u64) -> u64 = Command::Read(address) => Data;
+        /// Reads `len` bytes at `address`
+        read_memory(address: u64, len: u64) -> Vec<u8> =
+            Command::ReadMemory(address, len) => Memory;
+        /// Writes `bytes` to `address`
+        write_memory(address: u64, bytes: Vec<u8>) =
+            Command::WriteMemory(address, bytes) => None;
+        /// Reads the bytes around `address`, limited to the region containing it
+        read_around(address: u64, before: u64, after: u64) -> MemoryChunk =
+            Command::ReadAround(address, before, after) => MemoryChunk;
+        /// Address of the current instruction
+        program_counter() -> u64 = Command::ProgramCounter => Data;
+        /// Statistics of the program
+        debug_meta() -> DebugMeta = Command::DebugMeta => DebugMeta;
+        /// The headers, sections, segments and symbol tables of the program
+        get_elf_info() -> ElfInfo = Command::GetElfInfo => ElfInfo;
+        /// All DWARF debug information
+        dump_dwarf() -> Vec<DwarfAttribute> = Command::DumpDwarf => DwarfAttributes;
+        /// The decoded type of every variable as text
+        dump_types() -> String = Command::DumpTypes => File;
+        /// The current location in the source code
+        location() -> Location = Command::Location => Location;
+        /// All addresses of `line` in `filename`, or of the next line with code
+        find_line(line: u64, filename: &str) -> LineAddresses = Command::FindLine {
+            line,
+            filename: filename.to_owned(),
+        } => LineAddresses;
+        /// Sets or removes breakpoints on all allocation functions
+        break_on_allocation(enabled: bool) = Command::BreakOnAllocation(enabled) => None;
+        /// Sets or removes breakpoints on all I/O functions
+        break_on_io(enabled: bool) = Command::BreakOnIO(enabled) => None;
+        /// Continues until the current function returns
+        step_out() -> StopState = Command::StepOut => StopState;
+        /// Continues until `count` frames returned, with the value each of them returned
+        finish_frames(count: u64) -> StopState = Command::FinishFrames(count) => StopState;
+        /// Continues until a new line is reached
+        step_in() -> StopState = Command::StepIn => StopState;
+        /// Continues until the next statement is reached
+        step_statement() -> StopState = Command::StepStatement => StopState;
+        /// Continues until `function` is entered from the current line
+        step_into_target(function: &str) -> StopState =
+            Command::StepIntoTarget(function.to_owned()) => StopState;
+        /// The functions called on the current line
+        get_line_calls() -> Vec<String> = Command::GetLineCalls => LineCalls;
+        /// The lines around the current location with whether they are the current one
+        view_source(window: usize) -> Vec<(u64, String, bool)> =
+            Command::ViewSource(window) => CodeWindow;
+        /// The frames on the stack with the registers they saved, innermost first
+        backtrace() -> Vec<Frame> = Command::Backtrace => Backtrace;
+        /// The boundaries of all frames on the stack, innermost first
+        get_stack_frames() -> Vec<StackFrame> = Command::GetStackFrames => StackFrames;
+        /// The variables of the current function grouped by blocks
+        get_variable_scopes() -> Vec<VariableScope> =
+            Command::GetVariableScopes => VariableScopes;
+        /// All variables including the ones on the heap
+        discover_variables() -> Vec<DiscoveredVariable> =
+            Command::DiscoverVariables => DiscoveredVariables;
+        /// Restarts the debugee, keeping the breakpoints
+        restart_debugee() = Command::RestartDebugee => None;
+        /// All breakpoints
+        get_breakpoints() -> Vec<Breakpoint> = Command::GetBreakpoints => Breakpoints;
+        /// Deletes the breakpoint at `address`
+        delete_breakpoint(address: u64) = Command::DeleteBreakpoint(address) => None;
+        /// Reverts the last action, returns the remaining ones
+        undo() -> Vec<Action> = Command::Undo => Actions;
+        /// The actions that can be undone, most recent last
+        get_actions() -> Vec<Action> = Command::GetActions => Actions;
+        /// All functions of the program
+        get_functions() -> Vec<FunctionMeta> = Command::GetFunctions => Functions;
+        /// Functions whose name contains `pattern` grouped by source file, only the ones of the
+        /// user with `user_code`
+        find_functions(pattern: Option<String>, user_code: bool) -> Vec<FunctionGroup> =
+            Command::FindFunctions { pattern, user_code } => FunctionGroups;
+        /// Content of the source file `path`
+        get_file(path: &str) -> String = Command::GetFile(path.to_owned()) => File;
+        /// A source file with where it was read from, its `content` is `None` if it is missing
+        get_source(path: &str) -> SourceFile = Command::GetSource(path.to_owned()) => Source;
+        /// Disassembly of the binary
+        disassemble() -> String = Command::Disassemble => File;
+        /// Disassembly of a function grouped by source line
+        disassemble_function(function: &str) -> FunctionDisassembly =
+            Command::DisassembleFunction(function.to_owned()) => FunctionDisassembly;
+        /// Function, line, section and mapping containing `address`
+        resolve_address(address: u64) -> ResolvedAddress =
+            Command::ResolveAddress(address) => ResolvedAddress;
+        /// The imported functions with their PLT stub and GOT slot
+        get_plt_entries() -> Vec<PltEntry> = Command::GetPltEntries => PltEntries;
+        /// The memory maps of the debugee
+        maps() -> Vec<MemoryMap> = Command::Maps => Maps;
+        /// Regions added, removed or resized since the previous stop
+        maps_diff() -> Vec<MapChange> = Command::MapsDiff => MapChanges;
+        /// `argc`, `argv`, `envp` and the auxiliary vector at the top of the stack
+        process_stack() -> ProcessStack = Command::GetProcessStack => ProcessStack;
+        /// The discovered variables as JSON or CSV
+        export_variables(format: ExportFormat) -> String =
+            Command::ExportVariables(format) => File;
+        /// Snapshot of the current stop which can be viewed offline
+        export_snapshot(format: SnapshotFormat) -> Vec<u8> =
+            Command::ExportSnapshot(format) => Snapshot;
+        /// Report of the current stop as HTML or Markdown
+        generate_report(format: ReportFormat) -> String =
+            Command::GenerateReport(format) => File;
+        /// Recording of the session as JSON
+        export_session() -> String = Command::ExportSession => File;
+        /// Variables and registers pointing into the variable at `address`
+        find_references(address: u64) -> Vec<Reference> =
+            Command::FindReferences(address) => References;
+        /// Where the address of a pointer expression like `&var - 1` falls into
+        pointer_info(expression: &str) -> PointerInfo =
+            Command::PointerInfo(expression.to_owned()) => PointerInfo;
+        /// Records the value of the variable `name` at every stop
+        track_variable(name: &str) = Command::TrackVariable(name.to_owned()) => None;
+        /// Stops recording the value of the variable `name`
+        untrack_variable(name: &str) = Command::UntrackVariable(name.to_owned()) => None;
+        /// The recorded values of the tracked variable `name`
+        get_variable_history(name: &str) -> Vec<HistoryEntry> =
+            Command::GetVariableHistory(name.to_owned()) => VariableHistory;
+        /// Enables or disables tracing the executed lines
+        set_tracing(enabled: bool) = Command::SetTracing(enabled) => None;
+        /// The lines executed while tracing was enabled
+        get_execution_trace() -> ExecutionTrace = Command::GetExecutionTrace => ExecutionTrace;
+        /// How often the first line of every loop body was executed
+        get_line_hit_counts() -> Vec<LineHits> = Command::GetLineHitCounts => LineHitCounts;
+        /// Adds an invariant, returns all invariants
+        add_invariant(expression: &str) -> Vec<Invariant> =
+            Command::AddInvariant(expression.to_owned()) => Invariants;
+        /// Removes an invariant, returns the remaining ones
+        remove_invariant(expression: &str) -> Vec<Invariant> =
+            Command::RemoveInvariant(expression.to_owned()) => Invariants;
+        /// The invariants with their values at the last check
+        get_invariants() -> Vec<Invariant> = Command::GetInvariants => Invariants;
+        /// Watches the memory of `expression`, single-stepping only `function` if given, returns
+        /// all watches
+        add_watch(expression: &str, function: Option<String>) -> Vec<Watch> =
+            Command::AddWatch { expression: expression.to_owned(), function } => Watches;
+        /// Removes a watch, returns the remaining ones
+        remove_watch(expression: &str) -> Vec<Watch> =
+            Command::RemoveWatch(expression.to_owned()) => Watches;
+        /// The watches with their values at the last check
+        get_watches() -> Vec<Watch> = Command::GetWatches => Watches;
+        /// Linked lists and binary trees reached from the variables
+        get_data_structures() -> Vec<DataStructure> =
+            Command::GetDataStructures => DataStructures;
+        /// Continues while sampling the current function every `period` instructions
+        profile(period: u64) = Command::Profile(period) => None;
+        /// The result of the last profiling run
+        get_profile() -> Profile = Command::GetProfile => Profile;
+        /// The exercise next to the program with its current step
+        get_exercise() -> Option<Exercise> = Command::GetExercise => Exercise;
+        /// Asks the question whenever the debugee stops at its breakpoint, returns the quiz
+        add_quiz_question(question: QuizQuestion) -> Quiz =
+            Command::AddQuizQuestion(question) => Quiz;
+        /// Removes the question, returns the quiz
+        remove_quiz_question(question: QuizQuestion) -> Quiz =
+            Command::RemoveQuizQuestion(question) => Quiz;
+        /// The questions, the ones pending at the current stop and the answers given so far
+        get_quiz() -> Quiz = Command::GetQuiz => Quiz;
+        /// Checks the answer to the pending question about `expression`
+        answer_quiz(expression: &str, answer: &str) -> QuizAnswer = Command::AnswerQuiz {
+            expression: expression.to_owned(),
+            answer: answer.to_owned(),
+        } => QuizAnswer;
+        /// Cancels the command sent with
+        /// [`cancellable_command_then`](Client::cancellable_command_then) and `request`
+        cancel(request: &str) = Command::Cancel(request.to_owned()) => None;
+        /// The version and platform of the backend with its recent log messages
+        get_diagnostics() -> Diagnostics = Command::GetDiagnostics => Diagnostics;
+    }
+}
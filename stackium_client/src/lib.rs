@@ -0,0 +1,261 @@
+//! Client for the web API of the debugger, used by the UI and by tools driving the debugger
+//! The requests are sent with `ehttp`, so the client works natively and in the browser. Every
+//! command has an `async` method returning its output, e.g. [`Client::get_breakpoints`], the
+//! `*_then` methods call a callback instead for callers without an executor like the UI.
+
+use std::{
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use ehttp::{fetch, Request, Response};
+use stackium_shared::{Capabilities, Command, CommandOutput, Driver};
+use url::Url;
+
+mod commands;
+
+pub use commands::Continued;
+
+#[derive(Debug)]
+pub enum Error {
+    /// The request didn't reach the backend or the response couldn't be read
+    Request(String),
+    /// The backend failed to execute the command, contains the error it responded with
+    Backend(String),
+    /// The command returned another output than expected, e.g. because the backend is newer
+    UnexpectedOutput(Box<CommandOutput>),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Request(e) => write!(f, "Error: {}", e),
+            Error::Backend(e) => write!(f, "{}", e),
+            Error::UnexpectedOutput(output) => write!(f, "Unexpected output {:?}", output),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Connection to a debugger started with `-m web`
+#[derive(Debug, Clone)]
+pub struct Client {
+    url: Url,
+}
+
+impl Client {
+    /// Client for the backend at `url`, e.g. `http://localhost:8080`
+    pub fn new(url: Url) -> Self {
+        Self { url }
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// `path` on the backend with the query of the backend URL, e.g. the instructor token of a
+    /// read-only backend in `http://localhost:8080/?token=...`
+    fn endpoint(&self, path: &str) -> Result<Url, Error> {
+        let mut url = self
+            .url
+            .join(path)
+            .map_err(|e| Error::Request(e.to_string()))?;
+        url.set_query(self.url.query());
+        Ok(url)
+    }
+
+    /// Sends `request` and calls `on_done` with the body of a successful response
+    fn fetch_then(
+        request: Result<Request, Error>,
+        on_done: impl FnOnce(Result<Vec<u8>, Error>) + Send + 'static,
+    ) {
+        let request = match request {
+            Ok(request) => request,
+            Err(e) => return on_done(Err(e)),
+        };
+        fetch(request, move |response| {
+            on_done(match response {
+                Ok(Response {
+                    ok: true, bytes, ..
+                }) => Ok(bytes),
+                Ok(response) => Err(Error::Backend(
+                    response
+                        .text()
+                        .map(str::to_owned)
+                        .unwrap_or(response.status_text),
+                )),
+                Err(e) => Err(Error::Request(e)),
+            })
+        });
+    }
+
+    /// Executes `command` on the backend and calls `on_done` with its output
+    pub fn command_then(
+        &self,
+        command: &Command,
+        on_done: impl FnOnce(Result<CommandOutput, Error>) + Send + 'static,
+    ) {
+        self.request_then(command, None, on_done)
+    }
+
+    /// Like [`command_then`](Self::command_then), the command can be cancelled with
+    /// [`Command::Cancel`] and `request` until it finished
+    pub fn cancellable_command_then(
+        &self,
+        command: &Command,
+        request: &str,
+        on_done: impl FnOnce(Result<CommandOutput, Error>) + Send + 'static,
+    ) {
+        self.request_then(command, Some(request), on_done)
+    }
+
+    fn request_then(
+        &self,
+        command: &Command,
+        request: Option<&str>,
+        on_done: impl FnOnce(Result<CommandOutput, Error>) + Send + 'static,
+    ) {
+        let request = self.endpoint("/command").map(|mut url| {
+            if let Some(request) = request {
+                url.query_pairs_mut().append_pair("request", request);
+            }
+            Request::post(
+                url,
+                serde_json::to_vec(command).expect("commands are serializable"),
+            )
+        });
+        Self::fetch_then(request, move |body| {
+            on_done(body.and_then(|body| {
+                serde_json::from_slice(&body)
+                    .map_err(|_| Error::Backend(String::from_utf8_lossy(&body).into_owned()))
+            }))
+        });
+    }
+
+    /// Requests what the backend supports and calls `on_done` with it, fails for backends older
+    /// than the `/capabilities` endpoint
+    pub fn capabilities_then(
+        &self,
+        on_done: impl FnOnce(Result<Capabilities, Error>) + Send + 'static,
+    ) {
+        let request = self.endpoint("/capabilities").map(Request::get);
+        Self::fetch_then(request, move |body| {
+            on_done(body.and_then(|body| {
+                serde_json::from_slice(&body).map_err(|e| Error::Request(e.to_string()))
+            }))
+        });
+    }
+
+    /// Requests the client driving the session and calls `on_done` with it, `None` if no client
+    /// drives it. The client is identified by the `client` parameter in the query of the
+    /// backend URL.
+    pub fn driver_then(
+        &self,
+        on_done: impl FnOnce(Result<Option<Driver>, Error>) + Send + 'static,
+    ) {
+        let request = self.endpoint("/driver").map(Request::get);
+        Self::fetch_then(request, move |body| {
+            on_done(body.and_then(|body| {
+                serde_json::from_slice(&body).map_err(|e| Error::Request(e.to_string()))
+            }))
+        });
+    }
+
+    /// Lets other clients drive the session if this client drives it
+    pub fn release_driver_then(&self, on_done: impl FnOnce(Result<(), Error>) + Send + 'static) {
+        let request = self
+            .endpoint("/driver/release")
+            .map(|url| Request::post(url, vec![]));
+        Self::fetch_then(request, move |body| on_done(body.map(|_| ())));
+    }
+
+    /// Uploads a binary or C source file to debug it instead of the current program, calls
+    /// `on_done` with the path of the launched binary
+    pub fn launch_then(
+        &self,
+        name: &str,
+        content: Vec<u8>,
+        on_done: impl FnOnce(Result<String, Error>) + Send + 'static,
+    ) {
+        let request = self.endpoint("/launch").map(|mut url| {
+            url.query_pairs_mut().append_pair("name", name);
+            Request::post(url, content)
+        });
+        Self::fetch_then(request, move |body| {
+            on_done(body.map(|body| String::from_utf8_lossy(&body).into_owned()))
+        });
+    }
+
+    /// Executes `command` on the backend
+    pub async fn command(&self, command: Command) -> Result<CommandOutput, Error> {
+        let (on_done, pending) = pending();
+        self.command_then(&command, on_done);
+        pending.await
+    }
+
+    /// Version, supported commands and features of the backend
+    pub async fn capabilities(&self) -> Result<Capabilities, Error> {
+        let (on_done, pending) = pending();
+        self.capabilities_then(on_done);
+        pending.await
+    }
+
+    /// The client driving the session, `None` if no client drives it
+    pub async fn driver(&self) -> Result<Option<Driver>, Error> {
+        let (on_done, pending) = pending();
+        self.driver_then(on_done);
+        pending.await
+    }
+
+    /// Lets other clients drive the session if this client drives it
+    pub async fn release_driver(&self) -> Result<(), Error> {
+        let (on_done, pending) = pending();
+        self.release_driver_then(on_done);
+        pending.await
+    }
+
+    /// Uploads a binary or C source file to debug it instead of the current program, returns the
+    /// path of the launched binary
+    pub async fn launch(&self, name: &str, content: Vec<u8>) -> Result<String, Error> {
+        let (on_done, pending) = pending();
+        self.launch_then(name, content, on_done);
+        pending.await
+    }
+}
+
+/// Result of a callback, resolves once the callback was called
+struct Pending<T> {
+    state: Arc<Mutex<(Option<T>, Option<Waker>)>>,
+}
+
+fn pending<T: Send + 'static>() -> (impl FnOnce(T) + Send + 'static, Pending<T>) {
+    let state = Arc::new(Mutex::new((None, None::<Waker>)));
+    let callback_state = state.clone();
+    let callback = move |value| {
+        let mut state = callback_state.lock().unwrap();
+        state.0 = Some(value);
+        if let Some(waker) = state.1.take() {
+            waker.wake();
+        }
+    };
+    (callback, Pending { state })
+}
+
+impl<T> Future for Pending<T> {
+    type Output = T;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<T> {
+        let mut state = self.state.lock().unwrap();
+        match state.0.take() {
+            Some(value) => Poll::Ready(value),
+            None => {
+                state.1 = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
@@ -5,14 +5,289 @@ use std::str::FromStr;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Default, Serialize, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Default, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct Registers {
     pub instruction_pointer: u64,
     pub base_pointer: u64,
     pub stack_pointer: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Register {
+    pub name: String,
+    pub value: u64,
+}
+
+/// A single bit of the flags register
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Flag {
+    /// Short name like `ZF`
+    pub name: String,
+    pub description: String,
+    pub bit: u8,
+    pub set: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FullRegisters {
+    /// General purpose registers including the instruction and stack pointer
+    pub general: Vec<Register>,
+    /// Raw value of the flags register (`eflags` on x86_64, `pstate` on aarch64)
+    pub flags_register: u64,
+    pub flags: Vec<Flag>,
+    /// Segment registers, empty on architectures without segmentation
+    pub segment: Vec<Register>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Instruction {
+    pub address: u64,
+    /// Machine code as hex bytes separated by spaces
+    pub bytes: String,
+    pub text: String,
+}
+
+/// Consecutive instructions generated from the same source line
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SourceBlock {
+    pub location: Option<Location>,
+    /// Text of the source line if the file could be read
+    pub source: Option<String>,
+    pub instructions: Vec<Instruction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct FunctionDisassembly {
+    pub name: Option<String>,
+    pub low_pc: u64,
+    pub high_pc: u64,
+    pub blocks: Vec<SourceBlock>,
+}
+
+/// An imported function called through the procedure linkage table, see `GetPltEntries`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PltEntry {
+    pub function: String,
+    /// Stub in `.plt` or `.plt.sec` the program calls instead of the function
+    pub plt_address: u64,
+    /// Slot of the global offset table the stub jumps through
+    pub got_address: u64,
+    /// Address the slot holds now
+    pub target: u64,
+    /// Whether the dynamic linker patched the slot with the address of the function. Before
+    /// the first call it points back into the stub, which runs the dynamic linker.
+    pub resolved: bool,
+    /// Where the slot points, e.g. `libc.so.6+0x80e50` or `puts@plt+0x6`
+    pub target_description: String,
+}
+
+/// Headers, sections, segments and symbol tables of the debugged program, see `GetElfInfo`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ElfInfo {
+    /// 32 or 64
+    pub bits: u8,
+    pub little_endian: bool,
+    /// `Executable`, `Shared object or position independent executable`, `Relocatable` or
+    /// `Core dump`
+    pub file_type: String,
+    pub architecture: String,
+    /// Address the program starts at, usually `_start`
+    pub entry: u64,
+    /// Dynamic linker requested in `PT_INTERP`, `None` if the program is linked statically
+    pub interpreter: Option<String>,
+    pub sections: Vec<ElfSection>,
+    pub segments: Vec<ElfSegment>,
+    pub symbol_tables: Vec<ElfSymbolTable>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ElfSection {
+    pub name: String,
+    /// e.g. `PROGBITS` or `NOBITS`
+    pub section_type: String,
+    pub address: u64,
+    pub offset: u64,
+    pub size: u64,
+    /// Like readelf: `W` writable, `A` allocated at runtime, `X` executable, `M` mergeable,
+    /// `S` strings, `T` thread local
+    pub flags: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ElfSegment {
+    /// e.g. `LOAD`, `DYNAMIC` or `GNU_STACK`
+    pub segment_type: String,
+    pub offset: u64,
+    pub address: u64,
+    pub file_size: u64,
+    /// Larger than `file_size` if the segment contains zeroed memory like `.bss`
+    pub memory_size: u64,
+    /// `R`, `W` and `X` like `r-x` in the memory maps
+    pub flags: String,
+    /// Sections mapped by the segment
+    pub sections: Vec<String>,
+}
+
+/// The number of symbols in `.symtab` or `.dynsym`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ElfSymbolTable {
+    pub name: String,
+    pub symbols: usize,
+    pub functions: usize,
+    pub objects: usize,
+    /// Symbols defined in another file, e.g. functions of libc
+    pub undefined: usize,
+}
+
+/// Everything known about an address
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ResolvedAddress {
+    pub address: u64,
+    /// Enclosing function from the debug information or the symbol table
+    pub function: Option<String>,
+    /// Offset from the start of `function`
+    pub offset: Option<u64>,
+    pub location: Option<Location>,
+    /// Section of the program, e.g. `.text` or `.data`
+    pub section: Option<String>,
+    pub mapping: Option<MemoryMap>,
+    /// Short description like `main+0x24 (test.c:12)` or `libc.so.6+0x29d90`
+    pub description: String,
+}
+
+/// Explanation of the conditional instruction at the program counter
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct BranchExplanation {
+    pub address: u64,
+    /// Disassembled instruction, e.g. `jle 40113e <main+0x18>`
+    pub instruction: String,
+    /// Condition in terms of flags, e.g. `ZF = 1 or SF != OF`; `None` if the instruction isn't conditional
+    pub condition: Option<String>,
+    /// Flags tested by the condition with their current value
+    pub tested_flags: Vec<Flag>,
+    /// Whether the condition holds, i.e. the jump will be taken
+    pub taken: Option<bool>,
+    pub explanation: String,
+}
+
+/// Call of a library function the debugee stopped at because of `BreakOnAllocation` or
+/// `BreakOnIO`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LibraryCall {
+    pub function: String,
+    /// Name and formatted value of each argument, e.g. `("size", "40")`
+    pub arguments: Vec<(String, String)>,
+    /// Code the function was called from, e.g. `main+0x24 (test.c:12)`
+    pub caller: Option<String>,
+}
+
+/// Value returned by the function that was stepped out of
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ReturnValue {
+    pub function: Option<String>,
+    /// C-like name of the return type, `None` for `void` functions
+    pub type_name: Option<String>,
+    /// Formatted value, `None` for `void` functions or if it couldn't be read
+    pub value: Option<String>,
+    /// Content of the integer return register (`rax` on x86_64, `x0` on aarch64)
+    pub raw: u64,
+}
+
+/// Why the debugee stopped after a command resumed it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub enum StopReason {
+    /// The program was started or restarted and stopped at the first line of `main`, or at its
+    /// entry point if the debugger was started with `--stop-at-entry`
+    Started,
+    /// The command finished stepping
+    Step,
+    /// An enabled breakpoint was hit
+    Breakpoint,
+    /// Stopped by a signal, e.g. `SIGSEGV`
+    Signal(String),
+    /// Stopped with `Interrupt` while running
+    Interrupted,
+    /// Stopped because it kept computing for `seconds` after continuing, probably an infinite
+    /// loop. `backtrace` holds the names of the functions it was in, innermost first.
+    Timeout {
+        seconds: u64,
+        backtrace: Vec<String>,
+    },
+    /// The invariant added with `AddInvariant` became false
+    InvariantBroken(String),
+    /// The memory watched with `AddWatch` changed, with the values before and after
+    WatchChanged {
+        expression: String,
+        old: String,
+        new: String,
+    },
+    /// The program exited with the status code
+    Exited(i32),
+    /// The program was terminated by the signal
+    Killed(String),
+}
+
+/// State of the debugee after a step or continue command
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct StopState {
+    pub reason: StopReason,
+    /// `None` if the program isn't running anymore
+    pub registers: Option<Registers>,
+    pub location: Option<Location>,
+    /// The breakpoint the debugee stopped at
+    pub breakpoint: Option<Breakpoint>,
+    /// Value returned by the function stepped out of with `StepOut`
+    pub return_value: Option<ReturnValue>,
+    /// Values returned by the frames finished with `FinishFrames`, innermost first
+    #[serde(default)]
+    pub returned_frames: Vec<ReturnValue>,
+    /// Call of a library function stopped at because of `BreakOnAllocation` or `BreakOnIO`
+    pub library_call: Option<LibraryCall>,
+}
+
+/// Lifecycle of the debugee
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub enum DebugeeState {
+    /// Continued and didn't stop yet, e.g. because it is waiting for input
+    Running,
+    Stopped,
+    StoppedAtBreakpoint,
+    /// Exited with the status code
+    Exited(i32),
+    /// Terminated by the signal
+    Signaled(String),
+}
+
+/// Whether the debugee is running, stopped or exited, returned by `GetStatus` and `Continue`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct DebugeeStatus {
+    pub state: DebugeeState,
+    /// Milliseconds since the UNIX epoch when the debugee entered the state
+    pub since: u64,
+    /// Milliseconds since the UNIX epoch when the debugee was started
+    pub started: u64,
+    /// Where the debugee stopped, `None` while it is running or after it exited
+    pub stop: Option<StopState>,
+    /// Set once the debugee exited or was terminated
+    pub summary: Option<ExitSummary>,
+}
+
+/// What happened while the debugee ran, shown once it exited
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ExitSummary {
+    /// `Exited` with the status code or `Signaled`
+    pub state: DebugeeState,
+    /// Milliseconds from starting the debugee until it exited
+    pub duration: u64,
+    /// Number of step and continue commands
+    pub resumes: u64,
+    /// Number of single-stepped instructions, including those executed while tracing
+    pub instructions: u64,
+    /// Number of stops at an enabled breakpoint
+    pub breakpoints_hit: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct MemoryMap {
     pub from: u64,
     pub to: u64,
@@ -22,26 +297,239 @@ pub struct MemoryMap {
     pub shared: bool,
     pub offset: u64,
     pub mapped: String,
+    pub kind: RegionKind,
+    /// Friendly description, e.g. `heap`, `program code (.init .plt .text)` or `libc.so.6 code`
+    pub label: String,
+}
+
+/// What a memory region is used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+pub enum RegionKind {
+    Stack,
+    Heap,
+    /// Code of the program
+    Text,
+    /// Constants of the program, e.g. string literals
+    ReadOnlyData,
+    /// Initialized global variables of the program
+    Data,
+    /// Zero-initialized global variables of the program
+    Bss,
+    SharedLibrary,
+    /// Code provided by the kernel for fast system calls
+    Vdso,
+    /// Other memory provided by the kernel
+    Kernel,
+    /// Memory not backed by a file, e.g. large allocations or thread stacks
+    Anonymous,
+    Other,
 }
 
-#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub enum MapChangeKind {
+    Added,
+    Removed,
+    Resized {
+        previous_from: u64,
+        previous_to: u64,
+    },
+}
+
+/// Part of the stack belonging to one function call
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct StackFrame {
+    pub function: Option<String>,
+    /// The executed instruction, the return address for the callers
+    pub pc: u64,
+    /// Lowest address of the frame, the stack pointer
+    pub from: u64,
+    /// Canonical frame address, the stack pointer before the function was called. Arguments
+    /// passed on the stack lie right above it in the frame of the caller
+    pub to: u64,
+    /// Parameters of the function in declaration order with their values in this frame, only
+    /// filled by `Command::GetStackFrames`
+    pub arguments: Vec<Variable>,
+}
+
+/// A register of the caller saved on the stack by the function of a frame
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SavedRegister {
+    /// Register name, `return address` for the slot the call instruction pushed
+    pub name: String,
+    /// Where the register is saved
+    pub address: u64,
+    pub value: u64,
+}
+
+/// A function call on the stack as found by unwinding with the call frame information
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Frame {
+    /// The executed instruction, the return address for the callers
+    pub pc: u64,
+    /// Canonical frame address, the stack pointer before the function was called
+    pub cfa: u64,
+    pub frame_pointer: u64,
+    /// Where the function returns to, `None` for the outermost frame
+    pub return_address: Option<u64>,
+    /// Return address and registers of the caller saved in this frame, e.g. only the return
+    /// address for leaf functions without a frame pointer
+    pub saved_registers: Vec<SavedRegister>,
+    pub function: Option<String>,
+    pub location: Option<Location>,
+}
+
+/// A function or a block inside it with the variables declared in it
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct VariableScope {
+    /// Name of the function, `None` for blocks
+    pub function: Option<String>,
+    /// First line of the scope in the source code
+    pub line: Option<u64>,
+    pub low_pc: u64,
+    pub high_pc: u64,
+    /// Whether the program counter is inside the scope and all scopes enclosing it
+    pub live: bool,
+    pub variables: Vec<Variable>,
+    pub scopes: Vec<VariableScope>,
+}
+
+/// Memory around an address, see `Command::ReadAround`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MemoryChunk {
+    pub from: u64,
+    pub memory: Vec<u8>,
+    /// Label of the mapped region the memory belongs to
+    pub region: String,
+}
+
+/// A pointer to a string in `argv` or `envp`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ProcessString {
+    /// Where the pointer is stored on the stack
+    pub address: u64,
+    pub pointer: u64,
+    /// The string as a C string literal
+    pub value: String,
+}
+
+/// An entry of the auxiliary vector, which tells the program e.g. the page size and where its
+/// program headers were loaded
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct AuxEntry {
+    /// Where the entry is stored on the stack
+    pub address: u64,
+    pub key: u64,
+    /// Name of the key like `AT_PAGESZ`, `None` for keys unknown to the debugger
+    pub name: Option<String>,
+    pub value: u64,
+    /// C string literal of the string the value points to for `AT_EXECFN` and `AT_PLATFORM`
+    pub string: Option<String>,
+}
+
+/// What the kernel placed at the top of the stack before the program started: `argc`, the
+/// `argv` and `envp` pointer arrays, the auxiliary vector and the strings they point to
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct ProcessStack {
+    /// Address of `argc`, the stack pointer at the entry point
+    pub start: u64,
+    pub argc: u64,
+    pub argv: Vec<ProcessString>,
+    pub envp: Vec<ProcessString>,
+    pub auxv: Vec<AuxEntry>,
+    /// End of the stack region, the strings lie right below it
+    pub end: u64,
+}
+
+/// A message the debugger logged, with the spans it was logged in
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LogEntry {
+    /// Milliseconds since the debugger started
+    pub time: u64,
+    /// `ERROR`, `WARN`, `INFO`, `DEBUG` or `TRACE`
+    pub level: String,
+    /// Module logging the message
+    pub target: String,
+    /// Names of the spans the message was logged in, the outermost comes first, e.g. the walk
+    /// of the debug information around a failing read
+    pub spans: Vec<String>,
+    pub message: String,
+}
+
+/// What a bug report about the debugger needs, returned by `GetDiagnostics`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Diagnostics {
+    pub version: String,
+    /// Operating system and architecture the debugger was built for, e.g. `linux x86_64`
+    pub platform: String,
+    /// Kernel release, which tells e.g. WSL apart from other Linux systems
+    pub kernel: Option<String>,
+    pub program: String,
+    pub has_debug_info: bool,
+    /// The most recent log messages, the oldest comes first
+    pub log: Vec<LogEntry>,
+}
+
+/// Change of a memory region between two stops
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct MapChange {
+    /// The region at the current stop, or at the previous stop if it was removed
+    pub map: MemoryMap,
+    pub change: MapChangeKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub enum CommandOutput {
     Data(u64),
     Memory(Vec<u8>),
+    MemoryChunk(MemoryChunk),
     Variables(Vec<Variable>),
     DiscoveredVariables(Vec<DiscoveredVariable>),
     FunctionMeta(FunctionMeta),
     CodeWindow(Vec<(u64, String, bool)>),
     Registers(Registers),
+    FullRegisters(FullRegisters),
+    BranchExplanation(BranchExplanation),
+    FunctionDisassembly(FunctionDisassembly),
+    ResolvedAddress(ResolvedAddress),
+    PltEntries(Vec<PltEntry>),
+    ElfInfo(ElfInfo),
+    StopState(StopState),
+    Status(DebugeeStatus),
     DebugMeta(DebugMeta),
     Location(Location),
     DwarfAttributes(Vec<DwarfAttribute>),
     Help(Vec<String>),
     Breakpoints(Vec<Breakpoint>),
+    PlacedBreakpoint(PlacedBreakpoint),
+    Actions(Vec<Action>),
+    LineAddresses(LineAddresses),
     Functions(Vec<FunctionMeta>),
+    FunctionGroups(Vec<FunctionGroup>),
     File(String),
-    Backtrace(Vec<FunctionMeta>),
+    Source(SourceFile),
+    /// Serialized snapshot in the requested format
+    Snapshot(Vec<u8>),
+    Backtrace(Vec<Frame>),
+    StackFrames(Vec<StackFrame>),
+    VariableScopes(Vec<VariableScope>),
     Maps(Vec<MemoryMap>),
+    MapChanges(Vec<MapChange>),
+    ProcessStack(ProcessStack),
+    References(Vec<Reference>),
+    PointerInfo(PointerInfo),
+    VariableHistory(Vec<HistoryEntry>),
+    ExecutionTrace(ExecutionTrace),
+    LineHitCounts(Vec<LineHits>),
+    Invariants(Vec<Invariant>),
+    Watches(Vec<Watch>),
+    DataStructures(Vec<DataStructure>),
+    Profile(Profile),
+    /// `None` if there is no exercise next to the program
+    Exercise(Option<Exercise>),
+    Quiz(Quiz),
+    QuizAnswer(QuizAnswer),
+    Diagnostics(Diagnostics),
+    LineCalls(Vec<String>),
     None,
 }
 
@@ -113,13 +601,133 @@ impl ToString for TypeName {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+/// Size in bytes of a value of the type at `index`
+pub fn get_byte_size(types: &DataType, index: usize) -> usize {
+    match &types.0[index].1 {
+        TypeName::Name { name: _, byte_size } => *byte_size,
+        TypeName::Arr { arr_type, count } => {
+            count.iter().cloned().fold(1, |e1, e2| e1 * e2) * get_byte_size(types, *arr_type)
+        }
+        TypeName::Ref { index: _ } => 8usize,
+        TypeName::ProductType {
+            name: _,
+            members: _,
+            byte_size,
+        } => *byte_size,
+    }
+}
+
+/// Decodes the bytes of a value of the type at `index` into a human readable string.
+/// `memory` has to start at the address of the value.
+pub fn format_value(types: &DataType, index: usize, memory: &[u8]) -> Option<String> {
+    let size = get_byte_size(types, index);
+    if memory.len() < size {
+        return None;
+    }
+    let mut raw = [0u8; 8];
+    raw[..size.min(8)].copy_from_slice(&memory[..size.min(8)]);
+    let unsigned = u64::from_le_bytes(raw);
+    match &types.0[index].1 {
+        TypeName::Name { name, byte_size } => Some(match (name.as_str(), *byte_size) {
+            (_, 0) => String::new(),
+            ("float", 4) => f32::from_le_bytes(raw[..4].try_into().unwrap()).to_string(),
+            ("double", 8) => f64::from_le_bytes(raw).to_string(),
+            ("_Bool", _) | ("bool", _) => (unsigned != 0).to_string(),
+            (name, 1) if name.contains("char") => {
+                let value = if name.contains("unsigned") {
+                    (unsigned as u8) as i64
+                } else {
+                    (unsigned as u8 as i8) as i64
+                };
+                match unsigned as u8 {
+                    0x20..=0x7e => format!("{} '{}'", value, unsigned as u8 as char),
+                    _ => value.to_string(),
+                }
+            }
+            (name, size) if name.contains("unsigned") || size > 8 => match size {
+                1..=8 => unsigned.to_string(),
+                _ => memory[..size]
+                    .iter()
+                    .rev()
+                    .fold("0x".to_owned(), |acc, b| acc + &format!("{:02x}", b)),
+            },
+            (_, 1) => (unsigned as u8 as i8).to_string(),
+            (_, 2) => (unsigned as u16 as i16).to_string(),
+            (_, 4) => (unsigned as u32 as i32).to_string(),
+            _ => (unsigned as i64).to_string(),
+        }),
+        TypeName::Ref { index: _ } => Some(format!("{:#x}", unsigned)),
+        TypeName::Arr { arr_type, count } => {
+            let element_size = get_byte_size(types, *arr_type);
+            let elements = (0..count.iter().fold(1, |acc, e| acc * *e))
+                .map(|i| {
+                    format_value(types, *arr_type, &memory[i * element_size..])
+                        .unwrap_or("?".to_owned())
+                })
+                .collect::<Vec<String>>();
+            Some(format!("{{{}}}", elements.join(", ")))
+        }
+        TypeName::ProductType {
+            name: _,
+            members,
+            byte_size: _,
+        } => {
+            let members = members
+                .iter()
+                .map(|(name, member_type, offset)| {
+                    format!(
+                        ".{} = {}",
+                        name,
+                        format_value(types, *member_type, &memory[*offset..])
+                            .unwrap_or("?".to_owned())
+                    )
+                })
+                .collect::<Vec<String>>();
+            Some(format!("{{{}}}", members.join(", ")))
+        }
+    }
+}
+
+/// Numeric value of a scalar or pointer of the type at `index`, used for plotting
+pub fn numeric_value(types: &DataType, index: usize, memory: &[u8]) -> Option<f64> {
+    let size = get_byte_size(types, index);
+    if memory.len() < size || size == 0 || size > 8 {
+        return None;
+    }
+    let mut raw = [0u8; 8];
+    raw[..size].copy_from_slice(&memory[..size]);
+    let unsigned = u64::from_le_bytes(raw);
+    let shift = 64 - size as u32 * 8;
+    match &types.0[index].1 {
+        TypeName::Name { name, byte_size: _ } => Some(match (name.as_str(), size) {
+            ("float", 4) => f32::from_bits(unsigned as u32) as f64,
+            ("double", 8) => f64::from_bits(unsigned),
+            (name, _) if name.contains("unsigned") || name.contains("_Bool") => unsigned as f64,
+            _ => (((unsigned << shift) as i64) >> shift) as f64,
+        }),
+        TypeName::Ref { index: _ } => Some(unsigned as f64),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct DebugMeta {
     pub binary_name: String,
     pub file_type: String,
     pub files: Vec<String>,
     pub functions: i32,
     pub vars: i32,
+    /// Whether the program was built with debug information (`-g`)
+    pub has_debug_info: bool,
+    /// The separate file the debug information was read from, found by the build ID or
+    /// `.gnu_debuglink` of a stripped program or downloaded with debuginfod
+    pub debug_file: Option<String>,
+    /// Compilers of the compilation units from `DW_AT_producer`, GCC records its flags like
+    /// `GNU C17 11.4.0 -mtune=generic -march=x86-64 -g -O2`
+    pub producers: Vec<String>,
+    /// The flag like `-O2` if the program was compiled with optimizations, which keep variables
+    /// in registers or remove them and reorder lines
+    pub optimization: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
@@ -139,6 +747,14 @@ pub struct Variable {
     pub addr: Option<u64>,
     pub high_pc: u64,
     pub low_pc: u64,
+    /// Whether the variable is live at the program counter, `addr` and `value` are only set if
+    /// it is
+    pub in_scope: bool,
+    /// Where the variable is stored if it isn't in memory at `addr`, e.g. `in register rax` in
+    /// optimized binaries
+    pub optimized: Option<String>,
+    /// A formal parameter of the function instead of a local variable
+    pub parameter: bool,
 }
 
 pub const VARIABLE_MEM_PADDING: u64 = 30;
@@ -152,11 +768,275 @@ pub struct DiscoveredVariable {
     pub line: Option<u64>,
     pub addr: Option<u64>,
     pub memory: Option<Vec<u8>>,
+    /// Decoded, escaped C string for `char[]` and `char*` variables
+    pub string: Option<String>,
     pub high_pc: u64,
     pub low_pc: u64,
+    /// Header malloc keeps in the 16 bytes before the block, for pointer targets which are the
+    /// start of a heap block
+    #[serde(default)]
+    pub heap_chunk: Option<MallocChunk>,
+    /// Likely mistakes in the value, e.g. a pointer which isn't aligned for the type it points
+    /// to or points past the end of an array
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Chunk of glibc's allocator. malloc returns the address right after the chunk header, which
+/// holds the size of the previous chunk and the size of this one with three flags in its low
+/// bits. `free` reads the header in front of the pointer it gets, so freeing a pointer which
+/// malloc didn't return reads garbage as the size and corrupts the heap.
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq)]
+pub struct MallocChunk {
+    /// Address returned by malloc, the header starts 16 bytes before it
+    pub address: u64,
+    /// Size of the previous chunk, only used while it's free
+    pub prev_size: u64,
+    /// Size of the chunk including the header, without the flags
+    pub size: u64,
+    /// `PREV_INUSE` flag, the previous chunk is in use
+    pub prev_in_use: bool,
+    /// `IS_MMAPPED` flag, the chunk was mapped separately because it's large
+    pub mmapped: bool,
+    /// `NON_MAIN_ARENA` flag, the chunk belongs to the arena of another thread
+    pub non_main_arena: bool,
+    /// Read from the `PREV_INUSE` flag of the next chunk, `None` for the top chunk at the end of
+    /// the heap which has no next chunk
+    pub in_use: Option<bool>,
+}
+
+/// A variable or register holding a pointer into a queried address
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct Reference {
+    /// Variable name or register name
+    pub name: String,
+    /// Address of the pointer, `None` for registers
+    pub address: Option<u64>,
+    /// The pointer value
+    pub value: u64,
+}
+
+/// Describes what an address points into
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct PointerInfo {
+    pub address: u64,
+    /// Memory mapping containing the address
+    pub region: Option<MemoryMap>,
+    /// Variable or heap object containing the address
+    pub variable: Option<String>,
+    /// Innermost element or member containing the address, e.g. `arr[3]` or `node.next`
+    pub element: Option<String>,
+    /// Address of the element
+    pub element_address: Option<u64>,
+    /// Byte size of the element
+    pub element_size: Option<u64>,
+}
+
+/// Number of times execution entered a source line
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct LineHits {
+    pub file: String,
+    pub line: u64,
+    pub count: u64,
+}
+
+/// An expression checked by `AddInvariant`
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct Invariant {
+    pub expression: String,
+    /// Value at the last check, `None` if it wasn't evaluated yet
+    pub holds: Option<bool>,
+    /// Why the last check failed, e.g. because a variable isn't in scope
+    pub error: Option<String>,
+}
+
+/// Memory watched with `AddWatch`
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct Watch {
+    pub expression: String,
+    /// Address of the expression when the watch was added, which is watched from then on
+    pub address: u64,
+    pub size: u64,
+    /// The function whose code is single-stepped, everything is if `None`
+    pub function: Option<String>,
+    /// Value at the last check, the bytes in hex if it isn't a number or pointer
+    pub value: String,
+}
+
+/// Guided exercise loaded from `<program>.exercise.json`, its steps are shown one after another
+/// and advance when the debugee stops where they expect
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct Exercise {
+    pub title: String,
+    /// Source file the lines of the steps refer to, the file of `main` if it isn't set
+    #[serde(default)]
+    pub file: Option<String>,
+    pub steps: Vec<ExerciseStep>,
+    /// Index of the current step, equal to the number of steps once the exercise is done
+    #[serde(default)]
+    pub current: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct ExerciseStep {
+    /// Instructions shown while the step is the current one, e.g. "Look at how argv is laid
+    /// out, then press continue"
+    pub prompt: String,
+    /// The stop completing the step, any stop if it isn't set
+    #[serde(default)]
+    pub until: Option<ExerciseCondition>,
+    /// Whether a breakpoint is set at the line or function of `until` when the exercise is
+    /// loaded
+    #[serde(default)]
+    pub breakpoint: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq)]
+pub enum ExerciseCondition {
+    /// The debugee stopped at the line of the exercise file
+    Line(u64),
+    /// The debugee stopped in the function
+    Function(String),
+    /// The debugee exited
+    Exited,
+}
+
+/// What the student predicts in a quiz question
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone, Copy, PartialEq)]
+pub enum QuizKind {
+    /// The value of the expression, answered with a C expression like `42` or `'a'`
+    Value,
+    /// What the pointer expression points to, answered with a variable or element like `arr[2]`
+    Target,
+}
+
+/// A question asked whenever the debugee stops at a breakpoint, the answer is hidden until it
+/// was answered
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq)]
+pub struct QuizQuestion {
+    /// Address of the breakpoint
+    pub breakpoint: u64,
+    pub expression: String,
+    pub kind: QuizKind,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct QuizAnswer {
+    pub question: QuizQuestion,
+    pub answer: String,
+    pub correct: bool,
+    /// The actual value or target of the expression
+    pub solution: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct Quiz {
+    pub questions: Vec<QuizQuestion>,
+    /// Questions of the breakpoint the debugee stopped at which weren't answered yet
+    pub pending: Vec<QuizQuestion>,
+    /// Every answer given in this session, the score is the number of correct ones
+    pub answers: Vec<QuizAnswer>,
+}
+
+/// Shape of a data structure recognized from the members of its node struct pointing to the
+/// struct itself
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone, PartialEq)]
+pub enum StructureKind {
+    /// Nodes chained by `next`, `prev` is the member pointing back in doubly linked lists
+    LinkedList {
+        next: String,
+        prev: Option<String>,
+    },
+    BinaryTree {
+        left: String,
+        right: String,
+    },
+}
+
+/// A pointer from a node to another node of the same type
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct NodeLink {
+    pub member: String,
+    pub address: u64,
+    /// Index of the node it points to, `None` for null pointers and nodes that weren't read
+    pub node: Option<usize>,
+    /// Whether the address is mapped, pointers into unmapped memory are broken links
+    pub mapped: bool,
+}
+
+/// A struct reached from the variable of a data structure
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct StructureNode {
+    pub address: u64,
+    /// Members which aren't links with their formatted values
+    pub fields: Vec<(String, String)>,
+    pub links: Vec<NodeLink>,
+    /// Distance from the first node along the links
+    pub depth: usize,
+}
+
+/// A linked list or tree reached from a variable of the current function
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct DataStructure {
+    pub variable: String,
+    /// Name of the node struct
+    pub node_type: String,
+    pub kind: StructureKind,
+    /// Address of the first node, the value of a pointer variable
+    pub address: u64,
+    /// Nodes in list order or in-order for trees, every node is read once even if the links
+    /// form a cycle
+    pub nodes: Vec<StructureNode>,
+    /// Whether there were more nodes than are read
+    pub truncated: bool,
+}
+
+/// Source lines executed while tracing was enabled
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct ExecutionTrace {
+    pub enabled: bool,
+    /// Executed lines in order, consecutive instructions of the same line are merged
+    pub lines: Vec<Location>,
+    pub hit_counts: Vec<LineHits>,
+    /// Number of single-stepped instructions with debug information
+    pub instructions: u64,
+    /// Whether `lines` was cut off because the trace got too long
+    pub truncated: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct FunctionSamples {
+    pub name: String,
+    /// Samples where the program counter was inside the function itself
+    pub self_samples: u64,
+    /// Samples where the function was anywhere on the stack
+    pub total_samples: u64,
 }
 
-#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema)]
+/// Result of a profiling run, sorted by the number of samples
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct Profile {
+    pub samples: u64,
+    /// Number of instructions between two samples
+    pub period: u64,
+    pub functions: Vec<FunctionSamples>,
+    /// Samples per source line, `count` is the number of samples
+    pub lines: Vec<LineHits>,
+}
+
+/// Value of a tracked variable at a stop of the debugee
+#[derive(Debug, Serialize, Deserialize, schemars::JsonSchema, Clone)]
+pub struct HistoryEntry {
+    /// Number of the stop, counted since the variable history was reset
+    pub stop: u64,
+    pub location: Option<Location>,
+    /// Decoded value, `None` if the variable wasn't found at this stop
+    pub value: Option<String>,
+    /// Numeric value for plotting, `None` for aggregates
+    pub numeric: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
 pub struct DwarfAttribute {
     pub name: String,
     pub addr: u64,
@@ -170,6 +1050,58 @@ pub struct FunctionMeta {
     pub low_pc: Option<u64>,
     pub high_pc: Option<u64>,
     pub return_addr: Option<u64>,
+    /// Where the function is declared (`DW_AT_decl_file` and `DW_AT_decl_line`), `None` for
+    /// functions from the symbol table
+    #[serde(default)]
+    pub declaration: Option<Location>,
+    /// Parameters from the `DW_TAG_formal_parameter` entries, empty for functions from the
+    /// symbol table
+    #[serde(default)]
+    pub parameters: Vec<FunctionParameter>,
+    /// C declaration of the return type like `char *`, `None` for `void`
+    #[serde(default)]
+    pub return_type: Option<String>,
+}
+
+/// A parameter of a function, see `FunctionMeta`
+#[derive(Debug, Serialize, Deserialize, Clone, schemars::JsonSchema)]
+pub struct FunctionParameter {
+    pub name: Option<String>,
+    /// C declaration of the type like `const struct point *`, `...` for variadic functions
+    pub type_name: String,
+}
+
+impl FunctionMeta {
+    /// The declaration of the function like `int area(struct rectangle * rectangle)`
+    pub fn signature(&self) -> String {
+        let parameters = self
+            .parameters
+            .iter()
+            .map(|parameter| match &parameter.name {
+                Some(name) => format!("{} {}", parameter.type_name, name),
+                None => parameter.type_name.clone(),
+            })
+            .collect::<Vec<_>>();
+        format!(
+            "{} {}({})",
+            self.return_type.as_deref().unwrap_or("void"),
+            self.name.as_deref().unwrap_or("??"),
+            match parameters.is_empty() {
+                true => "void".to_owned(),
+                false => parameters.join(", "),
+            }
+        )
+    }
+}
+
+/// The functions defined in the compilation unit of a source file, see `FindFunctions`
+#[derive(Debug, Serialize, Deserialize, Clone, schemars::JsonSchema)]
+pub struct FunctionGroup {
+    /// Primary source file of the compilation unit, empty for the functions of the symbol table
+    /// of programs without debug information
+    pub file: String,
+    /// Sorted by name
+    pub functions: Vec<FunctionMeta>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
@@ -178,59 +1110,426 @@ pub struct Breakpoint {
     pub original_byte: u32,
     pub enabled: bool,
     pub location: Location,
+    /// The source text of the line when the breakpoint was set, used to find the line again
+    /// when the program is rebuilt after lines were inserted or removed above it
+    pub anchor: Option<SourceAnchor>,
+}
+
+/// Hashes of the source text of a line, whitespace at the beginning and end of lines is ignored
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SourceAnchor {
+    /// Hash of the line
+    pub line: u64,
+    /// Hash of the line and the lines directly above and below it
+    pub context: u64,
+}
+
+/// A breakpoint set at a source location
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct PlacedBreakpoint {
+    pub breakpoint: Breakpoint,
+    /// Set if the requested line has no code, e.g.
+    /// `line 3 has no code, breakpoint moved to line 5`
+    pub moved: Option<String>,
+}
+
+/// A change to the debugee made by a command, see `Command::Undo`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub enum Action {
+    SetBreakpoint(Breakpoint),
+    DeleteBreakpoint(Breakpoint),
+    WriteMemory {
+        address: u64,
+        old: Vec<u8>,
+        new: Vec<u8>,
+    },
+    SetRegister {
+        name: String,
+        old: u64,
+        new: u64,
+    },
+}
+
+/// An address generated for a source line
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LineAddress {
+    pub address: u64,
+    /// Whether the line table marks the address as the beginning of a statement
+    pub is_stmt: bool,
+}
+
+/// All addresses generated for a source line, e.g. for every copy of an unrolled loop
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct LineAddresses {
+    /// The line the addresses belong to, the next line with code if the requested one has none
+    pub line: u64,
+    /// Sorted by address
+    pub addresses: Vec<LineAddress>,
+    /// The address breakpoints are set at: the first statement of the line
+    pub canonical: u64,
 }
 
 /// Specifies a location for a breakpoint
-#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
 pub enum BreakpointPoint {
-    /// At the start of the specified function
+    /// At the start of the specified function, after the function prologue
     Name(String),
-    /// At the specified address
+    /// At the very first instruction of the specified function, before the prologue
+    /// set up the stack frame
+    Entry(String),
+    /// At the specified address, which has to be the start of an instruction in executable
+    /// memory. Other addresses are refused with `InvalidBreakpointAddress`, which suggests the
+    /// nearest instruction.
     Address(u64),
     /// At the specified location (ignores column)
     Location(Location),
 }
 
+/// File format used when exporting data from the debugger
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, schemars::JsonSchema)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+impl ExportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Json => "json",
+            ExportFormat::Csv => "csv",
+        }
+    }
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ExportFormat::Json),
+            "csv" => Ok(ExportFormat::Csv),
+            _ => Err(format!("Unknown export format \"{}\"", s)),
+        }
+    }
+}
+
+/// File format of exported snapshots
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, schemars::JsonSchema)]
+pub enum SnapshotFormat {
+    Json,
+    /// Gzip compressed JSON
+    Binary,
+}
+
+impl SnapshotFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            SnapshotFormat::Json => "json",
+            SnapshotFormat::Binary => "json.gz",
+        }
+    }
+}
+
+impl FromStr for SnapshotFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(SnapshotFormat::Json),
+            "binary" => Ok(SnapshotFormat::Binary),
+            _ => Err(format!("Unknown snapshot format \"{}\"", s)),
+        }
+    }
+}
+
+/// File format of the reports of a stop
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, Serialize, schemars::JsonSchema)]
+pub enum ReportFormat {
+    /// A single HTML page without external resources
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ReportFormat::Html => "html",
+            ReportFormat::Markdown => "md",
+        }
+    }
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "html" => Ok(ReportFormat::Html),
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            _ => Err(format!("Unknown report format \"{}\"", s)),
+        }
+    }
+}
+
+/// Where the text of a `SourceFile` was read from
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, schemars::JsonSchema)]
+pub enum SourceOrigin {
+    /// The path in the debug information
+    Local,
+    /// The path the `SetSourceMap` mapped it to
+    Mapped(String),
+    /// The source embedded in the debug information, e.g. with `clang -gembed-source`
+    Embedded,
+    /// Neither the file nor embedded source exist
+    Missing,
+}
+
+/// A source file of the program, returned by `GetSource`
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SourceFile {
+    /// Path of the file in the debug information
+    pub path: String,
+    /// `None` if the source isn't available, clients show the disassembly instead
+    pub content: Option<String>,
+    pub origin: SourceOrigin,
+    /// MD5 checksum of the file at compile time as hex, recorded by DWARF 5 line tables
+    pub md5: Option<String>,
+}
+
+/// Substitution of a path prefix in the paths of the debug information, e.g. the directory a
+/// program was compiled in inside a container, see `SetSourceMap`
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize, schemars::JsonSchema)]
+pub struct SourceMapping {
+    /// Prefix of the paths in the debug information, e.g. `/build/src`
+    pub from: String,
+    /// Local directory replacing it, e.g. `/home/user/src`
+    pub to: String,
+}
+
+impl FromStr for SourceMapping {
+    type Err = String;
+
+    /// Parses `from=to`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((from, to)) if !from.is_empty() => Ok(SourceMapping {
+                from: from.to_owned(),
+                to: to.to_owned(),
+            }),
+            _ => Err(format!("Expected FROM=TO instead of \"{}\"", s)),
+        }
+    }
+}
+
+/// State of the debugee at a stop, exported to view it offline
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Snapshot {
+    pub program: String,
+    pub location: Option<Location>,
+    pub registers: Registers,
+    pub full_registers: FullRegisters,
+    pub maps: Vec<MemoryMap>,
+    /// From the stack pointer to the end of the stack
+    pub stack: MemoryChunk,
+    pub variables: Vec<DiscoveredVariable>,
+    /// Contents of the heap regions
+    pub heap: Vec<MemoryChunk>,
+}
+
+/// Where the debugee stopped after a command resumed it
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct StopEvent {
+    pub pc: u64,
+    pub location: Option<Location>,
+}
+
+/// A command sent to the debugger and its result, recorded to replay the session
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct SessionEntry {
+    pub command: Command,
+    /// The output or the error message
+    pub output: Result<CommandOutput, String>,
+    /// Set if the command resumed the debugee
+    pub stop: Option<StopEvent>,
+}
+
+/// Version of this crate, the backend and the UI only understand each other if they were built
+/// with the same version
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// What the backend supports, returned by the `/capabilities` endpoint. The UI disables the
+/// features the backend doesn't support.
+#[derive(Debug, Clone, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Capabilities {
+    /// [`VERSION`] of the backend
+    pub version: String,
+    /// Names of the supported commands, e.g. `StepStatement`
+    pub commands: Vec<String>,
+    /// Cargo features the backend was built with, e.g. `gui`
+    pub features: Vec<String>,
+    /// The backend was started with `--read-only` and the client didn't send the instructor
+    /// token, only commands observing the debugee are executed
+    #[serde(default)]
+    pub read_only: bool,
+}
+
+impl Capabilities {
+    /// Capabilities of this build, with all commands known to it
+    pub fn current(features: Vec<String>) -> Self {
+        Self {
+            version: VERSION.to_owned(),
+            commands: command_names(),
+            features,
+            read_only: false,
+        }
+    }
+
+    pub fn supports(&self, command: &str) -> bool {
+        self.commands.iter().any(|c| c == command)
+    }
+}
+
+/// The client driving the session, returned by the `/driver` endpoint. While a client drives
+/// the session, the commands of other clients changing the debugee are rejected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+pub struct Driver {
+    /// Name the client sent in the `client_name` query parameter
+    pub name: String,
+    /// The client asking is the driver
+    pub you: bool,
+    /// Seconds until the session is released if the driver sends no further commands
+    pub expires_in: u64,
+    /// Number of other clients which sent requests recently
+    pub followers: u64,
+}
+
+/// Names of all variants of [`Command`], read from its JSON schema
+pub fn command_names() -> Vec<String> {
+    let schema = serde_json::to_value(schemars::schema_for!(Command)).unwrap_or_default();
+    schema["oneOf"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|variant| variant["properties"]["Command"]["enum"].as_array())
+        .flatten()
+        .filter_map(|name| name.as_str().map(str::to_owned))
+        .collect()
+}
+
 /// A command for the debugger to execute
 /// When using the web API take a look at the request JSON schema at the `/schema` endpoint
-#[derive(Deserialize, Serialize, schemars::JsonSchema)]
+#[derive(Debug, Clone, Deserialize, Serialize, schemars::JsonSchema)]
 #[serde(tag = "Command", content = "Argument")]
 pub enum Command {
-    /// Resumes the execution of the child
+    /// Resumes the execution of the child. Returns the `StopState` if it stops within a short
+    /// time, otherwise `Status(Running)` while it keeps running
     Continue,
+    /// Stops the running child
+    Interrupt,
+    /// Whether the child is running, stopped or exited and since when
+    GetStatus,
+    /// Stops the child if it keeps computing for the specified number of seconds after
+    /// continuing, 0 disables the timeout. Programs waiting for input aren't stopped.
+    SetContinueTimeout(u64),
+    /// Replaces the patterns of the functions `StepIn` steps over instead of entering them,
+    /// matched against the function names and the file names of the mapped objects, e.g.
+    /// `helper_*` or `libm.so*`. `*` matches any text. Functions without line information,
+    /// like the ones of libc, are always stepped over.
+    SetStepSkipList(Vec<String>),
+    /// Replaces the substitutions of path prefixes applied to the source files of the debug
+    /// information before reading them, e.g. `/build/src` to `/home/user/src` for programs
+    /// compiled in another directory. The first matching mapping is used, files which don't
+    /// exist after mapping are read from their original path or the source embedded in the
+    /// debug information. So are files outside the sources of the program and the paths
+    /// clients may read, given with `--allow-source` or `--source-map`. Over the web only the
+    /// instructor of `--read-only` may send it.
+    SetSourceMap(Vec<SourceMapping>),
     /// Quits the debugger
     Quit,
+    /// Removes all breakpoints and detaches from the child, which keeps running without the
+    /// debugger. Other commands fail afterwards.
+    DetachDebugger,
     /// Returns all registers with their current value
     GetRegister,
+    /// Returns all general purpose, flags and segment registers
+    GetFullRegisters,
+    /// Sets the register with the specified name to a value
+    SetRegister(String, u64),
+    /// Explains which flags the conditional instruction at the program counter tests and whether
+    /// the condition holds
+    ExplainBranch,
     /// Steps the child by one instruction
     StepInstruction,
+    /// Steps the child by the specified number of instructions
+    StepInstructionN(u64),
     /// Finds a function with the specified name
     FindFunc(String),
     /// Read from the specified address
     Read(u64),
-    /// Read memory specifying the address and the length in bytes
+    /// Read memory specifying the address and the length in bytes. The address has to be in a
+    /// readable region, the length is cut at its end
     ReadMemory(u64, u64),
+    /// Write the bytes to the specified address
+    WriteMemory(u64, Vec<u8>),
+    /// Read the bytes before and after the specified address, limited to the mapped region
+    /// containing it. Used to show the targets of pointers into memory no variable covers
+    ReadAround(u64, u64, u64),
     /// Returns the address of the current instruction
     ProgramCounter,
     /// Provides statistics of the current program
     DebugMeta,
+    /// Returns the headers, sections, segments and symbol table sizes of the program
+    GetElfInfo,
     /// Dumps all dwarf debug information; useful for debugging
     DumpDwarf,
+    /// Dumps the decoded type of every variable in the binary as text, type decoding changes
+    /// show up as differences in the dump
+    DumpTypes,
     /// Retrieves the current location in the source code
     Location,
-    /// Find the address of a line in the source code
+    /// Find all addresses of a line in the source code, or of the next line with code if the
+    /// line has none
     FindLine {
         line: u64,
         filename: String,
     },
-    /// Step over the current function call by continuing execution until another line in the current function is reached
+    /// Sets (`true`) or removes (`false`) breakpoints on all calls to `malloc`, `calloc`,
+    /// `realloc` and `free`. `Continue` returns the arguments of the call when one is hit
+    BreakOnAllocation(bool),
+    /// Sets (`true`) or removes (`false`) breakpoints on all calls to I/O functions like `read`,
+    /// `write`, `printf` and `puts`. `Continue` returns the arguments of the call when one is hit
+    BreakOnIO(bool),
+    /// Step over the current function call by continuing execution until another line in the current function is reached.
+    /// Returns the value returned by the function.
     StepOut,
-    /// Continue execution until a new line in the source code is reached
+    /// Continues until the specified number of frames returned, e.g. to leave several calls of
+    /// a recursive function at once. The return addresses are found by unwinding the stack
+    /// with the call frame information, a recursive call returning to the same address
+    /// doesn't stop early. Returns the value each frame returned in `returned_frames`.
+    FinishFrames(u64),
+    /// Continue execution until a new line in the source code is reached. Functions without
+    /// line information and the ones in the `SetStepSkipList` are stepped over.
     StepIn,
+    /// Continue execution until the next statement is reached, which can be on the same line,
+    /// e.g. the condition and the increment of a `for` loop
+    StepStatement,
+    /// Continue execution until the function with the given name is entered from the current
+    /// line, the other calls of the line run without stopping, e.g. `g` when stepping into `f`
+    /// of `f(g(x))`. Stops at the next line like `StepIn` if the line doesn't call it
+    StepIntoTarget(String),
+    /// Get the functions called on the current line in the order of the calls, according to
+    /// the call instructions of its disassembly
+    GetLineCalls,
     /// View the source code around the current location
     ViewSource(usize),
-    /// Get the current backtrace
+    /// Get the frames on the stack with the return address and the registers they saved, the
+    /// innermost frame comes first
     Backtrace,
+    /// Computes the boundaries of all frames on the stack up to `main` using the call frame
+    /// information, the innermost frame comes first
+    GetStackFrames,
+    /// Get the variables of the current function grouped by the blocks they are declared in
+    GetVariableScopes,
     /// For debugging purposes
     WaitPid,
     /// Read all variables found in the debug symbols
@@ -241,21 +1540,132 @@ pub enum Command {
     DiscoverVariables,
     /// Restarts the process being debugged
     RestartDebugee,
-    /// Set a breakpoints at the specified location
+    /// Set a breakpoints at the specified location. Breakpoints at a source location return
+    /// the placed breakpoint, which may be on a later line if the requested one has no code
     SetBreakpoint(BreakpointPoint),
     /// Retrieve all current breakpoints
     GetBreakpoints,
     /// Deletes the breakpoint at the specified address
     DeleteBreakpoint(u64),
+    /// Reverts the last breakpoint change, memory write or register write.
+    /// Returns the actions that can still be undone
+    Undo,
+    /// Retrieve the actions that can be undone, the most recent one comes last
+    GetActions,
     /// Retrieve a list of all functions
     GetFunctions,
-    /// Get source file
+    /// The functions with code grouped by the source file of their compilation unit. Only the
+    /// ones whose name contains `pattern` ignoring case and with `user_code` the ones compiled
+    /// in the directory of `main`, like `--user-code`.
+    FindFunctions {
+        pattern: Option<String>,
+        user_code: bool,
+    },
+    /// Get source file. Only the source files listed by `DebugMeta` and the ones allowed with
+    /// `--allow-source` can be read.
     GetFile(String),
+    /// Returns a source file with where it was read from. Unlike `GetFile` a missing file isn't
+    /// an error, its `content` is `None`.
+    GetSource(String),
     /// Get the disassembly of the binary using objdump
     Disassemble,
+    /// Disassembles the function with the specified name or containing the specified hex
+    /// address, grouping the instructions by source line
+    DisassembleFunction(String),
+    /// Resolves an address to the enclosing function, source line, section and memory mapping
+    ResolveAddress(u64),
+    /// The imported functions with their PLT stub and the GOT slot it jumps through, which is
+    /// patched with the address of the function at the first call if binding is lazy
+    GetPltEntries,
     /// For the CLI implementation
     Help,
+    /// Returns the memory maps of the debugee with the kind of every region
     Maps,
+    /// Returns the regions which were added, removed or resized since the previous stop
+    MapsDiff,
+    /// Returns `argc`, `argv`, `envp` and the auxiliary vector the kernel placed at the top of
+    /// the stack when the program started
+    GetProcessStack,
+    /// Exports all discovered variables with their type, address and decoded value
+    ExportVariables(ExportFormat),
+    /// Exports the registers, memory maps, stack, heap and discovered variables, they can be
+    /// viewed offline by dropping the file into the UI
+    ExportSnapshot(SnapshotFormat),
+    /// Generates a report of the current stop with the source around it, the backtrace, the
+    /// variables with their values, the memory of the current frame and the heap chunks, e.g.
+    /// to hand in with homework
+    GenerateReport(ReportFormat),
+    /// Exports every command sent to the debugger with its output and the stops as JSON, the
+    /// session can be replayed by dropping the file into the UI
+    ExportSession,
+    /// Finds all variables and registers pointing into the variable at the specified address
+    FindReferences(u64),
+    /// Evaluates a pointer expression like `0x7ffc1234`, `array + 3` or `&var - 1` and reports
+    /// the mapped region, variable and element the resulting address falls into
+    PointerInfo(String),
+    /// Records the value of the specified variable at every stop
+    TrackVariable(String),
+    /// Stops recording the value of the specified variable
+    UntrackVariable(String),
+    /// Retrieves the recorded values of a tracked variable
+    GetVariableHistory(String),
+    /// Enables or disables tracing; while enabled `Continue` single-steps and records every
+    /// executed source line. Enabling starts a new trace
+    SetTracing(bool),
+    /// Retrieves the source lines executed while tracing was enabled
+    GetExecutionTrace,
+    /// How often the first line of every loop body was executed in the current run, counted
+    /// by breakpoints which resume the program right away
+    GetLineHitCounts,
+    /// Checks a C expression like `arr[i - 1] <= arr[i]` using the variables of the current
+    /// function whenever the debugee stops. While invariants are set `Continue` single-steps
+    /// and stops at the first line where one becomes false.
+    AddInvariant(String),
+    RemoveInvariant(String),
+    /// The invariants with their values at the last check
+    GetInvariants,
+    /// Watches the memory of a C expression like `head->next` at the address it has now,
+    /// without hardware watchpoints: while watches are set `Continue` single-steps and stops
+    /// with `WatchChanged` after the instruction which changed it. With `function` only its
+    /// code is single-stepped, the rest runs at full speed and changes made by the functions
+    /// it calls are noticed once they return.
+    AddWatch {
+        expression: String,
+        function: Option<String>,
+    },
+    RemoveWatch(String),
+    /// The watches with their values at the last check
+    GetWatches,
+    /// Linked lists and binary trees reached from the variables of the current function, in
+    /// traversal order
+    GetDataStructures,
+    /// Continues until the next breakpoint or the end of the program, sampling the current
+    /// function and line every n instructions
+    Profile(u64),
+    /// Retrieves the result of the last profiling run
+    GetProfile,
+    /// The exercise loaded from `<program>.exercise.json` with its current step
+    GetExercise,
+    /// Asks the question whenever the debugee stops at its breakpoint
+    AddQuizQuestion(QuizQuestion),
+    RemoveQuizQuestion(QuizQuestion),
+    /// The questions, the ones pending at the current stop and the answers given so far
+    GetQuiz,
+    /// Checks the answer to the pending question about the expression
+    AnswerQuiz {
+        expression: String,
+        answer: String,
+    },
+    /// Cancels the command the same client sent to the webserver with this id in the `request`
+    /// query parameter. A command waiting for the debugger isn't executed, a running
+    /// `DiscoverVariables`, `Disassemble` or `DisassembleFunction` stops, as does a continue
+    /// single-stepping because of watches or the execution trace. Rejected while another client
+    /// drives the session and for the observers of `--read-only`.
+    Cancel(String),
+    /// The version and platform of the debugger with its recent log messages, to be attached
+    /// to bug reports. Over the web only the instructor of `--read-only` and the client driving
+    /// the session may send it.
+    GetDiagnostics,
 }
 
 impl FromStr for Command {
@@ -265,19 +1675,85 @@ impl FromStr for Command {
         let mut iter = s.split(" ").into_iter();
         match iter.next().ok_or("empty command".to_string())? {
             "get_functions" => Ok(Command::GetFunctions),
+            "find_functions" => Ok(Command::FindFunctions {
+                pattern: iter.next().map(str::to_owned),
+                user_code: false,
+            }),
+            "find_user_functions" => Ok(Command::FindFunctions {
+                pattern: iter.next().map(str::to_owned),
+                user_code: true,
+            }),
             "location" => Ok(Command::Location),
             "continue" => Ok(Command::Continue),
             "maps" => Ok(Command::Maps),
+            "maps_diff" => Ok(Command::MapsDiff),
+            "get_process_stack" => Ok(Command::GetProcessStack),
             "waitpid" => Ok(Command::WaitPid),
             "disassemble" => Ok(Command::Disassemble),
+            "disassemble_function" => Ok(Command::DisassembleFunction(
+                iter.next()
+                    .ok_or(format!("disassemble_function requires argument \"{}\"", s))?
+                    .to_string(),
+            )),
             "get_breakpoints" => Ok(Command::GetBreakpoints),
+            "undo" => Ok(Command::Undo),
+            "get_actions" => Ok(Command::GetActions),
             "quit" => Ok(Command::Quit),
+            "interrupt" => Ok(Command::Interrupt),
+            "status" => Ok(Command::GetStatus),
+            "set_timeout" => Ok(Command::SetContinueTimeout(
+                iter.next()
+                    .ok_or("set_timeout requires argument \"seconds\"")?
+                    .parse()
+                    .map_err(|_| "Invalid number")?,
+            )),
+            "set_skip_list" => Ok(Command::SetStepSkipList(
+                iter.map(|pattern| pattern.to_string()).collect(),
+            )),
+            "set_source_map" => Ok(Command::SetSourceMap(
+                iter.map(SourceMapping::from_str)
+                    .collect::<Result<_, _>>()?,
+            )),
+            "detach" => Ok(Command::DetachDebugger),
             "get_registers" => Ok(Command::GetRegister),
+            "get_full_registers" => Ok(Command::GetFullRegisters),
+            "explain_branch" => Ok(Command::ExplainBranch),
+            "set_register" => Ok(Command::SetRegister(
+                iter.next()
+                    .ok_or(format!("set_register requires 1st argument name \"{}\"", s))?
+                    .to_string(),
+                u64::from_str_radix(
+                    iter.next()
+                        .ok_or(format!(
+                            "set_register requires 2nd argument value \"{}\"",
+                            s
+                        ))?
+                        .trim_start_matches("0x"),
+                    16,
+                )
+                .map_err(|a| a.to_string())?,
+            )),
             "step_instruction" => Ok(Command::StepInstruction),
+            "step_instruction_n" => Ok(Command::StepInstructionN(
+                iter.next()
+                    .ok_or("step_instruction_n requires argument \"count\"")?
+                    .parse()
+                    .map_err(|_| "Invalid number")?,
+            )),
             "pc" => Ok(Command::ProgramCounter),
             "dump_dwarf" => Ok(Command::DumpDwarf),
+            "dump_types" => Ok(Command::DumpTypes),
             "backtrace" => Ok(Command::Backtrace),
+            "stack_frames" => Ok(Command::GetStackFrames),
+            "variable_scopes" => Ok(Command::GetVariableScopes),
             "step_in" => Ok(Command::StepIn),
+            "step_statement" => Ok(Command::StepStatement),
+            "step_into_target" => Ok(Command::StepIntoTarget(
+                iter.next()
+                    .ok_or(format!("step_into_target requires argument \"{}\"", s))?
+                    .to_string(),
+            )),
+            "line_calls" => Ok(Command::GetLineCalls),
             "read_variables" => Ok(Command::ReadVariables),
             "discover_variables" => Ok(Command::DiscoverVariables),
             "debug_meta" => Ok(Command::DebugMeta),
@@ -290,7 +1766,47 @@ impl FromStr for Command {
                 )
                 .map_err(|a| a.to_string())?,
             )),
+            "write_memory" => Ok(Command::WriteMemory(
+                u64::from_str_radix(
+                    iter.next()
+                        .ok_or(format!("write_memory requires argument \"{}\"", s))?
+                        .trim_start_matches("0x"),
+                    16,
+                )
+                .map_err(|a| a.to_string())?,
+                iter.map(|b| u8::from_str_radix(b.trim_start_matches("0x"), 16))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|a| a.to_string())?,
+            )),
+            "read_around" => {
+                let mut argument = |name: &str| {
+                    iter.next()
+                        .ok_or(format!("read_around requires argument \"{}\"", name))
+                };
+                let address =
+                    u64::from_str_radix(argument("address")?.trim_start_matches("0x"), 16)
+                        .map_err(|a| a.to_string())?;
+                let before = argument("before")?.parse().map_err(|_| "Invalid number")?;
+                let after = argument("after")?.parse().map_err(|_| "Invalid number")?;
+                Ok(Command::ReadAround(address, before, after))
+            }
             "help" => Ok(Command::Help),
+            "export_variables" => Ok(Command::ExportVariables(
+                iter.next()
+                    .ok_or(format!("export_variables requires argument \"{}\"", s))?
+                    .parse::<ExportFormat>()?,
+            )),
+            "export_session" => Ok(Command::ExportSession),
+            "export_snapshot" => Ok(Command::ExportSnapshot(
+                iter.next()
+                    .ok_or(format!("export_snapshot requires argument \"{}\"", s))?
+                    .parse::<SnapshotFormat>()?,
+            )),
+            "generate_report" => Ok(Command::GenerateReport(
+                iter.next()
+                    .ok_or(format!("generate_report requires argument \"{}\"", s))?
+                    .parse::<ReportFormat>()?,
+            )),
             "find_line" => Ok(Command::FindLine {
                 line: iter
                     .next()
@@ -302,12 +1818,148 @@ impl FromStr for Command {
                     .ok_or(format!("find_line requires 2nd argument file \"{}\"", s))?
                     .to_string(),
             }),
+            "resolve_address" => Ok(Command::ResolveAddress(
+                u64::from_str_radix(
+                    iter.next()
+                        .ok_or(format!("resolve_address requires argument \"{}\"", s))?
+                        .trim_start_matches("0x"),
+                    16,
+                )
+                .map_err(|a| a.to_string())?,
+            )),
+            "plt" => Ok(Command::GetPltEntries),
+            "elf" => Ok(Command::GetElfInfo),
+            "find_references" => Ok(Command::FindReferences(
+                u64::from_str_radix(
+                    iter.next()
+                        .ok_or(format!("find_references requires argument \"{}\"", s))?
+                        .trim_start_matches("0x"),
+                    16,
+                )
+                .map_err(|a| a.to_string())?,
+            )),
+            "pointer_info" => Ok(Command::PointerInfo(iter.collect::<Vec<_>>().join(" "))),
+            "track_variable" => Ok(Command::TrackVariable(
+                iter.next()
+                    .ok_or(format!("track_variable requires argument \"{}\"", s))?
+                    .to_string(),
+            )),
+            "untrack_variable" => Ok(Command::UntrackVariable(
+                iter.next()
+                    .ok_or(format!("untrack_variable requires argument \"{}\"", s))?
+                    .to_string(),
+            )),
+            "variable_history" => Ok(Command::GetVariableHistory(
+                iter.next()
+                    .ok_or(format!("variable_history requires argument \"{}\"", s))?
+                    .to_string(),
+            )),
+            "break_on_allocation" => Ok(Command::BreakOnAllocation(
+                match iter.next().ok_or(format!(
+                    "break_on_allocation requires argument on or off \"{}\"",
+                    s
+                ))? {
+                    "on" => true,
+                    "off" => false,
+                    a => {
+                        return Err(format!(
+                            "break_on_allocation expects on or off, got \"{}\"",
+                            a
+                        ))
+                    }
+                },
+            )),
+            "break_on_io" => Ok(Command::BreakOnIO(
+                match iter
+                    .next()
+                    .ok_or(format!("break_on_io requires argument on or off \"{}\"", s))?
+                {
+                    "on" => true,
+                    "off" => false,
+                    a => return Err(format!("break_on_io expects on or off, got \"{}\"", a)),
+                },
+            )),
+            "trace" => Ok(Command::SetTracing(
+                match iter
+                    .next()
+                    .ok_or(format!("trace requires argument on or off \"{}\"", s))?
+                {
+                    "on" => true,
+                    "off" => false,
+                    a => return Err(format!("trace expects on or off, got \"{}\"", a)),
+                },
+            )),
+            "execution_trace" => Ok(Command::GetExecutionTrace),
+            "line_hits" => Ok(Command::GetLineHitCounts),
+            "invariant" => Ok(Command::AddInvariant(iter.collect::<Vec<_>>().join(" "))),
+            "remove_invariant" => Ok(Command::RemoveInvariant(iter.collect::<Vec<_>>().join(" "))),
+            "invariants" => Ok(Command::GetInvariants),
+            "watch" => Ok(Command::AddWatch {
+                expression: iter.collect::<Vec<_>>().join(" "),
+                function: None,
+            }),
+            "watch_in" => Ok(Command::AddWatch {
+                function: Some(
+                    iter.next()
+                        .ok_or("watch_in requires a function and an expression")?
+                        .to_string(),
+                ),
+                expression: iter.collect::<Vec<_>>().join(" "),
+            }),
+            "remove_watch" => Ok(Command::RemoveWatch(iter.collect::<Vec<_>>().join(" "))),
+            "watches" => Ok(Command::GetWatches),
+            "data_structures" => Ok(Command::GetDataStructures),
+            "profile" => Ok(Command::Profile(match iter.next() {
+                Some(period) => period.parse::<u64>().map_err(|a| a.to_string())?,
+                None => 100,
+            })),
+            "get_profile" => Ok(Command::GetProfile),
+            "get_exercise" => Ok(Command::GetExercise),
+            "quiz_value" | "quiz_target" => Ok(Command::AddQuizQuestion(QuizQuestion {
+                breakpoint: u64::from_str_radix(
+                    iter.next()
+                        .ok_or("quiz requires an address and an expression".to_string())?
+                        .trim_start_matches("0x"),
+                    16,
+                )
+                .map_err(|e| e.to_string())?,
+                expression: iter.collect::<Vec<_>>().join(" "),
+                kind: match s.starts_with("quiz_value") {
+                    true => QuizKind::Value,
+                    false => QuizKind::Target,
+                },
+            })),
+            "get_quiz" => Ok(Command::GetQuiz),
+            "answer_quiz" => Ok(Command::AnswerQuiz {
+                expression: iter
+                    .next()
+                    .ok_or("answer_quiz requires an expression and an answer".to_string())?
+                    .to_string(),
+                answer: iter.collect::<Vec<_>>().join(" "),
+            }),
+            "get_diagnostics" => Ok(Command::GetDiagnostics),
+            "cancel" => Ok(Command::Cancel(
+                iter.next()
+                    .ok_or(format!("cancel requires argument \"{}\"", s))?
+                    .to_string(),
+            )),
             "find_func" => Ok(Command::FindFunc(
                 iter.next()
                     .ok_or(format!("find_func requires argument \"{}\"", s))?
                     .to_string(),
             )),
             "step_out" => Ok(Command::StepOut),
+            "finish_frames" => Ok(Command::FinishFrames(
+                iter.next()
+                    .ok_or("finish_frames requires argument \"count\"")?
+                    .parse()
+                    .map_err(|_| "Invalid number")?,
+            )),
+            "source" => Ok(Command::GetSource(
+                iter.next()
+                    .ok_or(format!("source requires argument \"{}\"", s))?
+                    .to_owned(),
+            )),
             "src" => Ok(Command::ViewSource(
                 iter.next()
                     .ok_or(format!("src requires argument \"{}\"", s))?
@@ -323,11 +1975,16 @@ impl FromStr for Command {
                     16,
                 ) {
                     Ok(a) => BreakpointPoint::Address(a),
-                    Err(_) => BreakpointPoint::Name(
-                        iter.next()
-                            .ok_or(format!("set_breakpoint requires argument \"{}\"", s))?
-                            .to_string(),
-                    ),
+                    Err(_) => {
+                        let name = iter
+                            .next()
+                            .ok_or(format!("set_breakpoint requires argument \"{}\"", s))?;
+                        // `*name` breaks at the raw function entry like in gdb
+                        match name.strip_prefix('*') {
+                            Some(name) => BreakpointPoint::Entry(name.to_string()),
+                            None => BreakpointPoint::Name(name.to_string()),
+                        }
+                    }
                 },
             )),
             _ => Err("Unknown command".to_string()),
@@ -0,0 +1,7 @@
+int main() {
+    short numbers[4] = {1, 2, 3, 4};
+    short *first = numbers;
+    short *misaligned = (short *)((char *)numbers + 1);
+    short *end = &numbers[4];
+    return *first + (misaligned < end);
+}
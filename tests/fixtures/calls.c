@@ -0,0 +1,12 @@
+int g(int x) {
+    return x + 1;
+}
+
+int f(int x) {
+    return x * 2;
+}
+
+int main() {
+    int result = f(g(1));
+    return result;
+}
@@ -0,0 +1,18 @@
+namespace shapes {
+class Rect {
+  public:
+    int width;
+    int height;
+    int area() const { return width * height; }
+};
+
+int scale(Rect &rect, int factor) {
+    rect.width *= factor;
+    return rect.area();
+}
+} // namespace shapes
+
+int main() {
+    shapes::Rect rect = {2, 3};
+    return shapes::scale(rect, 2);
+}
@@ -0,0 +1,20 @@
+#include <stdlib.h>
+
+struct node {
+    int value;
+    struct node *next;
+};
+
+int main() {
+    struct node *head = NULL;
+    for (int i = 0; i < 3; i++) {
+        struct node *node = malloc(sizeof(struct node));
+        node->value = i;
+        node->next = head;
+        head = node;
+    }
+    int *numbers = malloc(4 * sizeof(int));
+    numbers[0] = head->value;
+    free(numbers);
+    return 0;
+}
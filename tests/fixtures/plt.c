@@ -0,0 +1,7 @@
+#include <stdio.h>
+
+int main() {
+    puts("first");
+    puts("second");
+    return 0;
+}
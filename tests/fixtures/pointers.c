@@ -0,0 +1,9 @@
+int main() {
+    int value = 42;
+    int *pointer = &value;
+    int **double_pointer = &pointer;
+    int numbers[4] = {1, 2, 3, 4};
+    int *element = &numbers[2];
+    *element = **double_pointer;
+    return 0;
+}
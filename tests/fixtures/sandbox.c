@@ -0,0 +1,11 @@
+#include <fcntl.h>
+#include <sys/socket.h>
+#include <unistd.h>
+
+int main() {
+    int parent = open("..", O_RDONLY | O_DIRECTORY);
+    int created = open("created.txt", O_CREAT | O_WRONLY, 0600);
+    int network = socket(AF_INET, SOCK_STREAM, 0);
+    int forked = fork();
+    return 0;
+}
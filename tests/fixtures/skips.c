@@ -0,0 +1,12 @@
+#include <stdio.h>
+
+int helper(int x) {
+    return x + 1;
+}
+
+int main() {
+    int a = 1;
+    printf("%d\n", a);
+    a = helper(a);
+    return a;
+}
@@ -0,0 +1,21 @@
+struct point {
+    int x;
+    int y;
+};
+
+struct rectangle {
+    struct point origin;
+    struct point size;
+    char name[8];
+};
+
+int area(struct rectangle *rectangle) {
+    return rectangle->size.x * rectangle->size.y;
+}
+
+int main() {
+    struct rectangle rectangle = {{1, 2}, {3, 4}, "box"};
+    struct point corner = rectangle.origin;
+    int result = area(&rectangle);
+    return result - 12 + corner.x - 1;
+}
@@ -0,0 +1,31 @@
+typedef unsigned int count_t;
+typedef count_t size_of_list;
+
+struct tree {
+    int value;
+    struct tree *left;
+    struct tree *right;
+};
+
+typedef struct tree tree_t;
+
+int grid[2][3];
+const char *message = "types";
+
+int sum(int (*row)[3], size_of_list length) {
+    int total = 0;
+    for (count_t i = 0; i < length; i++) {
+        total += (*row)[i];
+    }
+    return total;
+}
+
+int main() {
+    tree_t leaf = {1, 0, 0};
+    tree_t root = {2, &leaf, 0};
+    tree_t **cursor = (tree_t *[]){&root, &leaf};
+    void *opaque = &root;
+    size_of_list length = 3;
+    int (*row)[3] = &grid[1];
+    return sum(row, length) + cursor[0]->value + (opaque == 0) - 2;
+}
@@ -0,0 +1,21 @@
+#include <stdlib.h>
+
+struct node {
+    int value;
+    struct node *next;
+};
+
+void append(struct node *list, int value) {
+    struct node *node = malloc(sizeof(struct node));
+    node->value = value;
+    node->next = NULL;
+    list->next = node;
+}
+
+int main() {
+    struct node head = {1, NULL};
+    int count = 0;
+    count++;
+    append(&head, 2);
+    return count;
+}
@@ -0,0 +1,114 @@
+use egui::RichText;
+use poll_promise::Promise;
+use stackium_shared::{Action, Breakpoint, Command, CommandOutput};
+use url::Url;
+
+use crate::{
+    capabilities,
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
+
+/// The breakpoint changes and memory and register writes which can be undone
+pub struct ActionWindow {
+    backend_url: Url,
+    actions: Promise<Result<Vec<Action>, String>>,
+    /// An `Undo` is pending, the other windows are refreshed once it is done
+    undoing: bool,
+}
+
+impl ActionWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            actions: Promise::from_ready(Ok(vec![])),
+            undoing: false,
+        };
+        ret.dirty();
+        ret
+    }
+}
+
+/// Short description of an action, e.g. `Wrote 2 bytes at 0x7ffc1234`
+fn describe_action(ctx: &egui::Context, action: &Action) -> String {
+    let location = |breakpoint: &Breakpoint| match breakpoint.location.line {
+        0 => format!("{:#x}", breakpoint.address),
+        line => trf(ctx, "line {}", &[&line]),
+    };
+    match action {
+        Action::SetBreakpoint(breakpoint) => {
+            trf(ctx, "Set breakpoint at {}", &[&location(breakpoint)])
+        }
+        Action::DeleteBreakpoint(breakpoint) => {
+            trf(ctx, "Deleted breakpoint at {}", &[&location(breakpoint)])
+        }
+        Action::WriteMemory { address, new, .. } => trf(
+            ctx,
+            "Wrote {} bytes at {}",
+            &[&new.len(), &format!("{:#x}", address)],
+        ),
+        Action::SetRegister { name, old, new } => trf(
+            ctx,
+            "Set {} from {} to {}",
+            &[name, &format!("{:#x}", old), &format!("{:#x}", new)],
+        ),
+    }
+}
+
+impl DebuggerWindowImpl for ActionWindow {
+    fn dirty(&mut self) {
+        self.actions = dispatch!(self.backend_url.clone(), Command::GetActions, Actions);
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        if let Some(unsupported) = capabilities::unsupported(ui.ctx(), "Undo") {
+            ui.label(RichText::new(unsupported).color(ui.visuals().warn_fg_color));
+            return false;
+        }
+        let mut dirty = false;
+        match self.actions.ready() {
+            Some(Ok(actions)) => {
+                if self.undoing {
+                    self.undoing = false;
+                    dirty = true;
+                }
+                if ui
+                    .add_enabled(
+                        !actions.is_empty(),
+                        egui::Button::new(tr(ui.ctx(), "⟲ Undo")),
+                    )
+                    .on_hover_text(tr(ui.ctx(), "Revert the most recent action"))
+                    .clicked()
+                {
+                    self.actions = dispatch!(self.backend_url.clone(), Command::Undo, Actions);
+                    self.undoing = true;
+                    return dirty;
+                }
+                ui.separator();
+                if actions.is_empty() {
+                    ui.label(RichText::new(tr(ui.ctx(), "Nothing to undo")).weak());
+                }
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for action in actions.iter().rev() {
+                        ui.label(describe_action(ui.ctx(), action));
+                    }
+                });
+            }
+            Some(Err(err)) => {
+                ui.label(
+                    RichText::new(format!("⚠ {}", err))
+                        .small()
+                        .color(ui.visuals().warn_fg_color),
+                );
+                if ui.small_button("Ok").clicked() {
+                    self.undoing = false;
+                    self.dirty();
+                }
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+        dirty
+    }
+}
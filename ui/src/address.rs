@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, ResolvedAddress};
+use url::Url;
+
+/// Resolves addresses to symbolic descriptions like `main+0x24 (test.c:12)`, caching the
+/// results until the next stop
+pub struct AddressResolver {
+    backend_url: Url,
+    resolved: HashMap<u64, Promise<Result<ResolvedAddress, String>>>,
+}
+
+impl AddressResolver {
+    pub fn new(backend_url: Url) -> Self {
+        Self {
+            backend_url,
+            resolved: HashMap::new(),
+        }
+    }
+
+    /// Description of `address` if it belongs to a function, symbol or mapped file, requests
+    /// it from the backend when called for the first time
+    pub fn describe(&mut self, address: u64) -> Option<String> {
+        // small values are most likely plain numbers, the first page is never mapped anyway
+        if address < 0x1000 {
+            return None;
+        }
+        let backend_url = &self.backend_url;
+        let resolved = self.resolved.entry(address).or_insert_with(|| {
+            dispatch!(
+                backend_url.clone(),
+                Command::ResolveAddress(address),
+                ResolvedAddress
+            )
+        });
+        match resolved.ready() {
+            Some(Ok(resolved)) if resolved.description != format!("{:#x}", address) => {
+                Some(resolved.description.clone())
+            }
+            _ => None,
+        }
+    }
+
+    /// Formats `address` as hex followed by its description if there is one
+    pub fn format(&mut self, address: u64) -> String {
+        match self.describe(address) {
+            Some(description) => format!("{:#x} <{}>", address, description),
+            None => format!("{:#x}", address),
+        }
+    }
+
+    /// Drops all cached results, e.g. because the memory mappings might have changed
+    pub fn clear(&mut self) {
+        self.resolved.clear();
+    }
+}
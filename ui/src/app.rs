@@ -1,22 +1,42 @@
-use egui::{load::SizedTexture, Align, Layout, TextureHandle};
+use egui::{load::SizedTexture, Align, Context, Layout, RichText, TextureHandle};
 use egui_dock::{DockArea, DockState, TabViewer};
 use poll_promise::Promise;
-use stackium_shared::{Command, CommandOutput, DebugMeta};
+use stackium_shared::{Capabilities, Command, CommandOutput, DebugMeta, DebugeeState, ExitSummary};
 use url::Url;
 
 use crate::{
+    action_window::ActionWindow,
     breakpoint_window::BreakpointWindow,
+    capabilities,
     code_window::CodeWindow,
-    command::{dispatch, dispatch_command_and_then},
+    command::{dispatch, dispatch_command_and_then, fetch_capabilities, launch_program},
+    config::Config,
     control_window::ControlWindow,
-    debugger_window::{DebuggerWindow, Metadata},
+    debugger_window::{DebuggerWindow, DebuggerWindowImpl, Metadata},
+    driver::{self, DriverBanner},
+    exercise_panel::ExercisePanel,
     graph_window::GraphWindow,
+    hexdump_window::HexdumpWindow,
+    history_window::HistoryWindow,
+    i18n::{self, tr, trf, Language},
+    invariant_window::InvariantWindow,
+    layout_window::LayoutWindow,
+    list_window::ListWindow,
     location::LocationWindow,
     map_window::MapWindow,
     memory_window::MemoryWindow,
+    plt_window::PltWindow,
+    profile_window::ProfileWindow,
+    quiz_window::QuizWindow,
     register_window::RegisterWindow,
+    replay_view::{parse_session, ReplayView},
+    scope_window::ScopeWindow,
+    selection,
     settings_window::SettingsWindow,
+    snapshot_view::{parse_snapshot, SnapshotView},
     toggle::toggle_ui,
+    tree_window::TreeWindow,
+    watch_window::WatchWindow,
 };
 
 enum State {
@@ -26,7 +46,8 @@ enum State {
         metadata: Promise<Result<DebugMeta, String>>,
         dockable_windows: DockState<&'static str>,
         icon: Option<TextureHandle>,
-        mapping: Promise<Result<(), String>>,
+        /// Polled after every command, switches to the exit screen once the child exited
+        status: Promise<Result<Option<ExitSummary>, String>>,
         restart_request: Option<Promise<Result<(), String>>>,
         tab_viewer: CustomTabViewer,
     },
@@ -34,12 +55,27 @@ enum State {
         message: String,
         restart_request: Option<Promise<Result<(), String>>>,
     },
+    /// The program exited, it can be restarted
+    Exited {
+        summary: ExitSummary,
+        restart_request: Option<Promise<Result<(), String>>>,
+    },
+    /// Viewing an exported snapshot, no debugger needed
+    Snapshot { view: SnapshotView },
+    /// Replaying an exported session, no debugger needed
+    Replay { view: ReplayView },
+    /// Waiting for the debugger to launch a dropped program
+    Launching {
+        file_name: String,
+        request: Promise<Result<String, String>>,
+    },
 }
 
 impl State {
     fn construct_debugging_state(backend_url: &Url) -> Self {
         let tab_viewer = CustomTabViewer {
             dirty: false,
+            language: Language::default(),
             windows: vec![
                 DebuggerWindow {
                     title: "Metadata",
@@ -64,7 +100,7 @@ impl State {
                 DebuggerWindow {
                     title: "Settings",
                     is_active: false,
-                    body: Box::from(SettingsWindow::new()),
+                    body: Box::from(SettingsWindow::new(backend_url.clone())),
                 },
                 DebuggerWindow {
                     title: "Controls",
@@ -76,21 +112,81 @@ impl State {
                     is_active: true,
                     body: Box::from(MemoryWindow::new(backend_url.clone())),
                 },
+                DebuggerWindow {
+                    title: "Variables",
+                    is_active: false,
+                    body: Box::from(ScopeWindow::new(backend_url.clone())),
+                },
                 DebuggerWindow {
                     title: "Graph",
                     is_active: false,
                     body: Box::from(GraphWindow::new(backend_url.clone())),
                 },
+                DebuggerWindow {
+                    title: "List",
+                    is_active: false,
+                    body: Box::from(ListWindow::new(backend_url.clone())),
+                },
+                DebuggerWindow {
+                    title: "Tree",
+                    is_active: false,
+                    body: Box::from(TreeWindow::new(backend_url.clone())),
+                },
+                DebuggerWindow {
+                    title: "History",
+                    is_active: false,
+                    body: Box::from(HistoryWindow::new(backend_url.clone())),
+                },
+                DebuggerWindow {
+                    title: "Invariants",
+                    is_active: false,
+                    body: Box::from(InvariantWindow::new(backend_url.clone())),
+                },
+                DebuggerWindow {
+                    title: "Watches",
+                    is_active: false,
+                    body: Box::from(WatchWindow::new(backend_url.clone())),
+                },
+                DebuggerWindow {
+                    title: "Quiz",
+                    is_active: false,
+                    body: Box::from(QuizWindow::new(backend_url.clone())),
+                },
+                DebuggerWindow {
+                    title: "Dynamic Linking",
+                    is_active: false,
+                    body: Box::from(PltWindow::new(backend_url.clone())),
+                },
+                DebuggerWindow {
+                    title: "Profile",
+                    is_active: false,
+                    body: Box::from(ProfileWindow::new(backend_url.clone())),
+                },
                 DebuggerWindow {
                     title: "Registers",
                     is_active: false,
                     body: Box::from(RegisterWindow::new(backend_url.clone())),
                 },
+                DebuggerWindow {
+                    title: "Hexdump",
+                    is_active: false,
+                    body: Box::from(HexdumpWindow::new(backend_url.clone())),
+                },
+                DebuggerWindow {
+                    title: "Memory Layout",
+                    is_active: false,
+                    body: Box::from(LayoutWindow::new(backend_url.clone())),
+                },
                 DebuggerWindow {
                     title: "Memory Mapping",
                     is_active: false,
                     body: Box::from(MapWindow::new(backend_url.clone())),
                 },
+                DebuggerWindow {
+                    title: "Undo History",
+                    is_active: false,
+                    body: Box::from(ActionWindow::new(backend_url.clone())),
+                },
             ],
         };
         let mut dock_state = DockState::new(vec!["Memory"]);
@@ -110,7 +206,7 @@ impl State {
             sidebar_open: true,
             backend_url: backend_url.clone(),
             metadata: { dispatch!(backend_url.clone(), Command::DebugMeta, DebugMeta) },
-            mapping: { dispatch_command_and_then(backend_url.clone(), Command::Maps, |maps| {}) },
+            status: dispatch_status(backend_url),
             dockable_windows: dock_state,
             tab_viewer,
             restart_request: None,
@@ -118,8 +214,80 @@ impl State {
     }
 }
 
+fn dispatch_status(backend_url: &Url) -> Promise<Result<Option<ExitSummary>, String>> {
+    dispatch_command_and_then(backend_url.clone(), Command::GetStatus, |out| match out {
+        CommandOutput::Status(status) => status.summary,
+        _ => unreachable!(),
+    })
+}
+
+/// The state to switch to once the restart requested on the failure or exit screen finished
+fn restart_result(
+    ctx: &Context,
+    restart_request: &mut Option<Promise<Result<(), String>>>,
+    backend_url: &Url,
+) -> Option<State> {
+    let result = restart_request.as_ref()?.ready()?;
+    let state = match result {
+        Ok(()) => State::construct_debugging_state(backend_url),
+        Err(e) => State::UnrecoverableFailure {
+            message: trf(
+                ctx,
+                "Restart failed: {}\n Please try manually restarting the debugger in the terminal.",
+                &[e],
+            ),
+            restart_request: None,
+        },
+    };
+    *restart_request = None;
+    Some(state)
+}
+
+fn restart_button(
+    ui: &mut egui::Ui,
+    restart_request: &mut Option<Promise<Result<(), String>>>,
+    backend_url: &Url,
+) {
+    if ui
+        .add(egui::Button::new(tr(ui.ctx(), "↻ Restart Process")).fill(ui.visuals().window_fill))
+        .clicked()
+    {
+        *restart_request = Some(dispatch_command_and_then(
+            backend_url.clone(),
+            Command::RestartDebugee,
+            |_| {},
+        ));
+    }
+}
+
+fn render_exit_summary(ui: &mut egui::Ui, summary: &ExitSummary) {
+    ui.heading(match &summary.state {
+        DebugeeState::Exited(code) => trf(ui.ctx(), "Program exited with code {}", &[code]),
+        DebugeeState::Signaled(signal) => trf(ui.ctx(), "Program was terminated by {}", &[signal]),
+        state => trf(ui.ctx(), "Program stopped: {}", &[&format!("{:?}", state)]),
+    });
+    egui::Grid::new("exit_summary")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label(tr(ui.ctx(), "Run time"));
+            ui.label(format!("{:.2} s", summary.duration as f64 / 1000.));
+            ui.end_row();
+            ui.label(tr(ui.ctx(), "Steps and continues"));
+            ui.label(summary.resumes.to_string());
+            ui.end_row();
+            ui.label(tr(ui.ctx(), "Instructions single-stepped"));
+            ui.label(summary.instructions.to_string());
+            ui.end_row();
+            ui.label(tr(ui.ctx(), "Breakpoints hit"));
+            ui.label(summary.breakpoints_hit.to_string());
+            ui.end_row();
+        });
+}
+
 struct CustomTabViewer {
     dirty: bool,
+    /// The tab titles are translated without access to the context
+    language: Language,
     windows: Vec<DebuggerWindow>,
 }
 
@@ -127,7 +295,7 @@ impl TabViewer for CustomTabViewer {
     type Tab = &'static str;
 
     fn title(&mut self, tab: &mut Self::Tab) -> egui::WidgetText {
-        tab.to_string().into()
+        self.language.translate(tab).into()
     }
 
     fn ui(&mut self, ui: &mut egui::Ui, tab_name: &mut Self::Tab) {
@@ -156,24 +324,105 @@ pub struct StackiumApp {
     backend_url: Url,
     state: State,
     next_state: Option<State>,
+    /// Taken once the backend answered, the windows read the capabilities from the context
+    capabilities: Option<Promise<Result<Capabilities, String>>>,
+    /// Shown next to the windows while debugging a program with an exercise
+    exercise: ExercisePanel,
+    driver: DriverBanner,
+    /// Identifies this UI to the backend, see [`driver::identified`]
+    client_id: String,
 }
 
 impl StackiumApp {
-    pub fn new(_cc: &eframe::CreationContext<'_>) -> Self {
-        let backend_url = Url::parse("http://localhost:8080").unwrap();
+    pub fn new(cc: &eframe::CreationContext<'_>) -> Self {
+        let config = Config::get(&cc.egui_ctx);
+        cc.egui_ctx.set_theme(config.color_scheme);
+        config.theme.apply(&cc.egui_ctx);
+        let backend_url = Url::parse(&config.backend_url)
+            .unwrap_or_else(|_| Url::parse(&Config::default().backend_url).unwrap());
+        let client_id = driver::client_id();
+        let backend_url = driver::identified(&backend_url, &client_id, &config.client_name);
         Self {
             state: State::construct_debugging_state(&backend_url),
+            capabilities: Some(fetch_capabilities(backend_url.clone())),
+            exercise: ExercisePanel::new(backend_url.clone()),
+            driver: DriverBanner::new(backend_url.clone()),
+            client_id,
             backend_url,
             next_state: None,
         }
     }
 }
 
+impl StackiumApp {
+    /// Opens snapshot and session files dropped onto the window, binaries and C sources are
+    /// launched in the debugger
+    fn open_dropped_file(&mut self, ctx: &egui::Context) {
+        let Some(file) = ctx.input(|i| i.raw.dropped_files.first().cloned()) else {
+            return;
+        };
+        let bytes = match (&file.bytes, &file.path) {
+            (Some(bytes), _) => Ok(bytes.to_vec()),
+            #[cfg(not(target_arch = "wasm32"))]
+            (None, Some(path)) => std::fs::read(path).map_err(|e| e.to_string()),
+            _ => Err(tr(ctx, "Failed reading the dropped file").to_owned()),
+        };
+        let file_name = match &file.path {
+            Some(path) => path.to_string_lossy().into_owned(),
+            None => file.name.clone(),
+        };
+        // sessions are a list of commands, snapshots a single object
+        let state = bytes.and_then(|bytes| {
+            if file_name.ends_with(".c") || bytes.starts_with(b"\x7fELF") {
+                let name = file_name.rsplit('/').next().unwrap_or_default();
+                return Ok(State::Launching {
+                    request: launch_program(self.backend_url.clone(), name, bytes),
+                    file_name,
+                });
+            }
+            match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+                Some(b'[') => parse_session(&bytes).map(|entries| State::Replay {
+                    view: ReplayView::new(file_name, entries),
+                }),
+                _ => parse_snapshot(&bytes).map(|snapshot| State::Snapshot {
+                    view: SnapshotView::new(file_name, snapshot),
+                }),
+            }
+        });
+        self.next_state = Some(state.unwrap_or_else(|message| State::UnrecoverableFailure {
+            message,
+            restart_request: None,
+        }));
+    }
+}
+
 impl eframe::App for StackiumApp {
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {
+        i18n::set_language(ctx, Config::get(ctx).language);
         if let Some(next_state) = self.next_state.take() {
+            // the program was restarted or another one launched
+            if matches!(next_state, State::Debugging { .. }) {
+                self.exercise.dirty();
+            }
             self.state = next_state;
         }
+        // the backend url or the client name was changed in the settings
+        let config = Config::get(ctx);
+        if let Ok(url) = Url::parse(&config.backend_url) {
+            let url = driver::identified(&url, &self.client_id, &config.client_name);
+            if url != self.backend_url {
+                self.state = State::construct_debugging_state(&url);
+                self.capabilities = Some(fetch_capabilities(url.clone()));
+                self.exercise = ExercisePanel::new(url.clone());
+                self.driver = DriverBanner::new(url.clone());
+                self.backend_url = url;
+            }
+        }
+        self.open_dropped_file(ctx);
+        if let Some(result) = self.capabilities.as_ref().and_then(|c| c.ready()) {
+            capabilities::set(ctx, result.clone());
+            self.capabilities = None;
+        }
         if let State::Debugging {
             sidebar_open: _,
             backend_url: _,
@@ -181,16 +430,24 @@ impl eframe::App for StackiumApp {
             dockable_windows: _,
             tab_viewer,
             icon: _,
-            mapping,
+            status,
             restart_request: _,
         } = &mut self.state
         {
-            if let Some(Err(_)) = mapping.ready() {
-                self.next_state = Some(State::UnrecoverableFailure {
-                    message: "Child process exited".to_owned(),
-                    restart_request: None,
-                });
-                // return;
+            match status.ready() {
+                Some(Ok(Some(summary))) => {
+                    self.next_state = Some(State::Exited {
+                        summary: summary.clone(),
+                        restart_request: None,
+                    });
+                }
+                Some(Err(err)) => {
+                    self.next_state = Some(State::UnrecoverableFailure {
+                        message: err.clone(),
+                        restart_request: None,
+                    });
+                }
+                _ => {}
             }
             for window in tab_viewer.windows.iter_mut() {
                 window.body.update(ctx, frame);
@@ -206,8 +463,8 @@ impl eframe::App for StackiumApp {
             // The top panel is often a good place for a menu bar:
             egui::menu::bar(ui, |ui| {
                 // #[cfg(not(target_arch = "wasm32"))] // no File->Quit on web pages!
-                ui.menu_button("File", |ui| {
-                    if ui.button("Quit").clicked() {
+                ui.menu_button(tr(ui.ctx(), "File"), |ui| {
+                    if ui.button(tr(ui.ctx(), "Quit")).clicked() {
                         // frame.
                     }
                 });
@@ -223,11 +480,14 @@ impl eframe::App for StackiumApp {
                 sidebar_open,
                 dockable_windows,
                 icon,
-                mapping,
+                status,
                 tab_viewer,
                 restart_request,
             } => {
+                let exercise = &mut self.exercise;
+                let driver = &mut self.driver;
                 tab_viewer.dirty = false;
+                tab_viewer.language = i18n::language(ctx);
 
                 if let Some(Some(Ok(p))) = restart_request.as_mut().map(|p| p.ready()) {
                     *restart_request = None;
@@ -238,7 +498,10 @@ impl eframe::App for StackiumApp {
                     ui.horizontal(|ui| {
                         egui::widgets::global_theme_preference_buttons(ui);
                         if ui
-                            .add(egui::Button::new("↻ Restart").fill(ui.visuals().window_fill))
+                            .add(
+                                egui::Button::new(tr(ui.ctx(), "↻ Restart"))
+                                    .fill(ui.visuals().window_fill),
+                            )
                             .clicked()
                         {
                             *restart_request = Some(dispatch_command_and_then(
@@ -247,6 +510,12 @@ impl eframe::App for StackiumApp {
                                 |_| {},
                             ));
                         }
+                        if let Ok(url) = backend_url.join("/export/session.json") {
+                            ui.hyperlink_to(tr(ui.ctx(), "⬇ Session"), url).on_hover_text(tr(
+                                ui.ctx(),
+                                "Download a recording of this session, drop it into the window to replay it",
+                            ));
+                        }
                     });
                     let texture = icon.get_or_insert_with(|| {
                         let icon = include_bytes!("../assets/icon-1024.png");
@@ -272,10 +541,10 @@ impl eframe::App for StackiumApp {
                         ui.heading("Stackium");
                         ui.add_space(20.);
                     });
-                    ui.heading("Windows");
+                    ui.heading(tr(ui.ctx(), "Windows"));
                     for window in tab_viewer.windows.iter_mut() {
                         ui.horizontal(|ui| {
-                            if ui.label(window.title).clicked() {
+                            if ui.label(tr(ui.ctx(), window.title)).clicked() {
                                 window.is_active = !window.is_active;
                             }
                             ui.with_layout(
@@ -326,22 +595,55 @@ impl eframe::App for StackiumApp {
                                 format!("Stackium {}", egui::special_emojis::GITHUB),
                                 "https://github.com/dotjulia/stackium",
                             );
-                            ui.label("made with ♥ by");
+                            ui.label(tr(ui.ctx(), "made with ♥ by"));
                             ui.hyperlink_to("dotjulia", "juli.zip")
                         });
                     });
                 });
 
+                egui::SidePanel::right("exercise_panel").show_animated(
+                    ctx,
+                    exercise.has_exercise(),
+                    |ui| exercise.ui(ui),
+                );
+
                 egui::CentralPanel::default()
                     .frame(egui::Frame::central_panel(&ctx.style()).inner_margin(0.))
                     .show(ctx, |ui| match metadata.ready() {
                         Some(m) => match m {
                             Ok(m) => {
                                 if !*sidebar_open {
-                                    if ui.button("Open Sidebar").clicked() {
+                                    if ui.button(tr(ui.ctx(), "Open Sidebar")).clicked() {
                                         *sidebar_open = true;
                                     }
                                 }
+                                if let Some(warning) = capabilities::version_warning(ui.ctx()) {
+                                    ui.colored_label(ui.visuals().warn_fg_color, warning);
+                                }
+                                if let Some(notice) = capabilities::read_only_notice(ui.ctx()) {
+                                    ui.label(notice);
+                                }
+                                driver.ui(ui);
+                                if !m.has_debug_info {
+                                    ui.colored_label(
+                                        ui.visuals().warn_fg_color,
+                                        trf(
+                                            ui.ctx(),
+                                            "⚠ {} has no debug information: source code and variables are unavailable and functions are read from the symbol table. Compile with -g -gdwarf-4",
+                                            &[&m.binary_name],
+                                        ),
+                                    );
+                                }
+                                if let Some(optimization) = &m.optimization {
+                                    ui.colored_label(
+                                        ui.visuals().warn_fg_color,
+                                        trf(
+                                            ui.ctx(),
+                                            "⚠ {} was compiled with {}: the compiler keeps variables in registers or removes them and reorders lines, so variables may be missing and steps may jump around. Compile with -O0 -g",
+                                            &[&m.binary_name, optimization],
+                                        ),
+                                    );
+                                }
 
                                 DockArea::new(dockable_windows)
                                     .style(egui_dock::Style::from_egui(ui.style()))
@@ -351,14 +653,17 @@ impl eframe::App for StackiumApp {
                                     // .allowed_splits(self.context.allowed_splits)
                                     .show_window_collapse_buttons(true)
                                     .show_inside(ui, tab_viewer);
+                                for message in selection::take(ctx) {
+                                    tab_viewer
+                                        .windows
+                                        .iter_mut()
+                                        .for_each(|w| w.body.receive(&message));
+                                }
                                 if tab_viewer.dirty {
                                     tab_viewer.dirty = false;
                                     tab_viewer.windows.iter_mut().for_each(|w| w.body.dirty());
-                                    *mapping = dispatch_command_and_then(
-                                        backend_url.clone(),
-                                        Command::Maps,
-                                        |_| {},
-                                    )
+                                    exercise.dirty();
+                                    *status = dispatch_status(backend_url)
                                 }
                             }
                             Err(e) => {
@@ -366,11 +671,11 @@ impl eframe::App for StackiumApp {
                                     message: e.clone(),
                                     restart_request: None,
                                 });
-                                ui.heading("Loading...".to_owned());
+                                ui.heading(tr(ui.ctx(), "Loading..."));
                             }
                         },
                         None => {
-                            ui.heading("Loading...".to_owned());
+                            ui.heading(tr(ui.ctx(), "Loading..."));
                         }
                     });
             }
@@ -379,40 +684,96 @@ impl eframe::App for StackiumApp {
                 restart_request,
             } => {
                 egui::CentralPanel::default().show(ctx, |ui| {
-                    match restart_request.as_mut().map(|p| p.ready()) {
-                        Some(Some(Ok(p))) => {
-                            *restart_request = None;
+                    if let Some(state) = restart_result(ctx, restart_request, &self.backend_url) {
+                        self.next_state = Some(state);
+                        return;
+                    }
+                    if restart_request.is_some() {
+                        ui.spinner();
+                        return;
+                    }
+
+                    ui.heading(tr(ui.ctx(), "Error"));
+                    ui.label(message.clone());
+                    ui.label(tr(ui.ctx(), "Please restart the debugger"));
+                    ui.label(
+                        RichText::new(tr(
+                            ui.ctx(),
+                            "Or drop a program (binary or .c file) here to debug it, or a snapshot (.json, .json.gz) or session file to view it offline",
+                        ))
+                        .weak(),
+                    );
+                    restart_button(ui, restart_request, &self.backend_url);
+                });
+            }
+            State::Exited {
+                summary,
+                restart_request,
+            } => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    if let Some(state) = restart_result(ctx, restart_request, &self.backend_url) {
+                        self.next_state = Some(state);
+                        return;
+                    }
+                    if restart_request.is_some() {
+                        ui.spinner();
+                        return;
+                    }
+                    render_exit_summary(ui, summary);
+                    ui.add_space(8.);
+                    restart_button(ui, restart_request, &self.backend_url);
+                    ui.label(
+                        RichText::new(tr(
+                            ui.ctx(),
+                            "Or drop a program (binary or .c file) here to debug it, or a snapshot (.json, .json.gz) or session file to view it offline",
+                        ))
+                        .weak(),
+                    );
+                });
+            }
+            State::Snapshot { view } => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.heading(tr(ui.ctx(), "Snapshot"));
+                        ui.label(RichText::new(&view.file_name).weak());
+                        if ui.button(tr(ui.ctx(), "✖ Close")).clicked() {
                             self.next_state =
                                 Some(State::construct_debugging_state(&self.backend_url));
-                            return;
-                        }
-                        Some(Some(Err(e))) => {
-                            self.next_state = Some(State::UnrecoverableFailure {
-                                message: format!("Restart failed: {}\n Please try manually restarting the debugger in the terminal.", e),
-                                restart_request: None,
-                            });
-                            return;
                         }
-                        Some(None) => {
-                            ui.spinner();
-                            return;
-                        }
-                        None => {}
+                    });
+                    view.ui(ui);
+                });
+            }
+            State::Launching { file_name, request } => {
+                egui::CentralPanel::default().show(ctx, |ui| match request.ready() {
+                    Some(Ok(_)) => {
+                        self.next_state = Some(State::construct_debugging_state(&self.backend_url));
                     }
-
-                    ui.heading("Error");
-                    ui.label(message.clone());
-                    ui.label("Please restart the debugger".to_owned());
-                    if ui
-                        .add(egui::Button::new("↻ Restart Process").fill(ui.visuals().window_fill))
-                        .clicked()
-                    {
-                        *restart_request = Some(dispatch_command_and_then(
-                            self.backend_url.clone(),
-                            Command::RestartDebugee,
-                            |_| {},
-                        ));
+                    Some(Err(message)) => {
+                        self.next_state = Some(State::UnrecoverableFailure {
+                            message: trf(ctx, "Failed launching {}:\n{}", &[file_name, message]),
+                            restart_request: None,
+                        });
                     }
+                    None => {
+                        ui.horizontal(|ui| {
+                            ui.spinner();
+                            ui.heading(trf(ui.ctx(), "Launching {}", &[file_name]));
+                        });
+                    }
+                });
+            }
+            State::Replay { view } => {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    ui.horizontal(|ui| {
+                        ui.heading(tr(ui.ctx(), "Replay"));
+                        ui.label(RichText::new(&view.file_name).weak());
+                        if ui.button(tr(ui.ctx(), "✖ Close")).clicked() {
+                            self.next_state =
+                                Some(State::construct_debugging_state(&self.backend_url));
+                        }
+                    });
+                    view.ui(ui);
                 });
             }
         }
@@ -1,21 +1,31 @@
-use egui::{ComboBox, RichText};
+use egui::{ComboBox, RichText, ScrollArea};
 use poll_promise::Promise;
-use stackium_shared::{Breakpoint, BreakpointPoint, Command, CommandOutput};
+use stackium_shared::{Breakpoint, BreakpointPoint, Command, CommandOutput, FunctionGroup};
 use url::Url;
 
-use crate::{command::dispatch_command_and_then, debugger_window::DebuggerWindowImpl};
+use crate::{
+    command::dispatch_command_and_then,
+    debugger_window::DebuggerWindowImpl,
+    i18n::tr,
+    selection::{self, Message},
+};
+
+/// Functions suggested at most while typing the name of a function
+const MAX_SUGGESTIONS: usize = 30;
 
 #[derive(PartialEq)]
 enum Selection {
     Address,
     Function,
+    FunctionEntry,
 }
 
-impl std::fmt::Debug for Selection {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl Selection {
+    fn name(&self) -> &'static str {
         match self {
-            Selection::Address => f.write_str("Address"),
-            Selection::Function => f.write_str("Function"),
+            Selection::Address => "Address",
+            Selection::Function => "Function",
+            Selection::FunctionEntry => "Function Entry",
         }
     }
 }
@@ -27,6 +37,24 @@ pub struct BreakpointWindow {
     backend_url: Url,
     warning: Option<String>,
     adding_breakpoint_req: Option<Promise<Result<(), String>>>,
+    /// Functions the name of a function is completed from, see `function_picker`
+    functions: Promise<Result<Vec<FunctionGroup>, String>>,
+    /// Only suggest functions compiled in the directory of `main`
+    user_code: bool,
+}
+
+fn find_functions(
+    backend_url: Url,
+    user_code: bool,
+) -> Promise<Result<Vec<FunctionGroup>, String>> {
+    dispatch!(
+        backend_url,
+        Command::FindFunctions {
+            pattern: None,
+            user_code,
+        },
+        FunctionGroups
+    )
 }
 
 impl BreakpointWindow {
@@ -35,9 +63,123 @@ impl BreakpointWindow {
             breakpoints: dispatch!(backend_url.clone(), Command::GetBreakpoints, Breakpoints),
             selected: Selection::Function,
             selection_input: "main".to_owned(),
-            backend_url,
+            backend_url: backend_url.clone(),
             warning: None,
             adding_breakpoint_req: None,
+            functions: find_functions(backend_url, true),
+            user_code: true,
+        }
+    }
+}
+
+impl BreakpointWindow {
+    /// The signature of the function typed in and the functions whose name contains the text
+    /// grouped by source file, clicking one picks it and shows its definition in the code window
+    fn function_picker(&mut self, ui: &mut egui::Ui) {
+        if ui
+            .checkbox(
+                &mut self.user_code,
+                tr(ui.ctx(), "Only functions of the program"),
+            )
+            .on_hover_text(tr(
+                ui.ctx(),
+                "Leaves out the functions of libraries compiled into the program",
+            ))
+            .changed()
+        {
+            self.functions = find_functions(self.backend_url.clone(), self.user_code);
+        }
+        let groups = match self.functions.ready() {
+            Some(Ok(groups)) => groups,
+            Some(Err(err)) => {
+                ui.label(
+                    RichText::new(format!("⚠ {}", err))
+                        .small()
+                        .color(ui.visuals().warn_fg_color),
+                );
+                return;
+            }
+            None => {
+                ui.spinner();
+                return;
+            }
+        };
+        let input = self.selection_input.to_lowercase();
+        let mut picked = None;
+        match groups
+            .iter()
+            .flat_map(|group| &group.functions)
+            .find(|f| f.name.as_deref() == Some(self.selection_input.as_str()))
+        {
+            Some(function) => {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(function.signature()).monospace());
+                    if let Some(declaration) = &function.declaration {
+                        if ui
+                            .small_button(tr(ui.ctx(), "show definition"))
+                            .on_hover_text(format!("{}:{}", declaration.file, declaration.line))
+                            .clicked()
+                        {
+                            selection::send(ui.ctx(), Message::Show(declaration.clone()));
+                        }
+                    }
+                });
+            }
+            None => {
+                let mut shown = 0;
+                ScrollArea::vertical().max_height(200.).show(ui, |ui| {
+                    for group in groups {
+                        let matching = group
+                            .functions
+                            .iter()
+                            .filter(|f| {
+                                f.name
+                                    .as_ref()
+                                    .map_or(false, |name| name.to_lowercase().contains(&input))
+                            })
+                            .take(MAX_SUGGESTIONS - shown)
+                            .collect::<Vec<_>>();
+                        if matching.is_empty() {
+                            continue;
+                        }
+                        shown += matching.len();
+                        ui.label(
+                            RichText::new(match group.file.is_empty() {
+                                true => tr(ui.ctx(), "Symbol table"),
+                                false => group.file.as_str(),
+                            })
+                            .small()
+                            .weak(),
+                        );
+                        for function in matching {
+                            let name = function.name.as_deref().unwrap_or_default();
+                            if ui
+                                .selectable_label(false, RichText::new(name).monospace())
+                                .on_hover_text(function.signature())
+                                .clicked()
+                            {
+                                picked = Some(function.clone());
+                            }
+                        }
+                        if shown >= MAX_SUGGESTIONS {
+                            ui.label(tr(
+                                ui.ctx(),
+                                "Type more of the name to see further functions",
+                            ));
+                            break;
+                        }
+                    }
+                });
+                if shown == 0 {
+                    ui.label(tr(ui.ctx(), "No function matches"));
+                }
+            }
+        }
+        if let Some(function) = picked {
+            self.selection_input = function.name.clone().unwrap_or_default();
+            if let Some(declaration) = function.declaration {
+                selection::send(ui.ctx(), Message::Show(declaration));
+            }
         }
     }
 }
@@ -56,7 +198,7 @@ impl DebuggerWindowImpl for BreakpointWindow {
         match self.breakpoints.ready() {
             Some(breakpoints) => match breakpoints {
                 Ok(breakpoints) => {
-                    ui.heading("Breakpoints");
+                    ui.heading(tr(ui.ctx(), "Breakpoints"));
                     for breakpoint in breakpoints.iter() {
                         ui.horizontal(|ui| {
                             ui.label(format!(
@@ -67,11 +209,14 @@ impl DebuggerWindowImpl for BreakpointWindow {
                                 breakpoint.address
                             ));
                             if ui
-                                .button(if breakpoint.enabled {
-                                    "disable"
-                                } else {
-                                    "enable"
-                                })
+                                .button(tr(
+                                    ui.ctx(),
+                                    if breakpoint.enabled {
+                                        "disable"
+                                    } else {
+                                        "enable"
+                                    },
+                                ))
                                 .clicked()
                             {
                                 self.adding_breakpoint_req = Some(dispatch_command_and_then(
@@ -94,10 +239,27 @@ impl DebuggerWindowImpl for BreakpointWindow {
         };
         ui.horizontal(|ui| {
             ComboBox::new("Address or Function", "")
-                .selected_text(format!("{:?}", self.selected))
+                .selected_text(tr(ui.ctx(), self.selected.name()))
                 .show_ui(ui, |ui| {
-                    ui.selectable_value(&mut self.selected, Selection::Address, "Address");
-                    ui.selectable_value(&mut self.selected, Selection::Function, "Function");
+                    ui.selectable_value(
+                        &mut self.selected,
+                        Selection::Address,
+                        tr(ui.ctx(), "Address"),
+                    );
+                    ui.selectable_value(
+                        &mut self.selected,
+                        Selection::Function,
+                        tr(ui.ctx(), "Function"),
+                    );
+                    ui.selectable_value(
+                        &mut self.selected,
+                        Selection::FunctionEntry,
+                        tr(ui.ctx(), "Function Entry"),
+                    )
+                    .on_hover_text(tr(
+                        ui.ctx(),
+                        "Breaks before the function prologue set up the stack frame",
+                    ));
                 });
             ui.text_edit_singleline(&mut self.selection_input);
 
@@ -117,7 +279,7 @@ impl DebuggerWindowImpl for BreakpointWindow {
                     }
                 }
             }
-            if ui.button("add").clicked() {
+            if ui.button(tr(ui.ctx(), "add")).clicked() {
                 let bp = match self.selected {
                     Selection::Address => {
                         if self.selection_input.starts_with("0x") {
@@ -137,6 +299,9 @@ impl DebuggerWindowImpl for BreakpointWindow {
                     Selection::Function => {
                         Some(BreakpointPoint::Name(self.selection_input.clone()))
                     }
+                    Selection::FunctionEntry => {
+                        Some(BreakpointPoint::Entry(self.selection_input.clone()))
+                    }
                 };
                 if let Some(bp) = bp {
                     self.warning = None;
@@ -146,7 +311,7 @@ impl DebuggerWindowImpl for BreakpointWindow {
                         |_| (),
                     ));
                 } else {
-                    self.warning = Some("Failed parsing number".to_owned());
+                    self.warning = Some(tr(ui.ctx(), "Failed parsing number").to_owned());
                 }
             }
         });
@@ -154,19 +319,19 @@ impl DebuggerWindowImpl for BreakpointWindow {
             Selection::Address => {
                 if self.selection_input.starts_with("0x") {
                     ui.label(
-                        RichText::new("⚠ parsing address as hex")
+                        RichText::new(tr(ui.ctx(), "⚠ parsing address as hex"))
                             .small()
                             .color(ui.visuals().warn_fg_color),
                     );
                 } else {
                     ui.label(
-                        RichText::new("⚠ parsing address as dec")
+                        RichText::new(tr(ui.ctx(), "⚠ parsing address as dec"))
                             .small()
                             .color(ui.visuals().warn_fg_color),
                     );
                 }
             }
-            Selection::Function => {}
+            Selection::Function | Selection::FunctionEntry => self.function_picker(ui),
         };
         if let Some(warning) = &self.warning {
             ui.label(
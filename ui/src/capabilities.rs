@@ -0,0 +1,61 @@
+use egui::{Context, Id};
+use stackium_shared::{Capabilities, VERSION};
+
+use crate::i18n::{tr, trf};
+
+fn id() -> Id {
+    Id::new("stackium_capabilities")
+}
+
+/// Remembers the capabilities of the backend, `Err` if it doesn't report them
+pub fn set(ctx: &Context, capabilities: Result<Capabilities, String>) {
+    ctx.data_mut(|d| d.insert_temp(id(), capabilities));
+}
+
+fn get(ctx: &Context) -> Option<Result<Capabilities, String>> {
+    ctx.data_mut(|d| d.get_temp(id()))
+}
+
+/// Why the backend can't execute `command`. `None` if it can or if it is unknown, e.g. because
+/// the capabilities weren't received yet.
+pub fn unsupported(ctx: &Context, command: &str) -> Option<String> {
+    match get(ctx)? {
+        Ok(capabilities) if !capabilities.supports(command) => Some(trf(
+            ctx,
+            "Update your backend: stackium {} doesn't support {}",
+            &[&capabilities.version, &command],
+        )),
+        _ => None,
+    }
+}
+
+/// Notice for clients of a backend started with `--read-only` which didn't send the instructor
+/// token
+pub fn read_only_notice(ctx: &Context) -> Option<&'static str> {
+    match get(ctx)? {
+        Ok(capabilities) if capabilities.read_only => Some(tr(
+            ctx,
+            "👁 Read-only demonstration: you can follow the program, only the instructor can control it",
+        )),
+        _ => None,
+    }
+}
+
+/// Warning about a backend built from another version than the UI
+pub fn version_warning(ctx: &Context) -> Option<String> {
+    match get(ctx)? {
+        Ok(capabilities) if capabilities.version != VERSION => Some(trf(
+            ctx,
+            "⚠ The backend uses version {} of the API and the UI version {}, update your backend",
+            &[&capabilities.version, &VERSION],
+        )),
+        Ok(_) => None,
+        Err(_) => Some(
+            tr(
+                ctx,
+                "⚠ The backend doesn't report its capabilities, update your backend",
+            )
+            .to_owned(),
+        ),
+    }
+}
@@ -1,14 +1,188 @@
-use egui::{CollapsingHeader, ComboBox, Response, RichText, ScrollArea, Sense, Slider, Vec2};
+use std::collections::HashMap;
+
+use egui::{text::CCursor, ComboBox, Response, RichText, ScrollArea, Slider, Vec2};
 use poll_promise::Promise;
-use stackium_shared::{Breakpoint, BreakpointPoint, Command, CommandOutput, Location};
+use stackium_shared::{
+    BranchExplanation, Breakpoint, BreakpointPoint, Command, CommandOutput, ExecutionTrace,
+    FunctionDisassembly, LineHits, Location, SourceFile, SourceOrigin, Variable, VariableScope,
+};
 use url::Url;
 
 use crate::{
-    command::dispatch_command_and_then,
+    address::AddressResolver,
+    command::{dispatch_command_and_then, Cancellable},
     debugger_window::DebuggerWindowImpl,
-    syntax_highlighting::{code_view_ui, CodeTheme},
+    i18n::{tr, trf},
+    scope_window::{flatten_scopes, format_value},
+    selection::{self, Message},
+    syntax_highlighting::{code_line_ui, code_view_ui, CodeTheme},
 };
 
+/// Compact execution count for the gutter, e.g. `×12` or `×45k`
+fn format_count(count: u64) -> String {
+    match count {
+        0..=9999 => format!("×{}", count),
+        10_000..=9_999_999 => format!("×{}k", count / 1000),
+        _ => format!("×{}M", count / 1_000_000),
+    }
+}
+
+/// Shortest path suffix of `file` which doesn't collide with any other file,
+/// e.g. `src/main.c` if there is also a `test/main.c`
+fn short_file_name(file: &str, files: &[String]) -> String {
+    let components = file.split('/').collect::<Vec<_>>();
+    for len in 1..components.len() {
+        let suffix = components[components.len() - len..].join("/");
+        if files
+            .iter()
+            .filter(|f| *f != file)
+            .all(|f| !f.ends_with(&format!("/{}", suffix)) && *f != suffix)
+        {
+            return suffix;
+        }
+    }
+    file.to_owned()
+}
+
+/// Whether `rest`, the text following a variable name, assigns to it, e.g. `= 4` or `++`
+fn is_assignment(rest: &str) -> bool {
+    let rest = rest.trim_start();
+    [
+        "++", "--", "<<=", ">>=", "+=", "-=", "*=", "/=", "%=", "&=", "|=", "^=",
+    ]
+    .iter()
+    .any(|op| rest.starts_with(op))
+        || (rest.starts_with('=') && !rest.starts_with("=="))
+}
+
+/// Names of the variables assigned on a line of C code. Members like `a.b = 1` are skipped.
+fn assigned_variables<'a>(line: &str, names: &[&'a str]) -> Vec<&'a str> {
+    let code = line.split("//").next().unwrap_or_default();
+    let mut assigned = vec![];
+    let mut start = None;
+    for (i, c) in code.char_indices().chain([(code.len(), ' ')]) {
+        if c.is_alphanumeric() || c == '_' {
+            start.get_or_insert(i);
+            continue;
+        }
+        let Some(from) = start.take() else {
+            continue;
+        };
+        let before = code[..from].trim_end();
+        let member = before.ends_with('.') || before.ends_with("->");
+        let incremented = before.ends_with("++") || before.ends_with("--");
+        if let Some(name) = names.iter().find(|n| **n == &code[from..i]) {
+            if !member && (incremented || is_assignment(&code[i..])) && !assigned.contains(name) {
+                assigned.push(*name);
+            }
+        }
+    }
+    assigned
+}
+
+/// The identifier around the character at `index`, e.g. `count` in `count += 1`
+fn identifier_at(line: &str, index: usize) -> Option<&str> {
+    let is_identifier = |c: char| c.is_alphanumeric() || c == '_';
+    let chars = line.char_indices().collect::<Vec<_>>();
+    let &(at, c) = chars.get(index)?;
+    if !is_identifier(c) {
+        return None;
+    }
+    let start = line[..at]
+        .char_indices()
+        .rev()
+        .take_while(|(_, c)| is_identifier(*c))
+        .last()
+        .map_or(at, |(i, _)| i);
+    let end = line[at..]
+        .char_indices()
+        .find(|(_, c)| !is_identifier(*c))
+        .map_or(line.len(), |(i, _)| at + i);
+    let identifier = &line[start..end];
+    match identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        true => None,
+        false => Some(identifier),
+    }
+}
+
+/// Character range of the statement starting at the 1-based `column` of the line table, ending
+/// before the next `;` or `,` or the parenthesis closing the statement, e.g. `i < n` in
+/// `for (i = 0; i < n; i++)`
+fn statement_range(line: &str, column: u64) -> Option<(usize, usize)> {
+    let chars = line.chars().collect::<Vec<_>>();
+    let start = (column as usize).checked_sub(1)?;
+    if start >= chars.len() {
+        return None;
+    }
+    let mut depth = 0;
+    let mut end = chars.len();
+    for (i, c) in chars.iter().enumerate().skip(start) {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' if depth == 0 => {
+                end = i;
+                break;
+            }
+            ')' | ']' => depth -= 1,
+            ';' | ',' | '{' if depth == 0 => {
+                end = i;
+                break;
+            }
+            _ => {}
+        }
+    }
+    while end > start && chars[end - 1].is_whitespace() {
+        end -= 1;
+    }
+    (end > start).then_some((start, end))
+}
+
+/// The identifier under the mouse pointer in a line shown with [`code_line_ui`]
+fn hovered_identifier(output: &egui::text_edit::TextEditOutput, line: &str) -> Option<String> {
+    let pos = output.response.hover_pos()?;
+    let cursor = output.galley.cursor_from_pos(pos - output.galley_pos);
+    identifier_at(line, cursor.ccursor.index).map(|i| i.to_owned())
+}
+
+/// Values of the variables assigned on the lines of the current function which were executed
+/// before the current line, shown at the end of those lines
+fn inline_values(code: &str, scopes: &[VariableScope], current: u64) -> HashMap<u64, String> {
+    let Some(first) = scopes.first().and_then(|s| s.line) else {
+        return HashMap::new();
+    };
+    let variables = flatten_scopes(scopes.to_vec())
+        .into_iter()
+        .filter(|v| v.in_scope && v.name.is_some())
+        .collect::<Vec<Variable>>();
+    let names = variables
+        .iter()
+        .filter_map(|v| v.name.as_deref())
+        .collect::<Vec<_>>();
+    let mut values = HashMap::new();
+    for (num, line) in code
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i as u64 + 1, l))
+        .filter(|(num, _)| first <= *num && *num < current)
+    {
+        let assigned = assigned_variables(line, &names)
+            .into_iter()
+            .filter_map(|name| variables.iter().find(|v| v.name.as_deref() == Some(name)))
+            .map(|v| {
+                format!(
+                    "{} = {}",
+                    v.name.as_deref().unwrap_or_default(),
+                    format_value(v)
+                )
+            })
+            .collect::<Vec<_>>();
+        if !assigned.is_empty() {
+            values.insert(num, assigned.join(", "));
+        }
+    }
+    values
+}
+
 #[derive(PartialEq)]
 enum Selected {
     Code,
@@ -20,19 +194,54 @@ pub struct CodeWindow {
     files: Promise<Result<Vec<String>, String>>,
     selected_file: String,
     displaying_file: String,
-    file: Promise<Result<String, String>>,
+    file: Promise<Result<SourceFile, String>>,
     breakpoints: Promise<Result<Vec<Breakpoint>, String>>,
-    create_breakpoint_request: Option<Promise<Result<(), String>>>,
+    /// Resolves to the note of a breakpoint that was moved to another line
+    create_breakpoint_request: Option<Promise<Result<Option<String>, String>>>,
+    /// Where the last breakpoint landed if its line had no code
+    breakpoint_moved: Option<String>,
     location: Promise<Result<Location, String>>,
-    disassembly: Promise<Result<String, String>>,
+    /// Stay on the selected file instead of following the current location into other files
+    pinned: bool,
+    /// File and line of the location the code view last switched to
+    followed_location: Option<(String, u64)>,
+    /// Scroll the current line into view once its file is shown
+    scroll_to_location: bool,
+    /// Variables of the current function, their values are shown next to the lines assigning them
+    scopes: Promise<Result<Vec<VariableScope>, String>>,
+    show_inline_values: bool,
+    /// Variable the context menu of the code view was opened on
+    context_variable: Option<String>,
+    /// Declaration line of the variable selected in another window
+    selected_line: Option<u64>,
+    /// Scroll the selected line into view once
+    scroll_to_selection: bool,
+    /// Line shown with `Message::Show`, e.g. the definition of a function
+    shown_line: Option<(String, u64)>,
+    disassembly: Cancellable<String>,
+    /// Only disassemble the current function, interleaved with its source
+    function_only: bool,
+    /// Scroll the disassembly to the instruction at the program counter whenever it changes
+    follow_pc: bool,
+    /// Program counter the disassembly was last scrolled to
+    followed_pc: Option<u64>,
+    function_disassembly: Option<Cancellable<FunctionDisassembly>>,
     selected_window: Selected,
     pc: Promise<Result<u64, String>>,
+    /// Explanation of the conditional instruction at the program counter
+    branch: Promise<Result<BranchExplanation, String>>,
+    /// Lines executed while tracing, used to shade lines by execution count
+    trace: Promise<Result<ExecutionTrace, String>>,
+    /// How often the loop bodies were executed in the current run, shown in the gutter
+    line_hits: Promise<Result<Vec<LineHits>, String>>,
     code_size: f32,
+    addresses: AddressResolver,
 }
 
 impl CodeWindow {
     pub fn new(backend_url: Url) -> Self {
         let mut s = Self {
+            addresses: AddressResolver::new(backend_url.clone()),
             backend_url: backend_url.clone(),
             files: Promise::from_ready(Err(String::new())),
             selected_file: String::new(),
@@ -40,10 +249,27 @@ impl CodeWindow {
             displaying_file: String::new(),
             breakpoints: Promise::from_ready(Err(String::new())),
             create_breakpoint_request: None,
+            breakpoint_moved: None,
             location: Promise::from_ready(Err(String::new())),
-            disassembly: dispatch!(backend_url, Command::Disassemble, File),
+            pinned: false,
+            followed_location: None,
+            scroll_to_location: false,
+            scopes: Promise::from_ready(Ok(vec![])),
+            show_inline_values: true,
+            context_variable: None,
+            selected_line: None,
+            scroll_to_selection: false,
+            shown_line: None,
+            disassembly: dispatch_cancellable!(backend_url, Command::Disassemble, File),
+            function_only: true,
+            follow_pc: true,
+            followed_pc: None,
+            function_disassembly: None,
             selected_window: Selected::Code,
             pc: Promise::from_ready(Ok(0)),
+            branch: Promise::from_ready(Err(String::new())),
+            trace: Promise::from_ready(Err(String::new())),
+            line_hits: Promise::from_ready(Err(String::new())),
             code_size: 16.,
         };
         s.dirty();
@@ -74,18 +300,233 @@ impl CodeWindow {
         }
         response
     }
-    fn render_disassembly(&mut self, ui: &mut egui::Ui, disassembly: String) -> bool {
-        let mut dirty = false;
+    /// Shows the program counter and the explanation of the current instruction, returns whether
+    /// the current instruction jumps
+    fn render_pc_header(&mut self, ui: &mut egui::Ui) -> Option<bool> {
         ui.horizontal(|ui| {
-            ui.label("Program Counter: ");
+            ui.label(tr(ui.ctx(), "Program Counter: "));
             match self.pc.ready() {
                 Some(pc) => match pc {
-                    Ok(pc) => ui.label(format!("{:#x?}", pc)),
+                    Ok(pc) => ui.label(RichText::new(self.addresses.format(*pc)).monospace()),
                     Err(e) => ui.label(e),
                 },
                 None => ui.spinner(),
             }
         });
+        match self.branch.ready() {
+            Some(Ok(branch)) => {
+                if branch.condition.is_some() {
+                    ui.horizontal_wrapped(|ui| {
+                        for flag in branch.tested_flags.iter() {
+                            ui.label(
+                                RichText::new(format!("{} = {}", flag.name, flag.set as u8))
+                                    .monospace()
+                                    .strong(),
+                            )
+                            .on_hover_text(&flag.description);
+                        }
+                    });
+                }
+                ui.label(RichText::new(&branch.explanation).small());
+                branch.taken
+            }
+            _ => None,
+        }
+    }
+    fn toggle_breakpoint(&mut self, address: u64, has_breakpoint: bool) {
+        self.create_breakpoint_request = Some(dispatch_command_and_then(
+            self.backend_url.clone(),
+            if has_breakpoint {
+                Command::DeleteBreakpoint(address)
+            } else {
+                Command::SetBreakpoint(BreakpointPoint::Address(address))
+            },
+            |_| None,
+        ));
+    }
+    /// Scrolls to the current instruction once after the program counter changed
+    fn follow_pc(&mut self, ui: &egui::Ui, rect: egui::Rect, pc: u64) {
+        if self.follow_pc && self.followed_pc != Some(pc) {
+            ui.scroll_to_rect(rect, Some(egui::Align::Center));
+            self.followed_pc = Some(pc);
+        }
+    }
+    /// Disassembly of the function containing the program counter
+    fn render_current_function(&mut self, ui: &mut egui::Ui) -> bool {
+        if self.function_disassembly.is_none() {
+            if let Some(Ok(pc)) = self.pc.ready() {
+                self.function_disassembly = Some(dispatch_cancellable!(
+                    self.backend_url.clone(),
+                    Command::DisassembleFunction(format!("{:#x}", pc)),
+                    FunctionDisassembly
+                ));
+            }
+        }
+        match self.function_disassembly.as_ref().and_then(|d| d.ready()) {
+            Some(Ok(disassembly)) => {
+                let disassembly = disassembly.clone();
+                self.render_function_disassembly(ui, &disassembly)
+            }
+            Some(Err(err)) => {
+                ui.label(err);
+                false
+            }
+            None => {
+                ui.spinner();
+                false
+            }
+        }
+    }
+
+    fn render_function_disassembly(
+        &mut self,
+        ui: &mut egui::Ui,
+        disassembly: &FunctionDisassembly,
+    ) -> bool {
+        let taken = self.render_pc_header(ui);
+        let mut dirty = false;
+        let pc = match self.pc.ready() {
+            Some(Ok(pc)) => Some(*pc),
+            _ => None,
+        };
+        let breakpoints = match self.breakpoints.ready() {
+            Some(Ok(breakpoints)) => breakpoints.iter().map(|b| b.address).collect(),
+            _ => vec![],
+        };
+        ui.label(
+            RichText::new(format!(
+                "{} ({:#x} - {:#x})",
+                disassembly.name.as_deref().unwrap_or("??"),
+                disassembly.low_pc,
+                disassembly.high_pc
+            ))
+            .strong(),
+        );
+        let theme = CodeTheme::from_style(ui.style(), self.code_size);
+        ScrollArea::both().auto_shrink([false; 2]).show(ui, |ui| {
+            for block in disassembly.blocks.iter() {
+                ui.horizontal(|ui| {
+                    let line = match &block.location {
+                        Some(location) => location.line.to_string(),
+                        None => "?".to_owned(),
+                    };
+                    ui.label(RichText::new(format!("{:>4}", line)).monospace().weak());
+                    code_view_ui(
+                        ui,
+                        block.source.as_deref().unwrap_or_default().trim(),
+                        &theme,
+                        "c",
+                        self.code_size,
+                    );
+                });
+                for instruction in block.instructions.iter() {
+                    ui.horizontal(|ui| {
+                        ui.add_space(self.code_size * 2.);
+                        let has_breakpoint = breakpoints.contains(&instruction.address);
+                        if Self::render_breakpoint(ui, has_breakpoint).clicked() {
+                            self.toggle_breakpoint(instruction.address, has_breakpoint);
+                            dirty = true;
+                        }
+                        let is_current = pc == Some(instruction.address);
+                        let address =
+                            RichText::new(format!("{:#x}", instruction.address)).monospace();
+                        if is_current {
+                            let color = if ui.style().visuals.dark_mode {
+                                egui::Color32::DARK_GREEN
+                            } else {
+                                egui::Color32::LIGHT_GREEN
+                            };
+                            let rect = ui.label(address.background_color(color).strong()).rect;
+                            self.follow_pc(ui, rect, instruction.address);
+                        } else {
+                            ui.label(address.weak());
+                        }
+                        code_view_ui(ui, &instruction.text, &theme, "asm", self.code_size)
+                            .on_hover_text(&instruction.bytes);
+                        if is_current {
+                            match taken {
+                                Some(true) => {
+                                    ui.label(RichText::new(tr(ui.ctx(), "⤴ taken")).strong())
+                                }
+                                Some(false) => {
+                                    ui.label(RichText::new(tr(ui.ctx(), "⤵ not taken")).strong())
+                                }
+                                None => ui.label(""),
+                            };
+                        }
+                    });
+                }
+            }
+        });
+        dirty
+    }
+    /// Switches to the file of the current location once after it changed
+    fn follow_location(&mut self, files: &[String]) {
+        let Some(Ok(location)) = self.location.ready() else {
+            return;
+        };
+        let followed = (location.file.clone(), location.line);
+        if self.pinned || self.followed_location.as_ref() == Some(&followed) {
+            return;
+        }
+        if files.contains(&location.file) {
+            self.selected_file = location.file.clone();
+        }
+        self.followed_location = Some(followed);
+        self.scroll_to_location = true;
+    }
+    /// Shows the value of the variable under the mouse pointer, right clicking it offers to show
+    /// it in the memory window
+    fn render_variable_hover(
+        &mut self,
+        response: &Response,
+        identifier: Option<String>,
+        variables: &[Variable],
+    ) {
+        let find = |name: &str| variables.iter().find(|v| v.name.as_deref() == Some(name));
+        if let Some(variable) = identifier.as_deref().and_then(find) {
+            response.clone().on_hover_ui_at_pointer(|ui| {
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(variable.name.as_deref().unwrap_or("??")).strong());
+                    if let Some(types) = &variable.type_name {
+                        ui.label(RichText::new(types.to_string()).monospace().weak());
+                    }
+                });
+                ui.label(RichText::new(format_value(variable)).monospace());
+                if let Some(addr) = variable.addr {
+                    ui.label(
+                        RichText::new(format!("@ {}", self.addresses.format(addr))).monospace(),
+                    );
+                }
+            });
+            if response.secondary_clicked() {
+                self.context_variable = variable.name.clone();
+            }
+        }
+        let Some(name) = self.context_variable.clone() else {
+            return;
+        };
+        let Some(variable) = find(&name) else {
+            return;
+        };
+        response.context_menu(|ui| match variable.addr {
+            Some(_) => {
+                if ui
+                    .button(trf(ui.ctx(), "Show {} in the Memory window", &[&name]))
+                    .clicked()
+                {
+                    selection::send(ui.ctx(), Message::Locate(format!("&{}", name)));
+                    ui.close_menu();
+                }
+            }
+            None => {
+                ui.label(trf(ui.ctx(), "{} is not stored in memory", &[&name]));
+            }
+        });
+    }
+    fn render_disassembly(&mut self, ui: &mut egui::Ui, disassembly: String) -> bool {
+        let mut dirty = false;
+        let taken = self.render_pc_header(ui);
         ScrollArea::both()
             .auto_shrink([false; 2])
             // .max_height(400.)
@@ -114,14 +555,14 @@ impl CodeWindow {
                                                         .any(|b| b.address == current_address);
                                                     if  has_breakpoint {
                                                         if Self::render_breakpoint(ui, true).clicked() {
-                                                            self.create_breakpoint_request = Some(dispatch_command_and_then(self.backend_url.clone(), Command::DeleteBreakpoint(current_address), |_| {}));
+                                                            self.create_breakpoint_request = Some(dispatch_command_and_then(self.backend_url.clone(), Command::DeleteBreakpoint(current_address), |_| None));
                                                             dirty = true;
                                                         }
                                                     } else {
                                                         if Self::render_breakpoint(ui, false)
                                                             .clicked()
                                                         {
-                                                            self.create_breakpoint_request = Some(dispatch_command_and_then(self.backend_url.clone(), Command::SetBreakpoint(BreakpointPoint::Address(current_address)), |_| {}));
+                                                            self.create_breakpoint_request = Some(dispatch_command_and_then(self.backend_url.clone(), Command::SetBreakpoint(BreakpointPoint::Address(current_address)), |_| None));
                                                             dirty = true;
                                                         }
                                                     }
@@ -137,6 +578,7 @@ impl CodeWindow {
                                                 egui::Vec2::new(7. * line.len() as f32, 15.),
                                                 egui::Sense::hover(),
                                             );
+                                            self.follow_pc(ui, rect, current_address);
                                             ui.painter().rect_filled(
                                                 rect,
                                                 2.,
@@ -158,6 +600,11 @@ impl CodeWindow {
                                                 )
                                                 .response
                                             });
+                                            match taken {
+                                                Some(true) => ui.label(RichText::new(tr(ui.ctx(), "⤴ taken")).strong()),
+                                                Some(false) => ui.label(RichText::new(tr(ui.ctx(), "⤵ not taken")).strong()),
+                                                None => ui.label(""),
+                                            };
                                         } else {
                                             code_view_ui(
                                                 ui,
@@ -186,11 +633,46 @@ impl CodeWindow {
         ui.add_space(2. * ui.spacing().item_spacing.y);
         let location = match self.location.ready() {
             Some(l) => match l {
-                Ok(l) => Some(l),
+                Ok(l) => Some(l.clone()),
                 Err(_) => None,
             },
             None => None,
         };
+        let hit_counts = match self.trace.ready() {
+            Some(Ok(trace)) => trace
+                .hit_counts
+                .iter()
+                .filter(|h| h.file == self.displaying_file)
+                .map(|h| (h.line, h.count))
+                .collect::<HashMap<_, _>>(),
+            _ => HashMap::new(),
+        };
+        let max_hits = hit_counts.values().copied().max().unwrap_or(0);
+        let inline_values = match (&location, self.scopes.ready()) {
+            (Some(location), Some(Ok(scopes)))
+                if self.show_inline_values && location.file == self.displaying_file =>
+            {
+                inline_values(code, scopes, location.line)
+            }
+            _ => HashMap::new(),
+        };
+        let loop_counts = match self.line_hits.ready() {
+            Some(Ok(line_hits)) => line_hits
+                .iter()
+                .filter(|h| h.file == self.displaying_file)
+                .map(|h| (h.line, h.count))
+                .collect::<HashMap<_, _>>(),
+            _ => HashMap::new(),
+        };
+        let variables = match (&location, self.scopes.ready()) {
+            (Some(location), Some(Ok(scopes))) if location.file == self.displaying_file => {
+                flatten_scopes(scopes.clone())
+                    .into_iter()
+                    .filter(|v| v.in_scope)
+                    .collect()
+            }
+            _ => vec![],
+        };
         ScrollArea::both()
             .auto_shrink([false; 2])
             // .max_height(400.)
@@ -224,7 +706,7 @@ impl CodeWindow {
                                                                 .unwrap()
                                                                 .address,
                                                         ),
-                                                        |_| {},
+                                                        |_| None,
                                                     ));
                                             } else {
                                                 self.create_breakpoint_request =
@@ -237,7 +719,12 @@ impl CodeWindow {
                                                                 column: 0,
                                                             }),
                                                         ),
-                                                        |_| {},
+                                                        |out| match out {
+                                                            CommandOutput::PlacedBreakpoint(
+                                                                placed,
+                                                            ) => placed.moved,
+                                                            _ => None,
+                                                        },
                                                     ));
                                             }
                                         };
@@ -252,12 +739,44 @@ impl CodeWindow {
                                     Self::render_breakpoint(ui, false);
                                 }
                             };
-                            ui.label(num.to_string());
+                            let hits = hit_counts.get(&(num as u64)).copied();
+                            let number = ui.label(num.to_string());
+                            if let Some(hits) = hits {
+                                number.on_hover_text(trf(ui.ctx(), "executed {} times", &[&hits]));
+                            }
+                            // a column of counters next to the line numbers of the loop bodies
+                            if !loop_counts.is_empty() {
+                                let count = loop_counts.get(&(num as u64)).copied();
+                                let counter = ui.add_sized(
+                                    Vec2::new(self.code_size * 2.5, self.code_size),
+                                    egui::Label::new(
+                                        RichText::new(count.map(format_count).unwrap_or_default())
+                                            .small()
+                                            .weak(),
+                                    ),
+                                );
+                                if let Some(count) = count {
+                                    counter.on_hover_text(trf(
+                                        ui.ctx(),
+                                        "loop body executed {} times in this run",
+                                        &[&count],
+                                    ));
+                                }
+                            }
 
-                            if match location {
-                                Some(l) => l.line == num as u64,
+                            let is_current = match &location {
+                                Some(l) => l.line == num as u64 && l.file == self.displaying_file,
                                 None => false,
-                            } {
+                            };
+                            // declarations are only known for the function at the current location
+                            let is_selected = (self.selected_line == Some(num as u64)
+                                && location
+                                    .as_ref()
+                                    .map_or(false, |l| l.file == self.displaying_file))
+                                || self.shown_line.as_ref().map_or(false, |(file, line)| {
+                                    *line == num as u64 && *file == self.displaying_file
+                                });
+                            if is_current || is_selected || hits.is_some() {
                                 let (rect, _) = ui.allocate_exact_size(
                                     egui::Vec2::new(
                                         self.code_size * 0.8 * line.len() as f32,
@@ -265,36 +784,108 @@ impl CodeWindow {
                                     ),
                                     egui::Sense::hover(),
                                 );
-                                if ui.style().visuals.dark_mode {
-                                    ui.painter()
-                                        .rect_filled(rect, 2., egui::Color32::DARK_GREEN);
-                                } else {
-                                    ui.painter()
-                                        .rect_filled(rect, 2., egui::Color32::LIGHT_GREEN);
+                                if is_selected && self.scroll_to_selection {
+                                    ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                                    self.scroll_to_selection = false;
+                                }
+                                if is_current {
+                                    if self.scroll_to_location {
+                                        ui.scroll_to_rect(rect, Some(egui::Align::Center));
+                                        self.scroll_to_location = false;
+                                    }
+                                    if ui.style().visuals.dark_mode {
+                                        ui.painter().rect_filled(
+                                            rect,
+                                            2.,
+                                            egui::Color32::DARK_GREEN,
+                                        );
+                                    } else {
+                                        ui.painter().rect_filled(
+                                            rect,
+                                            2.,
+                                            egui::Color32::LIGHT_GREEN,
+                                        );
+                                    }
+                                } else if is_selected {
+                                    ui.painter().rect_filled(
+                                        rect,
+                                        2.,
+                                        ui.visuals().selection.bg_fill,
+                                    );
+                                } else if let Some(hits) = hits {
+                                    // more intense the more often the line was executed
+                                    let intensity = 40 + (160 * hits / max_hits.max(1)) as u8;
+                                    ui.painter().rect_filled(
+                                        rect,
+                                        2.,
+                                        egui::Color32::from_rgba_unmultiplied(
+                                            255, 140, 0, intensity,
+                                        ),
+                                    );
                                 }
-                                ui.put(rect, |ui: &mut egui::Ui| {
+                                let mut identifier = None;
+                                let response = ui.put(rect, |ui: &mut egui::Ui| {
                                     ui.with_layout(
                                         egui::Layout::left_to_right(egui::Align::Min),
                                         |ui| {
-                                            code_view_ui(
+                                            let output = code_line_ui(
                                                 ui,
                                                 line,
                                                 &CodeTheme::from_style(ui.style(), self.code_size),
                                                 "c",
                                                 self.code_size,
-                                            )
+                                            );
+                                            identifier = hovered_identifier(&output, line);
+                                            // outline the statement being executed
+                                            let range = location
+                                                .as_ref()
+                                                .filter(|_| is_current)
+                                                .and_then(|l| statement_range(line, l.column));
+                                            if let Some((start, end)) = range {
+                                                let from = output
+                                                    .galley
+                                                    .pos_from_ccursor(CCursor::new(start));
+                                                let to = output
+                                                    .galley
+                                                    .pos_from_ccursor(CCursor::new(end));
+                                                ui.painter().rect_stroke(
+                                                    egui::Rect::from_min_max(from.min, to.max)
+                                                        .translate(output.galley_pos.to_vec2()),
+                                                    2.,
+                                                    egui::Stroke::new(
+                                                        1.5,
+                                                        ui.visuals().strong_text_color(),
+                                                    ),
+                                                );
+                                            }
+                                            output.response
                                         },
                                     )
-                                    .response
+                                    .inner
                                 });
+                                self.render_variable_hover(&response, identifier, &variables);
                             } else {
-                                code_view_ui(
+                                let output = code_line_ui(
                                     ui,
                                     line,
                                     &CodeTheme::from_style(ui.style(), self.code_size),
                                     "c",
                                     self.code_size,
                                 );
+                                let identifier = hovered_identifier(&output, line);
+                                self.render_variable_hover(
+                                    &output.response,
+                                    identifier,
+                                    &variables,
+                                );
+                            }
+                            if let Some(values) = inline_values.get(&(num as u64)) {
+                                ui.label(
+                                    RichText::new(format!("  {}", values))
+                                        .monospace()
+                                        .italics()
+                                        .weak(),
+                                );
                             }
                         });
                     });
@@ -305,7 +896,8 @@ impl CodeWindow {
         match &self.create_breakpoint_request {
             Some(req) => match req.ready() {
                 Some(req) => match req {
-                    Ok(_) => {
+                    Ok(moved) => {
+                        self.breakpoint_moved = moved.clone();
                         dirty = true;
                     }
                     Err(_) => {}
@@ -325,20 +917,35 @@ impl CodeWindow {
 }
 
 impl DebuggerWindowImpl for CodeWindow {
+    fn receive(&mut self, message: &Message) {
+        match message {
+            Message::Select(selection) => {
+                self.selected_line = selection.as_ref().and_then(|s| s.line);
+                self.scroll_to_selection = true;
+            }
+            Message::Show(location) => {
+                self.selected_file = location.file.clone();
+                self.shown_line = Some((location.file.clone(), location.line));
+                self.scroll_to_selection = true;
+            }
+            _ => {}
+        }
+    }
     fn update(&mut self, _ctx: &egui::Context, _frame: &mut eframe::Frame) {
         if self.displaying_file != self.selected_file {
             self.displaying_file = self.selected_file.clone();
             self.file = dispatch_command_and_then(
                 self.backend_url.clone(),
-                Command::GetFile(self.selected_file.clone()),
+                Command::GetSource(self.selected_file.clone()),
                 |output| match output {
-                    CommandOutput::File(file) => file,
+                    CommandOutput::Source(file) => file,
                     _ => unreachable!(),
                 },
             );
         }
     }
     fn dirty(&mut self) {
+        self.addresses.clear();
         self.files = dispatch_command_and_then(
             self.backend_url.clone(),
             stackium_shared::Command::DebugMeta,
@@ -355,6 +962,11 @@ impl DebuggerWindowImpl for CodeWindow {
             Breakpoints
         );
         self.location = dispatch!(self.backend_url.clone(), Command::Location, Location);
+        self.scopes = dispatch!(
+            self.backend_url.clone(),
+            Command::GetVariableScopes,
+            VariableScopes
+        );
         self.pc = dispatch_command_and_then(
             self.backend_url.clone(),
             Command::ProgramCounter,
@@ -363,36 +975,89 @@ impl DebuggerWindowImpl for CodeWindow {
                 _ => unreachable!(),
             },
         );
+        self.function_disassembly = None;
+        self.trace = dispatch!(
+            self.backend_url.clone(),
+            Command::GetExecutionTrace,
+            ExecutionTrace
+        );
+        self.line_hits = dispatch!(
+            self.backend_url.clone(),
+            Command::GetLineHitCounts,
+            LineHitCounts
+        );
+        self.branch = dispatch!(
+            self.backend_url.clone(),
+            Command::ExplainBranch,
+            BranchExplanation
+        );
     }
     fn ui(&mut self, ui: &mut egui::Ui) -> bool {
         ui.horizontal(|ui| {
-            ui.selectable_value(&mut self.selected_window, Selected::Code, "Code");
+            ui.selectable_value(
+                &mut self.selected_window,
+                Selected::Code,
+                tr(ui.ctx(), "Code"),
+            );
             ui.selectable_value(
                 &mut self.selected_window,
                 Selected::Disassemble,
-                "Disassemble",
+                tr(ui.ctx(), "Disassemble"),
             );
         });
-        ui.add(Slider::new(&mut self.code_size, 8.0..=32.0).text("Code size"));
+        ui.add(Slider::new(&mut self.code_size, 8.0..=32.0).text(tr(ui.ctx(), "Code size")));
         let mut dirty = false;
         if self.selected_window == Selected::Code {
             match self.files.ready() {
                 Some(files) => match files {
                     Ok(files) => {
+                        let files = files.clone();
+                        self.follow_location(&files);
                         if files.len() > 0 && self.selected_file.len() == 0 {
                             self.selected_file = files.first().unwrap().clone();
                         }
-                        ComboBox::from_label("File")
-                            .selected_text(self.selected_file.clone())
-                            .show_ui(ui, |ui| {
-                                for file in files {
-                                    ui.selectable_value(
-                                        &mut self.selected_file,
-                                        file.clone(),
-                                        file,
-                                    );
-                                }
-                            });
+                        ui.horizontal(|ui| {
+                            ComboBox::from_label(tr(ui.ctx(), "File"))
+                                .selected_text(short_file_name(&self.selected_file, &files))
+                                .show_ui(ui, |ui| {
+                                    for file in files.iter() {
+                                        ui.selectable_value(
+                                            &mut self.selected_file,
+                                            file.clone(),
+                                            short_file_name(file, &files),
+                                        )
+                                        .on_hover_text(file);
+                                    }
+                                });
+                            if ui
+                                .toggle_value(&mut self.pinned, tr(ui.ctx(), "📌 Pin"))
+                                .on_hover_text(tr(
+                                    ui.ctx(),
+                                    "Stay on this file instead of following the current line",
+                                ))
+                                .changed()
+                                && !self.pinned
+                            {
+                                // jump back to the current line
+                                self.followed_location = None;
+                            }
+                            ui.checkbox(
+                                &mut self.show_inline_values,
+                                tr(ui.ctx(), "Inline values"),
+                            )
+                            .on_hover_text(tr(
+                                ui.ctx(),
+                                "Show the values of the variables assigned on the lines \
+                                         executed in the current function",
+                            ));
+                            if let Some(moved) = &self.breakpoint_moved {
+                                ui.label(
+                                    RichText::new(format!("⚠ {}", moved))
+                                        .small()
+                                        .color(ui.visuals().warn_fg_color),
+                                );
+                            }
+                        });
                     }
                     Err(err) => {
                         ui.label(err);
@@ -403,11 +1068,39 @@ impl DebuggerWindowImpl for CodeWindow {
                 }
             }
             match self.file.ready() {
-                Some(code) => match code {
-                    Ok(code) => {
+                Some(file) => match file {
+                    Ok(SourceFile {
+                        content: Some(code),
+                        origin,
+                        ..
+                    }) => {
                         let code = code.clone();
+                        match origin {
+                            SourceOrigin::Mapped(path) => {
+                                ui.small(trf(ui.ctx(), "Read from {}", &[path]));
+                            }
+                            SourceOrigin::Embedded => {
+                                ui.small(tr(
+                                    ui.ctx(),
+                                    "The file is missing, showing the source embedded in the debug information",
+                                ));
+                            }
+                            SourceOrigin::Local | SourceOrigin::Missing => {}
+                        }
                         dirty = self.render_code(ui, &code);
                     }
+                    Ok(SourceFile { path, .. }) => {
+                        let path = path.clone();
+                        ui.colored_label(
+                            ui.visuals().warn_fg_color,
+                            trf(
+                                ui.ctx(),
+                                "⚠ The source of {} isn't available, showing the disassembly",
+                                &[&path],
+                            ),
+                        );
+                        dirty = self.render_current_function(ui);
+                    }
                     Err(err) => {
                         ui.label(err);
                     }
@@ -417,18 +1110,35 @@ impl DebuggerWindowImpl for CodeWindow {
                 }
             }
         } else {
-            match self.disassembly.ready() {
-                Some(disassembly) => match disassembly {
-                    Ok(disassembly) => {
-                        let disassembly = disassembly.clone();
-                        dirty = self.render_disassembly(ui, disassembly);
-                    }
-                    Err(err) => {
-                        ui.label(err);
+            ui.horizontal(|ui| {
+                // the other view has to be scrolled to the program counter again
+                if ui
+                    .checkbox(
+                        &mut self.function_only,
+                        tr(ui.ctx(), "Current function only"),
+                    )
+                    .changed()
+                {
+                    self.followed_pc = None;
+                }
+                ui.checkbox(&mut self.follow_pc, tr(ui.ctx(), "Follow program counter"));
+            });
+            if self.function_only {
+                dirty = self.render_current_function(ui);
+            } else {
+                match self.disassembly.ready() {
+                    Some(disassembly) => match disassembly {
+                        Ok(disassembly) => {
+                            let disassembly = disassembly.clone();
+                            dirty = self.render_disassembly(ui, disassembly);
+                        }
+                        Err(err) => {
+                            ui.label(err);
+                        }
+                    },
+                    None => {
+                        ui.spinner();
                     }
-                },
-                None => {
-                    ui.spinner();
                 }
             }
         }
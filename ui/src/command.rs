@@ -1,6 +1,11 @@
-use ehttp::{fetch, Request};
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicU64, Ordering},
+};
+
 use poll_promise::Promise;
-use stackium_shared::{Command, CommandOutput};
+use stackium_client::Client;
+use stackium_shared::{Capabilities, Command, CommandOutput};
 use url::Url;
 
 macro_rules! dispatch {
@@ -14,31 +19,100 @@ macro_rules! dispatch {
 
 pub(crate) use dispatch;
 
+/// Like [`dispatch`], for commands which can take long, see [`Cancellable`]
+macro_rules! dispatch_cancellable {
+    ($url:expr, $command:expr, $out:ident) => {
+        crate::command::dispatch_cancellable_and_then($url, $command, |out| match out {
+            CommandOutput::$out(a) => a,
+            _ => unreachable!(),
+        })
+    };
+}
+
 pub fn dispatch_command_and_then<T: Send>(
     backend_url: Url,
     command: Command,
     and_then: impl FnOnce(CommandOutput) -> T + Send + 'static,
 ) -> Promise<Result<T, String>> {
     let (sender, promise) = Promise::new();
-    let request = Request::post(
-        backend_url.join("/command").unwrap(),
-        serde_json::to_vec(&command).unwrap(),
-    );
-    fetch(request, move |response| match response {
-        Ok(response) => {
-            let body = response.text();
-            match body {
-                Some(body) => match serde_json::from_str(&body) {
-                    Ok(output) => {
-                        let output = and_then(output);
-                        sender.send(Ok(output));
-                    }
-                    Err(_) => sender.send(Err(body.to_owned())),
-                },
-                None => sender.send(Err("Failed to parse response".to_string())),
-            }
+    Client::new(backend_url).command_then(&command, move |output| {
+        sender.send(output.map(and_then).map_err(|e| e.to_string()))
+    });
+    promise
+}
+
+/// Id of the next cancellable command sent by this UI
+static NEXT_REQUEST: AtomicU64 = AtomicU64::new(0);
+
+/// A command which is cancelled on the backend when it is dropped before it finished, e.g. the
+/// variables of a stop the user already stepped past, so the debugger doesn't keep discovering
+/// them while the newer commands wait
+pub struct Cancellable<T: Send + 'static> {
+    promise: Promise<Result<T, String>>,
+    /// Backend and id of the command, `None` for a value which wasn't requested
+    request: Option<(Url, String)>,
+}
+
+impl<T: Send + 'static> Cancellable<T> {
+    pub fn from_ready(value: Result<T, String>) -> Self {
+        Self {
+            promise: Promise::from_ready(value),
+            request: None,
         }
-        Err(e) => sender.send(Err(format!("Error: {}", e))),
+    }
+}
+
+impl<T: Send + 'static> Deref for Cancellable<T> {
+    type Target = Promise<Result<T, String>>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.promise
+    }
+}
+
+impl<T: Send + 'static> Drop for Cancellable<T> {
+    fn drop(&mut self) {
+        if let (None, Some((backend_url, request))) = (self.promise.ready(), self.request.take()) {
+            Client::new(backend_url).command_then(&Command::Cancel(request), |_| {});
+        }
+    }
+}
+
+pub fn dispatch_cancellable_and_then<T: Send>(
+    backend_url: Url,
+    command: Command,
+    and_then: impl FnOnce(CommandOutput) -> T + Send + 'static,
+) -> Cancellable<T> {
+    let request = NEXT_REQUEST.fetch_add(1, Ordering::Relaxed).to_string();
+    let (sender, promise) = Promise::new();
+    Client::new(backend_url.clone()).cancellable_command_then(&command, &request, move |output| {
+        sender.send(output.map(and_then).map_err(|e| e.to_string()))
+    });
+    Cancellable {
+        promise,
+        request: Some((backend_url, request)),
+    }
+}
+
+/// Uploads a binary or C source file to debug it instead of the current program, resolves to
+/// the path of the launched binary
+pub fn launch_program(
+    backend_url: Url,
+    name: &str,
+    content: Vec<u8>,
+) -> Promise<Result<String, String>> {
+    let (sender, promise) = Promise::new();
+    Client::new(backend_url).launch_then(name, content, move |path| {
+        sender.send(path.map_err(|e| e.to_string()))
+    });
+    promise
+}
+
+/// Requests what the backend supports, fails for backends older than the `/capabilities` endpoint
+pub fn fetch_capabilities(backend_url: Url) -> Promise<Result<Capabilities, String>> {
+    let (sender, promise) = Promise::new();
+    Client::new(backend_url).capabilities_then(move |capabilities| {
+        sender.send(capabilities.map_err(|e| e.to_string()))
     });
     promise
 }
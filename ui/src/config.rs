@@ -0,0 +1,82 @@
+use egui::{Context, Id, ThemePreference};
+use serde::{Deserialize, Serialize};
+use stackium_shared::VARIABLE_MEM_PADDING;
+
+use crate::{i18n::Language, theme::Theme};
+
+/// Base numbers are shown in where a window shows one of several
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum NumberBase {
+    Hexadecimal,
+    Decimal,
+}
+
+/// Whether the step buttons step through lines of source code or single instructions
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum StepGranularity {
+    Source,
+    Instruction,
+}
+
+/// Settings of the UI edited in the Settings window and read by the other windows. They are
+/// stored with the egui memory, which eframe persists between sessions.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Address of the debugger backend, the UI reconnects when it changes
+    pub backend_url: String,
+    /// Name shown to the other clients connected to the backend while this UI drives the
+    /// session
+    pub client_name: String,
+    /// Seconds between polling the status of the running debugee
+    pub poll_interval: f64,
+    /// Bytes shown before and after every variable in the memory window, the backend reads
+    /// `VARIABLE_MEM_PADDING` bytes
+    pub memory_padding: u64,
+    pub number_base: NumberBase,
+    pub color_scheme: ThemePreference,
+    pub step_granularity: StepGranularity,
+    pub theme: Theme,
+    pub language: Language,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            backend_url: "http://localhost:8080".to_owned(),
+            client_name: String::new(),
+            poll_interval: 0.25,
+            memory_padding: VARIABLE_MEM_PADDING,
+            number_base: NumberBase::Hexadecimal,
+            color_scheme: ThemePreference::System,
+            step_granularity: StepGranularity::Source,
+            theme: Theme::default(),
+            language: Language::default(),
+        }
+    }
+}
+
+impl Config {
+    fn id() -> Id {
+        Id::new("stackium_config")
+    }
+
+    pub fn get(ctx: &Context) -> Self {
+        ctx.data_mut(|d| d.get_persisted(Self::id()))
+            .unwrap_or_default()
+    }
+
+    pub fn set(self, ctx: &Context) {
+        ctx.data_mut(|d| d.insert_persisted(Self::id(), self));
+    }
+
+    /// Changes the config only if `edit` changed a setting
+    pub fn edit(ctx: &Context, edit: impl FnOnce(&mut Config)) {
+        let config = Self::get(ctx);
+        let mut edited = config.clone();
+        edit(&mut edited);
+        if edited != config {
+            edited.set(ctx);
+        }
+    }
+}
@@ -1,23 +1,129 @@
 use egui::RichText;
 use poll_promise::Promise;
-use stackium_shared::Command;
+use stackium_shared::{Command, CommandOutput, DebugeeState, DebugeeStatus, StopReason, StopState};
 use url::Url;
 
-use crate::{command::dispatch_command_and_then, debugger_window::DebuggerWindowImpl};
+use crate::{
+    capabilities,
+    command::dispatch_command_and_then,
+    config::{Config, StepGranularity},
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
 
 pub struct ControlWindow {
-    promise: Option<Promise<Result<(), String>>>,
+    /// Pending command, resolves to whether the debugee is running or where it stopped if the
+    /// command resumed it
+    promise: Option<Promise<Result<Option<Resumed>, String>>>,
     backend_url: Url,
     warning: Option<String>,
+    /// Whether continuing single-steps and records the executed lines
+    tracing: bool,
+    /// Shown until the next command
+    stop_state: Option<StopState>,
+    /// Break on calls to allocation and IO functions
+    break_on_allocation: bool,
+    break_on_io: bool,
+    /// Number of instructions stepped at once in instruction mode
+    instruction_count: u64,
+    /// Number of frames Step Out returns from at once
+    finish_count: u64,
+    /// Set while the debugee keeps running after continuing, the status is polled until it
+    /// stops
+    running: bool,
+    /// Time the status is polled at next
+    poll_at: f64,
+    /// Seconds the debugee may keep computing after continuing before it is stopped
+    continue_timeout: u64,
+    /// Patterns of the functions stepping in steps over, separated by spaces
+    step_skips: String,
+    /// Functions called on the current line, offered for stepping into one of them
+    calls: Promise<Result<Vec<String>, String>>,
+}
+
+/// Outcome of a command resuming the debugee
+enum Resumed {
+    Running,
+    Stopped(Box<StopState>),
+}
+
+fn stop_state(output: CommandOutput) -> Option<Resumed> {
+    match output {
+        CommandOutput::StopState(state) => Some(Resumed::Stopped(Box::new(state))),
+        CommandOutput::Status(DebugeeStatus {
+            state: DebugeeState::Running,
+            ..
+        }) => Some(Resumed::Running),
+        CommandOutput::Status(status) => status.stop.map(|stop| Resumed::Stopped(Box::new(stop))),
+        _ => None,
+    }
+}
+
+/// Why and where the debugee stopped, e.g. `● Breakpoint at test.c:12`
+fn describe_stop(ctx: &egui::Context, state: &StopState) -> String {
+    let location = match (&state.location, &state.registers) {
+        (Some(location), _) => format!(
+            "{}:{}",
+            location.file.rsplit('/').next().unwrap_or_default(),
+            location.line
+        ),
+        (None, Some(registers)) => format!("{:#x}", registers.instruction_pointer),
+        (None, None) => String::new(),
+    };
+    match &state.reason {
+        StopReason::Started => trf(ctx, "▶ Started, stopped at {}", &[&location]),
+        StopReason::Step => trf(ctx, "⏸ Stopped at {}", &[&location]),
+        StopReason::Breakpoint => trf(ctx, "● Breakpoint at {}", &[&location]),
+        StopReason::Signal(signal) => trf(ctx, "⚠ Received {} at {}", &[signal, &location]),
+        StopReason::Interrupted => trf(ctx, "⏸ Interrupted at {}", &[&location]),
+        StopReason::Timeout { seconds, backtrace } => trf(
+            ctx,
+            "⚠ Possible infinite loop at {} (running for {} s)\n  in {}",
+            &[&location, seconds, &backtrace.join(" ← ")],
+        ),
+        StopReason::InvariantBroken(expression) => {
+            trf(ctx, "✖ Invariant {} broke at {}", &[expression, &location])
+        }
+        StopReason::WatchChanged {
+            expression,
+            old,
+            new,
+        } => trf(
+            ctx,
+            "👁 {} changed from {} to {} at {}",
+            &[expression, old, new, &location],
+        ),
+        StopReason::Exited(status) => trf(ctx, "⏹ Exited with status {}", &[status]),
+        StopReason::Killed(signal) => trf(ctx, "⏹ Terminated by {}", &[signal]),
+    }
 }
 
 impl ControlWindow {
     pub fn new(backend_url: Url) -> Self {
-        Self {
+        let mut ret = Self {
             promise: None,
             backend_url,
             warning: None,
-        }
+            tracing: false,
+            stop_state: None,
+            break_on_allocation: false,
+            break_on_io: false,
+            instruction_count: 1,
+            finish_count: 1,
+            running: false,
+            poll_at: 0.,
+            continue_timeout: 10,
+            step_skips: String::new(),
+            calls: Promise::from_ready(Ok(vec![])),
+        };
+        // show where the debugee stopped after starting before the first command
+        ret.promise = Some(dispatch_command_and_then(
+            ret.backend_url.clone(),
+            Command::GetStatus,
+            stop_state,
+        ));
+        ret.dirty();
+        ret
     }
 }
 
@@ -27,7 +133,18 @@ impl DebuggerWindowImpl for ControlWindow {
         match &self.promise {
             Some(promise) => match promise.ready() {
                 Some(result) => match result {
-                    Ok(_) => {
+                    Ok(Some(Resumed::Running)) => {
+                        self.running = true;
+                        self.stop_state = None;
+                        self.promise = None;
+                        ui.spinner()
+                    }
+                    Ok(status) => {
+                        self.stop_state = match status {
+                            Some(Resumed::Stopped(stop_state)) => Some(*stop_state.clone()),
+                            _ => None,
+                        };
+                        self.running = false;
                         dirty = true;
                         self.promise = None;
                         ui.spinner()
@@ -39,8 +156,36 @@ impl DebuggerWindowImpl for ControlWindow {
                 },
                 None => ui.spinner(),
             },
+            None if self.running => {
+                ui.spinner();
+                ui.label(tr(ui.ctx(), "Running…"));
+                let time = ui.input(|i| i.time);
+                let poll_interval = Config::get(ui.ctx()).poll_interval;
+                if time >= self.poll_at {
+                    self.poll_at = time + poll_interval;
+                    self.promise = Some(dispatch_command_and_then(
+                        self.backend_url.clone(),
+                        Command::GetStatus,
+                        stop_state,
+                    ));
+                }
+                ui.ctx()
+                    .request_repaint_after(std::time::Duration::from_secs_f64(poll_interval));
+                let r = ui.button(tr(ui.ctx(), "Interrupt")).on_hover_text(tr(
+                    ui.ctx(),
+                    "Stop the program, e.g. if it is waiting for input",
+                ));
+                if r.clicked() {
+                    self.promise = Some(dispatch_command_and_then(
+                        self.backend_url.clone(),
+                        Command::Interrupt,
+                        stop_state,
+                    ));
+                }
+                r
+            }
             None => {
-                let r = ui.button("Continue");
+                let r = ui.button(tr(ui.ctx(), "Continue"));
                 // if ui.button("Step Over").clicked() {
                 //     self.promise = Some(dispatch_command_and_then(
                 //         self.backend_url.clone(),
@@ -49,19 +194,230 @@ impl DebuggerWindowImpl for ControlWindow {
                 //     ));
                 // }
 
-                // if ui.button("Step In").clicked() {
-                //     self.promise = Some(dispatch_command_and_then(
-                //         self.backend_url.clone(),
-                //         Command::StepIn,
-                //         |_| {},
-                //     ));
-                // }
+                let mut step_granularity = Config::get(ui.ctx()).step_granularity;
+                ui.selectable_value(
+                    &mut step_granularity,
+                    StepGranularity::Source,
+                    tr(ui.ctx(), "Source"),
+                )
+                .on_hover_text(tr(ui.ctx(), "Step through lines of source code"));
+                ui.selectable_value(
+                    &mut step_granularity,
+                    StepGranularity::Instruction,
+                    tr(ui.ctx(), "Instruction"),
+                )
+                .on_hover_text(tr(ui.ctx(), "Step through single instructions"));
+                Config::edit(ui.ctx(), |c| c.step_granularity = step_granularity);
+
+                match step_granularity {
+                    StepGranularity::Source => {
+                        if ui
+                            .button(tr(ui.ctx(), "Step In"))
+                            .on_hover_text(tr(
+                                ui.ctx(),
+                                "Continue until a new line of source code is reached",
+                            ))
+                            .clicked()
+                        {
+                            self.promise = Some(dispatch_command_and_then(
+                                self.backend_url.clone(),
+                                Command::StepIn,
+                                stop_state,
+                            ));
+                        }
+
+                        // older backends answer with an error and the menu is left out
+                        if let Some(Ok(calls)) = self.calls.ready() {
+                            let mut target = None;
+                            if !calls.is_empty() {
+                                ui.menu_button(tr(ui.ctx(), "Step Into…"), |ui| {
+                                    for call in calls {
+                                        if ui.button(call).clicked() {
+                                            target = Some(call.clone());
+                                            ui.close_menu();
+                                        }
+                                    }
+                                })
+                                .response
+                                .on_hover_text(tr(
+                                    ui.ctx(),
+                                    "Continue until the chosen function called on this line is entered, the other calls run without stopping",
+                                ));
+                            }
+                            if let Some(target) = target {
+                                self.promise = Some(dispatch_command_and_then(
+                                    self.backend_url.clone(),
+                                    Command::StepIntoTarget(target),
+                                    stop_state,
+                                ));
+                            }
+                        }
 
-                if ui.button("Step Instruction").clicked() {
+                        let unsupported = capabilities::unsupported(ui.ctx(), "StepStatement");
+                        if ui
+                            .add_enabled(
+                                unsupported.is_none(),
+                                egui::Button::new(tr(ui.ctx(), "Step Statement")),
+                            )
+                            .on_disabled_hover_text(unsupported.unwrap_or_default())
+                            .on_hover_text(tr(
+                                ui.ctx(),
+                                "Continue until the next statement is reached, which can be on \
+                                     the same line, e.g. the condition of a for loop",
+                            ))
+                            .clicked()
+                        {
+                            self.promise = Some(dispatch_command_and_then(
+                                self.backend_url.clone(),
+                                Command::StepStatement,
+                                stop_state,
+                            ));
+                        }
+
+                        if ui
+                            .button(tr(ui.ctx(), "Step Out"))
+                            .on_hover_text(tr(
+                                ui.ctx(),
+                                "Continue until the current function returns",
+                            ))
+                            .clicked()
+                        {
+                            self.promise = Some(dispatch_command_and_then(
+                                self.backend_url.clone(),
+                                match self.finish_count {
+                                    1 => Command::StepOut,
+                                    count => Command::FinishFrames(count),
+                                },
+                                stop_state,
+                            ));
+                        }
+                        ui.add(
+                            egui::DragValue::new(&mut self.finish_count)
+                                .range(1..=1000)
+                                .prefix("× "),
+                        )
+                        .on_hover_text(tr(
+                            ui.ctx(),
+                            "Number of calls Step Out returns from, e.g. to leave several calls of a recursive function",
+                        ));
+                    }
+                    StepGranularity::Instruction => {
+                        ui.add(
+                            egui::DragValue::new(&mut self.instruction_count)
+                                .range(1..=10000)
+                                .suffix(tr(ui.ctx(), " instr.")),
+                        );
+                        if ui
+                            .button(tr(ui.ctx(), "Step"))
+                            .on_hover_text(tr(
+                                ui.ctx(),
+                                "Execute the specified number of instructions",
+                            ))
+                            .clicked()
+                        {
+                            self.promise = Some(dispatch_command_and_then(
+                                self.backend_url.clone(),
+                                match self.instruction_count {
+                                    1 => Command::StepInstruction,
+                                    count => Command::StepInstructionN(count),
+                                },
+                                stop_state,
+                            ));
+                        }
+                    }
+                }
+
+                if ui
+                    .checkbox(&mut self.tracing, tr(ui.ctx(), "Trace"))
+                    .on_hover_text(tr(
+                        ui.ctx(),
+                        "Single-step when continuing and record how often each line is executed",
+                    ))
+                    .changed()
+                {
+                    self.promise = Some(dispatch_command_and_then(
+                        self.backend_url.clone(),
+                        Command::SetTracing(self.tracing),
+                        |_| None,
+                    ));
+                }
+
+                if ui
+                    .checkbox(
+                        &mut self.break_on_allocation,
+                        tr(ui.ctx(), "Break on malloc/free"),
+                    )
+                    .on_hover_text(tr(
+                        ui.ctx(),
+                        "Stop at every call to malloc, calloc, realloc and free",
+                    ))
+                    .changed()
+                {
+                    self.promise = Some(dispatch_command_and_then(
+                        self.backend_url.clone(),
+                        Command::BreakOnAllocation(self.break_on_allocation),
+                        |_| None,
+                    ));
+                }
+
+                if ui
+                    .checkbox(&mut self.break_on_io, tr(ui.ctx(), "Break on I/O"))
+                    .on_hover_text(tr(
+                        ui.ctx(),
+                        "Stop at every call to read, write, printf, puts, scanf, ...",
+                    ))
+                    .changed()
+                {
                     self.promise = Some(dispatch_command_and_then(
                         self.backend_url.clone(),
-                        Command::StepInstruction,
-                        |_| {},
+                        Command::BreakOnIO(self.break_on_io),
+                        |_| None,
+                    ));
+                }
+
+                if ui
+                    .add(
+                        egui::DragValue::new(&mut self.continue_timeout)
+                            .range(0..=3600)
+                            .prefix(tr(ui.ctx(), "timeout "))
+                            .suffix(" s"),
+                    )
+                    .on_hover_text(tr(
+                        ui.ctx(),
+                        "Stop the program if it keeps computing for this long after continuing, \
+                             e.g. in an infinite loop. 0 disables the timeout.",
+                    ))
+                    .changed()
+                {
+                    self.promise = Some(dispatch_command_and_then(
+                        self.backend_url.clone(),
+                        Command::SetContinueTimeout(self.continue_timeout),
+                        |_| None,
+                    ));
+                }
+
+                if ui
+                    .add(
+                        egui::TextEdit::singleline(&mut self.step_skips)
+                            .hint_text(tr(ui.ctx(), "skip when stepping in"))
+                            .desired_width(140.),
+                    )
+                    .on_hover_text(tr(
+                        ui.ctx(),
+                        "Functions or libraries Step In steps over, e.g. helper_* libm.so*. \
+                             Functions without source code like printf are always stepped over.",
+                    ))
+                    .lost_focus()
+                {
+                    self.promise = Some(dispatch_command_and_then(
+                        self.backend_url.clone(),
+                        Command::SetStepSkipList(
+                            self.step_skips
+                                .split_whitespace()
+                                .map(str::to_owned)
+                                .collect(),
+                        ),
+                        |_| None,
                     ));
                 }
 
@@ -69,15 +425,62 @@ impl DebuggerWindowImpl for ControlWindow {
                     self.promise = Some(dispatch_command_and_then(
                         self.backend_url.clone(),
                         Command::Continue,
-                        |_| {},
+                        stop_state,
                     ));
                 }
                 r
             }
         };
+        if let Some(stop_state) = &self.stop_state {
+            let mut text = describe_stop(ui.ctx(), stop_state);
+            for return_value in stop_state
+                .return_value
+                .iter()
+                .chain(&stop_state.returned_frames)
+            {
+                let function = return_value.function.as_deref().unwrap_or("??");
+                text += &match (&return_value.type_name, &return_value.value) {
+                    (Some(type_name), Some(value)) => trf(
+                        ui.ctx(),
+                        "\n↩ {} returned {} ({})",
+                        &[&function, value, type_name],
+                    ),
+                    (Some(type_name), None) => trf(
+                        ui.ctx(),
+                        "\n↩ {} returned a {}, return register: {}",
+                        &[&function, type_name, &format!("{:#x}", return_value.raw)],
+                    ),
+                    (None, _) => trf(ui.ctx(), "\n↩ {} returned (void)", &[&function]),
+                };
+            }
+            if let Some(call) = &stop_state.library_call {
+                text += &format!(
+                    "\n☎ {}({}){}",
+                    call.function,
+                    call.arguments
+                        .iter()
+                        .map(|(name, value)| format!("{} = {}", name, value))
+                        .collect::<Vec<_>>()
+                        .join(", "),
+                    match &call.caller {
+                        Some(caller) => trf(ui.ctx(), " called from {}", &[caller]),
+                        None => String::new(),
+                    }
+                );
+            }
+            ui.label(
+                RichText::new(text)
+                    .monospace()
+                    .background_color(ui.visuals().extreme_bg_color),
+            );
+        }
         if let Some(warning) = &self.warning {
             ui.label(RichText::new(format!("⚠ {}", warning)).color(ui.visuals().warn_fg_color));
         }
         dirty
     }
+
+    fn dirty(&mut self) {
+        self.calls = dispatch!(self.backend_url.clone(), Command::GetLineCalls, LineCalls);
+    }
 }
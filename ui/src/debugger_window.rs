@@ -1,7 +1,13 @@
+use egui::RichText;
 use poll_promise::Promise;
-use stackium_shared::{Command, CommandOutput, DebugMeta};
+use stackium_shared::{Command, CommandOutput, DebugMeta, ElfInfo};
 use url::Url;
 
+use crate::{
+    i18n::{tr, trf},
+    selection::Message,
+};
+
 pub struct DebuggerWindow {
     pub title: &'static str,
     pub is_active: bool,
@@ -13,37 +19,198 @@ pub trait DebuggerWindowImpl {
     /// widget changed the debug state significantly
     fn ui(&mut self, ui: &mut egui::Ui) -> bool;
     fn dirty(&mut self) {}
+    /// Called with every message sent by a window, see [`crate::selection::send`]
+    fn receive(&mut self, _message: &Message) {}
     fn update(&mut self, ctx: &egui::Context, frame: &mut eframe::Frame) {}
 }
 
 pub struct Metadata {
     metadata: Promise<Result<DebugMeta, String>>,
+    elf: Promise<Result<ElfInfo, String>>,
 }
 
 impl Metadata {
     pub fn new(backend_url: Url) -> Self {
         Self {
-            metadata: { dispatch!(backend_url, Command::DebugMeta, DebugMeta) },
+            metadata: { dispatch!(backend_url.clone(), Command::DebugMeta, DebugMeta) },
+            elf: { dispatch!(backend_url, Command::GetElfInfo, ElfInfo) },
         }
     }
 }
 
+fn monospace(ui: &mut egui::Ui, text: impl Into<String>) {
+    ui.label(RichText::new(text).monospace());
+}
+
+/// Headers, sections, segments and symbol tables like `readelf -a`
+fn elf_ui(ui: &mut egui::Ui, elf: &ElfInfo) {
+    egui::CollapsingHeader::new(tr(ui.ctx(), "ELF header"))
+        .default_open(true)
+        .show(ui, |ui| {
+            egui::Grid::new("elf_header").show(ui, |ui| {
+                ui.label(tr(ui.ctx(), "Type"));
+                ui.label(match elf.file_type.as_str() {
+                    "Executable" => tr(ui.ctx(), "Executable"),
+                    "Shared object or position independent executable" => {
+                        tr(ui.ctx(), "Shared object or position independent executable")
+                    }
+                    "Relocatable" => tr(ui.ctx(), "Relocatable"),
+                    "Core dump" => tr(ui.ctx(), "Core dump"),
+                    other => other,
+                });
+                ui.end_row();
+                ui.label(tr(ui.ctx(), "Architecture"));
+                ui.label(format!(
+                    "{}, {} bit, {}",
+                    elf.architecture,
+                    elf.bits,
+                    match elf.little_endian {
+                        true => tr(ui.ctx(), "little endian"),
+                        false => tr(ui.ctx(), "big endian"),
+                    }
+                ));
+                ui.end_row();
+                ui.label(tr(ui.ctx(), "Entry point"));
+                monospace(ui, format!("{:#x}", elf.entry));
+                ui.end_row();
+                ui.label(tr(ui.ctx(), "Interpreter"))
+                    .on_hover_text(tr(ui.ctx(), "The dynamic linker which loads the libraries"));
+                match &elf.interpreter {
+                    Some(interpreter) => monospace(ui, interpreter),
+                    None => {
+                        ui.label(tr(ui.ctx(), "none, linked statically"));
+                    }
+                }
+                ui.end_row();
+            });
+        });
+    egui::CollapsingHeader::new(trf(ui.ctx(), "{} sections", &[&elf.sections.len()]))
+        .id_salt("elf_sections")
+        .show(ui, |ui| {
+            egui::Grid::new("elf_sections").striped(true).show(ui, |ui| {
+                ui.strong(tr(ui.ctx(), "Name"));
+                ui.strong(tr(ui.ctx(), "Type"));
+                ui.strong(tr(ui.ctx(), "Address"));
+                ui.strong(tr(ui.ctx(), "Offset"));
+                ui.strong(tr(ui.ctx(), "Size"));
+                ui.strong(tr(ui.ctx(), "Flags")).on_hover_text(tr(
+                    ui.ctx(),
+                    "W writable, A allocated at runtime, X executable, M mergeable, S strings, T thread local",
+                ));
+                ui.end_row();
+                for section in &elf.sections {
+                    monospace(ui, &section.name);
+                    ui.label(&section.section_type);
+                    monospace(ui, format!("{:#x}", section.address));
+                    monospace(ui, format!("{:#x}", section.offset));
+                    monospace(ui, format!("{:#x}", section.size));
+                    monospace(ui, &section.flags);
+                    ui.end_row();
+                }
+            });
+        });
+    egui::CollapsingHeader::new(trf(ui.ctx(), "{} segments", &[&elf.segments.len()]))
+        .id_salt("elf_segments")
+        .show(ui, |ui| {
+            egui::Grid::new("elf_segments")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.strong(tr(ui.ctx(), "Type"));
+                    ui.strong(tr(ui.ctx(), "Address"));
+                    ui.strong(tr(ui.ctx(), "Offset"));
+                    ui.strong(tr(ui.ctx(), "File size"));
+                    ui.strong(tr(ui.ctx(), "Memory size")).on_hover_text(tr(
+                        ui.ctx(),
+                        "Memory beyond the file size is zeroed, like the memory of .bss",
+                    ));
+                    ui.strong(tr(ui.ctx(), "Flags"));
+                    ui.strong(tr(ui.ctx(), "Sections"));
+                    ui.end_row();
+                    for segment in &elf.segments {
+                        ui.label(&segment.segment_type);
+                        monospace(ui, format!("{:#x}", segment.address));
+                        monospace(ui, format!("{:#x}", segment.offset));
+                        monospace(ui, format!("{:#x}", segment.file_size));
+                        monospace(ui, format!("{:#x}", segment.memory_size));
+                        monospace(ui, &segment.flags);
+                        monospace(ui, segment.sections.join(" "));
+                        ui.end_row();
+                    }
+                });
+        });
+    egui::CollapsingHeader::new(tr(ui.ctx(), "Symbol tables"))
+        .id_salt("elf_symbols")
+        .show(ui, |ui| {
+            egui::Grid::new("elf_symbols").striped(true).show(ui, |ui| {
+                ui.strong(tr(ui.ctx(), "Name"));
+                ui.strong(tr(ui.ctx(), "Symbols"));
+                ui.strong(tr(ui.ctx(), "Functions"));
+                ui.strong(tr(ui.ctx(), "Objects"));
+                ui.strong(tr(ui.ctx(), "Undefined"))
+                    .on_hover_text(tr(ui.ctx(), "Defined in a library, e.g. printf"));
+                ui.end_row();
+                for table in &elf.symbol_tables {
+                    monospace(ui, &table.name);
+                    ui.label(table.symbols.to_string());
+                    ui.label(table.functions.to_string());
+                    ui.label(table.objects.to_string());
+                    ui.label(table.undefined.to_string());
+                    ui.end_row();
+                }
+            });
+        });
+}
+
 impl DebuggerWindowImpl for Metadata {
     fn ui(&mut self, ui: &mut egui::Ui) -> bool {
         match self.metadata.ready() {
             Some(metadata) => match metadata {
                 Ok(metadata) => {
-                    ui.heading(format!("Debugging {}", metadata.binary_name));
-                    ui.label(format!("{} functions", metadata.functions));
+                    ui.heading(trf(ui.ctx(), "Debugging {}", &[&metadata.binary_name]));
+                    ui.label(trf(ui.ctx(), "{} functions", &[&metadata.functions]));
 
                     metadata.files.iter().for_each(|file| {
                         ui.label(file);
                     });
-                    ui.label(format!("{} variables", metadata.vars));
+                    ui.label(trf(ui.ctx(), "{} variables", &[&metadata.vars]));
+                    if let Some(debug_file) = &metadata.debug_file {
+                        ui.label(trf(ui.ctx(), "Debug information from {}", &[debug_file]));
+                    }
+                    if !metadata.producers.is_empty() {
+                        ui.label(tr(ui.ctx(), "Compiled by"));
+                        metadata.producers.iter().for_each(|producer| {
+                            monospace(ui, producer);
+                        });
+                    }
+                    match &metadata.optimization {
+                        Some(optimization) => {
+                            ui.colored_label(
+                                ui.visuals().warn_fg_color,
+                                trf(ui.ctx(), "⚠ Optimized with {}", &[optimization]),
+                            );
+                        }
+                        None => {
+                            ui.label(tr(ui.ctx(), "Not optimized"));
+                        }
+                    }
+                    ui.separator();
+                    match self.elf.ready() {
+                        Some(Ok(elf)) => elf_ui(ui, elf),
+                        Some(Err(err)) => {
+                            ui.label(
+                                RichText::new(format!("⚠ {}", err))
+                                    .small()
+                                    .color(ui.visuals().warn_fg_color),
+                            );
+                        }
+                        None => {
+                            ui.spinner();
+                        }
+                    }
                     false
                 }
                 Err(message) => {
-                    ui.label("Error");
+                    ui.label(tr(ui.ctx(), "Error"));
                     false
                 }
             },
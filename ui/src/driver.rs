@@ -0,0 +1,132 @@
+//! Identifies this UI to the backend, which lets only one of several clients connected to the
+//! same session change the debugee at a time, e.g. the instructor's while a projector and the
+//! students' UIs follow
+use std::time::Duration;
+
+use egui::RichText;
+use poll_promise::Promise;
+use stackium_client::Client;
+use stackium_shared::Driver;
+use url::Url;
+
+use crate::i18n::{tr, trf};
+
+/// Seconds between asking the backend which client drives the session
+const POLL_INTERVAL: f64 = 2.0;
+
+/// Random id for an instance of the UI, sent in the `client` query parameter
+pub fn client_id() -> String {
+    let mut random = [0u8; 8];
+    if let Err(e) = getrandom::getrandom(&mut random) {
+        log::warn!("Failed generating the client id: {}", e);
+    }
+    random.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// `backend_url` with the id of this UI and `name` from the settings in its query, the client
+/// sends them with every request
+pub fn identified(backend_url: &Url, client_id: &str, name: &str) -> Url {
+    let mut url = without_identity(backend_url);
+    url.query_pairs_mut()
+        .append_pair("client", client_id)
+        .append_pair("client_name", name);
+    url
+}
+
+/// `backend_url` without the parameters added by [`identified`], as entered in the settings
+pub fn without_identity(backend_url: &Url) -> Url {
+    let pairs = backend_url
+        .query_pairs()
+        .filter(|(key, _)| key != "client" && key != "client_name")
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect::<Vec<_>>();
+    let mut url = backend_url.clone();
+    url.set_query(None);
+    if !pairs.is_empty() {
+        url.query_pairs_mut().extend_pairs(pairs);
+    }
+    url
+}
+
+/// Tells that another client drives the session, or that this one does while others follow
+/// with a button to let them drive
+pub struct DriverBanner {
+    backend_url: Url,
+    driver: Promise<Result<Option<Driver>, String>>,
+    release: Option<Promise<Result<(), String>>>,
+    /// `egui` time of the last request for the driver
+    polled_at: f64,
+}
+
+impl DriverBanner {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            driver: Promise::from_ready(Ok(None)),
+            release: None,
+            polled_at: 0.,
+        };
+        ret.poll(0.);
+        ret
+    }
+
+    fn poll(&mut self, now: f64) {
+        let (sender, promise) = Promise::new();
+        Client::new(self.backend_url.clone())
+            .driver_then(move |driver| sender.send(driver.map_err(|e| e.to_string())));
+        self.driver = promise;
+        self.polled_at = now;
+    }
+
+    fn release(&mut self) {
+        let (sender, promise) = Promise::new();
+        Client::new(self.backend_url.clone())
+            .release_driver_then(move |released| sender.send(released.map_err(|e| e.to_string())));
+        self.release = Some(promise);
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        let now = ui.input(|i| i.time);
+        if let Some(Some(_)) = self.release.as_ref().map(|r| r.ready()) {
+            self.release = None;
+            self.poll(now);
+        }
+        if self.driver.ready().is_some() && now - self.polled_at >= POLL_INTERVAL {
+            self.poll(now);
+        }
+        ui.ctx()
+            .request_repaint_after(Duration::from_secs_f64(POLL_INTERVAL));
+        // backends without `/driver` answer with an error and don't lock the session
+        let Some(Ok(Some(driver))) = self.driver.ready().cloned() else {
+            return;
+        };
+        if !driver.you {
+            ui.label(
+                RichText::new(trf(
+                    ui.ctx(),
+                    "🔒 Currently driven by {}, you can follow the program until it is released",
+                    &[&driver.name],
+                ))
+                .color(ui.visuals().warn_fg_color),
+            );
+        } else if driver.followers > 0 {
+            ui.horizontal(|ui| {
+                ui.label(trf(
+                    ui.ctx(),
+                    "🔒 You are driving the session, {} other clients follow",
+                    &[&driver.followers],
+                ));
+                if ui
+                    .add_enabled(self.release.is_none(), egui::Button::new(tr(ui.ctx(), "Release")))
+                    .on_hover_text(tr(
+                        ui.ctx(),
+                        "Let another client control the program, you drive again with your next command",
+                    ))
+                    .clicked()
+                {
+                    self.release();
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use egui::RichText;
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, Exercise, ExerciseCondition};
+use url::Url;
+
+use crate::{
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
+
+/// Guides through the exercise next to the program, shown next to the windows while the backend
+/// has one
+pub struct ExercisePanel {
+    backend_url: Url,
+    exercise: Promise<Result<Option<Exercise>, String>>,
+}
+
+impl ExercisePanel {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            exercise: Promise::from_ready(Ok(None)),
+        };
+        ret.dirty();
+        ret
+    }
+
+    /// Whether there is an exercise to show, backends without `GetExercise` have none
+    pub fn has_exercise(&self) -> bool {
+        matches!(self.exercise.ready(), Some(Ok(Some(_))))
+    }
+}
+
+fn condition_text(ctx: &egui::Context, condition: &ExerciseCondition) -> String {
+    match condition {
+        ExerciseCondition::Line(line) => trf(ctx, "Stop at line {}", &[line]),
+        ExerciseCondition::Function(name) => trf(ctx, "Stop in {}", &[name]),
+        ExerciseCondition::Exited => tr(ctx, "Run until the program exits").to_owned(),
+    }
+}
+
+impl DebuggerWindowImpl for ExercisePanel {
+    fn dirty(&mut self) {
+        self.exercise = dispatch!(self.backend_url.clone(), Command::GetExercise, Exercise);
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let Some(Ok(Some(exercise))) = self.exercise.ready() else {
+            return false;
+        };
+        ui.heading(&exercise.title);
+        ui.label(trf(
+            ui.ctx(),
+            "Step {} of {}",
+            &[
+                &(exercise.current + 1).min(exercise.steps.len()),
+                &exercise.steps.len(),
+            ],
+        ));
+        ui.separator();
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            for (i, step) in exercise.steps.iter().enumerate() {
+                if i < exercise.current {
+                    ui.label(RichText::new(format!("✔ {}", step.prompt)).weak());
+                } else if i == exercise.current {
+                    ui.group(|ui| {
+                        ui.label(RichText::new(&step.prompt).strong());
+                        if let Some(condition) = &step.until {
+                            ui.label(RichText::new(condition_text(ui.ctx(), condition)).small());
+                        }
+                    });
+                } else {
+                    ui.label(RichText::new(format!("{}. {}", i + 1, step.prompt)).weak());
+                }
+            }
+            if exercise.current >= exercise.steps.len() {
+                ui.label(RichText::new(tr(ui.ctx(), "✔ Exercise completed")).strong());
+            }
+        });
+        false
+    }
+}
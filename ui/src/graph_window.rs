@@ -1,12 +1,28 @@
-use egui::{FontId, Rect, Response, Sense, Stroke, Ui, Vec2};
+use egui::{Rect, Response, RichText, Sense, Stroke, Ui, Vec2};
 use poll_promise::Promise;
-use stackium_shared::{Command, CommandOutput, DataType, MemoryMap, Registers, Variable};
+use stackium_shared::{
+    get_byte_size, Command, CommandOutput, DataType, DiscoveredVariable, Registers,
+    VARIABLE_MEM_PADDING,
+};
 use url::Url;
 
-use crate::{debugger_window::DebuggerWindowImpl, variable_window::get_byte_size};
+use crate::{
+    command::Cancellable,
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+    memory_window::format_element,
+    selection::Message,
+    theme::monospace,
+};
 
 trait NodeContent: Clone {
     fn render(&self, ui: &mut Ui) -> Response;
+    /// Compact content shown when the node is collapsed
+    fn render_summary(&self, ui: &mut Ui) -> Response;
+    /// Structs and arrays can be collapsed even without outgoing edges
+    fn is_composite(&self) -> bool;
+    /// Content changed since the last stop
+    fn changed(&self) -> bool;
 }
 
 #[derive(Clone)]
@@ -23,6 +39,10 @@ struct Node<Data: NodeContent> {
     height: f32,
     id: usize,
     connections: Vec<Edge>,
+    /// Moved by the user, the layout keeps its position
+    pinned: bool,
+    /// Shows a summary and hides all nodes only reachable through this one
+    collapsed: bool,
     pub data: Data,
 }
 
@@ -35,31 +55,79 @@ impl<D: NodeContent> Node<D> {
             height: 96f32,
             id,
             connections,
+            pinned: false,
+            collapsed: false,
             data,
         }
     }
 
+    fn displayed_height(&self) -> f32 {
+        if self.collapsed {
+            self.height.min(96f32)
+        } else {
+            self.height
+        }
+    }
+
     pub fn rect(&self, canvas: Rect) -> Rect {
         Rect::from_x_y_ranges(
             (canvas.min.x + self.x)..=(canvas.min.x + self.x + self.width),
-            (canvas.min.y + self.y)..=(canvas.min.y + self.y + self.height),
+            (canvas.min.y + self.y)..=(canvas.min.y + self.y + self.displayed_height()),
         )
     }
 
-    pub fn render(&self, ui: &mut Ui, canvas: Rect) {
+    /// Renders the node, returns true if the collapse toggle was clicked
+    pub fn render(&self, ui: &mut Ui, canvas: Rect, hidden_count: usize, selected: bool) -> bool {
         let fill_color = ui.style().visuals.extreme_bg_color;
-        let stroke_color = ui.style().visuals.text_color();
+        let stroke_color = if selected {
+            ui.style().visuals.selection.stroke.color
+        } else if self.data.changed() {
+            ui.style().visuals.warn_fg_color
+        } else {
+            ui.style().visuals.text_color()
+        };
         let rect = self.rect(canvas);
         ui.painter().rect(
             rect,
             4.0,
             fill_color,
             Stroke {
-                width: 2.0,
+                width: if selected { 4.0 } else { 2.0 },
                 color: stroke_color,
             },
         );
-        ui.put(rect, |ui: &mut Ui| self.data.render(ui));
+        if self.collapsed {
+            ui.put(rect, |ui: &mut Ui| {
+                ui.vertical(|ui| {
+                    self.data.render_summary(ui);
+                    if hidden_count > 0 {
+                        ui.label(
+                            RichText::new(trf(ui.ctx(), "+{} hidden", &[&hidden_count])).italics(),
+                        );
+                    }
+                })
+                .response
+            });
+        } else {
+            ui.put(rect, |ui: &mut Ui| self.data.render(ui));
+        }
+        if self.data.is_composite() || !self.connections.is_empty() {
+            let toggle = Rect::from_min_size(
+                rect.right_top() + Vec2::new(-20.0, 2.0),
+                Vec2::new(18.0, 18.0),
+            );
+            return ui
+                .put(
+                    toggle,
+                    egui::Button::new(if self.collapsed { "▸" } else { "▾" }).small(),
+                )
+                .on_hover_text(tr(
+                    ui.ctx(),
+                    if self.collapsed { "Expand" } else { "Collapse" },
+                ))
+                .clicked();
+        }
+        false
     }
 }
 
@@ -68,41 +136,173 @@ struct Graph<Data: NodeContent> {
     dragging_node: Option<usize>,
 }
 
+/// Assigns the depth and column of `node` and its unvisited children, returns the column
+fn layout_subtree(
+    node: usize,
+    node_depth: usize,
+    targets: &Vec<Vec<usize>>,
+    visited: &mut Vec<bool>,
+    depth: &mut Vec<usize>,
+    column: &mut Vec<f32>,
+    next_column: &mut f32,
+) -> f32 {
+    depth[node] = node_depth;
+    let mut child_columns = vec![];
+    for child in &targets[node] {
+        if !visited[*child] {
+            visited[*child] = true;
+            child_columns.push(layout_subtree(
+                *child,
+                node_depth + 1,
+                targets,
+                visited,
+                depth,
+                column,
+                next_column,
+            ));
+        }
+    }
+    column[node] = match (child_columns.first(), child_columns.last()) {
+        (Some(first), Some(last)) => (first + last) / 2.0,
+        _ => {
+            *next_column += 1.0;
+            *next_column - 1.0
+        }
+    };
+    column[node]
+}
+
 impl<D: NodeContent> Graph<D> {
+    /// Indices of the edge targets of every node
+    fn targets(&self) -> Vec<Vec<usize>> {
+        self.nodes
+            .iter()
+            .map(|node| {
+                node.connections
+                    .iter()
+                    .filter_map(|edge| self.nodes.iter().position(|n| n.id == edge.connection))
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    }
+
+    /// For every node `None` if it is visible, otherwise the collapsed node hiding it.
+    /// A node is hidden if it can only be reached through a collapsed node.
+    fn hidden_by(&self, targets: &[Vec<usize>]) -> Vec<Option<usize>> {
+        let count = self.nodes.len();
+        let mut has_incoming = vec![false; count];
+        for (source, node_targets) in targets.iter().enumerate() {
+            for target in node_targets {
+                if *target != source {
+                    has_incoming[*target] = true;
+                }
+            }
+        }
+        let mut visible = vec![false; count];
+        let mut stack = (0..count).filter(|i| !has_incoming[*i]).collect::<Vec<_>>();
+        while let Some(i) = stack.pop() {
+            if !visible[i] {
+                visible[i] = true;
+                if !self.nodes[i].collapsed {
+                    stack.extend(&targets[i]);
+                }
+            }
+        }
+        let mut owner = vec![None; count];
+        for collapsed in (0..count).filter(|i| visible[*i] && self.nodes[*i].collapsed) {
+            let mut stack = targets[collapsed].clone();
+            while let Some(i) = stack.pop() {
+                if !visible[i] && owner[i].is_none() {
+                    owner[i] = Some(collapsed);
+                    stack.extend(&targets[i]);
+                }
+            }
+        }
+        // Nodes only reachable through cycles without a root stay visible
+        owner
+    }
+
     pub fn new(nodes: Vec<Node<D>>) -> Self {
         Self {
             nodes,
             dragging_node: None,
         }
     }
+    /// Layered tree layout: the nodes are placed in rows by their depth in a spanning tree of
+    /// the edges, leaves get consecutive columns and parents are centered above their children
+    /// (a simplified Reingold–Tilford). Roots are ordered by id so the layout is stable across
+    /// refreshes, pinned nodes keep their position.
     pub fn arrange(&mut self) {
-        const PADDING: f32 = 10.0;
-        let per_line = (self.nodes.len() as f32).sqrt() as usize;
-        let mut curr_line_count = 0;
-        let mut y = 0f32;
-        for node in self.nodes.iter_mut() {
-            node.y = node.height * y + y * PADDING;
-            node.x = curr_line_count as f32 * node.width + curr_line_count as f32 * PADDING;
-            curr_line_count += 1;
-            if curr_line_count >= per_line {
-                curr_line_count = 0;
-                y += 1f32;
+        const PADDING: f32 = 20.0;
+        let count = self.nodes.len();
+        let hidden_by = self.hidden_by(&self.targets());
+        // Collapsed nodes are leaves of the layout, hidden nodes aren't placed at all
+        let targets = self
+            .targets()
+            .into_iter()
+            .enumerate()
+            .map(|(i, targets)| {
+                if self.nodes[i].collapsed {
+                    vec![]
+                } else {
+                    targets
+                        .into_iter()
+                        .filter(|t| hidden_by[*t].is_none())
+                        .collect()
+                }
+            })
+            .collect::<Vec<Vec<usize>>>();
+        let mut order = (0..count)
+            .filter(|i| hidden_by[*i].is_none())
+            .collect::<Vec<_>>();
+        order.sort_by_key(|i| self.nodes[*i].id);
+        let mut has_incoming = vec![false; count];
+        for (source, node_targets) in targets.iter().enumerate() {
+            for target in node_targets {
+                if *target != source {
+                    has_incoming[*target] = true;
+                }
             }
         }
-    }
-    pub fn rearrange_overlapping_nodes(&mut self) {
-        // rearrange nodes which are at exact same position
-        let mut node_rearranged_count = 0;
-        let mut nodes = self.nodes.clone();
-        for (_, node) in self.nodes.iter_mut().enumerate() {
-            if let Some(other_node) = nodes
-                .iter_mut()
-                .find(|n| n.id != node.id && n.x == node.x && n.y == node.y)
-            {
-                if node_rearranged_count % 2 == 0 {
-                    node.x += node.width;
-                }
-                node_rearranged_count += 1;
+        // Nodes without incoming edges first, remaining ones are only reachable through cycles
+        let roots = order
+            .iter()
+            .filter(|i| !has_incoming[**i])
+            .chain(order.iter().filter(|i| has_incoming[**i]))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut visited = vec![false; count];
+        let mut depth = vec![0usize; count];
+        let mut column = vec![0f32; count];
+        let mut next_column = 0f32;
+        for root in roots {
+            if !visited[root] {
+                visited[root] = true;
+                layout_subtree(
+                    root,
+                    0,
+                    &targets,
+                    &mut visited,
+                    &mut depth,
+                    &mut column,
+                    &mut next_column,
+                );
+            }
+        }
+        let column_width = self.nodes.iter().map(|n| n.width).fold(0.0, f32::max) + PADDING;
+        let row_height = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| hidden_by[*i].is_none())
+            .map(|(_, n)| n.displayed_height())
+            .fold(0.0, f32::max)
+            + PADDING;
+        for (i, node) in self.nodes.iter_mut().enumerate() {
+            if !node.pinned && hidden_by[i].is_none() {
+                node.x = column[i] * column_width;
+                node.y = depth[i] as f32 * row_height;
             }
         }
     }
@@ -110,13 +310,38 @@ impl<D: NodeContent> Graph<D> {
         self.arrange();
         self
     }
-    pub fn render(&mut self, ui: &mut Ui, width: f32, height: f32) -> Response {
+    /// `selected` is the id of the highlighted node
+    pub fn render(
+        &mut self,
+        ui: &mut Ui,
+        width: f32,
+        height: f32,
+        selected: Option<usize>,
+    ) -> Response {
         let (rect, res) = ui.allocate_exact_size(Vec2::new(width, height), Sense::drag());
-        let nodes_before = self.nodes.clone();
-        for node in self.nodes.iter_mut() {
-            node.render(ui, rect);
+        let targets = self.targets();
+        let hidden_by = self.hidden_by(&targets);
+        let mut toggled = None;
+        for (i, node) in self.nodes.iter().enumerate() {
+            if hidden_by[i].is_some() {
+                continue;
+            }
+            let hidden_count = hidden_by.iter().filter(|h| **h == Some(i)).count();
+            if node.render(ui, rect, hidden_count, selected == Some(node.id)) {
+                toggled = Some(i);
+            }
+            let mut drawn = vec![];
             for edge in node.connections.iter() {
-                if let Some(other_node) = nodes_before.iter().find(|n| n.id == edge.connection) {
+                // Edges into hidden nodes are re-routed to the collapsed node hiding them
+                let target = self
+                    .nodes
+                    .iter()
+                    .position(|n| n.id == edge.connection)
+                    .map(|t| hidden_by[t].unwrap_or(t))
+                    .filter(|t| *t != i && !drawn.contains(t));
+                if let Some(target) = target {
+                    drawn.push(target);
+                    let other_node = &self.nodes[target];
                     ui.painter().line_segment(
                         [node.rect(rect).max, other_node.rect(rect).min],
                         Stroke {
@@ -130,25 +355,27 @@ impl<D: NodeContent> Graph<D> {
                             .to_pos2(),
                         egui::Align2::LEFT_CENTER,
                         &edge.label,
-                        FontId {
-                            size: 12.0,
-                            family: egui::FontFamily::Monospace,
-                        },
+                        monospace(ui.ctx(), 12.0),
                         ui.visuals().text_color(),
                     );
                 }
             }
         }
+        if let Some(i) = toggled {
+            self.nodes[i].collapsed = !self.nodes[i].collapsed;
+            self.arrange();
+        }
         if res.drag_started() {
-            if let Some(index) = self
-                .nodes
-                .iter()
-                .position(|n| ui.rect_contains_pointer(n.rect(rect)))
+            if let Some(index) =
+                self.nodes.iter().enumerate().position(|(i, n)| {
+                    hidden_by[i].is_none() && ui.rect_contains_pointer(n.rect(rect))
+                })
             {
                 self.dragging_node = Some(index);
             }
         }
         if let Some(node_index) = self.dragging_node {
+            self.nodes[node_index].pinned = true;
             self.nodes[node_index].x += res.drag_delta().x;
             self.nodes[node_index].y += res.drag_delta().y;
             self.nodes[node_index].x = self.nodes[node_index].x.abs();
@@ -167,6 +394,31 @@ struct VariableNodeData {
     name: String,
     typeid: usize,
     addr: u64,
+    /// Memory of the variable without the padding
+    memory: Vec<u8>,
+    /// Memory at the previous stop
+    previous_memory: Option<Vec<u8>>,
+    string: Option<String>,
+    /// See [`DiscoveredVariable::warnings`], shown as a badge next to the name
+    warnings: Vec<String>,
+}
+
+impl VariableNodeData {
+    /// Whether the bytes `offset..offset + size` changed since the last stop
+    fn range_changed(&self, offset: usize, size: usize) -> bool {
+        self.previous_memory.as_ref().map_or(false, |previous| {
+            previous.get(offset..offset + size) != self.memory.get(offset..offset + size)
+        })
+    }
+
+    fn value_text(&self, text: String, changed: bool, ui: &Ui) -> RichText {
+        let text = RichText::new(text).monospace();
+        if changed {
+            text.color(ui.visuals().warn_fg_color).strong()
+        } else {
+            text
+        }
+    }
 }
 
 impl NodeContent for VariableNodeData {
@@ -175,10 +427,21 @@ impl NodeContent for VariableNodeData {
             ui.add_space(4.0);
             ui.vertical(|ui| {
                 ui.add_space(4.0);
-                ui.label(&self.name);
+                ui.horizontal(|ui| {
+                    ui.label(RichText::new(&self.name).strong());
+                    if !self.warnings.is_empty() {
+                        ui.label(RichText::new("⚠").color(ui.visuals().warn_fg_color))
+                            .on_hover_text(self.warnings.join("\n"));
+                    }
+                });
                 match &self.types.0[self.typeid].1 {
-                    stackium_shared::TypeName::Name { name, byte_size } => {
+                    stackium_shared::TypeName::Name { name, byte_size: _ } => {
                         ui.label(name);
+                        ui.label(self.value_text(
+                            format_element(&self.types, self.typeid, &self.memory),
+                            self.range_changed(0, self.memory.len()),
+                            ui,
+                        ));
                     }
                     stackium_shared::TypeName::Arr { arr_type, count } => {
                         ui.label(format!(
@@ -201,29 +464,84 @@ impl NodeContent for VariableNodeData {
                     stackium_shared::TypeName::ProductType {
                         name,
                         members,
-                        byte_size,
+                        byte_size: _,
                     } => {
                         ui.label(name);
-                        for (name, _, _) in members {
-                            ui.label(name);
+                        for (name, member_type, offset) in members {
+                            let value = match &self.types.0[*member_type].1 {
+                                stackium_shared::TypeName::Ref { .. } => "→".to_owned(),
+                                _ => self
+                                    .memory
+                                    .get(*offset..)
+                                    .map(|m| format_element(&self.types, *member_type, m))
+                                    .unwrap_or_default(),
+                            };
+                            let changed = self
+                                .range_changed(*offset, get_byte_size(&self.types, *member_type));
+                            ui.label(self.value_text(
+                                format!(".{} = {}", name, value),
+                                changed,
+                                ui,
+                            ));
                         }
                     }
                 };
+                if let Some(string) = &self.string {
+                    ui.label(RichText::new(string).monospace());
+                }
             });
         });
         ui.label(format!("{:#x?}", self.addr))
     }
-}
 
-type Section = (u64, u64, Promise<Result<Vec<u8>, String>>);
+    fn render_summary(&self, ui: &mut Ui) -> Response {
+        ui.horizontal(|ui| {
+            ui.add_space(4.0);
+            ui.vertical(|ui| {
+                ui.add_space(4.0);
+                ui.label(RichText::new(&self.name).strong());
+                match &self.types.0[self.typeid].1 {
+                    stackium_shared::TypeName::ProductType { name, members, .. } => {
+                        ui.label(trf(ui.ctx(), "{} {{} members}", &[name, &members.len()]));
+                    }
+                    stackium_shared::TypeName::Arr { count, .. } => {
+                        ui.label(trf(
+                            ui.ctx(),
+                            "[{} elements]",
+                            &[&count.iter().fold(1, |acc, e| acc * *e)],
+                        ));
+                    }
+                    _ => {}
+                }
+                if let Some(string) = &self.string {
+                    ui.label(RichText::new(string).monospace());
+                }
+            });
+        });
+        ui.label(format!("{:#x?}", self.addr))
+    }
+
+    fn changed(&self) -> bool {
+        self.range_changed(0, self.memory.len())
+    }
+
+    fn is_composite(&self) -> bool {
+        matches!(
+            self.types.0[self.typeid].1,
+            stackium_shared::TypeName::ProductType { .. } | stackium_shared::TypeName::Arr { .. }
+        )
+    }
+}
 
 pub struct GraphWindow {
     backend_url: Url,
     graph: Graph<VariableNodeData>,
-    variables: Promise<Result<Vec<Variable>, String>>,
-    mapping: Promise<Result<Vec<MemoryMap>, String>>,
-    additional_loaded_sections: Vec<Section>,
+    variables: Cancellable<Vec<DiscoveredVariable>>,
     registers: Promise<Result<Registers, String>>,
+    /// The current variables were already merged into the graph
+    variables_applied: bool,
+    /// Address of the variable selected in another window
+    selected: Option<u64>,
 }
 
 impl GraphWindow {
@@ -231,10 +549,10 @@ impl GraphWindow {
         let mut ret = Self {
             backend_url,
             graph: Graph::new(vec![]).arrange_place(),
-            variables: Promise::from_ready(Err(String::new())),
-            mapping: Promise::from_ready(Err(String::new())),
-            additional_loaded_sections: vec![],
+            variables: Cancellable::from_ready(Err(String::new())),
             registers: Promise::from_ready(Err(String::new())),
+            variables_applied: false,
+            selected: None,
         };
         ret.dirty();
         ret
@@ -243,19 +561,26 @@ impl GraphWindow {
 
 impl DebuggerWindowImpl for GraphWindow {
     fn dirty(&mut self) {
-        self.additional_loaded_sections = vec![];
-        // self.graph.nodes = vec![];
-        self.variables = dispatch!(self.backend_url.clone(), Command::ReadVariables, Variables);
-        self.mapping = dispatch!(self.backend_url.clone(), Command::Maps, Maps);
+        self.variables = dispatch_cancellable!(
+            self.backend_url.clone(),
+            Command::DiscoverVariables,
+            DiscoveredVariables
+        );
         self.registers = dispatch!(self.backend_url.clone(), Command::GetRegister, Registers);
+        self.variables_applied = false;
+    }
+    fn receive(&mut self, message: &Message) {
+        if let Message::Select(selection) = message {
+            self.selected = selection.as_ref().and_then(|s| s.address);
+        }
     }
     fn ui(&mut self, ui: &mut egui::Ui) -> bool {
-        let mut found_vars = vec![];
-        if let (Some(Ok(mapping)), Some(Ok(variables)), Some(Ok(registers))) = (
-            self.mapping.ready(),
+        if let (false, Some(Ok(variables)), Some(Ok(registers))) = (
+            self.variables_applied,
             self.variables.ready(),
             self.registers.ready(),
         ) {
+            self.variables_applied = true;
             let variables = variables
                 .iter()
                 .filter(|v| {
@@ -263,208 +588,153 @@ impl DebuggerWindowImpl for GraphWindow {
                         && registers.instruction_pointer <= v.high_pc
                 })
                 .collect::<Vec<_>>();
-            for variable in variables {
-                if let (Some(addr), Some(types)) = (variable.addr, &variable.type_name) {
-                    found_vars.append(&mut check_variable_recursive(
-                        mapping,
-                        &mut self.additional_loaded_sections,
-                        &self.backend_url,
-                        addr,
-                        0,
-                        types.clone(),
-                        variable.name.clone().unwrap_or(String::new()),
-                        false,
-                    ));
-                }
-            }
+            push_variables(&variables, &mut self.graph);
+        }
+        if ui
+            .button(tr(ui.ctx(), "⟲ Arrange"))
+            .on_hover_text(tr(
+                ui.ctx(),
+                "Reset the layout including nodes that were moved",
+            ))
+            .clicked()
+        {
+            self.graph.nodes.iter_mut().for_each(|n| n.pinned = false);
+            self.graph.arrange();
         }
-        push_variables(&found_vars, &mut self.graph);
-        self.graph
-            .render(ui, ui.available_width(), ui.available_height());
+        self.graph.render(
+            ui,
+            ui.available_width(),
+            ui.available_height(),
+            self.selected.map(|a| a as usize),
+        );
         false
     }
 }
 
-fn push_variables(
-    vars: &Vec<(u64, String, Vec<Edge>, usize, DataType)>,
-    graph: &mut Graph<VariableNodeData>,
+/// Collects the values of all pointers inside a value of the type at `type_index` together
+/// with their path (e.g. `.next` or `[2]`)
+fn collect_pointers(
+    types: &DataType,
+    type_index: usize,
+    memory: &[u8],
+    path: String,
+    pointers: &mut Vec<Edge>,
 ) {
-    let mut did_add = false;
-    for (addr, name, refs, typeid, types) in vars {
-        if let Some(node) = graph
-            .nodes
-            .iter_mut()
-            .find(|node| node.id == *addr as usize)
-        {
-            node.connections = refs.clone();
-        } else {
-            did_add = true;
-            graph.nodes.push(Node::new(
-                *addr as usize,
-                refs.clone(),
-                VariableNodeData {
-                    name: name.clone(),
-                    types: types.clone(),
-                    typeid: *typeid,
-                    addr: *addr,
-                },
-            ));
-            graph.rearrange_overlapping_nodes();
+    match &types.0[type_index].1 {
+        stackium_shared::TypeName::Name { .. } => {}
+        stackium_shared::TypeName::Ref { index: _ } => {
+            if let Some(value) = memory.get(..8) {
+                pointers.push(Edge {
+                    connection: u64::from_le_bytes(value.try_into().unwrap()) as usize,
+                    label: path,
+                });
+            }
+        }
+        stackium_shared::TypeName::Arr { arr_type, count } => {
+            let element_size = get_byte_size(types, *arr_type);
+            for i in 0..count.iter().fold(1, |acc, e| acc * *e) {
+                if let Some(memory) = memory.get(i * element_size..) {
+                    collect_pointers(
+                        types,
+                        *arr_type,
+                        memory,
+                        format!("{}[{}]", path, i),
+                        pointers,
+                    );
+                }
+            }
+        }
+        stackium_shared::TypeName::ProductType { members, .. } => {
+            for (name, member_type, offset) in members {
+                if let Some(memory) = memory.get(*offset..) {
+                    collect_pointers(
+                        types,
+                        *member_type,
+                        memory,
+                        format!("{}.{}", path, name),
+                        pointers,
+                    );
+                }
+            }
         }
     }
-    if did_add {
-        // graph.arrange();
-    }
-}
-
-fn read_value(memory: &Vec<u8>, offset: usize) -> u64 {
-    let value = &memory[offset..offset + 8];
-    let value = value[0] as u64
-        | (value[1] as u64) << 8
-        | (value[2] as u64) << 16
-        | (value[3] as u64) << 24
-        | (value[4] as u64) << 32
-        | (value[5] as u64) << 40
-        | (value[6] as u64) << 48
-        | (value[7] as u64) << 56;
-    value
 }
 
-/// Search Mode specifies if found types should be reported or just search for references which will then report types again
-/// Return Type: (addr, [references], type_index, types)
-fn check_variable_recursive(
-    mapping: &Vec<MemoryMap>,
-    sections: &mut Vec<Section>,
-    backend_url: &Url,
-    addr: u64,
-    type_index: usize,
-    types: DataType,
-    name: String,
-    search_mode: bool,
-) -> Vec<(u64, String, Vec<Edge>, usize, DataType)> {
-    let size = get_byte_size(&types, type_index);
-    if let Some(section) = sections
+/// Synchronizes the graph with the discovered variables, every variable (including heap
+/// objects found through pointers) becomes a node and every pointer inside it an edge
+fn push_variables(vars: &Vec<&DiscoveredVariable>, graph: &mut Graph<VariableNodeData>) {
+    // Variables sharing an address (e.g. `a` and `*ptr_to_a`) become a single node,
+    // preferring declared variables over aliases found through a pointer
+    let mut vars = vars
         .iter()
-        .find(|(start, end, _)| addr >= *start && addr + size as u64 - 1 <= *end)
-    {
-        if let Some(Ok(memory)) = section.2.ready() {
-            match &types.0[type_index].1 {
-                stackium_shared::TypeName::Name {
-                    name: _,
-                    byte_size: _,
-                } => {
-                    if !search_mode {
-                        return vec![(addr, name, vec![], type_index, types.clone())];
-                    } else {
-                        return vec![];
-                    }
-                }
-                stackium_shared::TypeName::Arr { arr_type, count } => {
-                    let mut ret_val = vec![];
-                    let mut refs = vec![];
-                    for i in 0..count.iter().fold(1, |acc, e| acc * *e) {
-                        let mut a = check_variable_recursive(
-                            mapping,
-                            sections,
-                            backend_url,
-                            addr + get_byte_size(&types, *arr_type) as u64 * i as u64,
-                            *arr_type,
-                            types.clone(),
-                            format!("{}[{}]", name, i),
-                            true,
-                        );
-                        if let Some(first) = a.iter().last() {
-                            refs.push(Edge {
-                                connection: first.0 as usize,
-                                label: format!("[{}]", i),
-                            });
-                        }
-                        ret_val.append(&mut a);
-                    }
-                    if !search_mode {
-                        ret_val.push((addr, name, refs, type_index, types.clone()));
-                    }
-                    return ret_val;
-                }
-                stackium_shared::TypeName::Ref { index } => {
-                    let mut ret_val = vec![];
-                    let value = read_value(memory, addr as usize - section.0 as usize);
-                    if !search_mode {
-                        ret_val.push((
-                            addr,
-                            name.clone(),
-                            vec![Edge {
-                                connection: value as usize,
-                                label: String::new(),
-                            }],
-                            type_index,
-                            types.clone(),
-                        ));
-                    }
-                    if let Some(index) = index {
-                        ret_val.append(&mut check_variable_recursive(
-                            mapping,
-                            sections,
-                            backend_url,
-                            value,
-                            *index,
-                            types,
-                            format!("*{}", name),
-                            false,
-                        ));
-                    }
-                    return ret_val;
-                }
-                stackium_shared::TypeName::ProductType {
-                    name: structname,
-                    members,
-                    byte_size,
-                } => {
-                    let mut ret_val = vec![];
-                    let mut refs = vec![];
-                    for (fieldname, prod_type_offset, offset) in members.iter() {
-                        let mut a = check_variable_recursive(
-                            mapping,
-                            sections,
-                            backend_url,
-                            addr + *offset as u64,
-                            *prod_type_offset,
-                            types.clone(),
-                            format!("{}.{}", name, fieldname),
-                            true,
-                        );
-                        if let Some(first) = a.iter().last() {
-                            refs.push(Edge {
-                                connection: first.0 as usize,
-                                label: fieldname.clone(),
-                            });
-                        }
-                        ret_val.append(&mut a);
-                    }
-                    if !search_mode {
-                        ret_val.push((addr, name, refs, type_index, types.clone()));
-                    }
-                    return ret_val;
-                }
+        .filter(|v| v.addr.is_some() && v.memory.is_some())
+        .collect::<Vec<_>>();
+    vars.sort_by_key(|v| {
+        (
+            v.addr,
+            v.name.as_ref().map_or(false, |n| n.starts_with('*')),
+        )
+    });
+    vars.dedup_by_key(|v| v.addr);
+
+    let mut did_change = false;
+    let count = graph.nodes.len();
+    graph
+        .nodes
+        .retain(|node| vars.iter().any(|v| v.addr == Some(node.id as u64)));
+    did_change |= count != graph.nodes.len();
+    for variable in vars {
+        let addr = variable.addr.unwrap();
+        let memory = &variable.memory.as_ref().unwrap()[VARIABLE_MEM_PADDING as usize..];
+        let mut refs = vec![];
+        collect_pointers(
+            &variable.types,
+            variable.type_index,
+            memory,
+            String::new(),
+            &mut refs,
+        );
+        let data = VariableNodeData {
+            name: variable.name.clone().unwrap_or_default(),
+            types: variable.types.clone(),
+            typeid: variable.type_index,
+            addr,
+            memory: memory.to_vec(),
+            previous_memory: None,
+            string: variable.string.clone(),
+            warnings: variable.warnings.clone(),
+        };
+        let height = match &variable.types.0[variable.type_index].1 {
+            stackium_shared::TypeName::ProductType { members, .. } => {
+                (members.len() as f32 * 16.0 + 64.0).max(96.0)
             }
+            _ => 96.0,
+        };
+        if let Some(node) = graph.nodes.iter_mut().find(|node| node.id == addr as usize) {
+            let connections = refs.iter().map(|e| e.connection).collect::<Vec<_>>();
+            if node
+                .connections
+                .iter()
+                .map(|e| e.connection)
+                .ne(connections)
+            {
+                did_change = true;
+            }
+            node.connections = refs;
+            let previous_memory = std::mem::take(&mut node.data.memory);
+            node.data = VariableNodeData {
+                previous_memory: Some(previous_memory),
+                ..data
+            };
+            node.height = height;
         } else {
-            return vec![];
-        }
-    } else {
-        if mapping
-            .iter()
-            .any(|m| m.from <= addr && addr + size as u64 <= m.to)
-        {
-            sections.push((
-                addr,
-                addr + size as u64 - 1,
-                dispatch!(
-                    backend_url.clone(),
-                    Command::ReadMemory(addr, size as u64),
-                    Memory
-                ),
-            ));
+            did_change = true;
+            let mut node = Node::new(addr as usize, refs, data);
+            node.height = height;
+            graph.nodes.push(node);
         }
-        return vec![];
+    }
+    if did_change {
+        graph.arrange();
     }
 }
@@ -0,0 +1,330 @@
+use egui::{RichText, ScrollArea};
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, MemoryMap, Registers};
+use url::Url;
+
+use crate::{
+    command::dispatch_command_and_then,
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
+
+const BYTES_PER_ROW: u64 = 16;
+/// Number of bytes shown at once, the arrows move by this amount
+const PAGE_SIZE: u64 = 512;
+
+/// Consecutive bytes read from a single mapped region
+struct Chunk {
+    from: u64,
+    memory: Promise<Result<Vec<u8>, String>>,
+}
+
+pub struct HexdumpWindow {
+    backend_url: Url,
+    /// First address shown, always a multiple of [`BYTES_PER_ROW`]
+    start: Option<u64>,
+    goto_input: String,
+    registers: Promise<Result<Registers, String>>,
+    mapping: Promise<Result<Vec<MemoryMap>, String>>,
+    /// `None` until the mapping is known
+    chunks: Option<Vec<Chunk>>,
+    /// Bytes of the page at the previous stop, used to highlight changes
+    previous: Vec<(u64, u8)>,
+    /// Address and text input of the byte currently being edited
+    editing: Option<(u64, String)>,
+    write_request: Option<Promise<Result<(), String>>>,
+    warning: Option<String>,
+}
+
+impl HexdumpWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            start: None,
+            goto_input: String::new(),
+            registers: Promise::from_ready(Err(String::new())),
+            mapping: Promise::from_ready(Err(String::new())),
+            chunks: None,
+            previous: vec![],
+            editing: None,
+            write_request: None,
+            warning: None,
+        };
+        ret.dirty();
+        ret
+    }
+
+    pub fn goto(&mut self, address: u64) {
+        self.start = Some(address - address % BYTES_PER_ROW);
+        self.chunks = None;
+        self.previous.clear();
+    }
+
+    fn byte(&self, address: u64) -> Option<u8> {
+        self.chunks.as_ref()?.iter().find_map(|chunk| {
+            let memory = chunk.memory.ready()?.as_ref().ok()?;
+            memory
+                .get(address.checked_sub(chunk.from)? as usize)
+                .copied()
+        })
+    }
+
+    /// Requests the mapped parts of the current page, unmapped memory can't be read
+    fn request_chunks(&mut self, start: u64, mapping: &[MemoryMap]) {
+        let end = start + PAGE_SIZE;
+        self.chunks = Some(
+            mapping
+                .iter()
+                .filter(|m| m.read && m.from < end && m.to > start)
+                .map(|m| {
+                    let from = m.from.max(start);
+                    let to = m.to.min(end);
+                    Chunk {
+                        from,
+                        memory: dispatch!(
+                            self.backend_url.clone(),
+                            Command::ReadMemory(from, to - from),
+                            Memory
+                        ),
+                    }
+                })
+                .collect(),
+        );
+    }
+
+    fn write_byte(&mut self, ctx: &egui::Context) {
+        let Some((address, input)) = &self.editing else {
+            return;
+        };
+        match u8::from_str_radix(input.trim().trim_start_matches("0x"), 16) {
+            Ok(byte) => {
+                self.warning = None;
+                self.write_request = Some(dispatch_command_and_then(
+                    self.backend_url.clone(),
+                    Command::WriteMemory(*address, vec![byte]),
+                    |_| (),
+                ));
+            }
+            Err(err) => self.warning = Some(trf(ctx, "Failed parsing byte: {}", &[&err])),
+        }
+    }
+
+    fn render_header(ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(format!("{:18}", tr(ui.ctx(), "Address")))
+                    .monospace()
+                    .strong(),
+            );
+            for column in 0..BYTES_PER_ROW {
+                ui.label(
+                    RichText::new(format!("{:02x}", column))
+                        .monospace()
+                        .strong(),
+                );
+            }
+            ui.label(RichText::new("ASCII").monospace().strong());
+        });
+    }
+
+    fn render_row(&mut self, ui: &mut egui::Ui, address: u64) {
+        ui.horizontal(|ui| {
+            ui.label(
+                RichText::new(format!("{:#018x}", address))
+                    .monospace()
+                    .weak(),
+            );
+            let mut ascii = String::new();
+            for column in 0..BYTES_PER_ROW {
+                let address = address + column;
+                let byte = self.byte(address);
+                ascii.push(match byte {
+                    Some(b @ 0x20..=0x7e) => b as char,
+                    Some(_) => '.',
+                    None => ' ',
+                });
+                match &mut self.editing {
+                    Some((editing, input)) if *editing == address => {
+                        let response = ui.add(
+                            egui::TextEdit::singleline(input)
+                                .font(egui::TextStyle::Monospace)
+                                .desired_width(16.0),
+                        );
+                        if response.lost_focus() {
+                            if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                                self.write_byte(ui.ctx());
+                            }
+                            self.editing = None;
+                        } else {
+                            response.request_focus();
+                        }
+                    }
+                    _ => {
+                        let Some(byte) = byte else {
+                            ui.label(RichText::new("??").monospace().weak());
+                            continue;
+                        };
+                        let changed = self
+                            .previous
+                            .iter()
+                            .any(|(a, previous)| *a == address && *previous != byte);
+                        let mut text = RichText::new(format!("{:02x}", byte)).monospace();
+                        if changed {
+                            text = text.color(ui.visuals().warn_fg_color).strong();
+                        } else if byte == 0 {
+                            text = text.weak();
+                        }
+                        if ui
+                            .add(egui::Label::new(text).sense(egui::Sense::click()))
+                            .on_hover_text(trf(
+                                ui.ctx(),
+                                "{}: {} - click to edit",
+                                &[&format!("{:#x}", address), &byte],
+                            ))
+                            .clicked()
+                        {
+                            self.editing = Some((address, format!("{:02x}", byte)));
+                        }
+                    }
+                }
+            }
+            ui.label(RichText::new(ascii).monospace());
+        });
+    }
+}
+
+impl DebuggerWindowImpl for HexdumpWindow {
+    fn dirty(&mut self) {
+        if let (Some(start), Some(_)) = (self.start, &self.chunks) {
+            self.previous = (start..start + PAGE_SIZE)
+                .filter_map(|a| Some((a, self.byte(a)?)))
+                .collect();
+        }
+        self.registers = dispatch!(self.backend_url.clone(), Command::GetRegister, Registers);
+        self.mapping = dispatch!(self.backend_url.clone(), Command::Maps, Maps);
+        self.chunks = None;
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut is_dirty = false;
+        if let Some(request) = &self.write_request {
+            match request.ready() {
+                Some(Ok(_)) => {
+                    self.write_request = None;
+                    is_dirty = true;
+                }
+                Some(Err(err)) => {
+                    self.warning = Some(err.clone());
+                    self.write_request = None;
+                }
+                None => {
+                    ui.spinner();
+                }
+            }
+        }
+        ui.horizontal(|ui| {
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.goto_input)
+                    .hint_text("0x7ffe...")
+                    .desired_width(140.0),
+            );
+            if ui.button(tr(ui.ctx(), "Go to")).clicked()
+                || (input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+            {
+                match u64::from_str_radix(self.goto_input.trim().trim_start_matches("0x"), 16) {
+                    Ok(address) => {
+                        self.warning = None;
+                        self.goto(address);
+                    }
+                    Err(err) => {
+                        self.warning = Some(trf(ui.ctx(), "Failed parsing address: {}", &[&err]))
+                    }
+                }
+            }
+            if let Some(Ok(registers)) = self.registers.ready() {
+                let stack_pointer = registers.stack_pointer;
+                let instruction_pointer = registers.instruction_pointer;
+                if ui.button(tr(ui.ctx(), "Stack Pointer")).clicked() {
+                    self.goto(stack_pointer);
+                }
+                if ui.button(tr(ui.ctx(), "Program Counter")).clicked() {
+                    self.goto(instruction_pointer);
+                }
+            }
+            if let Some(start) = self.start {
+                if ui
+                    .button("⏶")
+                    .on_hover_text(tr(ui.ctx(), "Previous page"))
+                    .clicked()
+                {
+                    self.goto(start.saturating_sub(PAGE_SIZE));
+                }
+                if ui
+                    .button("⏷")
+                    .on_hover_text(tr(ui.ctx(), "Next page"))
+                    .clicked()
+                {
+                    self.goto(start.saturating_add(PAGE_SIZE));
+                }
+            }
+        });
+        if let Some(warning) = &self.warning {
+            ui.label(
+                RichText::new(format!("⚠ {}", warning))
+                    .small()
+                    .color(ui.visuals().warn_fg_color),
+            );
+        }
+
+        if self.start.is_none() {
+            match self.registers.ready() {
+                Some(Ok(registers)) => self.goto(registers.stack_pointer),
+                Some(Err(err)) => {
+                    ui.label(err);
+                    return is_dirty;
+                }
+                None => {
+                    ui.spinner();
+                    return is_dirty;
+                }
+            }
+        }
+        let start = self.start.unwrap();
+        let mapping = match self.mapping.ready() {
+            Some(Ok(mapping)) => mapping.clone(),
+            Some(Err(err)) => {
+                ui.label(err);
+                return is_dirty;
+            }
+            None => {
+                ui.spinner();
+                return is_dirty;
+            }
+        };
+        if self.chunks.is_none() {
+            self.request_chunks(start, &mapping);
+        }
+
+        Self::render_header(ui);
+        ui.separator();
+        ScrollArea::vertical().show(ui, |ui| {
+            let mut region: Option<&MemoryMap> = None;
+            for address in (start..start + PAGE_SIZE).step_by(BYTES_PER_ROW as usize) {
+                let current = mapping
+                    .iter()
+                    .find(|m| m.from < address + BYTES_PER_ROW && m.to > address);
+                // label the start of every region with its name
+                if current.map(|m| m.from) != region.map(|m| m.from) {
+                    let text = match current {
+                        Some(map) => format!("── {} {:#x}..{:#x}", map.label, map.from, map.to),
+                        None => tr(ui.ctx(), "── not mapped").to_owned(),
+                    };
+                    ui.label(RichText::new(text).small().strong());
+                    region = current;
+                }
+                self.render_row(ui, address);
+            }
+        });
+        is_dirty
+    }
+}
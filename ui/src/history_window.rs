@@ -0,0 +1,182 @@
+use egui::RichText;
+use egui_plot::{Legend, Line, Plot, PlotPoints, Points};
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, HistoryEntry};
+use url::Url;
+
+use crate::{
+    command::dispatch_command_and_then,
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
+
+struct TrackedVariable {
+    name: String,
+    history: Promise<Result<Vec<HistoryEntry>, String>>,
+}
+
+pub struct HistoryWindow {
+    backend_url: Url,
+    name_input: String,
+    tracked: Vec<TrackedVariable>,
+    request: Option<Promise<Result<(), String>>>,
+    warning: Option<String>,
+}
+
+impl HistoryWindow {
+    pub fn new(backend_url: Url) -> Self {
+        Self {
+            backend_url,
+            name_input: String::new(),
+            tracked: vec![],
+            request: None,
+            warning: None,
+        }
+    }
+
+    fn fetch(&self, name: &str) -> Promise<Result<Vec<HistoryEntry>, String>> {
+        dispatch!(
+            self.backend_url.clone(),
+            Command::GetVariableHistory(name.to_owned()),
+            VariableHistory
+        )
+    }
+}
+
+impl DebuggerWindowImpl for HistoryWindow {
+    fn dirty(&mut self) {
+        for i in 0..self.tracked.len() {
+            self.tracked[i].history = self.fetch(&self.tracked[i].name);
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            ui.text_edit_singleline(&mut self.name_input);
+            if ui.button(tr(ui.ctx(), "Track")).clicked()
+                && !self.name_input.is_empty()
+                && !self.tracked.iter().any(|t| t.name == self.name_input)
+            {
+                self.warning = None;
+                let name = self.name_input.clone();
+                self.request = Some(dispatch_command_and_then(
+                    self.backend_url.clone(),
+                    Command::TrackVariable(name.clone()),
+                    |_| (),
+                ));
+                self.tracked.push(TrackedVariable {
+                    history: self.fetch(&name),
+                    name,
+                });
+            }
+        });
+        if let Some(request) = &self.request {
+            match request.ready() {
+                Some(Ok(_)) => {
+                    self.request = None;
+                    self.dirty();
+                }
+                Some(Err(err)) => {
+                    self.warning = Some(err.clone());
+                    self.request = None;
+                }
+                None => {
+                    ui.spinner();
+                }
+            }
+        }
+        if let Some(warning) = &self.warning {
+            ui.label(
+                RichText::new(format!("⚠ {}", warning))
+                    .small()
+                    .color(ui.visuals().warn_fg_color),
+            );
+        }
+
+        let mut untrack = None;
+        for (i, tracked) in self.tracked.iter().enumerate() {
+            ui.horizontal(|ui| {
+                ui.label(RichText::new(&tracked.name).monospace());
+                match tracked.history.ready() {
+                    Some(Ok(history)) => {
+                        let current = history
+                            .last()
+                            .and_then(|e| e.value.clone())
+                            .unwrap_or(tr(ui.ctx(), "<not in scope>").to_owned());
+                        ui.label(trf(
+                            ui.ctx(),
+                            "= {} ({} stops)",
+                            &[&current, &history.len()],
+                        ));
+                    }
+                    Some(Err(err)) => {
+                        ui.label(err);
+                    }
+                    None => {
+                        ui.spinner();
+                    }
+                }
+                if ui.small_button(tr(ui.ctx(), "Untrack")).clicked() {
+                    untrack = Some(i);
+                }
+            });
+        }
+        if let Some(i) = untrack {
+            let tracked = self.tracked.remove(i);
+            self.request = Some(dispatch_command_and_then(
+                self.backend_url.clone(),
+                Command::UntrackVariable(tracked.name),
+                |_| (),
+            ));
+        }
+
+        let entries = self
+            .tracked
+            .iter()
+            .filter_map(|t| match t.history.ready() {
+                Some(Ok(history)) => Some((t.name.clone(), history.clone())),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        let ctx = ui.ctx().clone();
+        Plot::new("variable_history")
+            .legend(Legend::default())
+            .x_axis_label(tr(ui.ctx(), "stop"))
+            .label_formatter(move |name, point| {
+                let location = entries
+                    .iter()
+                    .find(|(n, _)| n == name)
+                    .and_then(|(_, history)| {
+                        history
+                            .iter()
+                            .find(|e| e.stop == point.x.round() as u64)
+                            .and_then(|e| e.location.clone())
+                    })
+                    .map(|l| trf(&ctx, "\nline {}", &[&l.line]))
+                    .unwrap_or_default();
+                trf(
+                    &ctx,
+                    "{}\nstop {}: {}{}",
+                    &[&name, &point.x, &point.y, &location],
+                )
+            })
+            .show(ui, |plot_ui| {
+                for tracked in self.tracked.iter() {
+                    if let Some(Ok(history)) = tracked.history.ready() {
+                        let points = history
+                            .iter()
+                            .filter_map(|e| e.numeric.map(|n| [e.stop as f64, n]))
+                            .collect::<Vec<_>>();
+                        plot_ui
+                            .line(Line::new(PlotPoints::from(points.clone())).name(&tracked.name));
+                        plot_ui.points(
+                            Points::new(PlotPoints::from(points))
+                                .radius(3.)
+                                .name(&tracked.name),
+                        );
+                    }
+                }
+            });
+        false
+    }
+}
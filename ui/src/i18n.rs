@@ -0,0 +1,77 @@
+//! Translations of the texts of the UI. The English texts are the keys of the translations, a
+//! text without translation is shown in English.
+use std::fmt::Display;
+
+use egui::{Context, Id};
+use serde::{Deserialize, Serialize};
+
+mod german;
+
+/// Language of the UI, selected in the Settings window
+#[derive(Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum Language {
+    #[default]
+    English,
+    German,
+}
+
+impl Language {
+    pub const ALL: [Language; 2] = [Language::English, Language::German];
+
+    /// Name of the language in the language itself
+    pub fn name(self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::German => "Deutsch",
+        }
+    }
+
+    /// Translated texts sorted by their English text
+    fn translations(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Language::English => &[],
+            Language::German => german::TRANSLATIONS,
+        }
+    }
+
+    pub fn translate(self, text: &'static str) -> &'static str {
+        let translations = self.translations();
+        match translations.binary_search_by_key(&text, |(english, _)| english) {
+            Ok(index) => translations[index].1,
+            Err(_) => text,
+        }
+    }
+}
+
+fn id() -> Id {
+    Id::new("stackium_language")
+}
+
+/// Sets the language of the current frame, reading the config for every text would be slow
+pub fn set_language(ctx: &Context, language: Language) {
+    ctx.data_mut(|d| d.insert_temp(id(), language));
+}
+
+pub fn language(ctx: &Context) -> Language {
+    ctx.data(|d| d.get_temp(id())).unwrap_or_default()
+}
+
+/// `text` in the language of the UI
+pub fn tr(ctx: &Context, text: &'static str) -> &'static str {
+    language(ctx).translate(text)
+}
+
+/// Like [`tr`] for texts with `{}` placeholders, which are replaced by `args` in order
+pub fn trf(ctx: &Context, text: &'static str, args: &[&dyn Display]) -> String {
+    let mut parts = tr(ctx, text).split("{}");
+    let mut formatted = parts.next().unwrap_or_default().to_owned();
+    for (part, arg) in parts.zip(
+        args.iter()
+            .map(|a| a.to_string())
+            .chain(std::iter::repeat(String::new())),
+    ) {
+        formatted.push_str(&arg);
+        formatted.push_str(part);
+    }
+    formatted
+}
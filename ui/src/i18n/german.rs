@@ -0,0 +1,389 @@
+//! German texts of the UI by their English text, sorted by it, see [`super::tr`]
+
+pub const TRANSLATIONS: &[(&str, &str)] = &[
+    ("  ⋮ {} unmapped", "  ⋮ {} nicht gemappt"),
+    (" (line {})", " (Zeile {})"),
+    (" (not mapped)", " (nicht gemappt)"),
+    (" called from {}", " aufgerufen von {}"),
+    (" instr.", " Instr."),
+    ("+{} hidden", "+{} ausgeblendet"),
+    ("+{} more", "+{} weitere"),
+    ("<not in scope>", "<nicht im Gültigkeitsbereich>"),
+    ("= {} ({} stops)", "= {} ({} Halte)"),
+    ("Accessibility mode", "Barrierefreier Modus"),
+    ("Add", "Hinzufügen"),
+    ("Address", "Adresse"),
+    ("Answer the questions in the Quiz window to reveal the memory", "Beantworte die Fragen im Quiz-Fenster, um den Speicher anzuzeigen"),
+    ("Appearance", "Darstellung"),
+    ("Architecture", "Architektur"),
+    ("Arguments", "Argumente"),
+    ("Ask", "Fragen"),
+    ("Ask what a pointer points to", "Fragen, worauf ein Zeiger zeigt"),
+    ("At {}", "Bei {}"),
+    ("Backend log", "Backend-Protokoll"),
+    ("Base Pointer", "Basiszeiger"),
+    ("Before the first stop", "Vor dem ersten Halt"),
+    ("Break on I/O", "Bei Ein-/Ausgabe anhalten"),
+    ("Break on malloc/free", "Bei malloc/free anhalten"),
+    ("Breakpoints", "Haltepunkte"),
+    ("Breakpoints hit", "Erreichte Haltepunkte"),
+    ("Breaks before the function prologue set up the stack frame", "Hält an, bevor der Funktionsprolog den Stackframe aufgebaut hat"),
+    ("Bytes shown around variables", "Angezeigte Bytes um Variablen"),
+    ("Call stack", "Aufrufstack"),
+    ("Changed since the last stop", "Seit dem letzten Halt geändert"),
+    ("Changed since the previous stop", "Seit dem vorherigen Halt geändert"),
+    ("Check", "Prüfen"),
+    ("Click to edit", "Zum Bearbeiten klicken"),
+    ("Code size", "Schriftgröße"),
+    ("Collapse", "Zuklappen"),
+    ("Color scheme:", "Farbschema:"),
+    ("Commands at this stop ({})", "Befehle bei diesem Halt ({})"),
+    ("Compiled by", "Kompiliert von"),
+    ("Connect", "Verbinden"),
+    ("Connection", "Verbindung"),
+    ("Content", "Inhalt"),
+    ("Continue", "Fortsetzen"),
+    ("Continue until a new line of source code is reached", "Fortsetzen, bis eine neue Quellcodezeile erreicht ist"),
+    ("Continue until the chosen function called on this line is entered, the other calls run without stopping", "Fortfahren, bis die gewählte Funktion, die in dieser Zeile aufgerufen wird, betreten wird, die anderen Aufrufe laufen ohne anzuhalten"),
+    ("Continue until the current function returns", "Fortsetzen, bis die aktuelle Funktion zurückkehrt"),
+    ("Continue until the next breakpoint while sampling the current function", "Bis zum nächsten Haltepunkt fortsetzen und dabei die aktuelle Funktion abtasten"),
+    ("Continue until the next statement is reached, which can be on the same line, e.g. the condition of a for loop", "Fortsetzen, bis die nächste Anweisung erreicht ist, die in derselben Zeile sein kann, z. B. die Bedingung einer for-Schleife"),
+    ("Continuing single-steps and stops right after the memory changed, which is slow", "Fortsetzen läuft in Einzelschritten und hält direkt nach der Änderung des Speichers an, was langsam ist"),
+    ("Continuous", "Kontinuierlich"),
+    ("Controls", "Steuerung"),
+    ("Core dump", "Core-Dump"),
+    ("Current file: {}", "Aktuelle Datei: {}"),
+    ("Current function only", "Nur aktuelle Funktion"),
+    ("Dark", "Dunkel"),
+    ("Debug Info", "Debug-Infos"),
+    ("Debug information from {}", "Debuginformationen aus {}"),
+    ("Debugging", "Debuggen"),
+    ("Debugging {}", "Debugge {}"),
+    ("Decimal", "Dezimal"),
+    ("Declared in line {}", "Deklariert in Zeile {}"),
+    ("Defined in a library, e.g. printf", "In einer Bibliothek definiert, z. B. printf"),
+    ("Deleted breakpoint at {}", "Haltepunkt gelöscht bei {}"),
+    ("Diagram text size", "Textgröße der Diagramme"),
+    ("Disassemble", "Disassemblieren"),
+    ("Download a recording of this session, drop it into the window to replay it", "Lade eine Aufzeichnung dieser Sitzung herunter, lege sie im Fenster ab, um sie abzuspielen"),
+    ("Dynamic Linking", "Dynamisches Linken"),
+    ("ELF header", "ELF-Header"),
+    ("Entry point", "Einsprungpunkt"),
+    ("Error", "Fehler"),
+    ("Evaluates an address or pointer expression like `array + 3`, `ptr - 1` or `&var` and highlights where it points to", "Wertet einen Adress- oder Zeigerausdruck wie `array + 3`, `ptr - 1` oder `&var` aus und hebt hervor, wohin er zeigt"),
+    ("Executable", "Ausführbare Datei"),
+    ("Execute the specified number of instructions", "Die angegebene Anzahl Instruktionen ausführen"),
+    ("Executing {}", "Führt {} aus"),
+    ("Expand", "Aufklappen"),
+    ("Failed launching {}:\n{}", "{} konnte nicht gestartet werden:\n{}"),
+    ("Failed parsing address: {}", "Adresse konnte nicht gelesen werden: {}"),
+    ("Failed parsing byte: {}", "Byte konnte nicht gelesen werden: {}"),
+    ("Failed parsing number", "Zahl konnte nicht gelesen werden"),
+    ("Failed parsing number: {}", "Zahl konnte nicht gelesen werden: {}"),
+    ("Failed reading the dropped file", "Die abgelegte Datei konnte nicht gelesen werden"),
+    ("File", "Datei"),
+    ("File size", "Dateigröße"),
+    ("Flags", "Flags"),
+    ("Follow program counter", "Dem Befehlszähler folgen"),
+    ("Function", "Funktion"),
+    ("Function Entry", "Funktionseintritt"),
+    ("Functions", "Funktionen"),
+    ("Functions or libraries Step In steps over, e.g. helper_* libm.so*. Functions without source code like printf are always stepped over.", "Funktionen oder Bibliotheken, die Hineinspringen überspringt, z. B. helper_* libm.so*. Funktionen ohne Quellcode wie printf werden immer übersprungen."),
+    ("GOT slot", "GOT-Eintrag"),
+    ("Go to", "Gehe zu"),
+    ("Header at {}: prev_size {}, size {} with the flags PREV_INUSE={}, IS_MMAPPED={}, NON_MAIN_ARENA={}", "Header bei {}: prev_size {}, size {} mit den Flags PREV_INUSE={}, IS_MMAPPED={}, NON_MAIN_ARENA={}"),
+    ("Heap blocks:", "Heap-Blöcke:"),
+    ("Hexadecimal", "Hexadezimal"),
+    ("Hide bytes", "Bytes ausblenden"),
+    ("Highlight the variable in the other windows", "Die Variable in den anderen Fenstern hervorheben"),
+    ("History", "Verlauf"),
+    ("How often the status of a running program is checked", "Wie oft der Status eines laufenden Programms abgefragt wird"),
+    ("Inline values", "Werte im Code"),
+    ("Instruction", "Instruktion"),
+    ("Instruction Pointer", "Instruktionszeiger"),
+    ("Instructions", "Instruktionen"),
+    ("Instructions single-stepped", "Einzeln ausgeführte Instruktionen"),
+    ("Instructor", "Lehrkraft"),
+    ("Interpreter", "Interpreter"),
+    ("Interrupt", "Unterbrechen"),
+    ("Invalid pointers:", "Ungültige Zeiger:"),
+    ("Invariants", "Invarianten"),
+    ("Language:", "Sprache:"),
+    ("Last stop", "Letzter Halt"),
+    ("Launching {}", "Starte {}"),
+    ("Leaves out the functions of libraries compiled into the program", "Lässt die Funktionen von in das Programm kompilierten Bibliotheken weg"),
+    ("Let another client control the program, you drive again with your next command", "Einen anderen Client das Programm steuern lassen, mit deinem nächsten Befehl steuerst du wieder"),
+    ("Light", "Hell"),
+    ("Line", "Zeile"),
+    ("Link Register (return address)", "Link-Register (Rücksprungadresse)"),
+    ("List", "Liste"),
+    ("Lists all variables and registers pointing into this address, click into the plot to select an address", "Listet alle Variablen und Register auf, die auf diese Adresse zeigen, klicke in den Plot, um eine Adresse auszuwählen"),
+    ("Loading...", "Lädt..."),
+    ("Locals", "Lokale Variablen"),
+    ("Location", "Position"),
+    ("Location: {}:{}", "Position: {}:{}"),
+    ("Lock stack start", "Stackanfang fixieren"),
+    ("Locked at: {}", "Fixiert bei: {}"),
+    ("Memory", "Speicher"),
+    ("Memory Layout", "Speicherlayout"),
+    ("Memory Mapping", "Speicherabbildung"),
+    ("Memory before and after every variable in the Memory window", "Speicher vor und nach jeder Variable im Speicher-Fenster"),
+    ("Memory beyond the file size is zeroed, like the memory of .bss", "Speicher jenseits der Dateigröße ist mit Nullen gefüllt, wie der Speicher von .bss"),
+    ("Memory of the variables {}", "Speicher der Variablen {}"),
+    ("Memory size", "Speichergröße"),
+    ("Metadata", "Metadaten"),
+    ("Mode:", "Modus:"),
+    ("Name", "Name"),
+    ("Next page", "Nächste Seite"),
+    ("Next stop", "Nächster Halt"),
+    ("No function matches", "Keine Funktion passt"),
+    ("No questions at this stop", "Keine Fragen an dieser Stelle"),
+    ("No source code available for the current state of the process", "Für den aktuellen Zustand des Prozesses ist kein Quellcode verfügbar"),
+    ("No variable of the current function points to a binary tree", "Keine Variable der aktuellen Funktion zeigt auf einen Binärbaum"),
+    ("No variable of the current function points to a linked list", "Keine Variable der aktuellen Funktion zeigt auf eine verkettete Liste"),
+    ("No variables are stored in this region", "In diesem Bereich liegen keine Variablen"),
+    ("Nodes of type {}", "Knoten vom Typ {}"),
+    ("Not checked at this stop: {}", "An dieser Stelle nicht geprüft: {}"),
+    ("Not in memory:", "Nicht im Speicher:"),
+    ("Not optimized", "Nicht optimiert"),
+    ("Nothing points here", "Nichts zeigt hierher"),
+    ("Nothing to undo", "Nichts rückgängig zu machen"),
+    ("Number of calls Step Out returns from, e.g. to leave several calls of a recursive function", "Anzahl der Aufrufe, aus denen Herausspringen zurückkehrt, z. B. um mehrere Aufrufe einer rekursiven Funktion zu verlassen"),
+    ("Numbers:", "Zahlen:"),
+    ("Objects", "Objekte"),
+    ("Offset", "Offset"),
+    ("Only functions of the program", "Nur Funktionen des Programms"),
+    ("Only running UI code when there are animations or input.", "UI-Code läuft nur bei Animationen oder Eingaben."),
+    ("Only single-step this function, the rest of the program runs at full speed", "Nur diese Funktion wird einzeln durchlaufen, der Rest des Programms läuft mit voller Geschwindigkeit"),
+    ("Open Sidebar", "Seitenleiste öffnen"),
+    ("Or drop a program (binary or .c file) here to debug it, or a snapshot (.json, .json.gz) or session file to view it offline", "Oder lege hier ein Programm (Binärdatei oder .c-Datei) ab, um es zu debuggen, oder einen Snapshot (.json, .json.gz) oder eine Sitzungsdatei, um sie offline anzusehen"),
+    ("Overview", "Übersicht"),
+    ("Patterned strokes, labeled pointer arrows and high-contrast colors in the Memory window, which describes the pointers to screen readers as well", "Gemusterte Linien, beschriftete Zeigerpfeile und kontrastreiche Farben im Speicherfenster, das die Zeiger auch Screenreadern beschreibt"),
+    ("Please restart the debugger", "Bitte starte den Debugger neu"),
+    ("Pointed to by:", "Gezeigt von:"),
+    ("Pointer arithmetic adds multiples of the size of the type pointed to, casting to char* first moves by single bytes. A pointer which isn't aligned or points past the end of an array usually comes from mixing them up or counting one element too far.", "Zeigerarithmetik addiert Vielfache der Größe des Typs, auf den gezeigt wird, nach einem Cast zu char* bewegt sie sich um einzelne Bytes. Ein Zeiger, der nicht ausgerichtet ist oder hinter das Ende eines Arrays zeigt, kommt meist davon, beides zu verwechseln oder ein Element zu weit zu zählen."),
+    ("Pointers", "Zeiger"),
+    ("Points to", "Zeigt auf"),
+    ("Press Profile to sample the program until the next breakpoint", "Drücke Profilieren, um das Programm bis zum nächsten Haltepunkt abzutasten"),
+    ("Previous page", "Vorherige Seite"),
+    ("Previous stop", "Vorheriger Halt"),
+    ("Process stack: argc = {}", "Prozess-Stack: argc = {}"),
+    ("Profile", "Profil"),
+    ("Program Counter", "Befehlszähler"),
+    ("Program Counter: ", "Befehlszähler: "),
+    ("Program exited with code {}", "Programm mit Code {} beendet"),
+    ("Program stopped: {}", "Programm angehalten: {}"),
+    ("Program was terminated by {}", "Programm wurde durch {} beendet"),
+    ("Program: {}", "Programm: {}"),
+    ("Questions", "Fragen"),
+    ("Quit", "Beenden"),
+    ("Reactive", "Reaktiv"),
+    ("Read from {}", "Gelesen aus {}"),
+    ("Registers", "Register"),
+    ("Release", "Freigeben"),
+    ("Relocatable", "Verschiebbare Objektdatei"),
+    ("Remove", "Entfernen"),
+    ("Remove the question", "Frage entfernen"),
+    ("Repaint everything each frame", "Alles in jedem Frame neu zeichnen"),
+    ("Repaint when there are animations or input (e.g. mouse movement)", "Neu zeichnen bei Animationen oder Eingaben (z. B. Mausbewegung)"),
+    ("Replay", "Wiedergabe"),
+    ("Report (HTML)", "Bericht (HTML)"),
+    ("Report (Markdown)", "Bericht (Markdown)"),
+    ("Reset the layout including nodes that were moved", "Das Layout einschließlich verschobener Knoten zurücksetzen"),
+    ("Reset to defaults", "Auf Standardwerte zurücksetzen"),
+    ("Restart failed: {}\n Please try manually restarting the debugger in the terminal.", "Neustart fehlgeschlagen: {}\n Bitte starte den Debugger im Terminal manuell neu."),
+    ("Return value", "Rückgabewert"),
+    ("Returns to {}", "Kehrt zu {} zurück"),
+    ("Revert the most recent action", "Die letzte Aktion rückgängig machen"),
+    ("Run time", "Laufzeit"),
+    ("Run until the program exits", "Ausführen, bis das Programm endet"),
+    ("Running…", "Läuft…"),
+    ("Sample every", "Abtastung alle"),
+    ("Samples", "Stichproben"),
+    ("Samples inside the function itself", "Stichproben in der Funktion selbst"),
+    ("Samples with the function anywhere on the stack", "Stichproben mit der Funktion irgendwo auf dem Stack"),
+    ("Score: {} of {}", "Punkte: {} von {}"),
+    ("Sections", "Sektionen"),
+    ("Segment registers", "Segmentregister"),
+    ("Self", "Selbst"),
+    ("Set a breakpoint to ask questions at", "Setze einen Haltepunkt, an dem Fragen gestellt werden"),
+    ("Set breakpoint at {}", "Haltepunkt gesetzt bei {}"),
+    ("Set {} from {} to {}", "{} von {} auf {} gesetzt"),
+    ("Settings", "Einstellungen"),
+    ("Shared object or position independent executable", "Gemeinsame Bibliothek oder positionsunabhängige ausführbare Datei"),
+    ("Show Byte Order", "Bytereihenfolge anzeigen"),
+    ("Show Coordinates", "Koordinaten anzeigen"),
+    ("Show Grid", "Gitter anzeigen"),
+    ("Show the bytes of the variable", "Die Bytes der Variable anzeigen"),
+    ("Show the values of the variables assigned on the lines executed in the current function", "Zeigt die Werte der Variablen, die in den ausgeführten Zeilen der aktuellen Funktion zugewiesen werden"),
+    ("Show the variables in this region", "Die Variablen in diesem Bereich anzeigen"),
+    ("Show {} in the Memory window", "{} im Speicherfenster anzeigen"),
+    ("Shown to the other clients connected to the backend while you control the program", "Wird den anderen mit dem Backend verbundenen Clients angezeigt, während du das Programm steuerst"),
+    ("Single-step when continuing and record how often each line is executed", "Beim Fortsetzen einzeln schrittweise ausführen und aufzeichnen, wie oft jede Zeile ausgeführt wird"),
+    ("Size", "Größe"),
+    ("Size of the text in the memory plots, graphs and diagrams", "Größe des Texts in den Speicherplots, Graphen und Diagrammen"),
+    ("Snapshot (binary)", "Snapshot (binär)"),
+    ("Source", "Quellcode"),
+    ("Source lines", "Quellcodezeilen"),
+    ("Stack End Offset", "Versatz des Stackendes"),
+    ("Stack Pointer", "Stackpointer"),
+    ("Start", "Anfang"),
+    ("Status polling interval (s)", "Abfrageintervall des Status (s)"),
+    ("Stay on this file instead of following the current line", "Bei dieser Datei bleiben, statt der aktuellen Zeile zu folgen"),
+    ("Step", "Schritt"),
+    ("Step In", "Hineinspringen"),
+    ("Step Into…", "Hineinspringen in…"),
+    ("Step Out", "Herausspringen"),
+    ("Step Statement", "Anweisung"),
+    ("Step through lines of source code", "Schrittweise durch Quellcodezeilen"),
+    ("Step through single instructions", "Schrittweise durch einzelne Instruktionen"),
+    ("Step through:", "Schrittweise durch:"),
+    ("Step {} of {}", "Schritt {} von {}"),
+    ("Steps and continues", "Schritte und Fortsetzungen"),
+    ("Stop at every call to malloc, calloc, realloc and free", "Bei jedem Aufruf von malloc, calloc, realloc und free anhalten"),
+    ("Stop at every call to read, write, printf, puts, scanf, ...", "Bei jedem Aufruf von read, write, printf, puts, scanf, ... anhalten"),
+    ("Stop at line {}", "In Zeile {} anhalten"),
+    ("Stop in {}", "In {} anhalten"),
+    ("Stop the program if it keeps computing for this long after continuing, e.g. in an infinite loop. 0 disables the timeout.", "Hält das Programm an, wenn es nach dem Fortsetzen so lange weiterrechnet, z. B. in einer Endlosschleife. 0 deaktiviert das Zeitlimit."),
+    ("Stop the program, e.g. if it is waiting for input", "Hält das Programm an, z. B. wenn es auf Eingaben wartet"),
+    ("Stopped at {}", "Angehalten bei {}"),
+    ("Stopped at {}:{}", "Angehalten bei {}:{}"),
+    ("Stopped at {}:{} ({})", "Angehalten bei {}:{} ({})"),
+    ("Stopped outside of the source code", "Außerhalb des Quellcodes angehalten"),
+    ("Symbol table", "Symboltabelle"),
+    ("Symbol tables", "Symboltabellen"),
+    ("Symbols", "Symbole"),
+    ("Target", "Ziel"),
+    ("The byte at the lowest address is the least significant one (little-endian)", "Das Byte an der niedrigsten Adresse ist das niederwertigste (Little-Endian)"),
+    ("The compiler keeps these variables in registers or optimized them out, so they have no address", "Der Compiler hält diese Variablen in Registern oder hat sie wegoptimiert, daher haben sie keine Adresse"),
+    ("The current function has no debug information", "Die aktuelle Funktion hat keine Debuginformationen"),
+    ("The dynamic linker patched the slot with the address of the function", "Der dynamische Linker hat die Adresse der Funktion in den Eintrag geschrieben"),
+    ("The dynamic linker which loads the libraries", "Der dynamische Linker, der die Bibliotheken lädt"),
+    ("The file is missing, showing the source embedded in the debug information", "Die Datei fehlt, angezeigt wird der in den Debuginformationen eingebettete Quelltext"),
+    ("The program doesn't import any functions", "Das Programm importiert keine Funktionen"),
+    ("The program had no heap when the snapshot was taken", "Das Programm hatte keinen Heap, als der Snapshot erstellt wurde"),
+    ("The recent messages of the backend, attach them to bug reports", "Die letzten Meldungen des Backends, hänge sie an Fehlerberichte an"),
+    ("The slot points back into the stub, the first call runs the dynamic linker, which looks the function up and patches the slot", "Der Eintrag zeigt zurück in den Stub, der erste Aufruf startet den dynamischen Linker, der die Funktion sucht und den Eintrag überschreibt"),
+    ("The strings lie above, up to the end of the stack at {}", "Die Zeichenketten liegen darüber, bis zum Ende des Stacks bei {}"),
+    ("These pointers point to memory which can't be read, dereferencing them crashes the program", "Diese Zeiger zeigen auf Speicher, der nicht gelesen werden kann, sie zu dereferenzieren bringt das Programm zum Absturz"),
+    ("Total", "Gesamt"),
+    ("Trace", "Aufzeichnen"),
+    ("Track", "Verfolgen"),
+    ("Tree", "Baum"),
+    ("Try setting breakpoints or continuing the execution.", "Versuche, Haltepunkte zu setzen oder die Ausführung fortzusetzen."),
+    ("Type", "Typ"),
+    ("Type more of the name to see further functions", "Gib mehr vom Namen ein, um weitere Funktionen zu sehen"),
+    ("UI scale: {}%", "UI-Skalierung: {}%"),
+    ("Undefined", "Undefiniert"),
+    ("Undo History", "Rückgängig-Verlauf"),
+    ("Untrack", "Nicht mehr verfolgen"),
+    ("Update your backend: stackium {} doesn't support {}", "Aktualisiere dein Backend: stackium {} unterstützt {} nicht"),
+    ("Value", "Wert"),
+    ("Variables", "Variablen"),
+    ("W writable, A allocated at runtime, X executable, M mergeable, S strings, T thread local", "W beschreibbar, A zur Laufzeit im Speicher, X ausführbar, M zusammenführbar, S Zeichenketten, T threadlokal"),
+    ("Watches", "Überwachungen"),
+    ("What does {} point to?", "Worauf zeigt {}?"),
+    ("What is the value of {}?", "Welchen Wert hat {}?"),
+    ("What the kernel placed at the top of the stack before the program started: the argument count, the argv and envp arrays of pointers to the arguments and environment variables, the auxiliary vector and the strings themselves. The argv parameter of main points here.", "Was der Kernel vor dem Start des Programms oben auf den Stack gelegt hat: die Anzahl der Argumente, die Arrays argv und envp mit Zeigern auf die Argumente und Umgebungsvariablen, den Hilfsvektor und die Zeichenketten selbst. Der Parameter argv von main zeigt hierher."),
+    ("Windows", "Fenster"),
+    ("Wrote {} bytes at {}", "{} Bytes geschrieben bei {}"),
+    ("[{} elements]", "[{} Elemente]"),
+    ("\nline {}", "\nZeile {}"),
+    ("\n↩ {} returned (void)", "\n↩ {} ist zurückgekehrt (void)"),
+    ("\n↩ {} returned a {}, return register: {}", "\n↩ {} hat ein {} zurückgegeben, Rückgaberegister: {}"),
+    ("\n↩ {} returned {} ({})", "\n↩ {} hat {} zurückgegeben ({})"),
+    ("add", "hinzufügen"),
+    ("address", "Adresse"),
+    ("anonymous mapping", "anonymes Mapping"),
+    ("big endian", "Big-Endian"),
+    ("cycle", "Zyklus"),
+    ("disable", "deaktivieren"),
+    ("doubly linked", "doppelt verkettet"),
+    ("enable", "aktivieren"),
+    ("executed {} times", "{}-mal ausgeführt"),
+    ("first {} nodes", "erste {} Knoten"),
+    ("freed", "freigegeben"),
+    ("grew by {} bytes", "um {} Bytes gewachsen"),
+    ("in function", "in Funktion"),
+    ("in use", "belegt"),
+    ("instructions", "Instruktionen"),
+    ("line {}", "Zeile {}"),
+    ("little endian", "Little-Endian"),
+    ("loop body executed {} times in this run", "Schleifenrumpf in diesem Durchlauf {}-mal ausgeführt"),
+    ("made with ♥ by", "mit ♥ gemacht von"),
+    ("malloc header: {} bytes, {}", "malloc-Header: {} Bytes, {}"),
+    ("malloc stores the size of every block in a 16 byte header right before the address it returns. free reads the header in front of the pointer it gets, so freeing a pointer malloc didn't return, e.g. into the middle of a block, reads garbage as the size and corrupts the heap.", "malloc speichert die Größe jedes Blocks in einem 16 Byte großen Header direkt vor der zurückgegebenen Adresse. free liest den Header vor dem übergebenen Zeiger, daher liest das Freigeben eines Zeigers, den malloc nicht zurückgegeben hat, z. B. in die Mitte eines Blocks, Müll als Größe und beschädigt den Heap."),
+    ("new", "neu"),
+    ("none, linked statically", "keiner, statisch gelinkt"),
+    ("show definition", "Definition anzeigen"),
+    ("shrank by {} bytes", "um {} Bytes geschrumpft"),
+    ("singly linked", "einfach verkettet"),
+    ("skip when stepping in", "beim Hineinspringen überspringen"),
+    ("stop", "Halt"),
+    ("timeout ", "Zeitlimit "),
+    ("top chunk", "Top-Chunk"),
+    ("{ } block{}", "{ } Block{}"),
+    ("{} functions", "{} Funktionen"),
+    ("{} is empty", "{} ist leer"),
+    ("{} is not stored in memory", "{} liegt nicht im Speicher"),
+    ("{} nodes", "{} Knoten"),
+    ("{} samples", "{} Stichproben"),
+    ("{} sections", "{} Sektionen"),
+    ("{} segments", "{} Segmente"),
+    ("{} variables", "{} Variablen"),
+    ("{} {{} members}", "{} {{} Elemente}"),
+    ("{} {}, {} nodes", "{} {}, {} Knoten"),
+    ("{}: {} - click to edit", "{}: {} - zum Bearbeiten klicken"),
+    ("{}: {} bytes, {}", "{}: {} Bytes, {}"),
+    ("{}\nstop {}: {}{}", "{}\nHalt {}: {}{}"),
+    ("↻ Restart", "↻ Neustart"),
+    ("↻ Restart Process", "↻ Prozess neu starten"),
+    ("⏳ unresolved", "⏳ nicht aufgelöst"),
+    ("⏴ Overview", "⏴ Übersicht"),
+    ("⏸ Interrupted at {}", "⏸ Unterbrochen bei {}"),
+    ("⏸ Stopped at {}", "⏸ Angehalten bei {}"),
+    ("⏹ Exited with status {}", "⏹ Mit Status {} beendet"),
+    ("⏹ Terminated by {}", "⏹ Durch {} beendet"),
+    ("── not mapped", "── nicht gemappt"),
+    ("▶ Profile", "▶ Profilieren"),
+    ("▶ Started, stopped at {}", "▶ Gestartet, angehalten bei {}"),
+    ("● Breakpoint at {}", "● Haltepunkt bei {}"),
+    ("⚠ Invalid url: {}", "⚠ Ungültige URL: {}"),
+    ("⚠ Optimized with {}", "⚠ Optimiert mit {}"),
+    ("⚠ Possible infinite loop at {} (running for {} s)\n  in {}", "⚠ Mögliche Endlosschleife bei {} (läuft seit {} s)\n  in {}"),
+    ("⚠ Received {} at {}", "⚠ {} empfangen bei {}"),
+    ("⚠ Repainting the UI each frame. FPS: {}", "⚠ Die UI wird in jedem Frame neu gezeichnet. FPS: {}"),
+    ("⚠ The backend doesn't report its capabilities, update your backend", "⚠ Das Backend meldet seine Fähigkeiten nicht, aktualisiere dein Backend"),
+    ("⚠ The backend uses version {} of the API and the UI version {}, update your backend", "⚠ Das Backend nutzt Version {} der API und die Oberfläche Version {}, aktualisiere dein Backend"),
+    ("⚠ The source of {} isn't available, showing the disassembly", "⚠ Der Quelltext von {} ist nicht verfügbar, angezeigt wird die Disassemblierung"),
+    ("⚠ Warnings:", "⚠ Warnungen:"),
+    ("⚠ cycle: the last node links back to [{}]", "⚠ Zyklus: der letzte Knoten verweist zurück auf [{}]"),
+    ("⚠ parsing address as dec", "⚠ Adresse wird dezimal gelesen"),
+    ("⚠ parsing address as hex", "⚠ Adresse wird hexadezimal gelesen"),
+    ("⚠ {} has no debug information: source code and variables are unavailable and functions are read from the symbol table. Compile with -g -gdwarf-4", "⚠ {} hat keine Debug-Informationen: Quellcode und Variablen sind nicht verfügbar und Funktionen werden aus der Symboltabelle gelesen. Kompiliere mit -g -gdwarf-4"),
+    ("⚠ {} was compiled with {}: the compiler keeps variables in registers or removes them and reorders lines, so variables may be missing and steps may jump around. Compile with -O0 -g", "⚠ {} wurde mit {} kompiliert: Der Compiler hält Variablen in Registern oder entfernt sie und ordnet Zeilen um, daher können Variablen fehlen und Schritte springen. Kompiliere mit -O0 -g"),
+    ("✔ Correct, {} is {}", "✔ Richtig, {} ist {}"),
+    ("✔ Exercise completed", "✔ Übung abgeschlossen"),
+    ("✔ resolved", "✔ aufgelöst"),
+    ("✖ Close", "✖ Schließen"),
+    ("✖ Invariant {} broke at {}", "✖ Invariante {} verletzt bei {}"),
+    ("✖ broken link to unmapped {}", "✖ defekter Verweis auf nicht gemappte Adresse {}"),
+    ("✖ {} (unmapped)", "✖ {} (nicht gemappt)"),
+    ("✖ {} {} was removed", "✖ {} {} wurde entfernt"),
+    ("✘ {} is {}, not {}", "✘ {} ist {}, nicht {}"),
+    ("⟲ Arrange", "⟲ Anordnen"),
+    ("⟲ Undo", "⟲ Rückgängig"),
+    ("⤴ taken", "⤴ genommen"),
+    ("⤵ not taken", "⤵ nicht genommen"),
+    ("⬇ Export", "⬇ Exportieren"),
+    ("⬇ Session", "⬇ Sitzung"),
+    ("👁 Read-only demonstration: you can follow the program, only the instructor can control it", "👁 Schreibgeschützte Vorführung: Du kannst dem Programm folgen, nur die Lehrperson kann es steuern"),
+    ("👁 {} changed from {} to {} at {}", "👁 {} hat sich von {} zu {} geändert, bei {}"),
+    ("💬 Ascii", "💬 ASCII"),
+    ("📌 Pin", "📌 Anheften"),
+    ("📍 Locate", "📍 Finden"),
+    ("🔍 What points here?", "🔍 Was zeigt hierher?"),
+    ("🔒 Currently driven by {}, you can follow the program until it is released", "🔒 Gerade gesteuert von {}, du kannst dem Programm folgen, bis die Sitzung freigegeben wird"),
+    ("🔒 You are driving the session, {} other clients follow", "🔒 Du steuerst die Sitzung, {} andere Clients folgen"),
+    ("🔢 Decimal", "🔢 Dezimal"),
+];
@@ -0,0 +1,111 @@
+use egui::RichText;
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, Invariant};
+use url::Url;
+
+use crate::{
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
+
+/// Expressions like `arr[i - 1] <= arr[i]` the debugger stops at once they become false
+pub struct InvariantWindow {
+    backend_url: Url,
+    expression_input: String,
+    invariants: Promise<Result<Vec<Invariant>, String>>,
+}
+
+impl InvariantWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            expression_input: String::new(),
+            invariants: Promise::from_ready(Ok(vec![])),
+        };
+        ret.dirty();
+        ret
+    }
+}
+
+impl DebuggerWindowImpl for InvariantWindow {
+    fn dirty(&mut self) {
+        self.invariants = dispatch!(self.backend_url.clone(), Command::GetInvariants, Invariants);
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.expression_input)
+                    .hint_text("arr[i - 1] <= arr[i]")
+                    .code_editor(),
+            );
+            let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (ui.button(tr(ui.ctx(), "Add")).clicked() || submitted)
+                && !self.expression_input.is_empty()
+            {
+                self.invariants = dispatch!(
+                    self.backend_url.clone(),
+                    Command::AddInvariant(std::mem::take(&mut self.expression_input)),
+                    Invariants
+                );
+            }
+        });
+        ui.label(
+            RichText::new(
+                "Continuing single-steps and stops at the first line where an invariant becomes \
+                 false",
+            )
+            .small()
+            .weak(),
+        );
+        ui.separator();
+        let mut remove = None;
+        match self.invariants.ready() {
+            Some(Ok(invariants)) => {
+                egui::Grid::new("invariants").striped(true).show(ui, |ui| {
+                    for invariant in invariants {
+                        let (symbol, color) = match invariant.holds {
+                            Some(true) => ("✔", ui.visuals().text_color()),
+                            Some(false) => ("✖", ui.visuals().error_fg_color),
+                            None => ("?", ui.visuals().weak_text_color()),
+                        };
+                        ui.label(RichText::new(symbol).color(color));
+                        let label = ui.label(RichText::new(&invariant.expression).monospace());
+                        if let Some(error) = &invariant.error {
+                            label.on_hover_text(trf(
+                                ui.ctx(),
+                                "Not checked at this stop: {}",
+                                &[error],
+                            ));
+                        }
+                        if ui.small_button(tr(ui.ctx(), "Remove")).clicked() {
+                            remove = Some(invariant.expression.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            }
+            Some(Err(err)) => {
+                ui.label(
+                    RichText::new(format!("⚠ {}", err))
+                        .small()
+                        .color(ui.visuals().warn_fg_color),
+                );
+                if ui.small_button("Ok").clicked() {
+                    self.dirty();
+                }
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+        if let Some(expression) = remove {
+            self.invariants = dispatch!(
+                self.backend_url.clone(),
+                Command::RemoveInvariant(expression),
+                Invariants
+            );
+        }
+        false
+    }
+}
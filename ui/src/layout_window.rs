@@ -0,0 +1,294 @@
+use egui::{RichText, Vec2};
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, DiscoveredVariable, MemoryMap, RegionKind};
+use url::Url;
+
+use crate::{
+    command::Cancellable,
+    debugger_window::DebuggerWindowImpl,
+    hexdump_window::HexdumpWindow,
+    i18n::{tr, trf},
+    map_window::kind_color,
+    theme::text_on,
+};
+
+const BLOCK_WIDTH: f32 = 220.0;
+/// Variable names listed next to a block before the rest is summarized
+const MAX_NAMES: usize = 6;
+
+/// Consecutive maps drawn as one block, e.g. all maps of a shared library
+struct Block {
+    from: u64,
+    to: u64,
+    kind: RegionKind,
+    label: String,
+}
+
+/// The whole address space as the textbook diagram: stack at the top, then libraries, heap,
+/// globals and code. Blocks grow with the logarithm of their size so small regions stay
+/// visible. Clicking a block lists its variables and clicking those shows their bytes.
+pub struct LayoutWindow {
+    backend_url: Url,
+    mapping: Promise<Result<Vec<MemoryMap>, String>>,
+    variables: Cancellable<Vec<DiscoveredVariable>>,
+    /// Start address of the block zoomed into
+    zoomed: Option<u64>,
+    /// Bytes of the selected variable or region
+    hexdump: Option<HexdumpWindow>,
+}
+
+impl LayoutWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            mapping: Promise::from_ready(Err(String::new())),
+            variables: Cancellable::from_ready(Ok(vec![])),
+            zoomed: None,
+            hexdump: None,
+        };
+        ret.dirty();
+        ret
+    }
+
+    fn show_bytes(&mut self, address: u64) {
+        let hexdump = self
+            .hexdump
+            .get_or_insert_with(|| HexdumpWindow::new(self.backend_url.clone()));
+        hexdump.goto(address);
+    }
+}
+
+fn blocks(mapping: &[MemoryMap]) -> Vec<Block> {
+    let mut blocks: Vec<Block> = vec![];
+    for map in mapping {
+        if let Some(last) = blocks.last_mut() {
+            let same_library = map.kind == RegionKind::SharedLibrary
+                && last.kind == RegionKind::SharedLibrary
+                && last.label == library_name(map);
+            if same_library && last.to == map.from {
+                last.to = map.to;
+                continue;
+            }
+        }
+        blocks.push(Block {
+            from: map.from,
+            to: map.to,
+            kind: map.kind,
+            label: match map.kind {
+                RegionKind::SharedLibrary => library_name(map),
+                _ => map.label.clone(),
+            },
+        });
+    }
+    blocks
+}
+
+fn library_name(map: &MemoryMap) -> String {
+    map.mapped
+        .rsplit('/')
+        .next()
+        .unwrap_or(&map.mapped)
+        .to_owned()
+}
+
+/// e.g. `132 KiB` or `1.5 GiB`
+fn format_size(size: u64) -> String {
+    let units = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = size as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit + 1 < units.len() {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if size.fract() < 0.05 || size >= 100.0 {
+        format!("{:.0} {}", size, units[unit])
+    } else {
+        format!("{:.1} {}", size, units[unit])
+    }
+}
+
+fn block_height(block: &Block) -> f32 {
+    let pages = ((block.to - block.from) / 4096).max(1) as f32;
+    24.0 + 6.0 * pages.log2()
+}
+
+fn variables_in<'a>(
+    variables: &'a [DiscoveredVariable],
+    block: &Block,
+) -> Vec<&'a DiscoveredVariable> {
+    let mut inside = variables
+        .iter()
+        .filter(|v| v.addr.map_or(false, |a| block.from <= a && a < block.to))
+        .collect::<Vec<_>>();
+    inside.sort_by_key(|v| std::cmp::Reverse(v.addr));
+    inside
+}
+
+fn variable_name(variable: &DiscoveredVariable) -> String {
+    variable.name.clone().unwrap_or("??".to_owned())
+}
+
+impl LayoutWindow {
+    fn render_overview(
+        &mut self,
+        ui: &mut egui::Ui,
+        blocks: &[Block],
+        variables: &[DiscoveredVariable],
+    ) {
+        for (i, block) in blocks.iter().enumerate().rev() {
+            ui.horizontal(|ui| {
+                ui.vertical(|ui| {
+                    ui.monospace(format!("{:#014x}", block.to));
+                    ui.monospace(format!("{:#014x}", block.from));
+                });
+                let fill = kind_color(block.kind).gamma_multiply(0.8);
+                let button = egui::Button::new(
+                    RichText::new(format!(
+                        "{}\n{}",
+                        block.label,
+                        format_size(block.to - block.from)
+                    ))
+                    .color(text_on(fill)),
+                )
+                .fill(fill)
+                .min_size(Vec2::new(BLOCK_WIDTH, block_height(block)));
+                if ui
+                    .add(button)
+                    .on_hover_text(tr(ui.ctx(), "Show the variables in this region"))
+                    .clicked()
+                {
+                    self.zoomed = Some(block.from);
+                }
+                let inside = variables_in(variables, block);
+                let mut names = inside
+                    .iter()
+                    .take(MAX_NAMES)
+                    .map(|v| variable_name(v))
+                    .collect::<Vec<_>>();
+                if inside.len() > MAX_NAMES {
+                    names.push(trf(ui.ctx(), "+{} more", &[&(inside.len() - MAX_NAMES)]));
+                }
+                ui.label(RichText::new(names.join(", ")).monospace());
+            });
+            // unmapped space below the block
+            if let Some(below) = i.checked_sub(1).map(|i| &blocks[i]) {
+                if below.to != block.from {
+                    ui.label(
+                        RichText::new(trf(
+                            ui.ctx(),
+                            "  ⋮ {} unmapped",
+                            &[&format_size(block.from - below.to)],
+                        ))
+                        .small()
+                        .weak(),
+                    );
+                }
+            }
+        }
+    }
+
+    fn render_block(&mut self, ui: &mut egui::Ui, block: &Block, variables: &[DiscoveredVariable]) {
+        ui.horizontal(|ui| {
+            if ui.button(tr(ui.ctx(), "⏴ Overview")).clicked() {
+                self.zoomed = None;
+            }
+            ui.label(
+                RichText::new(&block.label)
+                    .strong()
+                    .color(kind_color(block.kind)),
+            );
+            ui.monospace(format!(
+                "{:#x}..{:#x}, {}",
+                block.from,
+                block.to,
+                format_size(block.to - block.from)
+            ));
+            if ui.button(tr(ui.ctx(), "Bytes")).clicked() {
+                self.show_bytes(block.from);
+            }
+        });
+        let inside = variables_in(variables, block);
+        if inside.is_empty() {
+            ui.label(tr(ui.ctx(), "No variables are stored in this region"));
+        }
+        egui::Grid::new("layout_variables")
+            .striped(true)
+            .show(ui, |ui| {
+                for variable in inside {
+                    let Some(address) = variable.addr else {
+                        continue;
+                    };
+                    if ui
+                        .link(RichText::new(variable_name(variable)).monospace())
+                        .on_hover_text(tr(ui.ctx(), "Show the bytes of the variable"))
+                        .clicked()
+                    {
+                        self.show_bytes(address);
+                    }
+                    ui.monospace(format!("{:#x}", address));
+                    ui.monospace(format!("+{:#x}", address - block.from));
+                    let size = variable.memory.as_ref().map_or(0, |m| m.len());
+                    ui.label(format_size(size as u64));
+                    ui.end_row();
+                }
+            });
+    }
+}
+
+impl DebuggerWindowImpl for LayoutWindow {
+    fn dirty(&mut self) {
+        self.mapping = dispatch!(self.backend_url.clone(), Command::Maps, Maps);
+        self.variables = dispatch_cancellable!(
+            self.backend_url.clone(),
+            Command::DiscoverVariables,
+            DiscoveredVariables
+        );
+        if let Some(hexdump) = &mut self.hexdump {
+            hexdump.dirty();
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let blocks = match self.mapping.ready() {
+            Some(Ok(mapping)) => blocks(mapping),
+            Some(Err(err)) => {
+                ui.label(err);
+                return false;
+            }
+            None => {
+                ui.spinner();
+                return false;
+            }
+        };
+        let variables = match self.variables.ready() {
+            Some(Ok(variables)) => variables.clone(),
+            _ => vec![],
+        };
+        let mut is_dirty = false;
+        egui::ScrollArea::vertical()
+            .id_salt("layout")
+            .max_height(if self.hexdump.is_some() {
+                ui.available_height() / 2.0
+            } else {
+                f32::INFINITY
+            })
+            .show(ui, |ui| {
+                match self
+                    .zoomed
+                    .and_then(|from| blocks.iter().find(|b| b.from == from))
+                {
+                    Some(block) => self.render_block(ui, block, &variables),
+                    None => self.render_overview(ui, &blocks, &variables),
+                }
+            });
+        if let Some(hexdump) = &mut self.hexdump {
+            ui.separator();
+            if ui.small_button(tr(ui.ctx(), "Hide bytes")).clicked() {
+                self.hexdump = None;
+            } else {
+                is_dirty = hexdump.ui(ui);
+            }
+        }
+        is_dirty
+    }
+}
@@ -3,20 +3,42 @@
 mod app;
 #[macro_use]
 mod command;
+mod action_window;
+mod address;
 mod breakpoint_window;
+mod capabilities;
 mod code_window;
+mod config;
 mod control_window;
 mod debugger_window;
+mod driver;
+mod exercise_panel;
 mod frame_history;
 mod graph_window;
+mod hexdump_window;
+mod history_window;
+mod i18n;
+mod invariant_window;
+mod layout_window;
+mod list_window;
 mod location;
 mod map_window;
 mod memory_window;
+mod plt_window;
+mod profile_window;
+mod quiz_window;
 mod register_window;
+mod replay_view;
+mod scope_window;
+mod selection;
 mod settings_window;
+mod snapshot_view;
 mod syntax_highlighting;
+mod theme;
 mod toggle;
+mod tree_window;
 mod variable_window;
+mod watch_window;
 pub use app::StackiumApp;
 mod rotated_plot_text;
 
@@ -0,0 +1,323 @@
+use egui::{Align2, Color32, Pos2, Rect, RichText, Sense, Stroke, Vec2};
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, DataStructure, NodeLink, StructureKind};
+use url::Url;
+
+use crate::{
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+    theme::monospace,
+    tree_window::{node_size, node_text},
+};
+
+/// Horizontal space between nodes the arrows are drawn in
+const GAP: f32 = 56.0;
+const INDEX_HEIGHT: f32 = 18.0;
+/// Space below the chain for the arrow of a cycle
+const BELOW: f32 = 40.0;
+
+/// How the walk along the `next` links ended
+enum ListEnd {
+    Null,
+    /// The last node points back to the node with this index
+    Cycle(usize),
+    /// A pointer into unmapped memory
+    Broken(u64),
+    /// More nodes than the debugger reads
+    Truncated,
+}
+
+/// A linked list reached from a variable drawn as a horizontal chain, showing where it ends
+pub struct ListWindow {
+    backend_url: Url,
+    structures: Promise<Result<Vec<DataStructure>, String>>,
+    /// Variable of the shown list, the first list if `None`
+    selected: Option<String>,
+}
+
+impl ListWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            structures: Promise::from_ready(Ok(vec![])),
+            selected: None,
+        };
+        ret.dirty();
+        ret
+    }
+}
+
+fn link<'a>(list: &'a DataStructure, index: usize, member: &str) -> Option<&'a NodeLink> {
+    list.nodes[index].links.iter().find(|l| l.member == member)
+}
+
+fn list_end(list: &DataStructure, next: &str) -> ListEnd {
+    // a list is empty if the variable itself is null or broken
+    let Some(last) = list.nodes.len().checked_sub(1) else {
+        return match list.address {
+            0 => ListEnd::Null,
+            address => ListEnd::Broken(address),
+        };
+    };
+    match link(list, last, next) {
+        Some(NodeLink {
+            node: Some(target), ..
+        }) => ListEnd::Cycle(*target),
+        Some(NodeLink { address: 0, .. }) | None => ListEnd::Null,
+        Some(NodeLink {
+            address,
+            mapped: false,
+            ..
+        }) => ListEnd::Broken(*address),
+        Some(_) => ListEnd::Truncated,
+    }
+}
+
+fn arrow(painter: &egui::Painter, from: Pos2, to: Pos2, color: Color32) {
+    painter.arrow(from, to - from, Stroke::new(2.0, color));
+}
+
+fn render_list(ui: &mut egui::Ui, list: &DataStructure, next: &str, prev: Option<&str>) {
+    let text = list.nodes.iter().map(node_text).collect::<Vec<_>>();
+    let node_size = node_size(ui.ctx(), &text);
+    let (response, painter) = ui.allocate_painter(
+        Vec2::new(
+            (list.nodes.len() + 1) as f32 * (node_size.x + GAP),
+            INDEX_HEIGHT + node_size.y + BELOW,
+        ),
+        Sense::hover(),
+    );
+    let origin = response.rect.min;
+    let rect_of = |i: usize| {
+        Rect::from_min_size(
+            origin + Vec2::new(i as f32 * (node_size.x + GAP), INDEX_HEIGHT),
+            node_size,
+        )
+    };
+    let font = monospace(ui.ctx(), 12.0);
+    let text_color = ui.visuals().text_color();
+    let error_color = ui.visuals().error_fg_color;
+    let next_y = node_size.y / 3.0;
+    let prev_y = node_size.y * 2.0 / 3.0;
+    for (i, node) in list.nodes.iter().enumerate() {
+        let rect = rect_of(i);
+        painter.rect(
+            rect,
+            4.0,
+            ui.visuals().extreme_bg_color,
+            Stroke::new(2.0, text_color),
+        );
+        painter.text(
+            rect.center(),
+            Align2::CENTER_CENTER,
+            &text[i],
+            font.clone(),
+            text_color,
+        );
+        painter.text(
+            rect.center_top(),
+            Align2::CENTER_BOTTOM,
+            format!("[{}]", i),
+            font.clone(),
+            ui.visuals().weak_text_color(),
+        );
+        ui.interact(rect, ui.id().with(node.address), Sense::hover())
+            .on_hover_text(format!("{} @ {:#x}", list.node_type, node.address));
+        if i + 1 < list.nodes.len() {
+            arrow(
+                &painter,
+                rect.right_top() + Vec2::new(0.0, next_y),
+                rect_of(i + 1).left_top() + Vec2::new(0.0, next_y),
+                text_color,
+            );
+        }
+        // every prev has to point to the node before, the first node may belong to a longer
+        // list
+        let Some(prev) = prev.and_then(|p| link(list, i, p)) else {
+            continue;
+        };
+        let expected = i.checked_sub(1);
+        if prev.node.is_some() && prev.node == expected {
+            arrow(
+                &painter,
+                rect.left_top() + Vec2::new(0.0, prev_y),
+                rect_of(i - 1).right_top() + Vec2::new(0.0, prev_y),
+                text_color,
+            );
+        } else if expected.is_some() || (prev.address != 0 && !prev.mapped) {
+            let from = rect.left_top() + Vec2::new(0.0, prev_y);
+            arrow(
+                &painter,
+                from,
+                from - Vec2::new(GAP / 2.0, 0.0),
+                error_color,
+            );
+            painter.text(
+                from - Vec2::new(GAP / 2.0, -4.0),
+                Align2::LEFT_TOP,
+                match prev.node {
+                    Some(node) => format!("{} → [{}]", prev.member, node),
+                    None => format!("{} ✖ {:#x}", prev.member, prev.address),
+                },
+                font.clone(),
+                error_color,
+            );
+        }
+    }
+    let end = list_end(list, next);
+    let (from, end_pos) = match list.nodes.len() {
+        0 => {
+            let start = origin + Vec2::new(0.0, INDEX_HEIGHT + next_y);
+            (start, start)
+        }
+        n => (
+            rect_of(n - 1).right_top() + Vec2::new(0.0, next_y),
+            rect_of(n - 1).right_top() + Vec2::new(GAP, next_y),
+        ),
+    };
+    match end {
+        ListEnd::Null => {
+            if !list.nodes.is_empty() {
+                arrow(&painter, from, end_pos, ui.visuals().weak_text_color());
+            }
+            painter.text(
+                end_pos + Vec2::new(4.0, 0.0),
+                Align2::LEFT_CENTER,
+                "NULL",
+                font,
+                ui.visuals().weak_text_color(),
+            );
+        }
+        ListEnd::Truncated => {
+            arrow(&painter, from, end_pos, text_color);
+            painter.text(end_pos, Align2::LEFT_CENTER, " …", font, text_color);
+        }
+        ListEnd::Broken(address) => {
+            if !list.nodes.is_empty() {
+                arrow(&painter, from, end_pos, error_color);
+            }
+            painter.text(
+                end_pos + Vec2::new(4.0, 0.0),
+                Align2::LEFT_CENTER,
+                trf(ui.ctx(), "✖ {} (unmapped)", &[&format!("{:#x}", address)]),
+                font,
+                error_color,
+            );
+        }
+        ListEnd::Cycle(target) => {
+            let warn_color = ui.visuals().warn_fg_color;
+            let last = rect_of(list.nodes.len() - 1).center_bottom();
+            let target = rect_of(target).center_bottom();
+            let bottom = last.y + BELOW / 2.0;
+            painter.line_segment(
+                [last, Pos2::new(last.x, bottom)],
+                Stroke::new(2.0, warn_color),
+            );
+            painter.line_segment(
+                [Pos2::new(last.x, bottom), Pos2::new(target.x, bottom)],
+                Stroke::new(2.0, warn_color),
+            );
+            arrow(&painter, Pos2::new(target.x, bottom), target, warn_color);
+            painter.text(
+                Pos2::new((last.x + target.x) / 2.0, bottom),
+                Align2::CENTER_TOP,
+                tr(ui.ctx(), "cycle"),
+                font,
+                warn_color,
+            );
+        }
+    }
+}
+
+impl DebuggerWindowImpl for ListWindow {
+    fn dirty(&mut self) {
+        self.structures = dispatch!(
+            self.backend_url.clone(),
+            Command::GetDataStructures,
+            DataStructures
+        );
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let lists = match self.structures.ready() {
+            Some(Ok(structures)) => structures
+                .iter()
+                .filter(|s| matches!(s.kind, StructureKind::LinkedList { .. }))
+                .collect::<Vec<_>>(),
+            Some(Err(err)) => {
+                ui.label(err);
+                return false;
+            }
+            None => {
+                ui.spinner();
+                return false;
+            }
+        };
+        let Some(list) = lists
+            .iter()
+            .find(|l| self.selected.as_ref() == Some(&l.variable))
+            .or(lists.first())
+        else {
+            ui.label(tr(
+                ui.ctx(),
+                "No variable of the current function points to a linked list",
+            ));
+            return false;
+        };
+        let StructureKind::LinkedList { next, prev } = &list.kind else {
+            return false;
+        };
+        let mut selected = list.variable.clone();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("list_variable")
+                .selected_text(&selected)
+                .show_ui(ui, |ui| {
+                    for l in lists.iter() {
+                        ui.selectable_value(&mut selected, l.variable.clone(), &l.variable);
+                    }
+                });
+            let kind = if prev.is_some() {
+                tr(ui.ctx(), "doubly linked")
+            } else {
+                tr(ui.ctx(), "singly linked")
+            };
+            ui.label(
+                RichText::new(trf(
+                    ui.ctx(),
+                    "{} {}, {} nodes",
+                    &[&kind, &list.node_type, &list.nodes.len()],
+                ))
+                .weak(),
+            );
+            match list_end(list, next) {
+                ListEnd::Cycle(target) => {
+                    ui.label(
+                        RichText::new(trf(
+                            ui.ctx(),
+                            "⚠ cycle: the last node links back to [{}]",
+                            &[&target],
+                        ))
+                        .color(ui.visuals().warn_fg_color),
+                    );
+                }
+                ListEnd::Broken(address) => {
+                    ui.label(
+                        RichText::new(trf(
+                            ui.ctx(),
+                            "✖ broken link to unmapped {}",
+                            &[&format!("{:#x}", address)],
+                        ))
+                        .color(ui.visuals().error_fg_color),
+                    );
+                }
+                ListEnd::Null | ListEnd::Truncated => {}
+            }
+        });
+        ui.separator();
+        egui::ScrollArea::horizontal().show(ui, |ui| {
+            render_list(ui, list, next, prev.as_deref());
+        });
+        self.selected = Some(selected);
+        false
+    }
+}
@@ -2,7 +2,10 @@ use poll_promise::Promise;
 use stackium_shared::{Command, CommandOutput, Location};
 use url::Url;
 
-use crate::debugger_window::DebuggerWindowImpl;
+use crate::{
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
 
 pub struct LocationWindow {
     location: Promise<Result<Location, String>>,
@@ -21,13 +24,23 @@ impl DebuggerWindowImpl for LocationWindow {
         match self.location.ready() {
             Some(location) => match location {
                 Ok(location) => {
-                    ui.label(format!("Current file: {}", location.file));
-                    ui.label(format!("Location: {}:{}", location.line, location.column))
+                    ui.label(trf(ui.ctx(), "Current file: {}", &[&location.file]));
+                    ui.label(trf(
+                        ui.ctx(),
+                        "Location: {}:{}",
+                        &[&location.line, &location.column],
+                    ))
                 }
                 Err(err) => {
                     if err.contains("NoSource") {
-                        ui.label("No source code available for the current state of the process");
-                        ui.label("Try setting breakpoints or continuing the execution.")
+                        ui.label(tr(
+                            ui.ctx(),
+                            "No source code available for the current state of the process",
+                        ));
+                        ui.label(tr(
+                            ui.ctx(),
+                            "Try setting breakpoints or continuing the execution.",
+                        ))
                     } else {
                         ui.label(err)
                     }
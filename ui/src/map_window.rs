@@ -1,12 +1,17 @@
-use egui::RichText;
+use egui::{Color32, RichText};
 use poll_promise::Promise;
-use stackium_shared::{Command, CommandOutput, MemoryMap};
+use stackium_shared::{Command, CommandOutput, MapChange, MapChangeKind, MemoryMap, RegionKind};
 use url::Url;
 
-use crate::debugger_window::DebuggerWindowImpl;
+use crate::{
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
 
 pub struct MapWindow {
     mapping: Promise<Result<Vec<MemoryMap>, String>>,
+    /// Regions which changed since the previous stop
+    changes: Promise<Result<Vec<MapChange>, String>>,
     backend_url: Url,
 }
 
@@ -14,6 +19,7 @@ impl MapWindow {
     pub fn new(backend_url: Url) -> Self {
         let mut ret = Self {
             mapping: Promise::from_ready(Err(String::new())),
+            changes: Promise::from_ready(Err(String::new())),
             backend_url,
         };
         ret.dirty();
@@ -24,12 +30,35 @@ impl MapWindow {
 impl DebuggerWindowImpl for MapWindow {
     fn dirty(&mut self) {
         self.mapping = dispatch!(self.backend_url.clone(), Command::Maps, Maps);
+        self.changes = dispatch!(self.backend_url.clone(), Command::MapsDiff, MapChanges);
     }
     fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let changes = match self.changes.ready() {
+            Some(Ok(changes)) => changes.as_slice(),
+            _ => &[],
+        };
         ui.vertical(|ui| match self.mapping.ready() {
             Some(mapping) => match mapping {
                 Ok(mapping) => {
                     egui::ScrollArea::vertical().show(ui, |ui| {
+                        for change in changes {
+                            if let MapChangeKind::Removed = change.change {
+                                ui.label(
+                                    RichText::new(trf(
+                                        ui.ctx(),
+                                        "✖ {} {} was removed",
+                                        &[
+                                            &change.map.label,
+                                            &format!(
+                                                "{:#x}..{:#x}",
+                                                change.map.from, change.map.to
+                                            ),
+                                        ],
+                                    ))
+                                    .color(ui.visuals().error_fg_color),
+                                );
+                            }
+                        }
                         for i in (0..mapping.len()).rev() {
                             let map = &mapping[i];
                             let connected = if i > 0 {
@@ -52,7 +81,43 @@ impl DebuggerWindowImpl for MapWindow {
                                     b(map.write, "w"),
                                     b(map.execute, "x")
                                 ));
-                                ui.label(&map.mapped);
+                                ui.label(RichText::new(&map.label).color(kind_color(map.kind)))
+                                    .on_hover_text(&map.mapped);
+                                if let Some(change) = changes
+                                    .iter()
+                                    .find(|c| c.map.from == map.from && c.map.to == map.to)
+                                {
+                                    let text = match change.change {
+                                        MapChangeKind::Added => tr(ui.ctx(), "new").to_owned(),
+                                        MapChangeKind::Resized {
+                                            previous_from,
+                                            previous_to,
+                                        } => {
+                                            let previous = previous_to - previous_from;
+                                            let current = map.to - map.from;
+                                            if current >= previous {
+                                                trf(
+                                                    ui.ctx(),
+                                                    "grew by {} bytes",
+                                                    &[&(current - previous)],
+                                                )
+                                            } else {
+                                                trf(
+                                                    ui.ctx(),
+                                                    "shrank by {} bytes",
+                                                    &[&(previous - current)],
+                                                )
+                                            }
+                                        }
+                                        MapChangeKind::Removed => return,
+                                    };
+                                    ui.label(
+                                        RichText::new(format!("★ {}", text))
+                                            .strong()
+                                            .color(ui.visuals().warn_fg_color),
+                                    )
+                                    .on_hover_text(tr(ui.ctx(), "Changed since the previous stop"));
+                                }
                             });
                             if !connected {
                                 ui.separator();
@@ -71,3 +136,16 @@ impl DebuggerWindowImpl for MapWindow {
         false
     }
 }
+
+/// Color of the label of a region, regions of the program stand out
+pub fn kind_color(kind: RegionKind) -> Color32 {
+    match kind {
+        RegionKind::Stack => Color32::from_rgb(0x4c, 0xaf, 0x50),
+        RegionKind::Heap => Color32::from_rgb(0xff, 0x98, 0x00),
+        RegionKind::Text => Color32::from_rgb(0x21, 0x96, 0xf3),
+        RegionKind::ReadOnlyData | RegionKind::Data | RegionKind::Bss => {
+            Color32::from_rgb(0x9c, 0x27, 0xb0)
+        }
+        _ => Color32::GRAY,
+    }
+}
@@ -1,18 +1,27 @@
-use egui::{Align, Align2, Color32, RichText, Stroke, Vec2, Vec2b};
+use egui::{Align2, Color32, RichText, Stroke, Vec2, Vec2b, WidgetInfo, WidgetType};
 use egui_plot::{Arrows, Plot};
-use egui_plot::{Line, PlotPoint, PlotPoints, PlotUi, Polygon, Text, VLine};
+use egui_plot::{Line, LineStyle, PlotPoint, PlotPoints, PlotUi, Polygon, Text, VLine};
 use poll_promise::Promise;
 use stackium_shared::{
-    Command, CommandOutput, DiscoveredVariable, Registers, VARIABLE_MEM_PADDING,
+    get_byte_size, Command, CommandOutput, DataType, DiscoveredVariable, MallocChunk, MemoryChunk,
+    PointerInfo, ProcessStack, Reference, Registers, StackFrame, TypeName, Variable,
+    VARIABLE_MEM_PADDING,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::ops::Range;
 use url::Url;
 
 use crate::LimitStringLen;
 use crate::{
-    command::dispatch_command_and_then, debugger_window::DebuggerWindowImpl,
-    rotated_plot_text::RotText, variable_window::get_byte_size,
+    address::AddressResolver,
+    command::{dispatch_command_and_then, Cancellable},
+    config::{Config, NumberBase},
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+    rotated_plot_text::RotText,
+    scope_window::{flatten_scopes, format_value},
+    selection::{Message, Selection},
+    theme::{line_style, palette, Theme},
 };
 
 #[derive(PartialEq, Copy, Clone)]
@@ -24,26 +33,125 @@ enum DataVisualization {
 
 pub struct MemoryWindow {
     backend_url: Url,
-    variables: Promise<Result<Vec<DiscoveredVariable>, String>>,
+    variables: Cancellable<Vec<DiscoveredVariable>>,
     registers: Promise<Result<Registers, String>>,
+    frames: Promise<Result<Vec<StackFrame>, String>>,
+    /// Live variables without an address, e.g. kept in registers by optimized binaries
+    register_variables: Promise<Result<Vec<Variable>, String>>,
     grid: bool,
     coordinates: bool,
+    /// Shows how the bytes of 2, 4 and 8 byte values combine to the little-endian value
+    byte_order: bool,
     cached_addresses: Option<Vec<u64>>,
+    /// Bytes shown around every variable when `cached_addresses` was computed
+    memory_padding: u64,
     data_visualization: DataVisualization,
+    /// Configured base `data_visualization` was last set from
+    number_base: Option<NumberBase>,
     first_base_pointer: Option<u64>,
+    /// Memory of the variables at the previous stop, used to highlight changes
+    previous_memory: HashMap<u64, u8>,
+    /// Address (hex) for the "what points here?" query
+    reference_query: String,
+    references: Option<Promise<Result<Vec<Reference>, String>>>,
+    /// Pointer expression, e.g. `array + 3`
+    pointer_expression: String,
+    pointer_info: Option<Promise<Result<PointerInfo, String>>>,
+    addresses: AddressResolver,
+    /// Memory around pointer targets no variable covers, by target address
+    pointer_targets: HashMap<u64, Promise<Result<MemoryChunk, String>>>,
+    /// Number of loaded pointer targets included in `cached_addresses`
+    loaded_pointer_targets: usize,
+    /// Variable selected in another window, its bytes are outlined
+    selection: Option<Selection>,
+    /// Hides the memory while questions of the quiz are pending
+    quiz_pending: bool,
+    process_stack: Promise<Result<ProcessStack, String>>,
 }
 
 impl MemoryWindow {
+    fn find_references(&mut self) {
+        if let Ok(addr) = u64::from_str_radix(self.reference_query.trim_start_matches("0x"), 16) {
+            self.references = Some(dispatch!(
+                self.backend_url.clone(),
+                Command::FindReferences(addr),
+                References
+            ));
+        }
+    }
+
+    fn locate_pointer(&mut self) {
+        self.pointer_info = Some(dispatch!(
+            self.backend_url.clone(),
+            Command::PointerInfo(self.pointer_expression.clone()),
+            PointerInfo
+        ));
+    }
+
+    /// Reads the memory around pointers which point neither into the stack nor into a
+    /// variable, so their arrows don't point into nowhere
+    fn request_pointer_targets(
+        &mut self,
+        variables: &[DiscoveredVariable],
+        stack_range: &Range<u64>,
+    ) {
+        let mut targets = vec![];
+        for variable in variables {
+            if let Some(address) = variable.addr {
+                pointer_values(variable, variable.type_index, address, &mut targets);
+            }
+        }
+        let covered = |target: u64| {
+            stack_range.contains(&target)
+                || variables.iter().any(|v| match (v.addr, &v.memory) {
+                    (Some(address), Some(memory)) => {
+                        let start = address - VARIABLE_MEM_PADDING;
+                        target >= start && target < start + memory.len() as u64
+                    }
+                    _ => false,
+                })
+        };
+        for target in targets {
+            if target == 0 || covered(target) || self.pointer_targets.len() >= MAX_POINTER_TARGETS {
+                continue;
+            }
+            let backend_url = self.backend_url.clone();
+            self.pointer_targets.entry(target).or_insert_with(|| {
+                dispatch!(
+                    backend_url,
+                    Command::ReadAround(target, POINTER_TARGET_CONTEXT, POINTER_TARGET_CONTEXT),
+                    MemoryChunk
+                )
+            });
+        }
+    }
+
     pub fn new(backend_url: Url) -> Self {
         let mut ret = Self {
+            addresses: AddressResolver::new(backend_url.clone()),
             backend_url,
-            variables: Promise::from_ready(Err(String::new())),
+            variables: Cancellable::from_ready(Err(String::new())),
             registers: Promise::from_ready(Err(String::new())),
+            frames: Promise::from_ready(Err(String::new())),
+            register_variables: Promise::from_ready(Err(String::new())),
             grid: false,
             coordinates: false,
+            byte_order: false,
             cached_addresses: None,
+            memory_padding: VARIABLE_MEM_PADDING,
             data_visualization: DataVisualization::Hex,
+            number_base: None,
             first_base_pointer: None,
+            previous_memory: HashMap::new(),
+            reference_query: String::new(),
+            references: None,
+            pointer_expression: String::new(),
+            pointer_info: None,
+            pointer_targets: HashMap::new(),
+            loaded_pointer_targets: 0,
+            selection: None,
+            quiz_pending: false,
+            process_stack: Promise::from_ready(Err(String::new())),
         };
         ret.dirty();
         ret
@@ -53,25 +161,26 @@ impl MemoryWindow {
 const ADDR_SPACING: f32 = 1.0f32;
 const ADDR_LENGTH: f32 = 5.5f32;
 const BAR_THICKNESS: f64 = 1.0f64;
+/// Where the pointer arrows turn towards their target
+const ARROWS_HOME_POS: f64 = 35f64;
 
-const COLORS: [egui::Color32; 6] = [
-    egui::Color32::from_rgb(0x00, 0x00, 0xff),
-    egui::Color32::from_rgb(0x00, 0xff, 0x00),
-    egui::Color32::from_rgb(0xff, 0x00, 0x00),
-    egui::Color32::from_rgb(0x00, 0xff, 0xff),
-    egui::Color32::from_rgb(0xff, 0x00, 0xff),
-    egui::Color32::from_rgb(0xff, 0xff, 0x00),
-];
+/// The message of an error of the backend like `UnreadableAddress("0x10 is not mapped")`
+fn error_message(err: &str) -> &str {
+    match (err.find('"'), err.rfind('"')) {
+        (Some(start), Some(end)) if start < end => &err[start + 1..end],
+        _ => err,
+    }
+}
 
 fn render_pointer_arrow(
     ui: &mut PlotUi,
     start: PlotPoint,
     end: PlotPoint,
     color: &egui::Color32,
+    style: LineStyle,
     arrow_counter: &mut i32,
     is_invalid: bool,
 ) {
-    const ARROWS_HOME_POS: f64 = 35f64;
     const ARROWS_HOME_OFFSET: f64 = 1.0;
     const ARROWS_END_OFFSET: f64 = 7f64;
 
@@ -89,7 +198,8 @@ fn render_pointer_arrow(
                     start.y,
                 ],
             ]))
-            .color(*color),
+            .color(*color)
+            .style(style),
         );
 
         ui.text(
@@ -113,6 +223,33 @@ fn render_pointer_arrow(
 
     let tip_length = text_size(ui);
 
+    if style != LineStyle::Solid {
+        // arrows can't be patterned, so only the tip is drawn as one
+        let tip = end.x + ARROWS_END_OFFSET;
+        ui.line(
+            Line::new(PlotPoints::new(vec![
+                [start.x, start.y],
+                [arrow_home, start.y],
+                [arrow_home, end.y],
+                [tip, end.y],
+            ]))
+            .color(*color)
+            .style(style)
+            .highlight(true),
+        );
+        ui.arrows(
+            Arrows::new(
+                PlotPoints::new(vec![[tip + 0.1, end.y]]),
+                PlotPoints::new(vec![[tip, end.y]]),
+            )
+            .tip_length(tip_length)
+            .color(*color)
+            .highlight(true),
+        );
+        *arrow_counter += 1;
+        return;
+    }
+
     ui.arrows(
         Arrows::new(
             PlotPoints::new(vec![[start.x, start.y]]),
@@ -153,13 +290,21 @@ fn render_type(
     offset: usize,
     name_override: Option<String>,
     address: u64,
-    color_override: Option<egui::Color32>,
+    palette_index: Option<usize>,
     arrow_counter: &mut i32,
+    byte_order: bool,
 ) {
-    let color = color_override.unwrap_or(COLORS[address as usize % COLORS.len()]);
+    // members and elements are drawn like the variable they belong to
+    let palette_index = palette_index.unwrap_or(address as usize);
+    let color = palette(ui.ctx(), palette_index);
+    let style = line_style(ui.ctx(), palette_index);
     let multiplier = if initial_bar { 2.5 } else { 1.0 };
     if let (Some(name), Some(memory)) = (&variable.name, &variable.memory) {
-        let name = name_override.unwrap_or(name.clone());
+        let name = match (&name_override, variable.warnings.is_empty()) {
+            // the warnings are listed below the plot
+            (None, false) if type_index == variable.type_index => format!("⚠ {}", name),
+            _ => name_override.unwrap_or(name.clone()),
+        };
         let mut position = addr_to_pos(address, &stack_range, Some(addresses));
         const BAR_PADDING: f64 = 0.2;
         position.x += BAR_THICKNESS * !initial_bar as u32 as f64
@@ -175,7 +320,8 @@ fn render_type(
                 [position.x + BAR_THICKNESS * multiplier, position.y + dest],
                 [position.x + BAR_THICKNESS * multiplier, position.y],
             ]))
-            .stroke(Stroke::new(1.0, color)),
+            .stroke(Stroke::new(1.0, color))
+            .style(style),
         );
         ui.add(RotText::new(
             name.limit_string_len(get_byte_size(&variable.types, type_index) as usize * 2),
@@ -187,12 +333,58 @@ fn render_type(
             ),
             None,
         ));
+        // Decoded string of `char[]`/`char*` variables next to the bar and pointer arrow
+        if let (true, Some(string)) = (type_index == variable.type_index, &variable.string) {
+            ui.text(
+                Text::new(
+                    PlotPoint::new(
+                        position.x + BAR_THICKNESS * multiplier + BAR_PADDING,
+                        position.y + dest / 2.0,
+                    ),
+                    RichText::new(string.limit_string_len(64))
+                        .font(egui::FontId {
+                            size: text_size(ui),
+                            family: egui::FontFamily::Monospace,
+                        })
+                        .color(color),
+                )
+                .anchor(Align2::LEFT_CENTER),
+            );
+        }
         match &variable.types.0[type_index].1 {
             stackium_shared::TypeName::Name {
                 name: _,
                 byte_size: _,
-            } => {}
+            } => {
+                if byte_order {
+                    render_byte_order(
+                        ui,
+                        variable,
+                        type_index,
+                        address,
+                        PlotPoint::new(
+                            position.x + BAR_THICKNESS * multiplier + BAR_PADDING,
+                            position.y,
+                        ),
+                        color,
+                    );
+                }
+            }
             stackium_shared::TypeName::Arr { arr_type, count } => {
+                if count.len() >= 2 {
+                    render_array_grid(
+                        ui,
+                        variable,
+                        memory,
+                        *arr_type,
+                        count,
+                        addresses,
+                        stack_range,
+                        &name,
+                        address,
+                        color,
+                    );
+                }
                 for i in 0..count.iter().fold(1, |acc, e| acc * *e) {
                     render_type(
                         ui,
@@ -202,10 +394,11 @@ fn render_type(
                         addresses,
                         stack_range,
                         offset + 1,
-                        Some(format!("{}[{}]", name, i)),
+                        Some(format!("{}{}", name, array_index(count, i))),
                         address + get_byte_size(&variable.types, *arr_type) as u64 * i as u64,
-                        Some(color),
+                        Some(palette_index),
                         arrow_counter,
+                        byte_order,
                     );
                 }
             }
@@ -218,7 +411,15 @@ fn render_type(
                         .expect("slice with incorrect length"),
                 );
                 let ptr_dst = addr_to_pos(ptr_val, &stack_range, Some(addresses));
-                render_pointer_arrow(ui, position, ptr_dst, &color, arrow_counter, ptr_val == 0);
+                render_pointer_arrow(
+                    ui,
+                    position,
+                    ptr_dst,
+                    &color,
+                    style,
+                    arrow_counter,
+                    ptr_val == 0,
+                );
             }
             stackium_shared::TypeName::ProductType {
                 name: _,
@@ -236,8 +437,9 @@ fn render_type(
                         offset + 1,
                         Some(name.clone()),
                         address + *member_offset as u64,
-                        Some(color),
+                        Some(palette_index),
                         arrow_counter,
+                        byte_order,
                     );
                 }
             }
@@ -245,6 +447,207 @@ fn render_type(
     }
 }
 
+/// Draws the bytes of a 2, 4 or 8 byte value as one little-endian word right of `start`, the
+/// position of its first byte, with arrows from every byte to its place in the word, followed
+/// by the decoded value
+fn render_byte_order(
+    ui: &mut PlotUi,
+    variable: &DiscoveredVariable,
+    type_index: usize,
+    address: u64,
+    start: PlotPoint,
+    color: egui::Color32,
+) {
+    const PAIR_WIDTH: f64 = 1.2;
+    const WORD_OFFSET: f64 = 3.0;
+    let size = get_byte_size(&variable.types, type_index);
+    if ![2, 4, 8].contains(&size) {
+        return;
+    }
+    let (Some(base), Some(memory)) = (variable.addr, &variable.memory) else {
+        return;
+    };
+    let index = (address - (base - VARIABLE_MEM_PADDING)) as usize;
+    let Some(bytes) = memory.get(index..index + size) else {
+        return;
+    };
+    let font = egui::FontId {
+        size: text_size(ui),
+        family: egui::FontFamily::Monospace,
+    };
+    let word_y = start.y + size as f64 * ADDR_SPACING as f64 / 2.0;
+    let word_x = start.x + WORD_OFFSET;
+    ui.text(
+        Text::new(
+            PlotPoint::new(word_x - PAIR_WIDTH, word_y),
+            RichText::new("0x").font(font.clone()).color(color),
+        )
+        .anchor(Align2::LEFT_CENTER),
+    );
+    for (i, byte) in bytes.iter().enumerate() {
+        // the most significant byte is written first but stored last
+        let pair_x = word_x + (size - 1 - i) as f64 * PAIR_WIDTH;
+        let byte_y = start.y + (i as f64 + 0.5) * ADDR_SPACING as f64;
+        let tip_y = if byte_y < word_y {
+            word_y - 0.4
+        } else {
+            word_y + 0.4
+        };
+        ui.arrows(
+            Arrows::new(
+                PlotPoints::new(vec![[start.x, byte_y]]),
+                PlotPoints::new(vec![[pair_x + PAIR_WIDTH / 2.0, tip_y]]),
+            )
+            .tip_length(text_size(ui) * 0.5)
+            .color(color),
+        );
+        ui.text(
+            Text::new(
+                PlotPoint::new(pair_x, word_y),
+                RichText::new(format!("{:02x}", byte))
+                    .font(font.clone())
+                    .color(color),
+            )
+            .anchor(Align2::LEFT_CENTER),
+        );
+    }
+    ui.text(
+        Text::new(
+            PlotPoint::new(word_x + size as f64 * PAIR_WIDTH + 0.5, word_y),
+            RichText::new(format!(
+                "= {}",
+                format_element(&variable.types, type_index, bytes)
+            ))
+            .font(font),
+        )
+        .anchor(Align2::LEFT_CENTER),
+    );
+}
+
+/// Row-major index of the `i`-th element of an array with the dimensions `count`, e.g. `[1][2]`
+fn array_index(count: &[usize], mut i: usize) -> String {
+    let mut indices = vec![];
+    for dimension in count.iter().rev() {
+        indices.push(i % dimension.max(&1));
+        i /= dimension.max(&1);
+    }
+    indices
+        .iter()
+        .rev()
+        .map(|i| format!("[{}]", i))
+        .collect::<String>()
+}
+
+/// Decodes a scalar value, aggregates are shown as `..` and values cut off by the end of
+/// `bytes` as `??`
+pub fn format_element(types: &DataType, type_index: usize, bytes: &[u8]) -> String {
+    match &types.0[type_index].1 {
+        stackium_shared::TypeName::Name { .. } => {
+            let size = get_byte_size(types, type_index);
+            bytes
+                .get(..size)
+                .and_then(|bytes| stackium_shared::format_value(types, type_index, bytes))
+                .unwrap_or("??".to_owned())
+        }
+        _ => "..".to_owned(),
+    }
+}
+
+/// Renders a multi-dimensional array as a grid left of the memory column. Every row of the
+/// grid lines up with the bytes it occupies to visualize the row-major layout.
+fn render_array_grid(
+    ui: &mut PlotUi,
+    variable: &DiscoveredVariable,
+    memory: &Vec<u8>,
+    element_type: usize,
+    count: &Vec<usize>,
+    addresses: &Vec<u64>,
+    stack_range: &Range<u64>,
+    name: &str,
+    address: u64,
+    color: egui::Color32,
+) {
+    const CELL_WIDTH: f64 = 4.0;
+    const GRID_MARGIN: f64 = 2.0;
+    let columns = *count.last().unwrap();
+    let rows = count.iter().fold(1, |acc, e| acc * *e) / columns.max(1);
+    let element_size = get_byte_size(&variable.types, element_type) as u64;
+    let base_addr = variable.addr.unwrap() - VARIABLE_MEM_PADDING;
+    let x_end = addr_to_pos(address, stack_range, Some(addresses)).x - GRID_MARGIN;
+    let x_start = x_end - CELL_WIDTH * columns as f64;
+    let font = egui::FontId {
+        size: text_size(ui),
+        family: egui::FontFamily::Monospace,
+    };
+    for row in 0..rows {
+        let row_addr = address + row as u64 * columns as u64 * element_size;
+        let bottom = addr_to_pos(row_addr, stack_range, Some(addresses)).y;
+        let top = bottom + (columns as u64 * element_size) as f64 * ADDR_SPACING as f64;
+        ui.text(
+            Text::new(
+                PlotPoint::new(x_start - 0.3, (bottom + top) / 2.0),
+                RichText::new(array_index(&count[..count.len() - 1], row))
+                    .font(font.clone())
+                    .color(color),
+            )
+            .anchor(Align2::RIGHT_CENTER),
+        );
+        for column in 0..columns {
+            let x = x_start + column as f64 * CELL_WIDTH;
+            ui.polygon(
+                Polygon::new(PlotPoints::new(vec![
+                    [x, bottom],
+                    [x, top],
+                    [x + CELL_WIDTH, top],
+                    [x + CELL_WIDTH, bottom],
+                ]))
+                .fill_color(color.gamma_multiply(if row % 2 == 0 { 0.1 } else { 0.25 }))
+                .stroke(Stroke::new(1.0, color)),
+            );
+            let mem_index = (row_addr + column as u64 * element_size - base_addr) as usize;
+            if let Some(bytes) = memory.get(mem_index..mem_index + element_size as usize) {
+                ui.text(
+                    Text::new(
+                        PlotPoint::new(x + CELL_WIDTH / 2.0, (bottom + top) / 2.0),
+                        RichText::new(format_element(&variable.types, element_type, bytes))
+                            .font(font.clone()),
+                    )
+                    .anchor(Align2::CENTER_CENTER),
+                );
+            }
+        }
+    }
+    let bottom = addr_to_pos(address, stack_range, Some(addresses)).y;
+    for column in 0..columns {
+        ui.text(
+            Text::new(
+                PlotPoint::new(x_start + (column as f64 + 0.5) * CELL_WIDTH, bottom - 0.3),
+                RichText::new(format!("[{}]", column))
+                    .font(font.clone())
+                    .color(color),
+            )
+            .anchor(Align2::CENTER_TOP),
+        );
+    }
+    ui.text(
+        Text::new(
+            PlotPoint::new(x_start, bottom - 1.5),
+            RichText::new(name).font(font).color(color),
+        )
+        .anchor(Align2::LEFT_TOP),
+    );
+}
+
+/// Start address and bytes of a variable with only `padding` of the bytes the backend reads
+/// around it
+fn padded_memory(address: u64, memory: &[u8], padding: u64) -> (u64, &[u8]) {
+    let trim = VARIABLE_MEM_PADDING.saturating_sub(padding) as usize;
+    match memory.get(trim..memory.len().saturating_sub(trim)) {
+        Some(memory) => (address - VARIABLE_MEM_PADDING + trim as u64, memory),
+        None => (address - VARIABLE_MEM_PADDING, memory),
+    }
+}
+
 fn render_variable(
     variable: &DiscoveredVariable,
     addresses: &Vec<u64>,
@@ -253,6 +656,8 @@ fn render_variable(
     initial_bar: bool,
     arrow_counter: &mut i32,
     visualization_style: DataVisualization,
+    previous_memory: &HashMap<u64, u8>,
+    byte_order: bool,
 ) {
     if let (Some(address), Some(name), Some(memory)) =
         (variable.addr, &variable.name, &variable.memory)
@@ -269,40 +674,442 @@ fn render_variable(
             address,
             None,
             arrow_counter,
+            byte_order,
         );
-        for (i, byte) in memory.iter().enumerate() {
-            let addr = address - VARIABLE_MEM_PADDING + i as u64;
-            let mut byte_pos = addr_to_pos(addr, &stack_range, Some(addresses));
-            byte_pos.x += ADDR_LENGTH as f64;
-            byte_pos.y += 0.5f64;
-            ui.text(
-                Text::new(
-                    byte_pos,
-                    RichText::new(match visualization_style {
-                        DataVisualization::Hex => format!("{:02x}", byte),
-                        DataVisualization::Ascii => {
-                            if *byte >= 0x20 && *byte <= 0x7e {
-                                format!("'{}'", *byte as char)
-                            } else if *byte == 0 {
-                                format!("'\\0")
-                            } else {
-                                format!("...")
+        let (start, memory) = padded_memory(address, memory, Config::get(ui.ctx()).memory_padding);
+        render_bytes(
+            ui,
+            start,
+            memory,
+            addresses,
+            &stack_range,
+            visualization_style,
+            previous_memory,
+        );
+    }
+}
+
+/// Draws the bytes of `memory` next to their addresses
+fn render_bytes(
+    ui: &mut PlotUi,
+    start: u64,
+    memory: &[u8],
+    addresses: &Vec<u64>,
+    stack_range: &Range<u64>,
+    visualization_style: DataVisualization,
+    previous_memory: &HashMap<u64, u8>,
+) {
+    for (i, byte) in memory.iter().enumerate() {
+        let addr = start + i as u64;
+        let mut byte_pos = addr_to_pos(addr, stack_range, Some(addresses));
+        byte_pos.x += ADDR_LENGTH as f64;
+        byte_pos.y += 0.5f64;
+        let changed = previous_memory.get(&addr).map_or(false, |old| old != byte);
+        let text = RichText::new(match visualization_style {
+            DataVisualization::Hex => format!("{:02x}", byte),
+            DataVisualization::Ascii => {
+                if *byte >= 0x20 && *byte <= 0x7e {
+                    format!("'{}'", *byte as char)
+                } else if *byte == 0 {
+                    format!("'\\0")
+                } else {
+                    format!("...")
+                }
+            }
+            DataVisualization::Decimal => format!("{:03}", *byte),
+        })
+        .font(egui::FontId {
+            size: text_size(ui),
+            family: egui::FontFamily::Monospace,
+        });
+        // Highlight bytes which changed since the last stop
+        let text = if changed {
+            let color = ui.ctx().style().visuals.warn_fg_color;
+            text.color(color)
+                .background_color(color.gamma_multiply(0.25))
+                .strong()
+        } else {
+            text
+        };
+        ui.text(Text::new(byte_pos, text).anchor(Align2::LEFT_CENTER));
+    }
+}
+
+/// Draws the memory around a pointer target which isn't covered by any variable, labeled with
+/// the region it belongs to
+fn render_pointer_target(
+    ui: &mut PlotUi,
+    chunk: &MemoryChunk,
+    addresses: &Vec<u64>,
+    stack_range: &Range<u64>,
+    visualization_style: DataVisualization,
+    previous_memory: &HashMap<u64, u8>,
+) {
+    render_bytes(
+        ui,
+        chunk.from,
+        &chunk.memory,
+        addresses,
+        stack_range,
+        visualization_style,
+        previous_memory,
+    );
+    let first = addr_to_pos(chunk.from, stack_range, Some(addresses));
+    let last = addr_to_pos(
+        chunk.from + (chunk.memory.len() as u64).max(1) - 1,
+        stack_range,
+        Some(addresses),
+    );
+    ui.text(
+        Text::new(
+            PlotPoint::new(
+                first.x + ADDR_LENGTH as f64 * 2.0,
+                (first.y + last.y + 1.0) / 2.0,
+            ),
+            RichText::new(&chunk.region)
+                .font(egui::FontId {
+                    size: text_size(ui),
+                    family: egui::FontFamily::Monospace,
+                })
+                .weak(),
+        )
+        .anchor(Align2::LEFT_CENTER),
+    );
+}
+
+/// Values of all pointers in the variable, following the same structure `render_type` draws
+fn pointer_values(
+    variable: &DiscoveredVariable,
+    type_index: usize,
+    address: u64,
+    values: &mut Vec<u64>,
+) {
+    let (Some(base), Some(memory)) = (variable.addr, &variable.memory) else {
+        return;
+    };
+    match &variable.types.0[type_index].1 {
+        stackium_shared::TypeName::Name { .. } => {}
+        stackium_shared::TypeName::Arr { arr_type, count } => {
+            for i in 0..count.iter().product::<usize>() {
+                pointer_values(
+                    variable,
+                    *arr_type,
+                    address + get_byte_size(&variable.types, *arr_type) as u64 * i as u64,
+                    values,
+                );
+            }
+        }
+        stackium_shared::TypeName::Ref { .. } => {
+            let index = (address - (base - VARIABLE_MEM_PADDING)) as usize;
+            if let Some(bytes) = memory.get(index..index + 8) {
+                values.push(u64::from_le_bytes(bytes.try_into().unwrap()));
+            }
+        }
+        stackium_shared::TypeName::ProductType { members, .. } => {
+            for (_, member_type_index, member_offset) in members {
+                pointer_values(
+                    variable,
+                    *member_type_index,
+                    address + *member_offset as u64,
+                    values,
+                );
+            }
+        }
+    }
+}
+
+/// At most this many pointer targets are loaded, the others point into nowhere
+const MAX_POINTER_TARGETS: usize = 32;
+/// Bytes loaded before and after a pointer target
+const POINTER_TARGET_CONTEXT: u64 = 16;
+
+const LOAD_POS: f64 = 20f64;
+/// `prev_size` and `size` malloc stores in front of a block
+const MALLOC_HEADER_SIZE: u64 = 16;
+
+/// Inverse of [`addr_to_pos`]
+fn pos_to_addr(pos: PlotPoint, stack_range: &Range<u64>, addresses: &[u64]) -> Option<u64> {
+    if pos.y < 0.0 {
+        return None;
+    }
+    let row = (pos.y / ADDR_SPACING as f64) as u64;
+    if pos.x < LOAD_POS {
+        Some(stack_range.start + row).filter(|addr| stack_range.contains(addr))
+    } else {
+        addresses.get(row as usize).copied()
+    }
+}
+
+/// Outlines `size` bytes starting at `address`
+fn render_highlight(
+    ui: &mut PlotUi,
+    address: u64,
+    size: u64,
+    stack_range: &Range<u64>,
+    addresses: &Vec<u64>,
+    color: Color32,
+) {
+    let start = addr_to_pos(address, stack_range, Some(addresses));
+    let end = addr_to_pos(address + size.max(1) - 1, stack_range, Some(addresses));
+    ui.polygon(
+        Polygon::new(PlotPoints::new(vec![
+            [start.x, start.y],
+            [start.x, end.y + ADDR_SPACING as f64],
+            [start.x + ADDR_LENGTH as f64, end.y + ADDR_SPACING as f64],
+            [start.x + ADDR_LENGTH as f64, start.y],
+        ]))
+        .fill_color(color.gamma_multiply(0.2))
+        .stroke(Stroke::new(2.0, color)),
+    );
+}
+
+/// Outlines the pointers found by the "what points here?" query
+fn render_references(
+    ui: &mut PlotUi,
+    references: &[Reference],
+    stack_range: &Range<u64>,
+    addresses: &Vec<u64>,
+) {
+    let color = ui.ctx().style().visuals.warn_fg_color;
+    for address in references.iter().filter_map(|r| r.address) {
+        render_highlight(ui, address, 8, stack_range, addresses, color);
+    }
+}
+
+/// Lists `argc`, `argv`, `envp` and the auxiliary vector the kernel placed above the frame of
+/// `main`, with the strings they point to
+fn render_process_stack(ui: &mut egui::Ui, stack: &ProcessStack) {
+    let response =
+        egui::CollapsingHeader::new(trf(ui.ctx(), "Process stack: argc = {}", &[&stack.argc]))
+            .id_salt("process_stack")
+            .show(ui, |ui| {
+                egui::Grid::new("process_stack_grid")
+                    .striped(true)
+                    .show(ui, |ui| {
+                        let row = |ui: &mut egui::Ui, address: u64, name: String, value: String| {
+                            ui.label(RichText::new(format!("{:#x}", address)).monospace().weak());
+                            ui.label(RichText::new(name).monospace());
+                            ui.label(RichText::new(value).monospace());
+                            ui.end_row();
+                        };
+                        row(ui, stack.start, "argc".to_owned(), stack.argc.to_string());
+                        for (name, strings) in [("argv", &stack.argv), ("envp", &stack.envp)] {
+                            for (i, string) in strings.iter().enumerate() {
+                                row(
+                                    ui,
+                                    string.address,
+                                    format!("{}[{}]", name, i),
+                                    format!("{:#x} → {}", string.pointer, string.value),
+                                );
                             }
                         }
-                        DataVisualization::Decimal => format!("{:03}", *byte),
-                    })
-                    .font(egui::FontId {
-                        size: text_size(ui),
-                        family: egui::FontFamily::Monospace,
-                    }),
-                )
-                .anchor(Align2::LEFT_CENTER),
+                        for entry in stack.auxv.iter() {
+                            let value = match &entry.string {
+                                Some(string) => format!("{:#x} → {}", entry.value, string),
+                                None => format!("{:#x}", entry.value),
+                            };
+                            row(
+                                ui,
+                                entry.address,
+                                entry.name.clone().unwrap_or(entry.key.to_string()),
+                                value,
+                            );
+                        }
+                    });
+                ui.label(
+                    RichText::new(trf(
+                        ui.ctx(),
+                        "The strings lie above, up to the end of the stack at {}",
+                        &[&format!("{:#x}", stack.end)],
+                    ))
+                    .weak(),
+                );
+            });
+    response.header_response.on_hover_text(tr(ui.ctx(), "What the kernel placed at the top of the stack before the program started: the argument count, the argv and envp arrays of pointers to the arguments and environment variables, the auxiliary vector and the strings themselves. The argv parameter of main points here."));
+}
+
+/// Whether malloc handed out the chunk, read from the header of the next chunk
+fn chunk_state(ctx: &egui::Context, chunk: &MallocChunk) -> &'static str {
+    match chunk.in_use {
+        Some(true) => tr(ctx, "in use"),
+        Some(false) => tr(ctx, "freed"),
+        None => tr(ctx, "top chunk"),
+    }
+}
+
+/// Outlines the header malloc keeps in the 16 bytes before a heap block, if they are shown
+fn render_malloc_header(
+    ui: &mut PlotUi,
+    chunk: &MallocChunk,
+    stack_range: &Range<u64>,
+    addresses: &Vec<u64>,
+) {
+    let header = chunk.address - MALLOC_HEADER_SIZE;
+    if !addresses.contains(&header) || !addresses.contains(&(chunk.address - 1)) {
+        return;
+    }
+    let color = ui.ctx().style().visuals.warn_fg_color;
+    let start = addr_to_pos(header, stack_range, Some(addresses));
+    let end = addr_to_pos(chunk.address - 1, stack_range, Some(addresses));
+    ui.polygon(
+        Polygon::new(PlotPoints::new(vec![
+            [start.x, start.y],
+            [start.x, end.y + ADDR_SPACING as f64],
+            [start.x + ADDR_LENGTH as f64, end.y + ADDR_SPACING as f64],
+            [start.x + ADDR_LENGTH as f64, start.y],
+        ]))
+        .fill_color(color.gamma_multiply(0.1))
+        .stroke(Stroke::new(1.0, color))
+        .style(LineStyle::dashed_dense()),
+    );
+    ui.text(
+        Text::new(
+            PlotPoint::new(start.x - 0.5, (start.y + end.y + ADDR_SPACING as f64) / 2.0),
+            RichText::new(trf(
+                ui.ctx(),
+                "malloc header: {} bytes, {}",
+                &[&chunk.size, &chunk_state(ui.ctx(), chunk)],
+            ))
+            .font(egui::FontId {
+                size: text_size(ui),
+                family: egui::FontFamily::Monospace,
+            })
+            .color(color),
+        )
+        .anchor(Align2::RIGHT_CENTER),
+    );
+}
+
+/// A pointer stored in a variable or in one of its members or elements
+struct Pointer {
+    /// The variable, member or element holding the pointer, e.g. `node.next`
+    source: String,
+    /// Where the pointer is stored
+    address: u64,
+    /// Description of where it points to, see [`describe_target`]
+    target: String,
+}
+
+impl std::fmt::Display for Pointer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} → {}", self.source, self.target)
+    }
+}
+
+/// Every pointer stored in `variables`
+fn find_pointers(variables: &[DiscoveredVariable]) -> Vec<Pointer> {
+    let mut pointers = vec![];
+    for variable in variables {
+        if let (Some(name), Some(address)) = (&variable.name, variable.addr) {
+            collect_pointers(
+                variable,
+                variable.type_index,
+                name.clone(),
+                address,
+                variables,
+                &mut pointers,
             );
         }
     }
+    pointers
 }
 
-const LOAD_POS: f64 = 20f64;
+fn collect_pointers(
+    variable: &DiscoveredVariable,
+    type_index: usize,
+    name: String,
+    address: u64,
+    variables: &[DiscoveredVariable],
+    pointers: &mut Vec<Pointer>,
+) {
+    let (Some(base), Some(memory)) = (variable.addr, &variable.memory) else {
+        return;
+    };
+    match &variable.types.0[type_index].1 {
+        TypeName::Ref { .. } => {
+            let index = (address - (base - VARIABLE_MEM_PADDING)) as usize;
+            if let Some(bytes) = memory.get(index..index + 8) {
+                let value =
+                    u64::from_le_bytes(bytes.try_into().expect("slice with incorrect length"));
+                pointers.push(Pointer {
+                    source: name,
+                    address,
+                    target: describe_target(variables, value),
+                });
+            }
+        }
+        TypeName::Arr { arr_type, count } => {
+            let size = get_byte_size(&variable.types, *arr_type) as u64;
+            for i in 0..count.iter().product::<usize>() {
+                collect_pointers(
+                    variable,
+                    *arr_type,
+                    format!("{}{}", name, array_index(count, i)),
+                    address + size * i as u64,
+                    variables,
+                    pointers,
+                );
+            }
+        }
+        TypeName::ProductType { members, .. } => {
+            for (member, member_type_index, member_offset) in members {
+                collect_pointers(
+                    variable,
+                    *member_type_index,
+                    format!("{}.{}", name, member),
+                    address + *member_offset as u64,
+                    variables,
+                    pointers,
+                );
+            }
+        }
+        TypeName::Name { .. } => {}
+    }
+}
+
+/// The variable `target` points into, e.g. `arr + 8 (0x7ffc1208)`, or just the address if it
+/// doesn't point into a variable
+fn describe_target(variables: &[DiscoveredVariable], target: u64) -> String {
+    if target == 0 {
+        return "NULL".to_owned();
+    }
+    let variable = variables.iter().find_map(|v| {
+        let address = v.addr?;
+        let size = (get_byte_size(&v.types, v.type_index) as u64).max(1);
+        (address..address + size)
+            .contains(&target)
+            .then_some((v.name.as_deref()?, target - address))
+    });
+    match variable {
+        Some((name, 0)) => format!("{} ({:#x})", name, target),
+        Some((name, offset)) => format!("{} + {} ({:#x})", name, offset, target),
+        None => format!("{:#x}", target),
+    }
+}
+
+/// Writes the source and destination of every pointer onto its arrow
+fn render_pointer_labels(
+    ui: &mut PlotUi,
+    pointers: &[Pointer],
+    stack_range: &Range<u64>,
+    addresses: &Vec<u64>,
+) {
+    for pointer in pointers {
+        let position = addr_to_pos(pointer.address, stack_range, Some(addresses));
+        ui.text(
+            Text::new(
+                PlotPoint::new(ARROWS_HOME_POS - 0.5, position.y),
+                RichText::new(pointer.to_string())
+                    .font(egui::FontId {
+                        size: text_size(ui),
+                        family: egui::FontFamily::Monospace,
+                    })
+                    .color(ui.ctx().style().visuals.strong_text_color()),
+            )
+            .anchor(Align2::RIGHT_BOTTOM),
+        );
+    }
+}
 
 fn addr_to_pos(address: u64, stack_range: &Range<u64>, addresses: Option<&Vec<u64>>) -> PlotPoint {
     if address < stack_range.start || address >= stack_range.end {
@@ -325,7 +1132,8 @@ fn text_size(plot_ui: &PlotUi) -> f32 {
         .transform()
         .rect_from_values(&[0.0, 0.0].into(), &[1.0, 1.0].into())
         .size()
-        * 0.7;
+        * 0.7
+        * Theme::get(plot_ui.ctx()).text_scale;
     scale.max_elem().clamp(0.001, 250.0)
 }
 
@@ -353,6 +1161,45 @@ fn render_category(ui: &mut PlotUi, category: &str, rect: [PlotPoint; 2]) {
     );
 }
 
+/// Draws a band labeled with the function name next to the stack for every frame
+fn render_frames(ui: &mut PlotUi, frames: &[StackFrame], stack_range: &Range<u64>) {
+    const BAND_X: f64 = -1.5;
+    const BAND_WIDTH: f64 = 0.5;
+    for (index, frame) in frames.iter().enumerate() {
+        let from = frame.from.max(stack_range.start);
+        let to = frame.to.min(stack_range.end);
+        if to <= from {
+            continue;
+        }
+        let bottom = addr_to_pos(from, stack_range, None).y;
+        let top = (to - stack_range.start) as f64 * ADDR_SPACING as f64;
+        let color = palette(ui.ctx(), index);
+        ui.add(
+            Polygon::new(PlotPoints::new(vec![
+                [BAND_X, bottom],
+                [BAND_X, top],
+                [BAND_X + BAND_WIDTH, top],
+                [BAND_X + BAND_WIDTH, bottom],
+            ]))
+            .fill_color(color.linear_multiply(0.3))
+            .stroke(Stroke::new(1.0, color))
+            .style(line_style(ui.ctx(), index)),
+        );
+        ui.text(
+            Text::new(
+                PlotPoint::new(BAND_X - BAND_WIDTH, (bottom + top) / 2.0),
+                RichText::new(frame.function.as_deref().unwrap_or("??"))
+                    .font(egui::FontId {
+                        size: text_size(ui),
+                        family: egui::FontFamily::Monospace,
+                    })
+                    .color(color),
+            )
+            .anchor(Align2::RIGHT_CENTER),
+        );
+    }
+}
+
 fn render_addresses(ui: &mut PlotUi, stack_range: &Range<u64>, addresses: &Vec<u64>) {
     if stack_range.end <= stack_range.start {
         return;
@@ -400,33 +1247,99 @@ fn render_addresses(ui: &mut PlotUi, stack_range: &Range<u64>, addresses: &Vec<u
 
 impl DebuggerWindowImpl for MemoryWindow {
     fn dirty(&mut self) {
-        self.variables = dispatch!(
+        if let Some(Ok(variables)) = self.variables.ready() {
+            self.previous_memory = variables
+                .iter()
+                .filter_map(|v| Some((v.addr? - VARIABLE_MEM_PADDING, v.memory.as_ref()?)))
+                .flat_map(|(start, memory)| {
+                    memory
+                        .iter()
+                        .enumerate()
+                        .map(move |(i, byte)| (start + i as u64, *byte))
+                })
+                .collect();
+        }
+        self.variables = dispatch_cancellable!(
             self.backend_url.clone(),
             Command::DiscoverVariables,
             DiscoveredVariables
         );
         self.registers = dispatch!(self.backend_url.clone(), Command::GetRegister, Registers);
+        self.frames = dispatch!(
+            self.backend_url.clone(),
+            Command::GetStackFrames,
+            StackFrames
+        );
+        self.register_variables = dispatch_command_and_then(
+            self.backend_url.clone(),
+            Command::GetVariableScopes,
+            |output| match output {
+                CommandOutput::VariableScopes(scopes) => flatten_scopes(scopes)
+                    .into_iter()
+                    .filter(|v| v.in_scope && v.addr.is_none())
+                    .collect(),
+                _ => unreachable!(),
+            },
+        );
+        self.process_stack = dispatch!(
+            self.backend_url.clone(),
+            Command::GetProcessStack,
+            ProcessStack
+        );
         self.cached_addresses = None;
+        self.addresses.clear();
+        self.pointer_targets.clear();
+        self.loaded_pointer_targets = 0;
+    }
+    fn receive(&mut self, message: &Message) {
+        match message {
+            Message::Select(selection) => self.selection = selection.clone(),
+            Message::Locate(expression) => {
+                self.pointer_expression = expression.clone();
+                self.locate_pointer();
+            }
+            Message::QuizPending(pending) => self.quiz_pending = *pending,
+            Message::Show(_) => {}
+        }
     }
+
     fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        if self.quiz_pending {
+            ui.label(tr(
+                ui.ctx(),
+                "Answer the questions in the Quiz window to reveal the memory",
+            ));
+            return false;
+        }
         let mut should_zoom_factor = 1f32;
+        // the bytes are shown in the configured base until the user picks another one
+        let number_base = Config::get(ui.ctx()).number_base;
+        if self.number_base != Some(number_base) {
+            self.number_base = Some(number_base);
+            self.data_visualization = match number_base {
+                NumberBase::Hexadecimal => DataVisualization::Hex,
+                NumberBase::Decimal => DataVisualization::Decimal,
+            };
+        }
         ui.horizontal(|ui| {
-            ui.checkbox(&mut self.grid, "Show Grid");
-            ui.checkbox(&mut self.coordinates, "Show Coordinates");
+            ui.checkbox(&mut self.grid, tr(ui.ctx(), "Show Grid"));
+            ui.checkbox(&mut self.coordinates, tr(ui.ctx(), "Show Coordinates"));
+            ui.checkbox(&mut self.byte_order, tr(ui.ctx(), "Show Byte Order"))
+                .on_hover_text(tr(ui.ctx(), "The byte at the lowest address is the least significant one (little-endian)"));
             ui.selectable_value(
                 &mut self.data_visualization,
                 DataVisualization::Hex,
-                "⬢ Hex",
+                tr(ui.ctx(), "⬢ Hex"),
             );
             ui.selectable_value(
                 &mut self.data_visualization,
                 DataVisualization::Ascii,
-                "💬 Ascii",
+                tr(ui.ctx(), "💬 Ascii"),
             );
             ui.selectable_value(
                 &mut self.data_visualization,
                 DataVisualization::Decimal,
-                "🔢 Decimal",
+                tr(ui.ctx(), "🔢 Decimal"),
             );
             if ui.button(RichText::new("-").monospace()).clicked() {
                 should_zoom_factor = 0.8;
@@ -434,12 +1347,251 @@ impl DebuggerWindowImpl for MemoryWindow {
             if ui.button(RichText::new("+").monospace()).clicked() {
                 should_zoom_factor = 1.2;
             }
+            ui.separator();
+            let query = ui.add(
+                egui::TextEdit::singleline(&mut self.reference_query)
+                    .hint_text(tr(ui.ctx(), "address"))
+                    .desired_width(110.0),
+            );
+            if ui
+                .button(tr(ui.ctx(), "🔍 What points here?"))
+                .on_hover_text(tr(ui.ctx(), "Lists all variables and registers pointing into this address, click into the plot to select an address"))
+                .clicked()
+                || (query.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+            {
+                self.find_references();
+            }
+            ui.menu_button(tr(ui.ctx(), "⬇ Export"), |ui| {
+                for (label, path) in [
+                    ("JSON", "/export/variables.json"),
+                    ("CSV", "/export/variables.csv"),
+                    ("Snapshot (JSON)", "/export/snapshot.json"),
+                    ("Snapshot (binary)", "/export/snapshot.json.gz"),
+                    ("Report (HTML)", "/export/report.html"),
+                    ("Report (Markdown)", "/export/report.md"),
+                ] {
+                    if let Ok(url) = self.backend_url.join(path) {
+                        ui.hyperlink_to(tr(ui.ctx(), label), url);
+                    }
+                }
+            });
+        });
+        ui.horizontal(|ui| {
+            let expression = ui.add(
+                egui::TextEdit::singleline(&mut self.pointer_expression)
+                    .hint_text("array + 3")
+                    .desired_width(110.0),
+            );
+            if ui
+                .button(tr(ui.ctx(), "📍 Locate"))
+                .on_hover_text(tr(ui.ctx(), "Evaluates an address or pointer expression like `array + 3`, `ptr - 1` or `&var` and highlights where it points to"))
+                .clicked()
+                || (expression.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)))
+            {
+                self.locate_pointer();
+            }
+            match self.pointer_info.as_ref().map(|p| p.ready()) {
+                Some(Some(Ok(info))) => {
+                    let mut text = self.addresses.format(info.address);
+                    if let (Some(element), Some(element_address)) =
+                        (&info.element, info.element_address)
+                    {
+                        text += &format!(" → {}", element);
+                        if info.address != element_address {
+                            text += &format!(" + {}", info.address - element_address);
+                        }
+                    }
+                    match &info.region {
+                        Some(region) => {
+                            text += &trf(
+                                ui.ctx(),
+                                " in {} ({}{}{})",
+                                &[
+                                    &if region.mapped.is_empty() {
+                                        tr(ui.ctx(), "anonymous mapping")
+                                    } else {
+                                        &region.mapped
+                                    },
+                                    &if region.read { "r" } else { "-" },
+                                    &if region.write { "w" } else { "-" },
+                                    &if region.execute { "x" } else { "-" },
+                                ],
+                            )
+                        }
+                        None => text += tr(ui.ctx(), " (not mapped)"),
+                    };
+                    ui.label(RichText::new(text).monospace());
+                }
+                Some(Some(Err(err))) => {
+                    ui.label(RichText::new(err).color(ui.visuals().error_fg_color));
+                }
+                Some(None) => {
+                    ui.spinner();
+                }
+                None => {}
+            }
         });
+        if let Some(references) = &self.references {
+            ui.horizontal_wrapped(|ui| match references.ready() {
+                Some(Ok(references)) if references.is_empty() => {
+                    ui.label(tr(ui.ctx(), "Nothing points here"));
+                }
+                Some(Ok(references)) => {
+                    ui.label(tr(ui.ctx(), "Pointed to by:"));
+                    for reference in references {
+                        match reference.address {
+                            Some(address) => ui.label(
+                                RichText::new(format!(
+                                    "{} @ {} → {:#x}",
+                                    reference.name,
+                                    self.addresses.format(address),
+                                    reference.value
+                                ))
+                                .monospace(),
+                            ),
+                            None => ui.label(
+                                RichText::new(format!(
+                                    "${} → {:#x}",
+                                    reference.name, reference.value
+                                ))
+                                .monospace(),
+                            ),
+                        };
+                    }
+                }
+                Some(Err(err)) => {
+                    ui.label(err);
+                }
+                None => {
+                    ui.spinner();
+                }
+            });
+        }
+        match self.register_variables.ready() {
+            Some(Ok(variables)) if !variables.is_empty() => {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new(tr(ui.ctx(), "Not in memory:")).strong()).on_hover_text(
+                        tr(ui.ctx(), "The compiler keeps these variables in registers or optimized them out, so they have no address"),
+                    );
+                    for variable in variables {
+                        ui.label(
+                            RichText::new(format!(
+                                "{} = {}",
+                                variable.name.as_deref().unwrap_or("??"),
+                                format_value(variable)
+                            ))
+                            .monospace()
+                            .background_color(ui.visuals().extreme_bg_color),
+                        );
+                    }
+                });
+            }
+            _ => {}
+        }
+        if let Some(Ok(variables)) = self.variables.ready() {
+            // pointers whose target the backend refused to read
+            let mut invalid = vec![];
+            for variable in variables {
+                let (Some(address), Some(name)) = (variable.addr, &variable.name) else {
+                    continue;
+                };
+                let mut targets = vec![];
+                pointer_values(variable, variable.type_index, address, &mut targets);
+                for target in targets {
+                    if let Some(Err(err)) =
+                        self.pointer_targets.get(&target).and_then(|t| t.ready())
+                    {
+                        invalid.push((name, target, err));
+                    }
+                }
+            }
+            if !invalid.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new(tr(ui.ctx(), "Invalid pointers:")).strong()).on_hover_text(
+                        tr(ui.ctx(), "These pointers point to memory which can't be read, dereferencing them crashes the program"),
+                    );
+                    for (name, target, err) in invalid {
+                        ui.label(
+                            RichText::new(format!("{} → {:#x}", name, target))
+                                .monospace()
+                                .color(ui.visuals().error_fg_color),
+                        )
+                        .on_hover_text(error_message(err));
+                    }
+                });
+            }
+        }
+        // older backends can't read it and the section is left out
+        if let Some(Ok(stack)) = self.process_stack.ready() {
+            render_process_stack(ui, stack);
+        }
+        if let Some(Ok(variables)) = self.variables.ready() {
+            let warnings = variables
+                .iter()
+                .flat_map(|v| v.warnings.iter().map(move |w| (v.name.as_deref(), w)))
+                .collect::<Vec<_>>();
+            if !warnings.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new(tr(ui.ctx(), "⚠ Warnings:")).strong()).on_hover_text(
+                        tr(ui.ctx(), "Pointer arithmetic adds multiples of the size of the type pointed to, casting to char* first moves by single bytes. A pointer which isn't aligned or points past the end of an array usually comes from mixing them up or counting one element too far."),
+                    );
+                    for (name, warning) in warnings {
+                        ui.label(
+                            RichText::new(format!("{} {}", name.unwrap_or("??"), warning))
+                                .monospace()
+                                .color(ui.visuals().warn_fg_color),
+                        );
+                    }
+                });
+            }
+            let blocks = variables
+                .iter()
+                .filter_map(|v| Some((v.name.as_deref()?, v.heap_chunk.as_ref()?)))
+                .collect::<Vec<_>>();
+            if !blocks.is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(RichText::new(tr(ui.ctx(), "Heap blocks:")).strong()).on_hover_text(
+                        tr(ui.ctx(), "malloc stores the size of every block in a 16 byte header right before the address it returns. free reads the header in front of the pointer it gets, so freeing a pointer malloc didn't return, e.g. into the middle of a block, reads garbage as the size and corrupts the heap."),
+                    );
+                    for (name, chunk) in blocks {
+                        ui.label(
+                            RichText::new(trf(
+                                ui.ctx(),
+                                "{}: {} bytes, {}",
+                                &[&name, &chunk.size, &chunk_state(ui.ctx(), chunk)],
+                            ))
+                            .monospace()
+                            .background_color(ui.visuals().extreme_bg_color),
+                        )
+                        .on_hover_text(trf(
+                            ui.ctx(),
+                            "Header at {}: prev_size {}, size {} with the flags PREV_INUSE={}, IS_MMAPPED={}, NON_MAIN_ARENA={}",
+                            &[
+                                &format!("{:#x}", chunk.address - MALLOC_HEADER_SIZE),
+                                &chunk.prev_size,
+                                &chunk.size,
+                                &(chunk.prev_in_use as u8),
+                                &(chunk.mmapped as u8),
+                                &(chunk.non_main_arena as u8),
+                            ],
+                        ));
+                    }
+                });
+            }
+        }
+        let mut clicked_address = None;
         if let (Some(Ok(variables)), Some(Ok(registers))) =
             (self.variables.ready(), self.registers.ready())
         {
             // let stack_range = registers.stack_pointer..registers.base_pointer;
-            let base = if self.first_base_pointer.is_some() {
+            let frames = match self.frames.ready() {
+                Some(Ok(frames)) => frames.clone(),
+                _ => vec![],
+            };
+            // the outermost frame ends where main was called
+            let base = if let Some(end) = frames.iter().map(|f| f.to).max() {
+                end
+            } else if self.first_base_pointer.is_some() {
                 self.first_base_pointer.unwrap()
             } else if registers.stack_pointer < registers.base_pointer {
                 self.first_base_pointer = Some(registers.base_pointer);
@@ -451,16 +1603,36 @@ impl DebuggerWindowImpl for MemoryWindow {
             let mut deduplicated_variables = variables.clone();
             deduplicated_variables.sort_by(|a, b| a.addr.unwrap().cmp(&b.addr.unwrap()));
             deduplicated_variables.dedup_by(|a, b| a.addr.unwrap() == b.addr.unwrap());
+            let pointer_targets = self
+                .pointer_targets
+                .values()
+                .filter_map(|p| p.ready()?.as_ref().ok().cloned())
+                .collect::<Vec<_>>();
+            if pointer_targets.len() != self.loaded_pointer_targets {
+                self.loaded_pointer_targets = pointer_targets.len();
+                self.cached_addresses = None;
+            }
             // self.cached_addresses = None;
+            let padding = Config::get(ui.ctx()).memory_padding;
+            if padding != self.memory_padding {
+                self.memory_padding = padding;
+                self.cached_addresses = None;
+            }
             if self.cached_addresses.is_none() {
+                self.request_pointer_targets(&deduplicated_variables, &stack_range);
                 let mut addresses = deduplicated_variables
                     .iter()
                     .map(|v| {
-                        (v.addr.unwrap() - VARIABLE_MEM_PADDING)
-                            ..(v.addr.unwrap() - VARIABLE_MEM_PADDING
-                                + v.memory.as_ref().unwrap().len() as u64)
+                        let memory = v.memory.as_ref().unwrap();
+                        let (start, memory) = padded_memory(v.addr.unwrap(), memory, padding);
+                        start..start + memory.len() as u64
                     })
                     .flatten()
+                    .chain(
+                        pointer_targets
+                            .iter()
+                            .flat_map(|c| c.from..c.from + c.memory.len() as u64),
+                    )
                     .collect::<HashSet<_>>()
                     .into_iter()
                     .collect::<Vec<_>>();
@@ -473,8 +1645,33 @@ impl DebuggerWindowImpl for MemoryWindow {
                 }
                 self.cached_addresses = Some(addresses.into_iter().collect());
             }
+            // the relationships shown by the arrows as text, for screen readers as well
+            let accessible = Theme::get(ui.ctx()).accessible;
+            let pointers = match accessible {
+                true => find_pointers(&deduplicated_variables),
+                false => vec![],
+            };
+            if !pointers.is_empty() {
+                ui.collapsing(tr(ui.ctx(), "Pointers"), |ui| {
+                    for pointer in pointers.iter() {
+                        ui.label(RichText::new(pointer.to_string()).monospace());
+                    }
+                });
+            }
+            let description = accessible.then(|| {
+                let names = deduplicated_variables
+                    .iter()
+                    .filter_map(|v| v.name.as_deref())
+                    .collect::<Vec<_>>();
+                let mut description =
+                    trf(ui.ctx(), "Memory of the variables {}", &[&names.join(", ")]);
+                for pointer in pointers.iter() {
+                    description += &format!(", {}", pointer);
+                }
+                description
+            });
             let mut arrow_counter = 0;
-            Plot::new("Memory")
+            let plot = Plot::new("Memory")
                 // .height(600f32)
                 .show_axes([false, false])
                 .show_grid(Vec2b::new(self.grid, self.grid))
@@ -491,6 +1688,75 @@ impl DebuggerWindowImpl for MemoryWindow {
                         );
                     }
                     render_addresses(ui, &stack_range, self.cached_addresses.as_ref().unwrap());
+                    render_frames(ui, &frames, &stack_range);
+                    render_pointer_labels(
+                        ui,
+                        &pointers,
+                        &stack_range,
+                        self.cached_addresses.as_ref().unwrap(),
+                    );
+                    if let Some(Some(Ok(references))) = self.references.as_ref().map(|r| r.ready())
+                    {
+                        render_references(
+                            ui,
+                            references,
+                            &stack_range,
+                            self.cached_addresses.as_ref().unwrap(),
+                        );
+                    }
+                    if let Some(Some(Ok(info))) = self.pointer_info.as_ref().map(|p| p.ready()) {
+                        let color = ui.ctx().style().visuals.selection.stroke.color;
+                        render_highlight(
+                            ui,
+                            info.element_address.unwrap_or(info.address),
+                            info.element_size.unwrap_or(1),
+                            &stack_range,
+                            self.cached_addresses.as_ref().unwrap(),
+                            color,
+                        );
+                    }
+                    if let Some(Selection {
+                        address: Some(address),
+                        size,
+                        ..
+                    }) = &self.selection
+                    {
+                        let color = ui.ctx().style().visuals.selection.bg_fill;
+                        render_highlight(
+                            ui,
+                            *address,
+                            *size,
+                            &stack_range,
+                            self.cached_addresses.as_ref().unwrap(),
+                            color,
+                        );
+                    }
+                    if ui.response().clicked() {
+                        clicked_address = ui.pointer_coordinate().and_then(|pos| {
+                            pos_to_addr(pos, &stack_range, self.cached_addresses.as_ref().unwrap())
+                        });
+                    }
+                    for chunk in pointer_targets.iter() {
+                        render_pointer_target(
+                            ui,
+                            chunk,
+                            self.cached_addresses.as_ref().unwrap(),
+                            &stack_range,
+                            self.data_visualization,
+                            &self.previous_memory,
+                        );
+                    }
+                    for chunk in deduplicated_variables
+                        .iter()
+                        .filter_map(|v| v.heap_chunk.as_ref())
+                    {
+                        render_malloc_header(
+                            ui,
+                            chunk,
+                            &stack_range,
+                            self.cached_addresses.as_ref().unwrap(),
+                        );
+                    }
                     for variable in deduplicated_variables {
                         render_variable(
                             &variable,
@@ -500,12 +1766,22 @@ impl DebuggerWindowImpl for MemoryWindow {
                             true,
                             &mut arrow_counter,
                             self.data_visualization,
+                            &self.previous_memory,
+                            self.byte_order,
                         );
                     }
                 });
+            if let Some(description) = description {
+                plot.response
+                    .widget_info(|| WidgetInfo::labeled(WidgetType::Other, true, &description));
+            }
         } else {
             ui.spinner();
         }
+        if let Some(addr) = clicked_address {
+            self.reference_query = format!("{:#x}", addr);
+            self.find_references();
+        }
         false
     }
 }
@@ -0,0 +1,88 @@
+use egui::RichText;
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, PltEntry};
+use url::Url;
+
+use crate::{debugger_window::DebuggerWindowImpl, i18n::tr};
+
+/// The imported functions with the GOT slots their PLT stubs jump through, so lazy binding can
+/// be watched: a slot points back into its stub until the function is called the first time
+pub struct PltWindow {
+    backend_url: Url,
+    entries: Promise<Result<Vec<PltEntry>, String>>,
+}
+
+impl PltWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            entries: Promise::from_ready(Ok(vec![])),
+        };
+        ret.dirty();
+        ret
+    }
+}
+
+impl DebuggerWindowImpl for PltWindow {
+    fn dirty(&mut self) {
+        self.entries = dispatch!(self.backend_url.clone(), Command::GetPltEntries, PltEntries);
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        match self.entries.ready() {
+            Some(Ok(entries)) if entries.is_empty() => {
+                ui.label(tr(ui.ctx(), "The program doesn't import any functions"));
+            }
+            Some(Ok(entries)) => {
+                egui::Grid::new("plt").striped(true).show(ui, |ui| {
+                    ui.strong(tr(ui.ctx(), "Function"));
+                    ui.strong("PLT");
+                    ui.strong(tr(ui.ctx(), "GOT slot"));
+                    ui.strong(tr(ui.ctx(), "Points to"));
+                    ui.end_row();
+                    for entry in entries {
+                        ui.label(RichText::new(&entry.function).monospace());
+                        ui.label(RichText::new(format!("{:#x}", entry.plt_address)).monospace());
+                        ui.label(RichText::new(format!("{:#x}", entry.got_address)).monospace());
+                        let target = ui.label(
+                            RichText::new(format!("{:#x}", entry.target)).monospace(),
+                        );
+                        target.on_hover_text(&entry.target_description);
+                        match entry.resolved {
+                            true => ui
+                                .label(tr(ui.ctx(), "✔ resolved"))
+                                .on_hover_text(tr(
+                                    ui.ctx(),
+                                    "The dynamic linker patched the slot with the address of the function",
+                                )),
+                            false => ui
+                                .label(
+                                    RichText::new(tr(ui.ctx(), "⏳ unresolved"))
+                                        .color(ui.visuals().warn_fg_color),
+                                )
+                                .on_hover_text(tr(
+                                    ui.ctx(),
+                                    "The slot points back into the stub, the first call runs the dynamic linker, which looks the function up and patches the slot",
+                                )),
+                        };
+                        ui.end_row();
+                    }
+                });
+            }
+            Some(Err(err)) => {
+                ui.label(
+                    RichText::new(format!("⚠ {}", err))
+                        .small()
+                        .color(ui.visuals().warn_fg_color),
+                );
+                if ui.small_button("Ok").clicked() {
+                    self.dirty();
+                }
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+        false
+    }
+}
@@ -0,0 +1,127 @@
+use egui::{Grid, RichText};
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, Profile};
+use url::Url;
+
+use crate::{
+    command::dispatch_command_and_then,
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
+
+/// Number of lines with the most samples shown below the functions
+const TOP_LINES: usize = 10;
+
+pub struct ProfileWindow {
+    backend_url: Url,
+    profile: Promise<Result<Profile, String>>,
+    period: u64,
+    request: Option<Promise<Result<(), String>>>,
+}
+
+impl ProfileWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            profile: Promise::from_ready(Err(String::new())),
+            period: 100,
+            request: None,
+        };
+        ret.dirty();
+        ret
+    }
+}
+
+fn percentage(samples: u64, total: u64) -> String {
+    format!("{:.1}%", 100. * samples as f64 / total.max(1) as f64)
+}
+
+impl DebuggerWindowImpl for ProfileWindow {
+    fn dirty(&mut self) {
+        self.profile = dispatch!(self.backend_url.clone(), Command::GetProfile, Profile);
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let mut is_dirty = false;
+        ui.horizontal(|ui| {
+            ui.label(tr(ui.ctx(), "Sample every"));
+            ui.add(egui::DragValue::new(&mut self.period).range(1..=100_000));
+            ui.label(tr(ui.ctx(), "instructions"));
+            match &self.request {
+                Some(request) => match request.ready() {
+                    Some(_) => {
+                        self.request = None;
+                        is_dirty = true;
+                    }
+                    None => {
+                        ui.spinner();
+                    }
+                },
+                None => {
+                    if ui
+                        .button(tr(ui.ctx(), "▶ Profile"))
+                        .on_hover_text(tr(
+                            ui.ctx(),
+                            "Continue until the next breakpoint while sampling the current function",
+                        ))
+                        .clicked()
+                    {
+                        self.request = Some(dispatch_command_and_then(
+                            self.backend_url.clone(),
+                            Command::Profile(self.period),
+                            |_| (),
+                        ));
+                    }
+                }
+            }
+        });
+
+        match self.profile.ready() {
+            Some(Ok(profile)) => {
+                ui.label(trf(ui.ctx(), "{} samples", &[&profile.samples]));
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    Grid::new("profile_functions").striped(true).show(ui, |ui| {
+                        ui.label(RichText::new(tr(ui.ctx(), "Function")).strong());
+                        ui.label(RichText::new(tr(ui.ctx(), "Self")).strong())
+                            .on_hover_text(tr(ui.ctx(), "Samples inside the function itself"));
+                        ui.label(RichText::new(tr(ui.ctx(), "Total")).strong())
+                            .on_hover_text(tr(
+                                ui.ctx(),
+                                "Samples with the function anywhere on the stack",
+                            ));
+                        ui.end_row();
+                        for function in profile.functions.iter() {
+                            ui.label(RichText::new(&function.name).monospace());
+                            ui.label(percentage(function.self_samples, profile.samples));
+                            ui.label(percentage(function.total_samples, profile.samples));
+                            ui.end_row();
+                        }
+                    });
+                    ui.separator();
+                    Grid::new("profile_lines").striped(true).show(ui, |ui| {
+                        ui.label(RichText::new(tr(ui.ctx(), "Line")).strong());
+                        ui.label(RichText::new(tr(ui.ctx(), "Samples")).strong());
+                        ui.end_row();
+                        for line in profile.lines.iter().take(TOP_LINES) {
+                            let file = line.file.rsplit('/').next().unwrap_or(&line.file);
+                            ui.label(RichText::new(format!("{}:{}", file, line.line)).monospace())
+                                .on_hover_text(&line.file);
+                            ui.label(percentage(line.count, profile.samples));
+                            ui.end_row();
+                        }
+                    });
+                });
+            }
+            Some(Err(_)) => {
+                ui.label(tr(
+                    ui.ctx(),
+                    "Press Profile to sample the program until the next breakpoint",
+                ));
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+        is_dirty
+    }
+}
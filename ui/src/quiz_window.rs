@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+
+use egui::{ComboBox, RichText};
+use poll_promise::Promise;
+use stackium_shared::{
+    Breakpoint, Command, CommandOutput, Quiz, QuizAnswer, QuizKind, QuizQuestion,
+};
+use url::Url;
+
+use crate::{
+    capabilities,
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+    selection::{self, Message},
+};
+
+/// Asks the student to predict values and pointer targets at breakpoints, the Memory window is
+/// hidden until the questions of the current stop are answered
+pub struct QuizWindow {
+    backend_url: Url,
+    quiz: Promise<Result<Quiz, String>>,
+    breakpoints: Promise<Result<Vec<Breakpoint>, String>>,
+    /// Breakpoint new questions are asked at
+    breakpoint: Option<u64>,
+    expression_input: String,
+    kind: QuizKind,
+    /// Answers being typed, by the expression of their question
+    answer_inputs: HashMap<String, String>,
+    answer: Option<Promise<Result<QuizAnswer, String>>>,
+    last_answer: Option<Result<QuizAnswer, String>>,
+    /// Adding or removing a question
+    request: Option<Promise<Result<Quiz, String>>>,
+    warning: Option<String>,
+    /// Whether the other windows were told that questions are pending
+    announced_pending: Option<bool>,
+}
+
+impl QuizWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            quiz: Promise::from_ready(Err(String::new())),
+            breakpoints: Promise::from_ready(Ok(vec![])),
+            breakpoint: None,
+            expression_input: String::new(),
+            kind: QuizKind::Value,
+            answer_inputs: HashMap::new(),
+            answer: None,
+            last_answer: None,
+            request: None,
+            warning: None,
+            announced_pending: None,
+        };
+        ret.dirty();
+        ret
+    }
+
+    fn add_question(&mut self, breakpoint: u64) {
+        let question = QuizQuestion {
+            breakpoint,
+            expression: std::mem::take(&mut self.expression_input),
+            kind: self.kind,
+        };
+        self.request = Some(dispatch!(
+            self.backend_url.clone(),
+            Command::AddQuizQuestion(question),
+            Quiz
+        ));
+    }
+}
+
+fn breakpoint_name(breakpoints: &[Breakpoint], address: u64) -> String {
+    match breakpoints.iter().find(|b| b.address == address) {
+        Some(breakpoint) => format!(
+            "{}:{}",
+            breakpoint
+                .location
+                .file
+                .rsplit('/')
+                .next()
+                .unwrap_or_default(),
+            breakpoint.location.line
+        ),
+        None => format!("{:#x}", address),
+    }
+}
+
+fn question_text(ctx: &egui::Context, question: &QuizQuestion) -> String {
+    match question.kind {
+        QuizKind::Value => trf(ctx, "What is the value of {}?", &[&question.expression]),
+        QuizKind::Target => trf(ctx, "What does {} point to?", &[&question.expression]),
+    }
+}
+
+impl DebuggerWindowImpl for QuizWindow {
+    fn dirty(&mut self) {
+        self.quiz = dispatch!(self.backend_url.clone(), Command::GetQuiz, Quiz);
+        self.breakpoints = dispatch!(
+            self.backend_url.clone(),
+            Command::GetBreakpoints,
+            Breakpoints
+        );
+    }
+
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        if let Some(Ok(quiz)) = self.quiz.ready() {
+            let pending = !quiz.pending.is_empty();
+            if self.announced_pending != Some(pending) {
+                self.announced_pending = Some(pending);
+                selection::send(ctx, Message::QuizPending(pending));
+            }
+        }
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        if let Some(unsupported) = capabilities::unsupported(ui.ctx(), "GetQuiz") {
+            ui.label(RichText::new(unsupported).color(ui.visuals().warn_fg_color));
+            return false;
+        }
+        if let Some(result) = self.answer.as_ref().and_then(|a| a.ready()) {
+            self.last_answer = Some(result.clone());
+            self.answer = None;
+            // the answered question is no longer pending
+            self.quiz = dispatch!(self.backend_url.clone(), Command::GetQuiz, Quiz);
+        }
+        if let Some(result) = self.request.as_ref().and_then(|r| r.ready()) {
+            match result {
+                Ok(quiz) => {
+                    self.quiz = Promise::from_ready(Ok(quiz.clone()));
+                    self.warning = None;
+                }
+                Err(e) => self.warning = Some(e.clone()),
+            }
+            self.request = None;
+        }
+        let breakpoints = match self.breakpoints.ready() {
+            Some(Ok(breakpoints)) => breakpoints.clone(),
+            _ => vec![],
+        };
+        let quiz = match self.quiz.ready() {
+            Some(Ok(quiz)) => quiz.clone(),
+            Some(Err(e)) => {
+                ui.label(e);
+                return false;
+            }
+            None => {
+                ui.spinner();
+                return false;
+            }
+        };
+
+        let correct = quiz.answers.iter().filter(|a| a.correct).count();
+        ui.heading(trf(
+            ui.ctx(),
+            "Score: {} of {}",
+            &[&correct, &quiz.answers.len()],
+        ));
+        if let Some(result) = &self.last_answer {
+            match result {
+                Ok(answer) if answer.correct => {
+                    ui.label(trf(
+                        ui.ctx(),
+                        "✔ Correct, {} is {}",
+                        &[&answer.question.expression, &answer.solution],
+                    ));
+                }
+                Ok(answer) => {
+                    ui.label(
+                        RichText::new(trf(
+                            ui.ctx(),
+                            "✘ {} is {}, not {}",
+                            &[
+                                &answer.question.expression,
+                                &answer.solution,
+                                &answer.answer,
+                            ],
+                        ))
+                        .color(ui.visuals().error_fg_color),
+                    );
+                }
+                Err(e) => {
+                    ui.label(
+                        RichText::new(format!("⚠ {}", e))
+                            .small()
+                            .color(ui.visuals().warn_fg_color),
+                    );
+                }
+            }
+        }
+        if quiz.pending.is_empty() {
+            ui.label(RichText::new(tr(ui.ctx(), "No questions at this stop")).weak());
+        }
+        for question in quiz.pending.iter() {
+            ui.group(|ui| {
+                ui.label(RichText::new(question_text(ui.ctx(), question)).strong());
+                ui.horizontal(|ui| {
+                    let input = self
+                        .answer_inputs
+                        .entry(question.expression.clone())
+                        .or_default();
+                    let hint = match question.kind {
+                        QuizKind::Value => "42",
+                        QuizKind::Target => "arr[2]",
+                    };
+                    let response = ui.add(
+                        egui::TextEdit::singleline(input)
+                            .hint_text(hint)
+                            .code_editor(),
+                    );
+                    let submitted =
+                        response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (ui.button(tr(ui.ctx(), "Check")).clicked() || submitted)
+                        && !input.is_empty()
+                    {
+                        self.answer = Some(dispatch!(
+                            self.backend_url.clone(),
+                            Command::AnswerQuiz {
+                                expression: question.expression.clone(),
+                                answer: std::mem::take(input),
+                            },
+                            QuizAnswer
+                        ));
+                    }
+                });
+            });
+        }
+
+        ui.separator();
+        ui.collapsing(tr(ui.ctx(), "Questions"), |ui| {
+            let mut remove = None;
+            egui::Grid::new("quiz_questions")
+                .striped(true)
+                .show(ui, |ui| {
+                    for question in quiz.questions.iter() {
+                        ui.label(breakpoint_name(&breakpoints, question.breakpoint));
+                        ui.label(question_text(ui.ctx(), question));
+                        if ui
+                            .button("🗑")
+                            .on_hover_text(tr(ui.ctx(), "Remove the question"))
+                            .clicked()
+                        {
+                            remove = Some(question.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            if let Some(question) = remove {
+                self.request = Some(dispatch!(
+                    self.backend_url.clone(),
+                    Command::RemoveQuizQuestion(question),
+                    Quiz
+                ));
+            }
+            if breakpoints.is_empty() {
+                ui.label(
+                    RichText::new(tr(ui.ctx(), "Set a breakpoint to ask questions at")).weak(),
+                );
+                return;
+            }
+            let selected = self
+                .breakpoint
+                .filter(|a| breakpoints.iter().any(|b| b.address == *a))
+                .unwrap_or(breakpoints[0].address);
+            ui.horizontal(|ui| {
+                ComboBox::new("quiz_breakpoint", "")
+                    .selected_text(trf(
+                        ui.ctx(),
+                        "At {}",
+                        &[&breakpoint_name(&breakpoints, selected)],
+                    ))
+                    .show_ui(ui, |ui| {
+                        for breakpoint in breakpoints.iter() {
+                            ui.selectable_value(
+                                &mut self.breakpoint,
+                                Some(breakpoint.address),
+                                breakpoint_name(&breakpoints, breakpoint.address),
+                            );
+                        }
+                    });
+                ui.selectable_value(&mut self.kind, QuizKind::Value, tr(ui.ctx(), "Value"));
+                ui.selectable_value(&mut self.kind, QuizKind::Target, tr(ui.ctx(), "Target"))
+                    .on_hover_text(tr(ui.ctx(), "Ask what a pointer points to"));
+            });
+            ui.horizontal(|ui| {
+                let input = ui.add(
+                    egui::TextEdit::singleline(&mut self.expression_input)
+                        .hint_text("*pointer")
+                        .code_editor(),
+                );
+                let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if (ui.button(tr(ui.ctx(), "Ask")).clicked() || submitted)
+                    && !self.expression_input.is_empty()
+                {
+                    self.add_question(selected);
+                }
+            });
+            if let Some(warning) = &self.warning {
+                ui.label(
+                    RichText::new(format!("⚠ {}", warning))
+                        .small()
+                        .color(ui.visuals().warn_fg_color),
+                );
+            }
+        });
+        false
+    }
+}
@@ -1,47 +1,235 @@
+use egui::{Grid, RichText};
 use poll_promise::Promise;
-use stackium_shared::{Command, CommandOutput, Registers};
+use stackium_shared::{Command, CommandOutput, FullRegisters, Register};
 use url::Url;
 
-use crate::debugger_window::DebuggerWindowImpl;
+use crate::{
+    address::AddressResolver,
+    command::dispatch_command_and_then,
+    config::{Config, NumberBase},
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+};
 
 pub struct RegisterWindow {
     backend_url: Url,
-    registers: Promise<Result<Registers, String>>,
+    registers: Promise<Result<FullRegisters, String>>,
+    /// Registers at the previous stop, used to highlight changes
+    previous: Option<FullRegisters>,
+    /// Name and text input of the register currently being edited
+    editing: Option<(String, String)>,
+    set_request: Option<Promise<Result<(), String>>>,
+    warning: Option<String>,
+    addresses: AddressResolver,
 }
 
 impl RegisterWindow {
     pub fn new(backend_url: Url) -> Self {
         let mut ret = Self {
+            addresses: AddressResolver::new(backend_url.clone()),
             backend_url,
             registers: Promise::from_ready(Err(String::new())),
+            previous: None,
+            editing: None,
+            set_request: None,
+            warning: None,
         };
         ret.dirty();
         ret
     }
+
+    fn changed(&self, register: &Register) -> bool {
+        self.previous.as_ref().map_or(false, |previous| {
+            previous
+                .general
+                .iter()
+                .chain(previous.segment.iter())
+                .find(|r| r.name == register.name)
+                .map_or(false, |r| r.value != register.value)
+        })
+    }
+
+    fn register_rows(&mut self, ui: &mut egui::Ui, registers: &[Register]) {
+        let number_base = Config::get(ui.ctx()).number_base;
+        for register in registers {
+            let text = RichText::new(&register.name).monospace();
+            let name = if self.changed(register) {
+                ui.label(text.color(ui.visuals().warn_fg_color).strong())
+                    .on_hover_text(tr(ui.ctx(), "Changed since the last stop"))
+            } else {
+                ui.label(text)
+            };
+            if let Some(description) = describe_register(&register.name) {
+                name.on_hover_text(tr(ui.ctx(), description));
+            }
+            let (value, other) = match number_base {
+                NumberBase::Hexadecimal => (
+                    format!("{:#018x}", register.value),
+                    format!("{}", register.value as i64),
+                ),
+                NumberBase::Decimal => (
+                    format!("{}", register.value as i64),
+                    format!("{:#x}", register.value),
+                ),
+            };
+            match &mut self.editing {
+                Some((name, input)) if *name == register.name => {
+                    let response = ui.text_edit_singleline(input);
+                    if response.lost_focus() {
+                        if ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                            self.set_register(ui.ctx(), register.name.clone());
+                        }
+                        self.editing = None;
+                    } else {
+                        response.request_focus();
+                    }
+                }
+                _ => {
+                    if ui
+                        .add(
+                            egui::Label::new(RichText::new(value).monospace())
+                                .sense(egui::Sense::click()),
+                        )
+                        .on_hover_text(tr(ui.ctx(), "Click to edit"))
+                        .clicked()
+                    {
+                        self.editing =
+                            Some((register.name.clone(), format!("{:#x}", register.value)));
+                    }
+                }
+            }
+            ui.label(RichText::new(other).weak());
+            match self.addresses.describe(register.value) {
+                Some(description) => ui.label(RichText::new(description).monospace()),
+                None => ui.label(""),
+            };
+            ui.end_row();
+        }
+    }
+
+    fn set_register(&mut self, ctx: &egui::Context, name: String) {
+        let Some((_, input)) = &self.editing else {
+            return;
+        };
+        let value = match input.strip_prefix("0x") {
+            Some(hex) => u64::from_str_radix(hex, 16),
+            None => input
+                .parse::<i64>()
+                .map(|v| v as u64)
+                .or(input.parse::<u64>()),
+        };
+        match value {
+            Ok(value) => {
+                self.warning = None;
+                self.set_request = Some(dispatch_command_and_then(
+                    self.backend_url.clone(),
+                    Command::SetRegister(name, value),
+                    |_| (),
+                ));
+            }
+            Err(err) => self.warning = Some(trf(ctx, "Failed parsing number: {}", &[&err])),
+        }
+    }
 }
 
-macro_rules! register_label {
-    ($ui:expr, $reg_nam:expr, $reg:expr) => {
-        $ui.label(format!("{}: {:#x} ({})", $reg_nam, $reg, $reg))
-    };
+/// Role of the registers students care about the most
+fn describe_register(name: &str) -> Option<&'static str> {
+    match name {
+        "rip" | "pc" => Some("Instruction Pointer"),
+        "rsp" | "sp" => Some("Stack Pointer"),
+        "rbp" | "x29" => Some("Base Pointer"),
+        "rax" | "x0" => Some("Return value"),
+        "x30" => Some("Link Register (return address)"),
+        _ => None,
+    }
 }
 
 impl DebuggerWindowImpl for RegisterWindow {
     fn dirty(&mut self) {
-        self.registers = dispatch!(self.backend_url.clone(), Command::GetRegister, Registers);
+        if let Some(Ok(registers)) = self.registers.ready() {
+            self.previous = Some(registers.clone());
+        }
+        self.addresses.clear();
+        self.registers = dispatch!(
+            self.backend_url.clone(),
+            Command::GetFullRegisters,
+            FullRegisters
+        );
     }
     fn ui(&mut self, ui: &mut egui::Ui) -> bool {
-        match self.registers.ready() {
-            Some(registers) => match registers {
-                Ok(registers) => {
-                    register_label!(ui, "Stack Pointer", registers.stack_pointer);
-                    register_label!(ui, "Base Pointer", registers.base_pointer);
-                    register_label!(ui, "Instruction Pointer", registers.instruction_pointer)
+        let mut is_dirty = false;
+        if let Some(request) = &self.set_request {
+            match request.ready() {
+                Some(Ok(_)) => {
+                    self.set_request = None;
+                    is_dirty = true;
+                }
+                Some(Err(err)) => {
+                    self.warning = Some(err.clone());
+                    self.set_request = None;
                 }
-                Err(e) => ui.label(format!("Err: {}", e)),
-            },
-            None => ui.spinner(),
+                None => {
+                    ui.spinner();
+                }
+            }
+        }
+        if let Some(warning) = &self.warning {
+            ui.label(
+                RichText::new(format!("⚠ {}", warning))
+                    .small()
+                    .color(ui.visuals().warn_fg_color),
+            );
+        }
+        let registers = match self.registers.ready() {
+            Some(Ok(registers)) => registers.clone(),
+            Some(Err(e)) => {
+                ui.label(format!("Err: {}", e));
+                return is_dirty;
+            }
+            None => {
+                ui.spinner();
+                return is_dirty;
+            }
         };
-        false
+        egui::ScrollArea::vertical().show(ui, |ui| {
+            Grid::new("general_registers")
+                .striped(true)
+                .show(ui, |ui| self.register_rows(ui, &registers.general));
+
+            ui.separator();
+            ui.horizontal_wrapped(|ui| {
+                ui.label(
+                    RichText::new(format!("flags {:#x}", registers.flags_register)).monospace(),
+                );
+                for flag in registers.flags.iter() {
+                    let was_set = self.previous.as_ref().and_then(|previous| {
+                        previous
+                            .flags
+                            .iter()
+                            .find(|f| f.bit == flag.bit)
+                            .map(|f| f.set)
+                    });
+                    let mut text = RichText::new(&flag.name).monospace();
+                    text = if flag.set { text.strong() } else { text.weak() };
+                    if was_set.map_or(false, |was_set| was_set != flag.set) {
+                        text = text.color(ui.visuals().warn_fg_color);
+                    }
+                    ui.label(text).on_hover_text(format!(
+                        "{} (bit {}) = {}",
+                        flag.description, flag.bit, flag.set as u8
+                    ));
+                }
+            });
+
+            if !registers.segment.is_empty() {
+                ui.separator();
+                egui::CollapsingHeader::new(tr(ui.ctx(), "Segment registers")).show(ui, |ui| {
+                    Grid::new("segment_registers")
+                        .striped(true)
+                        .show(ui, |ui| self.register_rows(ui, &registers.segment));
+                });
+            }
+        });
+        is_dirty
     }
 }
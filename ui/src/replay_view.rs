@@ -0,0 +1,238 @@
+use egui::{CollapsingHeader, RichText, ScrollArea};
+use stackium_shared::{Command, CommandOutput, Location, SessionEntry, SourceFile};
+
+use crate::{
+    i18n::{tr, trf},
+    snapshot_view::render_variable_grid,
+};
+
+/// Lines of source shown before and after the line the debugee stopped at
+const SOURCE_CONTEXT: u64 = 6;
+/// Outputs are cut off after this many characters
+const MAX_OUTPUT_LENGTH: usize = 4000;
+
+/// Parses a session exported with `ExportSession`
+pub fn parse_session(bytes: &[u8]) -> Result<Vec<SessionEntry>, String> {
+    serde_json::from_slice(bytes).map_err(|e| format!("Failed parsing session: {}", e))
+}
+
+/// Short description of a command like `SetBreakpoint {"Name":"main"}`
+fn describe_command(command: &Command) -> String {
+    let value = serde_json::to_value(command).unwrap_or_default();
+    let name = value["Command"].as_str().unwrap_or("?");
+    match value.get("Argument") {
+        Some(argument) => format!("{} {}", name, argument),
+        None => name.to_owned(),
+    }
+}
+
+fn describe_output(output: &Result<CommandOutput, String>) -> String {
+    let mut text = match output {
+        Ok(output) => format!("{:#?}", output),
+        Err(err) => format!("⚠ {}", err),
+    };
+    if text.len() > MAX_OUTPUT_LENGTH {
+        let mut end = MAX_OUTPUT_LENGTH;
+        while !text.is_char_boundary(end) {
+            end -= 1;
+        }
+        text.truncate(end);
+        text.push_str("..");
+    }
+    text
+}
+
+/// Steps through a recorded session without a running debugger
+pub struct ReplayView {
+    pub file_name: String,
+    entries: Vec<SessionEntry>,
+    /// Indices of the entries which resumed the debugee
+    stops: Vec<usize>,
+    /// Number of stops replayed, 0 is the state before the first command resumed the debugee
+    step: usize,
+}
+
+impl ReplayView {
+    pub fn new(file_name: String, entries: Vec<SessionEntry>) -> Self {
+        let stops = entries
+            .iter()
+            .enumerate()
+            .filter(|(_, e)| e.stop.is_some())
+            .map(|(i, _)| i)
+            .collect();
+        Self {
+            file_name,
+            entries,
+            stops,
+            step: 0,
+        }
+    }
+
+    /// The entries recorded from the current stop until the next one
+    fn step_entries(&self) -> &[SessionEntry] {
+        let start = match self.step {
+            0 => 0,
+            step => self.stops[step - 1],
+        };
+        let end = self
+            .stops
+            .get(self.step)
+            .copied()
+            .unwrap_or(self.entries.len());
+        &self.entries[start..end]
+    }
+
+    /// Source of the file, if the UI requested it until the end of the current step
+    fn source(&self, location: &Location) -> Option<&str> {
+        let end = self
+            .stops
+            .get(self.step)
+            .copied()
+            .unwrap_or(self.entries.len());
+        self.entries[..end]
+            .iter()
+            .rev()
+            .find_map(|entry| match (&entry.command, &entry.output) {
+                (Command::GetFile(path), Ok(CommandOutput::File(content)))
+                    if location.file.ends_with(path.as_str())
+                        || path.ends_with(location.file.as_str()) =>
+                {
+                    Some(content.as_str())
+                }
+                (
+                    Command::GetSource(path),
+                    Ok(CommandOutput::Source(SourceFile {
+                        content: Some(content),
+                        ..
+                    })),
+                ) if location.file.ends_with(path.as_str())
+                    || path.ends_with(location.file.as_str()) =>
+                {
+                    Some(content.as_str())
+                }
+                _ => None,
+            })
+    }
+
+    fn render_source(ui: &mut egui::Ui, source: &str, location: &Location) {
+        let first = location.line.saturating_sub(SOURCE_CONTEXT);
+        for (index, line) in source.lines().enumerate() {
+            let number = index as u64 + 1;
+            if number < first || number > location.line + SOURCE_CONTEXT {
+                continue;
+            }
+            let text = RichText::new(format!("{:4} {}", number, line)).monospace();
+            ui.label(match number == location.line {
+                true => text
+                    .strong()
+                    .background_color(ui.visuals().selection.bg_fill),
+                false => text,
+            });
+        }
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            if ui
+                .button("⏮")
+                .on_hover_text(tr(ui.ctx(), "Start"))
+                .clicked()
+            {
+                self.step = 0;
+            }
+            if ui
+                .button("⏴")
+                .on_hover_text(tr(ui.ctx(), "Previous stop"))
+                .clicked()
+            {
+                self.step = self.step.saturating_sub(1);
+            }
+            ui.add(
+                egui::Slider::new(&mut self.step, 0..=self.stops.len()).text(tr(ui.ctx(), "stop")),
+            );
+            if ui
+                .button("⏵")
+                .on_hover_text(tr(ui.ctx(), "Next stop"))
+                .clicked()
+            {
+                self.step = (self.step + 1).min(self.stops.len());
+            }
+            if ui
+                .button("⏭")
+                .on_hover_text(tr(ui.ctx(), "Last stop"))
+                .clicked()
+            {
+                self.step = self.stops.len();
+            }
+        });
+        ui.separator();
+        let entries = self.step_entries();
+        ScrollArea::vertical().show(ui, |ui| {
+            match self.step {
+                0 => {
+                    ui.heading(tr(ui.ctx(), "Before the first stop"));
+                }
+                step => {
+                    let entry = &self.entries[self.stops[step - 1]];
+                    ui.heading(format!("▶ {}", describe_command(&entry.command)));
+                    if let Some(stop) = &entry.stop {
+                        match &stop.location {
+                            Some(location) => ui.label(trf(
+                                ui.ctx(),
+                                "Stopped at {}:{} ({})",
+                                &[&location.file, &location.line, &format!("{:#x}", stop.pc)],
+                            )),
+                            None => ui.label(trf(
+                                ui.ctx(),
+                                "Stopped at {}",
+                                &[&format!("{:#x}", stop.pc)],
+                            )),
+                        };
+                        if let Some(location) = &stop.location {
+                            if let Some(source) = self.source(location) {
+                                ui.separator();
+                                Self::render_source(ui, source, location);
+                            }
+                        }
+                    }
+                    if !matches!(entry.output, Ok(CommandOutput::None)) {
+                        ui.label(
+                            RichText::new(describe_output(&entry.output))
+                                .monospace()
+                                .small(),
+                        );
+                    }
+                }
+            }
+            // the variables the memory window discovered at this stop
+            let variables = entries.iter().rev().find_map(|e| match &e.output {
+                Ok(CommandOutput::DiscoveredVariables(variables)) => Some(variables),
+                _ => None,
+            });
+            if let Some(variables) = variables {
+                ui.separator();
+                render_variable_grid(ui, "replay_variables", variables);
+            }
+            ui.separator();
+            CollapsingHeader::new(trf(
+                ui.ctx(),
+                "Commands at this stop ({})",
+                &[&entries.len()],
+            ))
+            .id_salt(self.step)
+            .show(ui, |ui| {
+                for (index, entry) in entries.iter().enumerate() {
+                    CollapsingHeader::new(describe_command(&entry.command))
+                        .id_salt((self.step, index))
+                        .show(ui, |ui| {
+                            ui.label(
+                                RichText::new(describe_output(&entry.output))
+                                    .monospace()
+                                    .small(),
+                            );
+                        });
+                }
+            });
+        });
+    }
+}
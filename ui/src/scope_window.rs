@@ -0,0 +1,306 @@
+use egui::{CollapsingHeader, RichText};
+use poll_promise::Promise;
+use stackium_shared::{
+    Command, CommandOutput, DataStructure, DiscoveredVariable, StackFrame, StructureKind, TypeName,
+    Variable, VariableScope,
+};
+use url::Url;
+
+use crate::{
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+    memory_window::format_element,
+    selection::{self, Message, Selection},
+};
+
+/// The variables of the current function grouped by the blocks they are declared in
+pub struct ScopeWindow {
+    backend_url: Url,
+    scopes: Promise<Result<Vec<VariableScope>, String>>,
+    /// Linked lists and trees reached from the variables, shown next to them
+    structures: Promise<Result<Vec<DataStructure>, String>>,
+    /// The discovered variables for the strings `char[]` and `char*` variables hold
+    strings: Promise<Result<Vec<DiscoveredVariable>, String>>,
+    /// The functions on the stack with the arguments they were called with
+    frames: Promise<Result<Vec<StackFrame>, String>>,
+    /// Clicking a variable selects it in all windows
+    selection: Option<Selection>,
+}
+
+impl ScopeWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            scopes: Promise::from_ready(Err(String::new())),
+            structures: Promise::from_ready(Ok(vec![])),
+            strings: Promise::from_ready(Ok(vec![])),
+            frames: Promise::from_ready(Ok(vec![])),
+            selection: None,
+        };
+        ret.dirty();
+        ret
+    }
+}
+
+/// The value of the variable and where it is stored if it isn't in memory
+pub fn format_value(variable: &Variable) -> String {
+    if !variable.in_scope {
+        return "not live".to_owned();
+    }
+    let (Some(types), Some(value)) = (&variable.type_name, variable.value) else {
+        return variable.optimized.clone().unwrap_or("..".to_owned());
+    };
+    let value = match &types.0[0].1 {
+        TypeName::Name { .. } => format_element(types, 0, &value.to_le_bytes()),
+        TypeName::Ref { .. } => format!("{:#x}", value),
+        _ => match variable.addr {
+            Some(addr) => format!("@ {:#x}", addr),
+            None => "..".to_owned(),
+        },
+    };
+    match &variable.optimized {
+        Some(optimized) => format!("{} ({})", value, optimized),
+        None => value,
+    }
+}
+
+/// All variables of the scopes and the scopes nested in them
+pub fn flatten_scopes(scopes: Vec<VariableScope>) -> Vec<Variable> {
+    scopes
+        .into_iter()
+        .flat_map(|scope| {
+            let mut variables = scope.variables;
+            variables.extend(flatten_scopes(scope.scopes));
+            variables
+        })
+        .collect()
+}
+
+/// Short description of a recognized data structure, e.g. `⛓ linked list, 5 nodes`
+pub fn describe_structure(structure: &DataStructure) -> String {
+    let kind = match &structure.kind {
+        StructureKind::LinkedList { prev: None, .. } => "⛓ linked list",
+        StructureKind::LinkedList { prev: Some(_), .. } => "⛓ doubly linked list",
+        StructureKind::BinaryTree { .. } => "🌳 binary tree",
+    };
+    let nodes = match structure.nodes.len() {
+        0 => "empty".to_owned(),
+        1 => "1 node".to_owned(),
+        n => format!("{}{} nodes", n, if structure.truncated { "+" } else { "" }),
+    };
+    format!("{}, {}", kind, nodes)
+}
+
+fn render_variables(
+    ui: &mut egui::Ui,
+    id: (&str, u64),
+    variables: &[&Variable],
+    structures: &[DataStructure],
+    strings: &[DiscoveredVariable],
+    selection: &mut Option<Selection>,
+) {
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        for variable in variables {
+            let text = |text: String| match variable.in_scope {
+                true => RichText::new(text).monospace(),
+                false => RichText::new(text).monospace().weak(),
+            };
+            let clicked = Selection::of(variable);
+            let is_selected = selection.as_ref() == Some(&clicked);
+            if ui
+                .selectable_label(
+                    is_selected,
+                    text(variable.name.clone().unwrap_or("??".to_owned())),
+                )
+                .on_hover_text(tr(ui.ctx(), "Highlight the variable in the other windows"))
+                .clicked()
+            {
+                *selection = (!is_selected).then_some(clicked);
+                selection::send(ui.ctx(), Message::Select(selection.clone()));
+            }
+            ui.label(text(
+                variable
+                    .type_name
+                    .as_ref()
+                    .map(|t| t.to_string())
+                    .unwrap_or_default(),
+            ))
+            .on_hover_text(match variable.line {
+                Some(line) => trf(ui.ctx(), "Declared in line {}", &[&line]),
+                None => String::new(),
+            });
+            let string = strings
+                .iter()
+                .find(|s| variable.in_scope && s.name == variable.name && s.addr == variable.addr)
+                .and_then(|s| s.string.as_ref());
+            ui.label(text(match string {
+                Some(string) => format!("{} {}", format_value(variable), string),
+                None => format_value(variable),
+            }));
+            let structure = structures
+                .iter()
+                .find(|s| variable.in_scope && variable.name.as_ref() == Some(&s.variable));
+            match structure {
+                Some(structure) => {
+                    ui.label(RichText::new(describe_structure(structure)).small())
+                        .on_hover_text(trf(ui.ctx(), "Nodes of type {}", &[&structure.node_type]));
+                }
+                None => {
+                    ui.label("");
+                }
+            }
+            ui.end_row();
+        }
+    });
+}
+
+fn render_scope(
+    ui: &mut egui::Ui,
+    scope: &VariableScope,
+    structures: &[DataStructure],
+    strings: &[DiscoveredVariable],
+    selection: &mut Option<Selection>,
+) {
+    let line = scope
+        .line
+        .map(|l| trf(ui.ctx(), " (line {})", &[&l]))
+        .unwrap_or_default();
+    let title = match &scope.function {
+        Some(function) => RichText::new(format!("fn {}{}", function, line)).strong(),
+        None => RichText::new(trf(ui.ctx(), "{ } block{}", &[&line])),
+    };
+    CollapsingHeader::new(if scope.live { title } else { title.weak() })
+        .id_salt(scope.low_pc)
+        .default_open(scope.live)
+        .show(ui, |ui| {
+            let (arguments, locals): (Vec<_>, Vec<_>) =
+                scope.variables.iter().partition(|v| v.parameter);
+            if !arguments.is_empty() {
+                ui.label(RichText::new(tr(ui.ctx(), "Arguments")).small().strong());
+                render_variables(
+                    ui,
+                    ("scope_arguments", scope.low_pc),
+                    &arguments,
+                    structures,
+                    strings,
+                    selection,
+                );
+                if !locals.is_empty() {
+                    ui.label(RichText::new(tr(ui.ctx(), "Locals")).small().strong());
+                }
+            }
+            render_variables(
+                ui,
+                ("scope_variables", scope.low_pc),
+                &locals,
+                structures,
+                strings,
+                selection,
+            );
+            for scope in scope.scopes.iter() {
+                render_scope(ui, scope, structures, strings, selection);
+            }
+        });
+}
+
+/// Every frame on the stack as a call with the values of its arguments, e.g. `middle(x = 3)`
+fn render_call_stack(ui: &mut egui::Ui, frames: &[StackFrame]) {
+    CollapsingHeader::new(RichText::new(tr(ui.ctx(), "Call stack")).strong())
+        .default_open(true)
+        .show(ui, |ui| {
+            for (i, frame) in frames.iter().enumerate() {
+                let arguments = frame
+                    .arguments
+                    .iter()
+                    .map(|a| {
+                        format!(
+                            "{} = {}",
+                            a.name.as_deref().unwrap_or("??"),
+                            format_value(a)
+                        )
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                ui.label(
+                    RichText::new(format!(
+                        "#{} {}({})",
+                        i,
+                        frame.function.as_deref().unwrap_or("??"),
+                        arguments
+                    ))
+                    .monospace(),
+                )
+                .on_hover_text(match i {
+                    0 => trf(ui.ctx(), "Executing {}", &[&format!("{:#x}", frame.pc)]),
+                    _ => trf(ui.ctx(), "Returns to {}", &[&format!("{:#x}", frame.pc)]),
+                });
+            }
+        });
+}
+
+impl DebuggerWindowImpl for ScopeWindow {
+    fn dirty(&mut self) {
+        self.scopes = dispatch!(
+            self.backend_url.clone(),
+            Command::GetVariableScopes,
+            VariableScopes
+        );
+        self.structures = dispatch!(
+            self.backend_url.clone(),
+            Command::GetDataStructures,
+            DataStructures
+        );
+        self.strings = dispatch!(
+            self.backend_url.clone(),
+            Command::DiscoverVariables,
+            DiscoveredVariables
+        );
+        self.frames = dispatch!(
+            self.backend_url.clone(),
+            Command::GetStackFrames,
+            StackFrames
+        );
+    }
+    fn receive(&mut self, message: &Message) {
+        if let Message::Select(selection) = message {
+            self.selection = selection.clone();
+        }
+    }
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        match self.scopes.ready() {
+            Some(Ok(scopes)) => {
+                if scopes.is_empty() {
+                    ui.label(tr(
+                        ui.ctx(),
+                        "The current function has no debug information",
+                    ));
+                }
+                let structures = match self.structures.ready() {
+                    Some(Ok(structures)) => structures.as_slice(),
+                    _ => &[],
+                };
+                let strings = match self.strings.ready() {
+                    Some(Ok(strings)) => strings.as_slice(),
+                    _ => &[],
+                };
+                egui::ScrollArea::vertical().show(ui, |ui| {
+                    for scope in scopes.iter() {
+                        render_scope(ui, scope, structures, strings, &mut self.selection);
+                    }
+                    if let Some(Ok(frames)) = self.frames.ready() {
+                        if !frames.is_empty() {
+                            render_call_stack(ui, frames);
+                        }
+                    }
+                });
+            }
+            Some(Err(err)) => {
+                ui.label(err);
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+        false
+    }
+}
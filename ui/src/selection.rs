@@ -0,0 +1,57 @@
+use egui::{Context, Id};
+use stackium_shared::{get_byte_size, Location, Variable};
+
+/// A variable selected in one window, the other windows highlight it
+#[derive(Clone, PartialEq)]
+pub struct Selection {
+    pub name: String,
+    pub address: Option<u64>,
+    pub size: u64,
+    /// Line the variable is declared in
+    pub line: Option<u64>,
+}
+
+impl Selection {
+    pub fn of(variable: &Variable) -> Self {
+        Self {
+            name: variable.name.clone().unwrap_or("??".to_owned()),
+            address: variable.addr,
+            size: variable
+                .type_name
+                .as_ref()
+                .map_or(0, |types| get_byte_size(types, 0) as u64),
+            line: variable.line,
+        }
+    }
+}
+
+/// Messages the windows send each other, the app passes every message to all windows with
+/// [`crate::debugger_window::DebuggerWindowImpl::receive`] at the end of the frame
+#[derive(Clone)]
+pub enum Message {
+    /// The selected variable changed, `None` clears the selection
+    Select(Option<Selection>),
+    /// Locate and highlight an address or pointer expression like `&x` in the memory window
+    Locate(String),
+    /// Show a line of a source file in the code window, e.g. the definition of a function
+    Show(Location),
+    /// Whether questions of the quiz are pending at the current stop, the memory window hides
+    /// the values until they are answered
+    QuizPending(bool),
+}
+
+fn id() -> Id {
+    Id::new("stackium_messages")
+}
+
+pub fn send(ctx: &Context, message: Message) {
+    ctx.data_mut(|d| {
+        d.get_temp_mut_or_default::<Vec<Message>>(id())
+            .push(message)
+    });
+}
+
+/// The messages sent since the last call
+pub fn take(ctx: &Context) -> Vec<Message> {
+    ctx.data_mut(|d| d.remove_temp(id())).unwrap_or_default()
+}
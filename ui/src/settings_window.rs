@@ -1,10 +1,25 @@
-use egui::RichText;
+use egui::{RichText, ThemePreference};
+use stackium_shared::VARIABLE_MEM_PADDING;
+use url::Url;
 
-use crate::{debugger_window::DebuggerWindowImpl, frame_history::FrameHistory};
+use crate::{
+    config::{Config, NumberBase, StepGranularity},
+    debugger_window::DebuggerWindowImpl,
+    driver,
+    frame_history::FrameHistory,
+    i18n::{tr, trf, Language},
+};
 
 pub struct SettingsWindow {
     frame_history: FrameHistory,
     run_mode: RunMode,
+    /// Backend url being edited, applied with the Connect button
+    backend_input: String,
+    /// Client name being edited, applied once the field loses focus
+    name_input: Option<String>,
+    url_error: Option<String>,
+    /// Backend url with the token and the id of this client, which `/logs` requires
+    backend_url: Url,
 }
 
 #[derive(PartialEq)]
@@ -14,10 +29,14 @@ enum RunMode {
 }
 
 impl SettingsWindow {
-    pub fn new() -> Self {
+    pub fn new(backend_url: Url) -> Self {
         Self {
             frame_history: FrameHistory::default(),
             run_mode: RunMode::Reactive,
+            backend_input: driver::without_identity(&backend_url).to_string(),
+            name_input: None,
+            url_error: None,
+            backend_url,
         }
     }
 }
@@ -35,27 +54,194 @@ impl DebuggerWindowImpl for SettingsWindow {
         }
     }
     fn ui(&mut self, ui: &mut egui::Ui) -> bool {
-        ui.collapsing("Debug Info", |ui| {
+        let mut config = Config::get(ui.ctx());
+        ui.collapsing(tr(ui.ctx(), "Connection"), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr(ui.ctx(), "Backend:"));
+                let input = ui.text_edit_singleline(&mut self.backend_input);
+                let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                if ui.button(tr(ui.ctx(), "Connect")).clicked() || submitted {
+                    match Url::parse(self.backend_input.trim()) {
+                        Ok(url) => {
+                            self.url_error = None;
+                            config.backend_url = url.to_string();
+                        }
+                        Err(err) => self.url_error = Some(err.to_string()),
+                    }
+                }
+            });
+            if let Some(err) = &self.url_error {
+                ui.label(
+                    RichText::new(trf(ui.ctx(), "⚠ Invalid url: {}", &[err]))
+                        .color(ui.visuals().warn_fg_color),
+                );
+            }
+            if let Ok(mut logs) = self.backend_url.join("logs") {
+                logs.set_query(self.backend_url.query());
+                ui.hyperlink_to(tr(ui.ctx(), "Backend log"), logs)
+                    .on_hover_text(tr(
+                        ui.ctx(),
+                        "The recent messages of the backend, attach them to bug reports",
+                    ));
+            }
+            ui.horizontal(|ui| {
+                ui.label(tr(ui.ctx(), "Name:"));
+                let name = self
+                    .name_input
+                    .get_or_insert_with(|| config.client_name.clone());
+                let input = ui
+                    .add(egui::TextEdit::singleline(name).hint_text(tr(ui.ctx(), "Instructor")))
+                    .on_hover_text(tr(
+                        ui.ctx(),
+                        "Shown to the other clients connected to the backend while you control the program",
+                    ));
+                // the UI reconnects with the new name, which shouldn't happen for every key
+                if input.lost_focus() {
+                    config.client_name = name.trim().to_owned();
+                    self.name_input = None;
+                }
+            });
+            ui.add(
+                egui::Slider::new(&mut config.poll_interval, 0.05..=2.0)
+                    .text(tr(ui.ctx(), "Status polling interval (s)")),
+            )
+            .on_hover_text(tr(
+                ui.ctx(),
+                "How often the status of a running program is checked",
+            ));
+        });
+        ui.collapsing(tr(ui.ctx(), "Debugging"), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr(ui.ctx(), "Step through:"));
+                ui.radio_value(
+                    &mut config.step_granularity,
+                    StepGranularity::Source,
+                    tr(ui.ctx(), "Source lines"),
+                );
+                ui.radio_value(
+                    &mut config.step_granularity,
+                    StepGranularity::Instruction,
+                    tr(ui.ctx(), "Instructions"),
+                );
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(ui.ctx(), "Numbers:"));
+                ui.radio_value(
+                    &mut config.number_base,
+                    NumberBase::Hexadecimal,
+                    tr(ui.ctx(), "Hexadecimal"),
+                );
+                ui.radio_value(
+                    &mut config.number_base,
+                    NumberBase::Decimal,
+                    tr(ui.ctx(), "Decimal"),
+                );
+            });
+            ui.add(
+                egui::Slider::new(&mut config.memory_padding, 0..=VARIABLE_MEM_PADDING)
+                    .text(tr(ui.ctx(), "Bytes shown around variables")),
+            )
+            .on_hover_text(tr(
+                ui.ctx(),
+                "Memory before and after every variable in the Memory window",
+            ));
+        });
+        ui.collapsing(tr(ui.ctx(), "Appearance"), |ui| {
+            ui.horizontal(|ui| {
+                ui.label(tr(ui.ctx(), "Color scheme:"));
+                let before = config.color_scheme;
+                ui.radio_value(
+                    &mut config.color_scheme,
+                    ThemePreference::System,
+                    tr(ui.ctx(), "System"),
+                );
+                ui.radio_value(
+                    &mut config.color_scheme,
+                    ThemePreference::Dark,
+                    tr(ui.ctx(), "Dark"),
+                );
+                ui.radio_value(
+                    &mut config.color_scheme,
+                    ThemePreference::Light,
+                    tr(ui.ctx(), "Light"),
+                );
+                if config.color_scheme != before {
+                    ui.ctx().set_theme(config.color_scheme);
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(tr(ui.ctx(), "Language:"));
+                for language in Language::ALL {
+                    ui.radio_value(&mut config.language, language, language.name());
+                }
+            });
+            ui.horizontal(|ui| {
+                ui.label(trf(
+                    ui.ctx(),
+                    "UI scale: {}%",
+                    &[&format!("{:.0}", ui.ctx().zoom_factor() * 100.0)],
+                ));
+                egui::gui_zoom::zoom_menu_buttons(ui);
+            });
+            ui.add(
+                egui::Slider::new(&mut config.theme.text_scale, 0.5..=2.5)
+                    .text(tr(ui.ctx(), "Diagram text size"))
+                    .fixed_decimals(1),
+            )
+            .on_hover_text(tr(
+                ui.ctx(),
+                "Size of the text in the memory plots, graphs and diagrams",
+            ));
+            if ui
+                .checkbox(
+                    &mut config.theme.accessible,
+                    tr(ui.ctx(), "Accessibility mode"),
+                )
+                .on_hover_text(tr(
+                    ui.ctx(),
+                    "Patterned strokes, labeled pointer arrows and high-contrast colors in the \
+                     Memory window, which describes the pointers to screen readers as well",
+                ))
+                .changed()
+            {
+                config.theme.apply(ui.ctx());
+            }
+        });
+        if ui.button(tr(ui.ctx(), "Reset to defaults")).clicked() {
+            config = Config::default();
+            self.backend_input = config.backend_url.clone();
+            self.name_input = None;
+            ui.ctx().set_theme(config.color_scheme);
+            config.theme.apply(ui.ctx());
+        }
+        Config::edit(ui.ctx(), |c| *c = config);
+        ui.separator();
+        ui.collapsing(tr(ui.ctx(), "Debug Info"), |ui| {
             ui.horizontal(|ui| {
                 let run_mode = &mut self.run_mode;
-                ui.label("Mode:");
-                ui.radio_value(run_mode, RunMode::Reactive, "Reactive")
-                    .on_hover_text(
+                ui.label(tr(ui.ctx(), "Mode:"));
+                ui.radio_value(run_mode, RunMode::Reactive, tr(ui.ctx(), "Reactive"))
+                    .on_hover_text(tr(
+                        ui.ctx(),
                         "Repaint when there are animations or input (e.g. mouse movement)",
-                    );
-                ui.radio_value(run_mode, RunMode::Continuous, "Continuous")
-                    .on_hover_text("Repaint everything each frame");
+                    ));
+                ui.radio_value(run_mode, RunMode::Continuous, tr(ui.ctx(), "Continuous"))
+                    .on_hover_text(tr(ui.ctx(), "Repaint everything each frame"));
             });
             if self.run_mode == RunMode::Continuous {
                 ui.label(
-                    RichText::new(format!(
-                        "⚠ Repainting the UI each frame. FPS: {:.1}",
-                        self.frame_history.fps()
+                    RichText::new(trf(
+                        ui.ctx(),
+                        "⚠ Repainting the UI each frame. FPS: {}",
+                        &[&format!("{:.1}", self.frame_history.fps())],
                     ))
                     .color(ui.visuals().warn_fg_color),
                 );
             } else {
-                ui.label("Only running UI code when there are animations or input.");
+                ui.label(tr(
+                    ui.ctx(),
+                    "Only running UI code when there are animations or input.",
+                ));
             }
             self.frame_history.ui(ui);
         });
@@ -0,0 +1,228 @@
+use std::io::Read;
+
+use egui::{RichText, ScrollArea};
+use stackium_shared::{
+    get_byte_size, DiscoveredVariable, MemoryChunk, Snapshot, VARIABLE_MEM_PADDING,
+};
+
+use crate::{
+    i18n::{tr, trf},
+    memory_window::format_element,
+};
+
+const BYTES_PER_ROW: usize = 16;
+
+/// Parses a snapshot exported as JSON or gzip compressed JSON
+pub fn parse_snapshot(bytes: &[u8]) -> Result<Snapshot, String> {
+    let json = if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut json = vec![];
+        flate2::read::GzDecoder::new(bytes)
+            .read_to_end(&mut json)
+            .map_err(|e| format!("Failed decompressing snapshot: {}", e))?;
+        json
+    } else {
+        bytes.to_vec()
+    };
+    serde_json::from_slice(&json).map_err(|e| format!("Failed parsing snapshot: {}", e))
+}
+
+/// Name, type, address and value of every variable
+pub fn render_variable_grid(ui: &mut egui::Ui, id: &str, variables: &[DiscoveredVariable]) {
+    egui::Grid::new(id).striped(true).show(ui, |ui| {
+        ui.label(RichText::new(tr(ui.ctx(), "Name")).strong());
+        ui.label(RichText::new(tr(ui.ctx(), "Type")).strong());
+        ui.label(RichText::new(tr(ui.ctx(), "Address")).strong());
+        ui.label(RichText::new(tr(ui.ctx(), "Value")).strong());
+        ui.end_row();
+        for variable in variables {
+            let (Some(name), Some(address), Some(memory)) =
+                (&variable.name, variable.addr, &variable.memory)
+            else {
+                continue;
+            };
+            let size = get_byte_size(&variable.types, variable.type_index);
+            let value = match (
+                &variable.string,
+                memory.get(VARIABLE_MEM_PADDING as usize..),
+            ) {
+                (Some(string), _) => string.clone(),
+                (None, Some(bytes)) if bytes.len() >= size => {
+                    format_element(&variable.types, variable.type_index, bytes)
+                }
+                _ => "..".to_owned(),
+            };
+            ui.label(RichText::new(name).monospace());
+            ui.label(RichText::new(variable.types.to_string()).monospace().weak());
+            ui.label(RichText::new(format!("{:#x}", address)).monospace());
+            ui.label(RichText::new(value).monospace());
+            ui.end_row();
+        }
+    });
+}
+
+#[derive(PartialEq, Clone, Copy)]
+enum SnapshotTab {
+    Overview,
+    Variables,
+    Stack,
+    Heap,
+    Maps,
+}
+
+/// Views an exported snapshot without a running debugger
+pub struct SnapshotView {
+    pub file_name: String,
+    snapshot: Snapshot,
+    tab: SnapshotTab,
+}
+
+impl SnapshotView {
+    pub fn new(file_name: String, snapshot: Snapshot) -> Self {
+        Self {
+            file_name,
+            snapshot,
+            tab: SnapshotTab::Overview,
+        }
+    }
+
+    fn render_overview(&self, ui: &mut egui::Ui) {
+        let snapshot = &self.snapshot;
+        ui.label(trf(ui.ctx(), "Program: {}", &[&snapshot.program]));
+        match &snapshot.location {
+            Some(location) => ui.label(trf(
+                ui.ctx(),
+                "Stopped at {}:{}",
+                &[&location.file, &location.line],
+            )),
+            None => ui.label(tr(ui.ctx(), "Stopped outside of the source code")),
+        };
+        ui.separator();
+        egui::Grid::new("snapshot_registers")
+            .striped(true)
+            .show(ui, |ui| {
+                for register in snapshot
+                    .full_registers
+                    .general
+                    .iter()
+                    .chain(snapshot.full_registers.segment.iter())
+                {
+                    ui.label(RichText::new(&register.name).monospace().strong());
+                    ui.label(RichText::new(format!("{:#018x}", register.value)).monospace());
+                    ui.label(RichText::new(register.value.to_string()).monospace().weak());
+                    ui.end_row();
+                }
+            });
+        ui.horizontal_wrapped(|ui| {
+            for flag in snapshot.full_registers.flags.iter() {
+                let text = RichText::new(&flag.name).monospace();
+                ui.label(if flag.set { text.strong() } else { text.weak() })
+                    .on_hover_text(&flag.description);
+            }
+        });
+    }
+
+    fn render_maps(&self, ui: &mut egui::Ui) {
+        egui::Grid::new("snapshot_maps")
+            .striped(true)
+            .show(ui, |ui| {
+                for map in self.snapshot.maps.iter() {
+                    ui.label(RichText::new(format!("{:#x}..{:#x}", map.from, map.to)).monospace());
+                    ui.label(
+                        RichText::new(format!(
+                            "{}{}{}",
+                            if map.read { 'r' } else { '-' },
+                            if map.write { 'w' } else { '-' },
+                            if map.execute { 'x' } else { '-' }
+                        ))
+                        .monospace(),
+                    );
+                    ui.label(&map.label);
+                    ui.end_row();
+                }
+            });
+    }
+
+    fn render_memory(ui: &mut egui::Ui, chunk: &MemoryChunk) {
+        ui.label(
+            RichText::new(format!(
+                "── {} {:#x}..{:#x}",
+                chunk.region,
+                chunk.from,
+                chunk.from + chunk.memory.len() as u64
+            ))
+            .small()
+            .strong(),
+        );
+        let row_height = ui.text_style_height(&egui::TextStyle::Monospace);
+        let rows = (chunk.memory.len() + BYTES_PER_ROW - 1) / BYTES_PER_ROW;
+        ScrollArea::vertical()
+            .id_salt(chunk.from)
+            .show_rows(ui, row_height, rows, |ui, range| {
+                for row in range {
+                    let bytes = &chunk.memory
+                        [row * BYTES_PER_ROW..((row + 1) * BYTES_PER_ROW).min(chunk.memory.len())];
+                    let hex = bytes
+                        .iter()
+                        .map(|b| format!("{:02x}", b))
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    let ascii = bytes
+                        .iter()
+                        .map(|b| match b {
+                            0x20..=0x7e => *b as char,
+                            _ => '.',
+                        })
+                        .collect::<String>();
+                    ui.label(
+                        RichText::new(format!(
+                            "{:#018x}  {:<47}  {}",
+                            chunk.from + (row * BYTES_PER_ROW) as u64,
+                            hex,
+                            ascii
+                        ))
+                        .monospace(),
+                    );
+                }
+            });
+    }
+
+    pub fn ui(&mut self, ui: &mut egui::Ui) {
+        ui.horizontal(|ui| {
+            for (tab, label) in [
+                (SnapshotTab::Overview, "Overview"),
+                (SnapshotTab::Variables, "Variables"),
+                (SnapshotTab::Stack, "Stack"),
+                (SnapshotTab::Heap, "Heap"),
+                (SnapshotTab::Maps, "Memory Mapping"),
+            ] {
+                ui.selectable_value(&mut self.tab, tab, tr(ui.ctx(), label));
+            }
+        });
+        ui.separator();
+        match self.tab {
+            SnapshotTab::Overview => {
+                ScrollArea::vertical().show(ui, |ui| self.render_overview(ui));
+            }
+            SnapshotTab::Variables => {
+                ScrollArea::vertical().show(ui, |ui| {
+                    render_variable_grid(ui, "snapshot_variables", &self.snapshot.variables)
+                });
+            }
+            SnapshotTab::Stack => Self::render_memory(ui, &self.snapshot.stack),
+            SnapshotTab::Heap => {
+                if self.snapshot.heap.is_empty() {
+                    ui.label(tr(
+                        ui.ctx(),
+                        "The program had no heap when the snapshot was taken",
+                    ));
+                }
+                for chunk in self.snapshot.heap.iter() {
+                    Self::render_memory(ui, chunk);
+                }
+            }
+            SnapshotTab::Maps => {
+                ScrollArea::vertical().show(ui, |ui| self.render_maps(ui));
+            }
+        }
+    }
+}
@@ -1,30 +1,40 @@
-use egui::{text::LayoutJob, FontId, Response};
+use egui::{text::LayoutJob, text_edit::TextEditOutput, FontId, Response};
 
 /// View some code with syntax highlighting and selection.
 pub fn code_view_ui(
     ui: &mut egui::Ui,
-    mut code: &str,
+    code: &str,
     theme: &CodeTheme,
     language: &str,
     font_size: f32,
 ) -> Response {
+    code_line_ui(ui, code, theme, language, font_size).response
+}
+
+/// Like [`code_view_ui`], the galley in the output tells which character is hovered
+pub fn code_line_ui(
+    ui: &mut egui::Ui,
+    mut code: &str,
+    theme: &CodeTheme,
+    language: &str,
+    font_size: f32,
+) -> TextEditOutput {
     let mut layouter = |ui: &egui::Ui, string: &str, _wrap_width: f32| {
         let layout_job = highlight(ui.ctx(), &theme, string, language);
         // layout_job.wrap.max_width = wrap_width; // no wrapping
         ui.fonts(|f| f.layout_job(layout_job))
     };
 
-    ui.add(
-        egui::TextEdit::multiline(&mut code)
-            .font(FontId {
-                size: font_size,
-                family: egui::FontFamily::Monospace,
-            }) // for cursor height
-            .code_editor()
-            .desired_rows(1)
-            .lock_focus(true)
-            .layouter(&mut layouter),
-    )
+    egui::TextEdit::multiline(&mut code)
+        .font(FontId {
+            size: font_size,
+            family: egui::FontFamily::Monospace,
+        }) // for cursor height
+        .code_editor()
+        .desired_rows(1)
+        .lock_focus(true)
+        .layouter(&mut layouter)
+        .show(ui)
 }
 
 /// Memoized Code highlighting
@@ -90,12 +100,12 @@ impl CodeTheme {
     pub fn from_memory(ctx: &egui::Context) -> Self {
         if ctx.style().visuals.dark_mode {
             ctx.data_mut(|d| {
-                d.get_persisted(egui::Id::new("dark"))
+                d.get_temp(egui::Id::new("dark"))
                     .unwrap_or_else(|| CodeTheme::dark(10.0))
             })
         } else {
             ctx.data_mut(|d| {
-                d.get_persisted(egui::Id::new("light"))
+                d.get_temp(egui::Id::new("light"))
                     .unwrap_or_else(|| CodeTheme::light(10.0))
             })
         }
@@ -103,9 +113,9 @@ impl CodeTheme {
 
     pub fn store_in_memory(self, ctx: &egui::Context) {
         if self.dark_mode {
-            ctx.data_mut(|d| d.insert_persisted(egui::Id::new("dark"), self));
+            ctx.data_mut(|d| d.insert_temp(egui::Id::new("dark"), self));
         } else {
-            ctx.data_mut(|d| d.insert_persisted(egui::Id::new("light"), self));
+            ctx.data_mut(|d| d.insert_temp(egui::Id::new("light"), self));
         }
     }
 }
@@ -148,7 +158,7 @@ impl CodeTheme {
         ui.horizontal_top(|ui| {
             let selected_id = egui::Id::new(1);
             let mut selected_tt: TokenType =
-                ui.data_mut(|d| *d.get_persisted_mut_or(selected_id, TokenType::Comment));
+                ui.data_mut(|d| *d.get_temp_mut_or(selected_id, TokenType::Comment));
 
             ui.vertical(|ui| {
                 ui.set_width(150.0);
@@ -190,7 +200,7 @@ impl CodeTheme {
 
             ui.add_space(16.0);
 
-            ui.data_mut(|d| d.insert_persisted(selected_id, selected_tt));
+            ui.data_mut(|d| d.insert_temp(selected_id, selected_tt));
 
             egui::Frame::group(ui.style())
                 .inner_margin(egui::Vec2::splat(2.0))
@@ -0,0 +1,150 @@
+use egui::{Color32, Context, FontFamily, FontId, Stroke, Visuals};
+use egui_plot::LineStyle;
+use serde::{Deserialize, Serialize};
+
+use crate::config::Config;
+
+/// Appearance of the plots and diagrams the windows draw themselves, part of the [`Config`].
+/// Colors are derived from the egui visuals so they stay readable in light and dark mode.
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Theme {
+    /// Factor applied to the text drawn into plots and diagrams
+    pub text_scale: f32,
+    /// Distinguishes the variables, pointers and frames by the pattern of their strokes as well,
+    /// labels every pointer arrow with its source and destination and uses high-contrast colors
+    pub accessible: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            text_scale: 1.0,
+            accessible: false,
+        }
+    }
+}
+
+impl Theme {
+    pub fn get(ctx: &Context) -> Self {
+        Config::get(ctx).theme
+    }
+
+    /// Applies the high-contrast visuals of the accessibility mode, or the default ones
+    pub fn apply(&self, ctx: &Context) {
+        for (theme, visuals) in [
+            (egui::Theme::Dark, Visuals::dark()),
+            (egui::Theme::Light, Visuals::light()),
+        ] {
+            let visuals = match self.accessible {
+                true => high_contrast(visuals),
+                false => visuals,
+            };
+            ctx.set_visuals_of(theme, visuals);
+        }
+    }
+}
+
+/// Black text on white or white text on black with strong widget outlines
+fn high_contrast(mut visuals: Visuals) -> Visuals {
+    let (text, background) = match visuals.dark_mode {
+        true => (Color32::WHITE, Color32::BLACK),
+        false => (Color32::BLACK, Color32::WHITE),
+    };
+    visuals.override_text_color = Some(text);
+    visuals.panel_fill = background;
+    visuals.window_fill = background;
+    visuals.extreme_bg_color = background;
+    visuals.window_stroke = Stroke::new(1.5, text);
+    let widgets = &mut visuals.widgets;
+    for widget in [
+        &mut widgets.noninteractive,
+        &mut widgets.inactive,
+        &mut widgets.hovered,
+        &mut widgets.active,
+        &mut widgets.open,
+    ] {
+        widget.fg_stroke = Stroke::new(widget.fg_stroke.width.max(1.5), text);
+        widget.bg_stroke = Stroke::new(widget.bg_stroke.width.max(1.0), text);
+    }
+    visuals.selection.stroke = Stroke::new(2.0, text);
+    visuals
+}
+
+/// Colors for telling neighbouring variables, pointers and frames apart, lighter on dark
+/// backgrounds and darker on light ones. The accessibility mode uses colors which people with
+/// color vision deficiencies can tell apart.
+pub fn palette(ctx: &Context, index: usize) -> Color32 {
+    const DARK: [Color32; 6] = [
+        Color32::from_rgb(0x64, 0x9c, 0xff),
+        Color32::from_rgb(0x4c, 0xd9, 0x64),
+        Color32::from_rgb(0xff, 0x6e, 0x6e),
+        Color32::from_rgb(0x40, 0xe0, 0xe0),
+        Color32::from_rgb(0xf0, 0x70, 0xf0),
+        Color32::from_rgb(0xf0, 0xd0, 0x40),
+    ];
+    const LIGHT: [Color32; 6] = [
+        Color32::from_rgb(0x1e, 0x4f, 0xd8),
+        Color32::from_rgb(0x1b, 0x8a, 0x2f),
+        Color32::from_rgb(0xc6, 0x28, 0x28),
+        Color32::from_rgb(0x00, 0x83, 0x8f),
+        Color32::from_rgb(0xa0, 0x1e, 0xa0),
+        Color32::from_rgb(0x9e, 0x7a, 0x00),
+    ];
+    // the Okabe-Ito palette, adjusted for the background
+    const ACCESSIBLE_DARK: [Color32; 6] = [
+        Color32::from_rgb(0x56, 0xb4, 0xe9),
+        Color32::from_rgb(0xe6, 0x9f, 0x00),
+        Color32::from_rgb(0x00, 0xc0, 0x90),
+        Color32::from_rgb(0xf0, 0xe4, 0x42),
+        Color32::from_rgb(0xff, 0x80, 0x40),
+        Color32::from_rgb(0xe0, 0x90, 0xc0),
+    ];
+    const ACCESSIBLE_LIGHT: [Color32; 6] = [
+        Color32::from_rgb(0x00, 0x50, 0x90),
+        Color32::from_rgb(0xa0, 0x50, 0x00),
+        Color32::from_rgb(0x00, 0x70, 0x50),
+        Color32::from_rgb(0x50, 0x50, 0x50),
+        Color32::from_rgb(0xb0, 0x30, 0x00),
+        Color32::from_rgb(0x90, 0x30, 0x70),
+    ];
+    let colors = match (Theme::get(ctx).accessible, ctx.style().visuals.dark_mode) {
+        (false, true) => DARK,
+        (false, false) => LIGHT,
+        (true, true) => ACCESSIBLE_DARK,
+        (true, false) => ACCESSIBLE_LIGHT,
+    };
+    colors[index % colors.len()]
+}
+
+/// Stroke pattern going with the [`palette`] color of `index`, solid unless the accessibility mode
+/// is on
+pub fn line_style(ctx: &Context, index: usize) -> LineStyle {
+    const PATTERNS: [LineStyle; 6] = [
+        LineStyle::Solid,
+        LineStyle::Dashed { length: 10.0 },
+        LineStyle::Dotted { spacing: 6.0 },
+        LineStyle::Dashed { length: 4.0 },
+        LineStyle::Dotted { spacing: 12.0 },
+        LineStyle::Dashed { length: 18.0 },
+    ];
+    match Theme::get(ctx).accessible {
+        true => PATTERNS[index % PATTERNS.len()],
+        false => LineStyle::Solid,
+    }
+}
+
+/// Black or white, whichever is readable on `fill`
+pub fn text_on(fill: Color32) -> Color32 {
+    let luminance = 0.299 * fill.r() as f32 + 0.587 * fill.g() as f32 + 0.114 * fill.b() as f32;
+    if luminance > 150.0 {
+        Color32::BLACK
+    } else {
+        Color32::WHITE
+    }
+}
+
+/// Monospace font for text drawn with a painter, `size` is scaled by the theme
+pub fn monospace(ctx: &Context, size: f32) -> FontId {
+    FontId::new(size * Theme::get(ctx).text_scale, FontFamily::Monospace)
+}
@@ -0,0 +1,232 @@
+use std::collections::HashMap;
+
+use egui::{Align2, Rect, RichText, Sense, Stroke, Vec2};
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, DataStructure, StructureKind, StructureNode};
+use url::Url;
+
+use crate::{
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+    theme::{monospace, Theme},
+};
+
+/// Space between neighbouring nodes and between levels
+const SPACING: Vec2 = Vec2::new(12.0, 36.0);
+const LINE_HEIGHT: f32 = 16.0;
+const CHAR_WIDTH: f32 = 7.5;
+
+/// A binary tree reached from a variable laid out top-down by depth. The nodes are placed in
+/// in-order, so every left subtree is left of its parent and every right subtree right of it.
+pub struct TreeWindow {
+    backend_url: Url,
+    structures: Promise<Result<Vec<DataStructure>, String>>,
+    /// Variable of the shown tree, the first tree if `None`
+    selected: Option<String>,
+    /// Fields of the nodes at the previous stop by address, nodes that changed are highlighted
+    previous: HashMap<u64, Vec<(String, String)>>,
+}
+
+impl TreeWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            structures: Promise::from_ready(Ok(vec![])),
+            selected: None,
+            previous: HashMap::new(),
+        };
+        ret.dirty();
+        ret
+    }
+
+    fn changed(&self, node: &StructureNode) -> bool {
+        !self.previous.is_empty() && self.previous.get(&node.address) != Some(&node.fields)
+    }
+
+    fn render_tree(&self, ui: &mut egui::Ui, tree: &DataStructure, left: &str) {
+        let text = tree.nodes.iter().map(node_text).collect::<Vec<_>>();
+        let node_size = node_size(ui.ctx(), &text);
+        let depth = tree.nodes.iter().map(|n| n.depth).max().unwrap_or(0);
+        let (response, painter) = ui.allocate_painter(
+            Vec2::new(
+                tree.nodes.len() as f32 * (node_size.x + SPACING.x),
+                (depth + 1) as f32 * (node_size.y + SPACING.y),
+            ),
+            Sense::hover(),
+        );
+        let origin = response.rect.min;
+        let rect_of = |i: usize| {
+            Rect::from_min_size(
+                origin
+                    + Vec2::new(
+                        i as f32 * (node_size.x + SPACING.x),
+                        tree.nodes[i].depth as f32 * (node_size.y + SPACING.y),
+                    ),
+                node_size,
+            )
+        };
+        let font = monospace(ui.ctx(), 12.0);
+        // edges first so the nodes are drawn over them
+        for (i, node) in tree.nodes.iter().enumerate() {
+            for link in node.links.iter() {
+                let from = rect_of(i).center_bottom();
+                let is_left = link.member == left;
+                let (to, color) = match link.node {
+                    Some(target) => (rect_of(target).center_top(), ui.visuals().text_color()),
+                    None if link.address != 0 && !link.mapped => {
+                        let offset = if is_left { -1.0 } else { 1.0 } * node_size.x / 3.0;
+                        (
+                            from + Vec2::new(offset, SPACING.y / 2.0),
+                            ui.visuals().error_fg_color,
+                        )
+                    }
+                    None => continue,
+                };
+                painter.line_segment([from, to], Stroke::new(2.0, color));
+                painter.text(
+                    from.lerp(to, 0.5),
+                    if is_left {
+                        Align2::RIGHT_CENTER
+                    } else {
+                        Align2::LEFT_CENTER
+                    },
+                    if link.node.is_some() {
+                        link.member.clone()
+                    } else {
+                        format!("{} ✖ {:#x}", link.member, link.address)
+                    },
+                    font.clone(),
+                    color,
+                );
+            }
+        }
+        for (i, node) in tree.nodes.iter().enumerate() {
+            let rect = rect_of(i);
+            let stroke_color = if self.changed(node) {
+                ui.visuals().warn_fg_color
+            } else {
+                ui.visuals().text_color()
+            };
+            painter.rect(
+                rect,
+                4.0,
+                ui.visuals().extreme_bg_color,
+                Stroke::new(2.0, stroke_color),
+            );
+            painter.text(
+                rect.center(),
+                Align2::CENTER_CENTER,
+                &text[i],
+                font.clone(),
+                ui.visuals().text_color(),
+            );
+            ui.interact(rect, ui.id().with(node.address), Sense::hover())
+                .on_hover_text(format!(
+                    "{} @ {:#x}\n{}",
+                    tree.node_type,
+                    node.address,
+                    node.fields
+                        .iter()
+                        .map(|(name, value)| format!("{} = {}", name, value))
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                ));
+        }
+    }
+}
+
+/// Size fitting the longest and tallest of the node texts, all nodes are drawn equally big
+pub fn node_size(ctx: &egui::Context, text: &[String]) -> Vec2 {
+    let scale = Theme::get(ctx).text_scale;
+    let lines = text.iter().map(|t| t.lines().count()).max().unwrap_or(1);
+    let chars = text
+        .iter()
+        .flat_map(|t| t.lines())
+        .map(|l| l.chars().count())
+        .max()
+        .unwrap_or(1);
+    Vec2::new(
+        (chars as f32 * CHAR_WIDTH * scale + 16.0).max(40.0),
+        lines.max(1) as f32 * LINE_HEIGHT * scale + 8.0,
+    )
+}
+
+/// The values of the node, with their names if there is more than one
+pub fn node_text(node: &StructureNode) -> String {
+    match node.fields.as_slice() {
+        [(_, value)] => value.clone(),
+        fields => fields
+            .iter()
+            .map(|(name, value)| format!("{} = {}", name, value))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    }
+}
+
+impl DebuggerWindowImpl for TreeWindow {
+    fn dirty(&mut self) {
+        if let Some(Ok(structures)) = self.structures.ready() {
+            self.previous = structures
+                .iter()
+                .flat_map(|s| s.nodes.iter())
+                .map(|n| (n.address, n.fields.clone()))
+                .collect();
+        }
+        self.structures = dispatch!(
+            self.backend_url.clone(),
+            Command::GetDataStructures,
+            DataStructures
+        );
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        let trees = match self.structures.ready() {
+            Some(Ok(structures)) => structures
+                .iter()
+                .filter(|s| matches!(s.kind, StructureKind::BinaryTree { .. }))
+                .collect::<Vec<_>>(),
+            Some(Err(err)) => {
+                ui.label(err);
+                return false;
+            }
+            None => {
+                ui.spinner();
+                return false;
+            }
+        };
+        let Some(tree) = trees
+            .iter()
+            .find(|t| self.selected.as_ref() == Some(&t.variable))
+            .or(trees.first())
+        else {
+            ui.label(tr(
+                ui.ctx(),
+                "No variable of the current function points to a binary tree",
+            ));
+            return false;
+        };
+        let mut selected = tree.variable.clone();
+        ui.horizontal(|ui| {
+            egui::ComboBox::from_id_salt("tree_variable")
+                .selected_text(&selected)
+                .show_ui(ui, |ui| {
+                    for t in trees.iter() {
+                        ui.selectable_value(&mut selected, t.variable.clone(), &t.variable);
+                    }
+                });
+            let count = match tree.truncated {
+                true => trf(ui.ctx(), "first {} nodes", &[&tree.nodes.len()]),
+                false => trf(ui.ctx(), "{} nodes", &[&tree.nodes.len()]),
+            };
+            ui.label(RichText::new(format!("{}, {}", tree.node_type, count)).weak());
+        });
+        ui.separator();
+        if tree.nodes.is_empty() {
+            ui.label(trf(ui.ctx(), "{} is empty", &[&tree.variable]));
+        } else if let StructureKind::BinaryTree { left, .. } = &tree.kind {
+            egui::ScrollArea::both().show(ui, |ui| self.render_tree(ui, tree, left));
+        }
+        self.selected = Some(selected);
+        false
+    }
+}
@@ -1,9 +1,18 @@
 use egui::{Color32, FontId, Pos2, RichText, ScrollArea, Stroke, Vec2};
 use poll_promise::Promise;
-use stackium_shared::{Command, CommandOutput, DataType, MemoryMap, Registers, TypeName, Variable};
+use stackium_shared::{
+    get_byte_size, Command, CommandOutput, DataType, Frame, MemoryMap, Registers, TypeName,
+    Variable,
+};
 use url::Url;
 
-use crate::{command::dispatch_command_and_then, debugger_window::DebuggerWindowImpl};
+use crate::{
+    address::AddressResolver,
+    command::dispatch_command_and_then,
+    debugger_window::DebuggerWindowImpl,
+    i18n::{tr, trf},
+    theme::{monospace, palette, text_on},
+};
 
 #[derive(PartialEq)]
 enum ActiveTab {
@@ -15,6 +24,8 @@ type Section = (u64, u64, String, Promise<Result<Vec<u8>, String>>);
 
 pub struct VariableWindow {
     variables: Promise<Result<Vec<Variable>, String>>,
+    /// Frames with the slots of the saved registers, shown as pointers next to the variables
+    backtrace: Promise<Result<Vec<Frame>, String>>,
     backend_url: Url,
     active_tab: ActiveTab,
     registers: Promise<Result<Registers, String>>,
@@ -25,6 +36,7 @@ pub struct VariableWindow {
     lock_stack: bool,
     lock_stack_addr: u64,
     rsp_offset: u64,
+    addresses: AddressResolver,
 }
 
 fn arrow_tip_length(
@@ -118,10 +130,7 @@ fn render_invalid_ptr_arrow(ui: &egui::Ui, rect: &egui::Rect, pos: f32, color: C
         Pos2::new(rect.max.x - 70.0, pos),
         egui::Align2::LEFT_CENTER,
         "?",
-        FontId {
-            size: 24.0,
-            family: egui::FontFamily::Monospace,
-        },
+        monospace(ui.ctx(), 24.0),
         color,
     );
 }
@@ -152,11 +161,8 @@ fn render_var_line(
             let galley = ui.painter().layout(
                 // (bottom - top).to_string(),
                 name.to_string(),
-                FontId {
-                    size: 15.0,
-                    family: egui::FontFamily::Monospace,
-                },
-                egui::Color32::WHITE,
+                monospace(ui.ctx(), 15.0),
+                text_on(color),
                 bottom - top,
             );
             let pos = Pos2::new(rect.min.x + offset - 8.0, bottom - 5.0);
@@ -185,29 +191,11 @@ fn render_var_line(
             Pos2::new(rect.min.x + 15.0 + offset, top + (bottom - top) / 2.0),
             egui::Align2::LEFT_CENTER,
             name,
-            FontId {
-                size: 10.0,
-                family: egui::FontFamily::Monospace,
-            },
+            monospace(ui.ctx(), 10.0),
             color,
         );
     }
 }
-pub fn get_byte_size(types: &DataType, index: usize) -> usize {
-    match &types.0[index].1 {
-        TypeName::Name { name: _, byte_size } => *byte_size,
-        TypeName::Arr { arr_type, count } => {
-            count.iter().cloned().fold(1, |e1, e2| e1 * e2) * get_byte_size(types, *arr_type)
-        }
-        TypeName::Ref { index: _ } => 8usize,
-        TypeName::ProductType {
-            name: _,
-            members: _,
-            byte_size,
-        } => *byte_size,
-    }
-}
-
 fn read_value_stack(addr: u64, registers: &Registers, rsp_offset: u64, stack: &[u8]) -> u64 {
     if addr < registers.stack_pointer - rsp_offset {
         return 0;
@@ -343,6 +331,9 @@ fn render_variable_override(
                             addr: Some(addr),
                             high_pc: var.high_pc,
                             low_pc: var.low_pc,
+                            in_scope: var.in_scope,
+                            optimized: None,
+                            parameter: false,
                         },
                         offset + 20.0,
                         stack,
@@ -420,6 +411,9 @@ fn render_variable_override(
                             addr: Some(addr),
                             high_pc: var.high_pc,
                             low_pc: var.low_pc,
+                            in_scope: var.in_scope,
+                            optimized: None,
+                            parameter: false,
                         },
                         offset + 20.0,
                         stack,
@@ -468,13 +462,6 @@ fn read_heap_value(addr: u64, sections: &Vec<Section>) -> Option<u64> {
     None
 }
 
-const COLORS: [Color32; 4] = [
-    Color32::DARK_RED,
-    Color32::from_rgb(169, 158, 0),
-    Color32::DARK_GREEN,
-    Color32::DARK_BLUE,
-];
-
 //TODO: maybe return possible section to load and factor out section loading code to seperate function in render_stack function
 // (size,addr)
 fn render_heap_variable(
@@ -501,7 +488,7 @@ fn render_heap_variable(
         top,
         bottom,
         &types.0[type_index].1.to_string(),
-        COLORS[color_walk as usize % COLORS.len()],
+        palette(ui.ctx(), color_walk as usize),
         true,
     );
     let mut ret_val = vec![];
@@ -577,7 +564,7 @@ fn render_heap_variable(
                         ui,
                         rect,
                         draw_ref_count,
-                        COLORS[color_walk % COLORS.len()],
+                        palette(ui.ctx(), color_walk),
                         (top + bottom) / 2.0 + 10.0,
                         get_section_y(rect, sections, value),
                         true,
@@ -718,6 +705,8 @@ impl VariableWindow {
     pub fn new(backend_url: Url) -> Self {
         let mut s = Self {
             variables: Promise::from_ready(Err(String::new())),
+            backtrace: Promise::from_ready(Ok(vec![])),
+            addresses: AddressResolver::new(backend_url.clone()),
             backend_url,
             active_tab: ActiveTab::StackView,
             registers: Promise::from_ready(Err(String::new())),
@@ -744,13 +733,13 @@ impl VariableWindow {
                         .column(egui_extras::Column::remainder())
                         .header(20.0, |mut header| {
                             header.col(|ui| {
-                                ui.heading("Name");
+                                ui.heading(tr(ui.ctx(), "Name"));
                             });
                             header.col(|ui| {
-                                ui.heading("Address");
+                                ui.heading(tr(ui.ctx(), "Address"));
                             });
                             header.col(|ui| {
-                                ui.heading("Content");
+                                ui.heading(tr(ui.ctx(), "Content"));
                             });
                         })
                         .body(|mut body| {
@@ -787,7 +776,7 @@ impl VariableWindow {
                                             ui.label(format!("{:#x}", address));
                                         });
                                         row.col(|ui| {
-                                            ui.label(format!("{:#x}", value));
+                                            ui.label(self.addresses.format(value));
                                         });
                                     });
                                 }
@@ -801,8 +790,40 @@ impl VariableWindow {
         }
     }
 
+    /// The return address and the registers saved by the current function as variables, e.g.
+    /// `Return Address (main+23)` and `Saved rbp`
+    fn saved_registers(&mut self) -> Vec<Variable> {
+        let Some(Ok(frames)) = self.backtrace.ready() else {
+            return vec![];
+        };
+        let Some(frame) = frames.first() else {
+            return vec![];
+        };
+        let saved_registers = frame.saved_registers.clone();
+        saved_registers
+            .into_iter()
+            .map(|saved| {
+                let name = match saved.name.as_str() {
+                    "return address" => match self.addresses.describe(saved.value) {
+                        Some(description) => format!("Return Address ({})", description),
+                        None => "Return Address".to_owned(),
+                    },
+                    register => format!("Saved {}", register),
+                };
+                Variable {
+                    name: Some(name),
+                    type_name: Some(DataType(vec![(0, TypeName::Ref { index: None })])),
+                    addr: Some(saved.address),
+                    in_scope: true,
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
     fn render_stack(&mut self, ui: &mut egui::Ui) -> egui::Response {
         let rsp_offset = self.rsp_offset;
+        let saved_registers = self.saved_registers();
         if let Some(Ok(registers)) = self.registers.ready() {
             let stack_start = if self.lock_stack {
                 self.lock_stack_addr
@@ -824,13 +845,13 @@ impl VariableWindow {
                                         body.row(height, |mut row| {
                                             row.col(|ui| {
                                                 ui.label(
-                                                    RichText::new("Address")
+                                                    RichText::new(tr(ui.ctx(), "Address"))
                                                         .color(ui.visuals().strong_text_color()),
                                                 );
                                             });
                                             row.col(|ui| {
                                                 ui.label(
-                                                    RichText::new("Byte")
+                                                    RichText::new(tr(ui.ctx(), "Byte"))
                                                         .color(ui.visuals().strong_text_color()),
                                                 );
                                             });
@@ -886,43 +907,7 @@ impl VariableWindow {
                                         |ui| {
                                             for (ivar, var) in vars
                                                 .iter()
-                                                .chain(
-                                                    [
-                                                        Variable {
-                                                            name: Some("Return Address".to_owned()),
-                                                            type_name: Some(DataType(vec![(
-                                                                0,
-                                                                stackium_shared::TypeName::Ref {
-                                                                    index: None,
-                                                                },
-                                                            )])),
-                                                            value: None,
-                                                            file: None,
-                                                            line: None,
-                                                            addr: Some(stack_start + 8),
-                                                            high_pc: 0,
-                                                            low_pc: 0,
-                                                        },
-                                                        Variable {
-                                                            name: Some(
-                                                                "Calling Base Pointer".to_owned(),
-                                                            ),
-                                                            type_name: Some(DataType(vec![(
-                                                                0,
-                                                                stackium_shared::TypeName::Ref {
-                                                                    index: None,
-                                                                },
-                                                            )])),
-                                                            value: None,
-                                                            file: None,
-                                                            line: None,
-                                                            addr: Some(stack_start),
-                                                            high_pc: 0,
-                                                            low_pc: 0,
-                                                        },
-                                                    ]
-                                                    .iter(),
-                                                )
+                                                .chain(saved_registers.iter())
                                                 .enumerate()
                                             {
                                                 render_variable(
@@ -932,7 +917,7 @@ impl VariableWindow {
                                                     rsp_offset,
                                                     heightpad,
                                                     height,
-                                                    COLORS[ivar % COLORS.len()],
+                                                    palette(ui.ctx(), ivar),
                                                     &mut draw_ref_count,
                                                     var,
                                                     0f32,
@@ -969,7 +954,7 @@ impl VariableWindow {
                                                                 ui,
                                                                 &rect,
                                                                 &mut draw_ref_count,
-                                                                COLORS[ivar % COLORS.len()],
+                                                                palette(ui.ctx(), ivar),
                                                                 current_y,
                                                                 dst_y,
                                                                 false,
@@ -992,7 +977,7 @@ impl VariableWindow {
                                                                         rsp_offset,
                                                                         heightpad,
                                                                         height,
-                                                                        COLORS[ivar % COLORS.len()],
+                                                                        palette(ui.ctx(), ivar),
                                                                         &mut draw_ref_count,
                                                                         &Variable {
                                                                             name: Some(
@@ -1009,6 +994,9 @@ impl VariableWindow {
                                                                             addr: Some(value),
                                                                             high_pc: 0,
                                                                             low_pc: 0,
+                                                                            in_scope: true,
+                                                                            optimized: None,
+                                                                            parameter: false,
                                                                         },
                                                                         0f32,
                                                                         stack,
@@ -1065,7 +1053,7 @@ impl VariableWindow {
                                                                 ui,
                                                                 &rect,
                                                                 &mut draw_ref_count,
-                                                                COLORS[ivar % COLORS.len()],
+                                                                palette(ui.ctx(), ivar),
                                                                 current_y,
                                                                 dst_y,
                                                                 true,
@@ -1117,7 +1105,7 @@ impl VariableWindow {
                                                                     ui,
                                                                     &rect,
                                                                     current_y,
-                                                                    COLORS[ivar % COLORS.len()],
+                                                                    palette(ui.ctx(), ivar),
                                                                 )
                                                             }
                                                         }
@@ -1227,11 +1215,8 @@ impl VariableWindow {
                                             ) + height / 2.0,
                                         ),
                                         egui::Align2::LEFT_CENTER,
-                                        "Stack Pointer",
-                                        FontId {
-                                            size: 10.0,
-                                            family: egui::FontFamily::Monospace,
-                                        },
+                                        tr(ui.ctx(), "Stack Pointer"),
+                                        monospace(ui.ctx(), 10.0),
                                         ui.visuals().text_color(),
                                     );
                                 }
@@ -1273,7 +1258,9 @@ impl VariableWindow {
 impl DebuggerWindowImpl for VariableWindow {
     fn dirty(&mut self) {
         self.additional_loaded_sections.clear();
+        self.addresses.clear();
         self.variables = dispatch!(self.backend_url.clone(), Command::ReadVariables, Variables);
+        self.backtrace = dispatch!(self.backend_url.clone(), Command::Backtrace, Backtrace);
         self.registers = dispatch!(self.backend_url.clone(), Command::GetRegister, Registers);
         self.mapping = dispatch!(self.backend_url.clone(), Command::Maps, Maps);
         self.stack = None
@@ -1291,7 +1278,7 @@ impl DebuggerWindowImpl for VariableWindow {
         if let Some(Ok(registers)) = self.registers.ready() {
             ui.horizontal(|ui| {
                 if ui
-                    .checkbox(&mut self.lock_stack, "Lock stack start")
+                    .checkbox(&mut self.lock_stack, tr(ui.ctx(), "Lock stack start"))
                     .clicked()
                 {
                     if self.lock_stack {
@@ -1299,10 +1286,17 @@ impl DebuggerWindowImpl for VariableWindow {
                     }
                 }
                 if self.lock_stack {
-                    ui.label(format!("Locked at: {:#x}", self.lock_stack_addr));
+                    ui.label(trf(
+                        ui.ctx(),
+                        "Locked at: {}",
+                        &[&format!("{:#x}", self.lock_stack_addr)],
+                    ));
                 }
                 if ui
-                    .add(egui::Slider::new(&mut self.rsp_offset, 0..=64).text("Stack End Offset"))
+                    .add(
+                        egui::Slider::new(&mut self.rsp_offset, 0..=64)
+                            .text(tr(ui.ctx(), "Stack End Offset")),
+                    )
                     .changed()
                 {
                     stack_dirty = true;
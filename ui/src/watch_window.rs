@@ -0,0 +1,116 @@
+use egui::RichText;
+use poll_promise::Promise;
+use stackium_shared::{Command, CommandOutput, Watch};
+use url::Url;
+
+use crate::{debugger_window::DebuggerWindowImpl, i18n::tr};
+
+/// Memory like `head->next` the debugger stops at once it changes, checked by single-stepping
+pub struct WatchWindow {
+    backend_url: Url,
+    expression_input: String,
+    /// Only the code of this function is single-stepped if it isn't empty
+    function_input: String,
+    watches: Promise<Result<Vec<Watch>, String>>,
+}
+
+impl WatchWindow {
+    pub fn new(backend_url: Url) -> Self {
+        let mut ret = Self {
+            backend_url,
+            expression_input: String::new(),
+            function_input: String::new(),
+            watches: Promise::from_ready(Ok(vec![])),
+        };
+        ret.dirty();
+        ret
+    }
+}
+
+impl DebuggerWindowImpl for WatchWindow {
+    fn dirty(&mut self) {
+        self.watches = dispatch!(self.backend_url.clone(), Command::GetWatches, Watches);
+    }
+
+    fn ui(&mut self, ui: &mut egui::Ui) -> bool {
+        ui.horizontal(|ui| {
+            let input = ui.add(
+                egui::TextEdit::singleline(&mut self.expression_input)
+                    .hint_text("head->next")
+                    .code_editor(),
+            );
+            let submitted = input.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            ui.add(
+                egui::TextEdit::singleline(&mut self.function_input)
+                    .hint_text(tr(ui.ctx(), "in function"))
+                    .desired_width(100.),
+            )
+            .on_hover_text(tr(
+                ui.ctx(),
+                "Only single-step this function, the rest of the program runs at full speed",
+            ));
+            if (ui.button(tr(ui.ctx(), "Add")).clicked() || submitted)
+                && !self.expression_input.is_empty()
+            {
+                let function = self.function_input.trim().to_owned();
+                self.watches = dispatch!(
+                    self.backend_url.clone(),
+                    Command::AddWatch {
+                        expression: std::mem::take(&mut self.expression_input),
+                        function: (!function.is_empty()).then_some(function),
+                    },
+                    Watches
+                );
+            }
+        });
+        ui.label(
+            RichText::new(tr(
+                ui.ctx(),
+                "Continuing single-steps and stops right after the memory changed, which is slow",
+            ))
+            .small()
+            .weak(),
+        );
+        ui.separator();
+        let mut remove = None;
+        match self.watches.ready() {
+            Some(Ok(watches)) => {
+                egui::Grid::new("watches").striped(true).show(ui, |ui| {
+                    for watch in watches {
+                        ui.label(RichText::new(&watch.expression).monospace())
+                            .on_hover_text(format!("{:#x}, {} B", watch.address, watch.size));
+                        ui.label(RichText::new(&watch.value).monospace());
+                        ui.label(
+                            RichText::new(watch.function.as_deref().unwrap_or_default()).weak(),
+                        );
+                        if ui.small_button(tr(ui.ctx(), "Remove")).clicked() {
+                            remove = Some(watch.expression.clone());
+                        }
+                        ui.end_row();
+                    }
+                });
+            }
+            Some(Err(err)) => {
+                ui.label(
+                    RichText::new(format!("⚠ {}", err))
+                        .small()
+                        .color(ui.visuals().warn_fg_color),
+                );
+                if ui.small_button("Ok").clicked() {
+                    self.dirty();
+                }
+            }
+            None => {
+                ui.spinner();
+            }
+        }
+        if let Some(expression) = remove {
+            self.watches = dispatch!(
+                self.backend_url.clone(),
+                Command::RemoveWatch(expression),
+                Watches
+            );
+        }
+        false
+    }
+}